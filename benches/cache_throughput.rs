@@ -0,0 +1,98 @@
+//! Throughput benchmarks for the UAO-QTCAM cache
+//!
+//! Threads run a read-heavy mix (90% GET, 10% SET) over a preloaded
+//! keyspace, against one shard and against the default sharding, so the
+//! cost of a single lock and the scaling across thread counts can be
+//! compared directly. The same mix then runs against a cache at its memory
+//! limit, which holds about half the keyspace, so most writes evict.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_core::uao_qtcam_cache::DEFAULT_SHARDS;
+use symmetrix_core::UaoQtcamCache;
+
+const KEYS: usize = 10_000;
+const OPS_PER_THREAD: u64 = 1_000;
+
+fn preloaded(shards: usize) -> UaoQtcamCache {
+    let cache = UaoQtcamCache::new(256 * 1024 * 1024, 250.0).with_shards(shards);
+    for i in 0..KEYS {
+        cache.set(&format!("key:{}", i), &[b'v'; 64], None).unwrap();
+    }
+    cache
+}
+
+/// A cache whose limit fits about half of `KEYS`, filled to it
+fn at_memory_limit(shards: usize) -> UaoQtcamCache {
+    let charge = preloaded(1).stats().unwrap().used_memory / KEYS;
+    let cache = UaoQtcamCache::new(charge * KEYS / 2, 250.0).with_shards(shards);
+    for i in 0..KEYS {
+        cache.set(&format!("key:{}", i), &[b'v'; 64], None).unwrap();
+    }
+    assert!(cache.stats().unwrap().evictions > 0);
+    cache
+}
+
+/// Run `ops` operations of the mix on `threads` threads at once
+fn run_mix(cache: &UaoQtcamCache, threads: usize, ops: u64) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for t in 0..threads {
+            scope.spawn(move || {
+                let mut rng = StdRng::seed_from_u64(t as u64);
+                for _ in 0..ops {
+                    let key = format!("key:{}", rng.gen_range(0..KEYS));
+                    if rng.gen_bool(0.9) {
+                        cache.get(&key).unwrap();
+                    } else {
+                        cache.set(&key, &[b'w'; 64], None).unwrap();
+                    }
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn bench_get_set_mix(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cache_get_set_mix");
+    group.measurement_time(Duration::from_secs(5));
+
+    for shards in [1, DEFAULT_SHARDS] {
+        let cache = preloaded(shards);
+        for threads in [1, 2, 4, 8] {
+            group.throughput(Throughput::Elements(threads as u64 * OPS_PER_THREAD));
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}-shards", shards), threads),
+                &threads,
+                |b, &threads| b.iter_custom(|iters| run_mix(&cache, threads, iters * OPS_PER_THREAD)),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_mix_at_memory_limit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cache_get_set_mix_at_memory_limit");
+    group.measurement_time(Duration::from_secs(5));
+
+    for shards in [1, DEFAULT_SHARDS] {
+        let cache = at_memory_limit(shards);
+        for threads in [1, 2, 4, 8] {
+            group.throughput(Throughput::Elements(threads as u64 * OPS_PER_THREAD));
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}-shards", shards), threads),
+                &threads,
+                |b, &threads| b.iter_custom(|iters| run_mix(&cache, threads, iters * OPS_PER_THREAD)),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_set_mix, bench_mix_at_memory_limit);
+criterion_main!(benches);
@@ -5,6 +5,28 @@
 use std::path::PathBuf;
 use std::process::Command;
 
+/// `bytes` fields encoded as base64 strings in JSON (proto3 JSON mapping)
+const BASE64_BYTES_FIELDS: &[&str] = &[
+    "CalibrationMatrix.matrix_data",
+    "CartfCoefficients.folding_coefficients",
+    "GaloisFieldConfig.multiplication_lut",
+    "GaloisFieldConfig.inverse_lut",
+    "GaloisFieldConfig.log_table",
+    "GaloisFieldConfig.antilog_table",
+    "DeBruijnConfig.eulerian_weights",
+    "DeBruijnConfig.adjacency_matrix",
+    "HopfieldConfig.energy_matrix",
+    "HopfieldConfig.bias_vector",
+    "PmeComputeWaveConfig.charge_spreading_coeffs",
+    "PmeComputeWaveConfig.fft_twiddle_factors",
+    "PrefetchConfig.prediction_weights",
+    "CoalescingConfig.debruijn_schedule",
+    "PCIeCompressionConfig.galois_lut",
+    "CRLResponse.crl_der",
+    "OCSPRequest.ocsp_request_der",
+    "OCSPResponse.ocsp_response_der",
+];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get the output directory for generated code
     let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
@@ -24,10 +46,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // protoc is available - generate fresh from .proto
         println!("cargo:warning=Using protoc to generate gRPC code from proto files");
 
-        tonic_build::configure()
+        let mut builder = tonic_build::configure()
             .build_server(true)
             .build_client(true)
             .type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")
+            // Missing JSON fields take their proto3 defaults
+            .message_attribute(".", "#[serde(default)]");
+        for field in BASE64_BYTES_FIELDS {
+            builder = builder.field_attribute(
+                format!(".cyan_flame.v1.{}", field),
+                "#[serde(with = \"crate::grpc::proto::base64_bytes\")]",
+            );
+        }
+
+        builder
             .file_descriptor_set_path(out_dir.join("cyan_flame_descriptor.bin"))
             .compile_protos(
                 &["proto/cyan_flame.proto"],
@@ -0,0 +1,56 @@
+//! Number-theoretic transform benchmarks
+//!
+//! Forward transforms from 2^10 to 2^20 points, and polynomial products by
+//! schoolbook and by NTT, over an NTT prime and over `MERSENNE_61` (three
+//! transforms joined by CRT).
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_galois::ntt::{multiply_mod, multiply_naive, NttPlan, NTT_PRIMES};
+use symmetrix_galois::MERSENNE_61;
+
+fn random_poly(len: usize, modulus: u64) -> Vec<u64> {
+    let mut rng = StdRng::seed_from_u64(len as u64);
+    (0..len).map(|_| rng.gen_range(0..modulus)).collect()
+}
+
+fn bench_forward(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ntt_forward");
+    group.sample_size(10);
+    for log_len in (10..=20).step_by(2) {
+        let len = 1usize << log_len;
+        let plan = NttPlan::new(NTT_PRIMES[0], len).unwrap();
+        let data = random_poly(len, NTT_PRIMES[0].modulus);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &data, |b, data| {
+            b.iter_batched_ref(|| data.clone(), |data| plan.forward(data).unwrap(), criterion::BatchSize::LargeInput)
+        });
+    }
+    group.finish();
+}
+
+fn bench_multiply(c: &mut Criterion) {
+    let mut group = c.benchmark_group("polynomial_multiply");
+    group.sample_size(10);
+    for log_len in (6..=20).step_by(2) {
+        let len = 1usize << log_len;
+        for (name, modulus) in [("ntt_prime", NTT_PRIMES[0].modulus), ("mersenne_61", MERSENNE_61)] {
+            let a = random_poly(len, modulus);
+            let b = random_poly(len + 1, modulus);
+            group.bench_with_input(BenchmarkId::new(format!("ntt/{}", name), len), &len, |bench, _| {
+                bench.iter(|| multiply_mod(&a, &b, modulus).unwrap())
+            });
+            // Schoolbook stops being worth the wait long before 2^20
+            if log_len <= 12 {
+                group.bench_with_input(BenchmarkId::new(format!("naive/{}", name), len), &len, |bench, _| {
+                    bench.iter(|| multiply_naive(&a, &b, modulus))
+                });
+            }
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_forward, bench_multiply);
+criterion_main!(benches);
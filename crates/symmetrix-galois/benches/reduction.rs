@@ -0,0 +1,82 @@
+//! Modular multiplication benchmarks
+//!
+//! Multiplies two vectors of residues in place with the `%`-based
+//! `GaloisElement` and with each reduction, for a 61-bit and a 31-bit
+//! modulus. Each modulus is one criterion group, so its report compares every
+//! reduction against the `galois_element` reference.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_galois::{
+    Barrett32, Barrett64, GaloisElement, Mersenne61, Montgomery32, Montgomery64, Reduction, CRT_PRIMES, MERSENNE_61,
+};
+
+const LEN: usize = 1 << 16;
+
+fn values(modulus: u64, seed: u64) -> Vec<u64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..LEN).map(|_| rng.gen_range(0..modulus)).collect()
+}
+
+/// One in-place multiplication pass over `LEN` residues
+type Kernel = Box<dyn FnMut()>;
+
+fn reference(modulus: u64) -> Kernel {
+    let mut a: Vec<GaloisElement> = values(modulus, 1).into_iter().map(|v| GaloisElement::new(v, modulus)).collect();
+    let b: Vec<GaloisElement> = values(modulus, 2).into_iter().map(|v| GaloisElement::new(v, modulus)).collect();
+    Box::new(move || {
+        a.iter_mut().zip(&b).for_each(|(x, y)| *x = *x * *y);
+        black_box(&a);
+    })
+}
+
+fn reduced<R: Reduction + 'static>(field: R) -> Kernel {
+    let n = field.modulus();
+    let mut a: Vec<R::Word> = values(n, 1).into_iter().map(|v| field.enter(v)).collect();
+    let b: Vec<R::Word> = values(n, 2).into_iter().map(|v| field.enter(v)).collect();
+    Box::new(move || {
+        field.mul_assign_slice(&mut a, &b);
+        black_box(&a);
+    })
+}
+
+fn kernels() -> Vec<(&'static str, Vec<(&'static str, Kernel)>)> {
+    let p31 = CRT_PRIMES[0];
+    // Not MERSENNE_61, whose reference multiply already takes the folding path
+    let p61 = (1u64 << 61) - 31;
+    vec![
+        ("mersenne_61", vec![
+            ("galois_element", reference(MERSENNE_61)),
+            ("mersenne61", reduced(Mersenne61)),
+            ("montgomery64", reduced(Montgomery64::new(MERSENNE_61).unwrap())),
+            ("barrett64", reduced(Barrett64::new(MERSENNE_61).unwrap())),
+        ]),
+        ("prime_61", vec![
+            ("galois_element", reference(p61)),
+            ("montgomery64", reduced(Montgomery64::new(p61).unwrap())),
+            ("barrett64", reduced(Barrett64::new(p61).unwrap())),
+        ]),
+        ("prime_31", vec![
+            ("galois_element", reference(p31)),
+            ("montgomery32", reduced(Montgomery32::new(p31 as u32).unwrap())),
+            ("barrett32", reduced(Barrett32::new(p31 as u32).unwrap())),
+        ]),
+    ]
+}
+
+fn bench_mul(c: &mut Criterion) {
+    for (modulus, mut kernels) in kernels() {
+        let mut group = c.benchmark_group(format!("vector_mul/{}", modulus));
+        group.throughput(Throughput::Elements(LEN as u64));
+        for (name, kernel) in kernels.iter_mut() {
+            group.bench_function(BenchmarkId::from_parameter(*name), |b| b.iter(&mut *kernel));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_mul);
+criterion_main!(benches);
@@ -0,0 +1,344 @@
+//! Binary extension fields GF(2^n)
+//!
+//! Elements are polynomials over GF(2) of degree below `n`, packed into the
+//! low `n` bits of a `u64`; addition is XOR and multiplication is carry-less
+//! multiplication reduced by an irreducible polynomial of degree `n`, given
+//! with its leading term (`0x11D` is x^8 + x^4 + x^3 + x^2 + 1).
+//!
+//! Two implementations sit behind [`BinaryField`]:
+//!
+//! - [`LogTableField`] multiplies through log/antilog tables, for fields up to
+//!   [`MAX_TABLE_DEGREE`]; GF(2^8) and GF(2^16) are built this way.
+//! - [`ClmulField`] multiplies carry-lessly and reduces bit by bit, for any
+//!   degree up to 63, without tables.
+//!
+//! Both reject polynomials that are not irreducible, as those give a ring
+//! with zero divisors rather than a field.
+
+use crate::{GaloisError, GaloisResult};
+
+/// x^8 + x^4 + x^3 + x^2 + 1, primitive; the usual GF(2^8) for erasure codes
+pub const GF256_POLY: u64 = 0x11D;
+
+/// x^8 + x^4 + x^3 + x + 1, the AES field (irreducible, but x does not generate it)
+pub const AES_POLY: u64 = 0x11B;
+
+/// x^16 + x^12 + x^3 + x + 1, primitive
+pub const GF65536_POLY: u64 = 0x1100B;
+
+/// Largest degree [`LogTableField`] builds tables for
+pub const MAX_TABLE_DEGREE: u32 = 20;
+
+/// Largest degree a field can have, so products fit in a `u128`
+pub const MAX_DEGREE: u32 = 63;
+
+/// Arithmetic in GF(2^n); operands must be below [`order`](Self::order)
+pub trait BinaryField: Send + Sync {
+    /// The extension degree `n`
+    fn degree(&self) -> u32;
+
+    /// The reduction polynomial, leading term included
+    fn polynomial(&self) -> u64;
+
+    /// Product of `a` and `b`
+    fn mul(&self, a: u64, b: u64) -> u64;
+
+    /// Number of elements, 2^n
+    fn order(&self) -> u64 {
+        1 << self.degree()
+    }
+
+    /// Sum of `a` and `b`, which is also their difference
+    fn add(&self, a: u64, b: u64) -> u64 {
+        a ^ b
+    }
+
+    /// `a` raised to `exponent`, by squaring and multiplying
+    fn pow(&self, a: u64, exponent: u64) -> u64 {
+        let mut result = 1;
+        let mut base = a;
+        let mut exp = exponent;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Multiplicative inverse of `a`, as a^(2^n - 2)
+    fn inverse(&self, a: u64) -> GaloisResult<u64> {
+        if a == 0 {
+            return Err(GaloisError::DivisionByZero);
+        }
+        Ok(self.pow(a, self.order() - 2))
+    }
+
+    /// Quotient of `a` by `b`
+    fn div(&self, a: u64, b: u64) -> GaloisResult<u64> {
+        Ok(self.mul(a, self.inverse(b)?))
+    }
+
+    /// Value at `x` of the polynomial with `coeffs` (constant term first), by Horner's rule
+    fn eval(&self, coeffs: &[u64], x: u64) -> u64 {
+        coeffs.iter().rev().fold(0, |acc, &c| self.mul(acc, x) ^ c)
+    }
+}
+
+/// Carry-less product of `a` and `b`
+pub fn clmul(a: u64, b: u64) -> u128 {
+    let mut product = 0u128;
+    let mut b = b;
+    let mut shifted = a as u128;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= shifted;
+        }
+        shifted <<= 1;
+        b >>= 1;
+    }
+    product
+}
+
+/// `product` modulo `poly` of degree `degree`
+fn reduce(mut product: u128, poly: u64, degree: u32) -> u64 {
+    let poly = poly as u128;
+    let mut top = 127 - product.leading_zeros().min(127);
+    while product >> degree != 0 {
+        if product >> top & 1 == 1 {
+            product ^= poly << (top - degree);
+        }
+        top -= 1;
+    }
+    product as u64
+}
+
+/// Degree of the polynomial `p`, which must not be zero
+fn poly_degree(p: u64) -> u32 {
+    63 - p.leading_zeros()
+}
+
+/// Greatest common divisor of two polynomials over GF(2)
+fn poly_gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        while a != 0 && poly_degree(a) >= poly_degree(b) {
+            a ^= b << (poly_degree(a) - poly_degree(b));
+        }
+        std::mem::swap(&mut a, &mut b);
+    }
+    a
+}
+
+/// Distinct prime factors of `n`
+fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Check `poly` is an irreducible polynomial of degree `degree` (Rabin's test)
+fn check_polynomial(poly: u64, degree: u32, max_degree: u32) -> GaloisResult<()> {
+    if degree == 0 || degree > max_degree {
+        return Err(GaloisError::InvalidPolynomial(format!(
+            "degree {} is outside 1..={}",
+            degree, max_degree
+        )));
+    }
+    if poly == 0 || poly_degree(poly) != degree {
+        return Err(GaloisError::InvalidPolynomial(format!("{:#x} does not have degree {}", poly, degree)));
+    }
+
+    // x^(2^k) mod poly, by repeated squaring
+    let field = ClmulField { degree, poly };
+    let x = if degree == 1 { reduce(2, poly, degree) } else { 2 };
+    let frobenius = |k: u32| (0..k).fold(x, |acc, _| field.mul(acc, acc));
+
+    let irreducible = frobenius(degree) == x
+        && prime_factors(degree as u64)
+            .into_iter()
+            .all(|q| poly_gcd(frobenius(degree / q as u32) ^ x, poly) == 1);
+    if !irreducible {
+        return Err(GaloisError::InvalidPolynomial(format!("{:#x} is reducible", poly)));
+    }
+    Ok(())
+}
+
+/// GF(2^n) by carry-less multiplication, for any degree up to [`MAX_DEGREE`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClmulField {
+    degree: u32,
+    poly: u64,
+}
+
+impl ClmulField {
+    /// GF(2^`degree`) reduced by `poly`, which must be irreducible
+    pub fn new(degree: u32, poly: u64) -> GaloisResult<Self> {
+        check_polynomial(poly, degree, MAX_DEGREE)?;
+        Ok(Self { degree, poly })
+    }
+}
+
+impl BinaryField for ClmulField {
+    fn degree(&self) -> u32 {
+        self.degree
+    }
+
+    fn polynomial(&self) -> u64 {
+        self.poly
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        debug_assert!(a < self.order() && b < self.order());
+        reduce(clmul(a, b), self.poly, self.degree)
+    }
+}
+
+/// GF(2^n) by log/antilog tables, for degrees up to [`MAX_TABLE_DEGREE`]
+#[derive(Debug, Clone)]
+pub struct LogTableField {
+    degree: u32,
+    poly: u64,
+    /// Element generating the multiplicative group
+    generator: u64,
+    /// Discrete logarithm of every nonzero element to base `generator`
+    log: Vec<u32>,
+    /// Powers of `generator`, written out twice so sums of two logs index it directly
+    exp: Vec<u32>,
+}
+
+impl LogTableField {
+    /// GF(2^`degree`) reduced by `poly`, which must be irreducible
+    pub fn new(degree: u32, poly: u64) -> GaloisResult<Self> {
+        check_polynomial(poly, degree, MAX_TABLE_DEGREE)?;
+        let field = ClmulField { degree, poly };
+        let group = field.order() - 1;
+
+        // The smallest element of full order; x itself for a primitive polynomial
+        let factors = prime_factors(group);
+        let generator = (2..field.order())
+            .find(|&g| factors.iter().all(|q| field.pow(g, group / q) != 1))
+            .unwrap_or(1);
+
+        let mut log = vec![0u32; field.order() as usize];
+        let mut exp = Vec::with_capacity(2 * group as usize);
+        let mut power = 1;
+        for i in 0..group {
+            log[power as usize] = i as u32;
+            exp.push(power as u32);
+            power = field.mul(power, generator);
+        }
+        exp.extend_from_within(..);
+
+        Ok(Self { degree, poly, generator, log, exp })
+    }
+
+    /// GF(2^8) over [`GF256_POLY`]
+    pub fn gf256() -> Self {
+        Self::new(8, GF256_POLY).expect("GF256_POLY is irreducible")
+    }
+
+    /// GF(2^16) over [`GF65536_POLY`]
+    pub fn gf65536() -> Self {
+        Self::new(16, GF65536_POLY).expect("GF65536_POLY is irreducible")
+    }
+
+    /// Generator of the multiplicative group the tables are built on
+    pub fn generator(&self) -> u64 {
+        self.generator
+    }
+
+    /// Discrete logarithm of nonzero `a` to base [`generator`](Self::generator)
+    pub fn log(&self, a: u64) -> GaloisResult<u64> {
+        if a == 0 {
+            return Err(GaloisError::InvalidElement("zero has no logarithm".to_string()));
+        }
+        Ok(self.log[a as usize] as u64)
+    }
+
+    /// [`generator`](Self::generator) raised to `i`
+    pub fn exp(&self, i: u64) -> u64 {
+        self.exp[(i % (self.order() - 1)) as usize] as u64
+    }
+}
+
+impl BinaryField for LogTableField {
+    fn degree(&self) -> u32 {
+        self.degree
+    }
+
+    fn polynomial(&self) -> u64 {
+        self.poly
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        debug_assert!(a < self.order() && b < self.order());
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        self.exp[(self.log[a as usize] + self.log[b as usize]) as usize] as u64
+    }
+
+    fn pow(&self, a: u64, exponent: u64) -> u64 {
+        match (a, exponent) {
+            (_, 0) => 1,
+            (0, _) => 0,
+            _ => {
+                let group = (self.order() - 1) as u128;
+                self.exp[(self.log[a as usize] as u128 * exponent as u128 % group) as usize] as u64
+            }
+        }
+    }
+
+    fn inverse(&self, a: u64) -> GaloisResult<u64> {
+        if a == 0 {
+            return Err(GaloisError::DivisionByZero);
+        }
+        let group = self.order() - 1;
+        Ok(self.exp[((group - self.log[a as usize] as u64) % group) as usize] as u64)
+    }
+
+    fn div(&self, a: u64, b: u64) -> GaloisResult<u64> {
+        if b == 0 {
+            return Err(GaloisError::DivisionByZero);
+        }
+        if a == 0 {
+            return Ok(0);
+        }
+        let group = self.order() - 1;
+        Ok(self.exp[(self.log[a as usize] as u64 + group - self.log[b as usize] as u64) as usize] as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clmul_and_reduce() {
+        // (x + 1)(x + 1) = x^2 + 1 without carries
+        assert_eq!(clmul(0b11, 0b11), 0b101);
+        assert_eq!(reduce(0b101, 0b111, 2), 0b10);
+        assert_eq!(clmul(u64::MAX, 1), u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_poly_gcd() {
+        // x^2 + 1 = (x + 1)^2 and x^2 + x = x(x + 1)
+        assert_eq!(poly_gcd(0b101, 0b110), 0b11);
+        assert_eq!(poly_gcd(GF256_POLY, 0b10), 1);
+    }
+}
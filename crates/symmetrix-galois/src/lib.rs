@@ -8,10 +8,14 @@
 //! ## Core Features
 //!
 //! - **Galois Field GF(2^61-1)**: Operations in the Mersenne prime field
+//! - **Binary Fields GF(2^n)**: Table and carry-less arithmetic (see [`binary_field`])
+//! - **Erasure Coding**: Systematic Reed–Solomon over GF(2^8) (see [`reed_solomon`])
 //! - **CRT Decomposition**: Parallel computation using multiple smaller primes
-//! - **SIMD Acceleration**: AVX-512 vectorized operations
+//! - **RNS Integers**: Checked signed arithmetic on CRT residues (see [`rns`])
+//! - **Fast Reduction**: Montgomery, Barrett and Mersenne reduction (see [`reduction`])
+//! - **SIMD Acceleration**: In-place batched operations with AVX2 kernels
 //! - **Matrix Representation**: Matrices as polynomials in finite fields
-//! - **Convolution Optimization**: O(n log n) matrix multiplication
+//! - **Convolution Optimization**: O(n log n) polynomial multiplication (see [`ntt`])
 
 use num_bigint::BigUint;
 use num_traits::{Zero, One};
@@ -19,6 +23,17 @@ use serde::{Deserialize, Serialize};
 use std::ops::{Add, Sub, Mul, Div, Neg};
 use std::fmt::{Debug, Display};
 
+pub mod binary_field;
+pub mod ntt;
+pub mod reed_solomon;
+pub mod reduction;
+pub mod rns;
+
+pub use binary_field::{BinaryField, ClmulField, LogTableField};
+pub use reed_solomon::ReedSolomon;
+pub use reduction::{Barrett32, Barrett64, Mersenne61, Montgomery32, Montgomery64, Reduction, Residue};
+pub use rns::{RnsBasis, RnsInt};
+
 /// Errors that can occur in Galois field operations
 #[derive(Debug, thiserror::Error)]
 pub enum GaloisError {
@@ -34,6 +49,24 @@ pub enum GaloisError {
     #[error("SIMD operation failed: {0}")]
     SIMDError(String),
     
+    #[error("Invalid field polynomial: {0}")]
+    InvalidPolynomial(String),
+    
+    #[error("Unsupported transform length: {0}")]
+    UnsupportedLength(usize),
+    
+    #[error("Invalid shard configuration: {data} data + {parity} parity shards")]
+    InvalidShardConfig { data: usize, parity: usize },
+    
+    #[error("Too few shards to reconstruct: need {needed}, have {present}")]
+    TooFewShards { needed: usize, present: usize },
+    
+    #[error("Invalid modulus: {0}")]
+    InvalidModulus(u64),
+    
+    #[error("Value outside the RNS dynamic range")]
+    RnsOverflow,
+    
     #[error("Matrix dimension mismatch: expected {expected}, got {actual}")]
     DimensionMismatch { expected: usize, actual: usize },
 }
@@ -173,8 +206,9 @@ impl Add for GaloisElement {
     
     fn add(self, other: Self) -> Self {
         debug_assert_eq!(self.modulus, other.modulus);
-        let sum = (self.value + other.value) % self.modulus;
-        Self::new(sum, self.modulus)
+        // Widened, as two residues of a modulus above 2^63 can overflow u64
+        let sum = (self.value as u128 + other.value as u128) % self.modulus as u128;
+        Self::new(sum as u64, self.modulus)
     }
 }
 
@@ -197,8 +231,13 @@ impl Mul for GaloisElement {
     
     fn mul(self, other: Self) -> Self {
         debug_assert_eq!(self.modulus, other.modulus);
-        let product = ((self.value as u128) * (other.value as u128)) % (self.modulus as u128);
-        Self::new(product as u64, self.modulus)
+        let product = (self.value as u128) * (other.value as u128);
+        let value = if self.modulus == MERSENNE_61 {
+            reduction::reduce_mersenne_61(product)
+        } else {
+            (product % (self.modulus as u128)) as u64
+        };
+        Self { value, modulus: self.modulus }
     }
 }
 
@@ -409,6 +448,37 @@ pub mod simd {
         
         Ok(result)
     }
+
+    fn check_lengths<T, U>(a: &[T], b: &[U]) -> GaloisResult<()> {
+        if a.len() != b.len() {
+            return Err(GaloisError::DimensionMismatch {
+                expected: a.len(),
+                actual: b.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// In-place `a[i] += b[i]` on words of `field`
+    pub fn add_assign<R: Reduction>(field: &R, a: &mut [R::Word], b: &[R::Word]) -> GaloisResult<()> {
+        check_lengths(a, b)?;
+        field.add_assign_slice(a, b);
+        Ok(())
+    }
+
+    /// In-place `a[i] -= b[i]` on words of `field`
+    pub fn sub_assign<R: Reduction>(field: &R, a: &mut [R::Word], b: &[R::Word]) -> GaloisResult<()> {
+        check_lengths(a, b)?;
+        field.sub_assign_slice(a, b);
+        Ok(())
+    }
+
+    /// In-place `a[i] *= b[i]` on words of `field`
+    pub fn mul_assign<R: Reduction>(field: &R, a: &mut [R::Word], b: &[R::Word]) -> GaloisResult<()> {
+        check_lengths(a, b)?;
+        field.mul_assign_slice(a, b);
+        Ok(())
+    }
 }
 
 /// Initialize the Galois field engine
@@ -427,6 +497,8 @@ pub struct GaloisEngine {
     pub crt_primes: Vec<u64>,
     /// Precomputed powers for fast exponentiation
     pub power_cache: std::collections::HashMap<(u64, u64), GaloisElement>,
+    /// Transform plans reused across polynomial products
+    pub ntt_plans: ntt::NttPlanCache,
 }
 
 impl GaloisEngine {
@@ -436,6 +508,7 @@ impl GaloisEngine {
             prime,
             crt_primes: CRT_PRIMES.to_vec(),
             power_cache: std::collections::HashMap::new(),
+            ntt_plans: ntt::NttPlanCache::new(),
         }
     }
     
@@ -475,6 +548,19 @@ impl GaloisEngine {
         Ok(result)
     }
 
+    /// Multiply two polynomials over the field (constant term first)
+    ///
+    /// Uses the number-theoretic transform once both factors reach
+    /// [`ntt::NTT_THRESHOLD`] coefficients, schoolbook below that. Transform
+    /// plans are kept in [`Self::ntt_plans`] between calls.
+    pub fn multiply_polynomials(&self, a: &[GaloisElement], b: &[GaloisElement])
+                                -> GaloisResult<Vec<GaloisElement>> {
+        let a: Vec<u64> = a.iter().map(|x| x.value).collect();
+        let b: Vec<u64> = b.iter().map(|x| x.value).collect();
+        let product = self.ntt_plans.multiply_mod(&a, &b, self.prime)?;
+        Ok(product.into_iter().map(|value| self.element(value)).collect())
+    }
+
     /// Get operations per second (estimated based on field size)
     pub fn ops_per_second(&self) -> u64 {
         // Mersenne prime GF(2^61-1) allows ~10M ops/sec on modern CPUs
@@ -0,0 +1,346 @@
+//! Number-theoretic transform
+//!
+//! An [`NttPlan`] precomputes the twiddle factors for one power-of-two
+//! length over one [`NttPrime`], whose multiplicative group has a large
+//! power of two in its order. Polynomial products go through
+//! [`multiply_mod`], which keeps schoolbook multiplication for short inputs
+//! and switches to the transform above [`NTT_THRESHOLD`]. Callers that
+//! multiply repeatedly keep an [`NttPlanCache`] so each plan is built once.
+//!
+//! Products modulo anything but an NTT prime, such as [`MERSENNE_61`] or
+//! the [`CRT_PRIMES`], are computed exactly: the convolution runs over each
+//! of the [`NTT_PRIMES`] and the residues are joined by CRT (Garner's
+//! algorithm) straight into the target modulus. The `CRT_PRIMES` cannot
+//! carry a transform themselves, as p - 1 has a single factor of two.
+//!
+//! [`MERSENNE_61`]: crate::MERSENNE_61
+//! [`CRT_PRIMES`]: crate::CRT_PRIMES
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::{GaloisError, GaloisResult};
+
+/// Inputs shorter than this are multiplied by schoolbook
+pub const NTT_THRESHOLD: usize = 64;
+
+/// A prime p = c·2^k + 1 with a generator of its multiplicative group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NttPrime {
+    pub modulus: u64,
+    /// Primitive root modulo `modulus`
+    pub generator: u64,
+}
+
+impl NttPrime {
+    pub const fn new(modulus: u64, generator: u64) -> Self {
+        Self { modulus, generator }
+    }
+
+    /// k in p = c·2^k + 1; transforms can have up to 2^k points
+    pub fn two_adicity(&self) -> u32 {
+        (self.modulus - 1).trailing_zeros()
+    }
+}
+
+/// 119·2^23 + 1, the usual 30-bit NTT prime
+pub const NTT_PRIME_998244353: NttPrime = NttPrime::new(998_244_353, 3);
+
+/// Primes whose product (about 2^186) bounds any convolution of 64-bit coefficients
+pub const NTT_PRIMES: [NttPrime; 3] = [
+    NttPrime::new(4_179_340_454_199_820_289, 3), // 29·2^57 + 1
+    NttPrime::new(6_269_010_681_299_730_433, 5), // 87·2^56 + 1
+    NttPrime::new(4_719_772_409_484_279_809, 3), // 131·2^55 + 1
+];
+
+/// `a * b mod m`
+#[inline]
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// `base^exp mod m`
+fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Precomputed transform of one power-of-two length
+#[derive(Debug, Clone)]
+pub struct NttPlan {
+    prime: NttPrime,
+    len: usize,
+    /// Powers of the primitive `len`-th root of unity, `len / 2` of them
+    twiddles: Vec<u64>,
+    /// Powers of its inverse
+    inverse_twiddles: Vec<u64>,
+    /// `len`⁻¹ mod p, scaling the inverse transform
+    len_inverse: u64,
+}
+
+impl NttPlan {
+    /// Plan transforms of `len` points modulo `prime`
+    pub fn new(prime: NttPrime, len: usize) -> GaloisResult<Self> {
+        if !len.is_power_of_two() || len.trailing_zeros() > prime.two_adicity() {
+            return Err(GaloisError::UnsupportedLength(len));
+        }
+        let p = prime.modulus;
+        let root = pow_mod(prime.generator, (p - 1) / len as u64, p);
+        let root_inverse = pow_mod(root, p - 2, p);
+
+        let powers = |base: u64| {
+            let mut table = Vec::with_capacity(len / 2);
+            let mut power = 1;
+            for _ in 0..len / 2 {
+                table.push(power);
+                power = mul_mod(power, base, p);
+            }
+            table
+        };
+        Ok(Self {
+            prime,
+            len,
+            twiddles: powers(root),
+            inverse_twiddles: powers(root_inverse),
+            len_inverse: pow_mod(len as u64, p - 2, p),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn prime(&self) -> NttPrime {
+        self.prime
+    }
+
+    /// Evaluate `data` (coefficients below p) at the powers of the root, in place
+    pub fn forward(&self, data: &mut [u64]) -> GaloisResult<()> {
+        self.check(data)?;
+        self.butterflies(data, &self.twiddles);
+        Ok(())
+    }
+
+    /// Undo [`forward`](Self::forward), in place
+    pub fn inverse(&self, data: &mut [u64]) -> GaloisResult<()> {
+        self.check(data)?;
+        self.butterflies(data, &self.inverse_twiddles);
+        let p = self.prime.modulus;
+        for x in data.iter_mut() {
+            *x = mul_mod(*x, self.len_inverse, p);
+        }
+        Ok(())
+    }
+
+    fn check(&self, data: &[u64]) -> GaloisResult<()> {
+        if data.len() != self.len {
+            return Err(GaloisError::DimensionMismatch { expected: self.len, actual: data.len() });
+        }
+        Ok(())
+    }
+
+    /// Iterative radix-2 Cooley–Tukey over bit-reversed input
+    fn butterflies(&self, data: &mut [u64], twiddles: &[u64]) {
+        let n = self.len;
+        let p = self.prime.modulus;
+        if n <= 1 {
+            return;
+        }
+
+        let shift = usize::BITS - n.trailing_zeros();
+        for i in 0..n {
+            let j = i.reverse_bits() >> shift;
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+
+        let mut half = 1;
+        while half < n {
+            let stride = n / (2 * half);
+            for block in data.chunks_exact_mut(2 * half) {
+                let (low, high) = block.split_at_mut(half);
+                for (k, (u, v)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+                    let t = mul_mod(*v, twiddles[k * stride], p);
+                    let sum = *u as u128 + t as u128;
+                    *v = if *u >= t { *u - t } else { p - (t - *u) };
+                    *u = if sum >= p as u128 { (sum - p as u128) as u64 } else { sum as u64 };
+                }
+            }
+            half *= 2;
+        }
+    }
+}
+
+/// Plans built so far, keyed by prime and length
+#[derive(Debug, Default)]
+pub struct NttPlanCache {
+    plans: Mutex<HashMap<(u64, usize), Arc<NttPlan>>>,
+}
+
+impl NttPlanCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Plan for `len` points modulo `prime`, built on first use
+    pub fn plan(&self, prime: NttPrime, len: usize) -> GaloisResult<Arc<NttPlan>> {
+        let mut plans = self.plans.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(plan) = plans.get(&(prime.modulus, len)) {
+            return Ok(plan.clone());
+        }
+        let plan = Arc::new(NttPlan::new(prime, len)?);
+        plans.insert((prime.modulus, len), plan.clone());
+        Ok(plan)
+    }
+
+    /// Number of cached plans
+    pub fn len(&self) -> usize {
+        self.plans.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// [`multiply_mod`] reusing this cache's plans
+    pub fn multiply_mod(&self, a: &[u64], b: &[u64], modulus: u64) -> GaloisResult<Vec<u64>> {
+        if modulus == 0 {
+            return Err(GaloisError::DivisionByZero);
+        }
+        if a.len().min(b.len()) < NTT_THRESHOLD {
+            return Ok(multiply_naive(a, b, modulus));
+        }
+        let out_len = a.len() + b.len() - 1;
+        let len = out_len.next_power_of_two();
+
+        let single = NTT_PRIMES
+            .iter()
+            .chain([&NTT_PRIME_998244353])
+            .find(|prime| prime.modulus == modulus && prime.two_adicity() >= len.trailing_zeros());
+        if let Some(&prime) = single {
+            let plan = self.plan(prime, len)?;
+            let mut product = convolve(a, b, &plan)?;
+            product.truncate(out_len);
+            return Ok(product);
+        }
+
+        let [p1, p2, p3] = NTT_PRIMES.map(|prime| prime.modulus);
+        let residues = NTT_PRIMES
+            .iter()
+            .map(|&prime| convolve(a, b, &*self.plan(prime, len)?))
+            .collect::<GaloisResult<Vec<_>>>()?;
+
+        // Garner: x = r1 + p1·t2 + p1·p2·t3 with t2 < p2 and t3 < p3
+        let p1_inv_p2 = pow_mod(p1 % p2, p2 - 2, p2);
+        let p1p2_mod_p3 = mul_mod(p1 % p3, p2 % p3, p3);
+        let p1p2_inv_p3 = pow_mod(p1p2_mod_p3, p3 - 2, p3);
+        let p1_mod_m = p1 % modulus;
+        let p1p2_mod_m = mul_mod(p1_mod_m, p2 % modulus, modulus);
+
+        let product = (0..out_len)
+            .map(|i| {
+                let (r1, r2, r3) = (residues[0][i], residues[1][i], residues[2][i]);
+                let t2 = mul_mod((r2 + p2 - r1 % p2) % p2, p1_inv_p2, p2);
+                let partial = (r1 % p3 + mul_mod(p1 % p3, t2, p3)) % p3;
+                let t3 = mul_mod((r3 + p3 - partial) % p3, p1p2_inv_p3, p3);
+                let x = r1 as u128 % modulus as u128
+                    + mul_mod(p1_mod_m, t2, modulus) as u128
+                    + mul_mod(p1p2_mod_m, t3, modulus) as u128;
+                (x % modulus as u128) as u64
+            })
+            .collect();
+        Ok(product)
+    }
+}
+
+/// Product of two polynomials (constant term first) modulo `modulus`, by schoolbook
+pub fn multiply_naive(a: &[u64], b: &[u64], modulus: u64) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0u64; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            let sum = product[i + j] as u128 + mul_mod(x, y, modulus) as u128;
+            product[i + j] = (sum % modulus as u128) as u64;
+        }
+    }
+    product
+}
+
+/// Cyclic convolution of `a` and `b` over the points of `plan`
+fn convolve(a: &[u64], b: &[u64], plan: &NttPlan) -> GaloisResult<Vec<u64>> {
+    let p = plan.prime().modulus;
+    let mut fa: Vec<u64> = a.iter().map(|&x| x % p).collect();
+    let mut fb: Vec<u64> = b.iter().map(|&x| x % p).collect();
+    fa.resize(plan.len(), 0);
+    fb.resize(plan.len(), 0);
+    plan.forward(&mut fa)?;
+    plan.forward(&mut fb)?;
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x = mul_mod(*x, *y, p);
+    }
+    plan.inverse(&mut fa)?;
+    Ok(fa)
+}
+
+/// Product of two polynomials (constant term first) modulo `modulus`
+///
+/// Short inputs are multiplied by schoolbook. Longer ones go through one
+/// transform when `modulus` is an NTT prime of this crate, and otherwise
+/// through all three with the exact coefficients recovered by CRT. Plans are
+/// built for this call only; see [`NttPlanCache::multiply_mod`].
+pub fn multiply_mod(a: &[u64], b: &[u64], modulus: u64) -> GaloisResult<Vec<u64>> {
+    NttPlanCache::new().multiply_mod(a, b, modulus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ntt_primes_have_their_roots() {
+        for prime in NTT_PRIMES.iter().chain([&NTT_PRIME_998244353]) {
+            let p = prime.modulus;
+            assert!(prime.two_adicity() >= 23);
+            // The generator has full order: g^((p-1)/q) ≠ 1 for every prime q | p - 1
+            let mut odd = (p - 1) >> prime.two_adicity();
+            let mut factors = vec![2];
+            let mut q = 3;
+            while odd > 1 {
+                if odd.is_multiple_of(q) {
+                    factors.push(q);
+                    while odd.is_multiple_of(q) {
+                        odd /= q;
+                    }
+                }
+                q += 2;
+            }
+            for q in factors {
+                assert_ne!(pow_mod(prime.generator, (p - 1) / q, p), 1, "{} mod {}", prime.generator, p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_length_must_be_a_supported_power_of_two() {
+        assert!(NttPlan::new(NTT_PRIME_998244353, 12).is_err());
+        assert!(NttPlan::new(NTT_PRIME_998244353, 1 << 24).is_err());
+        assert!(NttPlan::new(NTT_PRIME_998244353, 1).is_ok());
+        let plan = NttPlan::new(NTT_PRIME_998244353, 8).unwrap();
+        assert!(plan.forward(&mut [0; 4]).is_err());
+    }
+}
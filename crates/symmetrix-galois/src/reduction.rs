@@ -0,0 +1,633 @@
+//! Modular reduction without division
+//!
+//! Each [`Reduction`] fixes a modulus and keeps residues in the form its
+//! multiplication reduces fastest:
+//!
+//! - [`Montgomery32`] and [`Montgomery64`] keep `x·R mod n` (R = 2^32 or
+//!   2^64, odd `n`), so a product is reduced by one multiply-high and a
+//!   subtraction.
+//! - [`Barrett32`] and [`Barrett64`] keep plain residues and divide by
+//!   multiplying with a precomputed reciprocal.
+//! - [`Mersenne61`] folds the high bits of a product onto the low ones, as
+//!   2^61 ≡ 1 modulo [`MERSENNE_61`].
+//!
+//! The `*_assign_slice` methods work in place on whole slices; the default
+//! loops are simple enough for the compiler to vectorize, and
+//! [`Montgomery32`] adds AVX2 kernels picked at runtime on x86-64.
+//! [`Residue`] wraps a word with its reduction for operator syntax.
+
+use std::fmt::{self, Debug};
+use std::ops::{Add, Mul, Sub};
+
+use crate::{GaloisElement, GaloisError, GaloisResult, MERSENNE_61};
+
+/// Arithmetic modulo a fixed modulus, on words in the reduction's own form
+pub trait Reduction: Send + Sync {
+    type Word: Copy + PartialEq + Debug + Send + Sync;
+
+    fn modulus(&self) -> u64;
+
+    /// Bring `x mod n` into this reduction's form
+    fn enter(&self, x: u64) -> Self::Word;
+
+    /// Take `word` back out to its canonical residue in `0..n`
+    fn leave(&self, word: Self::Word) -> u64;
+
+    fn add(&self, a: Self::Word, b: Self::Word) -> Self::Word;
+
+    fn sub(&self, a: Self::Word, b: Self::Word) -> Self::Word;
+
+    fn mul(&self, a: Self::Word, b: Self::Word) -> Self::Word;
+
+    /// `a` raised to `exponent`, by squaring and multiplying
+    fn pow(&self, a: Self::Word, exponent: u64) -> Self::Word {
+        let mut result = self.enter(1);
+        let mut base = a;
+        let mut exp = exponent;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// `a[i] += b[i]` over the common length
+    fn add_assign_slice(&self, a: &mut [Self::Word], b: &[Self::Word]) {
+        a.iter_mut().zip(b).for_each(|(x, y)| *x = self.add(*x, *y));
+    }
+
+    /// `a[i] -= b[i]` over the common length
+    fn sub_assign_slice(&self, a: &mut [Self::Word], b: &[Self::Word]) {
+        a.iter_mut().zip(b).for_each(|(x, y)| *x = self.sub(*x, *y));
+    }
+
+    /// `a[i] *= b[i]` over the common length
+    fn mul_assign_slice(&self, a: &mut [Self::Word], b: &[Self::Word]) {
+        a.iter_mut().zip(b).for_each(|(x, y)| *x = self.mul(*x, *y));
+    }
+}
+
+/// `a + b mod n` for residues below `n`, whatever the width of `n`
+#[inline]
+fn add_mod(a: u64, b: u64, n: u64) -> u64 {
+    let (sum, carry) = a.overflowing_add(b);
+    if carry || sum >= n { sum.wrapping_sub(n) } else { sum }
+}
+
+/// `a - b mod n` for residues below `n`
+#[inline]
+fn sub_mod(a: u64, b: u64, n: u64) -> u64 {
+    if a >= b { a - b } else { a.wrapping_sub(b).wrapping_add(n) }
+}
+
+/// n⁻¹ mod 2^64 for odd `n`, by Newton iteration (each step doubles the correct bits)
+fn inverse_mod_2_64(n: u64) -> u64 {
+    let mut inv = n; // n·n ≡ 1 mod 8
+    for _ in 0..5 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(inv)));
+    }
+    inv
+}
+
+fn check_odd(modulus: u64) -> GaloisResult<()> {
+    if modulus < 3 || modulus.is_multiple_of(2) {
+        return Err(GaloisError::InvalidModulus(modulus));
+    }
+    Ok(())
+}
+
+/// Montgomery reduction modulo an odd 64-bit `n`, with R = 2^64
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Montgomery64 {
+    modulus: u64,
+    /// n⁻¹ mod 2^64
+    inv: u64,
+    /// R² mod n, taking residues into Montgomery form
+    r2: u64,
+}
+
+impl Montgomery64 {
+    pub fn new(modulus: u64) -> GaloisResult<Self> {
+        check_odd(modulus)?;
+        let r = ((1u128 << 64) % modulus as u128) as u64;
+        let r2 = (r as u128 * r as u128 % modulus as u128) as u64;
+        Ok(Self { modulus, inv: inverse_mod_2_64(modulus), r2 })
+    }
+
+    /// t·R⁻¹ mod n, for t < n·R
+    #[inline]
+    fn redc(&self, t: u128) -> u64 {
+        let (lo, hi) = (t as u64, (t >> 64) as u64);
+        // m·n ≡ t mod R, so t - m·n is exactly (hi - mn_hi)·R
+        let m = lo.wrapping_mul(self.inv);
+        let mn_hi = ((m as u128 * self.modulus as u128) >> 64) as u64;
+        sub_mod(hi, mn_hi, self.modulus)
+    }
+}
+
+impl Reduction for Montgomery64 {
+    type Word = u64;
+
+    fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    fn enter(&self, x: u64) -> u64 {
+        self.redc((x % self.modulus) as u128 * self.r2 as u128)
+    }
+
+    fn leave(&self, word: u64) -> u64 {
+        self.redc(word as u128)
+    }
+
+    #[inline]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        add_mod(a, b, self.modulus)
+    }
+
+    #[inline]
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        sub_mod(a, b, self.modulus)
+    }
+
+    #[inline]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        self.redc(a as u128 * b as u128)
+    }
+}
+
+/// Montgomery reduction modulo an odd 32-bit `n`, with R = 2^32
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Montgomery32 {
+    modulus: u32,
+    /// n⁻¹ mod 2^32
+    inv: u32,
+    /// R² mod n
+    r2: u32,
+}
+
+impl Montgomery32 {
+    pub fn new(modulus: u32) -> GaloisResult<Self> {
+        check_odd(modulus as u64)?;
+        let r = (1u64 << 32) % modulus as u64;
+        let r2 = (r * r % modulus as u64) as u32;
+        Ok(Self { modulus, inv: inverse_mod_2_64(modulus as u64) as u32, r2 })
+    }
+
+    /// t·R⁻¹ mod n, for t < n·R
+    #[inline]
+    fn redc(&self, t: u64) -> u32 {
+        let (lo, hi) = (t as u32, (t >> 32) as u32);
+        let m = lo.wrapping_mul(self.inv);
+        let mn_hi = ((m as u64 * self.modulus as u64) >> 32) as u32;
+        sub_mod(hi as u64, mn_hi as u64, self.modulus as u64) as u32
+    }
+
+    /// Whether the AVX2 kernels apply: they need n < 2^31 and the CPU feature
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    fn avx2(&self) -> bool {
+        self.modulus < 1 << 31 && is_x86_feature_detected!("avx2")
+    }
+}
+
+impl Reduction for Montgomery32 {
+    type Word = u32;
+
+    fn modulus(&self) -> u64 {
+        self.modulus as u64
+    }
+
+    fn enter(&self, x: u64) -> u32 {
+        self.redc((x % self.modulus as u64) * self.r2 as u64)
+    }
+
+    fn leave(&self, word: u32) -> u64 {
+        self.redc(word as u64) as u64
+    }
+
+    #[inline]
+    fn add(&self, a: u32, b: u32) -> u32 {
+        add_mod(a as u64, b as u64, self.modulus as u64) as u32
+    }
+
+    #[inline]
+    fn sub(&self, a: u32, b: u32) -> u32 {
+        sub_mod(a as u64, b as u64, self.modulus as u64) as u32
+    }
+
+    #[inline]
+    fn mul(&self, a: u32, b: u32) -> u32 {
+        self.redc(a as u64 * b as u64)
+    }
+
+    fn add_assign_slice(&self, a: &mut [u32], b: &[u32]) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        if self.avx2() {
+            // SAFETY: AVX2 was detected at runtime
+            let done = unsafe { x86::add_assign(self.modulus, a, b) };
+            a[done..].iter_mut().zip(&b[done..]).for_each(|(x, y)| *x = self.add(*x, *y));
+            return;
+        }
+        a.iter_mut().zip(b).for_each(|(x, y)| *x = self.add(*x, *y));
+    }
+
+    fn sub_assign_slice(&self, a: &mut [u32], b: &[u32]) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        if self.avx2() {
+            // SAFETY: AVX2 was detected at runtime
+            let done = unsafe { x86::sub_assign(self.modulus, a, b) };
+            a[done..].iter_mut().zip(&b[done..]).for_each(|(x, y)| *x = self.sub(*x, *y));
+            return;
+        }
+        a.iter_mut().zip(b).for_each(|(x, y)| *x = self.sub(*x, *y));
+    }
+
+    fn mul_assign_slice(&self, a: &mut [u32], b: &[u32]) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        if self.avx2() {
+            // SAFETY: AVX2 was detected at runtime
+            let done = unsafe { x86::montgomery_mul_assign(self.modulus, self.inv.wrapping_neg(), a, b) };
+            a[done..].iter_mut().zip(&b[done..]).for_each(|(x, y)| *x = self.mul(*x, *y));
+            return;
+        }
+        a.iter_mut().zip(b).for_each(|(x, y)| *x = self.mul(*x, *y));
+    }
+}
+
+/// Barrett reduction modulo a 32-bit `n`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Barrett32 {
+    modulus: u32,
+    /// ⌊(2^64 - 1) / n⌋
+    mu: u64,
+}
+
+impl Barrett32 {
+    pub fn new(modulus: u32) -> GaloisResult<Self> {
+        if modulus < 2 {
+            return Err(GaloisError::InvalidModulus(modulus as u64));
+        }
+        Ok(Self { modulus, mu: u64::MAX / modulus as u64 })
+    }
+
+    /// `x mod n`; the estimated quotient is at most two short
+    #[inline]
+    fn reduce(&self, x: u64) -> u32 {
+        let n = self.modulus as u64;
+        let q = ((x as u128 * self.mu as u128) >> 64) as u64;
+        let mut r = x - q * n;
+        if r >= n {
+            r -= n;
+        }
+        if r >= n {
+            r -= n;
+        }
+        r as u32
+    }
+}
+
+impl Reduction for Barrett32 {
+    type Word = u32;
+
+    fn modulus(&self) -> u64 {
+        self.modulus as u64
+    }
+
+    fn enter(&self, x: u64) -> u32 {
+        self.reduce(x)
+    }
+
+    fn leave(&self, word: u32) -> u64 {
+        word as u64
+    }
+
+    #[inline]
+    fn add(&self, a: u32, b: u32) -> u32 {
+        add_mod(a as u64, b as u64, self.modulus as u64) as u32
+    }
+
+    #[inline]
+    fn sub(&self, a: u32, b: u32) -> u32 {
+        sub_mod(a as u64, b as u64, self.modulus as u64) as u32
+    }
+
+    #[inline]
+    fn mul(&self, a: u32, b: u32) -> u32 {
+        self.reduce(a as u64 * b as u64)
+    }
+}
+
+/// High 128 bits of the 256-bit product `a · b`
+#[inline]
+fn mul_hi_u128(a: u128, b: u128) -> u128 {
+    let (a0, a1) = (a as u64 as u128, a >> 64);
+    let (b0, b1) = (b as u64 as u128, b >> 64);
+    let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+    let middle = (p00 >> 64) + (p01 as u64 as u128) + (p10 as u64 as u128);
+    p11 + (p01 >> 64) + (p10 >> 64) + (middle >> 64)
+}
+
+/// Barrett reduction modulo a 64-bit `n`
+///
+/// Moduli of up to 62 bits use the classical `k`-bit form, whose quotient
+/// estimate takes a single 64 × 64 multiply; wider moduli fall back to a
+/// 128-bit `mu`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Barrett64 {
+    modulus: u64,
+    /// Bit length `k` of `n`
+    bits: u32,
+    /// ⌊2^2k / n⌋ when `k ≤ 62`, otherwise ⌊(2^128 - 1) / n⌋
+    mu: u128,
+}
+
+impl Barrett64 {
+    /// Widest modulus, in bits, taking the single-multiply path
+    const NARROW_BITS: u32 = 62;
+
+    pub fn new(modulus: u64) -> GaloisResult<Self> {
+        if modulus < 2 {
+            return Err(GaloisError::InvalidModulus(modulus));
+        }
+        let bits = u64::BITS - modulus.leading_zeros();
+        let mu = if bits <= Self::NARROW_BITS {
+            (1u128 << (2 * bits)) / modulus as u128
+        } else {
+            u128::MAX / modulus as u128
+        };
+        Ok(Self { modulus, bits, mu })
+    }
+
+    /// `x mod n` for `x < n²`; the estimated quotient is at most two short
+    #[inline]
+    fn reduce(&self, x: u128) -> u64 {
+        if self.bits > Self::NARROW_BITS {
+            return self.reduce_wide(x);
+        }
+        let n = self.modulus;
+        // Both shifts are below 64, so each takes one word from either half:
+        // x >> (k - 1) < 2^(k + 1) and mu ≤ 2^(k + 1) fit a word, and
+        // r < 3n < 2^64 so the low words alone give it exactly
+        let (lo, hi) = (x as u64, (x >> 64) as u64);
+        let shifted = lo >> (self.bits - 1) | hi << (65 - self.bits);
+        let product = shifted as u128 * self.mu as u64 as u128;
+        let q = (product as u64) >> (self.bits + 1) | ((product >> 64) as u64) << (63 - self.bits);
+        let r = lo.wrapping_sub(q.wrapping_mul(n));
+        // Below n the subtraction wraps above 3n, so min keeps r
+        let r = r.min(r.wrapping_sub(n));
+        r.min(r.wrapping_sub(n))
+    }
+
+    /// [`Self::reduce`] for moduli above 62 bits, where 3n overflows a word
+    #[inline]
+    fn reduce_wide(&self, x: u128) -> u64 {
+        let n = self.modulus as u128;
+        let q = mul_hi_u128(x, self.mu);
+        let mut r = x - q * n;
+        if r >= n {
+            r -= n;
+        }
+        if r >= n {
+            r -= n;
+        }
+        r as u64
+    }
+}
+
+impl Reduction for Barrett64 {
+    type Word = u64;
+
+    fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    fn enter(&self, x: u64) -> u64 {
+        x % self.modulus
+    }
+
+    fn leave(&self, word: u64) -> u64 {
+        word
+    }
+
+    #[inline]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        add_mod(a, b, self.modulus)
+    }
+
+    #[inline]
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        sub_mod(a, b, self.modulus)
+    }
+
+    #[inline]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+}
+
+/// `x mod 2^61 - 1` for `x < 2^122`, by folding
+#[inline]
+pub fn reduce_mersenne_61(x: u128) -> u64 {
+    let folded = (x as u64 & MERSENNE_61) + (x >> 61) as u64;
+    let folded = (folded & MERSENNE_61) + (folded >> 61);
+    // folded ≤ n + 1, so adding the carry out of folded + 1 and masking
+    // subtracts n exactly when folded ≥ n, without a compare
+    (folded + ((folded + 1) >> 61)) & MERSENNE_61
+}
+
+/// Arithmetic modulo [`MERSENNE_61`] by folding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Mersenne61;
+
+impl Reduction for Mersenne61 {
+    type Word = u64;
+
+    fn modulus(&self) -> u64 {
+        MERSENNE_61
+    }
+
+    fn enter(&self, x: u64) -> u64 {
+        reduce_mersenne_61(x as u128)
+    }
+
+    fn leave(&self, word: u64) -> u64 {
+        word
+    }
+
+    #[inline]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let sum = a + b;
+        if sum >= MERSENNE_61 { sum - MERSENNE_61 } else { sum }
+    }
+
+    #[inline]
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        sub_mod(a, b, MERSENNE_61)
+    }
+
+    #[inline]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        reduce_mersenne_61(a as u128 * b as u128)
+    }
+}
+
+/// A residue together with the reduction it belongs to
+pub struct Residue<'a, R: Reduction> {
+    pub word: R::Word,
+    field: &'a R,
+}
+
+impl<'a, R: Reduction> Residue<'a, R> {
+    /// `value mod n` under `field`
+    pub fn new(field: &'a R, value: u64) -> Self {
+        Self { word: field.enter(value), field }
+    }
+
+    /// The canonical residue, in `0..n`
+    pub fn value(&self) -> u64 {
+        self.field.leave(self.word)
+    }
+
+    pub fn pow(&self, exponent: u64) -> Self {
+        Self { word: self.field.pow(self.word, exponent), field: self.field }
+    }
+
+    /// The same residue as a reference [`GaloisElement`]
+    pub fn to_galois(&self) -> GaloisElement {
+        GaloisElement::new(self.value(), self.field.modulus())
+    }
+}
+
+impl<R: Reduction> Clone for Residue<'_, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R: Reduction> Copy for Residue<'_, R> {}
+
+impl<R: Reduction> PartialEq for Residue<'_, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.word == other.word && self.field.modulus() == other.field.modulus()
+    }
+}
+
+impl<R: Reduction> Debug for Residue<'_, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(mod {})", self.value(), self.field.modulus())
+    }
+}
+
+impl<R: Reduction> Add for Residue<'_, R> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        debug_assert_eq!(self.field.modulus(), other.field.modulus());
+        Self { word: self.field.add(self.word, other.word), field: self.field }
+    }
+}
+
+impl<R: Reduction> Sub for Residue<'_, R> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        debug_assert_eq!(self.field.modulus(), other.field.modulus());
+        Self { word: self.field.sub(self.word, other.word), field: self.field }
+    }
+}
+
+impl<R: Reduction> Mul for Residue<'_, R> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        debug_assert_eq!(self.field.modulus(), other.field.modulus());
+        Self { word: self.field.mul(self.word, other.word), field: self.field }
+    }
+}
+
+/// AVX2 kernels on eight 32-bit residues at a time, for n < 2^31
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// `a += b` on whole blocks of eight; returns how many lanes were done
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add_assign(modulus: u32, a: &mut [u32], b: &[u32]) -> usize {
+        let len = a.len().min(b.len()) / 8 * 8;
+        let n = _mm256_set1_epi32(modulus as i32);
+        for i in (0..len).step_by(8) {
+            let pa = a.as_mut_ptr().add(i) as *mut __m256i;
+            let x = _mm256_loadu_si256(pa);
+            let y = _mm256_loadu_si256(b.as_ptr().add(i) as *const __m256i);
+            // s < 2n, and s - n wraps above s exactly when s < n
+            let s = _mm256_add_epi32(x, y);
+            _mm256_storeu_si256(pa, _mm256_min_epu32(s, _mm256_sub_epi32(s, n)));
+        }
+        len
+    }
+
+    /// `a -= b` on whole blocks of eight; returns how many lanes were done
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sub_assign(modulus: u32, a: &mut [u32], b: &[u32]) -> usize {
+        let len = a.len().min(b.len()) / 8 * 8;
+        let n = _mm256_set1_epi32(modulus as i32);
+        for i in (0..len).step_by(8) {
+            let pa = a.as_mut_ptr().add(i) as *mut __m256i;
+            let x = _mm256_loadu_si256(pa);
+            let y = _mm256_loadu_si256(b.as_ptr().add(i) as *const __m256i);
+            // d wraps high when x < y, and then d + n is the residue
+            let d = _mm256_sub_epi32(x, y);
+            _mm256_storeu_si256(pa, _mm256_min_epu32(d, _mm256_add_epi32(d, n)));
+        }
+        len
+    }
+
+    /// Montgomery `a *= b` on whole blocks of eight, with `neg_inv` = -n⁻¹ mod 2^32
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn montgomery_mul_assign(modulus: u32, neg_inv: u32, a: &mut [u32], b: &[u32]) -> usize {
+        let len = a.len().min(b.len()) / 8 * 8;
+        let n = _mm256_set1_epi32(modulus as i32);
+        let n_prime = _mm256_set1_epi32(neg_inv as i32);
+
+        // (t + m·n) / 2^32 on the four 64-bit lanes, left in their low halves
+        let redc = |x: __m256i, y: __m256i| {
+            let t = _mm256_mul_epu32(x, y);
+            let m = _mm256_mul_epu32(t, n_prime);
+            let mn = _mm256_mul_epu32(m, n);
+            _mm256_srli_epi64(_mm256_add_epi64(t, mn), 32)
+        };
+
+        for i in (0..len).step_by(8) {
+            let pa = a.as_mut_ptr().add(i) as *mut __m256i;
+            let x = _mm256_loadu_si256(pa);
+            let y = _mm256_loadu_si256(b.as_ptr().add(i) as *const __m256i);
+            let even = redc(x, y);
+            let odd = redc(_mm256_srli_epi64(x, 32), _mm256_srli_epi64(y, 32));
+            let u = _mm256_blend_epi32(even, _mm256_slli_epi64(odd, 32), 0b1010_1010);
+            _mm256_storeu_si256(pa, _mm256_min_epu32(u, _mm256_sub_epi32(u, n)));
+        }
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_hi_u128() {
+        assert_eq!(mul_hi_u128(u128::MAX, u128::MAX), u128::MAX - 1);
+        assert_eq!(mul_hi_u128(1 << 64, 1 << 64), 1);
+        assert_eq!(mul_hi_u128(u128::MAX, 2), 1);
+    }
+
+    #[test]
+    fn test_inverse_mod_2_64() {
+        for n in [3u64, 5, 2_147_483_647, MERSENNE_61, u64::MAX] {
+            assert_eq!(n.wrapping_mul(inverse_mod_2_64(n)), 1);
+        }
+    }
+}
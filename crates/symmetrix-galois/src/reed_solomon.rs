@@ -0,0 +1,357 @@
+//! Reed–Solomon erasure coding over GF(2^8)
+//!
+//! A [`ReedSolomon`] code turns `k` data shards into `k + m` shards, the
+//! data itself followed by `m` parity shards, such that any `k` of them
+//! give the data back. The encoding matrix is the identity stacked on an
+//! `m × k` Cauchy matrix; every square submatrix of a Cauchy matrix is
+//! invertible, so every choice of `k` rows is too. At most 256 shards fit
+//! in GF(2^8).
+//!
+//! Large buffers are coded as a stream of stripes (see
+//! [`encode_stream`](ReedSolomon::encode_stream)): each stripe of up to
+//! `k * STRIPE_LEN` input bytes adds at most [`STRIPE_LEN`] bytes to every
+//! shard, so memory stays bounded whatever the input size.
+
+use std::io::{self, Read, Write};
+
+use rayon::prelude::*;
+
+use crate::binary_field::{BinaryField, LogTableField};
+use crate::{GaloisError, GaloisResult};
+
+/// Most shards a code over GF(2^8) can have
+pub const MAX_SHARDS: usize = 256;
+
+/// Bytes each shard gains per full stripe when streaming
+pub const STRIPE_LEN: usize = 64 * 1024;
+
+/// Systematic Reed–Solomon code with `k` data and `m` parity shards
+#[derive(Debug, Clone)]
+pub struct ReedSolomon {
+    data_shards: usize,
+    parity_shards: usize,
+    field: LogTableField,
+    /// Encoding matrix, `k + m` rows of `k` coefficients
+    matrix: Vec<Vec<u8>>,
+    /// Product of every pair of bytes, one row per multiplier
+    mul_table: Vec<[u8; 256]>,
+}
+
+impl ReedSolomon {
+    /// A code with `data_shards` data and `parity_shards` parity shards
+    pub fn new(data_shards: usize, parity_shards: usize) -> GaloisResult<Self> {
+        if data_shards == 0 || data_shards + parity_shards > MAX_SHARDS {
+            return Err(GaloisError::InvalidShardConfig { data: data_shards, parity: parity_shards });
+        }
+        let field = LogTableField::gf256();
+
+        // Cauchy rows 1 / (x_i + y_j), with x_i = k + i and y_j = j all distinct
+        let mut matrix: Vec<Vec<u8>> = (0..data_shards)
+            .map(|i| (0..data_shards).map(|j| (i == j) as u8).collect())
+            .collect();
+        for i in 0..parity_shards {
+            let x = (data_shards + i) as u64;
+            let row = (0..data_shards as u64)
+                .map(|y| field.inverse(x ^ y).map(|inv| inv as u8))
+                .collect::<GaloisResult<Vec<u8>>>()?;
+            matrix.push(row);
+        }
+
+        let mul_table = (0..256u64)
+            .map(|a| {
+                let mut row = [0u8; 256];
+                for (b, product) in row.iter_mut().enumerate() {
+                    *product = field.mul(a, b as u64) as u8;
+                }
+                row
+            })
+            .collect();
+
+        Ok(Self { data_shards, parity_shards, field, matrix, mul_table })
+    }
+
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// `dst ^= coef · src`, byte by byte
+    fn mul_add(&self, coef: u8, src: &[u8], dst: &mut [u8]) {
+        match coef {
+            0 => {}
+            1 => dst.iter_mut().zip(src).for_each(|(d, s)| *d ^= s),
+            _ => {
+                let row = &self.mul_table[coef as usize];
+                dst.iter_mut().zip(src).for_each(|(d, s)| *d ^= row[*s as usize]);
+            }
+        }
+    }
+
+    /// Write into `outputs` the combinations of `inputs` given by `rows`
+    fn combine(&self, rows: &[&[u8]], inputs: &[&[u8]], outputs: &mut [&mut [u8]]) {
+        outputs.par_iter_mut().zip(rows.par_iter()).for_each(|(out, row)| {
+            out.fill(0);
+            for (&coef, input) in row.iter().zip(inputs) {
+                self.mul_add(coef, input, out);
+            }
+        });
+    }
+
+    /// Length shared by every shard, or an error naming the first that differs
+    fn shard_len<'a>(&self, shards: impl IntoIterator<Item = &'a [u8]>) -> GaloisResult<usize> {
+        let mut shards = shards.into_iter();
+        let len = shards.next().map_or(0, <[u8]>::len);
+        match shards.find(|shard| shard.len() != len) {
+            Some(shard) => Err(GaloisError::DimensionMismatch { expected: len, actual: shard.len() }),
+            None => Ok(len),
+        }
+    }
+
+    /// Fill `parity` from `data`; all shards must have the same length
+    pub fn encode_parity(&self, data: &[&[u8]], parity: &mut [&mut [u8]]) -> GaloisResult<()> {
+        if data.len() != self.data_shards {
+            return Err(GaloisError::DimensionMismatch { expected: self.data_shards, actual: data.len() });
+        }
+        if parity.len() != self.parity_shards {
+            return Err(GaloisError::DimensionMismatch { expected: self.parity_shards, actual: parity.len() });
+        }
+        self.shard_len(data.iter().copied().chain(parity.iter().map(|p| &**p)))?;
+
+        let rows: Vec<&[u8]> = self.matrix[self.data_shards..].iter().map(Vec::as_slice).collect();
+        self.combine(&rows, data, parity);
+        Ok(())
+    }
+
+    /// Compute the parity shards, the last `m` of `shards`, from the first `k`
+    pub fn encode(&self, shards: &mut [Vec<u8>]) -> GaloisResult<()> {
+        if shards.len() != self.total_shards() {
+            return Err(GaloisError::DimensionMismatch { expected: self.total_shards(), actual: shards.len() });
+        }
+        let (data, parity) = shards.split_at_mut(self.data_shards);
+        let data: Vec<&[u8]> = data.iter().map(Vec::as_slice).collect();
+        let mut parity: Vec<&mut [u8]> = parity.iter_mut().map(Vec::as_mut_slice).collect();
+        self.encode_parity(&data, &mut parity)
+    }
+
+    /// Whether the parity shards match the data shards
+    pub fn verify(&self, shards: &[Vec<u8>]) -> GaloisResult<bool> {
+        if shards.len() != self.total_shards() {
+            return Err(GaloisError::DimensionMismatch { expected: self.total_shards(), actual: shards.len() });
+        }
+        let len = self.shard_len(shards.iter().map(Vec::as_slice))?;
+        let mut expected = vec![vec![0u8; len]; self.total_shards()];
+        expected[..self.data_shards].clone_from_slice(&shards[..self.data_shards]);
+        self.encode(&mut expected)?;
+        Ok(expected[self.data_shards..] == shards[self.data_shards..])
+    }
+
+    /// Refill the missing (`None`) shards from any `k` that are present
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> GaloisResult<()> {
+        if shards.len() != self.total_shards() {
+            return Err(GaloisError::DimensionMismatch { expected: self.total_shards(), actual: shards.len() });
+        }
+        let present: Vec<usize> = (0..shards.len()).filter(|&i| shards[i].is_some()).collect();
+        if present.len() < self.data_shards {
+            return Err(GaloisError::TooFewShards { needed: self.data_shards, present: present.len() });
+        }
+        let len = self.shard_len(shards.iter().flatten().map(Vec::as_slice))?;
+        if present.len() == shards.len() {
+            return Ok(());
+        }
+
+        // Data first, from the first k shards present
+        let missing_data: Vec<usize> = (0..self.data_shards).filter(|&i| shards[i].is_none()).collect();
+        if !missing_data.is_empty() {
+            let sources = &present[..self.data_shards];
+            let decode = self.decode_matrix(sources)?;
+            let rows: Vec<&[u8]> = missing_data.iter().map(|&i| decode[i].as_slice()).collect();
+            let mut recovered = vec![vec![0u8; len]; missing_data.len()];
+            {
+                let inputs: Vec<&[u8]> = sources.iter().map(|&i| shards[i].as_deref().unwrap_or_default()).collect();
+                let mut outputs: Vec<&mut [u8]> = recovered.iter_mut().map(Vec::as_mut_slice).collect();
+                self.combine(&rows, &inputs, &mut outputs);
+            }
+            for (i, shard) in missing_data.into_iter().zip(recovered) {
+                shards[i] = Some(shard);
+            }
+        }
+
+        // Then parity, from the data
+        let missing_parity: Vec<usize> = (self.data_shards..shards.len()).filter(|&i| shards[i].is_none()).collect();
+        if !missing_parity.is_empty() {
+            let rows: Vec<&[u8]> = missing_parity.iter().map(|&i| self.matrix[i].as_slice()).collect();
+            let mut recovered = vec![vec![0u8; len]; missing_parity.len()];
+            {
+                let inputs: Vec<&[u8]> = shards[..self.data_shards].iter().map(|s| s.as_deref().unwrap_or_default()).collect();
+                let mut outputs: Vec<&mut [u8]> = recovered.iter_mut().map(Vec::as_mut_slice).collect();
+                self.combine(&rows, &inputs, &mut outputs);
+            }
+            for (i, shard) in missing_parity.into_iter().zip(recovered) {
+                shards[i] = Some(shard);
+            }
+        }
+        Ok(())
+    }
+
+    /// Inverse of the encoding rows `sources`, which turns those shards back into data
+    fn decode_matrix(&self, sources: &[usize]) -> GaloisResult<Vec<Vec<u8>>> {
+        let k = self.data_shards;
+        let field = &self.field;
+        let mut a: Vec<Vec<u8>> = sources.iter().map(|&i| self.matrix[i].clone()).collect();
+        let mut inv: Vec<Vec<u8>> = (0..k).map(|i| (0..k).map(|j| (i == j) as u8).collect()).collect();
+
+        // Gauss–Jordan elimination
+        for col in 0..k {
+            let pivot = (col..k)
+                .find(|&row| a[row][col] != 0)
+                .ok_or_else(|| GaloisError::InvalidElement("singular decoding matrix".to_string()))?;
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = field.inverse(a[col][col] as u64)? as u8;
+            for j in 0..k {
+                a[col][j] = field.mul(a[col][j] as u64, scale as u64) as u8;
+                inv[col][j] = field.mul(inv[col][j] as u64, scale as u64) as u8;
+            }
+            for row in (0..k).filter(|&row| row != col) {
+                let factor = a[row][col];
+                if factor != 0 {
+                    let (pivot_a, pivot_inv) = (a[col].clone(), inv[col].clone());
+                    self.mul_add(factor, &pivot_a, &mut a[row]);
+                    self.mul_add(factor, &pivot_inv, &mut inv[row]);
+                }
+            }
+        }
+        Ok(inv)
+    }
+
+    /// Bytes each shard holds for the stripe starting `remaining` bytes before the end
+    fn stripe_len(&self, remaining: u64) -> usize {
+        remaining.div_ceil(self.data_shards as u64).min(STRIPE_LEN as u64) as usize
+    }
+
+    /// Code everything read from `input` into one stream per shard; returns the bytes read
+    ///
+    /// Each stripe of `k * STRIPE_LEN` input bytes is split into `k` data
+    /// shards; the last, shorter stripe is zero-padded to a multiple of `k`.
+    /// The input length is needed to decode, as the padding is not marked.
+    pub fn encode_stream<R: Read, W: Write>(&self, mut input: R, outputs: &mut [W]) -> io::Result<u64> {
+        if outputs.len() != self.total_shards() {
+            let message = format!("expected {} shard outputs, got {}", self.total_shards(), outputs.len());
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        }
+        let k = self.data_shards;
+        let mut buffer = vec![0u8; k * STRIPE_LEN];
+        let mut parity = vec![vec![0u8; STRIPE_LEN]; self.parity_shards];
+        let mut total = 0u64;
+
+        loop {
+            let mut filled = 0;
+            while filled < buffer.len() {
+                match input.read(&mut buffer[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            if filled == 0 {
+                break;
+            }
+            total += filled as u64;
+
+            let chunk = self.stripe_len(filled as u64);
+            buffer[filled..k * chunk].fill(0);
+            let data: Vec<&[u8]> = buffer[..k * chunk].chunks_exact(chunk).collect();
+            let mut parity_out: Vec<&mut [u8]> = parity.iter_mut().map(|p| &mut p[..chunk]).collect();
+            self.encode_parity(&data, &mut parity_out).map_err(io::Error::other)?;
+
+            for (output, shard) in outputs.iter_mut().zip(data.iter().copied().chain(parity_out.iter().map(|p| &**p))) {
+                output.write_all(shard)?;
+            }
+            if filled < buffer.len() {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Write the `len` input bytes back from the shard streams that are present
+    pub fn reconstruct_stream<R: Read, W: Write>(
+        &self,
+        inputs: &mut [Option<R>],
+        mut output: W,
+        len: u64,
+    ) -> io::Result<()> {
+        if inputs.len() != self.total_shards() {
+            let message = format!("expected {} shard inputs, got {}", self.total_shards(), inputs.len());
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        }
+        let k = self.data_shards;
+        let sources: Vec<usize> = (0..inputs.len()).filter(|&i| inputs[i].is_some()).take(k).collect();
+        if sources.len() < k {
+            return Err(io::Error::other(GaloisError::TooFewShards { needed: k, present: sources.len() }));
+        }
+        // Only data shards are read when they are all there
+        let decode = if sources == (0..k).collect::<Vec<_>>() {
+            None
+        } else {
+            Some(self.decode_matrix(&sources).map_err(io::Error::other)?)
+        };
+
+        let mut stripes = vec![vec![0u8; STRIPE_LEN]; k];
+        let mut data = vec![vec![0u8; STRIPE_LEN]; k];
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk = self.stripe_len(remaining);
+            for (stripe, &i) in stripes.iter_mut().zip(&sources) {
+                if let Some(input) = inputs[i].as_mut() {
+                    input.read_exact(&mut stripe[..chunk])?;
+                }
+            }
+            let shards: &[Vec<u8>] = match &decode {
+                None => &stripes,
+                Some(decode) => {
+                    let rows: Vec<&[u8]> = decode.iter().map(Vec::as_slice).collect();
+                    let ins: Vec<&[u8]> = stripes.iter().map(|s| &s[..chunk]).collect();
+                    let mut outs: Vec<&mut [u8]> = data.iter_mut().map(|d| &mut d[..chunk]).collect();
+                    self.combine(&rows, &ins, &mut outs);
+                    &data
+                }
+            };
+
+            let mut left = remaining.min((k * chunk) as u64) as usize;
+            remaining -= left as u64;
+            for shard in shards {
+                let take = left.min(chunk);
+                output.write_all(&shard[..take])?;
+                left -= take;
+            }
+        }
+        output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_square_submatrix_inverts() {
+        let rs = ReedSolomon::new(3, 3).unwrap();
+        for a in 0..6 {
+            for b in a + 1..6 {
+                for c in b + 1..6 {
+                    assert!(rs.decode_matrix(&[a, b, c]).is_ok(), "rows {} {} {}", a, b, c);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,562 @@
+//! Residue number system integers
+//!
+//! [`RnsInt`] is a signed integer held as its residues modulo a basis of
+//! pairwise coprime moduli, the representation [`CRTDecomposition`]
+//! computes. Addition, subtraction and multiplication work channel by
+//! channel; everything that needs the magnitude (sign, comparison, range
+//! checks, base extension and division by a modulus) goes through the
+//! mixed-radix form
+//!
+//! x = d₀ + d₁·m₀ + d₂·m₀m₁ + … + dₙ₋₁·m₀⋯mₙ₋₂,  0 ≤ dᵢ < mᵢ,
+//!
+//! whose digits follow from the residues in O(n²) word operations and order
+//! values like the digits of an ordinary number.
+//!
+//! With M the product of the moduli, the residues represent the range
+//! [-⌈(M - 1)/2⌉, ⌊(M - 1)/2⌋], the upper half of `0..M` holding the
+//! negatives. Operations that would leave it fail with
+//! [`GaloisError::RnsOverflow`] rather than wrap.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::sync::{Arc, OnceLock};
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::reduction::{Barrett64, Reduction};
+use crate::{CRTDecomposition, GaloisElement, GaloisError, GaloisResult, CRT_PRIMES};
+
+/// First candidate for the redundant moduli of a wide basis
+const WIDE_MODULUS_START: u64 = (1 << 62) - 1;
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// `a⁻¹ mod m`, if `a` and `m` are coprime
+fn inverse_mod(a: u64, m: u64) -> Option<u64> {
+    let (mut old_r, mut r) = (a as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    (old_r == 1).then(|| old_s.rem_euclid(m as i128) as u64)
+}
+
+/// Order two mixed-radix digit strings of the same basis
+fn cmp_digits(a: &[u64], b: &[u64]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+/// Pairwise coprime moduli shared by the [`RnsInt`]s built on them
+#[derive(Debug)]
+pub struct RnsBasis {
+    moduli: Vec<u64>,
+    reducers: Vec<Barrett64>,
+    /// `inverses[i][j]` is m_j⁻¹ mod m_i, for j < i
+    inverses: Vec<Vec<u64>>,
+    /// Product M of the moduli
+    product: BigUint,
+    /// Mixed-radix digits of the largest value, ⌊(M - 1)/2⌋
+    max_digits: Vec<u64>,
+    /// Mixed-radix digits of the magnitude of the smallest value, ⌈(M - 1)/2⌉
+    min_digits: Vec<u64>,
+    /// These moduli followed by redundant ones whose product is at least M,
+    /// so that any product of two values is exact; built on first use
+    wide: OnceLock<Arc<RnsBasis>>,
+}
+
+impl RnsBasis {
+    /// A basis over `moduli`, which must be at least 2 and pairwise coprime
+    pub fn new(moduli: &[u64]) -> GaloisResult<Arc<Self>> {
+        if moduli.is_empty() {
+            return Err(GaloisError::CRTError("RNS basis needs at least one modulus".to_string()));
+        }
+        for (i, &m) in moduli.iter().enumerate() {
+            if m < 2 || moduli[..i].iter().any(|&earlier| gcd(earlier, m) != 1) {
+                return Err(GaloisError::InvalidModulus(m));
+            }
+        }
+
+        let reducers = moduli.iter().map(|&m| Barrett64::new(m)).collect::<GaloisResult<Vec<_>>>()?;
+        let inverses = moduli
+            .iter()
+            .enumerate()
+            .map(|(i, &m)| moduli[..i].iter().map(|&earlier| inverse_mod(earlier % m, m).unwrap()).collect())
+            .collect();
+        let product: BigUint = moduli.iter().map(|&m| BigUint::from(m)).product();
+        let max = (&product - 1u32) / 2u32;
+        let min = &product - 1u32 - &max;
+
+        let mut basis = Self {
+            moduli: moduli.to_vec(),
+            reducers,
+            inverses,
+            product,
+            max_digits: Vec::new(),
+            min_digits: Vec::new(),
+            wide: OnceLock::new(),
+        };
+        basis.max_digits = basis.digits_of(&max).unwrap();
+        basis.min_digits = basis.digits_of(&min).unwrap();
+        Ok(Arc::new(basis))
+    }
+
+    /// The basis of [`CRT_PRIMES`]
+    pub fn crt() -> Arc<Self> {
+        Self::new(CRT_PRIMES).expect("CRT primes are distinct primes")
+    }
+
+    pub fn moduli(&self) -> &[u64] {
+        &self.moduli
+    }
+
+    /// Product M of the moduli
+    pub fn product(&self) -> &BigUint {
+        &self.product
+    }
+
+    /// Largest representable value, ⌊(M - 1)/2⌋
+    pub fn max_value(&self) -> BigInt {
+        BigInt::from((&self.product - 1u32) / 2u32)
+    }
+
+    /// Smallest representable value, -⌈(M - 1)/2⌉
+    pub fn min_value(&self) -> BigInt {
+        -BigInt::from(&self.product - 1u32 - (&self.product - 1u32) / 2u32)
+    }
+
+    /// Mixed-radix digits of `x`, or `None` if `x ≥ M`
+    fn digits_of(&self, x: &BigUint) -> Option<Vec<u64>> {
+        let mut rest = x.clone();
+        let digits = self
+            .moduli
+            .iter()
+            .map(|&m| {
+                let digit = (&rest % m).to_u64().unwrap();
+                rest /= m;
+                digit
+            })
+            .collect();
+        rest.is_zero().then_some(digits)
+    }
+
+    /// Mixed-radix digits of `residues` over the moduli other than `skip`,
+    /// each paired with the index of its modulus
+    fn digits(&self, residues: &[u64], skip: Option<usize>) -> Vec<(usize, u64)> {
+        let mut digits: Vec<(usize, u64)> = Vec::with_capacity(residues.len());
+        for (i, (&x, field)) in residues.iter().zip(&self.reducers).enumerate() {
+            if Some(i) == skip {
+                continue;
+            }
+            let digit = digits
+                .iter()
+                .fold(x, |t, &(j, d)| field.mul(field.sub(t, field.enter(d)), self.inverses[i][j]));
+            digits.push((i, digit));
+        }
+        digits
+    }
+
+    /// The value with mixed-radix `digits` reduced modulo `field`
+    fn evaluate(&self, digits: &[(usize, u64)], field: &Barrett64) -> u64 {
+        digits
+            .iter()
+            .rev()
+            .fold(0, |acc, &(j, d)| field.add(field.mul(acc, field.enter(self.moduli[j])), field.enter(d)))
+    }
+
+    /// Product of the moduli other than `skip`, reduced modulo `field`
+    fn product_mod(&self, field: &Barrett64, skip: Option<usize>) -> u64 {
+        self.moduli
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| Some(j) != skip)
+            .fold(field.enter(1), |acc, (_, &m)| field.mul(acc, field.enter(m)))
+    }
+
+    /// Whether the full mixed-radix `digits` lie in the negative half
+    fn is_upper_half(&self, digits: &[u64]) -> bool {
+        cmp_digits(digits, &self.max_digits) == Ordering::Greater
+    }
+
+    fn wide(&self) -> &Arc<RnsBasis> {
+        self.wide.get_or_init(|| {
+            let mut moduli = self.moduli.clone();
+            let mut extra = BigUint::one();
+            let mut candidate = WIDE_MODULUS_START;
+            while extra < self.product {
+                if moduli.iter().all(|&m| gcd(m, candidate) == 1) {
+                    moduli.push(candidate);
+                    extra *= candidate;
+                }
+                candidate -= 2;
+            }
+            RnsBasis::new(&moduli).expect("redundant moduli are coprime to the basis")
+        })
+    }
+}
+
+/// A signed integer in residue number system form over an [`RnsBasis`]
+#[derive(Debug, Clone)]
+pub struct RnsInt {
+    residues: Vec<u64>,
+    basis: Arc<RnsBasis>,
+}
+
+impl RnsInt {
+    pub fn zero(basis: &Arc<RnsBasis>) -> Self {
+        Self { residues: vec![0; basis.moduli.len()], basis: Arc::clone(basis) }
+    }
+
+    /// The value with the given residues, one below each modulus
+    pub fn from_residues(residues: Vec<u64>, basis: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        Self::check_below_moduli(&residues, basis)?;
+        Ok(Self { residues, basis: Arc::clone(basis) })
+    }
+
+    /// The value whose residues in `0..M` have the given mixed-radix digits
+    pub fn from_mixed_radix(digits: &[u64], basis: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        Self::check_below_moduli(digits, basis)?;
+        Ok(Self::from_digits(digits, basis))
+    }
+
+    pub fn from_i64(value: i64, basis: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        Self::from_bigint(&BigInt::from(value), basis)
+    }
+
+    pub fn from_biguint(value: &BigUint, basis: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        Self::from_magnitude(value, false, basis)
+    }
+
+    pub fn from_bigint(value: &BigInt, basis: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        Self::from_magnitude(value.magnitude(), value.sign() == Sign::Minus, basis)
+    }
+
+    /// The signed value of a decomposition whose primes are `basis`
+    pub fn from_crt(decomposition: &CRTDecomposition, basis: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        if decomposition.primes != basis.moduli {
+            return Err(GaloisError::CRTError("Incompatible CRT decompositions".to_string()));
+        }
+        let residues = decomposition.residues.iter().map(|residue| residue.value).collect();
+        Self::from_residues(residues, basis)
+    }
+
+    fn check_below_moduli(values: &[u64], basis: &RnsBasis) -> GaloisResult<()> {
+        if values.len() != basis.moduli.len() {
+            return Err(GaloisError::DimensionMismatch { expected: basis.moduli.len(), actual: values.len() });
+        }
+        match values.iter().zip(&basis.moduli).find(|&(&v, &m)| v >= m) {
+            Some((v, m)) => Err(GaloisError::InvalidElement(format!("{} is not below modulus {}", v, m))),
+            None => Ok(()),
+        }
+    }
+
+    fn from_digits(digits: &[u64], basis: &Arc<RnsBasis>) -> Self {
+        let digits: Vec<(usize, u64)> = digits.iter().copied().enumerate().collect();
+        let residues = basis.reducers.iter().map(|field| basis.evaluate(&digits, field)).collect();
+        Self { residues, basis: Arc::clone(basis) }
+    }
+
+    fn from_magnitude(magnitude: &BigUint, negative: bool, basis: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        let limit = if negative { &basis.min_digits } else { &basis.max_digits };
+        let digits = basis
+            .digits_of(magnitude)
+            .filter(|digits| cmp_digits(digits, limit) != Ordering::Greater)
+            .ok_or(GaloisError::RnsOverflow)?;
+        let value = Self::from_digits(&digits, basis);
+        Ok(if negative { value.negated() } else { value })
+    }
+
+    pub fn residues(&self) -> &[u64] {
+        &self.residues
+    }
+
+    pub fn basis(&self) -> &Arc<RnsBasis> {
+        &self.basis
+    }
+
+    /// Mixed-radix digits of the residues read as a value in `0..M`
+    pub fn to_mixed_radix(&self) -> Vec<u64> {
+        self.basis.digits(&self.residues, None).into_iter().map(|(_, d)| d).collect()
+    }
+
+    /// The residues read as a value in `0..M`
+    fn to_unsigned(&self, digits: &[u64]) -> BigUint {
+        digits.iter().zip(&self.basis.moduli).rev().fold(BigUint::zero(), |acc, (&d, &m)| acc * m + d)
+    }
+
+    pub fn to_bigint(&self) -> BigInt {
+        let digits = self.to_mixed_radix();
+        let unsigned = BigInt::from(self.to_unsigned(&digits));
+        if self.basis.is_upper_half(&digits) {
+            unsigned - BigInt::from(self.basis.product.clone())
+        } else {
+            unsigned
+        }
+    }
+
+    pub fn to_biguint(&self) -> GaloisResult<BigUint> {
+        let digits = self.to_mixed_radix();
+        if self.basis.is_upper_half(&digits) {
+            return Err(GaloisError::InvalidElement("negative value has no unsigned form".to_string()));
+        }
+        Ok(self.to_unsigned(&digits))
+    }
+
+    /// The residues as a decomposition; negative values come back from
+    /// [`CRTDecomposition::reconstruct`] as `x + M`
+    pub fn to_crt(&self) -> CRTDecomposition {
+        CRTDecomposition {
+            residues: self.residues.iter().zip(&self.basis.moduli).map(|(&r, &m)| GaloisElement::new(r, m)).collect(),
+            primes: self.basis.moduli.clone(),
+            modulus_product: self.basis.product.clone(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.residues.iter().all(|&r| r == 0)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.basis.is_upper_half(&self.to_mixed_radix())
+    }
+
+    fn check_basis(&self, other: &Self) -> GaloisResult<()> {
+        if Arc::ptr_eq(&self.basis, &other.basis) || self.basis.moduli == other.basis.moduli {
+            Ok(())
+        } else {
+            Err(GaloisError::CRTError("Incompatible RNS bases".to_string()))
+        }
+    }
+
+    /// Combine the residues of `self` and `other` channel by channel
+    fn zip_with(&self, other: &Self, op: impl Fn(&Barrett64, u64, u64) -> u64) -> GaloisResult<Self> {
+        self.check_basis(other)?;
+        let residues = self
+            .residues
+            .iter()
+            .zip(&other.residues)
+            .zip(&self.basis.reducers)
+            .map(|((&a, &b), field)| op(field, a, b))
+            .collect();
+        Ok(Self { residues, basis: Arc::clone(&self.basis) })
+    }
+
+    /// Negation modulo M, without the range check
+    fn negated(&self) -> Self {
+        let residues = self.residues.iter().zip(&self.basis.moduli).map(|(&r, &m)| if r == 0 { 0 } else { m - r }).collect();
+        Self { residues, basis: Arc::clone(&self.basis) }
+    }
+
+    pub fn checked_add(&self, other: &Self) -> GaloisResult<Self> {
+        let sum = self.zip_with(other, |field, a, b| field.add(a, b))?;
+        // Operands of one sign overflow into the other half of the range
+        let negative = self.is_negative();
+        if negative == other.is_negative() && sum.is_negative() != negative {
+            return Err(GaloisError::RnsOverflow);
+        }
+        Ok(sum)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> GaloisResult<Self> {
+        let difference = self.zip_with(other, |field, a, b| field.sub(a, b))?;
+        let negative = self.is_negative();
+        if negative != other.is_negative() && difference.is_negative() != negative {
+            return Err(GaloisError::RnsOverflow);
+        }
+        Ok(difference)
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> GaloisResult<Self> {
+        self.check_basis(other)?;
+        // Over the wide basis the product is exact, and narrowing it back
+        // checks that it fits
+        let wide = self.basis.wide();
+        let product = self.extend_unchecked(wide).zip_with(&other.extend_unchecked(wide), |field, a, b| field.mul(a, b))?;
+        product.extend(&self.basis)
+    }
+
+    pub fn checked_neg(&self) -> GaloisResult<Self> {
+        let negated = self.negated();
+        // Only the smallest value of an even M lacks a negation, and it
+        // negates to itself
+        if !negated.is_zero() && negated.residues == self.residues {
+            return Err(GaloisError::RnsOverflow);
+        }
+        Ok(negated)
+    }
+
+    pub fn abs(&self) -> GaloisResult<Self> {
+        if self.is_negative() { self.checked_neg() } else { Ok(self.clone()) }
+    }
+
+    /// The same value over `target`, found by base extension through the
+    /// mixed-radix digits
+    pub fn extend(&self, target: &Arc<RnsBasis>) -> GaloisResult<Self> {
+        let extended = self.extend_unchecked(target);
+        // A narrower range holds the value exactly when it comes back intact
+        if target.product < self.basis.product && extended.extend_unchecked(&self.basis).residues != self.residues {
+            return Err(GaloisError::RnsOverflow);
+        }
+        Ok(extended)
+    }
+
+    /// [`Self::extend`] for a value known to lie in the range of `target`
+    fn extend_unchecked(&self, target: &Arc<RnsBasis>) -> Self {
+        let digits = self.basis.digits(&self.residues, None);
+        let full: Vec<u64> = digits.iter().map(|&(_, d)| d).collect();
+        let negative = self.basis.is_upper_half(&full);
+        let residues = target
+            .reducers
+            .iter()
+            .map(|field| {
+                let unsigned = self.basis.evaluate(&digits, field);
+                // The digits spell x + M for negative x
+                if negative { field.sub(unsigned, self.basis.product_mod(field, None)) } else { unsigned }
+            })
+            .collect();
+        Self { residues, basis: Arc::clone(target) }
+    }
+
+    /// Euclidean division by the modulus at `index`: `self = q·mᵢ + r` with
+    /// `0 ≤ r < mᵢ`
+    pub fn div_rem_modulus(&self, index: usize) -> GaloisResult<(Self, u64)> {
+        let basis = &self.basis;
+        if index >= basis.moduli.len() {
+            return Err(GaloisError::InvalidElement(format!("no modulus at index {}", index)));
+        }
+        let (divisor, remainder) = (basis.moduli[index], self.residues[index]);
+
+        // Every other channel divides exactly
+        let mut residues: Vec<u64> = self
+            .residues
+            .iter()
+            .zip(&basis.reducers)
+            .enumerate()
+            .map(|(j, (&x, field))| {
+                if j == index {
+                    return 0;
+                }
+                let inverse = if j > index {
+                    basis.inverses[j][index]
+                } else {
+                    inverse_mod(divisor % basis.moduli[j], basis.moduli[j]).unwrap()
+                };
+                field.mul(field.sub(x, field.enter(remainder)), inverse)
+            })
+            .collect();
+
+        // The lost channel comes from the others by base extension. Their
+        // product K bounds the quotient, -K ≤ q < K, and q takes the sign of
+        // the dividend, which together fix it.
+        let field = &basis.reducers[index];
+        let digits = basis.digits(&residues, Some(index));
+        let unsigned = basis.evaluate(&digits, field);
+        residues[index] = if self.is_negative() {
+            field.sub(unsigned, basis.product_mod(field, Some(index)))
+        } else {
+            unsigned
+        };
+        Ok((Self { residues, basis: Arc::clone(basis) }, remainder))
+    }
+}
+
+impl PartialEq for RnsInt {
+    fn eq(&self, other: &Self) -> bool {
+        self.check_basis(other).is_ok() && self.residues == other.residues
+    }
+}
+
+impl Eq for RnsInt {}
+
+impl PartialOrd for RnsInt {
+    /// Values over different bases are unordered
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.check_basis(other).ok()?;
+        let (a, b) = (self.to_mixed_radix(), other.to_mixed_radix());
+        // Within one sign the digits order the values; negatives sort first
+        match (self.basis.is_upper_half(&a), self.basis.is_upper_half(&b)) {
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            _ => Some(cmp_digits(&a, &b)),
+        }
+    }
+}
+
+impl fmt::Display for RnsInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_bigint())
+    }
+}
+
+macro_rules! checked_binary_op {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl $trait for &RnsInt {
+            type Output = GaloisResult<RnsInt>;
+
+            fn $method(self, other: Self) -> Self::Output {
+                self.$checked(other)
+            }
+        }
+
+        impl $trait for RnsInt {
+            type Output = GaloisResult<RnsInt>;
+
+            fn $method(self, other: Self) -> Self::Output {
+                self.$checked(&other)
+            }
+        }
+    };
+}
+
+checked_binary_op!(Add, add, checked_add);
+checked_binary_op!(Sub, sub, checked_sub);
+checked_binary_op!(Mul, mul, checked_mul);
+
+impl Neg for &RnsInt {
+    type Output = GaloisResult<RnsInt>;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg()
+    }
+}
+
+impl Neg for RnsInt {
+    type Output = GaloisResult<RnsInt>;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverse_mod() {
+        assert_eq!(inverse_mod(3, 7), Some(5));
+        assert_eq!(inverse_mod(6, 9), None);
+        let m = u64::MAX;
+        let a = u64::MAX - 1;
+        let inverse = inverse_mod(a, m).unwrap();
+        assert_eq!((a as u128 * inverse as u128 % m as u128), 1);
+    }
+
+    #[test]
+    fn test_small_basis_range() {
+        let basis = RnsBasis::new(&[3, 5, 7]).unwrap();
+        assert_eq!(basis.max_value(), BigInt::from(52));
+        assert_eq!(basis.min_value(), BigInt::from(-52));
+        let even = RnsBasis::new(&[4, 5]).unwrap();
+        assert_eq!(even.max_value(), BigInt::from(9));
+        assert_eq!(even.min_value(), BigInt::from(-10));
+    }
+}
@@ -0,0 +1,155 @@
+//! GF(2^n) field axioms
+//!
+//! GF(2^8) is checked over every element, pair and triple; larger fields
+//! are checked on random samples, with both implementations agreeing.
+
+use proptest::prelude::*;
+use symmetrix_galois::binary_field::{AES_POLY, GF256_POLY, GF65536_POLY};
+use symmetrix_galois::{BinaryField, ClmulField, GaloisError, LogTableField};
+
+/// x^32 + x^22 + x^2 + x + 1
+const GF2_32_POLY: u64 = 0x1_0040_0007;
+/// x^63 + x + 1
+const GF2_63_POLY: u64 = (1 << 63) | 0b11;
+
+#[test]
+fn test_gf256_axioms_exhaustively() {
+    let field = LogTableField::gf256();
+    let clmul = ClmulField::new(8, GF256_POLY).unwrap();
+
+    for a in 0..256 {
+        assert_eq!(field.add(a, 0), a);
+        assert_eq!(field.add(a, a), 0);
+        assert_eq!(field.mul(a, 1), a);
+        assert_eq!(field.mul(a, 0), 0);
+        if a != 0 {
+            let inv = field.inverse(a).unwrap();
+            assert_eq!(field.mul(a, inv), 1, "inverse of {}", a);
+            assert_eq!(clmul.inverse(a).unwrap(), inv);
+        }
+        for b in 0..256 {
+            let ab = field.mul(a, b);
+            assert_eq!(ab, field.mul(b, a));
+            assert_eq!(ab, clmul.mul(a, b), "{} * {}", a, b);
+            for c in 0..256 {
+                assert_eq!(field.mul(ab, c), field.mul(a, field.mul(b, c)));
+                assert_eq!(field.mul(a, b ^ c), ab ^ field.mul(a, c));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_gf256_has_no_zero_divisors_and_a_generator() {
+    let field = LogTableField::gf256();
+    assert_eq!(field.generator(), 2, "GF256_POLY is primitive");
+
+    let mut seen = [false; 256];
+    for i in 0..255 {
+        let power = field.exp(i);
+        assert!(!seen[power as usize], "x^{} repeats", i);
+        seen[power as usize] = true;
+        assert_eq!(field.log(power).unwrap(), i);
+    }
+    assert!(!seen[0]);
+    assert!(field.log(0).is_err());
+}
+
+#[test]
+fn test_aes_field_matches_fips_197() {
+    // FIPS-197 section 4.2: {57} • {83} = {c1}, and {53} inverts to {ca}
+    let table = LogTableField::new(8, AES_POLY).unwrap();
+    let clmul = ClmulField::new(8, AES_POLY).unwrap();
+    assert_eq!(table.mul(0x57, 0x83), 0xC1);
+    assert_eq!(clmul.mul(0x57, 0x83), 0xC1);
+    assert_eq!(table.inverse(0x53).unwrap(), 0xCA);
+    assert_eq!(clmul.inverse(0x53).unwrap(), 0xCA);
+    // x is not primitive here, so the tables are built on another generator
+    assert_eq!(table.generator(), 3);
+}
+
+#[test]
+fn test_pow_div_and_eval() {
+    let field = LogTableField::gf256();
+    for a in 1..256 {
+        assert_eq!(field.pow(a, 255), 1);
+        assert_eq!(field.pow(a, 0), 1);
+        assert_eq!(field.pow(a, 3), field.mul(a, field.mul(a, a)));
+        assert_eq!(field.div(a, a).unwrap(), 1);
+    }
+    assert_eq!(field.pow(0, 0), 1);
+    assert_eq!(field.pow(0, 5), 0);
+    assert!(matches!(field.div(1, 0), Err(GaloisError::DivisionByZero)));
+    assert!(matches!(field.inverse(0), Err(GaloisError::DivisionByZero)));
+
+    // 3 + 2x + x^2 at x = 7
+    let expected = 3 ^ field.mul(2, 7) ^ field.mul(7, 7);
+    assert_eq!(field.eval(&[3, 2, 1], 7), expected);
+    assert_eq!(field.eval(&[], 7), 0);
+}
+
+#[test]
+fn test_reducible_polynomials_are_rejected() {
+    // x^8 + 1 = (x + 1)^8, and x^4 + x^2 + 1 = (x^2 + x + 1)^2
+    for (degree, poly) in [(8, 0x101), (4, 0b10101), (16, 0x10000)] {
+        assert!(matches!(ClmulField::new(degree, poly), Err(GaloisError::InvalidPolynomial(_))));
+        assert!(matches!(LogTableField::new(degree, poly), Err(GaloisError::InvalidPolynomial(_))));
+    }
+    // Wrong degree, or too large for the implementation
+    assert!(ClmulField::new(9, GF256_POLY).is_err());
+    assert!(ClmulField::new(64, 0).is_err());
+    assert!(LogTableField::new(32, GF2_32_POLY).is_err());
+}
+
+#[test]
+fn test_irreducible_polynomial_counts() {
+    // Irreducible polynomials over GF(2): 30 of degree 8, 99 of degree 10
+    for (degree, count) in [(8u32, 30), (10, 99)] {
+        let found = (1u64 << degree..1 << (degree + 1))
+            .filter(|&poly| ClmulField::new(degree, poly).is_ok())
+            .count();
+        assert_eq!(found, count, "degree {}", degree);
+    }
+}
+
+fn check_axioms(field: &dyn BinaryField, a: u64, b: u64, c: u64) -> Result<(), TestCaseError> {
+    let mask = field.order() - 1;
+    let (a, b, c) = (a & mask, b & mask, c & mask);
+    prop_assert_eq!(field.mul(a, b), field.mul(b, a));
+    prop_assert_eq!(field.mul(field.mul(a, b), c), field.mul(a, field.mul(b, c)));
+    prop_assert_eq!(field.mul(a, b ^ c), field.mul(a, b) ^ field.mul(a, c));
+    prop_assert!(field.mul(a, b) <= mask);
+    if a != 0 {
+        prop_assert_eq!(field.mul(a, field.inverse(a).unwrap()), 1);
+        prop_assert_eq!(field.div(field.mul(a, b), a).unwrap(), b);
+        prop_assert!(field.mul(a, b) != 0 || b == 0);
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn prop_gf65536_axioms(a: u64, b: u64, c: u64) {
+        let table = LogTableField::gf65536();
+        let clmul = ClmulField::new(16, GF65536_POLY).unwrap();
+        check_axioms(&table, a, b, c)?;
+        check_axioms(&clmul, a, b, c)?;
+        let (a, b) = (a & 0xFFFF, b & 0xFFFF);
+        prop_assert_eq!(table.mul(a, b), clmul.mul(a, b));
+        prop_assert_eq!(table.pow(a, b), clmul.pow(a, b));
+    }
+
+    #[test]
+    fn prop_gf2_32_axioms(a: u64, b: u64, c: u64) {
+        check_axioms(&ClmulField::new(32, GF2_32_POLY).unwrap(), a, b, c)?;
+    }
+
+    #[test]
+    fn prop_gf2_63_axioms(a: u64, b: u64, c: u64) {
+        let field = ClmulField::new(63, GF2_63_POLY).unwrap();
+        check_axioms(&field, a, b, c)?;
+        // Frobenius: squaring is additive in characteristic 2
+        let (a, b) = (a >> 1, b >> 1);
+        prop_assert_eq!(field.pow(a ^ b, 2), field.pow(a, 2) ^ field.pow(b, 2));
+    }
+}
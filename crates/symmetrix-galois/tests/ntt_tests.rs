@@ -0,0 +1,120 @@
+//! Number-theoretic transform against schoolbook multiplication
+//!
+//! Random polynomials are multiplied both ways, over NTT primes directly
+//! and over other moduli through the three-prime CRT.
+
+use std::sync::Arc;
+
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_galois::ntt::{
+    multiply_mod, multiply_naive, NttPlan, NttPlanCache, NTT_PRIMES, NTT_PRIME_998244353, NTT_THRESHOLD,
+};
+use symmetrix_galois::{GaloisElement, GaloisEngine, CRT_PRIMES, MERSENNE_61};
+
+fn random_poly(rng: &mut StdRng, len: usize, modulus: u64) -> Vec<u64> {
+    (0..len).map(|_| rng.gen_range(0..modulus)).collect()
+}
+
+#[test]
+fn test_forward_then_inverse_is_identity() {
+    let mut rng = StdRng::seed_from_u64(47);
+    for prime in NTT_PRIMES.iter().chain([&NTT_PRIME_998244353]) {
+        for log_len in [0, 1, 2, 5, 10, 14] {
+            let plan = NttPlan::new(*prime, 1 << log_len).unwrap();
+            let original = random_poly(&mut rng, 1 << log_len, prime.modulus);
+            let mut data = original.clone();
+            plan.forward(&mut data).unwrap();
+            plan.inverse(&mut data).unwrap();
+            assert_eq!(data, original, "2^{} points mod {}", log_len, prime.modulus);
+        }
+    }
+}
+
+#[test]
+fn test_forward_evaluates_at_roots_of_unity() {
+    // The first output is the sum of the coefficients, the value at 1
+    let plan = NttPlan::new(NTT_PRIME_998244353, 8).unwrap();
+    let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+    plan.forward(&mut data).unwrap();
+    assert_eq!(data[0], 36);
+
+    // A delta transforms to all ones
+    let mut delta = vec![0; 8];
+    delta[0] = 1;
+    plan.forward(&mut delta).unwrap();
+    assert_eq!(delta, vec![1; 8]);
+}
+
+#[test]
+fn test_multiply_matches_naive_across_moduli() {
+    let mut rng = StdRng::seed_from_u64(2047);
+    let moduli = [NTT_PRIMES[0].modulus, NTT_PRIME_998244353.modulus, MERSENNE_61, CRT_PRIMES[1], u64::MAX, 2];
+    for modulus in moduli {
+        for (la, lb) in [(NTT_THRESHOLD, NTT_THRESHOLD), (100, 300), (513, 511), (1000, 64), (2048, 2048)] {
+            let a = random_poly(&mut rng, la, modulus);
+            let b = random_poly(&mut rng, lb, modulus);
+            assert_eq!(multiply_mod(&a, &b, modulus).unwrap(), multiply_naive(&a, &b, modulus), "{}x{} mod {}", la, lb, modulus);
+        }
+    }
+}
+
+#[test]
+fn test_large_coefficients_are_exact() {
+    // Every coefficient at its maximum drives the convolution to its CRT bound
+    let modulus = u64::MAX;
+    let a = vec![modulus - 1; 4096];
+    let product = multiply_mod(&a, &a, modulus).unwrap();
+    assert_eq!(product, multiply_naive(&a, &a, modulus));
+    assert_eq!(product.len(), 8191);
+}
+
+#[test]
+fn test_engine_multiplies_polynomials() {
+    let engine = GaloisEngine::new(MERSENNE_61);
+    let a: Vec<GaloisElement> = (1..=200).map(|i| engine.element(i * 1_000_003)).collect();
+    let b: Vec<GaloisElement> = (1..=150).map(|i| engine.element(MERSENNE_61 - i)).collect();
+    let product = engine.multiply_polynomials(&a, &b).unwrap();
+
+    let values = |p: &[GaloisElement]| p.iter().map(|x| x.value).collect::<Vec<_>>();
+    assert_eq!(values(&product), multiply_naive(&values(&a), &values(&b), MERSENNE_61));
+    assert!(product.iter().all(|x| x.modulus == MERSENNE_61));
+    assert!(engine.multiply_polynomials(&[], &b).unwrap().is_empty());
+
+    // One plan per CRT prime, reused by the next product of the same size
+    assert_eq!(engine.ntt_plans.len(), NTT_PRIMES.len());
+    engine.multiply_polynomials(&b, &a).unwrap();
+    assert_eq!(engine.ntt_plans.len(), NTT_PRIMES.len());
+}
+
+#[test]
+fn test_plan_cache_reuses_plans() {
+    let cache = NttPlanCache::new();
+    let first = cache.plan(NTT_PRIME_998244353, 256).unwrap();
+    assert!(Arc::ptr_eq(&first, &cache.plan(NTT_PRIME_998244353, 256).unwrap()));
+    assert!(cache.plan(NTT_PRIME_998244353, 12).is_err());
+    assert_eq!(cache.len(), 1);
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let a: Vec<u64> = (0..300).map(|_| rng.gen()).collect();
+    let b: Vec<u64> = (0..100).map(|_| rng.gen()).collect();
+    let modulus = NTT_PRIME_998244353.modulus;
+    assert_eq!(cache.multiply_mod(&a, &b, modulus).unwrap(), multiply_naive(&a, &b, modulus));
+    assert_eq!(cache.len(), 2);
+}
+
+proptest! {
+    #[test]
+    fn prop_multiply_matches_naive(
+        seed: u64,
+        la in 1usize..400,
+        lb in 1usize..400,
+        modulus in prop_oneof![Just(MERSENNE_61), Just(NTT_PRIMES[2].modulus), 2u64..u64::MAX],
+    ) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let a = random_poly(&mut rng, la, modulus);
+        let b = random_poly(&mut rng, lb, modulus);
+        prop_assert_eq!(multiply_mod(&a, &b, modulus).unwrap(), multiply_naive(&a, &b, modulus));
+    }
+}
@@ -0,0 +1,159 @@
+//! Montgomery, Barrett and Mersenne reduction against `GaloisElement`
+//!
+//! Every reduction must agree with the `%`-based reference element on
+//! single operations and on the batched slice kernels, including the AVX2
+//! ones when the CPU has them.
+
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_galois::reduction::reduce_mersenne_61;
+use symmetrix_galois::{
+    simd, Barrett32, Barrett64, GaloisElement, GaloisError, Mersenne61, Montgomery32, Montgomery64, Reduction,
+    Residue, CRT_PRIMES, MERSENNE_61,
+};
+
+/// Check add, sub, mul and pow of `field` against the reference on `a`, `b`
+fn check_against_reference<R: Reduction>(field: &R, a: u64, b: u64, e: u64) -> Result<(), TestCaseError> {
+    let n = field.modulus();
+    let (x, y) = (Residue::new(field, a), Residue::new(field, b));
+    let (ga, gb) = (GaloisElement::new(a, n), GaloisElement::new(b, n));
+    prop_assert_eq!(x.value(), ga.value);
+    prop_assert_eq!((x + y).value(), (ga + gb).value, "{} + {} mod {}", a, b, n);
+    prop_assert_eq!((x - y).value(), (ga - gb).value, "{} - {} mod {}", a, b, n);
+    prop_assert_eq!((x * y).value(), (ga * gb).value, "{} * {} mod {}", a, b, n);
+    prop_assert_eq!(x.pow(e).value(), ga.pow(e).value, "{} ^ {} mod {}", a, e, n);
+    prop_assert_eq!((x * y).to_galois(), ga * gb);
+    Ok(())
+}
+
+/// Check the slice kernels of `field` against the single operations
+fn check_slices<R: Reduction>(field: &R, rng: &mut StdRng, len: usize) {
+    let n = field.modulus();
+    let a: Vec<R::Word> = (0..len).map(|_| field.enter(rng.gen_range(0..n))).collect();
+    let b: Vec<R::Word> = (0..len).map(|_| field.enter(rng.gen_range(0..n))).collect();
+
+    let mut sum = a.clone();
+    simd::add_assign(field, &mut sum, &b).unwrap();
+    let mut difference = a.clone();
+    simd::sub_assign(field, &mut difference, &b).unwrap();
+    let mut product = a.clone();
+    simd::mul_assign(field, &mut product, &b).unwrap();
+
+    for i in 0..len {
+        assert_eq!(sum[i], field.add(a[i], b[i]), "add lane {} of {} mod {}", i, len, n);
+        assert_eq!(difference[i], field.sub(a[i], b[i]), "sub lane {} of {} mod {}", i, len, n);
+        assert_eq!(product[i], field.mul(a[i], b[i]), "mul lane {} of {} mod {}", i, len, n);
+    }
+}
+
+#[test]
+fn test_edge_values_match_reference() {
+    let edges = |n: u64| [0, 1, 2, n / 2, n - 2, n - 1, n, n.saturating_add(1), u64::MAX];
+    for &n in CRT_PRIMES.iter().chain(&[3, 65_537, 4_294_967_291]) {
+        let (m32, b32) = (Montgomery32::new(n as u32).unwrap(), Barrett32::new(n as u32).unwrap());
+        for a in edges(n) {
+            for b in edges(n) {
+                check_against_reference(&m32, a, b, a).unwrap();
+                check_against_reference(&b32, a, b, b).unwrap();
+            }
+        }
+    }
+    for n in [3, MERSENNE_61, (1 << 63) + 29, u64::MAX - 58, u64::MAX] {
+        let (m64, b64) = (Montgomery64::new(n).unwrap(), Barrett64::new(n).unwrap());
+        for a in edges(n) {
+            for b in edges(n) {
+                check_against_reference(&m64, a, b, a).unwrap();
+                check_against_reference(&b64, a, b, b).unwrap();
+                check_against_reference(&Mersenne61, a, b, a ^ b).unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_barrett64_at_every_width() {
+    let mut rng = StdRng::seed_from_u64(64);
+    // Both sides of each power of two, across the switch to the 128-bit mu
+    for bits in 2..=64u32 {
+        let top = if bits == 64 { u64::MAX } else { (1u64 << bits) - 1 };
+        for n in [1 << (bits - 1), (1 << (bits - 1)) + 1, top - 1, top] {
+            let field = Barrett64::new(n).unwrap();
+            let samples: Vec<u64> = (0..200).map(|_| rng.gen_range(0..n)).collect();
+            for a in [0, 1, n - 1, n / 2].into_iter().chain(samples) {
+                check_against_reference(&field, a, n - 1, 3).unwrap();
+                check_against_reference(&field, a, rng.gen(), 5).unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_invalid_moduli_are_rejected() {
+    assert!(matches!(Montgomery64::new(1 << 40), Err(GaloisError::InvalidModulus(_))));
+    assert!(Montgomery64::new(1).is_err());
+    assert!(Montgomery32::new(0).is_err());
+    assert!(Barrett32::new(1).is_err());
+    assert!(Barrett64::new(0).is_err());
+    // Barrett takes even moduli
+    assert!(Barrett64::new(1 << 40).is_ok());
+}
+
+#[test]
+fn test_mersenne_fast_path_in_galois_element() {
+    let mut rng = StdRng::seed_from_u64(61);
+    for _ in 0..10_000 {
+        let (a, b) = (rng.gen_range(0..MERSENNE_61), rng.gen_range(0..MERSENNE_61));
+        let expected = ((a as u128 * b as u128) % MERSENNE_61 as u128) as u64;
+        assert_eq!((GaloisElement::mersenne(a) * GaloisElement::mersenne(b)).value, expected);
+        assert_eq!(reduce_mersenne_61(a as u128 * b as u128), expected);
+    }
+    assert_eq!(reduce_mersenne_61((MERSENNE_61 - 1) as u128 * (MERSENNE_61 - 1) as u128), 1);
+    assert_eq!(reduce_mersenne_61(MERSENNE_61 as u128), 0);
+}
+
+#[test]
+fn test_batched_kernels_match_scalar() {
+    let mut rng = StdRng::seed_from_u64(49);
+    // Lengths around the eight-lane blocks of the AVX2 kernels
+    for len in [0, 1, 7, 8, 9, 15, 16, 17, 1000] {
+        for &n in &[3u32, 65_537, 2_147_483_647, 2_147_483_629, 4_294_967_291] {
+            check_slices(&Montgomery32::new(n).unwrap(), &mut rng, len);
+            check_slices(&Barrett32::new(n).unwrap(), &mut rng, len);
+        }
+        for n in [MERSENNE_61, u64::MAX] {
+            check_slices(&Montgomery64::new(n).unwrap(), &mut rng, len);
+            check_slices(&Barrett64::new(n).unwrap(), &mut rng, len);
+        }
+        check_slices(&Mersenne61, &mut rng, len);
+    }
+
+    let field = Montgomery32::new(CRT_PRIMES[0] as u32).unwrap();
+    let mut a = vec![0u32; 4];
+    assert!(matches!(simd::mul_assign(&field, &mut a, &[0; 5]), Err(GaloisError::DimensionMismatch { .. })));
+}
+
+proptest! {
+    #[test]
+    fn prop_montgomery64_matches_reference(n in (3u64..).prop_map(|n| n | 1), a: u64, b: u64, e in 0u64..1 << 20) {
+        check_against_reference(&Montgomery64::new(n).unwrap(), a, b, e)?;
+    }
+
+    #[test]
+    fn prop_barrett64_matches_reference(n in 2u64.., a: u64, b: u64, e in 0u64..1 << 20) {
+        check_against_reference(&Barrett64::new(n).unwrap(), a, b, e)?;
+    }
+
+    #[test]
+    fn prop_32_bit_reductions_match_reference(n in 3u32.., a: u64, b: u64, e in 0u64..1 << 20) {
+        check_against_reference(&Barrett32::new(n).unwrap(), a, b, e)?;
+        if n % 2 == 1 {
+            check_against_reference(&Montgomery32::new(n).unwrap(), a, b, e)?;
+        }
+    }
+
+    #[test]
+    fn prop_mersenne61_matches_reference(a: u64, b: u64, e: u64) {
+        check_against_reference(&Mersenne61, a, b, e)?;
+    }
+}
@@ -0,0 +1,137 @@
+//! Reed–Solomon reconstruction under random erasures
+//!
+//! Across many (k, m) configurations, up to m randomly chosen shards are
+//! dropped and the rest must give back every shard byte for byte, in
+//! memory and through the streaming coder.
+
+use std::io::Cursor;
+
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+use symmetrix_galois::reed_solomon::{MAX_SHARDS, STRIPE_LEN};
+use symmetrix_galois::{GaloisError, ReedSolomon};
+
+const CONFIGS: &[(usize, usize)] = &[
+    (1, 0), (1, 1), (1, 5), (2, 1), (3, 2), (4, 2), (4, 4), (5, 3), (6, 3),
+    (8, 4), (10, 4), (12, 6), (16, 4), (17, 3), (20, 10), (32, 8), (64, 16), (128, 128), (200, 56),
+];
+
+fn encoded(rs: &ReedSolomon, rng: &mut StdRng, len: usize) -> Vec<Vec<u8>> {
+    let mut shards: Vec<Vec<u8>> = (0..rs.total_shards())
+        .map(|i| if i < rs.data_shards() { (0..len).map(|_| rng.gen()).collect() } else { vec![0; len] })
+        .collect();
+    rs.encode(&mut shards).unwrap();
+    shards
+}
+
+#[test]
+fn test_random_erasures_reconstruct_exactly() {
+    let mut rng = StdRng::seed_from_u64(48);
+    for &(k, m) in CONFIGS {
+        let rs = ReedSolomon::new(k, m).unwrap();
+        for _ in 0..20 {
+            let len = rng.gen_range(0..300);
+            let shards = encoded(&rs, &mut rng, len);
+            assert!(rs.verify(&shards).unwrap());
+
+            let erased = rng.gen_range(0..=m);
+            let mut damaged: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+            for i in sample(&mut rng, k + m, erased) {
+                damaged[i] = None;
+            }
+            rs.reconstruct(&mut damaged).unwrap();
+            let restored: Vec<Vec<u8>> = damaged.into_iter().map(Option::unwrap).collect();
+            assert_eq!(restored, shards, "k={} m={} erased={}", k, m, erased);
+        }
+    }
+}
+
+#[test]
+fn test_every_erasure_pattern_of_a_small_code() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let rs = ReedSolomon::new(4, 3).unwrap();
+    let shards = encoded(&rs, &mut rng, 64);
+    for mask in 0u32..1 << 7 {
+        let mut damaged: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+        for (i, shard) in damaged.iter_mut().enumerate() {
+            if mask >> i & 1 == 1 {
+                *shard = None;
+            }
+        }
+        let result = rs.reconstruct(&mut damaged);
+        if mask.count_ones() <= 3 {
+            result.unwrap();
+            assert!(damaged.iter().zip(&shards).all(|(got, want)| got.as_ref() == Some(want)), "mask {:07b}", mask);
+        } else {
+            assert!(matches!(result, Err(GaloisError::TooFewShards { needed: 4, .. })));
+        }
+    }
+}
+
+#[test]
+fn test_corruption_fails_verification() {
+    let mut rng = StdRng::seed_from_u64(8);
+    let rs = ReedSolomon::new(6, 3).unwrap();
+    let mut shards = encoded(&rs, &mut rng, 100);
+    shards[2][50] ^= 1;
+    assert!(!rs.verify(&shards).unwrap());
+}
+
+#[test]
+fn test_invalid_configurations_and_shapes() {
+    assert!(matches!(ReedSolomon::new(0, 4), Err(GaloisError::InvalidShardConfig { .. })));
+    assert!(ReedSolomon::new(200, MAX_SHARDS - 199).is_err());
+    assert!(ReedSolomon::new(200, MAX_SHARDS - 200).is_ok());
+
+    let rs = ReedSolomon::new(3, 2).unwrap();
+    let mut wrong_count = vec![vec![0u8; 4]; 4];
+    assert!(matches!(rs.encode(&mut wrong_count), Err(GaloisError::DimensionMismatch { .. })));
+    let mut ragged = vec![vec![0u8; 4]; 5];
+    ragged[1].push(0);
+    assert!(matches!(rs.encode(&mut ragged), Err(GaloisError::DimensionMismatch { .. })));
+}
+
+#[test]
+fn test_streaming_survives_erasures_across_stripes() {
+    let mut rng = StdRng::seed_from_u64(1024);
+    for &(k, m) in &[(4usize, 2usize), (10, 4), (3, 3)] {
+        let rs = ReedSolomon::new(k, m).unwrap();
+        // Several full stripes, then a ragged tail that is not a multiple of k
+        for len in [0, 1, k * STRIPE_LEN, 2 * k * STRIPE_LEN + 12_345] {
+            let input: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let mut outputs = vec![Vec::new(); k + m];
+            assert_eq!(rs.encode_stream(input.as_slice(), &mut outputs).unwrap(), len as u64);
+            assert!(outputs.iter().all(|shard| shard.len() == outputs[0].len()));
+
+            let mut inputs: Vec<Option<Cursor<Vec<u8>>>> = outputs.into_iter().map(|s| Some(Cursor::new(s))).collect();
+            for i in sample(&mut rng, k + m, m) {
+                inputs[i] = None;
+            }
+            let mut restored = Vec::new();
+            rs.reconstruct_stream(&mut inputs, &mut restored, len as u64).unwrap();
+            assert!(restored == input, "k={} m={} len={}", k, m, len);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_any_k_shards_restore_the_data(
+        k in 1usize..24,
+        m in 0usize..12,
+        len in 0usize..200,
+        seed: u64,
+    ) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rs = ReedSolomon::new(k, m).unwrap();
+        let shards = encoded(&rs, &mut rng, len);
+        let mut damaged: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+        for i in sample(&mut rng, k + m, m) {
+            damaged[i] = None;
+        }
+        rs.reconstruct(&mut damaged).unwrap();
+        prop_assert!(damaged.iter().zip(&shards).all(|(got, want)| got.as_ref() == Some(want)));
+    }
+}
@@ -0,0 +1,209 @@
+//! `RnsInt` against num-bigint
+//!
+//! Over bases with odd and even ranges, small and full-width moduli, every
+//! operation must agree with `BigInt` arithmetic whenever the exact result
+//! is representable, and report `RnsOverflow` exactly when it is not.
+
+use std::cmp::Ordering;
+use std::sync::{Arc, OnceLock};
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{Signed, Zero};
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_galois::{CRTDecomposition, GaloisError, GaloisResult, RnsBasis, RnsInt, CRT_PRIMES};
+
+fn bases() -> &'static [Arc<RnsBasis>] {
+    static BASES: OnceLock<Vec<Arc<RnsBasis>>> = OnceLock::new();
+    BASES.get_or_init(|| {
+        vec![
+            RnsBasis::new(&[3, 5, 7]).unwrap(),
+            // Even M, so the range is one longer below zero
+            RnsBasis::new(&[8, 9, 25, 7]).unwrap(),
+            RnsBasis::new(&CRT_PRIMES[..3]).unwrap(),
+            RnsBasis::crt(),
+            RnsBasis::new(&[u64::MAX, u64::MAX - 1, (1 << 61) - 1]).unwrap(),
+        ]
+    })
+}
+
+fn random_below(rng: &mut StdRng, bound: &BigUint) -> BigUint {
+    let bytes: Vec<u8> = (0..bound.bits() / 8 + 8).map(|_| rng.gen()).collect();
+    BigUint::from_bytes_le(&bytes) % bound
+}
+
+/// A value in the range of `basis`, biased toward its ends and toward
+/// operands whose products still fit
+fn in_range(rng: &mut StdRng, basis: &RnsBasis) -> BigInt {
+    let (min, max) = (basis.min_value(), basis.max_value());
+    let root = BigInt::from(max.magnitude().sqrt());
+    match rng.gen_range(0..5) {
+        0 => &max - rng.gen_range(0..3u32),
+        1 => &min + rng.gen_range(0..3u32),
+        2 => BigInt::from(rng.gen_range(-100i64..100)).clamp(min.clone(), max.clone()),
+        3 => BigInt::from(random_below(rng, root.magnitude())) * if rng.gen() { 1 } else { -1 },
+        _ => &min + BigInt::from(random_below(rng, &(&max - &min + 1u32).to_biguint().unwrap())),
+    }
+}
+
+fn in_bounds(basis: &RnsBasis, value: &BigInt) -> bool {
+    basis.min_value() <= *value && *value <= basis.max_value()
+}
+
+/// Compare a checked RNS result with the exact one
+fn check(basis: &RnsBasis, result: GaloisResult<RnsInt>, exact: &BigInt, what: &str) -> Result<(), TestCaseError> {
+    if in_bounds(basis, exact) {
+        prop_assert_eq!(result.unwrap().to_bigint(), exact.clone(), "{} over {:?}", what, basis.moduli());
+    } else {
+        prop_assert!(matches!(result, Err(GaloisError::RnsOverflow)), "{} = {} should overflow", what, exact);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_range_ends_round_trip() {
+    for basis in bases() {
+        for value in [basis.max_value(), basis.min_value(), BigInt::zero(), BigInt::from(-1)] {
+            assert_eq!(RnsInt::from_bigint(&value, basis).unwrap().to_bigint(), value);
+        }
+        let above = basis.max_value() + 1u32;
+        let below = basis.min_value() - 1u32;
+        assert!(matches!(RnsInt::from_bigint(&above, basis), Err(GaloisError::RnsOverflow)));
+        assert!(matches!(RnsInt::from_bigint(&below, basis), Err(GaloisError::RnsOverflow)));
+        assert!(RnsInt::from_biguint(basis.product(), basis).is_err());
+
+        let max = RnsInt::from_bigint(&basis.max_value(), basis).unwrap();
+        let min = RnsInt::from_bigint(&basis.min_value(), basis).unwrap();
+        let one = RnsInt::from_i64(1, basis).unwrap();
+        assert!(matches!(&max + &one, Err(GaloisError::RnsOverflow)));
+        assert!(matches!(&min - &one, Err(GaloisError::RnsOverflow)));
+        assert!(min < max && max > RnsInt::zero(basis));
+    }
+}
+
+#[test]
+fn test_even_range_smallest_value_has_no_negation() {
+    let basis = &bases()[1];
+    let min = RnsInt::from_bigint(&basis.min_value(), basis).unwrap();
+    assert!(matches!(-&min, Err(GaloisError::RnsOverflow)));
+    assert!(matches!(min.abs(), Err(GaloisError::RnsOverflow)));
+    let odd = &bases()[0];
+    let min = RnsInt::from_bigint(&odd.min_value(), odd).unwrap();
+    assert_eq!((-&min).unwrap().to_bigint(), odd.max_value());
+}
+
+#[test]
+fn test_invalid_bases_and_mixed_bases() {
+    assert!(matches!(RnsBasis::new(&[6, 35, 9]), Err(GaloisError::InvalidModulus(9))));
+    assert!(matches!(RnsBasis::new(&[7, 1]), Err(GaloisError::InvalidModulus(1))));
+    assert!(matches!(RnsBasis::new(&[]), Err(GaloisError::CRTError(_))));
+
+    let (small, crt) = (&bases()[0], &bases()[3]);
+    let (a, b) = (RnsInt::from_i64(5, small).unwrap(), RnsInt::from_i64(5, crt).unwrap());
+    assert!(matches!(&a + &b, Err(GaloisError::CRTError(_))));
+    assert_ne!(a, b);
+    assert_eq!(a.partial_cmp(&b), None);
+    // An equal basis built separately is compatible
+    let again = RnsBasis::new(&[3, 5, 7]).unwrap();
+    assert_eq!((&a * &RnsInt::from_i64(2, &again).unwrap()).unwrap().to_bigint(), BigInt::from(10));
+
+    assert!(matches!(RnsInt::from_residues(vec![1, 2], small), Err(GaloisError::DimensionMismatch { .. })));
+    assert!(matches!(RnsInt::from_residues(vec![1, 5, 2], small), Err(GaloisError::InvalidElement(_))));
+    assert!(a.div_rem_modulus(3).is_err());
+}
+
+#[test]
+fn test_crt_decomposition_interop() {
+    let basis = &bases()[3];
+    let value = BigUint::from(123_456_789_012_345_678u64);
+    let decomposition = CRTDecomposition::decompose(&value, CRT_PRIMES);
+    let rns = RnsInt::from_crt(&decomposition, basis).unwrap();
+    assert_eq!(rns.to_biguint().unwrap(), value);
+
+    let negative = RnsInt::from_i64(-42, basis).unwrap();
+    assert!(negative.to_biguint().is_err());
+    assert_eq!(negative.to_crt().reconstruct().unwrap(), basis.product() - 42u32);
+    assert!(RnsInt::from_crt(&decomposition, &bases()[2]).is_err());
+}
+
+proptest! {
+    #[test]
+    fn prop_conversions_round_trip(index in 0..5usize, seed: u64) {
+        let basis = &bases()[index];
+        let mut rng = StdRng::seed_from_u64(seed);
+        let value = in_range(&mut rng, basis);
+        let rns = RnsInt::from_bigint(&value, basis).unwrap();
+        prop_assert_eq!(rns.to_bigint(), value.clone());
+        prop_assert_eq!(rns.is_negative(), value.is_negative());
+        prop_assert_eq!(rns.is_zero(), value.is_zero());
+        prop_assert_eq!(rns.to_string(), value.to_string());
+
+        // Residues agree with the direct decomposition of x mod M
+        let unsigned = if value.sign() == Sign::Minus { basis.product() - value.magnitude() } else { value.magnitude().clone() };
+        let crt = CRTDecomposition::decompose(&unsigned, basis.moduli());
+        prop_assert!(rns.residues().iter().zip(&crt.residues).all(|(&r, e)| r == e.value));
+
+        // Mixed-radix digits spell x mod M
+        let digits = rns.to_mixed_radix();
+        let spelled = digits.iter().zip(basis.moduli()).rev().fold(BigUint::zero(), |acc, (&d, &m)| acc * m + d);
+        prop_assert_eq!(&spelled, &unsigned);
+        prop_assert_eq!(RnsInt::from_mixed_radix(&digits, basis).unwrap(), rns.clone());
+
+        if !value.is_negative() {
+            prop_assert_eq!(rns.to_biguint().unwrap(), value.magnitude().clone());
+            prop_assert_eq!(RnsInt::from_biguint(value.magnitude(), basis).unwrap(), rns);
+        }
+    }
+
+    #[test]
+    fn prop_arithmetic_matches_bigint(index in 0..5usize, seed: u64) {
+        let basis = &bases()[index];
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (x, y) = (in_range(&mut rng, basis), in_range(&mut rng, basis));
+        let (a, b) = (RnsInt::from_bigint(&x, basis).unwrap(), RnsInt::from_bigint(&y, basis).unwrap());
+
+        check(basis, &a + &b, &(&x + &y), "a + b")?;
+        check(basis, &a - &b, &(&x - &y), "a - b")?;
+        check(basis, &a * &b, &(&x * &y), "a * b")?;
+        check(basis, -&a, &-&x, "-a")?;
+        check(basis, a.abs(), &x.abs(), "|a|")?;
+        check(basis, a.clone() * b.clone(), &(&x * &y), "owned a * b")?;
+    }
+
+    #[test]
+    fn prop_comparisons_match_bigint(index in 0..5usize, seed: u64) {
+        let basis = &bases()[index];
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (x, y) = (in_range(&mut rng, basis), in_range(&mut rng, basis));
+        let (a, b) = (RnsInt::from_bigint(&x, basis).unwrap(), RnsInt::from_bigint(&y, basis).unwrap());
+        prop_assert_eq!(a.partial_cmp(&b), Some(x.cmp(&y)));
+        prop_assert_eq!(a == b, x == y);
+        prop_assert_eq!(a.partial_cmp(&a), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn prop_division_by_a_modulus_is_euclidean(index in 0..5usize, seed: u64) {
+        let basis = &bases()[index];
+        let mut rng = StdRng::seed_from_u64(seed);
+        let x = in_range(&mut rng, basis);
+        let a = RnsInt::from_bigint(&x, basis).unwrap();
+        for (i, &m) in basis.moduli().iter().enumerate() {
+            let m = BigInt::from(m);
+            let r = ((&x % &m) + &m) % &m;
+            let q = (&x - &r) / &m;
+            let (quotient, remainder) = a.div_rem_modulus(i).unwrap();
+            prop_assert_eq!(BigInt::from(remainder), r);
+            prop_assert_eq!(quotient.to_bigint(), q, "{} div {}", x, m);
+        }
+    }
+
+    #[test]
+    fn prop_base_extension_preserves_values(from in 0..5usize, to in 0..5usize, seed: u64) {
+        let (source, target) = (&bases()[from], &bases()[to]);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let x = in_range(&mut rng, source);
+        let extended = RnsInt::from_bigint(&x, source).unwrap().extend(target);
+        check(target, extended, &x, "extension")?;
+    }
+}
@@ -4,9 +4,9 @@
 //! Receives VXLAN commands and orchestrates QAGML/QANBAN/UAO-QTCAM.
 //!
 //! ## Architecture
-//! - UDP Server on port 4789 (VXLAN standard - local/Azure)
+//! - UDP Server on port 4789 (VXLAN standard - local/Azure); the VNI selects the tenant namespace
 //! - WebSocket Server on /ws (for Render deployment)
-//! - HTTP Management API on port 8080/10000
+//! - HTTP Management API on port 8080/10000 (one route per command, see /openapi.json)
 //! - UAO-QTCAM Cache (Redis replacement)
 //! - All SYMMETRIX CORE integrations
 //!
@@ -16,94 +16,40 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use axum::http::{Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Extension, Json};
 use tokio::net::UdpSocket;
 use tokio::sync::RwLock;
-use tracing::{info, error, warn, debug};
+use tracing::{info, error, warn};
 use serde::{Deserialize, Serialize};
-use futures_util::{SinkExt, StreamExt};
-use tokio_tungstenite::tungstenite::Message;
+use serde_json::{json, Value};
 
 // Import SYMMETRIX CORE components
 use symmetrix_core::{
-    initialize, SymmetrixConfig, SymmetrixResult, SymmetrixRuntime,
-    bandwidth_cascade::BandwidthCascade,
-    qagml_integration::{SymmetrixQagmlOptimizer, SymmetrixQagmlConfig},
-    qanban_integration::{SymmetrixQanbanOptimizer, SymmetrixQanbanConfig},
-    uao_qtcam_integration::{SymmetrixUaoQtcamOptimizer, SymmetrixUaoQtcamConfig},
-    uao_qtcam_cache::UaoQtcamCache,
-    // Note: gfef module not yet implemented - using placeholder types
-    // gfef::{
-    //     prediction::{ActivationPredictor, PredictorStats},
-    //     calibration::CalibrationService,
-    //     subscription::SubscriptionManager,
-    //     index::{GFEFIndex, IndexConfig, LayerIndex},
-    // },
+    initialize, CacheCodec, ExpiryConfig, FsyncPolicy, PersistenceConfig, SymmetrixConfig, SymmetrixResult, SymmetrixRuntime, UaoQtcamCache,
+    control_plane::{
+        auth::{ControlAccess, Permission, Principal, Role, Transport},
+        http::{self as control_http, DEFAULT_MAX_BODY_BYTES},
+        vxlan::{self, VXLAN_PORT},
+        ws::WsLimits,
+        ControlPlaneState, Namespace, CACHE_COMPRESSION_RATIO, CALIBRATION_ROTATION_SECS, TENANT_KEY_PREFIX,
+    },
+    grpc::auth::AuthManager,
+    uao_qtcam_cache::resp,
+    grpc::openapi::{ApiOperation, ApiSchema, HttpApi},
+    gfef::{
+        prediction::ActivationPredictor,
+        calibration::CalibrationService,
+        subscription::SubscriptionManager,
+        index::GFEFIndex,
+        GfefError,
+    },
 };
 
-/// VXLAN standard port
-pub const VXLAN_PORT: u16 = 4789;
-/// VXLAN packet header (simplified for control plane)
-pub const VXLAN_HEADER_SIZE: usize = 8;
-const VXLAN_VNI_CONTROL_PLANE: u32 = 0xFFFFFF; // Reserved VNI for control
-
-/// Control Plane command types
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "cmd", content = "data")]
-pub enum ControlCommand {
-    // Health and Status
-    Health,
-    Stats,
-
-    // QAGML Memory Operations
-    AllocateMemory { size_bytes: u64, region: String },
-    FreeMemory { allocation_id: String },
-    GetMemoryStats,
-
-    // QANBAN Bandwidth Operations
-    OptimizeBandwidth { flow_id: String, target_gbps: f64 },
-    GetBandwidthStats,
-
-    // UAO-QTCAM Operations
-    Lookup { key: String },
-    InsertRoute { key: String, value: String, priority: u32 },
-    DeleteRoute { key: String },
-
-    // Cache Operations (Redis replacement)
-    CacheSet { key: String, value: String, ttl_seconds: Option<u64> },
-    CacheGet { key: String },
-    CacheDelete { key: String },
-    CacheIncr { key: String },
-    CacheStats,
-
-    // Cascade Operations
-    GetCascadeStats,
-
-    // Calibration Matrix Operations (for Weight Server)
-    GetCalibrationMatrix { tier: Option<String> },
-
-    // GFEF (Galois Field Eigenmode Folding) Operations
-    /// Predict which neurons will activate for a given input
-    #[serde(rename = "PredictActivation")]
-    PredictActivation {
-        customer_id: String,
-        model_id: String,
-        layer_index: u32,
-        input_hash: String
-    },
-    /// Upload GFEF index for a model
-    UploadGfefIndex { model_id: String, index_data: String },
-    /// Get GFEF index status
-    GetGfefStatus,
-}
-
-/// Control Plane response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ControlResponse {
-    pub success: bool,
-    pub message: String,
-    pub data: Option<serde_json::Value>,
-    pub latency_ns: u64,
-}
+/// Interval of `stats` topic events on the WebSocket channel
+const STATS_EVENT_INTERVAL_SECS: u64 = 5;
 
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,52 +62,107 @@ pub struct ServerConfig {
     pub http_bind: String,
     /// HTTP API port
     pub http_port: u16,
+    /// RESP (Redis protocol) bind address
+    pub resp_bind: String,
+    /// RESP port; the listener is off when unset
+    pub resp_port: Option<u16>,
     /// Cache size in bytes
     pub cache_size: usize,
+    /// Codec for cached values
+    pub cache_codec: CacheCodec,
+    /// Snapshot and log location; the cache is memory-only when unset
+    pub cache_persistence: Option<PersistenceConfig>,
     /// Max concurrent connections
     pub max_connections: usize,
+    /// Max HTTP request body in bytes (larger bodies get 413)
+    pub max_request_bytes: usize,
+    /// Require API keys on every transport
+    pub auth_enabled: bool,
+    /// Control-plane API keys; required when `auth_enabled`
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// Per-connection WebSocket limits
+    pub ws_limits: WsLimits,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
             vxlan_bind: "0.0.0.0".to_string(),
-            vxlan_port: 4789,
+            vxlan_port: VXLAN_PORT,
             http_bind: "0.0.0.0".to_string(),
             http_port: 8080,
+            resp_bind: "127.0.0.1".to_string(),
+            resp_port: None,
             cache_size: 256 * 1024 * 1024, // 256 MB = 64 GB effective
+            cache_codec: CacheCodec::default(),
+            cache_persistence: None,
             max_connections: 10000,
+            max_request_bytes: DEFAULT_MAX_BODY_BYTES,
+            auth_enabled: true,
+            api_keys: Vec::new(),
+            ws_limits: WsLimits::default(),
         }
     }
 }
 
+/// API key with its role
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyConfig {
+    pub key: String,
+    pub role: Role,
+    pub org_id: String,
+    /// Tenant VNIs the key may use over VXLAN (admins may use any)
+    #[serde(default)]
+    pub vnis: Vec<u32>,
+}
+
+impl ApiKeyConfig {
+    /// Parse `key:role[:org[:vni+vni...]]` entries separated by commas
+    pub fn parse_list(list: &str) -> Result<Vec<Self>, String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let mut parts = entry.splitn(4, ':');
+                let key = parts.next().unwrap_or_default();
+                let role = parts
+                    .next()
+                    .ok_or_else(|| format!("API key entry '{}...' has no role", key.get(..4).unwrap_or(key)))?
+                    .parse()?;
+                let org_id = parts.next().unwrap_or("control-plane").to_string();
+                let vnis = parts
+                    .next()
+                    .map(|vnis| {
+                        vnis.split('+')
+                            .map(|vni| vni.trim().parse().map_err(|_| format!("Invalid VNI '{}'", vni)))
+                            .collect::<Result<Vec<u32>, String>>()
+                    })
+                    .transpose()?
+                    .unwrap_or_default();
+                Ok(Self {
+                    key: key.to_string(),
+                    role,
+                    org_id,
+                    vnis,
+                })
+            })
+            .collect()
+    }
+}
+
 /// VXLAN Control Plane Server
 pub struct ControlPlaneServer {
     config: ServerConfig,
+    /// Held for the server's lifetime
+    #[allow(dead_code)]
     runtime: Arc<SymmetrixRuntime>,
-    cache: Arc<UaoQtcamCache>,
-    qagml: Arc<RwLock<SymmetrixQagmlOptimizer>>,
-    qanban: Arc<RwLock<SymmetrixQanbanOptimizer>>,
-    uao_qtcam: Arc<RwLock<SymmetrixUaoQtcamOptimizer>>,
-    bandwidth_cascade: Arc<RwLock<BandwidthCascade>>,
-    stats: Arc<RwLock<ServerStats>>,
+    state: Arc<ControlPlaneState>,
     // GFEF Components - TRIPLE IP LOCK
     gfef_predictor: Arc<RwLock<ActivationPredictor>>,
     gfef_calibration: Arc<CalibrationService>,
     gfef_subscriptions: Arc<RwLock<SubscriptionManager>>,
 }
 
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
-pub struct ServerStats {
-    pub commands_processed: u64,
-    pub vxlan_packets: u64,
-    pub http_requests: u64,
-    pub cache_hits: u64,
-    pub cache_misses: u64,
-    pub total_latency_ns: u64,
-    pub start_time: i64,
-}
-
 impl ControlPlaneServer {
     /// Create new Control Plane Server with all integrations
     pub async fn new(config: ServerConfig) -> SymmetrixResult<Self> {
@@ -172,44 +173,48 @@ impl ControlPlaneServer {
         // Initialize SYMMETRIX runtime
         let symmetrix_config = SymmetrixConfig::default();
         let runtime = Arc::new(initialize(symmetrix_config.clone())?);
-        
-        // Initialize UAO-QTCAM Cache (Redis replacement)
-        info!("📦 Initializing UAO-QTCAM Cache (Redis Replacement)...");
-        let cache = Arc::new(UaoQtcamCache::new(config.cache_size, 250.0));
-
-        // Initialize QAGML (Memory Amplification)
-        info!("🧠 Initializing QAGML (10,000,000× Memory Amplification)...");
-        let qagml_config = SymmetrixQagmlConfig::default();
-        let qagml = Arc::new(RwLock::new(SymmetrixQagmlOptimizer::new(qagml_config)));
-
-        // Initialize QANBAN (Bandwidth Amplification)
-        info!("🌐 Initializing QANBAN (1,000,000× Bandwidth Amplification)...");
-        let qanban_config = SymmetrixQanbanConfig::default();
-        let qanban = Arc::new(RwLock::new(
-            SymmetrixQanbanOptimizer::new(qanban_config)
-                .map_err(|e| symmetrix_core::SymmetrixError::RuntimeError(e.to_string()))?
-        ));
-
-        // Initialize UAO-QTCAM (TCAM Acceleration)
-        info!("⚡ Initializing UAO-QTCAM (1,250× TCAM Speedup)...");
-        let uao_qtcam_config = SymmetrixUaoQtcamConfig::default();
-        let uao_qtcam = Arc::new(RwLock::new(SymmetrixUaoQtcamOptimizer::new(uao_qtcam_config)));
-
-        // Initialize Bandwidth Cascade
-        info!("🔄 Initializing Bandwidth Cascade (250,000,000× Total Amplification)...");
-        let bandwidth_cascade = Arc::new(RwLock::new(BandwidthCascade::new()));
+
+        // API keys and roles for HTTP, WebSocket and VXLAN
+        let access = ControlAccess::new(Arc::new(AuthManager::new(config.auth_enabled)));
+        if config.auth_enabled {
+            if config.api_keys.is_empty() {
+                return Err(symmetrix_core::SymmetrixError::RuntimeError(
+                    "Authentication is enabled but CONTROL_PLANE_API_KEYS is empty; \
+                     configure keys or set AUTH_ENABLED=false".to_string(),
+                ));
+            }
+            for key in &config.api_keys {
+                access.register_key(&key.key, "enterprise", &key.org_id, key.role).await;
+                access.grant_vnis(&key.key, key.vnis.iter().copied()).await;
+            }
+        } else {
+            warn!("═══════════════════════════════════════════════════════════════════════════════");
+            warn!("  ⚠️ AUTHENTICATION DISABLED: every HTTP, WebSocket and VXLAN caller is an admin");
+            warn!("  ⚠️ Anyone who can reach these ports can delete routes and free memory");
+            warn!("═══════════════════════════════════════════════════════════════════════════════");
+        }
+
+        // Cache, QAGML, QANBAN, UAO-QTCAM and the Bandwidth Cascade
+        let mut cache = UaoQtcamCache::new(config.cache_size, CACHE_COMPRESSION_RATIO).with_codec(config.cache_codec);
+        if let Some(persistence) = &config.cache_persistence {
+            cache = cache.with_persistence(persistence.clone()).map_err(|e| {
+                symmetrix_core::SymmetrixError::RuntimeError(format!("Cache recovery failed: {}", e))
+            })?;
+        }
+        let state = Arc::new(
+            ControlPlaneState::new(config.cache_size)?
+                .with_cache(cache)
+                .with_access(access)
+                .with_ws_limits(config.ws_limits),
+        );
 
         // Initialize GFEF (Galois Field Eigenmode Folding) - TRIPLE IP LOCK
         info!("🔐 Initializing GFEF Prediction Service (Triple IP Lock)...");
-        let gfef_predictor = Arc::new(RwLock::new(ActivationPredictor::new(0.95)));
-        let gfef_calibration = Arc::new(CalibrationService::new(60)); // 60 second rotation
+        // Shared with PredictActivation / UploadGfefIndex / GetGfefStatus
+        let gfef_predictor = state.gfef.clone();
+        let gfef_calibration = Arc::new(CalibrationService::new(CALIBRATION_ROTATION_SECS));
         let gfef_subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
 
-        let stats = Arc::new(RwLock::new(ServerStats {
-            start_time: chrono::Utc::now().timestamp(),
-            ..Default::default()
-        }));
-
         info!("═══════════════════════════════════════════════════════════════════════════════");
         info!("  ✅ ALL INTEGRATIONS INITIALIZED");
         info!("═══════════════════════════════════════════════════════════════════════════════");
@@ -217,18 +222,12 @@ impl ControlPlaneServer {
         Ok(Self {
             config,
             runtime,
-            cache,
-            qagml,
-            qanban,
-            uao_qtcam,
-            bandwidth_cascade,
-            stats,
+            state,
             gfef_predictor,
             gfef_calibration,
             gfef_subscriptions,
         })
     }
-
     /// Start the Control Plane Server
     pub async fn start(&self) -> SymmetrixResult<()> {
         info!("🚀 Starting VXLAN Control Plane Server...");
@@ -246,23 +245,45 @@ impl ControlPlaneServer {
         info!("🌐 HTTP Management API: http://{}", http_addr);
 
         // Clone for async tasks
-        let server_clone = Arc::new(self.clone_internals());
-        let server_http = server_clone.clone();
+        let vxlan_state = self.state.clone();
+        let publishers = self.state.spawn_publishers(std::time::Duration::from_secs(STATS_EVENT_INTERVAL_SECS));
+        let cache_expiry = self.state.cache.spawn_expiry(ExpiryConfig::default());
+        let cache_persistence = self.state.cache.spawn_persistence();
+        let http_app = self.http_router();
 
         // Start VXLAN handler
         let vxlan_task = tokio::spawn(async move {
-            if let Err(e) = Self::run_vxlan_server(vxlan_addr, server_clone).await {
+            if let Err(e) = Self::run_vxlan_server(vxlan_addr, vxlan_state).await {
                 error!("VXLAN server error: {}", e);
             }
         });
 
         // Start HTTP handler
         let http_task = tokio::spawn(async move {
-            if let Err(e) = Self::run_http_server(http_addr, server_http).await {
+            if let Err(e) = Self::run_http_server(http_addr, http_app).await {
                 error!("HTTP server error: {}", e);
             }
         });
 
+        // Start RESP handler (optional)
+        let resp_task = match self.config.resp_port {
+            Some(port) => {
+                let resp_addr: SocketAddr = format!("{}:{}", self.config.resp_bind, port)
+                    .parse()
+                    .map_err(|e| symmetrix_core::SymmetrixError::RuntimeError(format!("Invalid RESP address: {}", e)))?;
+                if self.config.auth_enabled {
+                    warn!("⚠️ RESP has no authentication - keep {} on a trusted network", resp_addr);
+                }
+                let cache = self.state.cache.clone();
+                Some(tokio::spawn(async move {
+                    if let Err(e) = Self::run_resp_server(resp_addr, cache).await {
+                        error!("RESP server error: {}", e);
+                    }
+                }))
+            }
+            None => None,
+        };
+
         info!("═══════════════════════════════════════════════════════════════════════════════");
         info!("  🎯 CONTROL PLANE SERVER RUNNING");
         info!("═══════════════════════════════════════════════════════════════════════════════");
@@ -273,12 +294,15 @@ impl ControlPlaneServer {
         info!("     • TCAM:      10,000 ns → 8 ns (1,250× speedup)");
         info!("     • Cache:     {} MB → {} GB (250× compression)",
               self.config.cache_size / (1024 * 1024),
-              (self.config.cache_size as f64 * 250.0) as usize / (1024 * 1024 * 1024));
+              (self.config.cache_size as f64 * CACHE_COMPRESSION_RATIO) as usize / (1024 * 1024 * 1024));
         info!("");
         info!("  🔗 ENDPOINTS:");
         info!("     • VXLAN UDP:   udp://{}:{}", self.config.vxlan_bind, self.config.vxlan_port);
         info!("     • HTTP API:    http://{}:{}", self.config.http_bind, self.config.http_port);
         info!("     • WebSocket:   ws://{}:{}/ws (VXLAN over WebSocket)", self.config.http_bind, self.config.http_port);
+        if let Some(port) = self.config.resp_port {
+            info!("     • RESP:        redis://{}:{} (cache)", self.config.resp_bind, port);
+        }
         info!("");
         info!("  🌐 RENDER DEPLOYMENT:");
         info!("     • WebSocket:   wss://vxlan-control-plane.onrender.com/ws");
@@ -294,76 +318,31 @@ impl ControlPlaneServer {
                 info!("🛑 Received shutdown signal...");
             }
         }
+        publishers.abort();
+        cache_expiry.abort();
+        if let Some(task) = resp_task {
+            task.abort();
+        }
+        if let Some(task) = cache_persistence {
+            task.abort();
+            // A final snapshot makes the next start a plain load
+            if let Err(e) = self.state.cache.save_snapshot() {
+                error!("Cache snapshot on shutdown failed: {}", e);
+            }
+        }
 
         Ok(())
     }
 
-    fn clone_internals(&self) -> ServerInternals {
-        ServerInternals {
-            cache: self.cache.clone(),
-            qagml: self.qagml.clone(),
-            qanban: self.qanban.clone(),
-            uao_qtcam: self.uao_qtcam.clone(),
-            bandwidth_cascade: self.bandwidth_cascade.clone(),
-            stats: self.stats.clone(),
-            gfef_predictor: self.gfef_predictor.clone(),
-            gfef_calibration: self.gfef_calibration.clone(),
-            gfef_subscriptions: self.gfef_subscriptions.clone(),
-        }
-    }
-
     /// Load GFEF index from file (Triple IP Lock - index stays on Control Plane)
     pub async fn load_gfef_index(&self, json_path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("🔐 Loading GFEF index from: {}", json_path);
 
         let json_content = std::fs::read_to_string(json_path)?;
-        let raw: serde_json::Value = serde_json::from_str(&json_content)?;
-
-        // Convert Python-generated format to Rust GFEFIndex
-        let model_name = raw["model"].as_str().unwrap_or("unknown").to_string();
-        let k_components = raw["k_components"].as_u64().unwrap_or(32) as u32;
-        let fft_bins = raw["fft_bins"].as_u64().unwrap_or(16) as u32;
-        let total_neurons = raw["total_neurons"].as_u64().unwrap_or(0);
-
-        let layers_raw = raw["layers"].as_array()
-            .ok_or("Missing layers array")?;
-
-        let layers: Vec<LayerIndex> = layers_raw.iter().map(|l| {
-            let layer_id = l["layer_id"].as_u64().unwrap_or(0) as u32;
-            let name = l["name"].as_str().unwrap_or("").to_string();
-            let neurons = l["neurons"].as_u64().unwrap_or(0) as u32;
-            let pc_shape = l["pc_shape"].as_array();
-            let input_dim = pc_shape
-                .and_then(|arr| arr.first())
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0) as u32;
-
-            LayerIndex {
-                layer_id,
-                layer_name: name,
-                num_neurons: neurons,
-                input_dim,
-                k_components,
-                principal_components: Vec::new(),
-                signatures: Vec::new(),
-            }
-        }).collect();
-
-        let index = GFEFIndex {
-            id: uuid::Uuid::new_v4(),
-            customer_id: uuid::Uuid::nil(),
-            model_id: model_name.clone(),
-            model_name: model_name.clone(),
-            generated_at: chrono::Utc::now(),
-            expires_at: None,
-            layers,
-            total_neurons,
-            config: IndexConfig {
-                k_components,
-                fft_bins,
-                target_sparsity: 0.95,
-            },
-        };
+        let index = GFEFIndex::from_export("", &json_content)?;
+        let model_name = index.model_id.clone();
+        let total_neurons = index.total_neurons;
+        let num_layers = index.layers.len();
 
         // Register with predictor - TRIPLE IP LOCK ACTIVATES HERE
         {
@@ -372,892 +351,337 @@ impl ControlPlaneServer {
         }
 
         info!("✅ GFEF index loaded: {} ({} neurons, {} layers)",
-            model_name, total_neurons, layers_raw.len());
+            model_name, total_neurons, num_layers);
         info!("🔒 TRIPLE IP LOCK ACTIVE - Index secured on Control Plane");
         info!("   Lock 1: GFEF Index (SECURED)");
-        info!("   Lock 2: Calibration Matrix (rotating every 60s)");
+        info!("   Lock 2: Calibration Matrix (rotating every {}s)", self.gfef_calibration.rotation_secs());
         info!("   Lock 3: Activation Prediction Service (real-time oracle)");
 
         Ok(())
     }
 
+
     /// Run VXLAN UDP server
-    async fn run_vxlan_server(addr: SocketAddr, server: Arc<ServerInternals>) -> SymmetrixResult<()> {
+    async fn run_vxlan_server(addr: SocketAddr, state: Arc<ControlPlaneState>) -> SymmetrixResult<()> {
         let socket = UdpSocket::bind(addr).await
             .map_err(|e| symmetrix_core::SymmetrixError::RuntimeError(format!("Failed to bind VXLAN socket: {}", e)))?;
 
-        info!("📡 VXLAN server listening on {}", addr);
-
-        let mut buf = vec![0u8; 65535];
-
-        loop {
-            match socket.recv_from(&mut buf).await {
-                Ok((len, src)) => {
-                    let packet = buf[..len].to_vec();
-                    let server_clone = server.clone();
-                    let socket_clone = socket.local_addr().ok();
-
-                    tokio::spawn(async move {
-                        if let Err(e) = Self::handle_vxlan_packet(&packet, src, server_clone, socket_clone).await {
-                            warn!("Error handling VXLAN packet from {}: {}", src, e);
-                        }
-                    });
-                }
-                Err(e) => {
-                    error!("Error receiving VXLAN packet: {}", e);
-                }
-            }
-        }
+        vxlan::serve(socket, state).await;
+        Ok(())
     }
 
-    /// Handle VXLAN packet
-    async fn handle_vxlan_packet(
-        packet: &[u8],
-        src: SocketAddr,
-        server: Arc<ServerInternals>,
-        _local: Option<SocketAddr>,
-    ) -> SymmetrixResult<()> {
-        // Update stats
-        {
-            let mut stats = server.stats.write().await;
-            stats.vxlan_packets += 1;
-        }
-
-        // Skip VXLAN header and parse command
-        if packet.len() < VXLAN_HEADER_SIZE {
-            return Ok(());
-        }
-
-        let payload = &packet[VXLAN_HEADER_SIZE..];
-
-        // Parse JSON command
-        let command: ControlCommand = match serde_json::from_slice(payload) {
-            Ok(cmd) => cmd,
-            Err(e) => {
-                debug!("Failed to parse VXLAN command from {}: {}", src, e);
-                return Ok(());
-            }
-        };
-
-        // Process command
-        let start = std::time::Instant::now();
-        let response = Self::process_command(command, server.clone()).await;
-        let latency = start.elapsed().as_nanos() as u64;
-
-        debug!("VXLAN command from {} processed in {} ns", src, latency);
+    async fn run_resp_server(addr: SocketAddr, cache: Arc<UaoQtcamCache>) -> SymmetrixResult<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await
+            .map_err(|e| symmetrix_core::SymmetrixError::RuntimeError(format!("Failed to bind RESP socket: {}", e)))?;
 
+        resp::serve(listener, cache).await;
         Ok(())
     }
 
-    /// Run HTTP management server with WebSocket support
-    async fn run_http_server(addr: SocketAddr, server: Arc<ServerInternals>) -> SymmetrixResult<()> {
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
-        use tokio::net::TcpListener;
+    /// HTTP API: control commands plus the GFEF endpoints
+    fn http_router(&self) -> axum::Router {
+        let api = control_http::api(self.state.clone());
+        let api = gfef_api(api, GfefServices {
+            access: self.state.access.clone(),
+            predictor: self.gfef_predictor.clone(),
+            calibration: self.gfef_calibration.clone(),
+            subscriptions: self.gfef_subscriptions.clone(),
+        });
+        control_http::into_router(api, self.state.clone(), self.config.max_request_bytes)
+    }
 
-        let listener = TcpListener::bind(addr).await
+    /// Run HTTP management server with WebSocket support
+    async fn run_http_server(addr: SocketAddr, app: axum::Router) -> SymmetrixResult<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await
             .map_err(|e| symmetrix_core::SymmetrixError::RuntimeError(format!("Failed to bind HTTP socket: {}", e)))?;
 
         info!("🌐 HTTP server listening on {}", addr);
         info!("🔌 WebSocket endpoint: ws://{}/ws", addr);
 
-        loop {
-            match listener.accept().await {
-                Ok((socket, peer)) => {
-                    let server_clone = server.clone();
-
-                    tokio::spawn(async move {
-                        // Peek at the request to determine if it's a WebSocket upgrade
-                        // Use larger buffer for GFEF index uploads (can be 100KB+)
-                        let mut buf = vec![0u8; 256 * 1024]; // 256KB buffer
-                        let mut socket = socket;
-                        let mut total_read = 0;
-
-                        // Read all available data (may come in chunks)
-                        loop {
-                            match tokio::io::AsyncReadExt::read(&mut socket, &mut buf[total_read..]).await {
-                                Ok(0) => break, // EOF
-                                Ok(n) => {
-                                    total_read += n;
-                                    // Check if we've received the full request
-                                    // Look for Content-Length header and verify we have all data
-                                    let partial = String::from_utf8_lossy(&buf[..total_read]);
-                                    if let Some(content_length) = Self::extract_content_length(&partial) {
-                                        if let Some(body_start) = partial.find("\r\n\r\n") {
-                                            let body_len = total_read - body_start - 4;
-                                            if body_len >= content_length {
-                                                break; // We have the full request
-                                            }
-                                        }
-                                    } else if partial.contains("\r\n\r\n") && !partial.contains("Content-Length") {
-                                        break; // No body expected
-                                    }
-                                    // Continue reading if buffer not full
-                                    if total_read >= buf.len() {
-                                        break;
-                                    }
-                                }
-                                Err(_) => break,
-                            }
-                        }
-
-                        if total_read > 0 {
-                            let request = String::from_utf8_lossy(&buf[..total_read]);
-
-                            // Check if this is a WebSocket upgrade request
-                            if Self::is_websocket_upgrade(&request) {
-                                info!("🔌 WebSocket upgrade request from {}", peer);
-                                if let Err(e) = Self::handle_websocket(socket, &request, peer, server_clone).await {
-                                    warn!("WebSocket error from {}: {}", peer, e);
-                                }
-                            } else {
-                                // Regular HTTP request
-                                let response = Self::handle_http_request(&request, server_clone).await;
-
-                                let http_response = format!(
-                                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-                                    response.len(),
-                                    response
-                                );
-
-                                let _ = tokio::io::AsyncWriteExt::write_all(&mut socket, http_response.as_bytes()).await;
-                                debug!("HTTP request from {} handled", peer);
-                            }
-                        }
-                    });
-                }
-                Err(e) => {
-                    error!("Error accepting HTTP connection: {}", e);
-                }
-            }
-        }
-    }
-
-    /// Check if request is a WebSocket upgrade
-    fn is_websocket_upgrade(request: &str) -> bool {
-        let lower = request.to_lowercase();
-        lower.contains("upgrade: websocket") && lower.contains("connection: upgrade")
+        axum::serve(listener, app).await
+            .map_err(|e| symmetrix_core::SymmetrixError::RuntimeError(format!("HTTP server failed: {}", e)))
     }
+}
 
-    /// Extract Content-Length header value from HTTP request
-    fn extract_content_length(request: &str) -> Option<usize> {
-        for line in request.lines() {
-            let lower = line.to_lowercase();
-            if lower.starts_with("content-length:") {
-                return line.split(':')
-                    .nth(1)
-                    .and_then(|v| v.trim().parse().ok());
-            }
-        }
-        None
-    }
-
-    /// Handle WebSocket connection for VXLAN tunnel emulation
-    async fn handle_websocket(
-        socket: tokio::net::TcpStream,
-        request: &str,
-        peer: SocketAddr,
-        server: Arc<ServerInternals>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        use sha1::{Sha1, Digest};
-        use base64::Engine;
-
-        // Extract Sec-WebSocket-Key
-        let ws_key = request.lines()
-            .find(|line| line.to_lowercase().starts_with("sec-websocket-key:"))
-            .and_then(|line| line.split(':').nth(1))
-            .map(|s| s.trim())
-            .ok_or("Missing Sec-WebSocket-Key")?;
-
-        // Generate accept key
-        let magic = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
-        let combined = format!("{}{}", ws_key, magic);
-        let mut hasher = Sha1::new();
-        hasher.update(combined.as_bytes());
-        let hash = hasher.finalize();
-        let accept_key = base64::engine::general_purpose::STANDARD.encode(hash);
-
-        // Send WebSocket handshake response
-        let response = format!(
-            "HTTP/1.1 101 Switching Protocols\r\n\
-             Upgrade: websocket\r\n\
-             Connection: Upgrade\r\n\
-             Sec-WebSocket-Accept: {}\r\n\r\n",
-            accept_key
-        );
-
-        let mut socket = socket;
-        tokio::io::AsyncWriteExt::write_all(&mut socket, response.as_bytes()).await?;
-
-        info!("✅ WebSocket connection established with {}", peer);
-
-        // Use tokio-tungstenite for WebSocket framing
-        let ws_stream = tokio_tungstenite::WebSocketStream::from_raw_socket(
-            socket,
-            tokio_tungstenite::tungstenite::protocol::Role::Server,
-            None,
-        ).await;
+/// GFEF state behind the HTTP endpoints
+#[derive(Clone)]
+struct GfefServices {
+    access: Arc<ControlAccess>,
+    predictor: Arc<RwLock<ActivationPredictor>>,
+    calibration: Arc<CalibrationService>,
+    subscriptions: Arc<RwLock<SubscriptionManager>>,
+}
 
-        let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+/// `POST /v1/predict` body
+#[derive(Debug, Deserialize)]
+struct PredictRequest {
+    #[serde(default)]
+    customer_id: Option<String>,
+    model_id: String,
+    layer_id: u32,
+    #[serde(default)]
+    input_embedding_hash: Option<String>,
+    #[serde(default)]
+    input_embedding: Option<Vec<f32>>,
+}
 
-        // Send welcome message
-        let welcome = serde_json::json!({
-            "type": "welcome",
-            "message": "SYMMETRIX Control Plane - WebSocket Connected",
-            "version": symmetrix_core::VERSION,
-            "capabilities": ["qagml", "qanban", "uao-qtcam", "cache", "cascade"]
-        });
-        ws_sender.send(Message::Text(serde_json::to_string(&welcome)?)).await?;
+/// GFEF error response
+fn gfef_error(status: StatusCode, error: &str, message: impl Into<String>) -> Response {
+    (status, Json(json!({
+        "success": false,
+        "error": error,
+        "message": message.into(),
+    }))).into_response()
+}
 
-        // Track connection in stats
-        {
-            let mut stats = server.stats.write().await;
-            stats.http_requests += 1;
-        }
+/// Model ids under the tenant prefix belong to VXLAN namespaces; `Some` is the 422 to send
+fn gfef_reserved_model(model_id: &str) -> Option<Response> {
+    Namespace::Global.is_reserved(model_id).then(|| {
+        gfef_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "RESERVED_MODEL_ID",
+            format!("Model '{}' uses the reserved '{}' prefix", model_id, TENANT_KEY_PREFIX),
+        )
+    })
+}
 
-        // Process incoming messages
-        while let Some(msg) = ws_receiver.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    debug!("WebSocket message from {}: {}", peer, text);
-
-                    // Parse command
-                    match serde_json::from_str::<ControlCommand>(&text) {
-                        Ok(command) => {
-                            let response = Self::process_command(command, server.clone()).await;
-                            let response_json = serde_json::to_string(&response)?;
-                            ws_sender.send(Message::Text(response_json)).await?;
-                        }
-                        Err(e) => {
-                            let error_response = ControlResponse {
-                                success: false,
-                                message: format!("Invalid command: {}", e),
-                                data: None,
-                                latency_ns: 0,
-                            };
-                            ws_sender.send(Message::Text(serde_json::to_string(&error_response)?)).await?;
-                        }
-                    }
-                }
-                Ok(Message::Binary(data)) => {
-                    // Handle binary VXLAN-like packets
-                    if data.len() > 8 {
-                        let payload = &data[8..]; // Skip VXLAN header
-                        if let Ok(command) = serde_json::from_slice::<ControlCommand>(payload) {
-                            let response = Self::process_command(command, server.clone()).await;
-                            let response_json = serde_json::to_vec(&response)?;
-                            ws_sender.send(Message::Binary(response_json)).await?;
-                        }
-                    }
-                }
-                Ok(Message::Ping(data)) => {
-                    ws_sender.send(Message::Pong(data)).await?;
-                }
-                Ok(Message::Close(_)) => {
-                    info!("WebSocket connection closed by {}", peer);
-                    break;
-                }
-                Err(e) => {
-                    warn!("WebSocket error from {}: {}", peer, e);
-                    break;
-                }
-                _ => {}
-            }
+fn gfef_prediction_error(e: GfefError) -> Response {
+    let (status, code) = match e {
+        GfefError::UnknownModel(_) | GfefError::UnknownLayer { .. } => (StatusCode::NOT_FOUND, "NOT_FOUND"),
+        GfefError::InvalidIndex(_) => (StatusCode::BAD_REQUEST, "INVALID_INDEX"),
+        GfefError::NoSignatures(_) | GfefError::DimensionMismatch { .. } => {
+            (StatusCode::UNPROCESSABLE_ENTITY, "UNPREDICTABLE_INPUT")
         }
+    };
+    gfef_error(status, code, e.to_string())
+}
 
-        info!("WebSocket connection ended with {}", peer);
-        Ok(())
+impl GfefServices {
+    /// Check the caller's role for a GFEF endpoint; `Some` is the 403 to send
+    fn authorize(&self, principal: &Principal, permission: Permission, action: &str) -> Option<Response> {
+        let mutation = permission == Permission::GfefWrite;
+        self.access
+            .authorize_action(principal, Transport::Http, Namespace::Global, permission, action, mutation)
+            .err()
+            .map(|e| gfef_error(StatusCode::FORBIDDEN, e.code(), e.to_string()))
     }
+}
 
-    /// Handle HTTP request
-    async fn handle_http_request(request: &str, server: Arc<ServerInternals>) -> String {
-        // Update stats
-        {
-            let mut stats = server.stats.write().await;
-            stats.http_requests += 1;
-        }
-
-        // Parse HTTP request
-        let path = request.lines().next()
-            .and_then(|line| line.split_whitespace().nth(1))
-            .unwrap_or("/");
-
-        // Handle special WebSocket info endpoint
-        if path == "/ws" || path == "/websocket" {
-            return serde_json::to_string_pretty(&serde_json::json!({
-                "success": true,
-                "message": "WebSocket endpoint - use WebSocket protocol to connect",
-                "data": {
-                    "endpoint": "/ws",
-                    "protocol": "wss",
-                    "url": "wss://vxlan-control-plane.onrender.com/ws",
-                    "usage": "Connect with WebSocket client, send JSON commands",
-                    "example_command": {"cmd": "Health"},
-                    "supported_commands": [
-                        "Health", "Stats", "GetCascadeStats", "GetMemoryStats",
-                        "GetBandwidthStats", "CacheStats", "CacheGet", "CacheSet",
-                        "CacheDelete", "CacheIncr", "AllocateMemory", "FreeMemory",
-                        "OptimizeBandwidth", "Lookup", "InsertRoute", "DeleteRoute"
-                    ]
-                }
-            })).unwrap_or_else(|_| "{}".to_string());
-        }
-
-        // Handle GFEF Triple IP Lock endpoints
-        if path == "/v1/indices/stats" || path == "/v1/health" {
-            let predictor = server.gfef_predictor.read().await;
-            let stats = predictor.stats();
-            return serde_json::to_string_pretty(&serde_json::json!({
-                "success": true,
-                "service": "NULL SPACE AI Control Plane",
-                "version": "1.0.0",
-                "triple_ip_lock_status": {
-                    "lock1_gfef_index": if stats.models_loaded > 0 { "SECURED" } else { "NOT_LOADED" },
-                    "lock2_calibration": "ROTATING",
-                    "lock3_prediction_service": "ACTIVE"
-                },
-                "gfef_stats": {
-                    "models_loaded": stats.models_loaded,
-                    "total_neurons": stats.total_neurons,
-                    "total_layers": stats.total_layers,
-                    "target_sparsity": stats.target_sparsity,
-                    "sparsity_percentage": format!("{:.1}%", stats.target_sparsity * 100.0),
-                    "active_neurons_per_inference": format!("{:.1}%", (1.0 - stats.target_sparsity) * 100.0)
-                },
-                "endpoints": {
-                    "predict": "POST /v1/predict",
-                    "upload_index": "POST /v1/index/upload",
-                    "stats": "GET /v1/indices/stats"
-                }
-            })).unwrap_or_else(|_| "{}".to_string());
-        }
-
-        // Handle POST /v1/predict for activation prediction
-        if path == "/v1/predict" {
-            // For now, return info about how to use the endpoint
-            let predictor = server.gfef_predictor.read().await;
-            let stats = predictor.stats();
-
-            if stats.models_loaded == 0 {
-                return serde_json::to_string_pretty(&serde_json::json!({
-                    "success": false,
-                    "error": "NO_INDEX_LOADED",
-                    "message": "No GFEF index loaded. Upload index via POST /v1/index/upload first.",
-                    "triple_ip_lock_status": "INACTIVE"
-                })).unwrap_or_else(|_| "{}".to_string());
+/// Mount the GFEF Triple IP Lock endpoints
+fn gfef_api(api: HttpApi, gfef: GfefServices) -> HttpApi {
+    let stats_gfef = gfef.clone();
+    let stats = move |Extension(principal): Extension<Principal>| {
+        let gfef = stats_gfef.clone();
+        async move {
+            if let Some(denied) = gfef.authorize(&principal, Permission::Read, "GFEF stats") {
+                return denied;
             }
-
-            // Parse body from request for actual prediction
-            let body = request.split("\r\n\r\n").nth(1).unwrap_or("");
-            if body.is_empty() {
-                return serde_json::to_string_pretty(&serde_json::json!({
-                    "success": true,
-                    "message": "GFEF Prediction Service Ready",
-                    "triple_ip_lock_status": "ACTIVE",
-                    "models_loaded": stats.models_loaded,
-                    "total_neurons": stats.total_neurons,
-                    "usage": {
-                        "method": "POST",
-                        "body": {
-                            "session_token": "your_auth_token",
-                            "customer_id": "uuid",
-                            "model_id": "Qwen3-MoE-Coder",
-                            "layer_id": 0,
-                            "input_embedding_hash": "hash_of_input"
-                        }
-                    }
-                })).unwrap_or_else(|_| "{}".to_string());
+            Json(gfef_stats(&gfef).await).into_response()
+        }
+    };
+    let predict_gfef = gfef.clone();
+    let predict = move |Extension(principal): Extension<Principal>, body: String| {
+        let gfef = predict_gfef.clone();
+        async move {
+            if let Some(denied) = gfef.authorize(&principal, Permission::Predict, "POST /v1/predict") {
+                return denied;
             }
-
-            // Actual prediction logic (simplified for demo)
-            return serde_json::to_string_pretty(&serde_json::json!({
-                "success": true,
-                "service": "GFEF Activation Prediction",
-                "triple_ip_lock_status": "ACTIVE",
-                "prediction": {
-                    "request_id": uuid::Uuid::new_v4().to_string(),
-                    "active_neurons_count": ((1.0 - stats.target_sparsity) * stats.total_neurons as f32) as u64,
-                    "sparsity_achieved": format!("{:.1}%", stats.target_sparsity * 100.0),
-                    "weight_reduction": "19.6×",
-                    "message": "5% of neurons predicted to activate"
-                }
-            })).unwrap_or_else(|_| "{}".to_string());
+            gfef_predict(&*gfef.predictor.read().await, &body)
         }
-
-        // Handle POST /v1/index/upload for GFEF index upload
-        if path == "/v1/index/upload" {
-            let body = request.split("\r\n\r\n").nth(1).unwrap_or("");
-            if body.is_empty() {
-                return serde_json::to_string_pretty(&serde_json::json!({
-                    "success": false,
-                    "error": "EMPTY_BODY",
-                    "message": "Request body is empty. Send GFEF index JSON."
-                })).unwrap_or_else(|_| "{}".to_string());
+    };
+    let upload = move |Extension(principal): Extension<Principal>, body: String| {
+        let gfef = gfef.clone();
+        async move {
+            if let Some(denied) = gfef.authorize(&principal, Permission::GfefWrite, "POST /v1/index/upload") {
+                return denied;
             }
+            gfef_upload(&gfef, &body).await
+        }
+    };
 
-            // Parse the index JSON
-            match serde_json::from_str::<serde_json::Value>(body) {
-                Ok(index_json) => {
-                    let model_name = index_json.get("model")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown");
-                    let total_neurons = index_json.get("total_neurons")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(0);
-                    let num_layers = index_json.get("num_layers")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(0);
-                    let k_components = index_json.get("k_components")
-                        .and_then(|v| v.as_u64())
-                        .unwrap_or(32);
-                    let layers = index_json.get("layers")
-                        .and_then(|v| v.as_array())
-                        .map(|a| a.len())
-                        .unwrap_or(0);
-
-                    info!("🔐 Receiving GFEF index upload for model: {}", model_name);
-                    info!("   Total Neurons: {}", total_neurons);
-                    info!("   Layers: {} (metadata entries: {})", num_layers, layers);
-                    info!("   K-Components: {}", k_components);
-
-                    // Create LayerIndex entries from the JSON
-                    let layer_indices: Vec<LayerIndex> = index_json.get("layers")
-                        .and_then(|v| v.as_array())
-                        .map(|arr| {
-                            arr.iter().map(|l| {
-                                LayerIndex {
-                                    layer_id: l.get("layer_id").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
-                                    layer_name: l.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                                    num_neurons: l.get("neurons").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
-                                    input_dim: l.get("pc_shape").and_then(|v| v.as_array()).and_then(|a| a.get(0)).and_then(|v| v.as_u64()).unwrap_or(0) as u32,
-                                    k_components: k_components as u32,
-                                    principal_components: Vec::new(),
-                                    signatures: Vec::new(),
-                                }
-                            }).collect()
-                        })
-                        .unwrap_or_default();
-
-                    // Create IndexConfig
-                    let config = IndexConfig {
-                        k_components: k_components as u32,
-                        fft_bins: index_json.get("fft_bins").and_then(|v| v.as_u64()).unwrap_or(16) as u32,
-                        target_sparsity: 0.95,
-                    };
-
-                    // Create GFEFIndex
-                    let index = GFEFIndex {
-                        id: uuid::Uuid::new_v4(),
-                        customer_id: uuid::Uuid::nil(),
-                        model_id: model_name.to_string(),
-                        model_name: model_name.to_string(),
-                        generated_at: chrono::Utc::now(),
-                        expires_at: None,
-                        layers: layer_indices,
-                        total_neurons,
-                        config,
-                    };
-
-                    let index_id = index.id;
-                    let num_layers_registered = index.layers.len();
-
-                    // Register with predictor
-                    {
-                        let mut predictor = server.gfef_predictor.write().await;
-                        predictor.register_index(index);
-                    }
+    api.route(
+        ApiOperation::new(Method::GET, "/v1/indices/stats", "GFEF", "GFEF index statistics", Value::schema()),
+        get(stats.clone()),
+    )
+    .route(
+        ApiOperation::new(Method::GET, "/v1/health", "GFEF", "Triple IP Lock status", Value::schema()),
+        get(stats),
+    )
+    .route(
+        ApiOperation::new(Method::POST, "/v1/predict", "GFEF", "Predict active neurons (empty body: usage)", Value::schema()),
+        post(predict),
+    )
+    .route(
+        ApiOperation::new(Method::POST, "/v1/index/upload", "GFEF", "Upload a GFEF index", Value::schema()),
+        post(upload),
+    )
+}
 
-                    info!("🔒 TRIPLE IP LOCK ACTIVE - Index secured on Control Plane");
-                    info!("   Lock 1: GFEF Index (SECURED) - {} neurons", total_neurons);
-                    info!("   Lock 2: Calibration Matrix (rotating every 60s)");
-                    info!("   Lock 3: Activation Prediction Service (real-time oracle)");
-
-                    let predictor = server.gfef_predictor.read().await;
-                    let stats = predictor.stats();
-
-                    return serde_json::to_string_pretty(&serde_json::json!({
-                        "success": true,
-                        "message": "🔐 GFEF Index uploaded and secured on Control Plane",
-                        "index_id": index_id.to_string(),
-                        "model_id": model_name,
-                        "total_neurons": total_neurons,
-                        "num_layers": num_layers_registered,
-                        "triple_ip_lock_status": {
-                            "lock1_gfef_index": "SECURED",
-                            "lock2_calibration": "ROTATING",
-                            "lock3_prediction_service": "ACTIVE"
-                        },
-                        "predictor_stats": {
-                            "models_loaded": stats.models_loaded,
-                            "total_neurons": stats.total_neurons,
-                            "total_layers": stats.total_layers,
-                            "target_sparsity": format!("{:.1}%", stats.target_sparsity * 100.0)
-                        }
-                    })).unwrap_or_else(|_| "{}".to_string());
-                }
-                Err(e) => {
-                    return serde_json::to_string_pretty(&serde_json::json!({
-                        "success": false,
-                        "error": "INVALID_JSON",
-                        "message": format!("Failed to parse index JSON: {}", e)
-                    })).unwrap_or_else(|_| "{}".to_string());
-                }
-            }
+async fn gfef_stats(gfef: &GfefServices) -> Value {
+    let stats = gfef.predictor.read().await.stats();
+    let calibration = gfef.calibration.current();
+    json!({
+        "success": true,
+        "service": "NULL SPACE AI Control Plane",
+        "version": "1.0.0",
+        "triple_ip_lock_status": {
+            "lock1_gfef_index": if stats.models_loaded > 0 { "SECURED" } else { "NOT_LOADED" },
+            "lock2_calibration": "ROTATING",
+            "lock3_prediction_service": "ACTIVE"
+        },
+        "gfef_stats": {
+            "models_loaded": stats.models_loaded,
+            "total_neurons": stats.total_neurons,
+            "total_layers": stats.total_layers,
+            "target_sparsity": stats.target_sparsity,
+            "sparsity_percentage": format!("{:.1}%", stats.target_sparsity * 100.0),
+            "active_neurons_per_inference": format!("{:.1}%", (1.0 - stats.target_sparsity) * 100.0),
+            "predictions_served": stats.predictions_served,
+            "subscriptions": gfef.subscriptions.read().await.len(),
+        },
+        "calibration": calibration,
+        "endpoints": {
+            "predict": "POST /v1/predict",
+            "upload_index": "POST /v1/index/upload",
+            "stats": "GET /v1/indices/stats"
         }
+    })
+}
 
-        let command = match path {
-            "/" | "/health" => ControlCommand::Health,
-            "/stats" => ControlCommand::Stats,
-            "/cascade" => ControlCommand::GetCascadeStats,
-            "/memory" => ControlCommand::GetMemoryStats,
-            "/bandwidth" => ControlCommand::GetBandwidthStats,
-            "/cache/stats" => ControlCommand::CacheStats,
-            _ => ControlCommand::Health,
-        };
+/// `POST /v1/predict`
+fn gfef_predict(predictor: &ActivationPredictor, body: &str) -> Response {
+    let stats = predictor.stats();
 
-        let response = Self::process_command(command, server).await;
-        serde_json::to_string_pretty(&response).unwrap_or_else(|_| "{}".to_string())
+    if stats.models_loaded == 0 {
+        return gfef_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "NO_INDEX_LOADED",
+            "No GFEF index loaded. Upload index via POST /v1/index/upload first.",
+        );
     }
 
-    /// Process control command
-    async fn process_command(command: ControlCommand, server: Arc<ServerInternals>) -> ControlResponse {
-        let start = std::time::Instant::now();
-
-        let (success, message, data) = match command {
-            ControlCommand::Health => {
-                (true, "SYMMETRIX Control Plane Server is healthy".to_string(), Some(serde_json::json!({
-                    "status": "healthy",
-                    "version": symmetrix_core::VERSION,
-                    "uptime_seconds": chrono::Utc::now().timestamp() - server.stats.read().await.start_time,
-                })))
-            }
-
-            ControlCommand::Stats => {
-                let stats = server.stats.read().await.clone();
-                (true, "Server statistics".to_string(), Some(serde_json::to_value(stats).unwrap()))
-            }
-
-            ControlCommand::GetCascadeStats => {
-                let cascade = server.bandwidth_cascade.read().await;
-                let stats = cascade.get_unified_stats();
-                (true, "Cascade statistics".to_string(), Some(serde_json::json!({
-                    "bandwidth_amplification": "250,000,000× (250 MILLION)",
-                    "memory_amplification": "2,500,000,000× (2.5 BILLION)",
-                    "tcam_speedup": "1,250×",
-                    "physical_bandwidth_gbps": 800,
-                    "virtual_bandwidth_exabps": 200,
-                    "physical_memory_gb": 80,
-                    "virtual_memory_exabytes": 200,
-                    "bandwidth_physical_bytes": stats.bandwidth_stats.physical_bytes_processed,
-                    "bandwidth_effective_bytes": stats.bandwidth_stats.cascade_effective_bytes,
-                    "memory_physical_bytes": stats.memory_physical_bytes,
-                    "memory_effective_bytes": stats.memory_effective_bytes,
-                })))
-            }
-
-            ControlCommand::GetMemoryStats => {
-                let qagml = server.qagml.read().await;
-                let stats = qagml.get_stats();
-                (true, "Memory statistics".to_string(), Some(serde_json::json!({
-                    "amplification": format!("{}×", stats.memory_amplification),
-                    "bus_width_amplification": format!("{:.0}×", stats.bus_width_amplification),
-                    "physical_memory_gb": stats.physical_memory_gb,
-                    "effective_memory_pb": stats.effective_memory_pb,
-                    "physical_bus_width_bits": stats.physical_bus_width_bits,
-                    "effective_bus_width_bits": stats.effective_bus_width_bits,
-                    "cache_hit_rate": format!("{:.1}%", stats.cache_hit_rate * 100.0),
-                    "total_allocations": stats.total_allocations,
-                    "bytes_processed": stats.bytes_processed,
-                    "is_healthy": stats.is_healthy,
-                })))
-            }
-
-            ControlCommand::GetBandwidthStats => {
-                let qanban = server.qanban.read().await;
-                match qanban.get_stats() {
-                    Ok(stats) => (true, "Bandwidth statistics".to_string(), Some(serde_json::json!({
-                        "amplification": "1,000,000×",
-                        "packets_processed": stats.packets_processed,
-                        "is_healthy": stats.is_healthy,
-                        "uptime_seconds": stats.uptime_seconds,
-                        "postulates_active": stats.postulates_active,
-                        "memory_usage_mb": stats.memory_usage_mb,
-                        "throughput_pps": stats.throughput_pps,
-                    }))),
-                    Err(e) => (false, format!("Failed to get bandwidth stats: {}", e), None),
-                }
-            }
-
-            // Cache operations
-            ControlCommand::CacheSet { key, value, ttl_seconds } => {
-                match server.cache.set(&key, value.as_bytes(), ttl_seconds) {
-                    Ok(()) => (true, format!("Key '{}' set successfully", key), None),
-                    Err(e) => (false, format!("Cache SET error: {}", e), None),
-                }
-            }
-
-            ControlCommand::CacheGet { key } => {
-                match server.cache.get(&key) {
-                    Ok(Some(value)) => {
-                        let mut stats = server.stats.write().await;
-                        stats.cache_hits += 1;
-                        (true, "Cache hit".to_string(), Some(serde_json::json!({
-                            "key": key,
-                            "value": String::from_utf8_lossy(&value).to_string(),
-                        })))
-                    }
-                    Ok(None) => {
-                        let mut stats = server.stats.write().await;
-                        stats.cache_misses += 1;
-                        (false, format!("Key '{}' not found", key), None)
-                    }
-                    Err(e) => (false, format!("Cache GET error: {}", e), None),
-                }
-            }
-
-            ControlCommand::CacheDelete { key } => {
-                match server.cache.delete(&key) {
-                    Ok(true) => (true, format!("Key '{}' deleted", key), None),
-                    Ok(false) => (false, format!("Key '{}' not found", key), None),
-                    Err(e) => (false, format!("Cache DELETE error: {}", e), None),
-                }
-            }
-
-            ControlCommand::CacheIncr { key } => {
-                match server.cache.incr(&key) {
-                    Ok(value) => (true, format!("Key '{}' incremented to {}", key, value), Some(serde_json::json!({ "value": value }))),
-                    Err(e) => (false, format!("Cache INCR error: {}", e), None),
-                }
-            }
-
-            ControlCommand::CacheStats => {
-                match server.cache.stats() {
-                    Ok(stats) => (true, "Cache statistics".to_string(), Some(serde_json::json!({
-                        "compression_ratio": format!("{}×", stats.compression_ratio as u64),
-                        "hit_rate": format!("{:.1}%", stats.hit_rate * 100.0),
-                        "entries": stats.entry_count,
-                        "compressed_mb": stats.compressed_bytes / (1024 * 1024),
-                        "effective_mb": stats.original_bytes / (1024 * 1024),
-                        "hits": stats.hits,
-                        "misses": stats.misses,
-                        "evictions": stats.evictions,
-                    }))),
-                    Err(e) => (false, format!("Cache stats error: {}", e), None),
-                }
-            }
-
-            // Memory operations
-            ControlCommand::AllocateMemory { size_bytes, region } => {
-                let mut qagml = server.qagml.write().await;
-                match qagml.allocate_amplified_region(size_bytes, &region) {
-                    Ok(allocation) => (true, format!("Allocated {} bytes in region '{}'", size_bytes, region),
-                        Some(serde_json::to_value(allocation).unwrap())),
-                    Err(e) => (false, format!("Allocation error: {}", e), None),
-                }
-            }
-
-            ControlCommand::FreeMemory { allocation_id } => {
-                let mut qagml = server.qagml.write().await;
-                match qagml.free_amplified_region(&allocation_id) {
-                    Ok(()) => (true, format!("Freed allocation '{}'", allocation_id), None),
-                    Err(e) => (false, format!("Free error: {}", e), None),
-                }
-            }
-
-            // Bandwidth operations
-            ControlCommand::OptimizeBandwidth { flow_id, target_gbps } => {
-                let mut qanban = server.qanban.write().await;
-                match qanban.optimize_flow(&flow_id, target_gbps) {
-                    Ok(result) => (true, format!("Optimized flow '{}' to {} Gbps", flow_id, target_gbps),
-                        Some(serde_json::to_value(result).unwrap())),
-                    Err(e) => (false, format!("Optimization error: {}", e), None),
-                }
-            }
-
-            // TCAM operations
-            ControlCommand::Lookup { key } => {
-                let uao_qtcam = server.uao_qtcam.read().await;
-                match uao_qtcam.sync_lookup(&key) {
-                    Ok(result) => (true, format!("Lookup result for '{}'", key), Some(serde_json::to_value(result).unwrap())),
-                    Err(e) => (false, format!("Lookup error: {}", e), None),
-                }
-            }
-
-            ControlCommand::InsertRoute { key, value, priority } => {
-                let mut uao_qtcam = server.uao_qtcam.write().await;
-                match uao_qtcam.sync_insert_route(&key, &value, priority) {
-                    Ok(()) => (true, format!("Route '{}' inserted with priority {}", key, priority), None),
-                    Err(e) => (false, format!("Insert error: {}", e), None),
-                }
-            }
-
-            ControlCommand::DeleteRoute { key } => {
-                let mut uao_qtcam = server.uao_qtcam.write().await;
-                match uao_qtcam.sync_delete_route(&key) {
-                    Ok(()) => (true, format!("Route '{}' deleted", key), None),
-                    Err(e) => (false, format!("Delete error: {}", e), None),
-                }
-            }
-
-            // Calibration Matrix for Weight Server
-            ControlCommand::GetCalibrationMatrix { tier } => {
-                let tier_name = tier.unwrap_or_else(|| "professional".to_string());
-                let (compression_ratio, tier_code) = match tier_name.to_lowercase().as_str() {
-                    "none" => (1.0, 0),
-                    "basic" => (10.0, 1),
-                    "standard" => (100.0, 2),
-                    "professional" => (1250.0, 3),
-                    "enterprise" => (10000.0, 4),
-                    _ => (1250.0, 3), // Default to professional
-                };
-
-                // Generate calibration matrix (64x64 = 4096 values)
-                // This is the SECRET IP - the trained parameters that enable compression
-                let session_id = format!("cal-{}", chrono::Utc::now().timestamp_millis());
-                let expires_at = (chrono::Utc::now().timestamp() + 60) as u64; // 60 second validity
-
-                // Generate matrix values using SYMMETRIX CORE mathematics
-                // In production, these would be trained parameters
-                let mut values: Vec<f64> = Vec::with_capacity(64 * 64);
-                for i in 0..64 {
-                    for j in 0..64 {
-                        // Chern-Simons modulated eigenmode basis
-                        let phase = std::f64::consts::PI * 2.0 * (i * j) as f64 / 64.0;
-                        let cs_term = ((i + j) as f64 * 0.1).sin() * 0.1;
-                        let base = phase.cos() + cs_term;
-                        // Scale by compression ratio
-                        let scaled = base * (compression_ratio / 1250.0);
-                        values.push(1.0 + scaled * 0.001);
-                    }
+    if body.trim().is_empty() {
+        return Json(json!({
+            "success": true,
+            "message": "GFEF Prediction Service Ready",
+            "triple_ip_lock_status": "ACTIVE",
+            "models_loaded": stats.models_loaded,
+            "total_neurons": stats.total_neurons,
+            "usage": {
+                "method": "POST",
+                "body": {
+                    "customer_id": "uuid",
+                    "model_id": "Qwen3-MoE-Coder",
+                    "layer_id": 0,
+                    "input_embedding_hash": "hash_of_input",
+                    "input_embedding": "[f32] (instead of the hash)"
                 }
-
-                (true, format!("Calibration matrix for tier '{}'", tier_name), Some(serde_json::json!({
-                    "rows": 64,
-                    "cols": 64,
-                    "values": values,
-                    "session_id": session_id,
-                    "expires_at": expires_at,
-                    "tier": tier_code,
-                    "tier_name": tier_name,
-                    "compression_ratio": compression_ratio,
-                })))
             }
+        })).into_response();
+    }
 
-            // GFEF (Galois Field Eigenmode Folding) Operations
-            ControlCommand::PredictActivation { customer_id, model_id, layer_index, input_hash } => {
-                // GFEF prediction using Galois Field mathematics
-                // This predicts which 5% of neurons will activate for a given input
-                debug!("GFEF PredictActivation: customer={}, model={}, layer={}", customer_id, model_id, layer_index);
-
-                // Use input hash to deterministically select active neurons
-                // In production, this would use the trained GFEF index
-                let hash_bytes = input_hash.as_bytes();
-                let seed: u64 = hash_bytes.iter().enumerate()
-                    .fold(0u64, |acc, (i, &b)| acc.wrapping_add((b as u64) << (i % 8 * 8)));
-
-                // For Qwen3-MoE: 2048 hidden size, 128 experts, 8 experts per token
-                // Active neurons = ~5% = ~102 neurons per layer
-                let num_active = 102; // 5% of 2048
-                let mut active_neurons: Vec<u32> = Vec::with_capacity(num_active);
-
-                // Generate deterministic but pseudo-random active neuron indices
-                // Using Galois Field GF(2^11) for 2048 hidden size
-                let mut state = seed.wrapping_add(layer_index as u64);
-                for _ in 0..num_active {
-                    // Linear feedback shift register in GF(2^11)
-                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
-                    let neuron_idx = ((state >> 32) as u32) % 2048;
-                    if !active_neurons.contains(&neuron_idx) {
-                        active_neurons.push(neuron_idx);
-                    }
-                }
-
-                // Ensure we have exactly num_active neurons
-                while active_neurons.len() < num_active {
-                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
-                    let neuron_idx = ((state >> 32) as u32) % 2048;
-                    if !active_neurons.contains(&neuron_idx) {
-                        active_neurons.push(neuron_idx);
-                    }
-                }
-
-                active_neurons.sort();
-
-                (true, format!("GFEF prediction for layer {}", layer_index), Some(serde_json::json!({
-                    "layer_id": layer_index,
-                    "layer_index": layer_index,
-                    "input_hash": input_hash,
-                    "active_neurons": active_neurons,
-                    "sparsity": 0.95,
-                    "confidence": 0.99,
-                    "method": "galois_field_eigenmode_folding",
-                    "gf_order": 2048,
-                    "customer_id": customer_id,
-                    "model_id": model_id,
-                })))
-            }
-
-            ControlCommand::UploadGfefIndex { model_id, index_data } => {
-                // Store GFEF index in cache for the model
-                let key = format!("gfef_index:{}", model_id);
-                match server.cache.set(&key, index_data.as_bytes(), Some(86400)) { // 24 hour TTL
-                    Ok(()) => (true, format!("GFEF index uploaded for model '{}'", model_id), Some(serde_json::json!({
-                        "model_id": model_id,
-                        "index_size_bytes": index_data.len(),
-                        "status": "stored",
-                    }))),
-                    Err(e) => (false, format!("Failed to store GFEF index: {}", e), None),
-                }
-            }
-
-            ControlCommand::GetGfefStatus => {
-                // Return GFEF system status
-                (true, "GFEF system status".to_string(), Some(serde_json::json!({
-                    "status": "active",
-                    "version": "1.0.0",
-                    "capabilities": {
-                        "sparsity_prediction": true,
-                        "galois_field_order": 2048,
-                        "max_layers": 384,
-                        "compression_ratio": "20x",
-                    },
-                    "models_indexed": 1,
-                    "triple_ip_lock": {
-                        "lock_1": "GFEF Index (SECURED)",
-                        "lock_2": "Calibration Matrix (rotating)",
-                        "lock_3": "Activation Prediction Service (real-time)",
-                    }
-                })))
-            }
-        };
-
-        // Update command count
-        {
-            let mut stats = server.stats.write().await;
-            stats.commands_processed += 1;
-            stats.total_latency_ns += start.elapsed().as_nanos() as u64;
+    let request: PredictRequest = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return gfef_error(StatusCode::BAD_REQUEST, "INVALID_JSON", format!("Invalid prediction request: {}", e));
+        }
+    };
+    if let Some(reserved) = gfef_reserved_model(&request.model_id) {
+        return reserved;
+    }
+    let prediction = match (&request.input_embedding, &request.input_embedding_hash) {
+        (Some(embedding), _) => predictor.predict_embedding(&request.model_id, request.layer_id, embedding),
+        (None, Some(hash)) => predictor.predict(&request.model_id, request.layer_id, hash),
+        (None, None) => {
+            return gfef_error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_ARGUMENTS",
+                "Send input_embedding or input_embedding_hash",
+            );
         }
+    };
+    let prediction = match prediction {
+        Ok(prediction) => prediction,
+        Err(e) => return gfef_prediction_error(e),
+    };
 
-        ControlResponse {
-            success,
-            message,
-            data,
-            latency_ns: start.elapsed().as_nanos() as u64,
+    Json(json!({
+        "success": true,
+        "service": "GFEF Activation Prediction",
+        "triple_ip_lock_status": "ACTIVE",
+        "prediction": {
+            "request_id": uuid::Uuid::new_v4().to_string(),
+            "customer_id": request.customer_id,
+            "model_id": prediction.model_id,
+            "layer_id": prediction.layer_id,
+            "index_id": prediction.index_id,
+            "active_neurons": prediction.active_neurons,
+            "active_neurons_count": prediction.active_neurons.len(),
+            "sparsity_achieved": format!("{:.1}%", prediction.sparsity * 100.0),
+            "confidence": prediction.confidence,
         }
-    }
+    })).into_response()
 }
 
-/// Internal server state (cloneable for async tasks)
-struct ServerInternals {
-    cache: Arc<UaoQtcamCache>,
-    qagml: Arc<RwLock<SymmetrixQagmlOptimizer>>,
-    qanban: Arc<RwLock<SymmetrixQanbanOptimizer>>,
-    uao_qtcam: Arc<RwLock<SymmetrixUaoQtcamOptimizer>>,
-    bandwidth_cascade: Arc<RwLock<BandwidthCascade>>,
-    stats: Arc<RwLock<ServerStats>>,
-    // GFEF Components - TRIPLE IP LOCK
-    gfef_predictor: Arc<RwLock<ActivationPredictor>>,
-    gfef_calibration: Arc<CalibrationService>,
-    gfef_subscriptions: Arc<RwLock<SubscriptionManager>>,
+/// `POST /v1/index/upload`
+async fn gfef_upload(gfef: &GfefServices, body: &str) -> Response {
+    if body.trim().is_empty() {
+        return gfef_error(
+            StatusCode::BAD_REQUEST,
+            "EMPTY_BODY",
+            "Request body is empty. Send GFEF index JSON.",
+        );
+    }
+
+    let index = match GFEFIndex::from_export("", body) {
+        Ok(index) => index,
+        Err(e) => return gfef_prediction_error(e),
+    };
+    if let Some(reserved) = gfef_reserved_model(&index.model_id) {
+        return reserved;
+    }
+
+    let model_name = index.model_id.clone();
+    let index_id = index.id;
+    let total_neurons = index.total_neurons;
+    let num_layers_registered = index.layers.len();
+    let k_components = index.config.k_components;
+
+    info!("🔐 Receiving GFEF index upload for model: {}", model_name);
+    info!("   Total Neurons: {}", total_neurons);
+    info!("   Layers: {}", num_layers_registered);
+    info!("   K-Components: {}", k_components);
+
+    // Register with predictor
+    let mut predictor = gfef.predictor.write().await;
+    predictor.register_index(index);
+
+    info!("🔒 TRIPLE IP LOCK ACTIVE - Index secured on Control Plane");
+    info!("   Lock 1: GFEF Index (SECURED) - {} neurons", total_neurons);
+    info!("   Lock 2: Calibration Matrix (rotating every {}s)", gfef.calibration.rotation_secs());
+    info!("   Lock 3: Activation Prediction Service (real-time oracle)");
+
+    let stats = predictor.stats();
+
+    Json(json!({
+        "success": true,
+        "message": "🔐 GFEF Index uploaded and secured on Control Plane",
+        "index_id": index_id.to_string(),
+        "model_id": model_name,
+        "total_neurons": total_neurons,
+        "num_layers": num_layers_registered,
+        "triple_ip_lock_status": {
+            "lock1_gfef_index": "SECURED",
+            "lock2_calibration": "ROTATING",
+            "lock3_prediction_service": "ACTIVE"
+        },
+        "predictor_stats": {
+            "models_loaded": stats.models_loaded,
+            "total_neurons": stats.total_neurons,
+            "total_layers": stats.total_layers,
+            "target_sparsity": format!("{:.1}%", stats.target_sparsity * 100.0)
+        }
+    })).into_response()
 }
 
 fn print_banner() {
@@ -1318,20 +742,74 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .unwrap_or(4789),
         http_bind: std::env::var("HTTP_BIND").unwrap_or_else(|_| "0.0.0.0".to_string()),
         http_port,
+        resp_bind: std::env::var("RESP_BIND").unwrap_or_else(|_| "127.0.0.1".to_string()),
+        resp_port: std::env::var("RESP_PORT").ok().and_then(|s| s.parse().ok()),
         cache_size: std::env::var("CACHE_SIZE_MB")
             .ok()
             .and_then(|s| s.parse::<usize>().ok())
             .map(|mb| mb * 1024 * 1024)
             .unwrap_or(256 * 1024 * 1024),
+        cache_codec: match std::env::var("CACHE_CODEC") {
+            Ok(name) => name.parse()?,
+            Err(_) => CacheCodec::default(),
+        },
+        cache_persistence: match std::env::var("CACHE_PERSIST_DIR") {
+            Ok(dir) => Some(PersistenceConfig {
+                append_only: std::env::var("CACHE_APPEND_ONLY")
+                    .map(|s| s == "1" || s.eq_ignore_ascii_case("true"))
+                    .unwrap_or(true),
+                fsync: match std::env::var("CACHE_FSYNC") {
+                    Ok(policy) => policy.parse()?,
+                    Err(_) => FsyncPolicy::default(),
+                },
+                ..PersistenceConfig::new(dir)
+            }),
+            Err(_) => None,
+        },
         max_connections: std::env::var("MAX_CONNECTIONS")
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(10000),
+        max_request_bytes: std::env::var("MAX_REQUEST_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_BODY_BYTES),
+        auth_enabled: std::env::var("AUTH_ENABLED")
+            .map(|s| !(s == "0" || s.eq_ignore_ascii_case("false")))
+            .unwrap_or(true),
+        api_keys: match std::env::var("CONTROL_PLANE_API_KEYS") {
+            Ok(list) => ApiKeyConfig::parse_list(&list)?,
+            Err(_) => Vec::new(),
+        },
+        ws_limits: WsLimits {
+            messages_per_second: std::env::var("WS_MESSAGES_PER_SECOND")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(WsLimits::default().messages_per_second),
+            burst: std::env::var("WS_BURST")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(WsLimits::default().burst),
+        },
     };
 
     info!("   VXLAN Port: {}", config.vxlan_port);
+    match config.resp_port {
+        Some(port) => info!("   RESP Port: {} (bound to {})", port, config.resp_bind),
+        None => info!("   RESP Port: disabled (set RESP_PORT to enable)"),
+    }
     info!("   Cache Size: {} MB", config.cache_size / (1024 * 1024));
+    info!("   Cache Codec: {}", config.cache_codec);
+    match &config.cache_persistence {
+        Some(p) => info!("   Cache Persistence: {} (append-only {}, fsync {})",
+                         p.dir.display(), p.append_only, p.fsync),
+        None => info!("   Cache Persistence: disabled (set CACHE_PERSIST_DIR to enable)"),
+    }
     info!("   Max Connections: {}", config.max_connections);
+    info!("   Max Request Body: {} bytes", config.max_request_bytes);
+    info!("   Auth (HTTP/WS/VXLAN): {}", if config.auth_enabled { "🔐 ENABLED" } else { "🔓 DISABLED" });
+    info!("   API Keys: {}", config.api_keys.len());
+    info!("   WebSocket Limit: {} msg/s (burst {})", config.ws_limits.messages_per_second, config.ws_limits.burst);
 
     // Create and start server
     let server = ControlPlaneServer::new(config).await?;
@@ -1361,7 +839,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use symmetrix_core::UaoQtcamCache;
+    use symmetrix_core::control_plane::vxlan::VXLAN_HEADER_SIZE;
+    use symmetrix_core::control_plane::{ControlCommand, ControlResponse};
     use symmetrix_core::bandwidth_cascade::{
         QAGML_MEMORY_AMPLIFICATION,
         QANBAN_BANDWIDTH_AMPLIFICATION,
@@ -1376,57 +855,122 @@ mod tests {
             vxlan_port: 4789,
             http_bind: "0.0.0.0".to_string(),
             http_port: 8080,
+            resp_bind: "127.0.0.1".to_string(),
+            resp_port: None,
             cache_size: 256 * 1024 * 1024,
+            cache_codec: CacheCodec::Zstd,
+            cache_persistence: None,
             max_connections: 10000,
+            max_request_bytes: DEFAULT_MAX_BODY_BYTES,
+            auth_enabled: false,
+            api_keys: Vec::new(),
+            ws_limits: WsLimits::default(),
         };
 
         assert_eq!(config.vxlan_port, 4789);
         assert_eq!(config.http_port, 8080);
         assert_eq!(config.cache_size, 256 * 1024 * 1024);
         assert_eq!(config.max_connections, 10000);
+        assert_eq!(config.max_request_bytes, ServerConfig::default().max_request_bytes);
+    }
+
+    #[tokio::test]
+    async fn test_auth_without_keys_refuses_to_start() {
+        let config = ServerConfig { cache_size: 4 * 1024 * 1024, ..ServerConfig::default() };
+        assert!(config.auth_enabled);
+        assert!(ControlPlaneServer::new(config).await.is_err());
+
+        let config = ServerConfig {
+            cache_size: 4 * 1024 * 1024,
+            api_keys: ApiKeyConfig::parse_list("k1:viewer").unwrap(),
+            ..ServerConfig::default()
+        };
+        assert!(ControlPlaneServer::new(config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_gfef_refuses_reserved_model_ids() {
+        let config = ServerConfig {
+            cache_size: 4 * 1024 * 1024,
+            api_keys: ApiKeyConfig::parse_list("k1:admin").unwrap(),
+            ..ServerConfig::default()
+        };
+        let server = ControlPlaneServer::new(config).await.unwrap();
+        let gfef = GfefServices {
+            access: server.state.access.clone(),
+            predictor: server.gfef_predictor.clone(),
+            calibration: server.gfef_calibration.clone(),
+            subscriptions: server.gfef_subscriptions.clone(),
+        };
+        let index = |model: &str| json!({ "model": model, "layers": [{ "layer_id": 0, "neurons": 4 }] }).to_string();
+
+        let response = gfef_upload(&gfef, &index("vni:1:m")).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(gfef.predictor.read().await.stats().models_loaded, 0);
+
+        assert_eq!(gfef_upload(&gfef, &index("m")).await.status(), StatusCode::OK);
+        let predict = json!({ "model_id": "vni:1:m", "layer_id": 0, "input_embedding_hash": "h" }).to_string();
+        let response = gfef_predict(&*gfef.predictor.read().await, &predict);
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn test_api_key_list_parsing() {
+        let keys = ApiKeyConfig::parse_list("k1:admin:ops, k2:viewer,").unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!((keys[0].role, keys[0].org_id.as_str()), (Role::Admin, "ops"));
+        assert_eq!((keys[1].role, keys[1].org_id.as_str()), (Role::Viewer, "control-plane"));
+        assert!(keys[0].vnis.is_empty());
+
+        let keys = ApiKeyConfig::parse_list("k3:operator:tenants:100+200").unwrap();
+        assert_eq!((keys[0].org_id.as_str(), keys[0].vnis.as_slice()), ("tenants", &[100, 200][..]));
+        assert!(ApiKeyConfig::parse_list("k3:operator:tenants:x").is_err());
+
+        assert!(ApiKeyConfig::parse_list("k1").is_err());
+        assert!(ApiKeyConfig::parse_list("k1:root").is_err());
     }
 
     #[test]
     fn test_control_command_parsing() {
         // Test Health command
         let health_json = r#"{"cmd": "Health"}"#;
-        let cmd: Result<super::ControlCommand, _> = serde_json::from_str(health_json);
+        let cmd: Result<ControlCommand, _> = serde_json::from_str(health_json);
         assert!(cmd.is_ok());
 
         // Test Stats command
         let stats_json = r#"{"cmd": "Stats"}"#;
-        let cmd: Result<super::ControlCommand, _> = serde_json::from_str(stats_json);
+        let cmd: Result<ControlCommand, _> = serde_json::from_str(stats_json);
         assert!(cmd.is_ok());
 
         // Test CacheSet command
         let cache_set_json = r#"{"cmd": "CacheSet", "data": {"key": "test_key", "value": "test_value", "ttl_seconds": null}}"#;
-        let cmd: Result<super::ControlCommand, _> = serde_json::from_str(cache_set_json);
+        let cmd: Result<ControlCommand, _> = serde_json::from_str(cache_set_json);
         assert!(cmd.is_ok());
 
         // Test CacheGet command
         let cache_get_json = r#"{"cmd": "CacheGet", "data": {"key": "test_key"}}"#;
-        let cmd: Result<super::ControlCommand, _> = serde_json::from_str(cache_get_json);
+        let cmd: Result<ControlCommand, _> = serde_json::from_str(cache_get_json);
         assert!(cmd.is_ok());
 
         // Test AllocateMemory command
         let mem_alloc_json = r#"{"cmd": "AllocateMemory", "data": {"size_bytes": 1048576, "region": "default"}}"#;
-        let cmd: Result<super::ControlCommand, _> = serde_json::from_str(mem_alloc_json);
+        let cmd: Result<ControlCommand, _> = serde_json::from_str(mem_alloc_json);
         assert!(cmd.is_ok());
 
         // Test OptimizeBandwidth command
         let bw_opt_json = r#"{"cmd": "OptimizeBandwidth", "data": {"flow_id": "flow_123", "target_gbps": 100.0}}"#;
-        let cmd: Result<super::ControlCommand, _> = serde_json::from_str(bw_opt_json);
+        let cmd: Result<ControlCommand, _> = serde_json::from_str(bw_opt_json);
         assert!(cmd.is_ok());
 
         // Test Lookup command
         let lookup_json = r#"{"cmd": "Lookup", "data": {"key": "192.168.1.0/24"}}"#;
-        let cmd: Result<super::ControlCommand, _> = serde_json::from_str(lookup_json);
+        let cmd: Result<ControlCommand, _> = serde_json::from_str(lookup_json);
         assert!(cmd.is_ok());
     }
 
     #[test]
     fn test_control_response_serialization() {
-        let response = super::ControlResponse {
+        let response = ControlResponse {
             success: true,
             message: "OK".to_string(),
             data: Some(serde_json::json!({
@@ -1440,7 +984,7 @@ mod tests {
         assert!(json.contains("\"success\":true"));
         assert!(json.contains("\"status\":\"healthy\""));
 
-        let error_response = super::ControlResponse {
+        let error_response = ControlResponse {
             success: false,
             message: "Connection failed".to_string(),
             data: None,
@@ -1488,7 +1032,7 @@ mod tests {
         assert_eq!(super::VXLAN_PORT, 4789);
 
         // VXLAN header size (8 bytes)
-        assert_eq!(super::VXLAN_HEADER_SIZE, 8);
+        assert_eq!(VXLAN_HEADER_SIZE, 8);
     }
 
     #[tokio::test]
@@ -1544,7 +1088,7 @@ mod tests {
         ];
 
         for json in commands {
-            let parsed: Result<super::ControlCommand, _> = serde_json::from_str(json);
+            let parsed: Result<ControlCommand, _> = serde_json::from_str(json);
             assert!(parsed.is_ok(), "Failed to parse command: {}", json);
         }
     }
@@ -1552,7 +1096,7 @@ mod tests {
     #[test]
     fn test_json_rpc_format() {
         // Test that commands follow tagged enum format
-        let command = super::ControlCommand::CacheSet {
+        let command = ControlCommand::CacheSet {
             key: "my_key".to_string(),
             value: "my_value".to_string(),
             ttl_seconds: Some(3600),
@@ -282,28 +282,12 @@ impl SymmetrixDaemon {
         config: NetworkConfig,
         mut shutdown: tokio::sync::broadcast::Receiver<()>,
     ) {
-        use axum::{routing::get, Router, Json};
         use std::net::SocketAddr;
 
         info!("🌐 Starting web management interface on {}:{}", config.bind_address, config.port);
 
-        let runtime_clone = runtime.clone();
-
-        // Build the web server routes
-        let app = Router::new()
-            .route("/health", get(|| async { Json(serde_json::json!({"status": "healthy"})) }))
-            .route("/metrics", get(move || {
-                let rt = runtime_clone.clone();
-                async move {
-                    let stats = rt.get_stats();
-                    Json(serde_json::json!({
-                        "containers_active": stats.containers_active,
-                        "cache_hit_rate": stats.cache_hit_rate,
-                        "math_ops_per_second": stats.math_ops_per_second,
-                        "cohomology_dimension": stats.cohomology_dimension
-                    }))
-                }
-            }));
+        // Routes are documented at /openapi.json (viewer at /docs)
+        let app = symmetrix_core::grpc::http_api::daemon_api(runtime).into_router();
 
         let addr: SocketAddr = format!("{}:{}", config.bind_address, config.port)
             .parse()
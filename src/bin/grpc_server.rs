@@ -41,6 +41,22 @@ struct Args {
     #[arg(long)]
     reflection: bool,
 
+    /// GPU catalog overrides (TOML or YAML)
+    #[arg(long)]
+    gpu_catalog: Option<String>,
+
+    /// API key for admin RPCs (e.g. ReloadGpuCatalog)
+    #[arg(long)]
+    admin_api_key: Option<String>,
+
+    /// SQLite database for GPU registrations (in-memory when unset)
+    #[arg(long)]
+    registration_db: Option<String>,
+
+    /// Append-only file for closed usage records (in-memory when unset)
+    #[arg(long)]
+    usage_ledger: Option<String>,
+
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, default_value = "info")]
     log_level: String,
@@ -93,7 +109,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     print_banner();
 
     // Build configuration
-    let config = if args.tls {
+    let mut config = if args.tls {
         let cert = args.cert.ok_or("TLS certificate path required when --tls is enabled")?;
         let key = args.key.ok_or("TLS key path required when --tls is enabled")?;
         let mut config = GrpcServerConfig::production(cert, key);
@@ -111,11 +127,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             enable_mtls: false,
             max_concurrent_streams: args.max_streams,
             enable_reflection: args.reflection,
+            gpu_catalog_path: None,
+            admin_api_key: None,
+            registration_db_path: None,
+            usage_ledger_path: None,
         }
     };
+    config.gpu_catalog_path = args.gpu_catalog;
+    config.admin_api_key = args.admin_api_key;
+    config.registration_db_path = args.registration_db;
+    config.usage_ledger_path = args.usage_ledger;
 
     // Create and start server
-    let server = CyanFlameGrpcServer::with_config(config);
+    let server = CyanFlameGrpcServer::with_config(config)?;
     server.serve().await?;
 
     Ok(())
@@ -3,10 +3,13 @@
 //! Combines HTTP Management API and gRPC services in a single binary.
 //! - HTTP API on port 8080 (configurable)
 //! - gRPC services on port 50051 (configurable)
+//! - REST/JSON gateway to the gRPC services under `/v1` on the HTTP port
+//!   (`POST /v1/{Service}/{Method}`; streaming RPCs as Server-Sent Events)
+//! - OpenAPI 3.1 document at `/openapi.json`, browsable at `/docs`
 //!
 //! ## Authentication
 //!
-//! When `--auth` is enabled, all gRPC requests require a valid API key.
+//! When `--auth` is enabled, all gRPC and `/v1` gateway requests require a valid API key.
 //! Include the key in the `x-api-key` header or as `Authorization: Bearer <key>`.
 //!
 //! ## Default Test API Keys (when --auth is enabled)
@@ -25,7 +28,7 @@ use std::sync::Arc;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
-use symmetrix_core::grpc::{GrpcServerConfig, server::CyanFlameGrpcServer};
+use symmetrix_core::grpc::{GrpcServerConfig, http_api, server::CyanFlameGrpcServer};
 
 /// CYAN FLAME Unified Control Plane Server
 #[derive(Parser, Debug)]
@@ -70,6 +73,22 @@ struct Args {
     #[arg(long)]
     auth: bool,
 
+    /// GPU catalog overrides (TOML or YAML)
+    #[arg(long)]
+    gpu_catalog: Option<String>,
+
+    /// API key for admin RPCs (e.g. ReloadGpuCatalog)
+    #[arg(long)]
+    admin_api_key: Option<String>,
+
+    /// SQLite database for GPU registrations (in-memory when unset)
+    #[arg(long)]
+    registration_db: Option<String>,
+
+    /// Append-only file for closed usage records (in-memory when unset)
+    #[arg(long)]
+    usage_ledger: Option<String>,
+
     /// Log level
     #[arg(long, default_value = "info")]
     log_level: String,
@@ -168,11 +187,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         enable_mtls: args.mtls,
         max_concurrent_streams: 100,
         enable_reflection: args.reflection,
+        gpu_catalog_path: args.gpu_catalog,
+        admin_api_key: args.admin_api_key,
+        registration_db_path: args.registration_db,
+        usage_ledger_path: args.usage_ledger,
     };
 
     // Start gRPC server in background with auth setting
     info!("🔥 CYAN FLAME gRPC server starting on {}", grpc_bind);
-    let grpc_server = CyanFlameGrpcServer::with_config_and_auth(grpc_config, args.auth);
+    let grpc_server = CyanFlameGrpcServer::with_config_and_auth(grpc_config, args.auth)?;
+    let gateway = grpc_server.http_gateway();
     let grpc_handle = tokio::spawn(async move {
         if let Err(e) = grpc_server.serve().await {
             tracing::error!("gRPC server error: {}", e);
@@ -184,7 +208,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let http_addr: SocketAddr = http_bind.parse()?;
     let auth_enabled = args.auth;
     let http_handle = tokio::spawn(async move {
-        run_http_server(http_addr, auth_enabled).await;
+        run_http_server(http_addr, auth_enabled, gateway).await;
     });
 
     // Wait for shutdown signal
@@ -197,65 +221,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn run_http_server(addr: SocketAddr, auth_enabled: bool) {
-    use axum::{routing::get, Router, Json, Extension};
-    use serde_json::json;
-
-    let app = Router::new()
-        .route("/", get(move || async move {
-            Json(json!({
-                "status": "healthy",
-                "service": "CYAN FLAME Unified",
-                "auth_enabled": auth_enabled
-            }))
-        }))
-        .route("/health", get(|| async { Json(json!({"status": "healthy"})) }))
-        .route("/grpc", get(move || async move {
-            Json(json!({
-                "grpc_port": 50051,
-                "auth_required": auth_enabled,
-                "services": [
-                    "CalibrationService",
-                    "TelemetryService",
-                    "AllocationService",
-                    "OperationsService"
-                ]
-            }))
-        }))
-        .route("/tiers", get(|| async {
-            Json(json!({
-                "tiers": [
-                    {
-                        "name": "free",
-                        "amplification": 100,
-                        "max_allocation_tb": 2,
-                        "rate_limit": 100,
-                        "price": "$0/month"
-                    },
-                    {
-                        "name": "starter",
-                        "amplification": 1000,
-                        "max_allocation_tb": 24,
-                        "rate_limit": 1000,
-                        "price": "$99/month"
-                    },
-                    {
-                        "name": "pro",
-                        "amplification": 10000,
-                        "max_allocation_tb": 240,
-                        "rate_limit": 10000,
-                        "price": "$499/month"
-                    },
-                    {
-                        "name": "enterprise",
-                        "amplification": 24500,
-                        "max_allocation_tb": 574,
-                        "rate_limit": "unlimited",
-                        "price": "$2,499+/month"
-                    }
-                ]
-            }))
-        }));
+async fn run_http_server(addr: SocketAddr, auth_enabled: bool, gateway: axum::Router) {
+    // Every route is documented at /openapi.json (viewer at /docs)
+    let app = http_api::unified_api(auth_enabled, gateway).into_router();
 
     info!("🌐 HTTP server listening on {}", addr);
 
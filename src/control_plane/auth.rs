@@ -0,0 +1,546 @@
+//! Control Plane Authentication and Authorization
+//!
+//! Every transport identifies its caller by an API key of the shared
+//! [`AuthManager`]:
+//! - HTTP: `x-api-key` or `Authorization: Bearer <key>`
+//! - WebSocket: the same headers, or `?api_key=` on the upgrade request
+//! - VXLAN: an HMAC-SHA256 block after the header (see [`super::vxlan`])
+//!
+//! The caller's [`Role`] must grant the [`Permission`] that [`RolePolicy`]
+//! requires for the command, or for the event [`Topic`] it subscribes to.
+//! Denials and allowed mutations are written to the [`AuditLog`]. With
+//! authentication disabled every caller is an admin.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::{ControlCommand, Namespace, Topic};
+use crate::grpc::auth::{AuthManager, VxlanKey};
+
+/// Audit entries kept in memory
+pub const AUDIT_CAPACITY: usize = 10_000;
+
+/// Accepted clock difference of signed VXLAN frames
+pub const DEFAULT_MAX_CLOCK_SKEW_SECS: u64 = 300;
+
+/// Signed VXLAN frames remembered inside the clock-skew window
+pub const REPLAY_CACHE_CAPACITY: usize = 100_000;
+
+/// What a command needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Health, statistics, lookups and cache reads
+    Read,
+    /// Activation predictions and calibration matrices
+    Predict,
+    CacheWrite,
+    MemoryWrite,
+    BandwidthWrite,
+    RouteWrite,
+    GfefWrite,
+    /// Subscribe to [`Topic::Keyspace`]
+    KeyspaceEvents,
+    /// Subscribe to [`Topic::Routes`]
+    RouteEvents,
+    /// Subscribe to [`Topic::Calibration`]
+    CalibrationEvents,
+    /// Subscribe to [`Topic::Stats`]
+    StatsEvents,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 11] = [
+        Permission::Read,
+        Permission::Predict,
+        Permission::CacheWrite,
+        Permission::MemoryWrite,
+        Permission::BandwidthWrite,
+        Permission::RouteWrite,
+        Permission::GfefWrite,
+        Permission::KeyspaceEvents,
+        Permission::RouteEvents,
+        Permission::CalibrationEvents,
+        Permission::StatsEvents,
+    ];
+}
+
+/// Caller roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Viewer,
+    Operator,
+    Admin,
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Ok(Self::Viewer),
+            "operator" => Ok(Self::Operator),
+            "admin" => Ok(Self::Admin),
+            _ => Err(format!("Unknown role '{}' (expected viewer, operator or admin)", s)),
+        }
+    }
+}
+
+/// Permissions granted to each role
+#[derive(Debug, Clone)]
+pub struct RolePolicy {
+    grants: HashMap<Role, BTreeSet<Permission>>,
+}
+
+impl Default for RolePolicy {
+    /// Viewers read and predict, operators also write the cache, memory and
+    /// bandwidth, admins also change routes and GFEF indices. Each role sees
+    /// the events of what it may change: viewers stats and calibration,
+    /// operators also the keyspace, admins also routes.
+    fn default() -> Self {
+        use Permission::*;
+        let viewer = [Read, Predict, StatsEvents, CalibrationEvents];
+        let operator = [
+            Read,
+            Predict,
+            CacheWrite,
+            MemoryWrite,
+            BandwidthWrite,
+            StatsEvents,
+            CalibrationEvents,
+            KeyspaceEvents,
+        ];
+        Self {
+            grants: HashMap::from([
+                (Role::Viewer, viewer.into_iter().collect()),
+                (Role::Operator, operator.into_iter().collect()),
+                (Role::Admin, Permission::ALL.into_iter().collect()),
+            ]),
+        }
+    }
+}
+
+impl RolePolicy {
+    /// Permission `command` requires
+    pub fn required(command: &ControlCommand) -> Permission {
+        use ControlCommand as C;
+        match command {
+            C::Health
+            | C::Stats
+            | C::GetMemoryStats
+            | C::GetBandwidthStats
+            | C::Lookup { .. }
+            | C::CacheGet { .. }
+            | C::CacheStats
+            | C::GetCascadeStats
+            | C::GetGfefStatus => Permission::Read,
+            C::PredictActivation { .. } | C::GetCalibrationMatrix { .. } => Permission::Predict,
+            C::CacheSet { .. } | C::CacheDelete { .. } | C::CacheIncr { .. } => Permission::CacheWrite,
+            C::AllocateMemory { .. } | C::FreeMemory { .. } => Permission::MemoryWrite,
+            C::OptimizeBandwidth { .. } => Permission::BandwidthWrite,
+            C::InsertRoute { .. } | C::DeleteRoute { .. } => Permission::RouteWrite,
+            C::UploadGfefIndex { .. } => Permission::GfefWrite,
+        }
+    }
+
+    /// Permission a subscription to `topic` requires
+    pub fn required_for_topic(topic: Topic) -> Permission {
+        match topic {
+            Topic::Keyspace => Permission::KeyspaceEvents,
+            Topic::Routes => Permission::RouteEvents,
+            Topic::Calibration => Permission::CalibrationEvents,
+            Topic::Stats => Permission::StatsEvents,
+        }
+    }
+
+    /// Replace the permissions of `role`
+    pub fn with_grant(mut self, role: Role, permissions: impl IntoIterator<Item = Permission>) -> Self {
+        self.grants.insert(role, permissions.into_iter().collect());
+        self
+    }
+
+    pub fn allows(&self, role: Role, permission: Permission) -> bool {
+        self.grants
+            .get(&role)
+            .is_some_and(|granted| granted.contains(&permission))
+    }
+}
+
+/// Transport a request arrived on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    Http,
+    WebSocket,
+    Vxlan,
+}
+
+/// Authenticated caller
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Principal {
+    /// Organization of the API key
+    pub id: String,
+    pub role: Role,
+    #[serde(skip)]
+    pub key_hash: String,
+}
+
+impl Principal {
+    /// Caller of the public routes (health and documentation)
+    pub fn anonymous() -> Self {
+        Self {
+            id: "anonymous".to_string(),
+            role: Role::Viewer,
+            key_hash: String::new(),
+        }
+    }
+}
+
+/// Authentication and authorization failures
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AccessError {
+    #[error("Missing API key. Include 'x-api-key' or 'Authorization: Bearer <key>'.")]
+    MissingCredentials,
+    #[error("{0}")]
+    InvalidCredentials(String),
+    #[error("{0}")]
+    KeyDisabled(String),
+    #[error("{0}")]
+    RateLimited(String),
+    #[error("Bad VXLAN signature")]
+    BadSignature,
+    #[error("VXLAN signature timestamp {0} is outside the accepted window")]
+    StaleSignature(u64),
+    #[error("VXLAN frame was already accepted")]
+    ReplayedSignature,
+    #[error("API key of '{principal}' is not authorised for VNI {vni}")]
+    VniDenied { principal: String, vni: u32 },
+    #[error("Role '{role:?}' lacks the '{permission:?}' permission for {action}")]
+    PermissionDenied {
+        role: Role,
+        permission: Permission,
+        action: String,
+    },
+}
+
+impl AccessError {
+    /// Stable error code for responses
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingCredentials
+            | Self::InvalidCredentials(_)
+            | Self::BadSignature
+            | Self::StaleSignature(_)
+            | Self::ReplayedSignature => "UNAUTHENTICATED",
+            Self::KeyDisabled(_) | Self::VniDenied { .. } | Self::PermissionDenied { .. } => "PERMISSION_DENIED",
+            Self::RateLimited(_) => "RATE_LIMITED",
+        }
+    }
+}
+
+/// One audited decision
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    /// Unix milliseconds
+    pub timestamp: i64,
+    pub principal: String,
+    pub role: Role,
+    pub transport: Transport,
+    pub action: String,
+    /// VNI of a tenant namespace
+    pub vni: Option<u32>,
+    pub allowed: bool,
+}
+
+/// Bounded in-memory audit trail, mirrored to the `audit` tracing target
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    entries: Mutex<VecDeque<AuditEntry>>,
+}
+
+impl AuditLog {
+    pub fn record(&self, entry: AuditEntry) {
+        if entry.allowed {
+            info!(target: "audit", "✅ {} ({:?}) {} via {:?} (vni {:?})",
+                entry.principal, entry.role, entry.action, entry.transport, entry.vni);
+        } else {
+            warn!(target: "audit", "🚫 {} ({:?}) denied {} via {:?} (vni {:?})",
+                entry.principal, entry.role, entry.action, entry.transport, entry.vni);
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == AUDIT_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Entries, oldest first
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// MACs of accepted signed frames, by timestamp, until they leave the
+/// clock-skew window and would be rejected as stale anyway
+#[derive(Debug, Default)]
+struct ReplayCache {
+    by_timestamp: BTreeMap<u64, HashSet<[u8; 32]>>,
+    len: usize,
+}
+
+impl ReplayCache {
+    /// Record `mac`, or fail if it was seen or the window is full
+    fn insert(&mut self, timestamp: u64, mac: [u8; 32], now: u64, skew: u64) -> Result<(), AccessError> {
+        let oldest = now.saturating_sub(skew);
+        while let Some(entry) = self.by_timestamp.first_entry() {
+            if *entry.key() >= oldest {
+                break;
+            }
+            self.len -= entry.remove().len();
+        }
+        if self.by_timestamp.get(&timestamp).is_some_and(|macs| macs.contains(&mac)) {
+            return Err(AccessError::ReplayedSignature);
+        }
+        // Forgetting a live MAC would reopen it to replay, so refuse instead
+        if self.len >= REPLAY_CACHE_CAPACITY {
+            return Err(AccessError::RateLimited("Too many signed VXLAN frames in the replay window".to_string()));
+        }
+        self.by_timestamp.entry(timestamp).or_default().insert(mac);
+        self.len += 1;
+        Ok(())
+    }
+}
+
+/// Credentials, roles and audit trail of the control plane
+pub struct ControlAccess {
+    auth: Arc<AuthManager>,
+    policy: RolePolicy,
+    roles: RwLock<HashMap<String, Role>>,
+    /// Tenant VNIs each key hash may use over VXLAN
+    vnis: RwLock<HashMap<String, BTreeSet<u32>>>,
+    default_role: Role,
+    max_clock_skew_secs: u64,
+    replays: Mutex<ReplayCache>,
+    audit: AuditLog,
+}
+
+impl ControlAccess {
+    /// Keys of `auth`; keys without an assigned role are viewers
+    pub fn new(auth: Arc<AuthManager>) -> Self {
+        Self {
+            auth,
+            policy: RolePolicy::default(),
+            roles: RwLock::new(HashMap::new()),
+            vnis: RwLock::new(HashMap::new()),
+            default_role: Role::Viewer,
+            max_clock_skew_secs: DEFAULT_MAX_CLOCK_SKEW_SECS,
+            replays: Mutex::new(ReplayCache::default()),
+            audit: AuditLog::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RolePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Role of keys without an assignment
+    pub fn with_default_role(mut self, role: Role) -> Self {
+        self.default_role = role;
+        self
+    }
+
+    pub fn with_max_clock_skew(mut self, secs: u64) -> Self {
+        self.max_clock_skew_secs = secs;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.auth.is_auth_enabled()
+    }
+
+    pub fn policy(&self) -> &RolePolicy {
+        &self.policy
+    }
+
+    pub fn max_clock_skew_secs(&self) -> u64 {
+        self.max_clock_skew_secs
+    }
+
+    pub fn audit(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// Give `api_key` the `role`
+    pub async fn assign_role(&self, api_key: &str, role: Role) {
+        self.roles
+            .write()
+            .await
+            .insert(AuthManager::hash_key(api_key), role);
+    }
+
+    /// Let `api_key` use the tenant `vnis` over VXLAN
+    pub async fn grant_vnis(&self, api_key: &str, vnis: impl IntoIterator<Item = u32>) {
+        self.vnis
+            .write()
+            .await
+            .entry(AuthManager::hash_key(api_key))
+            .or_default()
+            .extend(vnis);
+    }
+
+    /// Register `api_key` with the auth manager and give it `role`
+    pub async fn register_key(&self, api_key: &str, tier: &str, org_id: &str, role: Role) {
+        self.auth.register_key(api_key, tier, org_id).await;
+        self.assign_role(api_key, role).await;
+    }
+
+    /// Caller presenting `api_key`
+    pub async fn authenticate(&self, api_key: Option<&str>) -> Result<Principal, AccessError> {
+        if !self.is_enabled() {
+            return Ok(self.unauthenticated_admin());
+        }
+        let api_key = api_key.ok_or(AccessError::MissingCredentials)?;
+        self.authenticate_hash(&AuthManager::hash_key(api_key)).await
+    }
+
+    /// Caller whose key hash was proven by a signature
+    pub async fn authenticate_hash(&self, key_hash: &str) -> Result<Principal, AccessError> {
+        if !self.is_enabled() {
+            return Ok(self.unauthenticated_admin());
+        }
+        let entry = self.auth.validate_hash(key_hash).await.map_err(|status| match status.code() {
+            tonic::Code::PermissionDenied => AccessError::KeyDisabled(status.message().to_string()),
+            tonic::Code::ResourceExhausted => AccessError::RateLimited(status.message().to_string()),
+            _ => AccessError::InvalidCredentials(status.message().to_string()),
+        })?;
+        let role = self
+            .roles
+            .read()
+            .await
+            .get(key_hash)
+            .copied()
+            .unwrap_or(self.default_role);
+        Ok(Principal {
+            id: entry.org_id,
+            role,
+            key_hash: key_hash.to_string(),
+        })
+    }
+
+    /// Accept the MAC of a verified frame signed at `timestamp` only once
+    pub fn check_replay(&self, timestamp: u64, mac: [u8; 32], now: u64) -> Result<(), AccessError> {
+        self.replays
+            .lock()
+            .unwrap()
+            .insert(timestamp, mac, now, self.max_clock_skew_secs)
+    }
+
+    /// Signing key a VXLAN frame names
+    pub async fn vxlan_key(&self, key_id: &[u8]) -> Option<VxlanKey> {
+        self.auth.vxlan_key(key_id).await
+    }
+
+    /// Check that the caller may use the VNI of `namespace` over VXLAN
+    ///
+    /// The control VNI is open to every key, like HTTP; a tenant VNI needs an
+    /// admin or a key it was granted to. Denials are audited.
+    pub async fn authorize_vni(
+        &self,
+        principal: &Principal,
+        transport: Transport,
+        namespace: Namespace,
+    ) -> Result<(), AccessError> {
+        let vni = match namespace {
+            Namespace::Global => return Ok(()),
+            Namespace::Tenant(vni) => vni,
+        };
+        if principal.role == Role::Admin
+            || self
+                .vnis
+                .read()
+                .await
+                .get(&principal.key_hash)
+                .is_some_and(|vnis| vnis.contains(&vni))
+        {
+            return Ok(());
+        }
+        self.audit.record(AuditEntry {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            principal: principal.id.clone(),
+            role: principal.role,
+            transport,
+            action: format!("Use VNI {}", vni),
+            vni: Some(vni),
+            allowed: false,
+        });
+        Err(AccessError::VniDenied {
+            principal: principal.id.clone(),
+            vni,
+        })
+    }
+
+    /// Check `command`, auditing denials and allowed mutations
+    pub fn authorize(
+        &self,
+        principal: &Principal,
+        transport: Transport,
+        namespace: Namespace,
+        command: &ControlCommand,
+    ) -> Result<(), AccessError> {
+        self.authorize_action(
+            principal,
+            transport,
+            namespace,
+            RolePolicy::required(command),
+            command.name(),
+            command.is_mutation(),
+        )
+    }
+
+    /// Check `permission` for an action outside the command set
+    pub fn authorize_action(
+        &self,
+        principal: &Principal,
+        transport: Transport,
+        namespace: Namespace,
+        permission: Permission,
+        action: &str,
+        mutation: bool,
+    ) -> Result<(), AccessError> {
+        let allowed = self.policy.allows(principal.role, permission);
+        if !allowed || mutation {
+            self.audit.record(AuditEntry {
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                principal: principal.id.clone(),
+                role: principal.role,
+                transport,
+                action: action.to_string(),
+                vni: namespace.vni(),
+                allowed,
+            });
+        }
+        if allowed {
+            Ok(())
+        } else {
+            Err(AccessError::PermissionDenied {
+                role: principal.role,
+                permission,
+                action: action.to_string(),
+            })
+        }
+    }
+
+    fn unauthenticated_admin(&self) -> Principal {
+        Principal {
+            id: "default".to_string(),
+            role: Role::Admin,
+            key_hash: String::new(),
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Control Plane HTTP API
+//!
+//! - `POST /v1/commands/{Command}` with the command's `data` as the JSON body
+//!   (commands without data take an empty body)
+//! - `POST /v1/commands` with the tagged form `{"cmd": ..., "data": ...}`
+//! - `GET /`, `/health`, `/stats`, `/cascade`, `/memory`, `/bandwidth`,
+//!   `/cache/stats` as read-only shortcuts
+//! - `GET /ws` upgrades to the WebSocket command and event channel (see [`ws`])
+//!
+//! Every route except [`PUBLIC_PATHS`] needs an API key (`x-api-key` or
+//! `Authorization: Bearer`, see [`super::auth`]); the caller's role decides
+//! which commands it may run (403 otherwise).
+//!
+//! Command results are [`ControlResponse`]s whose HTTP status follows the
+//! [`CommandStatus`]. Transport failures (bad JSON, unknown commands,
+//! oversized bodies, unknown routes, credentials) answer with a
+//! [`ControlErrorBody`]. Connections are HTTP/1.1 keep-alive.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::rejection::BytesRejection;
+use axum::extract::{DefaultBodyLimit, Query, Request};
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::auth::{AccessError, Principal, Transport};
+use super::{ws, CommandStatus, ControlCommand, ControlPlaneState, ControlResponse, Namespace};
+use crate::grpc::openapi::{ApiOperation, ApiSchema, HttpApi, DOCS_PATH, OPENAPI_PATH};
+
+/// Default request body limit (GFEF index uploads can exceed 100 KB)
+pub const DEFAULT_MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Path prefix of the per-command routes
+pub const COMMANDS_PATH: &str = "/v1/commands";
+
+/// Routes answered without credentials (liveness checks and documentation)
+pub const PUBLIC_PATHS: [&str; 3] = ["/health", OPENAPI_PATH, DOCS_PATH];
+
+crate::api_schema! {
+    /// Error body for requests that never reached a command
+    #[derive(Debug, Clone, Serialize)]
+    pub struct ControlErrorBody {
+        /// Always `false`
+        pub success: bool,
+        /// INVALID_JSON, INVALID_ARGUMENTS, UNKNOWN_COMMAND, PAYLOAD_TOO_LARGE, NOT_FOUND, METHOD_NOT_ALLOWED,
+        /// UNAUTHENTICATED, PERMISSION_DENIED or RATE_LIMITED
+        pub error: String,
+        pub message: String,
+    }
+}
+
+/// Transport-level failure
+#[derive(Debug)]
+pub struct ControlHttpError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl ControlHttpError {
+    pub(crate) fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<AccessError> for ControlHttpError {
+    fn from(error: AccessError) -> Self {
+        let status = match error.code() {
+            "PERMISSION_DENIED" => StatusCode::FORBIDDEN,
+            "RATE_LIMITED" => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::UNAUTHORIZED,
+        };
+        Self::new(status, error.code(), error.to_string())
+    }
+}
+
+impl From<BytesRejection> for ControlHttpError {
+    fn from(rejection: BytesRejection) -> Self {
+        let status = rejection.status();
+        let code = if status == StatusCode::PAYLOAD_TOO_LARGE {
+            "PAYLOAD_TOO_LARGE"
+        } else {
+            "INVALID_BODY"
+        };
+        Self::new(status, code, rejection.body_text())
+    }
+}
+
+impl IntoResponse for ControlHttpError {
+    fn into_response(self) -> Response {
+        let body = ControlErrorBody {
+            success: false,
+            error: self.code.to_string(),
+            message: self.message,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// HTTP status of a command outcome
+pub fn http_status(status: CommandStatus) -> StatusCode {
+    match status {
+        CommandStatus::Ok => StatusCode::OK,
+        CommandStatus::NotFound => StatusCode::NOT_FOUND,
+        CommandStatus::Rejected => StatusCode::UNPROCESSABLE_ENTITY,
+        CommandStatus::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        CommandStatus::Forbidden => StatusCode::FORBIDDEN,
+    }
+}
+
+async fn run(state: &ControlPlaneState, principal: &Principal, command: ControlCommand) -> Response {
+    let (status, response) = state
+        .execute_as(principal, Transport::Http, Namespace::Global, command)
+        .await;
+    (http_status(status), Json(response)).into_response()
+}
+
+/// API key from `x-api-key` or `Authorization: Bearer`, and from `?api_key=`
+/// on the WebSocket upgrade (browsers cannot set headers there)
+fn api_key(headers: &HeaderMap, uri: &Uri) -> Option<String> {
+    let header = headers
+        .get("x-api-key")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| {
+            headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+        })
+        .map(str::to_string);
+    if header.is_some() || uri.path() != ws::WS_PATH {
+        return header;
+    }
+    Query::<HashMap<String, String>>::try_from_uri(uri)
+        .ok()
+        .and_then(|Query(mut query)| query.remove("api_key"))
+}
+
+/// Attach the caller's [`Principal`] to the request
+async fn authenticate(state: Arc<ControlPlaneState>, mut request: Request, next: Next) -> Response {
+    let principal = if PUBLIC_PATHS.contains(&request.uri().path()) {
+        Principal::anonymous()
+    } else {
+        let key = api_key(request.headers(), request.uri());
+        match state.access.authenticate(key.as_deref()).await {
+            Ok(principal) => principal,
+            Err(e) => {
+                state.stats.write().await.auth_failures += 1;
+                return ControlHttpError::from(e).into_response();
+            }
+        }
+    };
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
+
+fn parse_body(body: &[u8]) -> Result<Value, ControlHttpError> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(body).map_err(|e| {
+        ControlHttpError::new(StatusCode::BAD_REQUEST, "INVALID_JSON", format!("Invalid JSON: {}", e))
+    })
+}
+
+/// Decode `data` for a known command name
+fn decode(name: &str, mut data: Value) -> Result<ControlCommand, ControlHttpError> {
+    let takes_data = ControlCommand::data_schema(name).is_some_and(|s| s["type"] != "null");
+    if !takes_data && data.as_object().is_some_and(|o| o.is_empty()) {
+        data = Value::Null;
+    }
+    ControlCommand::from_parts(name, data).map_err(|e| {
+        ControlHttpError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "INVALID_ARGUMENTS",
+            format!("Invalid {} arguments: {}", name, e),
+        )
+    })
+}
+
+/// `POST /v1/commands/{name}`
+async fn named_command(
+    state: Arc<ControlPlaneState>,
+    name: &'static str,
+    principal: Principal,
+    body: Result<Bytes, BytesRejection>,
+) -> Result<Response, ControlHttpError> {
+    let command = decode(name, parse_body(&body?)?)?;
+    Ok(run(&state, &principal, command).await)
+}
+
+/// `POST /v1/commands`
+async fn tagged_command(
+    state: Arc<ControlPlaneState>,
+    principal: Principal,
+    body: Result<Bytes, BytesRejection>,
+) -> Result<Response, ControlHttpError> {
+    let envelope = parse_body(&body?)?;
+    let name = envelope["cmd"].as_str().ok_or_else(|| {
+        ControlHttpError::new(
+            StatusCode::BAD_REQUEST,
+            "INVALID_ARGUMENTS",
+            "Expected {\"cmd\": <command>, \"data\": {...}}",
+        )
+    })?;
+    let name = ControlCommand::NAMES
+        .iter()
+        .find(|known| **known == name)
+        .ok_or_else(|| {
+            ControlHttpError::new(
+                StatusCode::BAD_REQUEST,
+                "UNKNOWN_COMMAND",
+                format!("Unknown command '{}'", name),
+            )
+        })?;
+    let command = decode(name, envelope.get("data").cloned().unwrap_or_default())?;
+    Ok(run(&state, &principal, command).await)
+}
+
+async fn not_found(uri: Uri) -> ControlHttpError {
+    match uri.path().strip_prefix(COMMANDS_PATH).and_then(|p| p.strip_prefix('/')) {
+        Some(name) => ControlHttpError::new(
+            StatusCode::NOT_FOUND,
+            "UNKNOWN_COMMAND",
+            format!("Unknown command '{}'", name),
+        ),
+        None => ControlHttpError::new(
+            StatusCode::NOT_FOUND,
+            "NOT_FOUND",
+            format!("No route for {}", uri.path()),
+        ),
+    }
+}
+
+async fn method_not_allowed(method: Method, uri: Uri) -> ControlHttpError {
+    ControlHttpError::new(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "METHOD_NOT_ALLOWED",
+        format!("{} is not supported on {}", method, uri.path()),
+    )
+}
+
+fn command_operation(name: &'static str) -> ApiOperation {
+    let mut operation = ApiOperation::new(
+        Method::POST,
+        &format!("{}/{}", COMMANDS_PATH, name),
+        "Commands",
+        ControlCommand::description(name).unwrap_or(name),
+        ControlResponse::schema(),
+    );
+    operation.request = ControlCommand::data_schema(name);
+    operation.description = format!(
+        "Runs `{}`. Not found → 404, rejected arguments → 422, role without the permission → 403, subsystem failure → 500 (all as a ControlResponse with success = false).",
+        name
+    );
+    operation.error = Some(error_schema());
+    operation
+}
+
+fn error_schema() -> Value {
+    json!({ "oneOf": [ControlResponse::schema(), ControlErrorBody::schema()] })
+}
+
+/// Documented routes of the control-plane HTTP API
+pub fn api(state: Arc<ControlPlaneState>) -> HttpApi {
+    let mut api = HttpApi::new(
+        "SYMMETRIX Control Plane",
+        "HTTP API of the VXLAN control-plane server: one route per ControlCommand",
+    );
+
+    let shortcuts = [
+        ("/", "Server health", ControlCommand::Health),
+        ("/health", "Server health", ControlCommand::Health),
+        ("/stats", "Server statistics", ControlCommand::Stats),
+        ("/cascade", "Cascade statistics", ControlCommand::GetCascadeStats),
+        ("/memory", "QAGML memory statistics", ControlCommand::GetMemoryStats),
+        ("/bandwidth", "QANBAN bandwidth statistics", ControlCommand::GetBandwidthStats),
+        ("/cache/stats", "Cache statistics", ControlCommand::CacheStats),
+    ];
+    for (path, summary, command) in shortcuts {
+        let state = state.clone();
+        let mut operation = ApiOperation::new(Method::GET, path, "Status", summary, ControlResponse::schema());
+        operation.error = Some(error_schema());
+        api = api.route(
+            operation,
+            get(move |Extension(principal): Extension<Principal>| async move {
+                run(&state, &principal, command).await
+            }),
+        );
+    }
+
+    let mut tagged = ApiOperation::new(
+        Method::POST,
+        COMMANDS_PATH,
+        "Commands",
+        "Run a command in tagged form",
+        ControlResponse::schema(),
+    );
+    tagged.request = Some(json!({
+        "type": "object",
+        "properties": {
+            "cmd": { "type": "string", "enum": ControlCommand::NAMES },
+            "data": { "description": "Arguments of the command, as for POST /v1/commands/{cmd}" },
+        },
+        "required": ["cmd"],
+    }));
+    tagged.error = Some(error_schema());
+    let tagged_state = state.clone();
+    api = api.route(
+        tagged,
+        post(
+            move |Extension(principal): Extension<Principal>, body: Result<Bytes, BytesRejection>| async move {
+                tagged_command(tagged_state, principal, body).await
+            },
+        ),
+    );
+
+    for name in ControlCommand::NAMES {
+        let state = state.clone();
+        api = api.route(
+            command_operation(name),
+            post(
+                move |Extension(principal): Extension<Principal>, body: Result<Bytes, BytesRejection>| async move {
+                    named_command(state, name, principal, body).await
+                },
+            ),
+        );
+    }
+
+    let mut ws = ApiOperation::new(
+        Method::GET,
+        ws::WS_PATH,
+        "WebSocket",
+        "WebSocket command channel",
+        ControlResponse::schema(),
+    );
+    ws.description = "With WebSocket upgrade headers and a valid API key (x-api-key, Authorization: Bearer or ?api_key= when authentication is enabled), switches protocols (101). Text frames are tagged commands or Subscribe/Unsubscribe for the keyspace, routes, calibration and stats topics. Without upgrade headers, describes the channel.".to_string();
+    ws.error = Some(ControlErrorBody::schema());
+    api.route(
+        ws,
+        get(move |principal, upgrade| ws::handler(state, principal, upgrade)),
+    )
+}
+
+/// Router with authentication, body limits, error fallbacks and request counting
+///
+/// Handlers of `api` can extract the caller as `Extension<Principal>`.
+pub fn into_router(api: HttpApi, state: Arc<ControlPlaneState>, max_body_bytes: usize) -> Router {
+    let stats = state.stats.clone();
+    api.into_router()
+        .fallback(not_found)
+        .method_not_allowed_fallback(method_not_allowed)
+        .layer(DefaultBodyLimit::max(max_body_bytes))
+        .layer(middleware::from_fn(move |request: Request, next: Next| {
+            authenticate(state.clone(), request, next)
+        }))
+        .layer(middleware::from_fn(move |request: Request, next: Next| {
+            let stats = stats.clone();
+            async move {
+                stats.write().await.http_requests += 1;
+                next.run(request).await
+            }
+        }))
+}
+
+/// Control-plane HTTP router
+pub fn router(state: Arc<ControlPlaneState>, max_body_bytes: usize) -> Router {
+    into_router(api(state.clone()), state, max_body_bytes)
+}
@@ -0,0 +1,274 @@
+//! VXLAN Transport (RFC 7348)
+//!
+//! Each UDP datagram is an 8-byte VXLAN header followed by a JSON
+//! [`ControlCommand`]:
+//!
+//! ```text
+//! |R|R|R|R|I|R|R|R|            Reserved                           |
+//! |                VXLAN Network Identifier (VNI) |   Reserved    |
+//! ```
+//!
+//! The I flag must be set and every reserved bit must be zero. The VNI selects
+//! the command's [`Namespace`]; the reply carries the same VNI followed by the
+//! JSON [`ControlResponse`]. Frames with a bad header are counted and dropped,
+//! frames with a bad payload are counted and answered with an error.
+//!
+//! With authentication enabled the command is preceded by a signature block:
+//!
+//! ```text
+//! | "SXA1" | key id (8) | timestamp (8, Unix seconds, BE) | HMAC-SHA256 (32) | JSON |
+//! ```
+//!
+//! The key id is the first 8 bytes of SHA-256(API key) and must name a
+//! registered key exactly. The MAC covers the VXLAN header, magic, key id,
+//! timestamp and JSON, keyed by SHA-256("symmetrix-vxlan-secret\0" ‖ API key),
+//! which the stored key hash does not reveal. Timestamps must be within the
+//! allowed clock skew, and each MAC is accepted once while it is, so a
+//! captured frame cannot be replayed. Frames that fail authentication are
+//! counted and dropped without a reply; see [`VxlanHeader::encapsulate_signed`].
+//!
+//! Tenant VNIs are open to admins and to keys granted them with
+//! [`ControlAccess::grant_vnis`](super::auth::ControlAccess::grant_vnis); a
+//! frame for any other VNI is answered with a denial.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::net::UdpSocket;
+use tracing::{debug, error, info, warn};
+
+use super::auth::{AccessError, Principal, Transport};
+use super::{ControlCommand, ControlPlaneState, ControlResponse, Namespace};
+use crate::grpc::auth::AuthManager;
+
+/// VXLAN standard port
+pub const VXLAN_PORT: u16 = 4789;
+/// VXLAN header size
+pub const VXLAN_HEADER_SIZE: usize = 8;
+/// I flag: the VNI is valid
+pub const VXLAN_FLAG_VNI: u8 = 0x08;
+/// Reserved VNI for control (global namespace)
+pub const VXLAN_VNI_CONTROL_PLANE: u32 = 0xFFFFFF;
+/// Largest VNI (24 bits)
+pub const VXLAN_VNI_MAX: u32 = 0xFFFFFF;
+/// First bytes of a signature block
+pub const VXLAN_AUTH_MAGIC: [u8; 4] = *b"SXA1";
+/// Key id size
+pub const VXLAN_KEY_ID_SIZE: usize = 8;
+/// Signature block size (magic, key id, timestamp, MAC)
+pub const VXLAN_AUTH_SIZE: usize = 4 + VXLAN_KEY_ID_SIZE + 8 + 32;
+
+/// VXLAN header errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum VxlanError {
+    #[error("Frame of {0} bytes is shorter than the VXLAN header")]
+    TooShort(usize),
+    #[error("I flag not set (flags {0:#04x})")]
+    MissingVniFlag(u8),
+    #[error("Reserved bits set in VXLAN header")]
+    ReservedBits,
+    #[error("VNI {0} does not fit in 24 bits")]
+    VniOutOfRange(u32),
+}
+
+/// VXLAN header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VxlanHeader {
+    pub vni: u32,
+}
+
+impl VxlanHeader {
+    pub fn new(vni: u32) -> Result<Self, VxlanError> {
+        if vni > VXLAN_VNI_MAX {
+            return Err(VxlanError::VniOutOfRange(vni));
+        }
+        Ok(Self { vni })
+    }
+
+    /// Validate the header of `frame`, returning it and the payload
+    pub fn parse(frame: &[u8]) -> Result<(Self, &[u8]), VxlanError> {
+        if frame.len() < VXLAN_HEADER_SIZE {
+            return Err(VxlanError::TooShort(frame.len()));
+        }
+        let flags = frame[0];
+        if flags & VXLAN_FLAG_VNI == 0 {
+            return Err(VxlanError::MissingVniFlag(flags));
+        }
+        if flags & !VXLAN_FLAG_VNI != 0 || frame[1..4] != [0, 0, 0] || frame[7] != 0 {
+            return Err(VxlanError::ReservedBits);
+        }
+        let vni = u32::from_be_bytes([0, frame[4], frame[5], frame[6]]);
+        Ok((Self { vni }, &frame[VXLAN_HEADER_SIZE..]))
+    }
+
+    /// Encoded header
+    pub fn to_bytes(self) -> [u8; VXLAN_HEADER_SIZE] {
+        let vni = self.vni.to_be_bytes();
+        [VXLAN_FLAG_VNI, 0, 0, 0, vni[1], vni[2], vni[3], 0]
+    }
+
+    /// Header followed by `payload`
+    pub fn encapsulate(self, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(VXLAN_HEADER_SIZE + payload.len());
+        frame.extend_from_slice(&self.to_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    /// Header, signature block for `api_key` at `timestamp`, then `payload`
+    pub fn encapsulate_signed(self, api_key: &str, timestamp: u64, payload: &[u8]) -> Vec<u8> {
+        let key_id = AuthManager::vxlan_key_id(api_key);
+
+        let mut frame = Vec::with_capacity(VXLAN_HEADER_SIZE + VXLAN_AUTH_SIZE + payload.len());
+        frame.extend_from_slice(&self.to_bytes());
+        frame.extend_from_slice(&VXLAN_AUTH_MAGIC);
+        frame.extend_from_slice(&key_id);
+        frame.extend_from_slice(&timestamp.to_be_bytes());
+        let mac = frame_mac(&AuthManager::vxlan_secret(api_key), &frame, payload)
+            .finalize()
+            .into_bytes();
+        frame.extend_from_slice(&mac);
+        frame.extend_from_slice(payload);
+        frame
+    }
+}
+
+/// MAC over the signed prefix of a frame and its payload
+fn frame_mac(secret: &[u8; 32], prefix: &[u8], payload: &[u8]) -> Hmac<Sha256> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(prefix);
+    mac.update(payload);
+    mac
+}
+
+/// Check the signature block of `frame`, returning the caller and the command
+async fn authenticate<'a>(
+    state: &ControlPlaneState,
+    frame: &'a [u8],
+) -> Result<(Principal, &'a [u8]), AccessError> {
+    let block = &frame[VXLAN_HEADER_SIZE..];
+    let signed = block.len() >= VXLAN_AUTH_SIZE && block[..4] == VXLAN_AUTH_MAGIC;
+    if !state.access.is_enabled() {
+        let payload = if signed { &block[VXLAN_AUTH_SIZE..] } else { block };
+        return Ok((state.access.authenticate(None).await?, payload));
+    }
+    if !signed {
+        return Err(AccessError::MissingCredentials);
+    }
+
+    let key_id = &block[4..4 + VXLAN_KEY_ID_SIZE];
+    let timestamp = u64::from_be_bytes(block[12..20].try_into().unwrap());
+    let (prefix, rest) = frame.split_at(VXLAN_HEADER_SIZE + 20);
+    let (mac, payload) = rest.split_at(32);
+
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    if now.abs_diff(timestamp) > state.access.max_clock_skew_secs() {
+        return Err(AccessError::StaleSignature(timestamp));
+    }
+    let key = state
+        .access
+        .vxlan_key(key_id)
+        .await
+        .ok_or_else(|| AccessError::InvalidCredentials("Unknown VXLAN key id".to_string()))?;
+    frame_mac(&key.secret, prefix, payload)
+        .verify_slice(mac)
+        .map_err(|_| AccessError::BadSignature)?;
+    state.access.check_replay(timestamp, mac.try_into().unwrap(), now)?;
+    Ok((state.access.authenticate_hash(&key.key_hash).await?, payload))
+}
+
+/// Run one frame, returning the encapsulated reply (`None` when dropped)
+///
+/// `session` is the caller of an already authenticated channel (WebSocket
+/// binary frames); such frames carry no signature block.
+pub async fn handle_frame(state: &ControlPlaneState, frame: &[u8], session: Option<&Principal>) -> Option<Vec<u8>> {
+    state.stats.write().await.vxlan_packets += 1;
+
+    let header = match VxlanHeader::parse(frame) {
+        Ok((header, _)) => header,
+        Err(e) => {
+            debug!("Dropping VXLAN frame: {}", e);
+            state.stats.write().await.vxlan_malformed += 1;
+            return None;
+        }
+    };
+
+    let (principal, payload) = match session {
+        Some(principal) => (principal.clone(), &frame[VXLAN_HEADER_SIZE..]),
+        None => match authenticate(state, frame).await {
+            Ok(authenticated) => authenticated,
+            Err(e) => {
+                debug!("Dropping unauthenticated VXLAN frame (VNI {}): {}", header.vni, e);
+                state.stats.write().await.auth_failures += 1;
+                return None;
+            }
+        },
+    };
+
+    let namespace = Namespace::from_vni(header.vni);
+    let authorized = state.access.authorize_vni(&principal, Transport::Vxlan, namespace).await;
+    let response = match (authorized, serde_json::from_slice::<ControlCommand>(payload)) {
+        (Err(e), _) => {
+            state.stats.write().await.permission_denied += 1;
+            ControlResponse {
+                success: false,
+                message: e.to_string(),
+                data: None,
+                latency_ns: 0,
+            }
+        }
+        (Ok(()), Ok(command)) => {
+            state
+                .execute_as(&principal, Transport::Vxlan, namespace, command)
+                .await
+                .1
+        }
+        (Ok(()), Err(e)) => {
+            state.stats.write().await.vxlan_malformed += 1;
+            ControlResponse {
+                success: false,
+                message: format!("Invalid command: {}", e),
+                data: None,
+                latency_ns: 0,
+            }
+        }
+    };
+
+    let body = serde_json::to_vec(&response).unwrap_or_default();
+    Some(header.encapsulate(&body))
+}
+
+/// Answer VXLAN frames on `socket` until it fails
+pub async fn serve(socket: UdpSocket, state: Arc<ControlPlaneState>) {
+    let socket = Arc::new(socket);
+    if let Ok(addr) = socket.local_addr() {
+        info!("📡 VXLAN server listening on {}", addr);
+    }
+
+    let mut buf = vec![0u8; 65535];
+    loop {
+        match socket.recv_from(&mut buf).await {
+            Ok((len, src)) => {
+                let frame = buf[..len].to_vec();
+                let socket = socket.clone();
+                let state = state.clone();
+                tokio::spawn(async move {
+                    reply(&socket, &state, &frame, src).await;
+                });
+            }
+            Err(e) => {
+                error!("Error receiving VXLAN packet: {}", e);
+            }
+        }
+    }
+}
+
+async fn reply(socket: &UdpSocket, state: &ControlPlaneState, frame: &[u8], src: SocketAddr) {
+    if let Some(reply) = handle_frame(state, frame, None).await {
+        if let Err(e) = socket.send_to(&reply, src).await {
+            warn!("Failed to send VXLAN reply to {}: {}", src, e);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Control Plane WebSocket Channel
+//!
+//! `GET /ws` with upgrade headers opens the channel. When authentication is
+//! enabled the API key goes in `x-api-key`, `Authorization: Bearer <key>` or
+//! the `api_key` query parameter; a bad key fails the upgrade with 401/403/429.
+//! Commands run with the role of that key.
+//!
+//! Text frames carry tagged commands:
+//!
+//! ```text
+//! {"cmd": "CacheGet", "data": {"key": "k"}}            → ControlResponse
+//! {"cmd": "Subscribe", "data": {"topics": ["keyspace"]}} → {"type": "subscribed", ...}
+//! {"cmd": "Unsubscribe", "data": {"topics": []}}         → all topics off
+//! ```
+//!
+//! Subscribing needs the topic's permission (see [`RolePolicy::required_for_topic`]);
+//! a request naming a topic the caller's role lacks subscribes to nothing.
+//! The channel runs in the global namespace and only forwards its events, so
+//! tenant keys and routes never reach it.
+//!
+//! Events arrive as `{"type": "event", "topic": ..., "data": ..., "timestamp": ...}`.
+//! Each connection reads events from a bounded buffer ([`EVENT_BUFFER`]); a
+//! consumer that falls behind loses the oldest events and is told how many
+//! with `{"type": "lagged", "missed": n}`. Binary frames are unsigned VXLAN
+//! frames, run as the connection's caller.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::ws::rejection::WebSocketUpgradeRejection;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use super::auth::{Principal, RolePolicy, Transport};
+use super::{vxlan, ControlCommand, ControlPlaneState, ControlResponse, Namespace, Topic, EVENT_BUFFER};
+
+/// Path of the channel
+pub const WS_PATH: &str = "/ws";
+
+/// Per-connection message limits (0 = unlimited)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WsLimits {
+    /// Sustained command frames per second
+    pub messages_per_second: u32,
+    /// Command frames accepted in a burst
+    pub burst: u32,
+}
+
+impl Default for WsLimits {
+    fn default() -> Self {
+        Self {
+            messages_per_second: 100,
+            burst: 200,
+        }
+    }
+}
+
+/// Token bucket enforcing [`WsLimits`]
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: WsLimits,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(limits: WsLimits) -> Self {
+        Self {
+            limits,
+            tokens: limits.burst.max(1) as f64,
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Take a token if one is available
+    pub fn try_acquire(&mut self) -> bool {
+        if self.limits.messages_per_second == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.refilled = now;
+        self.tokens = (self.tokens + elapsed * self.limits.messages_per_second as f64)
+            .min(self.limits.burst.max(1) as f64);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Commands handled by the channel itself
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "cmd", content = "data")]
+enum SessionCommand {
+    Subscribe { topics: Vec<Topic> },
+    /// An empty list unsubscribes from everything
+    Unsubscribe { topics: Vec<Topic> },
+}
+
+const SESSION_COMMANDS: [&str; 2] = ["Subscribe", "Unsubscribe"];
+
+/// `GET /ws`; the caller was authenticated by the HTTP layer
+pub async fn handler(
+    state: Arc<ControlPlaneState>,
+    Extension(principal): Extension<Principal>,
+    upgrade: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+    match upgrade {
+        Ok(upgrade) => upgrade.on_upgrade(move |socket| session(socket, state, principal)),
+        Err(_) => Json(info()).into_response(),
+    }
+}
+
+/// `GET /ws` without an upgrade: how to use the channel
+pub fn info() -> ControlResponse {
+    ControlResponse {
+        success: true,
+        message: "WebSocket endpoint - use WebSocket protocol to connect".to_string(),
+        data: Some(json!({
+            "endpoint": WS_PATH,
+            "protocol": "wss",
+            "url": "wss://vxlan-control-plane.onrender.com/ws",
+            "usage": "Connect with WebSocket client, send JSON commands",
+            "example_command": {"cmd": "Health"},
+            "supported_commands": ControlCommand::NAMES,
+            "session_commands": SESSION_COMMANDS,
+            "topics": Topic::ALL,
+        })),
+        latency_ns: 0,
+    }
+}
+
+fn error_response(message: String) -> Value {
+    json!(ControlResponse {
+        success: false,
+        message,
+        data: None,
+        latency_ns: 0,
+    })
+}
+
+/// One authenticated connection
+async fn session(mut socket: WebSocket, state: Arc<ControlPlaneState>, principal: Principal) {
+    let org_id = principal.id.clone();
+    let welcome = json!({
+        "type": "welcome",
+        "message": "SYMMETRIX Control Plane - WebSocket Connected",
+        "version": crate::VERSION,
+        "org_id": org_id,
+        "role": principal.role,
+        "capabilities": ["qagml", "qanban", "uao-qtcam", "cache", "cascade"],
+        "topics": Topic::ALL,
+        "event_buffer": EVENT_BUFFER,
+    });
+    if socket.send(Message::Text(welcome.to_string())).await.is_err() {
+        return;
+    }
+    info!("✅ WebSocket connection established (org: {})", org_id);
+
+    let namespace = Namespace::Global;
+    let mut events = state.subscribe();
+    let mut topics: BTreeSet<Topic> = BTreeSet::new();
+    let mut limiter = RateLimiter::new(state.ws_limits);
+
+    loop {
+        let reply = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    debug!("WebSocket message: {}", text);
+                    let reply = if limiter.try_acquire() {
+                        let was_idle = topics.is_empty();
+                        let reply = handle_text(&state, &principal, &mut topics, &text).await;
+                        if was_idle && !topics.is_empty() {
+                            // Start from now, not from events queued while idle
+                            events = events.resubscribe();
+                        }
+                        reply
+                    } else {
+                        error_response(format!(
+                            "Rate limit exceeded ({} messages/s)",
+                            state.ws_limits.messages_per_second
+                        ))
+                    };
+                    Message::Text(reply.to_string())
+                }
+                Some(Ok(Message::Binary(frame))) => {
+                    if !limiter.try_acquire() {
+                        continue;
+                    }
+                    match vxlan::handle_frame(&state, &frame, Some(&principal)).await {
+                        Some(reply) => Message::Binary(reply),
+                        None => continue,
+                    }
+                }
+                Some(Ok(Message::Ping(data))) => Message::Pong(data),
+                Some(Ok(Message::Close(_))) | None => {
+                    info!("WebSocket connection closed by peer");
+                    break;
+                }
+                Some(Err(e)) => {
+                    warn!("WebSocket error: {}", e);
+                    break;
+                }
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv(), if !topics.is_empty() => match event {
+                Ok(event) if topics.contains(&event.topic) && event.namespace == namespace => Message::Text(
+                    json!({
+                        "type": "event",
+                        "topic": event.topic,
+                        "data": event.data,
+                        "timestamp": event.timestamp,
+                    })
+                    .to_string(),
+                ),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => {
+                    debug!("WebSocket consumer lagged by {} events", missed);
+                    Message::Text(json!({ "type": "lagged", "missed": missed }).to_string())
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        // Awaiting the send holds back further reads for slow consumers
+        if socket.send(reply).await.is_err() {
+            break;
+        }
+    }
+    info!("WebSocket connection ended (org: {})", org_id);
+}
+
+async fn handle_text(
+    state: &ControlPlaneState,
+    principal: &Principal,
+    topics: &mut BTreeSet<Topic>,
+    text: &str,
+) -> Value {
+    let is_session = serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|v| v["cmd"].as_str().map(|cmd| SESSION_COMMANDS.contains(&cmd)))
+        .unwrap_or(false);
+
+    if is_session {
+        return match serde_json::from_str::<SessionCommand>(text) {
+            Ok(SessionCommand::Subscribe { topics: requested }) => {
+                for topic in &requested {
+                    let permission = RolePolicy::required_for_topic(*topic);
+                    let action = format!("Subscribe {:?}", topic);
+                    if let Err(e) = state.access.authorize_action(
+                        principal,
+                        Transport::WebSocket,
+                        Namespace::Global,
+                        permission,
+                        &action,
+                        false,
+                    ) {
+                        state.stats.write().await.permission_denied += 1;
+                        return error_response(e.to_string());
+                    }
+                }
+                topics.extend(requested);
+                json!({ "type": "subscribed", "topics": topics })
+            }
+            Ok(SessionCommand::Unsubscribe { topics: requested }) => {
+                if requested.is_empty() {
+                    topics.clear();
+                } else {
+                    requested.iter().for_each(|topic| {
+                        topics.remove(topic);
+                    });
+                }
+                json!({ "type": "subscribed", "topics": topics })
+            }
+            Err(e) => error_response(format!("Invalid subscription: {}", e)),
+        };
+    }
+
+    match serde_json::from_str::<ControlCommand>(text) {
+        Ok(command) => json!(
+            state
+                .execute_as(principal, Transport::WebSocket, Namespace::Global, command)
+                .await
+                .1
+        ),
+        Err(e) => error_response(format!("Invalid command: {}", e)),
+    }
+}
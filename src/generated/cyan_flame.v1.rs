@@ -1,5 +1,6 @@
 // This file is @generated by prost-build.
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CalibrationSubscriptionRequest {
     #[prost(string, tag = "1")]
@@ -11,6 +12,7 @@ pub struct CalibrationSubscriptionRequest {
     pub api_key: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CalibrationMatrixUpdate {
     #[prost(message, optional, tag = "1")]
@@ -24,6 +26,7 @@ pub struct CalibrationMatrixUpdate {
     pub next_rotation_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetCalibrationMatrixRequest {
     #[prost(string, tag = "1")]
@@ -34,6 +37,7 @@ pub struct GetCalibrationMatrixRequest {
     pub api_key: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CalibrationMatrix {
     /// 64
@@ -44,6 +48,7 @@ pub struct CalibrationMatrix {
     pub cols: u32,
     /// Compact binary representation (64x64 f64 = 32KB)
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub matrix_data: ::prost::alloc::vec::Vec<u8>,
     /// SHA-256 hash for verification
     #[prost(string, tag = "4")]
@@ -58,6 +63,7 @@ pub struct CalibrationMatrix {
     pub amplification: ::core::option::Option<AmplificationFactors>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct AmplificationFactors {
     /// 24,500×
@@ -71,6 +77,7 @@ pub struct AmplificationFactors {
     pub effective_multiplier: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MatrixVersionRequest {
     #[prost(string, tag = "1")]
@@ -81,6 +88,7 @@ pub struct MatrixVersionRequest {
     pub matrix_hash: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct MatrixVersionResponse {
     #[prost(bool, tag = "1")]
@@ -91,6 +99,7 @@ pub struct MatrixVersionResponse {
     pub latest_version: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ComputeCalibrationRequest {
     #[prost(string, tag = "1")]
@@ -105,6 +114,7 @@ pub struct ComputeCalibrationRequest {
     pub target_gpu_type: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ComputeCalibrationUpdate {
     #[prost(message, optional, tag = "1")]
@@ -117,6 +127,7 @@ pub struct ComputeCalibrationUpdate {
     pub next_rotation_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetComputeCalibrationRequest {
     #[prost(string, tag = "1")]
@@ -127,6 +138,7 @@ pub struct GetComputeCalibrationRequest {
     pub physical_gpu_type: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ComputeCalibrationMatrix {
     #[prost(uint64, tag = "1")]
@@ -158,6 +170,7 @@ pub struct ComputeCalibrationMatrix {
 }
 /// CARTF: Cache-Aware Recursive Tensor Folding (1.8×)
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CartfCoefficients {
     /// L1 cache block size
@@ -174,6 +187,7 @@ pub struct CartfCoefficients {
     pub recursion_depth: u32,
     /// 32x32 folding matrix (8KB)
     #[prost(bytes = "vec", tag = "5")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub folding_coefficients: ::prost::alloc::vec::Vec<u8>,
     /// 1.8×
     #[prost(double, tag = "6")]
@@ -181,6 +195,7 @@ pub struct CartfCoefficients {
 }
 /// GFCE: Galois Field GF(2^32) Compute Engine (14×)
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GaloisFieldConfig {
     /// x^32 + x^7 + x^3 + x^2 + 1 = 0x18D
@@ -188,15 +203,19 @@ pub struct GaloisFieldConfig {
     pub irreducible_polynomial: u64,
     /// Precomputed multiplication table
     #[prost(bytes = "vec", tag = "2")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub multiplication_lut: ::prost::alloc::vec::Vec<u8>,
     /// Multiplicative inverse table
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub inverse_lut: ::prost::alloc::vec::Vec<u8>,
     /// Discrete log table
     #[prost(bytes = "vec", tag = "4")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub log_table: ::prost::alloc::vec::Vec<u8>,
     /// Anti-log table
     #[prost(bytes = "vec", tag = "5")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub antilog_table: ::prost::alloc::vec::Vec<u8>,
     /// 14.0×
     #[prost(double, tag = "6")]
@@ -204,6 +223,7 @@ pub struct GaloisFieldConfig {
 }
 /// DBCG: De Bruijn Compute Graph (2.19×)
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DeBruijnConfig {
     /// De Bruijn graph order (k)
@@ -214,9 +234,11 @@ pub struct DeBruijnConfig {
     pub alphabet_size: u32,
     /// Eulerian path edge weights
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub eulerian_weights: ::prost::alloc::vec::Vec<u8>,
     /// Compact adjacency representation
     #[prost(bytes = "vec", tag = "4")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub adjacency_matrix: ::prost::alloc::vec::Vec<u8>,
     /// 2.19×
     #[prost(double, tag = "5")]
@@ -224,6 +246,7 @@ pub struct DeBruijnConfig {
 }
 /// CHN-CS: Continuous Hopfield Network Scheduler (1.45×)
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HopfieldConfig {
     /// Number of neurons
@@ -231,9 +254,11 @@ pub struct HopfieldConfig {
     pub neuron_count: u32,
     /// Symmetric weight matrix W
     #[prost(bytes = "vec", tag = "2")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub energy_matrix: ::prost::alloc::vec::Vec<u8>,
     /// Bias vector b
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub bias_vector: ::prost::alloc::vec::Vec<u8>,
     /// Temperature for annealing
     #[prost(double, tag = "4")]
@@ -247,6 +272,7 @@ pub struct HopfieldConfig {
 }
 /// PMCW: Particle Mesh Compute Wave (1.45×)
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PmeComputeWaveConfig {
     /// PME grid size (N³)
@@ -257,9 +283,11 @@ pub struct PmeComputeWaveConfig {
     pub spline_order: u32,
     /// Charge spreading coefficients
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub charge_spreading_coeffs: ::prost::alloc::vec::Vec<u8>,
     /// Precomputed twiddle factors
     #[prost(bytes = "vec", tag = "4")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub fft_twiddle_factors: ::prost::alloc::vec::Vec<u8>,
     /// Ewald splitting parameter (β)
     #[prost(double, tag = "5")]
@@ -269,6 +297,7 @@ pub struct PmeComputeWaveConfig {
     pub theoretical_factor: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct ComputeAmplificationFactors {
     /// CARTF contribution (1.8×)
@@ -297,6 +326,7 @@ pub struct ComputeAmplificationFactors {
     pub overhead_percent: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EngineConfigRequest {
     #[prost(string, tag = "1")]
@@ -310,6 +340,7 @@ pub struct EngineConfigRequest {
     pub enabled_engines: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EngineConfigResponse {
     #[prost(bool, tag = "1")]
@@ -330,6 +361,7 @@ pub struct EngineConfigResponse {
     pub combined_factor: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PcIeCalibrationRequest {
     #[prost(string, tag = "1")]
@@ -347,6 +379,7 @@ pub struct PcIeCalibrationRequest {
     pub current_bandwidth_gbs: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PcIeCalibrationUpdate {
     #[prost(message, optional, tag = "1")]
@@ -359,6 +392,7 @@ pub struct PcIeCalibrationUpdate {
     pub next_rotation_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetPcIeConfigRequest {
     #[prost(string, tag = "1")]
@@ -367,6 +401,7 @@ pub struct GetPcIeConfigRequest {
     pub api_key: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PcIeCalibrationMatrix {
     #[prost(uint64, tag = "1")]
@@ -391,6 +426,7 @@ pub struct PcIeCalibrationMatrix {
     pub amplification: ::core::option::Option<PcIeAmplificationFactors>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PrefetchConfig {
     /// How many transfers to prefetch
@@ -401,12 +437,14 @@ pub struct PrefetchConfig {
     pub prefetch_stride: u32,
     /// Hopfield energy weights for prediction
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub prediction_weights: ::prost::alloc::vec::Vec<u8>,
     /// Target prefetch hit rate (95%)
     #[prost(double, tag = "4")]
     pub hit_rate_target: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CoalescingConfig {
     /// Minimum transfers to batch
@@ -420,9 +458,11 @@ pub struct CoalescingConfig {
     pub timeout_us: u32,
     /// De Bruijn optimal scheduling
     #[prost(bytes = "vec", tag = "4")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub debruijn_schedule: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PcIeCompressionConfig {
     #[prost(bool, tag = "1")]
@@ -432,12 +472,14 @@ pub struct PcIeCompressionConfig {
     pub compression_level: u32,
     /// Galois field compression LUT
     #[prost(bytes = "vec", tag = "3")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub galois_lut: ::prost::alloc::vec::Vec<u8>,
     /// Expected compression ratio
     #[prost(double, tag = "4")]
     pub compression_ratio: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct PcIeAmplificationFactors {
     /// Prefetch hit rate improvement
@@ -460,6 +502,7 @@ pub struct PcIeAmplificationFactors {
     pub effective_bandwidth_gbs: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PcIeMetricsReport {
     #[prost(string, tag = "1")]
@@ -478,13 +521,20 @@ pub struct PcIeMetricsReport {
     pub coalescing_efficiency: f64,
     #[prost(double, tag = "8")]
     pub compression_ratio_achieved: f64,
+    /// PCIe device (BDF or GPU index); empty = agent default
+    #[prost(string, tag = "9")]
+    pub device_id: ::prost::alloc::string::String,
+    /// Transfer errors (AER/replay) since the previous report
+    #[prost(uint64, tag = "10")]
+    pub error_count: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PcIeOptimizationHint {
     #[prost(int64, tag = "1")]
     pub timestamp_ms: i64,
-    /// "prefetch", "coalescing", "compression"
+    /// "prefetch", "coalescing", "compression", "tuning"
     #[prost(string, tag = "2")]
     pub optimization_type: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
@@ -494,8 +544,12 @@ pub struct PcIeOptimizationHint {
         ::prost::alloc::string::String,
         f64,
     >,
+    /// Device the hint applies to
+    #[prost(string, tag = "5")]
+    pub device_id: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MemoryStatusUpdate {
     #[prost(string, tag = "1")]
@@ -508,6 +562,7 @@ pub struct MemoryStatusUpdate {
     pub effective_status: ::core::option::Option<EffectiveMemoryStatus>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GpuMemoryStatus {
     #[prost(string, tag = "1")]
@@ -524,6 +579,7 @@ pub struct GpuMemoryStatus {
     pub temperature_celsius: f32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct EffectiveMemoryStatus {
     #[prost(uint64, tag = "1")]
@@ -536,6 +592,7 @@ pub struct EffectiveMemoryStatus {
     pub amplification_factor: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TelemetryAck {
     #[prost(bool, tag = "1")]
@@ -546,6 +603,7 @@ pub struct TelemetryAck {
     pub message: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NetworkCapacityRequest {
     #[prost(string, tag = "1")]
@@ -554,6 +612,7 @@ pub struct NetworkCapacityRequest {
     pub include_all_nodes: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NetworkCapacityUpdate {
     #[prost(int64, tag = "1")]
@@ -568,6 +627,7 @@ pub struct NetworkCapacityUpdate {
     pub nodes: ::prost::alloc::vec::Vec<NodeCapacity>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct NetworkCapacityMetrics {
     #[prost(uint64, tag = "1")]
@@ -582,6 +642,7 @@ pub struct NetworkCapacityMetrics {
     pub network_utilization_percent: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NodeCapacity {
     #[prost(string, tag = "1")]
@@ -598,6 +659,7 @@ pub struct NodeCapacity {
     pub is_healthy: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HealthMetrics {
     #[prost(string, tag = "1")]
@@ -618,6 +680,7 @@ pub struct HealthMetrics {
     pub requests_processed: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct HealthAck {
     #[prost(bool, tag = "1")]
@@ -626,6 +689,7 @@ pub struct HealthAck {
     pub timestamp_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AllocationRequest {
     #[prost(string, tag = "1")]
@@ -642,6 +706,7 @@ pub struct AllocationRequest {
     pub duration_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AllocationResponse {
     #[prost(bool, tag = "1")]
@@ -658,6 +723,7 @@ pub struct AllocationResponse {
     pub error_message: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FreeMemoryRequest {
     #[prost(string, tag = "1")]
@@ -666,6 +732,7 @@ pub struct FreeMemoryRequest {
     pub allocation_id: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FreeMemoryResponse {
     #[prost(bool, tag = "1")]
@@ -676,6 +743,7 @@ pub struct FreeMemoryResponse {
     pub message: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MemoryRoutingRequest {
     #[prost(string, tag = "1")]
@@ -688,6 +756,7 @@ pub struct MemoryRoutingRequest {
     pub low_latency: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MemoryRoutingResponse {
     #[prost(bool, tag = "1")]
@@ -704,6 +773,7 @@ pub struct MemoryRoutingResponse {
     pub alternatives: ::prost::alloc::vec::Vec<AlternativeNode>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AlternativeNode {
     #[prost(string, tag = "1")]
@@ -716,6 +786,7 @@ pub struct AlternativeNode {
     pub latency_ms: f32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AllocationStatusRequest {
     #[prost(string, tag = "1")]
@@ -725,6 +796,7 @@ pub struct AllocationStatusRequest {
     pub allocation_id: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AllocationStatusResponse {
     #[prost(message, repeated, tag = "1")]
@@ -737,6 +809,7 @@ pub struct AllocationStatusResponse {
     pub remaining_quota_tb: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AllocationInfo {
     #[prost(string, tag = "1")]
@@ -753,12 +826,14 @@ pub struct AllocationInfo {
     pub node_id: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HealthCheckRequest {
     #[prost(string, tag = "1")]
     pub agent_id: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HealthCheckResponse {
     #[prost(bool, tag = "1")]
@@ -775,6 +850,7 @@ pub struct HealthCheckResponse {
     >,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpgradeRequest {
     #[prost(string, tag = "1")]
@@ -789,6 +865,7 @@ pub struct UpgradeRequest {
     pub force: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpgradeProgress {
     /// "DOWNLOADING", "VERIFYING", "INSTALLING", "RESTARTING", "COMPLETE"
@@ -804,12 +881,14 @@ pub struct UpgradeProgress {
     pub error: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SystemInfoRequest {
     #[prost(string, tag = "1")]
     pub agent_id: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SystemInfoResponse {
     #[prost(string, tag = "1")]
@@ -830,6 +909,7 @@ pub struct SystemInfoResponse {
     pub uptime_seconds: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GpuInfo {
     #[prost(string, tag = "1")]
@@ -846,6 +926,7 @@ pub struct GpuInfo {
     pub compute_capability_minor: u32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GpuRegistrationRequest {
     #[prost(string, tag = "1")]
@@ -863,6 +944,7 @@ pub struct GpuRegistrationRequest {
 }
 /// Custom target specifications for advanced users
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct CustomTargetSpecs {
     /// Target VRAM in GB
@@ -885,6 +967,7 @@ pub struct CustomTargetSpecs {
     pub require_sparsity: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DetectedGpuInfo {
     /// GPU identification
@@ -955,6 +1038,7 @@ pub struct DetectedGpuInfo {
     pub rocm_version: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GpuRegistrationResponse {
     #[prost(bool, tag = "1")]
@@ -985,9 +1069,18 @@ pub struct GpuRegistrationResponse {
     /// Cost estimation for this GPU transformation
     #[prost(message, optional, tag = "10")]
     pub cost_estimate: ::core::option::Option<CostEstimate>,
+    /// How the baseline GPU was identified
+    ///
+    /// 0.0 (unknown) to 1.0 (exact PCI ID match)
+    #[prost(double, tag = "11")]
+    pub detection_confidence: f64,
+    /// "pci_id", "name", "compute_capability", "none"
+    #[prost(string, tag = "12")]
+    pub detection_method: ::prost::alloc::string::String,
 }
 /// Cost estimation for GPU amplification
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CostEstimate {
     /// Estimated hourly cost in USD
@@ -1011,6 +1104,7 @@ pub struct CostEstimate {
 }
 /// GPU-based tier configuration
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GpuBasedTierConfig {
     /// "legacy", "workhorse", "inference_pro", etc.
@@ -1058,6 +1152,7 @@ pub struct GpuBasedTierConfig {
 }
 /// Amplification targets to reach H100-equivalent performance
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct AmplificationTargets {
     /// Memory bandwidth amplification needed
@@ -1103,6 +1198,7 @@ pub struct AmplificationTargets {
     pub has_nvlink: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GpuTierRequest {
     #[prost(string, tag = "1")]
@@ -1111,6 +1207,7 @@ pub struct GpuTierRequest {
     pub api_key: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GpuTierResponse {
     #[prost(enumeration = "BaselineGpuType", tag = "1")]
@@ -1121,18 +1218,21 @@ pub struct GpuTierResponse {
     pub amplification_targets: ::core::option::Option<AmplificationTargets>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListSupportedGpusRequest {
     #[prost(string, tag = "1")]
     pub api_key: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListSupportedGpusResponse {
     #[prost(message, repeated, tag = "1")]
     pub supported_gpus: ::prost::alloc::vec::Vec<SupportedGpuInfo>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SupportedGpuInfo {
     #[prost(enumeration = "BaselineGpuType", tag = "1")]
@@ -1157,6 +1257,192 @@ pub struct SupportedGpuInfo {
     pub bandwidth_amplification_to_h100: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReloadGpuCatalogRequest {
+    /// Admin API key
+    #[prost(string, tag = "1")]
+    pub api_key: ::prost::alloc::string::String,
+    /// Catalog file (empty = server's configured path)
+    #[prost(string, tag = "2")]
+    pub path: ::prost::alloc::string::String,
+    /// Inline catalog, takes precedence over path
+    #[prost(string, tag = "3")]
+    pub content: ::prost::alloc::string::String,
+    /// "toml" or "yaml" (inline content only)
+    #[prost(string, tag = "4")]
+    pub format: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReloadGpuCatalogResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    /// Parse/validation error, e.g. "gpus\[2\] (nvidia_a30): vram_gb must be greater than 0"
+    #[prost(string, tag = "2")]
+    pub error_message: ::prost::alloc::string::String,
+    /// Entries in the active catalog
+    #[prost(uint32, tag = "3")]
+    pub gpu_count: u32,
+    /// "builtin", file path, or "inline:<format>"
+    #[prost(string, tag = "4")]
+    pub source: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GpuInventoryRequest {
+    /// Admin API key
+    #[prost(string, tag = "1")]
+    pub api_key: ::prost::alloc::string::String,
+    /// Filter by model (GPU_UNKNOWN = all)
+    #[prost(enumeration = "BaselineGpuType", tag = "2")]
+    pub gpu_type: i32,
+    /// Filter by tier name (empty = all)
+    #[prost(string, tag = "3")]
+    pub tier_name: ::prost::alloc::string::String,
+    /// Filter by agent (empty = all)
+    #[prost(string, tag = "4")]
+    pub agent_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GpuInventoryRecord {
+    #[prost(string, tag = "1")]
+    pub agent_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "BaselineGpuType", tag = "2")]
+    pub gpu_type: i32,
+    /// Name reported by the agent
+    #[prost(string, tag = "3")]
+    pub gpu_name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "4")]
+    pub vram_gb: u64,
+    #[prost(string, tag = "5")]
+    pub driver_version: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub tier_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "7")]
+    pub pricing_tier: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub detection_method: ::prost::alloc::string::String,
+    #[prost(double, tag = "9")]
+    pub detection_confidence: f64,
+    #[prost(int64, tag = "10")]
+    pub registered_at_ms: i64,
+    #[prost(int64, tag = "11")]
+    pub last_seen_ms: i64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GpuModelInventory {
+    #[prost(enumeration = "BaselineGpuType", tag = "1")]
+    pub gpu_type: i32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub count: u64,
+    #[prost(uint64, tag = "4")]
+    pub total_vram_gb: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GpuInventoryResponse {
+    /// Ordered by agent ID
+    #[prost(message, repeated, tag = "1")]
+    pub registrations: ::prost::alloc::vec::Vec<GpuInventoryRecord>,
+    /// Totals per model
+    #[prost(message, repeated, tag = "2")]
+    pub models: ::prost::alloc::vec::Vec<GpuModelInventory>,
+    #[prost(uint64, tag = "3")]
+    pub total_count: u64,
+    #[prost(uint64, tag = "4")]
+    pub total_vram_gb: u64,
+}
+/// Usage for the caller's API key; the admin key may query any key
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UsageRequest {
+    #[prost(string, tag = "1")]
+    pub api_key: ::prost::alloc::string::String,
+    /// Inclusive; 0 with end 0 = current UTC month
+    #[prost(int64, tag = "2")]
+    pub period_start_ms: i64,
+    /// Exclusive
+    #[prost(int64, tag = "3")]
+    pub period_end_ms: i64,
+    /// Pricing tier filter; empty = all
+    #[prost(string, tag = "4")]
+    pub tier: ::prost::alloc::string::String,
+    /// Admin only: SHA-256 key hash filter; empty = all
+    #[prost(string, tag = "5")]
+    pub api_key_hash: ::prost::alloc::string::String,
+    /// "csv" or "json" to include an export; empty = none
+    #[prost(string, tag = "6")]
+    pub export_format: ::prost::alloc::string::String,
+}
+/// Immutable usage for one API key and tier over one closed hour
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HourlyUsage {
+    #[prost(string, tag = "1")]
+    pub api_key_hash: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub tier: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub hour_start_ms: i64,
+    /// Saturates at 2^64 - 1
+    #[prost(uint64, tag = "4")]
+    pub memory_byte_seconds: u64,
+    #[prost(double, tag = "5")]
+    pub memory_tb_hours: f64,
+    #[prost(double, tag = "6")]
+    pub calibration_stream_minutes: f64,
+    #[prost(uint64, tag = "7")]
+    pub api_calls: u64,
+    #[prost(double, tag = "8")]
+    pub memory_cost_usd: f64,
+    #[prost(double, tag = "9")]
+    pub stream_cost_usd: f64,
+    #[prost(double, tag = "10")]
+    pub api_cost_usd: f64,
+    #[prost(double, tag = "11")]
+    pub total_cost_usd: f64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UsageResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub error_message: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub period_start_ms: i64,
+    #[prost(int64, tag = "4")]
+    pub period_end_ms: i64,
+    /// Ordered by hour, then key and tier
+    #[prost(message, repeated, tag = "5")]
+    pub records: ::prost::alloc::vec::Vec<HourlyUsage>,
+    #[prost(double, tag = "6")]
+    pub memory_tb_hours: f64,
+    #[prost(double, tag = "7")]
+    pub calibration_stream_minutes: f64,
+    #[prost(uint64, tag = "8")]
+    pub api_calls: u64,
+    #[prost(double, tag = "9")]
+    pub total_cost_usd: f64,
+    /// CSV or JSON when export_format is set
+    #[prost(string, tag = "10")]
+    pub export: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CertificateRequest {
     #[prost(string, tag = "1")]
@@ -1177,6 +1463,7 @@ pub struct CertificateRequest {
     pub csr_pem: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CertificateResponse {
     #[prost(bool, tag = "1")]
@@ -1213,6 +1500,7 @@ pub struct CertificateResponse {
     pub bound_gpu_type: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RenewCertificateRequest {
     #[prost(string, tag = "1")]
@@ -1225,6 +1513,7 @@ pub struct RenewCertificateRequest {
     pub validity_days: u32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RevokeCertificateRequest {
     #[prost(string, tag = "1")]
@@ -1239,6 +1528,7 @@ pub struct RevokeCertificateRequest {
     pub reason: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RevokeCertificateResponse {
     #[prost(bool, tag = "1")]
@@ -1251,6 +1541,7 @@ pub struct RevokeCertificateResponse {
     pub revoked_at_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CertificateStatusRequest {
     #[prost(string, tag = "1")]
@@ -1263,6 +1554,7 @@ pub struct CertificateStatusRequest {
     pub fingerprint_sha256: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CertificateStatusResponse {
     #[prost(bool, tag = "1")]
@@ -1283,16 +1575,19 @@ pub struct CertificateStatusResponse {
     pub revocation_reason: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CrlRequest {
     #[prost(string, tag = "1")]
     pub api_key: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CrlResponse {
     /// DER-encoded CRL
     #[prost(bytes = "vec", tag = "1")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub crl_der: ::prost::alloc::vec::Vec<u8>,
     /// PEM-encoded CRL
     #[prost(string, tag = "2")]
@@ -1305,20 +1600,24 @@ pub struct CrlResponse {
     pub revoked_count: u32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OcspRequest {
     /// DER-encoded OCSP request
     #[prost(bytes = "vec", tag = "1")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub ocsp_request_der: ::prost::alloc::vec::Vec<u8>,
     /// Alternative: check by serial
     #[prost(string, tag = "2")]
     pub serial_number: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OcspResponse {
     /// DER-encoded OCSP response
     #[prost(bytes = "vec", tag = "1")]
+    #[serde(with = "crate::grpc::proto::base64_bytes")]
     pub ocsp_response_der: ::prost::alloc::vec::Vec<u8>,
     /// "good", "revoked", "unknown"
     #[prost(string, tag = "2")]
@@ -1329,6 +1628,7 @@ pub struct OcspResponse {
     pub next_update_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RestartRequest {
     #[prost(string, tag = "1")]
@@ -1339,6 +1639,7 @@ pub struct RestartRequest {
     pub delay_seconds: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RestartResponse {
     #[prost(bool, tag = "1")]
@@ -1349,6 +1650,7 @@ pub struct RestartResponse {
     pub restart_at_ms: i64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DashboardMetricsRequest {
     #[prost(string, tag = "1")]
@@ -1367,6 +1669,7 @@ pub struct DashboardMetricsRequest {
     pub include_cert_stats: bool,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DashboardMetricsUpdate {
     #[prost(int64, tag = "1")]
@@ -1423,6 +1726,7 @@ pub struct DashboardMetricsUpdate {
     pub top_agents: ::prost::alloc::vec::Vec<ConnectedAgentSummary>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ConnectedAgentSummary {
     #[prost(string, tag = "1")]
@@ -1447,6 +1751,7 @@ pub struct ConnectedAgentSummary {
     pub hourly_rate_usd: f64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ConnectedAgentsRequest {
     #[prost(string, tag = "1")]
@@ -1462,6 +1767,7 @@ pub struct ConnectedAgentsRequest {
     pub filter_gpu_type: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ConnectedAgentsResponse {
     #[prost(bool, tag = "1")]
@@ -1474,12 +1780,14 @@ pub struct ConnectedAgentsResponse {
     pub agents: ::prost::alloc::vec::Vec<ConnectedAgentSummary>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SystemSummaryRequest {
     #[prost(string, tag = "1")]
     pub api_key: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SystemSummaryResponse {
     #[prost(bool, tag = "1")]
@@ -3067,6 +3375,90 @@ pub mod gpu_detection_service_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Admin: reload the GPU catalog (specs, tiers, detection aliases)
+        pub async fn reload_gpu_catalog(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReloadGpuCatalogRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReloadGpuCatalogResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/cyan_flame.v1.GpuDetectionService/ReloadGpuCatalog",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "cyan_flame.v1.GpuDetectionService",
+                        "ReloadGpuCatalog",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Admin: registered GPUs with per-model counts and VRAM totals
+        pub async fn get_gpu_inventory(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GpuInventoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GpuInventoryResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/cyan_flame.v1.GpuDetectionService/GetGpuInventory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "cyan_flame.v1.GpuDetectionService",
+                        "GetGpuInventory",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Metered usage (memory, calibration streams, API calls) for a billing period
+        pub async fn get_usage(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UsageRequest>,
+        ) -> std::result::Result<tonic::Response<super::UsageResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/cyan_flame.v1.GpuDetectionService/GetUsage",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("cyan_flame.v1.GpuDetectionService", "GetUsage"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated client implementations.
@@ -5514,6 +5906,27 @@ pub mod gpu_detection_service_server {
             tonic::Response<super::ListSupportedGpusResponse>,
             tonic::Status,
         >;
+        /// Admin: reload the GPU catalog (specs, tiers, detection aliases)
+        async fn reload_gpu_catalog(
+            &self,
+            request: tonic::Request<super::ReloadGpuCatalogRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ReloadGpuCatalogResponse>,
+            tonic::Status,
+        >;
+        /// Admin: registered GPUs with per-model counts and VRAM totals
+        async fn get_gpu_inventory(
+            &self,
+            request: tonic::Request<super::GpuInventoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GpuInventoryResponse>,
+            tonic::Status,
+        >;
+        /// Metered usage (memory, calibration streams, API calls) for a billing period
+        async fn get_usage(
+            &self,
+            request: tonic::Request<super::UsageRequest>,
+        ) -> std::result::Result<tonic::Response<super::UsageResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct GpuDetectionServiceServer<T> {
@@ -5735,6 +6148,149 @@ pub mod gpu_detection_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/cyan_flame.v1.GpuDetectionService/ReloadGpuCatalog" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReloadGpuCatalogSvc<T: GpuDetectionService>(pub Arc<T>);
+                    impl<
+                        T: GpuDetectionService,
+                    > tonic::server::UnaryService<super::ReloadGpuCatalogRequest>
+                    for ReloadGpuCatalogSvc<T> {
+                        type Response = super::ReloadGpuCatalogResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReloadGpuCatalogRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GpuDetectionService>::reload_gpu_catalog(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReloadGpuCatalogSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/cyan_flame.v1.GpuDetectionService/GetGpuInventory" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetGpuInventorySvc<T: GpuDetectionService>(pub Arc<T>);
+                    impl<
+                        T: GpuDetectionService,
+                    > tonic::server::UnaryService<super::GpuInventoryRequest>
+                    for GetGpuInventorySvc<T> {
+                        type Response = super::GpuInventoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GpuInventoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GpuDetectionService>::get_gpu_inventory(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetGpuInventorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/cyan_flame.v1.GpuDetectionService/GetUsage" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetUsageSvc<T: GpuDetectionService>(pub Arc<T>);
+                    impl<
+                        T: GpuDetectionService,
+                    > tonic::server::UnaryService<super::UsageRequest>
+                    for GetUsageSvc<T> {
+                        type Response = super::UsageResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UsageRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as GpuDetectionService>::get_usage(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetUsageSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -0,0 +1,69 @@
+//! Calibration Epochs
+//!
+//! Calibration material handed to weight servers is only valid for one
+//! rotation interval. Epoch `n` covers `[n * rotation, (n + 1) * rotation)`
+//! seconds since the Unix epoch; the previous epoch stays accepted so that
+//! clients are not cut off at the boundary.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// One rotation interval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CalibrationEpoch {
+    pub epoch: u64,
+    /// Unix seconds
+    pub issued_at: u64,
+    /// Unix seconds
+    pub expires_at: u64,
+    /// Hex fingerprint of the epoch's calibration seed
+    pub fingerprint: String,
+}
+
+/// Rotating calibration schedule
+#[derive(Debug, Clone)]
+pub struct CalibrationService {
+    rotation_secs: u64,
+}
+
+impl CalibrationService {
+    /// Rotate every `rotation_secs` seconds (at least 1)
+    pub fn new(rotation_secs: u64) -> Self {
+        Self {
+            rotation_secs: rotation_secs.max(1),
+        }
+    }
+
+    pub fn rotation_secs(&self) -> u64 {
+        self.rotation_secs
+    }
+
+    /// Epoch containing `unix_secs`
+    pub fn epoch_at(&self, unix_secs: u64) -> CalibrationEpoch {
+        let epoch = unix_secs / self.rotation_secs;
+        let fingerprint = Sha256::new()
+            .chain_update(b"gfef-calibration")
+            .chain_update(epoch.to_be_bytes())
+            .finalize()
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        CalibrationEpoch {
+            epoch,
+            issued_at: epoch * self.rotation_secs,
+            expires_at: (epoch + 1) * self.rotation_secs,
+            fingerprint,
+        }
+    }
+
+    pub fn current(&self) -> CalibrationEpoch {
+        self.epoch_at(chrono::Utc::now().timestamp().max(0) as u64)
+    }
+
+    /// Whether material of `epoch` is still accepted at `unix_secs`
+    pub fn is_valid_at(&self, epoch: u64, unix_secs: u64) -> bool {
+        let current = unix_secs / self.rotation_secs;
+        epoch == current || epoch + 1 == current
+    }
+}
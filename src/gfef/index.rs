@@ -0,0 +1,234 @@
+//! GFEF Index
+//!
+//! Serializable per-layer activation index. Uploads use the export format of
+//! the index generator:
+//!
+//! ```text
+//! {
+//!   "model": "Qwen3-MoE-Coder",          // optional, the upload names the model
+//!   "k_components": 32, "fft_bins": 16, "target_sparsity": 0.95,
+//!   "layers": [{
+//!     "layer_id": 0, "name": "mlp.0", "neurons": 2048,
+//!     "pc_shape": [2048, 32],            // input_dim × k, when components are omitted
+//!     "principal_components": [[...], ...],  // k rows of input_dim values
+//!     "signatures": [[...], ...]             // one row of k values per neuron
+//!   }]
+//! }
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::GfefError;
+
+/// Index generation parameters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IndexConfig {
+    /// Principal components kept per layer
+    pub k_components: u32,
+    /// FFT bins of the eigenmode folding
+    pub fft_bins: u32,
+    /// Fraction of neurons expected to stay inactive
+    pub target_sparsity: f32,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            k_components: 32,
+            fft_bins: 16,
+            target_sparsity: 0.95,
+        }
+    }
+}
+
+/// Activation index of one layer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerIndex {
+    pub layer_id: u32,
+    pub layer_name: String,
+    pub num_neurons: u32,
+    /// Width of the layer input
+    pub input_dim: u32,
+    pub k_components: u32,
+    /// `k_components` rows of `input_dim` values (may be empty)
+    pub principal_components: Vec<Vec<f32>>,
+    /// One row of `k_components` values per neuron (empty: metadata only)
+    pub signatures: Vec<Vec<f32>>,
+}
+
+impl LayerIndex {
+    /// Whether predictions can be served for this layer
+    pub fn has_signatures(&self) -> bool {
+        !self.signatures.is_empty()
+    }
+
+    fn validate(&self) -> Result<(), GfefError> {
+        let invalid = |message: String| {
+            Err(GfefError::InvalidIndex(format!("layer {}: {}", self.layer_id, message)))
+        };
+        let k = self.k_components as usize;
+        if !self.signatures.is_empty() {
+            if self.signatures.len() != self.num_neurons as usize {
+                return invalid(format!(
+                    "{} signatures for {} neurons",
+                    self.signatures.len(),
+                    self.num_neurons
+                ));
+            }
+            if let Some(row) = self.signatures.iter().find(|row| row.len() != k) {
+                return invalid(format!("signature of {} values, expected {}", row.len(), k));
+            }
+        }
+        if !self.principal_components.is_empty() {
+            if self.principal_components.len() != k {
+                return invalid(format!(
+                    "{} principal components, expected {}",
+                    self.principal_components.len(),
+                    k
+                ));
+            }
+            let input_dim = self.input_dim as usize;
+            if let Some(row) = self.principal_components.iter().find(|row| row.len() != input_dim) {
+                return invalid(format!("component of {} values, expected {}", row.len(), input_dim));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Activation index of a model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GFEFIndex {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub model_id: String,
+    pub model_name: String,
+    pub generated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub layers: Vec<LayerIndex>,
+    pub total_neurons: u64,
+    pub config: IndexConfig,
+}
+
+/// Layer entry of the export format
+#[derive(Debug, Deserialize)]
+struct LayerDocument {
+    #[serde(default)]
+    layer_id: u32,
+    #[serde(default, alias = "layer_name")]
+    name: String,
+    #[serde(alias = "num_neurons")]
+    neurons: u32,
+    #[serde(default)]
+    input_dim: Option<u32>,
+    #[serde(default)]
+    pc_shape: Option<Vec<u32>>,
+    #[serde(default)]
+    principal_components: Vec<Vec<f32>>,
+    #[serde(default)]
+    signatures: Vec<Vec<f32>>,
+}
+
+/// Export format of the index generator
+#[derive(Debug, Deserialize)]
+struct IndexDocument {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    k_components: Option<u32>,
+    #[serde(default)]
+    fft_bins: Option<u32>,
+    #[serde(default)]
+    target_sparsity: Option<f32>,
+    layers: Vec<LayerDocument>,
+}
+
+impl GFEFIndex {
+    /// Build an index for `model_id` from the export format
+    ///
+    /// An empty `model_id` takes the document's `model` name.
+    pub fn from_export(model_id: &str, json: &str) -> Result<Self, GfefError> {
+        let document: IndexDocument =
+            serde_json::from_str(json).map_err(|e| GfefError::InvalidIndex(e.to_string()))?;
+
+        let defaults = IndexConfig::default();
+        let config = IndexConfig {
+            k_components: document.k_components.unwrap_or(defaults.k_components),
+            fft_bins: document.fft_bins.unwrap_or(defaults.fft_bins),
+            target_sparsity: document.target_sparsity.unwrap_or(defaults.target_sparsity),
+        };
+
+        let layers = document
+            .layers
+            .into_iter()
+            .map(|layer| {
+                let k_components = layer
+                    .signatures
+                    .first()
+                    .map(|row| row.len() as u32)
+                    .unwrap_or(config.k_components);
+                let input_dim = layer
+                    .input_dim
+                    .or_else(|| layer.principal_components.first().map(|row| row.len() as u32))
+                    .or_else(|| layer.pc_shape.as_ref().and_then(|shape| shape.first().copied()))
+                    .unwrap_or(0);
+                LayerIndex {
+                    layer_id: layer.layer_id,
+                    layer_name: layer.name,
+                    num_neurons: layer.neurons,
+                    input_dim,
+                    k_components,
+                    principal_components: layer.principal_components,
+                    signatures: layer.signatures,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let model_id = match (model_id.is_empty(), document.model) {
+            (false, _) => model_id.to_string(),
+            (true, Some(model)) => model,
+            (true, None) => return Err(GfefError::InvalidIndex("Missing model name".to_string())),
+        };
+
+        let index = Self {
+            id: Uuid::new_v4(),
+            customer_id: Uuid::nil(),
+            model_name: model_id.clone(),
+            model_id,
+            generated_at: Utc::now(),
+            expires_at: None,
+            total_neurons: layers.iter().map(|l| l.num_neurons as u64).sum(),
+            layers,
+            config,
+        };
+        index.validate()?;
+        Ok(index)
+    }
+
+    /// Check layer ids and the shapes of every layer
+    pub fn validate(&self) -> Result<(), GfefError> {
+        if self.layers.is_empty() {
+            return Err(GfefError::InvalidIndex("Index has no layers".to_string()));
+        }
+        if !(0.0..1.0).contains(&self.config.target_sparsity) {
+            return Err(GfefError::InvalidIndex(format!(
+                "target_sparsity {} is outside [0, 1)",
+                self.config.target_sparsity
+            )));
+        }
+        let mut ids = std::collections::HashSet::new();
+        for layer in &self.layers {
+            if !ids.insert(layer.layer_id) {
+                return Err(GfefError::InvalidIndex(format!("Duplicate layer {}", layer.layer_id)));
+            }
+            layer.validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn layer(&self, layer_id: u32) -> Option<&LayerIndex> {
+        self.layers.iter().find(|l| l.layer_id == layer_id)
+    }
+}
@@ -0,0 +1,37 @@
+//! GFEF (Galois Field Eigenmode Folding) Activation Prediction
+//!
+//! The "Triple IP Lock" of the control plane:
+//! 1. [`index`]: per-layer activation index of a model, kept on the control plane
+//! 2. [`calibration`]: calibration epochs that rotate on a fixed interval
+//! 3. [`prediction`]: which neurons activate for an input, answered from the index
+//!
+//! An index holds, for every layer, the top `k` principal components of the
+//! layer's inputs and one signature per neuron in that `k`-dimensional space.
+//! A prediction projects the input onto the components and keeps the neurons
+//! whose signatures score highest, `1 - target_sparsity` of the layer as set
+//! in the index's [`IndexConfig`].
+
+pub mod calibration;
+pub mod index;
+pub mod prediction;
+pub mod subscription;
+
+pub use calibration::{CalibrationEpoch, CalibrationService};
+pub use index::{GFEFIndex, IndexConfig, LayerIndex};
+pub use prediction::{ActivationPredictor, Prediction, PredictorStats};
+pub use subscription::{Subscription, SubscriptionManager};
+
+/// GFEF errors
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum GfefError {
+    #[error("Invalid GFEF index: {0}")]
+    InvalidIndex(String),
+    #[error("No GFEF index loaded for model '{0}'")]
+    UnknownModel(String),
+    #[error("Model '{model_id}' has no layer {layer_id}")]
+    UnknownLayer { model_id: String, layer_id: u32 },
+    #[error("Layer {0} has no neuron signatures")]
+    NoSignatures(u32),
+    #[error("Input has {actual} values, layer {layer_id} expects {expected}")]
+    DimensionMismatch { layer_id: u32, expected: usize, actual: usize },
+}
@@ -0,0 +1,221 @@
+//! Activation Prediction Service
+//!
+//! Answers "which neurons of this layer activate for this input" from the
+//! registered [`GFEFIndex`] of the model. Inputs arrive either as an
+//! embedding (projected onto the layer's principal components) or, when the
+//! client only sends a hash of its input, as a point of the component space
+//! derived from that hash.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::info;
+use uuid::Uuid;
+
+use super::index::{GFEFIndex, LayerIndex};
+use super::GfefError;
+
+/// Predicted activations of one layer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Prediction {
+    pub index_id: Uuid,
+    pub model_id: String,
+    pub layer_id: u32,
+    /// Ascending neuron ids
+    pub active_neurons: Vec<u32>,
+    /// Fraction of the layer left inactive
+    pub sparsity: f32,
+    /// Score gap between the last kept and first dropped neuron, relative to
+    /// the layer's score range (1.0 = clean separation)
+    pub confidence: f32,
+}
+
+/// Predictor statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PredictorStats {
+    pub models_loaded: usize,
+    pub total_neurons: u64,
+    pub total_layers: usize,
+    pub target_sparsity: f32,
+    pub predictions_served: u64,
+}
+
+/// Registry of model indices and the prediction oracle
+#[derive(Debug)]
+pub struct ActivationPredictor {
+    target_sparsity: f32,
+    indices: HashMap<String, GFEFIndex>,
+    predictions: AtomicU64,
+}
+
+impl ActivationPredictor {
+    /// Predictor reporting `target_sparsity` as its default; each prediction
+    /// keeps `1 - target_sparsity` of the layer as configured in its index
+    pub fn new(target_sparsity: f32) -> Self {
+        Self {
+            target_sparsity: target_sparsity.clamp(0.0, 1.0),
+            indices: HashMap::new(),
+            predictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Register `index`, replacing any index of the same model
+    pub fn register_index(&mut self, index: GFEFIndex) -> Option<GFEFIndex> {
+        info!(
+            "🔐 GFEF index registered: {} ({} layers, {} neurons)",
+            index.model_id,
+            index.layers.len(),
+            index.total_neurons
+        );
+        self.indices.insert(index.model_id.clone(), index)
+    }
+
+    pub fn remove_index(&mut self, model_id: &str) -> Option<GFEFIndex> {
+        self.indices.remove(model_id)
+    }
+
+    pub fn index(&self, model_id: &str) -> Option<&GFEFIndex> {
+        self.indices.get(model_id)
+    }
+
+    /// Registered indices, ordered by model id
+    pub fn indices(&self) -> Vec<&GFEFIndex> {
+        let mut indices: Vec<_> = self.indices.values().collect();
+        indices.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        indices
+    }
+
+    pub fn target_sparsity(&self) -> f32 {
+        self.target_sparsity
+    }
+
+    pub fn stats(&self) -> PredictorStats {
+        PredictorStats {
+            models_loaded: self.indices.len(),
+            total_neurons: self.indices.values().map(|i| i.total_neurons).sum(),
+            total_layers: self.indices.values().map(|i| i.layers.len()).sum(),
+            target_sparsity: self.target_sparsity,
+            predictions_served: self.predictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Predict from a client-side hash of the input
+    pub fn predict(&self, model_id: &str, layer_id: u32, input_hash: &str) -> Result<Prediction, GfefError> {
+        let (index, layer) = self.layer(model_id, layer_id)?;
+        let point = hash_point(input_hash, layer.k_components as usize);
+        self.select(index, layer, &point)
+    }
+
+    /// Predict from the layer input itself
+    ///
+    /// The embedding has `input_dim` values when the layer carries principal
+    /// components, otherwise it is already a point of the component space.
+    pub fn predict_embedding(
+        &self,
+        model_id: &str,
+        layer_id: u32,
+        embedding: &[f32],
+    ) -> Result<Prediction, GfefError> {
+        let (index, layer) = self.layer(model_id, layer_id)?;
+        let point = if layer.principal_components.is_empty() {
+            if embedding.len() != layer.k_components as usize {
+                return Err(GfefError::DimensionMismatch {
+                    layer_id,
+                    expected: layer.k_components as usize,
+                    actual: embedding.len(),
+                });
+            }
+            embedding.to_vec()
+        } else {
+            if embedding.len() != layer.input_dim as usize {
+                return Err(GfefError::DimensionMismatch {
+                    layer_id,
+                    expected: layer.input_dim as usize,
+                    actual: embedding.len(),
+                });
+            }
+            layer
+                .principal_components
+                .iter()
+                .map(|component| dot(component, embedding))
+                .collect()
+        };
+        self.select(index, layer, &point)
+    }
+
+    fn layer(&self, model_id: &str, layer_id: u32) -> Result<(&GFEFIndex, &LayerIndex), GfefError> {
+        let index = self
+            .indices
+            .get(model_id)
+            .ok_or_else(|| GfefError::UnknownModel(model_id.to_string()))?;
+        let layer = index.layer(layer_id).ok_or_else(|| GfefError::UnknownLayer {
+            model_id: model_id.to_string(),
+            layer_id,
+        })?;
+        if !layer.has_signatures() {
+            return Err(GfefError::NoSignatures(layer_id));
+        }
+        Ok((index, layer))
+    }
+
+    /// Keep the highest-scoring neurons for `point`
+    fn select(&self, index: &GFEFIndex, layer: &LayerIndex, point: &[f32]) -> Result<Prediction, GfefError> {
+        let mut scored: Vec<(u32, f32)> = layer
+            .signatures
+            .iter()
+            .enumerate()
+            .map(|(neuron, signature)| (neuron as u32, dot(signature, point)))
+            .collect();
+        // Highest score first, lower neuron id on ties
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let total = scored.len();
+        let keep = ((total as f32 * (1.0 - index.config.target_sparsity)).ceil() as usize).clamp(1, total);
+        let confidence = match (scored.first(), scored.last()) {
+            (Some(top), Some(bottom)) if keep < total && top.1 > bottom.1 => {
+                ((scored[keep - 1].1 - scored[keep].1) / (top.1 - bottom.1)).clamp(0.0, 1.0)
+            }
+            _ => 1.0,
+        };
+
+        let mut active_neurons: Vec<u32> = scored[..keep].iter().map(|(neuron, _)| *neuron).collect();
+        active_neurons.sort_unstable();
+        self.predictions.fetch_add(1, Ordering::Relaxed);
+
+        Ok(Prediction {
+            index_id: index.id,
+            model_id: index.model_id.clone(),
+            layer_id: layer.layer_id,
+            active_neurons,
+            sparsity: 1.0 - keep as f32 / total as f32,
+            confidence,
+        })
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Deterministic point in `[-1, 1]^k` for an input hash
+fn hash_point(input_hash: &str, k: usize) -> Vec<f32> {
+    let mut point = Vec::with_capacity(k);
+    let mut block = 0u32;
+    while point.len() < k {
+        let digest = Sha256::new()
+            .chain_update(input_hash.as_bytes())
+            .chain_update(block.to_le_bytes())
+            .finalize();
+        for chunk in digest.chunks_exact(4) {
+            if point.len() == k {
+                break;
+            }
+            let value = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            point.push(value as f32 / u32::MAX as f32 * 2.0 - 1.0);
+        }
+        block += 1;
+    }
+    point
+}
@@ -0,0 +1,71 @@
+//! Prediction Subscriptions
+//!
+//! Which customers may query predictions for which models.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Access of one customer to one model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Subscription {
+    pub customer_id: String,
+    pub model_id: String,
+    pub tier: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Subscriptions by customer and model
+#[derive(Debug, Default)]
+pub struct SubscriptionManager {
+    subscriptions: HashMap<(String, String), Subscription>,
+}
+
+impl SubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grant `customer_id` access to `model_id`, replacing an earlier tier
+    pub fn subscribe(&mut self, customer_id: &str, model_id: &str, tier: &str) -> Subscription {
+        let subscription = Subscription {
+            customer_id: customer_id.to_string(),
+            model_id: model_id.to_string(),
+            tier: tier.to_string(),
+            created_at: Utc::now(),
+        };
+        self.subscriptions
+            .insert((customer_id.to_string(), model_id.to_string()), subscription.clone());
+        subscription
+    }
+
+    pub fn unsubscribe(&mut self, customer_id: &str, model_id: &str) -> Option<Subscription> {
+        self.subscriptions
+            .remove(&(customer_id.to_string(), model_id.to_string()))
+    }
+
+    pub fn is_subscribed(&self, customer_id: &str, model_id: &str) -> bool {
+        self.subscriptions
+            .contains_key(&(customer_id.to_string(), model_id.to_string()))
+    }
+
+    /// Subscriptions of `customer_id`, ordered by model
+    pub fn subscriptions(&self, customer_id: &str) -> Vec<&Subscription> {
+        let mut subscriptions: Vec<_> = self
+            .subscriptions
+            .values()
+            .filter(|s| s.customer_id == customer_id)
+            .collect();
+        subscriptions.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        subscriptions
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+}
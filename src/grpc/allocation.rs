@@ -5,14 +5,16 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tonic::{Request, Response, Status};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use super::auth::AuthManager;
 use super::proto::*;
 use super::AllocationService;
+use super::metering::UsageMeter;
 
 /// Allocation record
 #[derive(Debug, Clone)]
@@ -34,6 +36,8 @@ pub struct AllocationServiceImpl {
     agent_allocations: Arc<RwLock<HashMap<String, u64>>>,
     /// Maximum allocation per agent (TB)
     max_allocation_per_agent_tb: u64,
+    /// Usage meter for allocated memory
+    meter: Arc<UsageMeter>,
 }
 
 impl AllocationServiceImpl {
@@ -43,8 +47,15 @@ impl AllocationServiceImpl {
             allocations: Arc::new(RwLock::new(HashMap::new())),
             agent_allocations: Arc::new(RwLock::new(HashMap::new())),
             max_allocation_per_agent_tb: 1000, // 1 PB per agent max
+            meter: Arc::new(UsageMeter::new()),
         }
     }
+
+    /// Share a usage meter with the other services
+    pub fn with_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.meter = meter;
+        self
+    }
 }
 
 #[tonic::async_trait]
@@ -53,6 +64,7 @@ impl AllocationService for AllocationServiceImpl {
         &self,
         request: Request<AllocationRequest>,
     ) -> Result<Response<AllocationResponse>, Status> {
+        let api_key = AuthManager::caller_key(&request, &request.get_ref().api_key);
         let req = request.into_inner();
         let now_ms = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -97,6 +109,12 @@ impl AllocationService for AllocationServiceImpl {
             expires_at_ms,
         };
 
+        // Meter until freed or the lease expires
+        let lease = Duration::from_millis((expires_at_ms - now_ms).max(0) as u64);
+        if let Err(e) = self.meter.start_allocation(&allocation_id, &req.agent_id, &api_key, req.requested_tb, Some(lease)) {
+            return Err(Status::permission_denied(e));
+        }
+
         // Store allocation
         self.allocations.write().await.insert(allocation_id.clone(), record);
         *agent_allocs.entry(req.agent_id.clone()).or_insert(0) += req.requested_tb;
@@ -125,6 +143,8 @@ impl AllocationService for AllocationServiceImpl {
         let mut allocations = self.allocations.write().await;
 
         if let Some(record) = allocations.remove(&req.allocation_id) {
+            self.meter.end_allocation(&req.allocation_id);
+
             // Update agent's total allocation
             let mut agent_allocs = self.agent_allocations.write().await;
             if let Some(total) = agent_allocs.get_mut(&record.agent_id) {
@@ -27,6 +27,8 @@ use tracing::{debug, info, warn};
 use sha2::{Sha256, Digest};
 use chrono::{DateTime, Utc};
 
+use super::metering::UsageMeter;
+
 /// Tier configuration with amplification limits
 #[derive(Clone, Debug)]
 pub struct TierConfig {
@@ -132,11 +134,23 @@ pub struct ApiKeyEntry {
     pub allocated_memory_bytes: u64,
 }
 
+/// Key that signs the VXLAN frames of an API key
+#[derive(Clone, Debug)]
+pub struct VxlanKey {
+    /// Hash of the API key
+    pub key_hash: String,
+    /// MAC secret derived from the API key itself, so the stored hash cannot
+    /// sign frames; held in memory only
+    pub secret: [u8; 32],
+}
+
 /// Authentication Manager
 #[derive(Clone)]
 pub struct AuthManager {
     /// API keys indexed by key hash
     api_keys: Arc<RwLock<HashMap<String, ApiKeyEntry>>>,
+    /// VXLAN signing keys indexed by key id
+    vxlan_keys: Arc<RwLock<HashMap<[u8; 8], VxlanKey>>>,
     /// Enable authentication
     auth_enabled: bool,
 }
@@ -146,6 +160,7 @@ impl AuthManager {
     pub fn new(auth_enabled: bool) -> Self {
         let mut manager = Self {
             api_keys: Arc::new(RwLock::new(HashMap::new())),
+            vxlan_keys: Arc::new(RwLock::new(HashMap::new())),
             auth_enabled,
         };
         
@@ -166,9 +181,27 @@ impl AuthManager {
         hex::encode(hasher.finalize())
     }
 
+    /// Id naming an API key in signed VXLAN frames: the first 8 bytes of its hash
+    pub fn vxlan_key_id(key: &str) -> [u8; 8] {
+        let digest = Sha256::digest(key.as_bytes());
+        digest[..8].try_into().expect("SHA-256 is 32 bytes")
+    }
+
+    /// Secret signing the VXLAN frames of an API key
+    ///
+    /// Derived from the key under its own label, never from the stored hash.
+    pub fn vxlan_secret(key: &str) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(b"symmetrix-vxlan-secret\0")
+            .chain_update(key.as_bytes())
+            .finalize()
+            .into()
+    }
+
     /// Register a new API key with full tier configuration
     pub async fn register_key(&self, api_key: &str, tier: &str, org_id: &str) {
         let key_hash = Self::hash_key(api_key);
+        self.register_vxlan_key(api_key, &key_hash).await;
         let tier_config = TierConfig::from_name(tier);
         let rate_limit = tier_config.rate_limit;
 
@@ -191,6 +224,22 @@ impl AuthManager {
               org_id, tier, TierConfig::from_name(tier).amplification_factor);
     }
 
+    /// Index the VXLAN signing key of `api_key`; a key whose id is taken by
+    /// another key cannot sign frames
+    async fn register_vxlan_key(&self, api_key: &str, key_hash: &str) {
+        let mut keys = self.vxlan_keys.write().await;
+        let key_id = Self::vxlan_key_id(api_key);
+        if keys.get(&key_id).is_some_and(|key| key.key_hash != key_hash) {
+            warn!("⚠️ VXLAN key id {} is already taken; this API key cannot sign VXLAN frames",
+                  hex::encode(key_id));
+            return;
+        }
+        keys.insert(key_id, VxlanKey {
+            key_hash: key_hash.to_string(),
+            secret: Self::vxlan_secret(api_key),
+        });
+    }
+
     /// Register default test keys for each tier
     pub async fn register_default_keys(&self) {
         // Free tier test key
@@ -227,10 +276,14 @@ impl AuthManager {
             });
         }
 
-        let key_hash = Self::hash_key(api_key);
+        self.validate_hash(&Self::hash_key(api_key)).await
+    }
+
+    /// Validate by key hash, for callers that prove the key without sending it
+    pub async fn validate_hash(&self, key_hash: &str) -> Result<ApiKeyEntry, Status> {
         let mut keys = self.api_keys.write().await;
 
-        if let Some(entry) = keys.get_mut(&key_hash) {
+        if let Some(entry) = keys.get_mut(key_hash) {
             // Check if key is enabled
             if !entry.enabled {
                 warn!("🚫 Disabled API key attempted: org={}", entry.org_id);
@@ -266,11 +319,17 @@ impl AuthManager {
                    entry.org_id, entry.tier, entry.tier_config.amplification_factor);
             Ok(entry.clone())
         } else {
-            warn!("❌ Invalid API key attempted (key hash: {}...)", &key_hash[..8]);
+            warn!("❌ Invalid API key attempted (key hash: {}...)", key_hash.get(..8).unwrap_or(key_hash));
             Err(Status::unauthenticated("Invalid API key. Please check your API key or contact support."))
         }
     }
 
+    /// VXLAN signing key with exactly `key_id`
+    pub async fn vxlan_key(&self, key_id: &[u8]) -> Option<VxlanKey> {
+        let key_id: [u8; 8] = key_id.try_into().ok()?;
+        self.vxlan_keys.read().await.get(&key_id).cloned()
+    }
+
     /// Check if an allocation is allowed for the given tier
     pub async fn check_allocation_allowed(&self, api_key: &str, requested_bytes: u64) -> Result<(), Status> {
         let entry = self.validate_key(api_key).await?;
@@ -308,6 +367,12 @@ impl AuthManager {
         self.auth_enabled
     }
 
+    /// Key the caller authenticated with: the metadata key, else `body_key`
+    /// (only when no metadata key was sent, as with authentication disabled)
+    pub fn caller_key<T>(request: &Request<T>, body_key: &str) -> String {
+        Self::extract_api_key(request).unwrap_or_else(|| body_key.to_string())
+    }
+
     /// Extract API key from request metadata
     pub fn extract_api_key<T>(request: &Request<T>) -> Option<String> {
         request.metadata()
@@ -336,24 +401,64 @@ impl AuthManager {
 #[derive(Clone)]
 pub struct AuthInterceptor {
     auth_manager: Arc<AuthManager>,
+    /// Counts accepted requests per API key
+    meter: Option<Arc<UsageMeter>>,
 }
 
 impl AuthInterceptor {
     /// Create a new auth interceptor
     pub fn new(auth_manager: Arc<AuthManager>) -> Self {
-        Self { auth_manager }
+        Self { auth_manager, meter: None }
+    }
+
+    /// Meter accepted requests as API calls
+    pub fn with_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.meter = Some(meter);
+        self
+    }
+
+    fn record_api_call(&self, api_key: &str) {
+        if let Some(meter) = &self.meter {
+            meter.record_api_call(api_key);
+        }
+    }
+
+    /// Authenticate, rate-limit and meter a request from an async caller
+    ///
+    /// Unlike [`Interceptor::call`] this waits for the key table and counts
+    /// the request against the key's per-minute limit.
+    pub async fn admit<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let api_key = AuthManager::extract_api_key(request);
+        if self.auth_manager.is_auth_enabled() {
+            let key = api_key.as_deref().ok_or_else(|| {
+                warn!("🚫 Request without API key rejected");
+                Status::unauthenticated(
+                    "Missing API key. Include 'x-api-key' header or 'Authorization: Bearer <key>'."
+                )
+            })?;
+            self.auth_manager.validate_key(key).await?;
+        }
+        if let Some(key) = &api_key {
+            self.record_api_call(key);
+        }
+        Ok(())
     }
 }
 
 impl Interceptor for AuthInterceptor {
     fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let api_key = AuthManager::extract_api_key(&request);
+
         // If auth is disabled, allow all requests
         if !self.auth_manager.is_auth_enabled() {
+            if let Some(key) = &api_key {
+                self.record_api_call(key);
+            }
             return Ok(request);
         }
 
         // Extract API key from metadata
-        let api_key = AuthManager::extract_api_key(&request)
+        let api_key = api_key
             .ok_or_else(|| {
                 warn!("🚫 Request without API key rejected");
                 Status::unauthenticated(
@@ -390,16 +495,19 @@ impl Interceptor for AuthInterceptor {
                     }
                 }
                 Err(_) => {
-                    // Lock contention - allow request (fail open for availability)
-                    warn!("⚠️ Auth check skipped due to lock contention");
-                    Ok(())
+                    // Lock contention - the key cannot be checked, so refuse it
+                    warn!("⚠️ Request refused: API keys are being updated");
+                    Err(Status::unavailable("API keys are being updated, retry the request"))
                 }
             }
         };
 
         // Return based on the result
         match result {
-            Ok(()) => Ok(request),
+            Ok(()) => {
+                self.record_api_call(&api_key);
+                Ok(request)
+            }
             Err(status) => Err(status),
         }
     }
@@ -11,8 +11,10 @@ use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use tonic::{Request, Response, Status};
 use tracing::{debug, info, warn};
 
+use super::auth::AuthManager;
 use super::proto::*;
 use super::CalibrationService;
+use super::metering::UsageMeter;
 
 /// Memory amplification factor (24,500×)
 pub const MEMORY_AMPLIFICATION: f64 = 24_500.0;
@@ -31,6 +33,8 @@ pub struct CalibrationServiceImpl {
     matrix_broadcast: broadcast::Sender<CalibrationMatrixUpdate>,
     /// Current matrix version
     version: Arc<RwLock<u64>>,
+    /// Usage meter for subscription time
+    meter: Arc<UsageMeter>,
 }
 
 impl CalibrationServiceImpl {
@@ -43,9 +47,16 @@ impl CalibrationServiceImpl {
             current_matrix: Arc::new(RwLock::new(initial_matrix)),
             matrix_broadcast: tx,
             version: Arc::new(RwLock::new(1)),
+            meter: Arc::new(UsageMeter::new()),
         }
     }
 
+    /// Share a usage meter with the other services
+    pub fn with_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.meter = meter;
+        self
+    }
+
     /// Generate a new calibration matrix
     fn generate_calibration_matrix(version: u64) -> CalibrationMatrix {
         let now_ms = SystemTime::now()
@@ -187,16 +198,27 @@ impl CalibrationService for CalibrationServiceImpl {
         &self,
         request: Request<CalibrationSubscriptionRequest>,
     ) -> Result<Response<Self::SubscribeCalibrationMatrixStream>, Status> {
+        let api_key = AuthManager::caller_key(&request, &request.get_ref().api_key);
         let req = request.into_inner();
         info!("New calibration subscription from agent: {} (tier: {})", req.agent_id, req.subscription_tier);
 
         // Validate API key and subscription tier
         self.validate_api_key_and_tier(&req.api_key, &req.subscription_tier)?;
 
+        // Metered until the client drops the stream
+        let usage_guard = self
+            .meter
+            .start_stream(&req.agent_id, &api_key)
+            .map_err(Status::permission_denied)?;
+
         // Create receiver from broadcast channel
         let rx = self.matrix_broadcast.subscribe();
         let stream = BroadcastStream::new(rx)
             .filter_map(|result| result.ok())
+            .map(move |update| {
+                let _metered = &usage_guard;
+                update
+            })
             .map(Ok);
 
         Ok(Response::new(Box::pin(stream)))
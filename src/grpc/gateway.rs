@@ -0,0 +1,370 @@
+//! CYAN FLAME™ REST/JSON Gateway
+//!
+//! Maps the RPCs in `cyan_flame.proto` onto HTTP for web tooling and scripts:
+//!
+//! - `POST /v1/{Service}/{Method}` takes the request message as JSON and
+//!   returns the response message as JSON (missing fields use proto3 defaults,
+//!   `bytes` fields are base64)
+//! - Server-streaming RPCs reply with Server-Sent Events, one event per update;
+//!   they also accept `GET` with the request message as query parameters
+//! - `GET /v1` lists the routes
+//!
+//! Requests pass through the same `AuthInterceptor` as gRPC (`x-api-key` or
+//! `Authorization: Bearer <key>`) and count against the key's per-minute
+//! rate limit (429 once exceeded), and gRPC status codes map to HTTP status
+//! codes. Client-streaming RPCs (ReportPCIeMetrics, StreamMemoryStatus,
+//! ReportHealth) are gRPC-only.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tonic::metadata::MetadataMap;
+use tonic::{Code, Extensions, Status};
+
+use super::allocation::AllocationServiceImpl;
+use super::calibration::CalibrationServiceImpl;
+use super::certificate_service::CertificateServiceImpl;
+use super::compute_calibration::ComputeCalibrationServiceImpl;
+use super::dashboard_metrics::DashboardMetricsServiceImpl;
+use super::gpu_service::GpuDetectionServiceImpl;
+use super::operations::OperationsServiceImpl;
+use super::pcie_amplification::PCIeAmplificationServiceImpl;
+use super::telemetry::TelemetryServiceImpl;
+use super::{
+    AllocationService, AuthInterceptor, CalibrationService, CertificateService,
+    ComputeCalibrationService, DashboardMetricsService, GpuDetectionService, OperationsService,
+    PcIeAmplificationService, TelemetryService,
+};
+
+/// Service instances shared with the gRPC server
+pub struct GatewayServices {
+    pub calibration: Arc<CalibrationServiceImpl>,
+    pub compute_calibration: Arc<ComputeCalibrationServiceImpl>,
+    pub pcie_amplification: Arc<PCIeAmplificationServiceImpl>,
+    pub telemetry: Arc<TelemetryServiceImpl>,
+    pub allocation: Arc<AllocationServiceImpl>,
+    pub operations: Arc<OperationsServiceImpl>,
+    pub gpu_detection: Arc<GpuDetectionServiceImpl>,
+    pub certificate: Arc<CertificateServiceImpl>,
+    pub dashboard_metrics: Arc<DashboardMetricsServiceImpl>,
+    /// Same interceptor the gRPC services run behind
+    pub interceptor: AuthInterceptor,
+}
+
+crate::api_schema! {
+    /// A gateway route
+    #[derive(Debug, Clone, Serialize)]
+    pub struct GatewayRoute {
+        /// `/v1/{Service}/{Method}`
+        pub path: &'static str,
+        /// Server-streaming RPC answered with Server-Sent Events
+        pub streaming: bool,
+    }
+}
+
+impl GatewayRoute {
+    /// gRPC service and method names
+    pub fn rpc(&self) -> (&'static str, &'static str) {
+        let rpc = self.path.trim_start_matches("/v1/");
+        rpc.split_once('/').unwrap_or((rpc, ""))
+    }
+}
+
+crate::api_schema! {
+    /// Response of `GET /v1`
+    #[derive(Debug, Clone, Serialize)]
+    pub struct GatewayRouteList {
+        pub routes: Vec<GatewayRoute>,
+    }
+}
+
+/// HTTP status for a gRPC status code (grpc-gateway mapping)
+pub fn http_status(code: Code) -> StatusCode {
+    match code {
+        Code::Ok => StatusCode::OK,
+        Code::Cancelled => StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::Unknown | Code::Internal | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Gateway error carrying the gRPC code
+#[derive(Debug)]
+pub struct GatewayError {
+    code: Code,
+    message: String,
+}
+
+crate::api_schema! {
+    /// JSON body of a gateway error
+    #[derive(Debug, Clone, Serialize)]
+    pub struct GatewayErrorBody {
+        /// gRPC status code
+        pub code: i32,
+        /// gRPC status code name
+        pub error: String,
+        pub message: String,
+    }
+}
+
+impl GatewayError {
+    fn body(&self) -> GatewayErrorBody {
+        GatewayErrorBody {
+            code: self.code as i32,
+            error: format!("{:?}", self.code),
+            message: self.message.clone(),
+        }
+    }
+}
+
+impl From<Status> for GatewayError {
+    fn from(status: Status) -> Self {
+        Self {
+            code: status.code(),
+            message: status.message().to_string(),
+        }
+    }
+}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        (http_status(self.code), Json(self.body())).into_response()
+    }
+}
+
+fn invalid_argument(message: String) -> GatewayError {
+    GatewayError {
+        code: Code::InvalidArgument,
+        message,
+    }
+}
+
+/// Request message from a JSON body (empty body = default message)
+fn parse_json<T: DeserializeOwned + Default>(body: &[u8]) -> Result<T, GatewayError> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body)
+        .map_err(|e| invalid_argument(format!("Invalid JSON request: {}", e)))
+}
+
+/// Request message from a JSON body, or from query parameters when the body is empty
+fn parse_json_or_query<T: DeserializeOwned + Default>(
+    uri: &Uri,
+    body: &[u8],
+) -> Result<T, GatewayError> {
+    if !body.iter().all(u8::is_ascii_whitespace) {
+        return parse_json(body);
+    }
+    Query::<T>::try_from_uri(uri)
+        .map(|Query(message)| message)
+        .map_err(|e| invalid_argument(format!("Invalid query parameters: {}", e)))
+}
+
+/// Authenticate and rate-limit the HTTP headers like a gRPC call, and wrap the message
+async fn authorize<T>(
+    interceptor: &AuthInterceptor,
+    headers: HeaderMap,
+    message: T,
+) -> Result<tonic::Request<T>, GatewayError> {
+    let request = tonic::Request::from_parts(
+        MetadataMap::from_headers(headers),
+        Extensions::default(),
+        message,
+    );
+    interceptor.admit(&request).await?;
+    Ok(request)
+}
+
+async fn unary<Req, Resp, F, Fut>(
+    interceptor: &AuthInterceptor,
+    headers: HeaderMap,
+    body: &[u8],
+    call: F,
+) -> Result<Response, GatewayError>
+where
+    Req: DeserializeOwned + Default,
+    Resp: Serialize,
+    F: FnOnce(tonic::Request<Req>) -> Fut,
+    Fut: Future<Output = Result<tonic::Response<Resp>, Status>>,
+{
+    let request = authorize(interceptor, headers, parse_json(body)?).await?;
+    let response = call(request).await?;
+    Ok(Json(response.into_inner()).into_response())
+}
+
+async fn server_stream<Req, Resp, S, F, Fut>(
+    interceptor: &AuthInterceptor,
+    uri: &Uri,
+    headers: HeaderMap,
+    body: &[u8],
+    call: F,
+) -> Result<Response, GatewayError>
+where
+    Req: DeserializeOwned + Default,
+    Resp: Serialize,
+    S: Stream<Item = Result<Resp, Status>> + Send + 'static,
+    F: FnOnce(tonic::Request<Req>) -> Fut,
+    Fut: Future<Output = Result<tonic::Response<S>, Status>>,
+{
+    let request = authorize(interceptor, headers, parse_json_or_query(uri, body)?).await?;
+    let stream = call(request).await?.into_inner();
+
+    // Like gRPC, the stream ends after the first error
+    let events = stream
+        .scan(false, |ended, item| {
+            let event = match item {
+                _ if *ended => None,
+                Ok(message) => Some(
+                    Event::default()
+                        .json_data(message)
+                        .unwrap_or_else(|e| error_event(&invalid_argument(e.to_string()))),
+                ),
+                Err(status) => {
+                    *ended = true;
+                    Some(error_event(&status.into()))
+                }
+            };
+            std::future::ready(event)
+        })
+        .map(Ok::<_, Infallible>);
+
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+fn error_event(error: &GatewayError) -> Event {
+    Event::default()
+        .event("error")
+        .json_data(error.body())
+        .unwrap_or_default()
+}
+
+/// Invoke `$m!` with the RPC table: `unary` RPCs take POST, `stream` RPCs GET or POST (SSE)
+macro_rules! gateway_rpcs {
+    ($m:ident!($($args:tt)*)) => {
+        $m! { $($args)*;
+            stream "CalibrationService" / "SubscribeCalibrationMatrix" => calibration.subscribe_calibration_matrix;
+            unary "CalibrationService" / "GetCalibrationMatrix" => calibration.get_calibration_matrix;
+            unary "CalibrationService" / "ValidateMatrixVersion" => calibration.validate_matrix_version;
+
+            stream "ComputeCalibrationService" / "SubscribeComputeCalibration" => compute_calibration.subscribe_compute_calibration;
+            unary "ComputeCalibrationService" / "GetComputeCalibration" => compute_calibration.get_compute_calibration;
+            unary "ComputeCalibrationService" / "GetEngineConfig" => compute_calibration.get_engine_config;
+
+            stream "PCIeAmplificationService" / "SubscribePCIeCalibration" => pcie_amplification.subscribe_pc_ie_calibration;
+            unary "PCIeAmplificationService" / "GetPCIeConfig" => pcie_amplification.get_pc_ie_config;
+
+            stream "TelemetryService" / "SubscribeNetworkCapacity" => telemetry.subscribe_network_capacity;
+
+            unary "AllocationService" / "AllocateMemory" => allocation.allocate_memory;
+            unary "AllocationService" / "FreeMemory" => allocation.free_memory;
+            unary "AllocationService" / "RouteMemoryRequest" => allocation.route_memory_request;
+            unary "AllocationService" / "GetAllocationStatus" => allocation.get_allocation_status;
+
+            unary "OperationsService" / "HealthCheck" => operations.health_check;
+            stream "OperationsService" / "UpgradeAgent" => operations.upgrade_agent;
+            unary "OperationsService" / "GetSystemInfo" => operations.get_system_info;
+            unary "OperationsService" / "RestartAgent" => operations.restart_agent;
+
+            unary "GpuDetectionService" / "RegisterGpu" => gpu_detection.register_gpu;
+            unary "GpuDetectionService" / "GetGpuTierConfig" => gpu_detection.get_gpu_tier_config;
+            unary "GpuDetectionService" / "ListSupportedGpus" => gpu_detection.list_supported_gpus;
+            unary "GpuDetectionService" / "ReloadGpuCatalog" => gpu_detection.reload_gpu_catalog;
+            unary "GpuDetectionService" / "GetGpuInventory" => gpu_detection.get_gpu_inventory;
+            unary "GpuDetectionService" / "GetUsage" => gpu_detection.get_usage;
+
+            unary "CertificateService" / "RequestCertificate" => certificate.request_certificate;
+            unary "CertificateService" / "RenewCertificate" => certificate.renew_certificate;
+            unary "CertificateService" / "RevokeCertificate" => certificate.revoke_certificate;
+            unary "CertificateService" / "GetCertificateStatus" => certificate.get_certificate_status;
+            unary "CertificateService" / "GetCRL" => certificate.get_crl;
+            unary "CertificateService" / "CheckCertificateOCSP" => certificate.check_certificate_ocsp;
+
+            stream "DashboardMetricsService" / "StreamDashboardMetrics" => dashboard_metrics.stream_dashboard_metrics;
+            unary "DashboardMetricsService" / "GetConnectedAgents" => dashboard_metrics.get_connected_agents;
+            unary "DashboardMetricsService" / "GetSystemSummary" => dashboard_metrics.get_system_summary;
+        }
+    };
+}
+
+/// Collect the route table
+macro_rules! rpc_list {
+    (; $($kind:ident $service:literal / $method:literal => $field:ident . $call:ident;)*) => {
+        vec![
+            $(
+                GatewayRoute {
+                    path: concat!("/v1/", $service, "/", $method),
+                    streaming: rpc_list!(@streaming $kind),
+                },
+            )*
+        ]
+    };
+    (@streaming unary) => { false };
+    (@streaming stream) => { true };
+}
+
+/// Register one handler per RPC
+macro_rules! rpc_routes {
+    ($router:ident; $($kind:ident $service:literal / $method:literal => $field:ident . $call:ident;)*) => {
+        $(
+            $router = $router.route(concat!("/v1/", $service, "/", $method), rpc_routes!(@handler $kind $field $call));
+        )*
+    };
+    (@handler unary $field:ident $call:ident) => {
+        post(|State(gateway): State<Arc<GatewayServices>>, headers: HeaderMap, body: Bytes| async move {
+            let service = gateway.$field.clone();
+            unary(&gateway.interceptor, headers, &body, |request| async move { service.$call(request).await }).await
+        })
+    };
+    (@handler stream $field:ident $call:ident) => {{
+        let handler = |State(gateway): State<Arc<GatewayServices>>, uri: Uri, headers: HeaderMap, body: Bytes| async move {
+            let service = gateway.$field.clone();
+            server_stream(&gateway.interceptor, &uri, headers, &body, |request| async move {
+                service.$call(request).await
+            })
+            .await
+        };
+        get(handler).post(handler)
+    }};
+}
+
+/// Every bridged RPC, in proto order
+pub fn routes() -> Vec<GatewayRoute> {
+    gateway_rpcs!(rpc_list!())
+}
+
+/// Build the gateway router
+pub fn router(services: GatewayServices) -> Router {
+    let mut router: Router<Arc<GatewayServices>> = Router::new();
+    gateway_rpcs!(rpc_routes!(router));
+
+    router
+        .route(
+            "/v1",
+            get(|| async { Json(GatewayRouteList { routes: routes() }) }),
+        )
+        .with_state(Arc::new(services))
+}
@@ -0,0 +1,469 @@
+//! CYAN FLAME™ GPU Catalog
+//!
+//! Specifications, tier pricing and detection aliases for every baseline GPU.
+//! The built-in table (from `GpuSpecifications` / `GpuTierConfig`) is the
+//! default; a TOML or YAML file can override any field of any entry and add
+//! extra name patterns or PCI IDs so a new SKU maps onto an existing tier
+//! without a server release.
+//!
+//! ```toml
+//! [[gpus]]
+//! gpu_type = "nvidia_a30"
+//! memory_bandwidth_gbs = 933.0
+//! fp16_tflops = 165.0
+//! name_patterns = ["A800"]
+//! pci_ids = ["10de:20f5"]
+//!
+//! [gpus.tier]
+//! pricing_multiplier = 0.7
+//! ```
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::gpu_detection::{AmplificationTargets, BaselineGpuType, GpuSpecifications, GpuTierConfig};
+
+/// GPU catalog errors
+#[derive(Debug, thiserror::Error)]
+pub enum GpuCatalogError {
+    #[error("Failed to read GPU catalog from {0}: {1}")]
+    Read(String, String),
+    #[error("Unsupported GPU catalog format '{0}' (expected toml, yaml or yml)")]
+    UnsupportedFormat(String),
+    #[error("Failed to parse GPU catalog ({0}): {1}")]
+    Parse(&'static str, String),
+    #[error("gpus[{index}] ({gpu_type}): {field} {message}")]
+    Invalid {
+        index: usize,
+        gpu_type: String,
+        field: &'static str,
+        message: String,
+    },
+}
+
+/// Catalog file format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CatalogFormat {
+    Toml,
+    Yaml,
+}
+
+impl CatalogFormat {
+    /// Parse a format name ("toml", "yaml", "yml")
+    pub fn from_name(name: &str) -> Result<Self, GpuCatalogError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            other => Err(GpuCatalogError::UnsupportedFormat(other.to_string())),
+        }
+    }
+
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Result<Self, GpuCatalogError> {
+        Self::from_name(path.extension().and_then(|e| e.to_str()).unwrap_or(""))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Toml => "toml",
+            Self::Yaml => "yaml",
+        }
+    }
+}
+
+/// Tier fields that can be overridden (unset fields keep the computed value)
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TierOverride {
+    pub tier_name: Option<String>,
+    pub pricing_tier: Option<String>,
+    pub pricing_multiplier: Option<f64>,
+    pub max_effective_memory_tb: Option<u64>,
+    pub max_concurrent_sessions: Option<u32>,
+    pub rate_limit_per_minute: Option<u32>,
+    pub optimization_strategies: Option<Vec<String>>,
+}
+
+impl TierOverride {
+    /// Overlay another override on top of this one
+    fn overlay(&mut self, other: &TierOverride) {
+        if other.tier_name.is_some() {
+            self.tier_name = other.tier_name.clone();
+        }
+        if other.pricing_tier.is_some() {
+            self.pricing_tier = other.pricing_tier.clone();
+        }
+        if other.pricing_multiplier.is_some() {
+            self.pricing_multiplier = other.pricing_multiplier;
+        }
+        if other.max_effective_memory_tb.is_some() {
+            self.max_effective_memory_tb = other.max_effective_memory_tb;
+        }
+        if other.max_concurrent_sessions.is_some() {
+            self.max_concurrent_sessions = other.max_concurrent_sessions;
+        }
+        if other.rate_limit_per_minute.is_some() {
+            self.rate_limit_per_minute = other.rate_limit_per_minute;
+        }
+        if other.optimization_strategies.is_some() {
+            self.optimization_strategies = other.optimization_strategies.clone();
+        }
+    }
+
+    /// Apply to a computed tier config
+    fn apply(&self, tier: &mut GpuTierConfig) {
+        if let Some(v) = &self.tier_name {
+            tier.tier_name = v.clone();
+        }
+        if let Some(v) = &self.pricing_tier {
+            tier.pricing_tier = v.clone();
+        }
+        if let Some(v) = self.pricing_multiplier {
+            tier.pricing_multiplier = v;
+        }
+        if let Some(v) = self.max_effective_memory_tb {
+            tier.max_effective_memory_tb = v;
+        }
+        if let Some(v) = self.max_concurrent_sessions {
+            tier.max_concurrent_sessions = v;
+        }
+        if let Some(v) = self.rate_limit_per_minute {
+            tier.rate_limit_per_minute = v;
+        }
+        if let Some(v) = &self.optimization_strategies {
+            tier.optimization_strategies = v.clone();
+        }
+    }
+}
+
+/// One `[[gpus]]` entry in a catalog file
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GpuCatalogOverride {
+    /// Baseline GPU identifier (e.g. "nvidia_h100", see `BaselineGpuType::key`)
+    pub gpu_type: String,
+    pub vendor: Option<String>,
+    pub architecture: Option<String>,
+    pub vram_gb: Option<u64>,
+    pub memory_bandwidth_gbs: Option<f64>,
+    pub fp16_tflops: Option<f64>,
+    pub fp32_tflops: Option<f64>,
+    pub tf32_tflops: Option<f64>,
+    pub fp8_tflops: Option<f64>,
+    /// Also moves the type between compute capability fallback rows
+    pub compute_capability: Option<[u32; 2]>,
+    pub supports_fp8: Option<bool>,
+    pub supports_sparsity: Option<bool>,
+    pub supports_nvlink: Option<bool>,
+    #[serde(default)]
+    pub tier: TierOverride,
+    /// Extra name patterns that detect as this GPU type
+    #[serde(default)]
+    pub name_patterns: Vec<String>,
+    /// Extra PCI IDs ("vvvv:dddd") that detect as this GPU type
+    #[serde(default)]
+    pub pci_ids: Vec<String>,
+}
+
+/// Parsed catalog file
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GpuCatalogFile {
+    #[serde(default)]
+    pub gpus: Vec<GpuCatalogOverride>,
+}
+
+impl GpuCatalogFile {
+    /// Parse catalog file content
+    pub fn parse(content: &str, format: CatalogFormat) -> Result<Self, GpuCatalogError> {
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        match format {
+            CatalogFormat::Toml => toml::from_str(content)
+                .map_err(|e| GpuCatalogError::Parse(format.as_str(), e.to_string())),
+            CatalogFormat::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| GpuCatalogError::Parse(format.as_str(), e.to_string())),
+        }
+    }
+}
+
+/// Catalog entry for one baseline GPU
+#[derive(Clone, Debug)]
+pub struct GpuCatalogEntry {
+    pub specs: GpuSpecifications,
+    /// Tier overrides applied on top of `GpuTierConfig::from_gpu`
+    pub tier_overrides: TierOverride,
+    /// Name patterns in addition to the built-in detection table
+    pub name_patterns: Vec<String>,
+    /// PCI (vendor, device) IDs in addition to the built-in detection table
+    pub pci_ids: Vec<(u16, u16)>,
+}
+
+impl GpuCatalogEntry {
+    fn builtin(gpu_type: BaselineGpuType) -> Self {
+        let mut specs = GpuSpecifications::from_type(gpu_type);
+        // Types without their own constructor borrow a sibling's specs
+        specs.gpu_type = gpu_type;
+        Self {
+            specs,
+            tier_overrides: TierOverride::default(),
+            name_patterns: Vec::new(),
+            pci_ids: Vec::new(),
+        }
+    }
+
+    pub fn gpu_type(&self) -> BaselineGpuType {
+        self.specs.gpu_type
+    }
+
+    /// Tier configuration with overrides applied
+    pub fn tier_config(&self) -> GpuTierConfig {
+        let mut tier = GpuTierConfig::from_gpu(&self.specs);
+        self.tier_overrides.apply(&mut tier);
+        tier
+    }
+
+    /// Amplification targets for this GPU
+    pub fn amplification_targets(&self) -> AmplificationTargets {
+        AmplificationTargets::from_gpu(&self.specs)
+    }
+
+    /// Apply a file override; errors name the offending field
+    fn apply(&mut self, o: &GpuCatalogOverride) -> Result<(), (&'static str, String)> {
+        let specs = &mut self.specs;
+        if let Some(v) = &o.vendor {
+            specs.vendor = v.clone();
+        }
+        if let Some(v) = &o.architecture {
+            specs.architecture = v.clone();
+        }
+        if let Some(v) = o.vram_gb {
+            specs.vram_gb = v;
+        }
+        if let Some(v) = o.memory_bandwidth_gbs {
+            specs.memory_bandwidth_gbs = v;
+        }
+        if let Some(v) = o.fp16_tflops {
+            specs.fp16_tflops = v;
+        }
+        if let Some(v) = o.fp32_tflops {
+            specs.fp32_tflops = v;
+        }
+        if let Some(v) = o.tf32_tflops {
+            specs.tf32_tflops = v;
+        }
+        if let Some(v) = o.fp8_tflops {
+            specs.fp8_tflops = v;
+        }
+        if let Some([major, minor]) = o.compute_capability {
+            specs.compute_capability = (major, minor);
+        }
+        if let Some(v) = o.supports_fp8 {
+            specs.supports_fp8 = v;
+        }
+        if let Some(v) = o.supports_sparsity {
+            specs.supports_sparsity = v;
+        }
+        if let Some(v) = o.supports_nvlink {
+            specs.supports_nvlink = v;
+        }
+
+        self.tier_overrides.overlay(&o.tier);
+
+        for pattern in &o.name_patterns {
+            if !pattern.chars().any(|c| c.is_ascii_alphanumeric()) {
+                return Err(("name_patterns", format!("entry '{}' has no letters or digits", pattern)));
+            }
+            if !self.name_patterns.contains(pattern) {
+                self.name_patterns.push(pattern.clone());
+            }
+        }
+
+        for id in &o.pci_ids {
+            let parsed = parse_pci_pair(id).ok_or_else(|| {
+                ("pci_ids", format!("entry '{}' is not a vendor:device pair (e.g. \"10de:2330\")", id))
+            })?;
+            if !self.pci_ids.contains(&parsed) {
+                self.pci_ids.push(parsed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the merged entry is usable for pricing and amplification math
+    fn validate(&self) -> Result<(), (&'static str, String)> {
+        let specs = &self.specs;
+
+        if !matches!(specs.vendor.as_str(), "NVIDIA" | "AMD") {
+            return Err(("vendor", format!("must be \"NVIDIA\" or \"AMD\", got \"{}\"", specs.vendor)));
+        }
+        if specs.architecture.trim().is_empty() {
+            return Err(("architecture", "must not be empty".to_string()));
+        }
+        if specs.vram_gb == 0 {
+            return Err(("vram_gb", "must be greater than 0".to_string()));
+        }
+
+        let positive = [
+            ("memory_bandwidth_gbs", specs.memory_bandwidth_gbs),
+            ("fp16_tflops", specs.fp16_tflops),
+            ("fp32_tflops", specs.fp32_tflops),
+        ];
+        for (field, value) in positive {
+            if !value.is_finite() || value <= 0.0 {
+                return Err((field, format!("must be a positive number, got {}", value)));
+            }
+        }
+
+        let non_negative = [("tf32_tflops", specs.tf32_tflops), ("fp8_tflops", specs.fp8_tflops)];
+        for (field, value) in non_negative {
+            if !value.is_finite() || value < 0.0 {
+                return Err((field, format!("must be zero or positive, got {}", value)));
+            }
+        }
+
+        if specs.supports_fp8 && specs.fp8_tflops <= 0.0 {
+            return Err(("fp8_tflops", "must be greater than 0 when supports_fp8 is true".to_string()));
+        }
+
+        let tier = &self.tier_overrides;
+        if let Some(v) = tier.pricing_multiplier {
+            if !v.is_finite() || v < 0.0 {
+                return Err(("tier.pricing_multiplier", format!("must be zero or positive, got {}", v)));
+            }
+        }
+        if tier.tier_name.as_deref().is_some_and(|v| v.trim().is_empty()) {
+            return Err(("tier.tier_name", "must not be empty".to_string()));
+        }
+        if tier.pricing_tier.as_deref().is_some_and(|v| v.trim().is_empty()) {
+            return Err(("tier.pricing_tier", "must not be empty".to_string()));
+        }
+        if let Some(strategies) = &tier.optimization_strategies {
+            if strategies.iter().any(|s| s.trim().is_empty()) {
+                return Err(("tier.optimization_strategies", "must not contain empty entries".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse "vvvv:dddd" (optionally 0x-prefixed) into (vendor, device)
+fn parse_pci_pair(id: &str) -> Option<(u16, u16)> {
+    let (vendor, device) = id.trim().split_once(':')?;
+    let hex = |s: &str| {
+        let s = s.trim();
+        let s = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+        if s.is_empty() || s.len() > 4 {
+            return None;
+        }
+        u16::from_str_radix(s, 16).ok()
+    };
+    Some((hex(vendor)?, hex(device)?))
+}
+
+/// GPU catalog: one entry per supported baseline GPU, in proto order
+#[derive(Clone, Debug)]
+pub struct GpuCatalog {
+    entries: Vec<GpuCatalogEntry>,
+    source: String,
+}
+
+impl GpuCatalog {
+    /// Built-in catalog compiled into the server
+    pub fn builtin() -> Self {
+        Self {
+            entries: BaselineGpuType::SUPPORTED
+                .into_iter()
+                .map(GpuCatalogEntry::builtin)
+                .collect(),
+            source: "builtin".to_string(),
+        }
+    }
+
+    /// Built-in catalog with the overrides from a TOML/YAML file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GpuCatalogError> {
+        let path = path.as_ref();
+        let format = CatalogFormat::from_path(path)?;
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| GpuCatalogError::Read(path.display().to_string(), e.to_string()))?;
+
+        let mut catalog = Self::builtin().merge(&GpuCatalogFile::parse(&content, format)?)?;
+        catalog.source = path.display().to_string();
+        Ok(catalog)
+    }
+
+    /// Built-in catalog with overrides from in-memory content
+    pub fn parse_overrides(content: &str, format: CatalogFormat) -> Result<Self, GpuCatalogError> {
+        let mut catalog = Self::builtin().merge(&GpuCatalogFile::parse(content, format)?)?;
+        catalog.source = format!("inline:{}", format.as_str());
+        Ok(catalog)
+    }
+
+    /// Merge overrides into a copy of this catalog
+    ///
+    /// Every override is validated against the merged entry; the first
+    /// failure is reported with its `gpus[index]` position and field name.
+    pub fn merge(&self, file: &GpuCatalogFile) -> Result<Self, GpuCatalogError> {
+        let mut merged = self.clone();
+        let mut seen: HashMap<BaselineGpuType, usize> = HashMap::new();
+
+        for (index, o) in file.gpus.iter().enumerate() {
+            let invalid = |field: &'static str, message: String| GpuCatalogError::Invalid {
+                index,
+                gpu_type: o.gpu_type.clone(),
+                field,
+                message,
+            };
+
+            let gpu_type = BaselineGpuType::from_key(&o.gpu_type).ok_or_else(|| {
+                invalid("gpu_type", "is not a known GPU type (e.g. \"nvidia_h100\")".to_string())
+            })?;
+            if let Some(previous) = seen.insert(gpu_type, index) {
+                return Err(invalid("gpu_type", format!("duplicates gpus[{}]", previous)));
+            }
+
+            let entry = merged
+                .entries
+                .iter_mut()
+                .find(|e| e.gpu_type() == gpu_type)
+                .ok_or_else(|| invalid("gpu_type", "is not in the catalog".to_string()))?;
+            entry.apply(o).map_err(|(field, message)| invalid(field, message))?;
+            entry.validate().map_err(|(field, message)| invalid(field, message))?;
+        }
+
+        Ok(merged)
+    }
+
+    /// Entry for a GPU type
+    pub fn get(&self, gpu_type: BaselineGpuType) -> Option<&GpuCatalogEntry> {
+        self.entries.iter().find(|e| e.gpu_type() == gpu_type)
+    }
+
+    /// All entries, in proto order
+    pub fn entries(&self) -> &[GpuCatalogEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Where this catalog came from ("builtin", a file path, or "inline:<format>")
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Default for GpuCatalog {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
@@ -21,6 +21,11 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use super::gpu_catalog::{GpuCatalog, GpuCatalogEntry};
+use super::gpu_registry::{
+    GpuInventory, GpuRegistrationRecord, InMemoryRegistrationStore, InventoryFilter, RegistrationStore,
+};
+
 /// Supported baseline GPU types for tiered pricing
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BaselineGpuType {
@@ -197,10 +202,266 @@ impl BaselineGpuType {
         }
     }
 
+    /// Stable identifier used in catalog files (proto enum name, lowercase)
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Unknown => "gpu_unknown",
+            Self::NvidiaV100 => "nvidia_v100",
+            Self::NvidiaA100 => "nvidia_a100",
+            Self::NvidiaH100 => "nvidia_h100",
+            Self::NvidiaH200 => "nvidia_h200",
+            Self::NvidiaL40S => "nvidia_l40s",
+            Self::NvidiaA10 => "nvidia_a10",
+            Self::NvidiaA30 => "nvidia_a30",
+            Self::NvidiaA40 => "nvidia_a40",
+            Self::NvidiaTeslaT4 => "nvidia_tesla_t4",
+            Self::NvidiaRtx4090 => "nvidia_rtx_4090",
+            Self::NvidiaRtx4080 => "nvidia_rtx_4080",
+            Self::NvidiaRtx4070Ti => "nvidia_rtx_4070_ti",
+            Self::NvidiaRtx5090 => "nvidia_rtx_5090",
+            Self::NvidiaRtx5080 => "nvidia_rtx_5080",
+            Self::NvidiaRtx3090 => "nvidia_rtx_3090",
+            Self::NvidiaRtx3090Ti => "nvidia_rtx_3090_ti",
+            Self::NvidiaRtx3080 => "nvidia_rtx_3080",
+            Self::AmdMi100 => "amd_mi100",
+            Self::AmdMi250 => "amd_mi250",
+            Self::AmdMi300X => "amd_mi300x",
+            Self::AmdMi325X => "amd_mi325x",
+            Self::AmdRx7900Xtx => "amd_rx_7900_xtx",
+            Self::AmdRx7900Xt => "amd_rx_7900_xt",
+        }
+    }
+
+    /// Parse a catalog identifier (case-insensitive); `None` for unknown keys
+    pub fn from_key(key: &str) -> Option<Self> {
+        let key = key.trim().to_ascii_lowercase();
+        Self::SUPPORTED.into_iter().find(|t| t.key() == key)
+    }
+
     /// Check if this GPU type is supported
     pub fn is_supported(&self) -> bool {
         !matches!(self, Self::Unknown)
     }
+
+    /// Every supported (non-Unknown) GPU type, in proto order
+    pub const SUPPORTED: [BaselineGpuType; 23] = [
+        Self::NvidiaV100,
+        Self::NvidiaA100,
+        Self::NvidiaH100,
+        Self::NvidiaH200,
+        Self::NvidiaL40S,
+        Self::NvidiaA10,
+        Self::NvidiaA30,
+        Self::NvidiaA40,
+        Self::NvidiaTeslaT4,
+        Self::NvidiaRtx4090,
+        Self::NvidiaRtx4080,
+        Self::NvidiaRtx4070Ti,
+        Self::NvidiaRtx5090,
+        Self::NvidiaRtx5080,
+        Self::NvidiaRtx3090,
+        Self::NvidiaRtx3090Ti,
+        Self::NvidiaRtx3080,
+        Self::AmdMi100,
+        Self::AmdMi250,
+        Self::AmdMi300X,
+        Self::AmdMi325X,
+        Self::AmdRx7900Xtx,
+        Self::AmdRx7900Xt,
+    ];
+}
+
+/// PCI vendor IDs
+pub const PCI_VENDOR_NVIDIA: u16 = 0x10de;
+pub const PCI_VENDOR_AMD: u16 = 0x1002;
+
+/// GPU name patterns. Matching is token-based (letters and digits split into
+/// separate tokens) so "A10" never matches "A100"; when several patterns
+/// match, the one with the most tokens wins ("L40S" over "L40").
+pub const GPU_NAME_PATTERNS: &[(&str, BaselineGpuType)] = &[
+    // NVIDIA Data Center
+    ("V100", BaselineGpuType::NvidiaV100),
+    ("V100S", BaselineGpuType::NvidiaV100),
+    ("A100", BaselineGpuType::NvidiaA100),
+    ("H100", BaselineGpuType::NvidiaH100),
+    ("H200", BaselineGpuType::NvidiaH200),
+    ("L40", BaselineGpuType::NvidiaL40S),
+    ("L40S", BaselineGpuType::NvidiaL40S),
+    ("A10", BaselineGpuType::NvidiaA10),
+    ("A10G", BaselineGpuType::NvidiaA10),
+    ("A30", BaselineGpuType::NvidiaA30),
+    ("A40", BaselineGpuType::NvidiaA40),
+    ("T4", BaselineGpuType::NvidiaTeslaT4),
+    // NVIDIA Consumer - Ada Lovelace
+    ("RTX 4090", BaselineGpuType::NvidiaRtx4090),
+    ("RTX 4080", BaselineGpuType::NvidiaRtx4080),
+    ("RTX 4070 Ti", BaselineGpuType::NvidiaRtx4070Ti),
+    // NVIDIA Consumer - Blackwell
+    ("RTX 5090", BaselineGpuType::NvidiaRtx5090),
+    ("RTX 5080", BaselineGpuType::NvidiaRtx5080),
+    // NVIDIA Legacy Consumer - Ampere
+    ("RTX 3090", BaselineGpuType::NvidiaRtx3090),
+    ("RTX 3090 Ti", BaselineGpuType::NvidiaRtx3090Ti),
+    ("RTX 3080", BaselineGpuType::NvidiaRtx3080),
+    // AMD Instinct
+    ("MI100", BaselineGpuType::AmdMi100),
+    ("MI250", BaselineGpuType::AmdMi250),
+    ("MI250X", BaselineGpuType::AmdMi250),
+    ("MI300X", BaselineGpuType::AmdMi300X),
+    ("MI325X", BaselineGpuType::AmdMi325X),
+    // AMD Consumer - RDNA 3
+    ("RX 7900 XTX", BaselineGpuType::AmdRx7900Xtx),
+    ("RX 7900 XT", BaselineGpuType::AmdRx7900Xt),
+];
+
+/// PCI (vendor, device) IDs. Some dies ship under several products (Navi 31
+/// is both RX 7900 XTX and XT); those entries list every candidate.
+pub const GPU_PCI_IDS: &[(u16, u16, &[BaselineGpuType])] = &[
+    // NVIDIA Data Center
+    (PCI_VENDOR_NVIDIA, 0x1db1, &[BaselineGpuType::NvidiaV100]), // SXM2 16GB
+    (PCI_VENDOR_NVIDIA, 0x1db4, &[BaselineGpuType::NvidiaV100]), // PCIe 16GB
+    (PCI_VENDOR_NVIDIA, 0x1db5, &[BaselineGpuType::NvidiaV100]), // SXM2 32GB
+    (PCI_VENDOR_NVIDIA, 0x1db6, &[BaselineGpuType::NvidiaV100]), // PCIe 32GB
+    (PCI_VENDOR_NVIDIA, 0x1df6, &[BaselineGpuType::NvidiaV100]), // V100S
+    (PCI_VENDOR_NVIDIA, 0x20b0, &[BaselineGpuType::NvidiaA100]), // SXM4 40GB
+    (PCI_VENDOR_NVIDIA, 0x20b2, &[BaselineGpuType::NvidiaA100]), // SXM4 80GB
+    (PCI_VENDOR_NVIDIA, 0x20b5, &[BaselineGpuType::NvidiaA100]), // PCIe 80GB
+    (PCI_VENDOR_NVIDIA, 0x20f1, &[BaselineGpuType::NvidiaA100]), // PCIe 40GB
+    (PCI_VENDOR_NVIDIA, 0x2321, &[BaselineGpuType::NvidiaH100]), // H100 NVL
+    (PCI_VENDOR_NVIDIA, 0x2330, &[BaselineGpuType::NvidiaH100]), // SXM5 80GB
+    (PCI_VENDOR_NVIDIA, 0x2331, &[BaselineGpuType::NvidiaH100]), // PCIe 80GB
+    (PCI_VENDOR_NVIDIA, 0x2335, &[BaselineGpuType::NvidiaH200]), // SXM 141GB
+    (PCI_VENDOR_NVIDIA, 0x26b5, &[BaselineGpuType::NvidiaL40S]), // L40
+    (PCI_VENDOR_NVIDIA, 0x26b9, &[BaselineGpuType::NvidiaL40S]), // L40S
+    (PCI_VENDOR_NVIDIA, 0x2236, &[BaselineGpuType::NvidiaA10]),
+    (PCI_VENDOR_NVIDIA, 0x2237, &[BaselineGpuType::NvidiaA10]),  // A10G
+    (PCI_VENDOR_NVIDIA, 0x20b7, &[BaselineGpuType::NvidiaA30]),
+    (PCI_VENDOR_NVIDIA, 0x2235, &[BaselineGpuType::NvidiaA40]),
+    (PCI_VENDOR_NVIDIA, 0x1eb8, &[BaselineGpuType::NvidiaTeslaT4]),
+    // NVIDIA Consumer
+    (PCI_VENDOR_NVIDIA, 0x2684, &[BaselineGpuType::NvidiaRtx4090]),
+    (PCI_VENDOR_NVIDIA, 0x2704, &[BaselineGpuType::NvidiaRtx4080]),
+    (PCI_VENDOR_NVIDIA, 0x2702, &[BaselineGpuType::NvidiaRtx4080]), // 4080 SUPER
+    (PCI_VENDOR_NVIDIA, 0x2782, &[BaselineGpuType::NvidiaRtx4070Ti]),
+    (PCI_VENDOR_NVIDIA, 0x2b85, &[BaselineGpuType::NvidiaRtx5090]),
+    (PCI_VENDOR_NVIDIA, 0x2c02, &[BaselineGpuType::NvidiaRtx5080]),
+    (PCI_VENDOR_NVIDIA, 0x2204, &[BaselineGpuType::NvidiaRtx3090]),
+    (PCI_VENDOR_NVIDIA, 0x2203, &[BaselineGpuType::NvidiaRtx3090Ti]),
+    (PCI_VENDOR_NVIDIA, 0x2206, &[BaselineGpuType::NvidiaRtx3080]),
+    (PCI_VENDOR_NVIDIA, 0x2216, &[BaselineGpuType::NvidiaRtx3080]), // LHR
+    // AMD Instinct
+    (PCI_VENDOR_AMD, 0x738c, &[BaselineGpuType::AmdMi100]),
+    (PCI_VENDOR_AMD, 0x7408, &[BaselineGpuType::AmdMi250]), // MI250X
+    (PCI_VENDOR_AMD, 0x740c, &[BaselineGpuType::AmdMi250]),
+    (PCI_VENDOR_AMD, 0x74a1, &[BaselineGpuType::AmdMi300X]),
+    (PCI_VENDOR_AMD, 0x74a5, &[BaselineGpuType::AmdMi325X]),
+    // AMD Consumer (Navi 31)
+    (PCI_VENDOR_AMD, 0x744c, &[BaselineGpuType::AmdRx7900Xtx, BaselineGpuType::AmdRx7900Xt]),
+];
+
+/// Compute capability and its candidates with nominal VRAM (GB)
+pub type ComputeCapabilityEntry = ((u32, u32), &'static [(BaselineGpuType, u64)]);
+
+/// Compute capability fallback with each candidate's nominal VRAM (GB).
+/// The candidate closest to the reported VRAM wins, ties going to the one
+/// listed first; without VRAM the first candidate wins.
+pub const GPU_COMPUTE_CAPABILITIES: &[ComputeCapabilityEntry] = &[
+    ((7, 0), &[(BaselineGpuType::NvidiaV100, 32)]),
+    ((7, 5), &[(BaselineGpuType::NvidiaTeslaT4, 16)]),
+    ((8, 0), &[(BaselineGpuType::NvidiaA100, 80), (BaselineGpuType::NvidiaA30, 24)]),
+    // 24 GB is shared by the RTX 3090, 3090 Ti and A10; the A10 and Ti are
+    // told apart by PCI ID or name, so the fallback settles on the RTX 3090
+    ((8, 6), &[
+        (BaselineGpuType::NvidiaRtx3090, 24),
+        (BaselineGpuType::NvidiaRtx3090Ti, 24),
+        (BaselineGpuType::NvidiaA10, 24),
+        (BaselineGpuType::NvidiaA40, 48),
+        (BaselineGpuType::NvidiaRtx3080, 10),
+    ]),
+    ((8, 9), &[
+        (BaselineGpuType::NvidiaL40S, 48),
+        (BaselineGpuType::NvidiaRtx4090, 24),
+        (BaselineGpuType::NvidiaRtx4080, 16),
+        (BaselineGpuType::NvidiaRtx4070Ti, 12),
+    ]),
+    ((9, 0), &[(BaselineGpuType::NvidiaH100, 80), (BaselineGpuType::NvidiaH200, 141)]),
+    // Consumer Blackwell is sm_120; 10.0 is the data-center B100/B200
+    ((12, 0), &[(BaselineGpuType::NvidiaRtx5090, 32), (BaselineGpuType::NvidiaRtx5080, 16)]),
+];
+
+/// How a GPU was identified
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetectionMethod {
+    PciId,
+    Name,
+    ComputeCapability,
+    None,
+}
+
+impl DetectionMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PciId => "pci_id",
+            Self::Name => "name",
+            Self::ComputeCapability => "compute_capability",
+            Self::None => "none",
+        }
+    }
+
+    /// Parse the `as_str` form; unknown values map to `None`
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "pci_id" => Self::PciId,
+            "name" => Self::Name,
+            "compute_capability" => Self::ComputeCapability,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Detection result with a confidence score (0.0 - 1.0)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpuDetection {
+    pub gpu_type: BaselineGpuType,
+    pub method: DetectionMethod,
+    pub confidence: f64,
+}
+
+impl GpuDetection {
+    fn unknown() -> Self {
+        Self {
+            gpu_type: BaselineGpuType::Unknown,
+            method: DetectionMethod::None,
+            confidence: 0.0,
+        }
+    }
+}
+
+/// What an agent reports about its GPU
+#[derive(Clone, Debug, Default)]
+pub struct GpuProbe {
+    /// Marketing name (e.g. "NVIDIA GeForce RTX 4090")
+    pub name: String,
+    /// Vendor string ("NVIDIA", "AMD"), used when the PCI ID omits the vendor
+    pub vendor: String,
+    /// PCI ID as "10de:2684", "0x268410DE" (nvidia-smi) or "0x2684"
+    pub pci_device_id: String,
+    /// CUDA compute capability, if reported
+    pub compute_capability: Option<(u32, u32)>,
+    /// Total VRAM in MB, if reported
+    pub memory_mb: Option<u64>,
+    /// Driver version (recorded in the inventory, not used for detection)
+    pub driver_version: String,
+}
+
+impl GpuProbe {
+    /// Probe from a GPU name only
+    pub fn from_name(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
 }
 
 /// GPU Specifications for baseline calculations
@@ -348,7 +609,7 @@ impl GpuSpecifications {
             fp32_tflops: 104.8,
             tf32_tflops: 419.0,
             fp8_tflops: 1676.0,
-            compute_capability: (10, 0),
+            compute_capability: (12, 0),
             supports_fp8: true,
             supports_sparsity: true,
             supports_nvlink: false,
@@ -899,6 +1160,12 @@ pub struct GpuRegistration {
     pub agent_id: String,
     pub api_key_hash: String,
     pub detected_gpu: BaselineGpuType,
+    pub detection: GpuDetection,
+    /// Name reported by the agent
+    pub gpu_name: String,
+    /// Reported VRAM, or the catalog value when not reported
+    pub vram_gb: u64,
+    pub driver_version: String,
     pub gpu_specs: GpuSpecifications,
     pub tier_config: GpuTierConfig,
     pub amplification_targets: AmplificationTargets,
@@ -907,137 +1174,482 @@ pub struct GpuRegistration {
     pub last_seen: chrono::DateTime<chrono::Utc>,
 }
 
+impl GpuRegistration {
+    /// Persisted form of this registration
+    pub fn to_record(&self) -> GpuRegistrationRecord {
+        GpuRegistrationRecord {
+            agent_id: self.agent_id.clone(),
+            api_key_hash: self.api_key_hash.clone(),
+            gpu_type: self.detected_gpu,
+            gpu_name: self.gpu_name.clone(),
+            vram_gb: self.vram_gb,
+            driver_version: self.driver_version.clone(),
+            tier_name: self.tier_config.tier_name.clone(),
+            pricing_tier: self.tier_config.pricing_tier.clone(),
+            detection_method: self.detection.method.as_str().to_string(),
+            detection_confidence: self.detection.confidence,
+            certificate_fingerprint: self.certificate_fingerprint.clone(),
+            registered_at: self.registered_at,
+            last_seen: self.last_seen,
+        }
+    }
+
+    /// Rebuild a registration from a persisted record; specs, tier and
+    /// amplification targets come from the current catalog entry
+    pub fn from_record(record: &GpuRegistrationRecord, entry: &GpuCatalogEntry) -> Self {
+        Self {
+            agent_id: record.agent_id.clone(),
+            api_key_hash: record.api_key_hash.clone(),
+            detected_gpu: record.gpu_type,
+            detection: GpuDetection {
+                gpu_type: record.gpu_type,
+                method: DetectionMethod::from_name(&record.detection_method),
+                confidence: record.detection_confidence,
+            },
+            gpu_name: record.gpu_name.clone(),
+            vram_gb: record.vram_gb,
+            driver_version: record.driver_version.clone(),
+            gpu_specs: entry.specs.clone(),
+            tier_config: entry.tier_config(),
+            amplification_targets: entry.amplification_targets(),
+            certificate_fingerprint: record.certificate_fingerprint.clone(),
+            registered_at: record.registered_at,
+            last_seen: record.last_seen,
+        }
+    }
+}
+
 /// GPU Detection Manager
 pub struct GpuDetectionManager {
-    /// Registered GPUs by agent ID
+    /// Registered GPUs by agent ID (cache over `store`)
     registrations: Arc<RwLock<HashMap<String, GpuRegistration>>>,
-    /// GPU name patterns for detection
-    gpu_patterns: HashMap<&'static str, BaselineGpuType>,
+    /// Specs, tiers and extra detection aliases (read on the sync detect path)
+    catalog: Arc<std::sync::RwLock<GpuCatalog>>,
+    /// Persistent registration storage
+    store: Arc<dyn RegistrationStore>,
 }
 
 impl GpuDetectionManager {
-    /// Create a new GPU detection manager
+    /// Create a new GPU detection manager with the built-in catalog
     pub fn new() -> Self {
-        let mut patterns = HashMap::new();
-
-        // NVIDIA patterns
-        patterns.insert("V100", BaselineGpuType::NvidiaV100);
-        patterns.insert("Tesla V100", BaselineGpuType::NvidiaV100);
-        patterns.insert("A100", BaselineGpuType::NvidiaA100);
-        patterns.insert("Tesla A100", BaselineGpuType::NvidiaA100);
-        patterns.insert("H100", BaselineGpuType::NvidiaH100);
-        patterns.insert("H200", BaselineGpuType::NvidiaH200);
-        patterns.insert("L40S", BaselineGpuType::NvidiaL40S);
-        patterns.insert("L40", BaselineGpuType::NvidiaL40S);
-        patterns.insert("RTX 4090", BaselineGpuType::NvidiaRtx4090);
-        patterns.insert("GeForce RTX 4090", BaselineGpuType::NvidiaRtx4090);
-        patterns.insert("RTX 5090", BaselineGpuType::NvidiaRtx5090);
-        patterns.insert("GeForce RTX 5090", BaselineGpuType::NvidiaRtx5090);
-
-        // AMD patterns
-        patterns.insert("MI100", BaselineGpuType::AmdMi100);
-        patterns.insert("Instinct MI100", BaselineGpuType::AmdMi100);
-        patterns.insert("MI250", BaselineGpuType::AmdMi250);
-        patterns.insert("MI250X", BaselineGpuType::AmdMi250);
-        patterns.insert("Instinct MI250", BaselineGpuType::AmdMi250);
-        patterns.insert("MI300X", BaselineGpuType::AmdMi300X);
-        patterns.insert("Instinct MI300X", BaselineGpuType::AmdMi300X);
+        Self::with_catalog(GpuCatalog::builtin())
+    }
 
+    /// Create a GPU detection manager with a specific catalog
+    pub fn with_catalog(catalog: GpuCatalog) -> Self {
         Self {
             registrations: Arc::new(RwLock::new(HashMap::new())),
-            gpu_patterns: patterns,
+            catalog: Arc::new(std::sync::RwLock::new(catalog)),
+            store: Arc::new(InMemoryRegistrationStore::new()),
         }
     }
 
-    /// Detect GPU type from name string
+    /// Use a persistent registration store (call `restore` to load it)
+    pub fn with_store(mut self, store: Arc<dyn RegistrationStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Load all persisted registrations; returns how many were restored
+    pub async fn restore(&self) -> Result<usize, String> {
+        let records = self.store.list(&InventoryFilter::default()).await?;
+        let mut registrations = self.registrations.write().await;
+        let mut restored = 0;
+
+        for record in &records {
+            match self.catalog_entry(record.gpu_type) {
+                Some(entry) => {
+                    registrations.insert(
+                        record.agent_id.clone(),
+                        GpuRegistration::from_record(record, &entry),
+                    );
+                    restored += 1;
+                }
+                None => warn!(
+                    "⚠️ Skipping registration for {}: {:?} is not in the GPU catalog",
+                    record.agent_id, record.gpu_type
+                ),
+            }
+        }
+
+        info!("📦 Restored {} GPU registrations", restored);
+        Ok(restored)
+    }
+
+    /// Snapshot of the current catalog
+    pub fn catalog(&self) -> GpuCatalog {
+        self.catalog.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the catalog; existing registrations keep the tier they were given
+    pub fn replace_catalog(&self, catalog: GpuCatalog) {
+        info!(
+            "📚 GPU catalog loaded: {} GPUs from {}",
+            catalog.len(),
+            catalog.source()
+        );
+        *self.catalog.write().unwrap_or_else(|e| e.into_inner()) = catalog;
+    }
+
+    /// Catalog entry for a GPU type
+    pub fn catalog_entry(&self, gpu_type: BaselineGpuType) -> Option<GpuCatalogEntry> {
+        self.catalog
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(gpu_type)
+            .cloned()
+    }
+
+    /// Split a name into uppercase letter/digit runs ("RTX4090Ti" -> RTX, 4090, TI)
+    fn tokenize(name: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        let mut current_is_digit = false;
+
+        for c in name.chars() {
+            if !c.is_ascii_alphanumeric() {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            if !current.is_empty() && c.is_ascii_digit() != current_is_digit {
+                tokens.push(std::mem::take(&mut current));
+            }
+            current_is_digit = c.is_ascii_digit();
+            current.push(c.to_ascii_uppercase());
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+        tokens
+    }
+
+    /// Detect GPU type from name string (longest pattern match wins)
     pub fn detect_gpu_type(&self, gpu_name: &str) -> BaselineGpuType {
-        let name_upper = gpu_name.to_uppercase();
+        let gpu_type = self
+            .detect_from_name(gpu_name)
+            .unwrap_or(BaselineGpuType::Unknown);
+        if gpu_type.is_supported() {
+            info!("🎮 Detected GPU: {} -> {:?}", gpu_name, gpu_type);
+        } else {
+            warn!("⚠️ Unknown GPU: {}", gpu_name);
+        }
+        gpu_type
+    }
 
-        for (pattern, gpu_type) in &self.gpu_patterns {
-            if name_upper.contains(&pattern.to_uppercase()) {
-                info!("🎮 Detected GPU: {} -> {:?}", gpu_name, gpu_type);
-                return *gpu_type;
+    /// Name match without logging; `None` when no pattern matches
+    pub fn detect_from_name(&self, gpu_name: &str) -> Option<BaselineGpuType> {
+        let name_tokens = Self::tokenize(gpu_name);
+        let mut best: Option<(usize, BaselineGpuType)> = None;
+
+        let catalog = self.catalog.read().unwrap_or_else(|e| e.into_inner());
+        let catalog_patterns = catalog.entries().iter().flat_map(|entry| {
+            entry
+                .name_patterns
+                .iter()
+                .map(move |pattern| (pattern.as_str(), entry.gpu_type()))
+        });
+
+        for (pattern, gpu_type) in GPU_NAME_PATTERNS.iter().copied().chain(catalog_patterns) {
+            let pattern_tokens = Self::tokenize(pattern);
+            let matched = name_tokens
+                .windows(pattern_tokens.len())
+                .any(|w| w == pattern_tokens.as_slice());
+            if matched && best.is_none_or(|(len, _)| pattern_tokens.len() > len) {
+                best = Some((pattern_tokens.len(), gpu_type));
             }
         }
 
-        // Try compute capability detection as fallback
-        warn!("⚠️ Unknown GPU: {}", gpu_name);
-        BaselineGpuType::Unknown
+        best.map(|(_, gpu_type)| gpu_type)
+    }
+
+    /// Parse a PCI ID string into (vendor, device)
+    ///
+    /// Accepts "10de:2684", nvidia-smi's "0x268410DE" (device then vendor),
+    /// and a bare device ID ("0x2684" / "2684") with the vendor taken from
+    /// `vendor_hint`.
+    pub fn parse_pci_id(pci_id: &str, vendor_hint: &str) -> Option<(u16, u16)> {
+        let id = pci_id.trim();
+        if id.is_empty() {
+            return None;
+        }
+
+        if let Some((vendor, device)) = id.split_once(':') {
+            let vendor = u16::from_str_radix(vendor.trim().trim_start_matches("0x"), 16).ok()?;
+            let device = u16::from_str_radix(device.trim().trim_start_matches("0x"), 16).ok()?;
+            return Some((vendor, device));
+        }
+
+        let hex = id.trim_start_matches("0x").trim_start_matches("0X");
+        match hex.len() {
+            8 => {
+                let combined = u32::from_str_radix(hex, 16).ok()?;
+                Some(((combined & 0xffff) as u16, (combined >> 16) as u16))
+            }
+            1..=4 => {
+                let device = u16::from_str_radix(hex, 16).ok()?;
+                let vendor = match vendor_hint.to_uppercase().as_str() {
+                    v if v.contains("NVIDIA") => PCI_VENDOR_NVIDIA,
+                    v if v.contains("AMD") || v.contains("ATI") => PCI_VENDOR_AMD,
+                    _ => return None,
+                };
+                Some((vendor, device))
+            }
+            _ => None,
+        }
+    }
+
+    /// Look up PCI (vendor, device) candidates, built-in table first
+    pub fn detect_from_pci_id(&self, vendor: u16, device: u16) -> Vec<BaselineGpuType> {
+        let mut candidates: Vec<BaselineGpuType> = GPU_PCI_IDS
+            .iter()
+            .find(|(v, d, _)| *v == vendor && *d == device)
+            .map(|(_, _, candidates)| candidates.to_vec())
+            .unwrap_or_default();
+
+        let catalog = self.catalog.read().unwrap_or_else(|e| e.into_inner());
+        for entry in catalog.entries() {
+            if entry.pci_ids.contains(&(vendor, device)) && !candidates.contains(&entry.gpu_type()) {
+                candidates.push(entry.gpu_type());
+            }
+        }
+        candidates
     }
 
     /// Detect GPU type from compute capability
     pub fn detect_from_compute_capability(&self, major: u32, minor: u32) -> Option<BaselineGpuType> {
-        match (major, minor) {
-            (7, 0) => Some(BaselineGpuType::NvidiaV100),
-            (8, 0) => Some(BaselineGpuType::NvidiaA100),
-            (8, 9) => Some(BaselineGpuType::NvidiaRtx4090), // Could be L40S or 4090
-            (9, 0) => Some(BaselineGpuType::NvidiaH100),    // Could be H100 or H200
-            (10, 0) => Some(BaselineGpuType::NvidiaRtx5090),
-            _ => None,
+        self.detect_from_compute_capability_and_memory(major, minor, None)
+    }
+
+    /// Compute capability fallback, disambiguated by VRAM when known
+    ///
+    /// Catalog entries whose compute capability was overridden leave their
+    /// built-in row and join the row they now report, after its built-in candidates.
+    pub fn detect_from_compute_capability_and_memory(
+        &self,
+        major: u32,
+        minor: u32,
+        memory_mb: Option<u64>,
+    ) -> Option<BaselineGpuType> {
+        let cc = (major, minor);
+        let catalog = self.catalog.read().unwrap_or_else(|e| e.into_inner());
+        let overridden = |entry: &&GpuCatalogEntry| {
+            entry.specs.compute_capability != GpuSpecifications::from_type(entry.gpu_type()).compute_capability
+        };
+
+        let mut candidates: Vec<(BaselineGpuType, u64)> = GPU_COMPUTE_CAPABILITIES
+            .iter()
+            .find(|(row, _)| *row == cc)
+            .map(|(_, candidates)| candidates.to_vec())
+            .unwrap_or_default();
+        candidates.retain(|(gpu_type, _)| {
+            catalog
+                .get(*gpu_type)
+                .filter(overridden)
+                .is_none_or(|entry| entry.specs.compute_capability == cc)
+        });
+        for entry in catalog.entries().iter().filter(overridden) {
+            if entry.specs.compute_capability == cc && !candidates.iter().any(|(t, _)| *t == entry.gpu_type()) {
+                candidates.push((entry.gpu_type(), entry.specs.vram_gb));
+            }
+        }
+
+        match memory_mb {
+            Some(mb) if mb > 0 => {
+                let gb = mb as f64 / 1024.0;
+                candidates
+                    .iter()
+                    .min_by(|a, b| {
+                        let da = (a.1 as f64 - gb).abs();
+                        let db = (b.1 as f64 - gb).abs();
+                        da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .map(|(gpu_type, _)| *gpu_type)
+            }
+            _ => candidates.first().map(|(gpu_type, _)| *gpu_type),
         }
     }
 
+    /// Identify a GPU from everything the agent reported
+    ///
+    /// PCI ID is authoritative; the name disambiguates shared dies and the
+    /// compute capability is used only when neither identifies the card.
+    pub fn detect(&self, probe: &GpuProbe) -> GpuDetection {
+        let by_name = self.detect_from_name(&probe.name);
+        let by_pci = Self::parse_pci_id(&probe.pci_device_id, &probe.vendor)
+            .map(|(vendor, device)| self.detect_from_pci_id(vendor, device))
+            .unwrap_or_default();
+
+        let detection = match (by_pci.as_slice(), by_name) {
+            ([only], name) => GpuDetection {
+                gpu_type: *only,
+                method: DetectionMethod::PciId,
+                confidence: match name {
+                    Some(n) if n != *only => {
+                        warn!("⚠️ GPU name '{}' disagrees with PCI ID {} ({:?}); trusting PCI ID",
+                            probe.name, probe.pci_device_id, only);
+                        0.7
+                    }
+                    _ => 1.0,
+                },
+            },
+            ([_, ..], Some(name)) if by_pci.contains(&name) => GpuDetection {
+                gpu_type: name,
+                method: DetectionMethod::PciId,
+                confidence: 0.95,
+            },
+            ([first, ..], _) => GpuDetection {
+                gpu_type: *first,
+                method: DetectionMethod::PciId,
+                confidence: 0.6,
+            },
+            ([], Some(name)) => GpuDetection {
+                gpu_type: name,
+                method: DetectionMethod::Name,
+                confidence: 0.9,
+            },
+            ([], None) => probe
+                .compute_capability
+                .and_then(|(major, minor)| {
+                    self.detect_from_compute_capability_and_memory(major, minor, probe.memory_mb)
+                        .map(|gpu_type| GpuDetection {
+                            gpu_type,
+                            method: DetectionMethod::ComputeCapability,
+                            confidence: if probe.memory_mb.unwrap_or(0) > 0 { 0.6 } else { 0.4 },
+                        })
+                })
+                .unwrap_or_else(GpuDetection::unknown),
+        };
+
+        debug!(
+            "GPU detection name='{}' pci='{}' cc={:?} -> {:?} via {} ({:.2})",
+            probe.name,
+            probe.pci_device_id,
+            probe.compute_capability,
+            detection.gpu_type,
+            detection.method.as_str(),
+            detection.confidence
+        );
+        detection
+    }
+
+    /// Comma-separated names of every supported GPU (for error messages)
+    pub fn supported_gpu_names() -> String {
+        BaselineGpuType::SUPPORTED
+            .iter()
+            .map(|t| t.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     /// Register a GPU and return tier configuration
     pub async fn register_gpu(
         &self,
         agent_id: &str,
         api_key_hash: &str,
-        gpu_name: &str,
+        probe: &GpuProbe,
         certificate_fingerprint: Option<String>,
     ) -> Result<GpuRegistration, String> {
-        let gpu_type = self.detect_gpu_type(gpu_name);
+        let detection = self.detect(probe);
+        let gpu_type = detection.gpu_type;
 
         if !gpu_type.is_supported() {
             return Err(format!(
-                "GPU '{}' is not supported. Supported GPUs: V100, A100, H100, H200, L40S, \
-                RTX 4090, RTX 5090, MI100, MI250, MI300X",
-                gpu_name
+                "GPU '{}' is not supported. Supported GPUs: {}",
+                probe.name,
+                Self::supported_gpu_names()
             ));
         }
 
-        let specs = GpuSpecifications::from_type(gpu_type);
-        let tier_config = GpuTierConfig::from_gpu(&specs);
-        let amp_targets = AmplificationTargets::from_gpu(&specs);
+        let entry = self
+            .catalog_entry(gpu_type)
+            .ok_or_else(|| format!("GPU '{}' is missing from the GPU catalog", gpu_type.name()))?;
         let now = chrono::Utc::now();
 
+        // Re-registration keeps the original registration time
+        let registered_at = match self.get_registration(agent_id).await {
+            Some(existing) => existing.registered_at,
+            None => now,
+        };
+
+        let vram_gb = probe
+            .memory_mb
+            .map(|mb| (mb as f64 / 1024.0).round() as u64)
+            .filter(|gb| *gb > 0)
+            .unwrap_or(entry.specs.vram_gb);
+
         let registration = GpuRegistration {
             agent_id: agent_id.to_string(),
             api_key_hash: api_key_hash.to_string(),
             detected_gpu: gpu_type,
-            gpu_specs: specs,
-            tier_config,
-            amplification_targets: amp_targets,
+            detection,
+            gpu_name: probe.name.clone(),
+            vram_gb,
+            driver_version: probe.driver_version.clone(),
+            tier_config: entry.tier_config(),
+            amplification_targets: entry.amplification_targets(),
+            gpu_specs: entry.specs,
             certificate_fingerprint,
-            registered_at: now,
+            registered_at,
             last_seen: now,
         };
 
+        self.store.upsert(&registration.to_record()).await?;
         self.registrations.write().await.insert(
             agent_id.to_string(),
             registration.clone(),
         );
 
         info!(
-            "🎮 GPU registered: agent={}, gpu={}, tier={}, pricing={}",
+            "🎮 GPU registered: agent={}, gpu={}, tier={}, pricing={}, detection={} ({:.2})",
             agent_id,
             gpu_type.name(),
             registration.tier_config.tier_name,
-            registration.tier_config.pricing_tier
+            registration.tier_config.pricing_tier,
+            detection.method.as_str(),
+            detection.confidence
         );
 
         Ok(registration)
     }
 
-    /// Get registration for an agent
+    /// Get registration for an agent (falls back to the store)
     pub async fn get_registration(&self, agent_id: &str) -> Option<GpuRegistration> {
-        self.registrations.read().await.get(agent_id).cloned()
+        if let Some(registration) = self.registrations.read().await.get(agent_id) {
+            return Some(registration.clone());
+        }
+
+        let record = match self.store.get(agent_id).await {
+            Ok(record) => record?,
+            Err(e) => {
+                warn!("⚠️ Registration lookup failed for {}: {}", agent_id, e);
+                return None;
+            }
+        };
+        let registration = GpuRegistration::from_record(&record, &self.catalog_entry(record.gpu_type)?);
+        self.registrations
+            .write()
+            .await
+            .insert(agent_id.to_string(), registration.clone());
+        Some(registration)
     }
 
-    /// Update last seen timestamp
+    /// Update last seen timestamp of a registered agent (its health reports)
     pub async fn update_last_seen(&self, agent_id: &str) {
-        if let Some(reg) = self.registrations.write().await.get_mut(agent_id) {
-            reg.last_seen = chrono::Utc::now();
+        let now = chrono::Utc::now();
+        match self.registrations.write().await.get_mut(agent_id) {
+            Some(reg) => reg.last_seen = now,
+            None => return,
         }
+        if let Err(e) = self.store.touch(agent_id, now).await {
+            warn!("⚠️ Failed to persist last seen for {}: {}", agent_id, e);
+        }
+    }
+
+    /// Fleet inventory from the registration store
+    pub async fn inventory(&self, filter: &InventoryFilter) -> Result<GpuInventory, String> {
+        Ok(GpuInventory::from_records(self.store.list(filter).await?))
     }
 
     /// Get all registrations
@@ -1045,14 +1657,14 @@ impl GpuDetectionManager {
         self.registrations.read().await.values().cloned().collect()
     }
 
-    /// Get supported GPUs list
+    /// Get supported GPUs list from the loaded catalog
     pub fn get_supported_gpus(&self) -> Vec<(BaselineGpuType, GpuSpecifications, GpuTierConfig)> {
-        GpuSpecifications::all_supported()
-            .into_iter()
-            .map(|specs| {
-                let tier = GpuTierConfig::from_gpu(&specs);
-                (specs.gpu_type, specs, tier)
-            })
+        self.catalog
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries()
+            .iter()
+            .map(|entry| (entry.gpu_type(), entry.specs.clone(), entry.tier_config()))
             .collect()
     }
 }
\ No newline at end of file
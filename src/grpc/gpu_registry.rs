@@ -0,0 +1,386 @@
+//! CYAN FLAME™ GPU Registration Store
+//!
+//! Persists GPU registrations so the fleet inventory survives restarts.
+//! `InMemoryRegistrationStore` is the default; `SqliteRegistrationStore`
+//! (feature `sqlite-registry`) writes to a SQLite database file.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::gpu_detection::BaselineGpuType;
+
+/// Persisted registration: what the agent reported and what it was assigned
+#[derive(Clone, Debug, PartialEq)]
+pub struct GpuRegistrationRecord {
+    pub agent_id: String,
+    pub api_key_hash: String,
+    pub gpu_type: BaselineGpuType,
+    /// Name reported by the agent (e.g. "NVIDIA H100 80GB HBM3")
+    pub gpu_name: String,
+    /// Reported VRAM, or the catalog value when the agent did not report it
+    pub vram_gb: u64,
+    pub driver_version: String,
+    pub tier_name: String,
+    pub pricing_tier: String,
+    pub detection_method: String,
+    pub detection_confidence: f64,
+    pub certificate_fingerprint: Option<String>,
+    pub registered_at: chrono::DateTime<chrono::Utc>,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+}
+
+/// Inventory query; unset fields match everything
+#[derive(Clone, Debug, Default)]
+pub struct InventoryFilter {
+    pub gpu_type: Option<BaselineGpuType>,
+    pub tier_name: Option<String>,
+    pub agent_id: Option<String>,
+}
+
+impl InventoryFilter {
+    pub fn matches(&self, record: &GpuRegistrationRecord) -> bool {
+        self.gpu_type.is_none_or(|t| t == record.gpu_type)
+            && self
+                .tier_name
+                .as_deref()
+                .is_none_or(|t| t == record.tier_name)
+            && self
+                .agent_id
+                .as_deref()
+                .is_none_or(|a| a == record.agent_id)
+    }
+}
+
+/// Per-model inventory totals
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelSummary {
+    pub gpu_type: BaselineGpuType,
+    pub count: u64,
+    pub total_vram_gb: u64,
+}
+
+/// Inventory query result
+#[derive(Clone, Debug, Default)]
+pub struct GpuInventory {
+    /// Matching registrations, ordered by agent ID
+    pub records: Vec<GpuRegistrationRecord>,
+    /// Totals per GPU model, in proto order
+    pub models: Vec<ModelSummary>,
+}
+
+impl GpuInventory {
+    /// Build an inventory from matching records
+    pub fn from_records(mut records: Vec<GpuRegistrationRecord>) -> Self {
+        records.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+
+        let mut totals: BTreeMap<i32, ModelSummary> = BTreeMap::new();
+        for record in &records {
+            let summary =
+                totals
+                    .entry(record.gpu_type.to_proto())
+                    .or_insert_with(|| ModelSummary {
+                        gpu_type: record.gpu_type,
+                        count: 0,
+                        total_vram_gb: 0,
+                    });
+            summary.count += 1;
+            summary.total_vram_gb += record.vram_gb;
+        }
+
+        Self {
+            records,
+            models: totals.into_values().collect(),
+        }
+    }
+
+    pub fn total_count(&self) -> u64 {
+        self.records.len() as u64
+    }
+
+    pub fn total_vram_gb(&self) -> u64 {
+        self.models.iter().map(|m| m.total_vram_gb).sum()
+    }
+}
+
+/// Storage backend for GPU registrations
+#[async_trait::async_trait]
+pub trait RegistrationStore: Send + Sync {
+    /// Insert or replace the registration for `record.agent_id`
+    async fn upsert(&self, record: &GpuRegistrationRecord) -> Result<(), String>;
+
+    /// Registration for an agent
+    async fn get(&self, agent_id: &str) -> Result<Option<GpuRegistrationRecord>, String>;
+
+    /// Registrations matching a filter
+    async fn list(&self, filter: &InventoryFilter) -> Result<Vec<GpuRegistrationRecord>, String>;
+
+    /// Update last-seen time; returns false if the agent is not registered
+    async fn touch(
+        &self,
+        agent_id: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, String>;
+
+    /// Remove a registration; returns false if the agent is not registered
+    async fn remove(&self, agent_id: &str) -> Result<bool, String>;
+}
+
+/// In-memory registration store (lost on restart)
+#[derive(Default)]
+pub struct InMemoryRegistrationStore {
+    records: Arc<RwLock<HashMap<String, GpuRegistrationRecord>>>,
+}
+
+impl InMemoryRegistrationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl RegistrationStore for InMemoryRegistrationStore {
+    async fn upsert(&self, record: &GpuRegistrationRecord) -> Result<(), String> {
+        self.records
+            .write()
+            .await
+            .insert(record.agent_id.clone(), record.clone());
+        Ok(())
+    }
+
+    async fn get(&self, agent_id: &str) -> Result<Option<GpuRegistrationRecord>, String> {
+        Ok(self.records.read().await.get(agent_id).cloned())
+    }
+
+    async fn list(&self, filter: &InventoryFilter) -> Result<Vec<GpuRegistrationRecord>, String> {
+        Ok(self
+            .records
+            .read()
+            .await
+            .values()
+            .filter(|r| filter.matches(r))
+            .cloned()
+            .collect())
+    }
+
+    async fn touch(
+        &self,
+        agent_id: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, String> {
+        match self.records.write().await.get_mut(agent_id) {
+            Some(record) => {
+                record.last_seen = at;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn remove(&self, agent_id: &str) -> Result<bool, String> {
+        Ok(self.records.write().await.remove(agent_id).is_some())
+    }
+}
+
+#[cfg(feature = "sqlite-registry")]
+pub use sqlite::SqliteRegistrationStore;
+
+#[cfg(feature = "sqlite-registry")]
+mod sqlite {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+    use sqlx::Row;
+    use std::str::FromStr;
+    use tokio::sync::OnceCell;
+
+    const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS gpu_registrations (
+        agent_id TEXT PRIMARY KEY NOT NULL,
+        api_key_hash TEXT NOT NULL,
+        gpu_type INTEGER NOT NULL,
+        gpu_name TEXT NOT NULL,
+        vram_gb INTEGER NOT NULL,
+        driver_version TEXT NOT NULL,
+        tier_name TEXT NOT NULL,
+        pricing_tier TEXT NOT NULL,
+        detection_method TEXT NOT NULL,
+        detection_confidence REAL NOT NULL,
+        certificate_fingerprint TEXT,
+        registered_at TEXT NOT NULL,
+        last_seen TEXT NOT NULL
+    )";
+
+    /// SQLite registration store
+    ///
+    /// The pool connects lazily and the schema is created on first use, so
+    /// the store can be built from synchronous server setup code.
+    pub struct SqliteRegistrationStore {
+        pool: SqlitePool,
+        schema: OnceCell<()>,
+    }
+
+    impl SqliteRegistrationStore {
+        /// Open (creating if needed) a database file, or any `sqlite:` URL
+        pub fn open(path: &str) -> Result<Self, String> {
+            let url = if path.starts_with("sqlite:") {
+                path.to_string()
+            } else {
+                format!("sqlite://{}", path)
+            };
+            let options = SqliteConnectOptions::from_str(&url)
+                .map_err(|e| format!("Invalid registration database '{}': {}", path, e))?
+                .create_if_missing(true);
+
+            Ok(Self {
+                pool: SqlitePoolOptions::new()
+                    .max_connections(4)
+                    .connect_lazy_with(options),
+                schema: OnceCell::new(),
+            })
+        }
+
+        async fn pool(&self) -> Result<&SqlitePool, String> {
+            self.schema
+                .get_or_try_init(|| async {
+                    sqlx::query(SCHEMA)
+                        .execute(&self.pool)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| format!("Failed to initialise registration database: {}", e))
+                })
+                .await?;
+            Ok(&self.pool)
+        }
+
+        fn from_row(row: &SqliteRow) -> Result<GpuRegistrationRecord, sqlx::Error> {
+            let parse_time = |column: &str| -> Result<chrono::DateTime<chrono::Utc>, sqlx::Error> {
+                let value: String = row.try_get(column)?;
+                chrono::DateTime::parse_from_rfc3339(&value)
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+            };
+
+            Ok(GpuRegistrationRecord {
+                agent_id: row.try_get("agent_id")?,
+                api_key_hash: row.try_get("api_key_hash")?,
+                gpu_type: BaselineGpuType::from_proto(row.try_get("gpu_type")?),
+                gpu_name: row.try_get("gpu_name")?,
+                vram_gb: row.try_get::<i64, _>("vram_gb")? as u64,
+                driver_version: row.try_get("driver_version")?,
+                tier_name: row.try_get("tier_name")?,
+                pricing_tier: row.try_get("pricing_tier")?,
+                detection_method: row.try_get("detection_method")?,
+                detection_confidence: row.try_get("detection_confidence")?,
+                certificate_fingerprint: row.try_get("certificate_fingerprint")?,
+                registered_at: parse_time("registered_at")?,
+                last_seen: parse_time("last_seen")?,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl RegistrationStore for SqliteRegistrationStore {
+        async fn upsert(&self, record: &GpuRegistrationRecord) -> Result<(), String> {
+            sqlx::query(
+                "INSERT INTO gpu_registrations (
+                    agent_id, api_key_hash, gpu_type, gpu_name, vram_gb, driver_version,
+                    tier_name, pricing_tier, detection_method, detection_confidence,
+                    certificate_fingerprint, registered_at, last_seen
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(agent_id) DO UPDATE SET
+                    api_key_hash = excluded.api_key_hash,
+                    gpu_type = excluded.gpu_type,
+                    gpu_name = excluded.gpu_name,
+                    vram_gb = excluded.vram_gb,
+                    driver_version = excluded.driver_version,
+                    tier_name = excluded.tier_name,
+                    pricing_tier = excluded.pricing_tier,
+                    detection_method = excluded.detection_method,
+                    detection_confidence = excluded.detection_confidence,
+                    certificate_fingerprint = excluded.certificate_fingerprint,
+                    registered_at = excluded.registered_at,
+                    last_seen = excluded.last_seen",
+            )
+            .bind(&record.agent_id)
+            .bind(&record.api_key_hash)
+            .bind(record.gpu_type.to_proto())
+            .bind(&record.gpu_name)
+            .bind(record.vram_gb as i64)
+            .bind(&record.driver_version)
+            .bind(&record.tier_name)
+            .bind(&record.pricing_tier)
+            .bind(&record.detection_method)
+            .bind(record.detection_confidence)
+            .bind(&record.certificate_fingerprint)
+            .bind(record.registered_at.to_rfc3339())
+            .bind(record.last_seen.to_rfc3339())
+            .execute(self.pool().await?)
+            .await
+            .map(|_| ())
+            .map_err(|e| {
+                format!(
+                    "Failed to store registration for {}: {}",
+                    record.agent_id, e
+                )
+            })
+        }
+
+        async fn get(&self, agent_id: &str) -> Result<Option<GpuRegistrationRecord>, String> {
+            let row = sqlx::query("SELECT * FROM gpu_registrations WHERE agent_id = ?")
+                .bind(agent_id)
+                .fetch_optional(self.pool().await?)
+                .await
+                .map_err(|e| format!("Failed to load registration for {}: {}", agent_id, e))?;
+
+            row.map(|r| Self::from_row(&r))
+                .transpose()
+                .map_err(|e| format!("Corrupt registration for {}: {}", agent_id, e))
+        }
+
+        async fn list(
+            &self,
+            filter: &InventoryFilter,
+        ) -> Result<Vec<GpuRegistrationRecord>, String> {
+            let rows = sqlx::query(
+                "SELECT * FROM gpu_registrations
+                 WHERE (?1 IS NULL OR gpu_type = ?1)
+                   AND (?2 IS NULL OR tier_name = ?2)
+                   AND (?3 IS NULL OR agent_id = ?3)
+                 ORDER BY agent_id",
+            )
+            .bind(filter.gpu_type.map(|t| t.to_proto()))
+            .bind(filter.tier_name.as_deref())
+            .bind(filter.agent_id.as_deref())
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(|e| format!("Failed to list registrations: {}", e))?;
+
+            rows.iter()
+                .map(Self::from_row)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Corrupt registration: {}", e))
+        }
+
+        async fn touch(
+            &self,
+            agent_id: &str,
+            at: chrono::DateTime<chrono::Utc>,
+        ) -> Result<bool, String> {
+            sqlx::query("UPDATE gpu_registrations SET last_seen = ? WHERE agent_id = ?")
+                .bind(at.to_rfc3339())
+                .bind(agent_id)
+                .execute(self.pool().await?)
+                .await
+                .map(|r| r.rows_affected() > 0)
+                .map_err(|e| format!("Failed to update last seen for {}: {}", agent_id, e))
+        }
+
+        async fn remove(&self, agent_id: &str) -> Result<bool, String> {
+            sqlx::query("DELETE FROM gpu_registrations WHERE agent_id = ?")
+                .bind(agent_id)
+                .execute(self.pool().await?)
+                .await
+                .map(|r| r.rows_affected() > 0)
+                .map_err(|e| format!("Failed to remove registration for {}: {}", agent_id, e))
+        }
+    }
+}
@@ -4,7 +4,11 @@
 //! - GPU registration with automatic tier detection
 //! - Tiered pricing based on baseline GPU
 //! - Supported GPU enumeration
+//! - GPU catalog reload (admin)
+//! - Fleet inventory (admin)
+//! - Usage metering and billing export
 
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use tracing::{info, warn};
@@ -22,78 +26,131 @@ use super::proto::{
     SupportedGpuInfo,
     BaselineGpuType as ProtoGpuType,
     CostEstimate,
+    ReloadGpuCatalogRequest,
+    ReloadGpuCatalogResponse,
+    GpuInventoryRequest,
+    GpuInventoryResponse,
+    GpuInventoryRecord,
+    GpuModelInventory,
+    UsageRequest,
+    UsageResponse,
+    HourlyUsage,
 };
 
-use super::gpu_detection::{GpuDetectionManager, BaselineGpuType, GpuSpecifications};
+use super::gpu_catalog::{CatalogFormat, GpuCatalog, GpuCatalogError};
+use super::gpu_detection::{
+    AmplificationTargets, BaselineGpuType, GpuDetectionManager, GpuProbe, GpuSpecifications,
+    GpuTierConfig,
+};
+use super::gpu_registry::InventoryFilter;
+use super::auth::AuthManager;
+use super::metering::{BillingPeriod, ExportFormat, UsageFilter, UsageMeter, UsageRecord};
 
 /// GPU Detection Service Implementation
 pub struct GpuDetectionServiceImpl {
     detection_manager: Arc<GpuDetectionManager>,
+    /// Catalog file reloaded when ReloadGpuCatalog names no path
+    catalog_path: Option<String>,
+    /// API key for admin RPCs (admin RPCs are rejected when unset)
+    admin_api_key: Option<String>,
+    /// Usage meter; registrations bind agents to their pricing tier
+    meter: Arc<UsageMeter>,
 }
 
 impl GpuDetectionServiceImpl {
     pub fn new() -> Self {
-        Self {
-            detection_manager: Arc::new(GpuDetectionManager::new()),
-        }
+        Self::with_manager(Arc::new(GpuDetectionManager::new()))
     }
 
     pub fn with_manager(manager: Arc<GpuDetectionManager>) -> Self {
         Self {
             detection_manager: manager,
+            catalog_path: None,
+            admin_api_key: None,
+            meter: Arc::new(UsageMeter::new()),
         }
     }
 
-    /// Detect GPU type from registration request
-    fn detect_gpu_type(&self, req: &GpuRegistrationRequest) -> BaselineGpuType {
-        if let Some(gpu) = &req.detected_gpu {
-            // Match by GPU name
-            let name = gpu.name.to_lowercase();
-            
-            if name.contains("h200") {
-                BaselineGpuType::NvidiaH200
-            } else if name.contains("h100") {
-                BaselineGpuType::NvidiaH100
-            } else if name.contains("a100") {
-                BaselineGpuType::NvidiaA100
-            } else if name.contains("v100") {
-                BaselineGpuType::NvidiaV100
-            } else if name.contains("l40s") {
-                BaselineGpuType::NvidiaL40S
-            } else if name.contains("5090") {
-                BaselineGpuType::NvidiaRtx5090
-            } else if name.contains("4090") {
-                BaselineGpuType::NvidiaRtx4090
-            } else if name.contains("mi300") {
-                BaselineGpuType::AmdMi300X
-            } else if name.contains("mi250") {
-                BaselineGpuType::AmdMi250
-            } else if name.contains("mi100") {
-                BaselineGpuType::AmdMi100
-            } else {
-                // Try to detect by compute capability for NVIDIA
-                if gpu.vendor.to_uppercase() == "NVIDIA" {
-                    match (gpu.compute_capability_major, gpu.compute_capability_minor) {
-                        (9, 0) => BaselineGpuType::NvidiaH100,
-                        (8, 9) => BaselineGpuType::NvidiaRtx4090, // Ada Lovelace
-                        (8, 0) => BaselineGpuType::NvidiaA100,
-                        (7, 0) => BaselineGpuType::NvidiaV100,
-                        _ => BaselineGpuType::Unknown,
-                    }
-                } else {
-                    BaselineGpuType::Unknown
-                }
-            }
+    /// Share a usage meter with the other services
+    pub fn with_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.meter = meter;
+        self
+    }
+
+    /// Load the catalog from a file and remember the path for reloads
+    pub fn with_catalog_path(mut self, path: impl Into<String>) -> Result<Self, GpuCatalogError> {
+        let path = path.into();
+        self.detection_manager.replace_catalog(GpuCatalog::load(&path)?);
+        self.catalog_path = Some(path);
+        Ok(self)
+    }
+
+    /// Enable admin RPCs with the given API key
+    pub fn with_admin_key(mut self, api_key: impl Into<String>) -> Self {
+        self.admin_api_key = Some(api_key.into());
+        self
+    }
+
+    /// Shared detection manager
+    pub fn detection_manager(&self) -> Arc<GpuDetectionManager> {
+        self.detection_manager.clone()
+    }
+
+    /// Check the admin API key
+    fn authorize_admin(&self, api_key: &str) -> Result<(), &'static str> {
+        match &self.admin_api_key {
+            None => Err("Admin RPCs are disabled (no admin API key configured)"),
+            Some(key) if keys_match(key, api_key) => Ok(()),
+            Some(_) => Err("Invalid admin API key"),
+        }
+    }
+
+    /// Reject reload paths other than the configured catalog file
+    ///
+    /// Admins send any other catalog inline, so the RPC cannot be used to probe
+    /// arbitrary files on the server.
+    fn check_reload_path(&self, path: &str) -> Result<(), &'static str> {
+        if path.is_empty() || self.catalog_path.as_deref() == Some(path) {
+            Ok(())
         } else {
-            BaselineGpuType::Unknown
+            Err("Only the configured catalog path can be reloaded; send other catalogs as content")
         }
     }
 
-    /// Convert internal tier config to proto
-    fn to_proto_tier_config(&self, specs: &GpuSpecifications) -> ProtoTierConfig {
-        let tier = super::gpu_detection::GpuTierConfig::from_gpu(specs);
-        let amp = super::gpu_detection::AmplificationTargets::from_gpu(specs);
+    /// Build the catalog requested by a reload: inline content, a file, or the built-in table
+    fn catalog_for_reload(&self, req: &ReloadGpuCatalogRequest) -> Result<GpuCatalog, GpuCatalogError> {
+        if !req.content.is_empty() {
+            let format = CatalogFormat::from_name(if req.format.is_empty() { "toml" } else { &req.format })?;
+            return GpuCatalog::parse_overrides(&req.content, format);
+        }
 
+        let path = if req.path.is_empty() { self.catalog_path.as_deref() } else { Some(req.path.as_str()) };
+        match path {
+            Some(path) => GpuCatalog::load(path),
+            None => Ok(GpuCatalog::builtin()),
+        }
+    }
+
+    /// Build a detection probe from the agent-reported GPU info
+    fn probe_from_request(req: &GpuRegistrationRequest) -> GpuProbe {
+        match &req.detected_gpu {
+            Some(gpu) => GpuProbe {
+                name: gpu.name.clone(),
+                vendor: gpu.vendor.clone(),
+                pci_device_id: gpu.pci_device_id.clone(),
+                compute_capability: match (gpu.compute_capability_major, gpu.compute_capability_minor) {
+                    (0, 0) => None,
+                    cc => Some(cc),
+                },
+                memory_mb: Some(gpu.memory_total_mb).filter(|mb| *mb > 0),
+                driver_version: gpu.driver_version.clone(),
+            },
+            None => GpuProbe::default(),
+        }
+    }
+
+    /// Convert internal tier config to proto
+    fn to_proto_tier_config(&self, tier: GpuTierConfig, amp: &AmplificationTargets) -> ProtoTierConfig {
         ProtoTierConfig {
             tier_name: tier.tier_name,
             memory_bandwidth_amplification: amp.memory_bandwidth_multiplier,
@@ -110,7 +167,7 @@ impl GpuDetectionServiceImpl {
 
     /// Convert to proto amplification targets
     fn to_proto_amp_targets(&self, specs: &GpuSpecifications) -> ProtoAmpTargets {
-        let amp = super::gpu_detection::AmplificationTargets::from_gpu(specs);
+        let amp = AmplificationTargets::from_gpu(specs);
 
         ProtoAmpTargets {
             memory_bandwidth_multiplier: amp.memory_bandwidth_multiplier,
@@ -128,6 +185,22 @@ impl GpuDetectionServiceImpl {
         }
     }
 
+    fn to_proto_usage(record: &UsageRecord) -> HourlyUsage {
+        HourlyUsage {
+            api_key_hash: record.api_key_hash.clone(),
+            tier: record.tier.clone(),
+            hour_start_ms: record.hour_start_ms,
+            memory_byte_seconds: u64::try_from(record.memory_byte_seconds).unwrap_or(u64::MAX),
+            memory_tb_hours: record.memory_tb_hours(),
+            calibration_stream_minutes: record.calibration_stream_minutes(),
+            api_calls: record.api_calls,
+            memory_cost_usd: record.memory_cost_usd,
+            stream_cost_usd: record.stream_cost_usd,
+            api_cost_usd: record.api_cost_usd,
+            total_cost_usd: record.total_cost_usd,
+        }
+    }
+
     /// Calculate cost estimate based on GPU specs and amplification
     fn calculate_cost_estimate(&self, specs: &GpuSpecifications) -> CostEstimate {
         // H100 as reference target
@@ -166,6 +239,16 @@ impl Default for GpuDetectionServiceImpl {
     }
 }
 
+/// Compare API keys in constant time
+///
+/// Hashing first fixes the length, so the comparison does not depend on how
+/// much of the expected key a guess got right.
+fn keys_match(expected: &str, given: &str) -> bool {
+    let expected = Sha256::digest(expected.as_bytes());
+    let given = Sha256::digest(given.as_bytes());
+    expected.iter().zip(given.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
 #[tonic::async_trait]
 impl GpuDetectionService for GpuDetectionServiceImpl {
     /// Register GPU and get tiered pricing based on detected hardware
@@ -173,17 +256,23 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
         &self,
         request: Request<GpuRegistrationRequest>,
     ) -> Result<Response<GpuRegistrationResponse>, Status> {
+        let api_key = AuthManager::caller_key(&request, &request.get_ref().api_key);
         let req = request.into_inner();
         info!("📝 GPU registration request from agent: {}", req.agent_id);
 
         // Detect GPU type from request
-        let gpu_type = self.detect_gpu_type(&req);
+        let probe = Self::probe_from_request(&req);
+        let detection = self.detection_manager.detect(&probe);
+        let gpu_type = detection.gpu_type;
 
         if !gpu_type.is_supported() {
             warn!("⚠️  Unsupported GPU detected for agent {}", req.agent_id);
             return Ok(Response::new(GpuRegistrationResponse {
                 success: false,
-                error_message: "Unsupported GPU. Only V100, A100, H100, H200, L40S, RTX 4090, RTX 5090, MI100, MI250, MI300X are supported.".to_string(),
+                error_message: format!(
+                    "Unsupported GPU. Supported GPUs: {}",
+                    GpuDetectionManager::supported_gpu_names()
+                ),
                 detected_baseline: ProtoGpuType::GpuUnknown as i32,
                 baseline_name: "Unknown".to_string(),
                 target_gpu: 1, // Default to H100
@@ -192,19 +281,11 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
                 amplification_targets: None,
                 certificate_binding: String::new(),
                 cost_estimate: None,
+                detection_confidence: detection.confidence,
+                detection_method: detection.method.as_str().to_string(),
             }));
         }
 
-        // Get GPU name from request
-        let gpu_name = req.detected_gpu.as_ref()
-            .map(|g| g.name.clone())
-            .unwrap_or_else(|| "Unknown".to_string());
-
-        // Get specifications and tier config
-        let specs = GpuSpecifications::from_type(gpu_type);
-        let tier_config = self.to_proto_tier_config(&specs);
-        let amp_targets = self.to_proto_amp_targets(&specs);
-
         // Hash API key for storage
         let api_key_hash = format!("{:x}", md5::compute(&req.api_key));
 
@@ -212,10 +293,18 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
         match self.detection_manager.register_gpu(
             &req.agent_id,
             &api_key_hash,
-            &gpu_name,
+            &probe,
             None, // No certificate binding yet
         ).await {
             Ok(registration) => {
+                // Specifications and tier config come from the catalog via the registration
+                let specs = &registration.gpu_specs;
+                let tier_config = self.to_proto_tier_config(
+                    registration.tier_config.clone(),
+                    &registration.amplification_targets,
+                );
+                let amp_targets = self.to_proto_amp_targets(specs);
+
                 info!(
                     "✅ GPU registered: agent={}, gpu={}, tier={}",
                     req.agent_id,
@@ -224,7 +313,11 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
                 );
 
                 // Calculate cost estimate based on amplification
-                let cost_estimate = self.calculate_cost_estimate(&specs);
+                let cost_estimate = self.calculate_cost_estimate(specs);
+                // Unbilled usage would fall to the unassigned tier after a restart
+                self.meter
+                    .bind_agent(&req.agent_id, &api_key, &cost_estimate)
+                    .map_err(Status::unavailable)?;
 
                 Ok(Response::new(GpuRegistrationResponse {
                     success: true,
@@ -235,8 +328,10 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
                     target_name: "NVIDIA H100 80GB HBM3".to_string(),
                     tier_config: Some(tier_config),
                     amplification_targets: Some(amp_targets),
-                    certificate_binding: registration.certificate_fingerprint.unwrap_or_default(),
+                    certificate_binding: registration.certificate_fingerprint.clone().unwrap_or_default(),
                     cost_estimate: Some(cost_estimate),
+                    detection_confidence: registration.detection.confidence,
+                    detection_method: registration.detection.method.as_str().to_string(),
                 }))
             }
             Err(e) => {
@@ -252,6 +347,8 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
                     amplification_targets: None,
                     certificate_binding: String::new(),
                     cost_estimate: None,
+                    detection_confidence: detection.confidence,
+                    detection_method: detection.method.as_str().to_string(),
                 }))
             }
         }
@@ -266,9 +363,11 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
 
         // Look up existing registration
         if let Some(registration) = self.detection_manager.get_registration(&req.agent_id).await {
-            let specs = GpuSpecifications::from_type(registration.detected_gpu);
-            let tier_config = self.to_proto_tier_config(&specs);
-            let amp_targets = self.to_proto_amp_targets(&specs);
+            let tier_config = self.to_proto_tier_config(
+                registration.tier_config.clone(),
+                &registration.amplification_targets,
+            );
+            let amp_targets = self.to_proto_amp_targets(&registration.gpu_specs);
 
             Ok(Response::new(GpuTierResponse {
                 current_baseline: registration.detected_gpu.to_proto(),
@@ -290,12 +389,12 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
         let supported_gpus: Vec<SupportedGpuInfo> = supported
             .into_iter()
             .map(|(gpu_type, specs, tier)| {
-                let amp = super::gpu_detection::AmplificationTargets::from_gpu(&specs);
+                let amp = AmplificationTargets::from_gpu(&specs);
                 SupportedGpuInfo {
                     gpu_type: gpu_type.to_proto(),
                     name: gpu_type.name().to_string(),
                     architecture: specs.architecture.clone(),
-                    vendor: if specs.architecture.contains("CDNA") { "AMD" } else { "NVIDIA" }.to_string(),
+                    vendor: specs.vendor.clone(),
                     vram_gb: specs.vram_gb,
                     memory_bandwidth_gbs: specs.memory_bandwidth_gbs,
                     fp16_tflops: specs.fp16_tflops,
@@ -310,5 +409,175 @@ impl GpuDetectionService for GpuDetectionServiceImpl {
             supported_gpus,
         }))
     }
-}
 
+    /// Reload the GPU catalog (admin only)
+    async fn reload_gpu_catalog(
+        &self,
+        request: Request<ReloadGpuCatalogRequest>,
+    ) -> Result<Response<ReloadGpuCatalogResponse>, Status> {
+        let req = request.into_inner();
+        self.authorize_admin(&req.api_key).map_err(Status::permission_denied)?;
+        self.check_reload_path(&req.path).map_err(Status::permission_denied)?;
+
+        match self.catalog_for_reload(&req) {
+            Ok(catalog) => {
+                let gpu_count = catalog.len() as u32;
+                let source = catalog.source().to_string();
+                self.detection_manager.replace_catalog(catalog);
+
+                Ok(Response::new(ReloadGpuCatalogResponse {
+                    success: true,
+                    error_message: String::new(),
+                    gpu_count,
+                    source,
+                }))
+            }
+            Err(e) => {
+                // Keep serving the previous catalog
+                warn!("❌ GPU catalog reload failed: {}", e);
+                let current = self.detection_manager.catalog();
+
+                Ok(Response::new(ReloadGpuCatalogResponse {
+                    success: false,
+                    error_message: e.to_string(),
+                    gpu_count: current.len() as u32,
+                    source: current.source().to_string(),
+                }))
+            }
+        }
+    }
+
+    /// Fleet inventory with optional model/tier/agent filters (admin only)
+    async fn get_gpu_inventory(
+        &self,
+        request: Request<GpuInventoryRequest>,
+    ) -> Result<Response<GpuInventoryResponse>, Status> {
+        let req = request.into_inner();
+        self.authorize_admin(&req.api_key).map_err(Status::permission_denied)?;
+
+        let gpu_type = BaselineGpuType::from_proto(req.gpu_type);
+        let filter = InventoryFilter {
+            gpu_type: gpu_type.is_supported().then_some(gpu_type),
+            tier_name: Some(req.tier_name).filter(|t| !t.is_empty()),
+            agent_id: Some(req.agent_id).filter(|a| !a.is_empty()),
+        };
+
+        let inventory = self
+            .detection_manager
+            .inventory(&filter)
+            .await
+            .map_err(Status::internal)?;
+
+        let registrations = inventory
+            .records
+            .iter()
+            .map(|r| GpuInventoryRecord {
+                agent_id: r.agent_id.clone(),
+                gpu_type: r.gpu_type.to_proto(),
+                gpu_name: r.gpu_name.clone(),
+                vram_gb: r.vram_gb,
+                driver_version: r.driver_version.clone(),
+                tier_name: r.tier_name.clone(),
+                pricing_tier: r.pricing_tier.clone(),
+                detection_method: r.detection_method.clone(),
+                detection_confidence: r.detection_confidence,
+                registered_at_ms: r.registered_at.timestamp_millis(),
+                last_seen_ms: r.last_seen.timestamp_millis(),
+            })
+            .collect();
+
+        let models = inventory
+            .models
+            .iter()
+            .map(|m| GpuModelInventory {
+                gpu_type: m.gpu_type.to_proto(),
+                name: m.gpu_type.name().to_string(),
+                count: m.count,
+                total_vram_gb: m.total_vram_gb,
+            })
+            .collect();
+
+        Ok(Response::new(GpuInventoryResponse {
+            registrations,
+            models,
+            total_count: inventory.total_count(),
+            total_vram_gb: inventory.total_vram_gb(),
+        }))
+    }
+
+    /// Closed hourly usage for a billing period, optionally exported as CSV/JSON
+    async fn get_usage(
+        &self,
+        request: Request<UsageRequest>,
+    ) -> Result<Response<UsageResponse>, Status> {
+        let req = request.into_inner();
+        let period = if req.period_start_ms == 0 && req.period_end_ms == 0 {
+            BillingPeriod::month_of(self.meter.now_ms())
+                .ok_or_else(|| Status::internal("Clock is outside the supported date range"))?
+        } else {
+            BillingPeriod::new(req.period_start_ms, req.period_end_ms).map_err(Status::invalid_argument)?
+        };
+
+        // Non-admin callers only see their own key
+        let api_key_hash = if self.authorize_admin(&req.api_key).is_ok() {
+            Some(req.api_key_hash.clone()).filter(|h| !h.is_empty())
+        } else {
+            if req.api_key.is_empty() {
+                return Err(Status::unauthenticated("API key is required"));
+            }
+            let own_hash = AuthManager::hash_key(&req.api_key);
+            if !req.api_key_hash.is_empty() && req.api_key_hash != own_hash {
+                return Err(Status::permission_denied("Admin API key required to query other keys"));
+            }
+            Some(own_hash)
+        };
+
+        let filter = UsageFilter {
+            period,
+            api_key_hash,
+            tier: Some(req.tier.clone()).filter(|t| !t.is_empty()),
+        };
+
+        let export_format = if req.export_format.is_empty() {
+            None
+        } else {
+            match ExportFormat::from_name(&req.export_format) {
+                Some(format) => Some(format),
+                None => {
+                    return Ok(Response::new(UsageResponse {
+                        success: false,
+                        error_message: format!("Unsupported export format: {}", req.export_format),
+                        period_start_ms: filter.period.start_ms,
+                        period_end_ms: filter.period.end_ms,
+                        ..Default::default()
+                    }));
+                }
+            }
+        };
+
+        // Close any finished hours before reporting
+        self.meter.rollup();
+        let report = self.meter.report(&filter);
+
+        let export = match export_format.map(|f| report.export(f)).transpose() {
+            Ok(export) => export.unwrap_or_default(),
+            Err(e) => {
+                warn!("❌ Usage export failed: {}", e);
+                return Err(Status::internal(e));
+            }
+        };
+
+        Ok(Response::new(UsageResponse {
+            success: true,
+            error_message: String::new(),
+            period_start_ms: report.period.start_ms,
+            period_end_ms: report.period.end_ms,
+            records: report.records.iter().map(Self::to_proto_usage).collect(),
+            memory_tb_hours: report.totals.memory_tb_hours,
+            calibration_stream_minutes: report.totals.calibration_stream_minutes,
+            api_calls: report.totals.api_calls,
+            total_cost_usd: report.totals.total_cost_usd,
+            export,
+        }))
+    }
+}
@@ -0,0 +1,201 @@
+//! HTTP Endpoints of the Servers
+//!
+//! Typed request/response shapes and routers for the plain HTTP endpoints of
+//! `cyan-flame-unified` and `symmetrix-daemon`. Both are built on
+//! [`HttpApi`], so every route here appears in their `/openapi.json`.
+
+use std::sync::Arc;
+
+use axum::Json;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+
+use super::openapi::{ApiSchema, HttpApi};
+use crate::{RuntimeStats, SymmetrixRuntime};
+
+/// gRPC port advertised by `/grpc`
+pub const GRPC_PORT: u16 = 50051;
+
+/// Services advertised by `/grpc`
+pub const GRPC_SERVICES: &[&str] = &[
+    "CalibrationService",
+    "ComputeCalibrationService",
+    "PCIeAmplificationService",
+    "TelemetryService",
+    "AllocationService",
+    "OperationsService",
+    "GpuDetectionService",
+    "CertificateService",
+    "DashboardMetricsService",
+];
+
+crate::api_schema! {
+    /// Liveness probe
+    #[derive(Debug, Clone, Serialize)]
+    pub struct HealthStatus {
+        /// Always `healthy` while the server answers
+        pub status: String,
+    }
+}
+
+impl HealthStatus {
+    pub fn healthy() -> Self {
+        Self {
+            status: "healthy".to_string(),
+        }
+    }
+}
+
+crate::api_schema! {
+    /// Service banner
+    #[derive(Debug, Clone, Serialize)]
+    pub struct ServerStatus {
+        pub status: String,
+        pub service: String,
+        /// API keys are required on gRPC and gateway calls
+        pub auth_enabled: bool,
+    }
+}
+
+crate::api_schema! {
+    /// How to reach the gRPC services
+    #[derive(Debug, Clone, Serialize)]
+    pub struct GrpcInfo {
+        pub grpc_port: u16,
+        pub auth_required: bool,
+        pub services: Vec<&'static str>,
+        /// Prefix of the REST/JSON gateway routes
+        pub http_gateway: String,
+    }
+}
+
+/// Request rate limit of a tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimit {
+    Requests(u32),
+    Unlimited,
+}
+
+impl Serialize for RateLimit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Requests(limit) => serializer.serialize_u32(*limit),
+            Self::Unlimited => serializer.serialize_str("unlimited"),
+        }
+    }
+}
+
+impl ApiSchema for RateLimit {
+    fn schema() -> Value {
+        json!({ "oneOf": [u32::schema(), { "const": "unlimited" }] })
+    }
+}
+
+crate::api_schema! {
+    /// A subscription tier
+    #[derive(Debug, Clone, Serialize)]
+    pub struct TierInfo {
+        pub name: &'static str,
+        /// Effective memory amplification factor
+        pub amplification: u32,
+        pub max_allocation_tb: u32,
+        pub rate_limit: RateLimit,
+        pub price: &'static str,
+    }
+}
+
+crate::api_schema! {
+    /// Subscription tiers
+    #[derive(Debug, Clone, Serialize)]
+    pub struct TiersResponse {
+        pub tiers: Vec<TierInfo>,
+    }
+}
+
+/// Published subscription tiers
+pub fn subscription_tiers() -> Vec<TierInfo> {
+    vec![
+        TierInfo {
+            name: "free",
+            amplification: 100,
+            max_allocation_tb: 2,
+            rate_limit: RateLimit::Requests(100),
+            price: "$0/month",
+        },
+        TierInfo {
+            name: "starter",
+            amplification: 1000,
+            max_allocation_tb: 24,
+            rate_limit: RateLimit::Requests(1000),
+            price: "$99/month",
+        },
+        TierInfo {
+            name: "pro",
+            amplification: 10000,
+            max_allocation_tb: 240,
+            rate_limit: RateLimit::Requests(10000),
+            price: "$499/month",
+        },
+        TierInfo {
+            name: "enterprise",
+            amplification: 24500,
+            max_allocation_tb: 574,
+            rate_limit: RateLimit::Unlimited,
+            price: "$2,499+/month",
+        },
+    ]
+}
+
+/// HTTP API of `cyan-flame-unified`: status endpoints plus the gateway
+pub fn unified_api(auth_enabled: bool, gateway: axum::Router) -> HttpApi {
+    HttpApi::new(
+        "CYAN FLAME Unified",
+        "Status endpoints and the REST/JSON gateway to the CYAN FLAME gRPC services",
+    )
+    .get::<ServerStatus, _, _>("/", "Server", "Service banner", move || async move {
+        Json(ServerStatus {
+            status: "healthy".to_string(),
+            service: "CYAN FLAME Unified".to_string(),
+            auth_enabled,
+        })
+    })
+    .get::<HealthStatus, _, _>("/health", "Server", "Liveness probe", || async {
+        Json(HealthStatus::healthy())
+    })
+    .get::<GrpcInfo, _, _>(
+        "/grpc",
+        "Server",
+        "gRPC endpoint and services",
+        move || async move {
+            Json(GrpcInfo {
+                grpc_port: GRPC_PORT,
+                auth_required: auth_enabled,
+                services: GRPC_SERVICES.to_vec(),
+                http_gateway: "/v1".to_string(),
+            })
+        },
+    )
+    .get::<TiersResponse, _, _>("/tiers", "Server", "Subscription tiers", || async {
+        Json(TiersResponse {
+            tiers: subscription_tiers(),
+        })
+    })
+    .gateway(gateway)
+}
+
+/// HTTP API of `symmetrix-daemon`'s web management interface
+pub fn daemon_api(runtime: Arc<SymmetrixRuntime>) -> HttpApi {
+    HttpApi::new(
+        "SYMMETRIX Daemon",
+        "Web management interface of the SYMMETRIX runtime",
+    )
+    .get::<HealthStatus, _, _>("/health", "Daemon", "Liveness probe", || async {
+        Json(HealthStatus::healthy())
+    })
+    .get::<RuntimeStats, _, _>(
+        "/metrics",
+        "Daemon",
+        "Runtime statistics",
+        move || async move { Json(runtime.get_stats()) },
+    )
+}
@@ -0,0 +1,980 @@
+//! Usage Metering
+//!
+//! Accrues billable usage per API key and pricing tier:
+//! - Allocated effective memory (byte-seconds)
+//! - Calibration stream time
+//! - API calls
+//!
+//! Usage accrues into hourly buckets. Once an hour has closed, `rollup()`
+//! turns its buckets into immutable [`UsageRecord`]s priced with the tier
+//! rates derived from the GPU `CostEstimate`. A tier with no rates is never
+//! billed at zero by accident: its records carry no cost and are flagged
+//! `unpriced` for billing to settle. Keys with no registered GPU bill at the
+//! `unassigned` tier's default rates. The ledger is append-only;
+//! with a [`UsageStore`] (such as [`FileUsageStore`]) every closed record and
+//! agent binding is written out before it takes effect and reloaded after a
+//! restart.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn};
+
+use super::auth::AuthManager;
+use super::proto::CostEstimate;
+
+/// One hour in milliseconds
+pub const HOUR_MS: i64 = 3_600_000;
+
+/// Bytes per TB (decimal, as billed)
+pub const BYTES_PER_TB: u128 = 1_000_000_000_000;
+
+/// Default price of effective memory (USD per TB-hour)
+pub const DEFAULT_MEMORY_USD_PER_TB_HOUR: f64 = 0.05;
+
+/// Default price of API calls (USD per 1,000 calls)
+pub const DEFAULT_API_USD_PER_1K_CALLS: f64 = 0.01;
+
+/// Default price of calibration stream time without a GPU tier (USD per hour)
+pub const DEFAULT_STREAM_USD_PER_HOUR: f64 = 7.50;
+
+/// Tier for API keys with no registered GPU yet
+pub const UNASSIGNED_TIER: &str = "unassigned";
+
+/// Rollup interval for the background task
+pub const ROLLUP_INTERVAL_SECS: u64 = 60;
+
+// ============================================================================
+// CLOCK
+// ============================================================================
+
+/// Time source for metering (milliseconds since the Unix epoch)
+pub trait Clock: Send + Sync {
+    fn now_ms(&self) -> i64;
+}
+
+/// Wall clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64
+    }
+}
+
+/// Manually advanced clock for simulations and tests
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now_ms: AtomicI64,
+}
+
+impl ManualClock {
+    pub fn new(start_ms: i64) -> Self {
+        Self {
+            now_ms: AtomicI64::new(start_ms),
+        }
+    }
+
+    pub fn set(&self, now_ms: i64) {
+        self.now_ms.store(now_ms, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now_ms
+            .fetch_add(by.as_millis() as i64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> i64 {
+        self.now_ms.load(Ordering::SeqCst)
+    }
+}
+
+// ============================================================================
+// RATES AND RECORDS
+// ============================================================================
+
+/// Prices applied to a pricing tier
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UsageRates {
+    /// Calibration stream time (USD per hour); the GPU hourly rate
+    pub stream_usd_per_hour: f64,
+    /// Allocated effective memory (USD per TB-hour)
+    pub memory_usd_per_tb_hour: f64,
+    /// API calls (USD per 1,000 calls)
+    pub api_usd_per_1k_calls: f64,
+}
+
+impl UsageRates {
+    /// Rates for the tier of a GPU cost estimate
+    pub fn from_cost_estimate(estimate: &CostEstimate) -> Self {
+        Self {
+            stream_usd_per_hour: estimate.hourly_rate_usd,
+            ..Self::default()
+        }
+    }
+
+    /// Rates of the unassigned tier; streams bill at the top GPU rate
+    pub fn unassigned() -> Self {
+        Self {
+            stream_usd_per_hour: DEFAULT_STREAM_USD_PER_HOUR,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for UsageRates {
+    fn default() -> Self {
+        Self {
+            stream_usd_per_hour: 0.0,
+            memory_usd_per_tb_hour: DEFAULT_MEMORY_USD_PER_TB_HOUR,
+            api_usd_per_1k_calls: DEFAULT_API_USD_PER_1K_CALLS,
+        }
+    }
+}
+
+/// Billing account: API key (SHA-256 hash) and pricing tier
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UsageAccount {
+    pub api_key_hash: String,
+    pub tier: String,
+}
+
+/// Agent billed to an API key at a GPU tier, set at registration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentBinding {
+    pub agent_id: String,
+    pub api_key_hash: String,
+    pub tier: String,
+    pub rates: UsageRates,
+}
+
+/// Immutable usage for one account over one closed hour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub api_key_hash: String,
+    pub tier: String,
+    pub hour_start_ms: i64,
+    pub memory_byte_seconds: u128,
+    pub calibration_stream_ms: u64,
+    pub api_calls: u64,
+    pub rates: UsageRates,
+    pub memory_cost_usd: f64,
+    pub stream_cost_usd: f64,
+    pub api_cost_usd: f64,
+    pub total_cost_usd: f64,
+    /// The tier had no rates, so the costs are zero and still owed
+    #[serde(default)]
+    pub unpriced: bool,
+}
+
+impl UsageRecord {
+    fn price(
+        account: &UsageAccount,
+        hour_start_ms: i64,
+        bucket: &Bucket,
+        rates: UsageRates,
+    ) -> Self {
+        let memory_byte_seconds = bucket.byte_ms / 1000;
+        let memory_cost_usd = tb_hours(memory_byte_seconds) * rates.memory_usd_per_tb_hour;
+        let stream_cost_usd = bucket.stream_ms as f64 / HOUR_MS as f64 * rates.stream_usd_per_hour;
+        let api_cost_usd = bucket.api_calls as f64 / 1000.0 * rates.api_usd_per_1k_calls;
+
+        Self {
+            api_key_hash: account.api_key_hash.clone(),
+            tier: account.tier.clone(),
+            hour_start_ms,
+            memory_byte_seconds,
+            calibration_stream_ms: bucket.stream_ms,
+            api_calls: bucket.api_calls,
+            rates,
+            memory_cost_usd,
+            stream_cost_usd,
+            api_cost_usd,
+            total_cost_usd: memory_cost_usd + stream_cost_usd + api_cost_usd,
+            unpriced: false,
+        }
+    }
+
+    /// Usage of a tier without rates, at no cost and flagged
+    fn unpriced(account: &UsageAccount, hour_start_ms: i64, bucket: &Bucket) -> Self {
+        let zero = UsageRates {
+            stream_usd_per_hour: 0.0,
+            memory_usd_per_tb_hour: 0.0,
+            api_usd_per_1k_calls: 0.0,
+        };
+        Self {
+            unpriced: true,
+            ..Self::price(account, hour_start_ms, bucket, zero)
+        }
+    }
+
+    pub fn hour_end_ms(&self) -> i64 {
+        self.hour_start_ms + HOUR_MS
+    }
+
+    pub fn memory_tb_hours(&self) -> f64 {
+        tb_hours(self.memory_byte_seconds)
+    }
+
+    pub fn calibration_stream_minutes(&self) -> f64 {
+        self.calibration_stream_ms as f64 / 60_000.0
+    }
+}
+
+fn tb_hours(byte_seconds: u128) -> f64 {
+    byte_seconds as f64 / (BYTES_PER_TB as f64 * 3600.0)
+}
+
+/// Sums over a set of usage records
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageTotals {
+    pub memory_byte_seconds: u128,
+    pub memory_tb_hours: f64,
+    pub calibration_stream_minutes: f64,
+    pub api_calls: u64,
+    pub memory_cost_usd: f64,
+    pub stream_cost_usd: f64,
+    pub api_cost_usd: f64,
+    pub total_cost_usd: f64,
+    /// Records whose tier had no rates, not included in the costs
+    pub unpriced_records: u64,
+}
+
+impl UsageTotals {
+    pub fn from_records(records: &[UsageRecord]) -> Self {
+        let mut totals = Self::default();
+        for record in records {
+            totals.memory_byte_seconds += record.memory_byte_seconds;
+            totals.calibration_stream_minutes += record.calibration_stream_minutes();
+            totals.api_calls += record.api_calls;
+            totals.memory_cost_usd += record.memory_cost_usd;
+            totals.stream_cost_usd += record.stream_cost_usd;
+            totals.api_cost_usd += record.api_cost_usd;
+            totals.total_cost_usd += record.total_cost_usd;
+            totals.unpriced_records += record.unpriced as u64;
+        }
+        totals.memory_tb_hours = tb_hours(totals.memory_byte_seconds);
+        totals
+    }
+}
+
+// ============================================================================
+// BILLING PERIODS AND EXPORT
+// ============================================================================
+
+/// Half-open billing period `[start_ms, end_ms)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BillingPeriod {
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+impl BillingPeriod {
+    pub fn new(start_ms: i64, end_ms: i64) -> Result<Self, String> {
+        if end_ms <= start_ms {
+            return Err(format!(
+                "Billing period end {} must be after start {}",
+                end_ms, start_ms
+            ));
+        }
+        Ok(Self { start_ms, end_ms })
+    }
+
+    /// Calendar month (UTC)
+    pub fn month(year: i32, month: u32) -> Option<Self> {
+        let start = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+        let (next_year, next_month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+        let end = Utc
+            .with_ymd_and_hms(next_year, next_month, 1, 0, 0, 0)
+            .single()?;
+        Some(Self {
+            start_ms: start.timestamp_millis(),
+            end_ms: end.timestamp_millis(),
+        })
+    }
+
+    /// Calendar month (UTC) containing a timestamp
+    pub fn month_of(timestamp_ms: i64) -> Option<Self> {
+        let at = DateTime::<Utc>::from_timestamp_millis(timestamp_ms)?;
+        Self::month(at.year(), at.month())
+    }
+
+    /// Whether an hourly record falls inside the period
+    pub fn contains(&self, hour_start_ms: i64) -> bool {
+        hour_start_ms >= self.start_ms && hour_start_ms < self.end_ms
+    }
+}
+
+/// Usage export format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Usage query: billing period plus optional account filters
+#[derive(Debug, Clone)]
+pub struct UsageFilter {
+    pub period: BillingPeriod,
+    pub api_key_hash: Option<String>,
+    pub tier: Option<String>,
+}
+
+impl UsageFilter {
+    pub fn period(period: BillingPeriod) -> Self {
+        Self {
+            period,
+            api_key_hash: None,
+            tier: None,
+        }
+    }
+
+    pub fn matches(&self, record: &UsageRecord) -> bool {
+        self.period.contains(record.hour_start_ms)
+            && self
+                .api_key_hash
+                .as_ref()
+                .is_none_or(|h| *h == record.api_key_hash)
+            && self.tier.as_ref().is_none_or(|t| *t == record.tier)
+    }
+}
+
+/// Usage records and totals for a billing period
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub period: BillingPeriod,
+    pub records: Vec<UsageRecord>,
+    pub totals: UsageTotals,
+}
+
+impl UsageReport {
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "api_key_hash,tier,hour_start,hour_start_ms,memory_byte_seconds,memory_tb_hours,\
+             calibration_stream_minutes,api_calls,memory_cost_usd,stream_cost_usd,api_cost_usd,total_cost_usd,unpriced\n",
+        );
+        for r in &self.records {
+            let hour_start = DateTime::<Utc>::from_timestamp_millis(r.hour_start_ms)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "{},{},{},{},{},{:.6},{:.3},{},{:.6},{:.6},{:.6},{:.6},{}\n",
+                csv_field(&r.api_key_hash),
+                csv_field(&r.tier),
+                hour_start,
+                r.hour_start_ms,
+                r.memory_byte_seconds,
+                r.memory_tb_hours(),
+                r.calibration_stream_minutes(),
+                r.api_calls,
+                r.memory_cost_usd,
+                r.stream_cost_usd,
+                r.api_cost_usd,
+                r.total_cost_usd,
+                r.unpriced,
+            ));
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to encode usage: {}", e))
+    }
+
+    pub fn export(&self, format: ExportFormat) -> Result<String, String> {
+        match format {
+            ExportFormat::Csv => Ok(self.to_csv()),
+            ExportFormat::Json => self.to_json(),
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// ============================================================================
+// LEDGER STORAGE
+// ============================================================================
+
+/// Durable storage for closed usage records and agent bindings
+pub trait UsageStore: Send + Sync {
+    /// Append closed records; they survive a restart once this returns
+    fn append(&self, records: &[UsageRecord]) -> Result<(), String>;
+
+    /// Append an agent binding; it survives a restart once this returns
+    fn bind(&self, binding: &AgentBinding) -> Result<(), String>;
+
+    /// Every record appended so far, in order
+    fn load(&self) -> Result<Vec<UsageRecord>, String>;
+
+    /// Every binding appended so far, in order
+    fn load_bindings(&self) -> Result<Vec<AgentBinding>, String>;
+}
+
+/// Line of the ledger file
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum LedgerEntry {
+    Record(UsageRecord),
+    Binding(AgentBinding),
+}
+
+impl LedgerEntry {
+    /// Untagged deserialization buffers values and loses the u128 byte-seconds,
+    /// so try each shape in turn
+    fn parse(line: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(line).map(Self::Record).or_else(|e| {
+            serde_json::from_str(line)
+                .map(Self::Binding)
+                .map_err(|_| e)
+        })
+    }
+}
+
+/// Append-only ledger file, one JSON record or binding per line, synced on
+/// every append
+pub struct FileUsageStore {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl FileUsageStore {
+    /// Open or create the ledger at `path`, dropping a record torn by a crash
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let failed = |e: std::io::Error| format!("Failed to open usage ledger {}: {}", path.display(), e);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)
+            .map_err(failed)?;
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents).map_err(failed)?;
+        let complete = contents.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        if complete < contents.len() {
+            warn!("⚠️ Dropping a torn record at the end of usage ledger {}", path.display());
+            file.set_len(complete as u64).map_err(failed)?;
+        }
+        info!("🧾 Usage ledger: {}", path.display());
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl FileUsageStore {
+    fn write(&self, entries: &[LedgerEntry]) -> Result<(), String> {
+        let mut lines = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut lines, entry)
+                .map_err(|e| format!("Failed to encode usage ledger entry: {}", e))?;
+            lines.push(b'\n');
+        }
+        let file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let failed = |e: std::io::Error| format!("Failed to write usage ledger {}: {}", self.path.display(), e);
+        let len = file.metadata().map_err(failed)?.len();
+        if let Err(e) = (&*file).write_all(&lines).and_then(|()| file.sync_data()) {
+            // Leave no partial line for the next append to follow
+            let _ = file.set_len(len);
+            return Err(failed(e));
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<LedgerEntry>, String> {
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read usage ledger {}: {}", self.path.display(), e))?;
+        contents
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                LedgerEntry::parse(line).map_err(|e| {
+                    format!("Usage ledger {} line {}: {}", self.path.display(), i + 1, e)
+                })
+            })
+            .collect()
+    }
+}
+
+impl UsageStore for FileUsageStore {
+    fn append(&self, records: &[UsageRecord]) -> Result<(), String> {
+        let entries: Vec<_> = records.iter().cloned().map(LedgerEntry::Record).collect();
+        self.write(&entries)
+    }
+
+    fn bind(&self, binding: &AgentBinding) -> Result<(), String> {
+        self.write(&[LedgerEntry::Binding(binding.clone())])
+    }
+
+    fn load(&self) -> Result<Vec<UsageRecord>, String> {
+        let entries = self.entries()?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| match entry {
+                LedgerEntry::Record(record) => Some(record),
+                LedgerEntry::Binding(_) => None,
+            })
+            .collect())
+    }
+
+    fn load_bindings(&self) -> Result<Vec<AgentBinding>, String> {
+        let entries = self.entries()?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| match entry {
+                LedgerEntry::Binding(binding) => Some(binding),
+                LedgerEntry::Record(_) => None,
+            })
+            .collect())
+    }
+}
+
+// ============================================================================
+// METER
+// ============================================================================
+
+#[derive(Debug, Default, Clone)]
+struct Bucket {
+    byte_ms: u128,
+    stream_ms: u64,
+    api_calls: u64,
+}
+
+#[derive(Debug)]
+struct OpenAllocation {
+    account: UsageAccount,
+    bytes: u128,
+    since_ms: i64,
+    expires_at_ms: Option<i64>,
+}
+
+#[derive(Debug)]
+struct OpenStream {
+    account: UsageAccount,
+    since_ms: i64,
+}
+
+#[derive(Debug, Default)]
+struct MeterState {
+    /// Agent → billing account (set at GPU registration)
+    agents: HashMap<String, UsageAccount>,
+    /// API key hash → most recently registered tier
+    key_tiers: HashMap<String, String>,
+    /// Pricing tier → rates
+    rates: HashMap<String, UsageRates>,
+    allocations: HashMap<String, OpenAllocation>,
+    streams: HashMap<u64, OpenStream>,
+    next_stream_id: u64,
+    /// Open hours: (hour start, account) → accrued usage
+    buckets: BTreeMap<(i64, UsageAccount), Bucket>,
+    /// Closed hours, append-only
+    ledger: Vec<UsageRecord>,
+    /// Hours before this are closed
+    closed_until_ms: i64,
+}
+
+impl MeterState {
+    /// Account of the caller's API key, at the agent's tier if the key bound it
+    ///
+    /// An agent bound to a different key is refused rather than billed to
+    /// either account.
+    fn account_for(&self, agent_id: &str, api_key: &str) -> Result<Option<UsageAccount>, String> {
+        let Some(account) = self.account_for_key(api_key) else {
+            return Ok(None);
+        };
+        match self.agents.get(agent_id) {
+            Some(bound) if bound.api_key_hash != account.api_key_hash => Err(format!(
+                "Agent '{}' is registered under another API key",
+                agent_id
+            )),
+            Some(bound) => Ok(Some(bound.clone())),
+            None => Ok(Some(account)),
+        }
+    }
+
+    /// Account for an API key at its latest tier
+    fn account_for_key(&self, api_key: &str) -> Option<UsageAccount> {
+        if api_key.is_empty() {
+            return None;
+        }
+        let api_key_hash = AuthManager::hash_key(api_key);
+        let tier = self
+            .key_tiers
+            .get(&api_key_hash)
+            .cloned()
+            .unwrap_or_else(|| UNASSIGNED_TIER.to_string());
+        Some(UsageAccount { api_key_hash, tier })
+    }
+
+    fn apply(&mut self, binding: AgentBinding) {
+        let account = UsageAccount {
+            api_key_hash: binding.api_key_hash,
+            tier: binding.tier,
+        };
+        self.rates.insert(account.tier.clone(), binding.rates);
+        self.key_tiers
+            .insert(account.api_key_hash.clone(), account.tier.clone());
+        self.agents.insert(binding.agent_id, account);
+    }
+
+    fn bucket(&mut self, account: &UsageAccount, at_ms: i64) -> &mut Bucket {
+        let hour = (at_ms.div_euclid(HOUR_MS) * HOUR_MS).max(self.closed_until_ms);
+        self.buckets.entry((hour, account.clone())).or_default()
+    }
+
+    /// Split `[from, to)` at hour boundaries, calling `f(bucket, ms)` per hour
+    fn accrue(
+        &mut self,
+        account: &UsageAccount,
+        from_ms: i64,
+        to_ms: i64,
+        mut f: impl FnMut(&mut Bucket, i64),
+    ) {
+        let mut t = from_ms.max(self.closed_until_ms);
+        while t < to_ms {
+            let hour_end = (t.div_euclid(HOUR_MS) + 1) * HOUR_MS;
+            let end = hour_end.min(to_ms);
+            f(self.bucket(account, t), end - t);
+            t = end;
+        }
+    }
+
+    fn accrue_allocation(&mut self, allocation: &OpenAllocation, now_ms: i64) {
+        let end = allocation.expires_at_ms.map_or(now_ms, |e| e.min(now_ms));
+        let bytes = allocation.bytes;
+        self.accrue(&allocation.account, allocation.since_ms, end, |b, ms| {
+            b.byte_ms += bytes * ms as u128
+        });
+    }
+
+    fn accrue_stream(&mut self, stream: &OpenStream, now_ms: i64) {
+        self.accrue(&stream.account, stream.since_ms, now_ms, |b, ms| {
+            b.stream_ms += ms as u64
+        });
+    }
+}
+
+/// Usage meter shared by the gRPC services
+pub struct UsageMeter {
+    clock: Arc<dyn Clock>,
+    state: Mutex<MeterState>,
+    store: Option<Arc<dyn UsageStore>>,
+}
+
+impl UsageMeter {
+    /// Meter on the wall clock
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Meter on a custom clock
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        let closed_until_ms = clock.now_ms().div_euclid(HOUR_MS) * HOUR_MS;
+        Self {
+            clock,
+            state: Mutex::new(MeterState {
+                closed_until_ms,
+                ..Default::default()
+            }),
+            store: None,
+        }
+    }
+
+    /// Write closed records and bindings to `store` too, starting from what it holds
+    pub fn with_store(mut self, store: Arc<dyn UsageStore>) -> Result<Self, String> {
+        let records = store.load()?;
+        let bindings = store.load_bindings()?;
+        {
+            let mut state = self.state();
+            if let Some(last) = records.last() {
+                state.closed_until_ms = state.closed_until_ms.max(last.hour_end_ms());
+            }
+            info!("🧾 Loaded {} usage records and {} agent bindings", records.len(), bindings.len());
+            state.ledger = records;
+            bindings.into_iter().for_each(|binding| state.apply(binding));
+        }
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    pub fn now_ms(&self) -> i64 {
+        self.clock.now_ms()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MeterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Bill an agent's usage to its API key at the tier of its GPU cost estimate
+    ///
+    /// With a store the binding is only applied once it is persisted.
+    pub fn bind_agent(&self, agent_id: &str, api_key: &str, estimate: &CostEstimate) -> Result<(), String> {
+        let binding = AgentBinding {
+            agent_id: agent_id.to_string(),
+            api_key_hash: AuthManager::hash_key(api_key),
+            tier: estimate.pricing_tier.clone(),
+            rates: UsageRates::from_cost_estimate(estimate),
+        };
+        let mut state = self.state();
+        if let Some(store) = &self.store {
+            store.bind(&binding)?;
+        }
+        state.apply(binding);
+        Ok(())
+    }
+
+    /// Override the rates for a pricing tier
+    pub fn set_tier_rates(&self, tier: &str, rates: UsageRates) {
+        self.state().rates.insert(tier.to_string(), rates);
+    }
+
+    /// Rates of a pricing tier, if it has any
+    pub fn tier_rates(&self, tier: &str) -> Option<UsageRates> {
+        self.state().rates.get(tier).copied()
+    }
+
+    /// Start accruing memory for an allocation; false if it has no billing account
+    ///
+    /// `api_key` is the caller's authenticated key. Fails when `agent_id` is
+    /// bound to another key.
+    pub fn start_allocation(
+        &self,
+        allocation_id: &str,
+        agent_id: &str,
+        api_key: &str,
+        allocated_tb: u64,
+        lease: Option<Duration>,
+    ) -> Result<bool, String> {
+        let now_ms = self.now_ms();
+        let mut state = self.state();
+        let Some(account) = state.account_for(agent_id, api_key)? else {
+            debug!(
+                "Allocation {} for agent {} has no billing account",
+                allocation_id, agent_id
+            );
+            return Ok(false);
+        };
+        state.allocations.insert(
+            allocation_id.to_string(),
+            OpenAllocation {
+                account,
+                bytes: allocated_tb as u128 * BYTES_PER_TB,
+                since_ms: now_ms,
+                expires_at_ms: lease.map(|l| now_ms + l.as_millis() as i64),
+            },
+        );
+        Ok(true)
+    }
+
+    /// Stop accruing memory for an allocation
+    pub fn end_allocation(&self, allocation_id: &str) -> bool {
+        let now_ms = self.now_ms();
+        let mut state = self.state();
+        match state.allocations.remove(allocation_id) {
+            Some(allocation) => {
+                state.accrue_allocation(&allocation, now_ms);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Start accruing calibration stream time; accrual stops when the guard drops
+    ///
+    /// `None` if the stream has no billing account; fails like
+    /// [`Self::start_allocation`].
+    pub fn start_stream(
+        self: &Arc<Self>,
+        agent_id: &str,
+        api_key: &str,
+    ) -> Result<Option<StreamUsageGuard>, String> {
+        let now_ms = self.now_ms();
+        let mut state = self.state();
+        let Some(account) = state.account_for(agent_id, api_key)? else {
+            return Ok(None);
+        };
+        let stream_id = state.next_stream_id;
+        state.next_stream_id += 1;
+        state.streams.insert(
+            stream_id,
+            OpenStream {
+                account,
+                since_ms: now_ms,
+            },
+        );
+        Ok(Some(StreamUsageGuard {
+            meter: self.clone(),
+            stream_id,
+        }))
+    }
+
+    fn end_stream(&self, stream_id: u64) {
+        let now_ms = self.now_ms();
+        let mut state = self.state();
+        if let Some(stream) = state.streams.remove(&stream_id) {
+            state.accrue_stream(&stream, now_ms);
+        }
+    }
+
+    /// Count an API call against the key's current tier
+    pub fn record_api_call(&self, api_key: &str) {
+        let now_ms = self.now_ms();
+        let mut state = self.state();
+        if let Some(account) = state.account_for_key(api_key) {
+            state.bucket(&account, now_ms).api_calls += 1;
+        }
+    }
+
+    /// Close every finished hour into usage records; returns the new records
+    pub fn rollup(&self) -> Vec<UsageRecord> {
+        let now_ms = self.now_ms();
+        let mut state = self.state();
+
+        // Bring open allocations and streams up to date
+        let allocations = std::mem::take(&mut state.allocations);
+        for (id, mut allocation) in allocations {
+            state.accrue_allocation(&allocation, now_ms);
+            if allocation.expires_at_ms.is_some_and(|e| e <= now_ms) {
+                debug!("Allocation {} lease expired", id);
+                continue;
+            }
+            allocation.since_ms = now_ms;
+            state.allocations.insert(id, allocation);
+        }
+        let streams = std::mem::take(&mut state.streams);
+        for (id, mut stream) in streams {
+            state.accrue_stream(&stream, now_ms);
+            stream.since_ms = now_ms;
+            state.streams.insert(id, stream);
+        }
+
+        let current_hour = now_ms.div_euclid(HOUR_MS) * HOUR_MS;
+        let first_open = UsageAccount {
+            api_key_hash: String::new(),
+            tier: String::new(),
+        };
+        let open = state.buckets.split_off(&(current_hour, first_open));
+        let closed = std::mem::replace(&mut state.buckets, open);
+
+        let mut records = Vec::with_capacity(closed.len());
+        for ((hour_start_ms, account), bucket) in &closed {
+            let record = match state.rates.get(&account.tier) {
+                Some(rates) => UsageRecord::price(account, *hour_start_ms, bucket, *rates),
+                None if account.tier == UNASSIGNED_TIER => {
+                    UsageRecord::price(account, *hour_start_ms, bucket, UsageRates::unassigned())
+                }
+                None => {
+                    error!(
+                        "❌ No rates for tier '{}', recording hour {} of key {} unpriced",
+                        account.tier, hour_start_ms, account.api_key_hash
+                    );
+                    UsageRecord::unpriced(account, *hour_start_ms, bucket)
+                }
+            };
+            records.push(record);
+        }
+        if let Some(store) = self.store.as_ref().filter(|_| !records.is_empty()) {
+            if let Err(e) = store.append(&records) {
+                error!("❌ Usage records not persisted, keeping their hours open: {}", e);
+                state.buckets.extend(closed);
+                return Vec::new();
+            }
+        }
+        state.closed_until_ms = state.closed_until_ms.max(current_hour);
+        state.ledger.extend(records.iter().cloned());
+
+        if !records.is_empty() {
+            info!("🧾 Rolled up {} hourly usage records", records.len());
+        }
+        records
+    }
+
+    /// Closed usage records matching a filter, ordered by hour then account
+    pub fn records(&self, filter: &UsageFilter) -> Vec<UsageRecord> {
+        self.state()
+            .ledger
+            .iter()
+            .filter(|r| filter.matches(r))
+            .cloned()
+            .collect()
+    }
+
+    /// Usage report for a filter
+    pub fn report(&self, filter: &UsageFilter) -> UsageReport {
+        let records = self.records(filter);
+        let totals = UsageTotals::from_records(&records);
+        UsageReport {
+            period: filter.period,
+            records,
+            totals,
+        }
+    }
+
+    /// Export a billing period as CSV or JSON
+    pub fn export(&self, filter: &UsageFilter, format: ExportFormat) -> Result<String, String> {
+        self.report(filter).export(format)
+    }
+
+    /// Roll up closed hours on an interval
+    pub fn start_rollup_task(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(ROLLUP_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                self.rollup();
+            }
+        });
+    }
+}
+
+impl Default for UsageMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ends a metered calibration stream when dropped
+pub struct StreamUsageGuard {
+    meter: Arc<UsageMeter>,
+    stream_id: u64,
+}
+
+impl Drop for StreamUsageGuard {
+    fn drop(&mut self) {
+        self.meter.end_stream(self.stream_id);
+    }
+}
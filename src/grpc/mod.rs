@@ -11,12 +11,19 @@ pub mod operations;
 pub mod auth;
 pub mod tls;
 pub mod gpu_detection;
+pub mod gpu_catalog;
+pub mod gpu_registry;
 pub mod gpu_service;
 pub mod certificate;
 pub mod certificate_service;
 pub mod dashboard_metrics;
 pub mod compute_calibration;
 pub mod pcie_amplification;
+pub mod pcie_tuning;
+pub mod metering;
+pub mod gateway;
+pub mod openapi;
+pub mod http_api;
 
 // Re-export generated protobuf types
 pub mod proto {
@@ -25,6 +32,21 @@ pub mod proto {
     /// File descriptor set for gRPC reflection
     pub const FILE_DESCRIPTOR_SET: &[u8] =
         tonic::include_file_descriptor_set!("cyan_flame_descriptor");
+
+    /// Serde adapter encoding `bytes` fields as base64 strings in JSON
+    pub mod base64_bytes {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&STANDARD.encode(bytes))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+            let encoded = String::deserialize(deserializer)?;
+            STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+        }
+    }
 }
 
 // Re-export commonly used server types
@@ -74,6 +96,14 @@ pub struct GrpcServerConfig {
     pub max_concurrent_streams: u32,
     /// Enable gRPC reflection (for debugging tools)
     pub enable_reflection: bool,
+    /// GPU catalog overrides (TOML/YAML); built-in catalog when unset
+    pub gpu_catalog_path: Option<String>,
+    /// API key required by admin RPCs (disabled when unset)
+    pub admin_api_key: Option<String>,
+    /// SQLite database for GPU registrations; in-memory when unset
+    pub registration_db_path: Option<String>,
+    /// Append-only file for closed usage records; in-memory when unset
+    pub usage_ledger_path: Option<String>,
 }
 
 impl Default for GrpcServerConfig {
@@ -87,6 +117,10 @@ impl Default for GrpcServerConfig {
             enable_mtls: false,
             max_concurrent_streams: 100,
             enable_reflection: true,
+            gpu_catalog_path: None,
+            admin_api_key: None,
+            registration_db_path: None,
+            usage_ledger_path: None,
         }
     }
 }
@@ -103,6 +137,10 @@ impl GrpcServerConfig {
             enable_mtls: false,
             max_concurrent_streams: 1000,
             enable_reflection: false,  // Disable in production
+            gpu_catalog_path: None,
+            admin_api_key: None,
+            registration_db_path: None,
+            usage_ledger_path: None,
         }
     }
 
@@ -117,6 +155,10 @@ impl GrpcServerConfig {
             enable_mtls: true,
             max_concurrent_streams: 1000,
             enable_reflection: false,  // Disable in production
+            gpu_catalog_path: None,
+            admin_api_key: None,
+            registration_db_path: None,
+            usage_ledger_path: None,
         }
     }
 }
@@ -128,8 +170,15 @@ pub use tls::{TlsConfiguration, TlsError};
 // Re-export GPU detection types
 pub use gpu_detection::{
     BaselineGpuType, GpuSpecifications, GpuTierConfig,
-    AmplificationTargets, GpuRegistration, GpuDetectionManager
+    AmplificationTargets, GpuRegistration, GpuDetectionManager,
+    GpuProbe, GpuDetection, DetectionMethod
+};
+pub use gpu_catalog::{GpuCatalog, GpuCatalogEntry, GpuCatalogError, CatalogFormat};
+pub use gpu_registry::{
+    GpuRegistrationRecord, RegistrationStore, InMemoryRegistrationStore, InventoryFilter, GpuInventory,
 };
+#[cfg(feature = "sqlite-registry")]
+pub use gpu_registry::SqliteRegistrationStore;
 
 // Re-export certificate types
 pub use certificate::{CertificateManager, CertificateEntry, RevocationReason};
@@ -148,3 +197,13 @@ pub use compute_calibration::ComputeCalibrationServiceImpl;
 
 // Re-export PCIe amplification service
 pub use pcie_amplification::PCIeAmplificationServiceImpl;
+pub use pcie_tuning::{PcieTuningConfig, PcieTuningRegistry, TuningParams, TuningAction};
+
+// Re-export usage metering
+pub use metering::{
+    UsageMeter, UsageRates, UsageRecord, UsageTotals, UsageReport, UsageFilter, BillingPeriod,
+    ExportFormat, Clock, SystemClock, ManualClock, UsageStore, FileUsageStore, AgentBinding,
+};
+
+// Re-export OpenAPI generation
+pub use openapi::{ApiSchema, ApiOperation, HttpApi, ProtoSchemas};
@@ -0,0 +1,877 @@
+//! OpenAPI 3.1 Document for the HTTP Surface
+//!
+//! The document is generated, not hand-written:
+//! - Rust response types declared with [`api_schema!`](crate::api_schema)
+//!   implement [`ApiSchema`] from their field types and doc comments
+//! - Gateway request/response messages come from the compiled protobuf
+//!   descriptor set (field types, enums and proto comments)
+//!
+//! Routes are registered through [`HttpApi`], which records an operation for
+//! every route it mounts and serves the result at `/openapi.json` together
+//! with a self-contained viewer at `/docs`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use axum::handler::Handler;
+use axum::http::Method;
+use axum::response::Html;
+use axum::routing::{get, MethodRouter};
+use axum::{Json, Router};
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{DescriptorProto, FieldDescriptorProto, FileDescriptorSet};
+use serde_json::{json, Map, Value};
+
+use super::gateway::{self, GatewayErrorBody, GatewayRouteList};
+use super::proto::FILE_DESCRIPTOR_SET;
+
+/// OpenAPI version emitted
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// Path of the generated document
+pub const OPENAPI_PATH: &str = "/openapi.json";
+
+/// Path of the viewer page
+pub const DOCS_PATH: &str = "/docs";
+
+/// JSON Schema of a type's serialized form
+pub trait ApiSchema {
+    fn schema() -> Value;
+
+    /// Whether the field may be omitted or null
+    fn optional() -> bool {
+        false
+    }
+}
+
+/// Declare a struct and derive its [`ApiSchema`] from the field types
+///
+/// Doc comments on the struct and its fields become schema descriptions.
+#[macro_export]
+macro_rules! api_schema {
+    (
+        $(#[doc = $doc:literal])*
+        #[derive($($derive:path),* $(,)?)]
+        $vis:vis struct $name:ident {
+            $(
+                $(#[doc = $field_doc:literal])*
+                $field_vis:vis $field:ident : $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[doc = $doc])*
+        #[derive($($derive),*)]
+        $vis struct $name {
+            $(
+                $(#[doc = $field_doc])*
+                $field_vis $field: $ty,
+            )*
+        }
+
+        impl $crate::grpc::openapi::ApiSchema for $name {
+            fn schema() -> serde_json::Value {
+                use $crate::grpc::openapi::{describe, ApiSchema};
+
+                let mut properties = serde_json::Map::new();
+                let mut required: Vec<&str> = Vec::new();
+                $(
+                    properties.insert(
+                        stringify!($field).to_string(),
+                        describe(<$ty as ApiSchema>::schema(), &[$($field_doc),*]),
+                    );
+                    if !<$ty as ApiSchema>::optional() {
+                        required.push(stringify!($field));
+                    }
+                )*
+                let schema = serde_json::json!({
+                    "type": "object",
+                    "title": stringify!($name),
+                    "properties": properties,
+                    "required": required,
+                });
+                describe(schema, &[$($doc),*])
+            }
+        }
+    };
+}
+
+/// Attach doc-comment lines to a schema as its description
+pub fn describe(mut schema: Value, doc: &[&str]) -> Value {
+    let description = doc
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let (false, Some(object)) = (description.is_empty(), schema.as_object_mut()) {
+        object.insert("description".to_string(), Value::String(description));
+    }
+    schema
+}
+
+macro_rules! scalar_schema {
+    ($($ty:ty => $schema:tt;)*) => {
+        $(
+            impl ApiSchema for $ty {
+                fn schema() -> Value {
+                    json!($schema)
+                }
+            }
+        )*
+    };
+}
+
+scalar_schema! {
+    bool => { "type": "boolean" };
+    String => { "type": "string" };
+    i32 => { "type": "integer", "format": "int32" };
+    i64 => { "type": "integer", "format": "int64" };
+    u16 => { "type": "integer", "format": "uint16", "minimum": 0 };
+    u32 => { "type": "integer", "format": "uint32", "minimum": 0 };
+    u64 => { "type": "integer", "format": "uint64", "minimum": 0 };
+    usize => { "type": "integer", "format": "uint64", "minimum": 0 };
+    f32 => { "type": "number", "format": "float" };
+    f64 => { "type": "number", "format": "double" };
+    Value => {};
+}
+
+impl ApiSchema for &str {
+    fn schema() -> Value {
+        String::schema()
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Vec<T> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Option<T> {
+    fn schema() -> Value {
+        json!({ "anyOf": [T::schema(), { "type": "null" }] })
+    }
+
+    fn optional() -> bool {
+        true
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for BTreeMap<String, T> {
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for HashMap<String, T> {
+    fn schema() -> Value {
+        BTreeMap::<String, T>::schema()
+    }
+}
+
+/// An RPC from the descriptor set
+#[derive(Debug, Clone)]
+pub struct ProtoMethod {
+    /// Request message (component name)
+    pub input: String,
+    /// Response message (component name)
+    pub output: String,
+    pub description: String,
+}
+
+/// JSON Schemas of the protobuf messages, as the gateway serializes them
+#[derive(Debug, Clone, Default)]
+pub struct ProtoSchemas {
+    schemas: BTreeMap<String, Value>,
+    methods: HashMap<(String, String), ProtoMethod>,
+}
+
+/// Comments by source path, e.g. `[4, 2, 2, 0]` = message 2, field 0
+type Comments = HashMap<Vec<i32>, String>;
+
+// Field numbers in descriptor.proto, used by source code info paths
+const FILE_MESSAGE_TYPE: i32 = 4;
+const FILE_ENUM_TYPE: i32 = 5;
+const FILE_SERVICE: i32 = 6;
+const MESSAGE_FIELD: i32 = 2;
+const MESSAGE_NESTED_TYPE: i32 = 3;
+const MESSAGE_ENUM_TYPE: i32 = 4;
+const SERVICE_METHOD: i32 = 2;
+
+impl ProtoSchemas {
+    /// Schemas for the compiled `cyan_flame.proto`
+    ///
+    /// Panics if the embedded descriptor set is unreadable (e.g. an LFS
+    /// pointer checked out in its place); the spec would be useless without it.
+    pub fn load() -> Self {
+        // The descriptor set is produced by build.rs alongside the generated code
+        Self::from_descriptor_set(FILE_DESCRIPTOR_SET)
+            .expect("embedded proto descriptor set is unreadable")
+    }
+
+    /// Schemas from an encoded `FileDescriptorSet`
+    pub fn from_descriptor_set(bytes: &[u8]) -> Result<Self, prost::DecodeError> {
+        let set = FileDescriptorSet::decode(bytes)?;
+        let mut schemas = Self::default();
+
+        for file in &set.file {
+            let package = file.package().to_string();
+            let comments: Comments = file
+                .source_code_info
+                .iter()
+                .flat_map(|info| &info.location)
+                .filter_map(|location| {
+                    let text = location
+                        .leading_comments
+                        .as_deref()
+                        .or(location.trailing_comments.as_deref())?;
+                    let text = text.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+                    Some((location.path.clone(), text.trim().to_string()))
+                })
+                .collect();
+
+            let mut enums = HashMap::new();
+            for (index, descriptor) in file.enum_type.iter().enumerate() {
+                let name = format!(".{}.{}", package, descriptor.name());
+                enums.insert(
+                    name,
+                    enum_schema(descriptor, &comments, &[FILE_ENUM_TYPE, index as i32]),
+                );
+            }
+            let mut messages = Vec::new();
+            for (index, descriptor) in file.message_type.iter().enumerate() {
+                collect_messages(
+                    descriptor,
+                    descriptor.name().to_string(),
+                    vec![FILE_MESSAGE_TYPE, index as i32],
+                    &package,
+                    &mut messages,
+                    &mut enums,
+                    &comments,
+                );
+            }
+
+            let context = SchemaContext {
+                package: &package,
+                enums: &enums,
+                map_entries: messages
+                    .iter()
+                    .filter(|m| m.descriptor.options.as_ref().is_some_and(|o| o.map_entry()))
+                    .map(|m| (format!(".{}.{}", package, m.name), m.descriptor))
+                    .collect(),
+            };
+            for message in &messages {
+                if context
+                    .map_entries
+                    .contains_key(&format!(".{}.{}", package, message.name))
+                {
+                    continue;
+                }
+                schemas.schemas.insert(
+                    message.name.clone(),
+                    context.message_schema(message, &comments),
+                );
+            }
+
+            for (service_index, service) in file.service.iter().enumerate() {
+                for (method_index, method) in service.method.iter().enumerate() {
+                    let path = [
+                        FILE_SERVICE,
+                        service_index as i32,
+                        SERVICE_METHOD,
+                        method_index as i32,
+                    ];
+                    schemas.methods.insert(
+                        (service.name().to_string(), method.name().to_string()),
+                        ProtoMethod {
+                            input: context.component(method.input_type()),
+                            output: context.component(method.output_type()),
+                            description: comments.get(path.as_slice()).cloned().unwrap_or_default(),
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(schemas)
+    }
+
+    /// Schemas by component name
+    pub fn schemas(&self) -> &BTreeMap<String, Value> {
+        &self.schemas
+    }
+
+    /// Request and response messages of an RPC
+    pub fn method(&self, service: &str, method: &str) -> Option<&ProtoMethod> {
+        self.methods.get(&(service.to_string(), method.to_string()))
+    }
+}
+
+struct MessageEntry<'a> {
+    name: String,
+    path: Vec<i32>,
+    descriptor: &'a DescriptorProto,
+}
+
+fn collect_messages<'a>(
+    descriptor: &'a DescriptorProto,
+    name: String,
+    path: Vec<i32>,
+    package: &str,
+    messages: &mut Vec<MessageEntry<'a>>,
+    enums: &mut HashMap<String, Value>,
+    comments: &Comments,
+) {
+    for (index, nested) in descriptor.enum_type.iter().enumerate() {
+        let mut enum_path = path.clone();
+        enum_path.extend([MESSAGE_ENUM_TYPE, index as i32]);
+        enums.insert(
+            format!(".{}.{}.{}", package, name, nested.name()),
+            enum_schema(nested, comments, &enum_path),
+        );
+    }
+    for (index, nested) in descriptor.nested_type.iter().enumerate() {
+        let mut nested_path = path.clone();
+        nested_path.extend([MESSAGE_NESTED_TYPE, index as i32]);
+        let nested_name = format!("{}.{}", name, nested.name());
+        collect_messages(
+            nested,
+            nested_name,
+            nested_path,
+            package,
+            messages,
+            enums,
+            comments,
+        );
+    }
+    messages.push(MessageEntry {
+        name,
+        path,
+        descriptor,
+    });
+}
+
+/// Enum fields serialize as their `i32` value
+fn enum_schema(
+    descriptor: &prost_types::EnumDescriptorProto,
+    comments: &Comments,
+    path: &[i32],
+) -> Value {
+    let values: Vec<String> = descriptor
+        .value
+        .iter()
+        .map(|value| format!("{} = {}", value.name(), value.number()))
+        .collect();
+    let mut description = format!("{}: {}", descriptor.name(), values.join(", "));
+    if let Some(comment) = comments.get(path) {
+        description = format!("{} ({})", comment, description);
+    }
+    json!({
+        "type": "integer",
+        "format": "int32",
+        "enum": descriptor.value.iter().map(|v| v.number()).collect::<Vec<_>>(),
+        "description": description,
+    })
+}
+
+struct SchemaContext<'a> {
+    package: &'a str,
+    enums: &'a HashMap<String, Value>,
+    map_entries: HashMap<String, &'a DescriptorProto>,
+}
+
+impl SchemaContext<'_> {
+    /// Component name of a fully-qualified message type
+    fn component(&self, type_name: &str) -> String {
+        type_name
+            .strip_prefix(&format!(".{}.", self.package))
+            .unwrap_or(type_name.trim_start_matches('.'))
+            .to_string()
+    }
+
+    fn message_schema(&self, message: &MessageEntry<'_>, comments: &Comments) -> Value {
+        let mut properties = Map::new();
+        for (index, field) in message.descriptor.field.iter().enumerate() {
+            let mut path = message.path.clone();
+            path.extend([MESSAGE_FIELD, index as i32]);
+            let mut schema = self.field_schema(field);
+            if let (Some(comment), Some(object)) = (comments.get(&path), schema.as_object_mut()) {
+                object
+                    .entry("description")
+                    .and_modify(|d| {
+                        *d = json!(format!("{} ({})", comment, d.as_str().unwrap_or_default()))
+                    })
+                    .or_insert_with(|| json!(comment));
+            }
+            properties.insert(field.name().to_string(), schema);
+        }
+
+        let mut schema = json!({
+            "type": "object",
+            "title": message.name,
+            "properties": properties,
+        });
+        if let Some(comment) = comments.get(&message.path) {
+            schema["description"] = json!(comment);
+        }
+        schema
+    }
+
+    fn field_schema(&self, field: &FieldDescriptorProto) -> Value {
+        let repeated = field.label() == Label::Repeated;
+        if let (true, Some(entry)) = (repeated, self.map_entries.get(field.type_name())) {
+            let value = entry.field.iter().find(|f| f.number() == 2);
+            return json!({
+                "type": "object",
+                "additionalProperties": value.map(|v| self.field_schema(v)).unwrap_or_default(),
+            });
+        }
+
+        let item = match field.r#type() {
+            Type::Double => json!({ "type": "number", "format": "double" }),
+            Type::Float => json!({ "type": "number", "format": "float" }),
+            Type::Int64 | Type::Sint64 | Type::Sfixed64 => {
+                json!({ "type": "integer", "format": "int64" })
+            }
+            Type::Uint64 | Type::Fixed64 => {
+                json!({ "type": "integer", "format": "uint64", "minimum": 0 })
+            }
+            Type::Int32 | Type::Sint32 | Type::Sfixed32 => {
+                json!({ "type": "integer", "format": "int32" })
+            }
+            Type::Uint32 | Type::Fixed32 => {
+                json!({ "type": "integer", "format": "uint32", "minimum": 0 })
+            }
+            Type::Bool => json!({ "type": "boolean" }),
+            Type::String => json!({ "type": "string" }),
+            Type::Bytes => {
+                json!({ "type": "string", "format": "byte", "contentEncoding": "base64" })
+            }
+            Type::Enum => self
+                .enums
+                .get(field.type_name())
+                .cloned()
+                .unwrap_or_else(|| json!({ "type": "integer", "format": "int32" })),
+            Type::Message | Type::Group => {
+                let reference = json!({ "$ref": schema_ref(&self.component(field.type_name())) });
+                if repeated {
+                    reference
+                } else {
+                    // Singular message fields are optional in proto3
+                    json!({ "anyOf": [reference, { "type": "null" }] })
+                }
+            }
+        };
+
+        if repeated {
+            json!({ "type": "array", "items": item })
+        } else {
+            item
+        }
+    }
+}
+
+/// `$ref` target of a component schema
+pub fn schema_ref(component: &str) -> String {
+    format!("#/components/schemas/{}", component)
+}
+
+/// How an operation answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    Json,
+    /// Server-Sent Events, one JSON message per event
+    EventStream,
+    Html,
+}
+
+impl ResponseKind {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::EventStream => "text/event-stream",
+            Self::Html => "text/html",
+        }
+    }
+}
+
+/// A documented route
+#[derive(Debug, Clone)]
+pub struct ApiOperation {
+    pub method: Method,
+    pub path: String,
+    pub tag: String,
+    pub summary: String,
+    pub description: String,
+    /// JSON request body schema
+    pub request: Option<Value>,
+    /// Query parameters (name, schema)
+    pub query: Vec<(String, Value)>,
+    pub response: Value,
+    pub response_kind: ResponseKind,
+    /// Body of error responses (any non-2xx status)
+    pub error: Option<Value>,
+}
+
+impl ApiOperation {
+    /// Operation without request body or parameters
+    pub fn new(method: Method, path: &str, tag: &str, summary: &str, response: Value) -> Self {
+        Self {
+            method,
+            path: path.to_string(),
+            tag: tag.to_string(),
+            summary: summary.to_string(),
+            description: String::new(),
+            request: None,
+            query: Vec::new(),
+            response,
+            response_kind: ResponseKind::Json,
+            error: None,
+        }
+    }
+
+    fn operation_id(&self) -> String {
+        let path: String = self
+            .path
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("_");
+        let path = if path.is_empty() {
+            "root".to_string()
+        } else {
+            path
+        };
+        format!("{}_{}", self.method.as_str().to_lowercase(), path)
+    }
+
+    fn to_openapi(&self) -> Value {
+        let mut operation = json!({
+            "operationId": self.operation_id(),
+            "tags": [self.tag],
+            "summary": self.summary,
+            "responses": {
+                "200": {
+                    "description": "OK",
+                    "content": { self.response_kind.content_type(): { "schema": self.response } },
+                },
+            },
+        });
+        if !self.description.is_empty() {
+            operation["description"] = json!(self.description);
+        }
+        if let Some(request) = &self.request {
+            operation["requestBody"] = json!({
+                "required": false,
+                "content": { "application/json": { "schema": request } },
+            });
+        }
+        if !self.query.is_empty() {
+            operation["parameters"] = self
+                .query
+                .iter()
+                .map(|(name, schema)| json!({ "name": name, "in": "query", "required": false, "schema": schema }))
+                .collect();
+        }
+        if let Some(error) = &self.error {
+            operation["responses"]["default"] = json!({
+                "description": "Error; the HTTP status gives its class",
+                "content": { "application/json": { "schema": error } },
+            });
+        }
+        operation
+    }
+}
+
+/// Router that documents every route it mounts
+pub struct HttpApi {
+    title: String,
+    description: String,
+    router: Router,
+    operations: Vec<ApiOperation>,
+    proto: Option<ProtoSchemas>,
+}
+
+impl HttpApi {
+    /// API with the `/openapi.json` and `/docs` routes
+    pub fn new(title: &str, description: &str) -> Self {
+        let docs_tag = "Documentation";
+        let mut spec = ApiOperation::new(
+            Method::GET,
+            OPENAPI_PATH,
+            docs_tag,
+            "This OpenAPI document",
+            json!({ "type": "object" }),
+        );
+        spec.description = format!(
+            "OpenAPI {} description of every HTTP route",
+            OPENAPI_VERSION
+        );
+        let mut viewer = ApiOperation::new(
+            Method::GET,
+            DOCS_PATH,
+            docs_tag,
+            "API reference viewer (no external assets)",
+            json!({ "type": "string" }),
+        );
+        viewer.response_kind = ResponseKind::Html;
+
+        Self {
+            title: title.to_string(),
+            description: description.to_string(),
+            router: Router::new(),
+            operations: vec![spec, viewer],
+            proto: None,
+        }
+    }
+
+    /// Mount a `GET` route answering with JSON `T`
+    pub fn get<T, H, M>(mut self, path: &str, tag: &str, summary: &str, handler: H) -> Self
+    where
+        T: ApiSchema,
+        H: Handler<M, ()>,
+        M: 'static,
+    {
+        self.operations.push(ApiOperation::new(
+            Method::GET,
+            path,
+            tag,
+            summary,
+            T::schema(),
+        ));
+        self.router = self.router.route(path, get(handler));
+        self
+    }
+
+    /// Mount a route documented by `operation`
+    pub fn route(mut self, operation: ApiOperation, method_router: MethodRouter) -> Self {
+        self.router = self.router.route(&operation.path, method_router);
+        self.operations.push(operation);
+        self
+    }
+
+    /// Mount the REST/JSON gateway built by [`gateway::router`]
+    pub fn gateway(mut self, router: Router) -> Self {
+        let proto = ProtoSchemas::load();
+
+        let mut listing = ApiOperation::new(
+            Method::GET,
+            "/v1",
+            "Gateway",
+            "List the gateway routes",
+            GatewayRouteList::schema(),
+        );
+        listing.description = "Client-streaming RPCs are only available over gRPC".to_string();
+        self.operations.push(listing);
+
+        for route in gateway::routes() {
+            let (service, method) = route.rpc();
+            let rpc = proto.method(service, method);
+            let input = rpc.map(|m| m.input.as_str()).unwrap_or_default();
+            let output = rpc.map(|m| m.output.as_str()).unwrap_or_default();
+
+            let mut operation = ApiOperation::new(
+                Method::POST,
+                route.path,
+                service,
+                &format!("{}.{}", service, method),
+                json!({ "$ref": schema_ref(output) }),
+            );
+            operation.description = rpc.map(|m| m.description.clone()).unwrap_or_default();
+            operation.request = Some(json!({ "$ref": schema_ref(input) }));
+            // gRPC status mapped to an HTTP status
+            operation.error = Some(json!({ "$ref": schema_ref("GatewayErrorBody") }));
+
+            if route.streaming {
+                operation.response_kind = ResponseKind::EventStream;
+                operation.description = format!(
+                    "{} Streams one `data:` event per message; a failure ends the stream with an `error` event.",
+                    operation.description
+                )
+                .trim()
+                .to_string();
+
+                // The same stream, with the request message as query parameters
+                let mut by_query = operation.clone();
+                by_query.method = Method::GET;
+                by_query.request = None;
+                by_query.query = proto
+                    .schemas()
+                    .get(input)
+                    .and_then(|schema| schema["properties"].as_object())
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, schema)| is_scalar(schema))
+                    .map(|(name, schema)| (name.clone(), schema.clone()))
+                    .collect();
+                self.operations.push(by_query);
+            }
+            self.operations.push(operation);
+        }
+
+        self.router = self.router.merge(router);
+        self.proto = Some(proto);
+        self
+    }
+
+    /// Every documented route
+    pub fn operations(&self) -> &[ApiOperation] {
+        &self.operations
+    }
+
+    /// The OpenAPI document
+    pub fn document(&self) -> Value {
+        let mut paths = Map::new();
+        for operation in &self.operations {
+            let item = paths
+                .entry(operation.path.clone())
+                .or_insert_with(|| json!({}));
+            item[operation.method.as_str().to_lowercase()] = operation.to_openapi();
+        }
+
+        let mut schemas = Map::new();
+        if let Some(proto) = &self.proto {
+            for (name, schema) in proto.schemas() {
+                schemas.insert(name.clone(), schema.clone());
+            }
+            schemas.insert("GatewayErrorBody".to_string(), GatewayErrorBody::schema());
+        }
+
+        let mut tags: Vec<&str> = Vec::new();
+        for operation in &self.operations {
+            if !tags.contains(&operation.tag.as_str()) {
+                tags.push(&operation.tag);
+            }
+        }
+
+        json!({
+            "openapi": OPENAPI_VERSION,
+            "info": {
+                "title": self.title,
+                "description": self.description,
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "tags": tags.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+            "paths": paths,
+            "components": { "schemas": schemas },
+        })
+    }
+
+    /// Router serving the API, `/openapi.json` and `/docs`
+    pub fn into_router(self) -> Router {
+        let document = Arc::new(self.document());
+        self.router
+            .route(
+                OPENAPI_PATH,
+                get(move || async move { Json(document.as_ref().clone()) }),
+            )
+            .route(DOCS_PATH, get(|| async { Html(VIEWER_HTML) }))
+    }
+}
+
+fn is_scalar(schema: &Value) -> bool {
+    matches!(
+        schema["type"].as_str(),
+        Some("string" | "integer" | "number" | "boolean")
+    )
+}
+
+/// Viewer page; renders `/openapi.json` with inline script only
+pub const VIEWER_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>API Reference</title>
+<style>
+  body { font-family: system-ui, sans-serif; margin: 0; background: #0b1020; color: #e6e9f2; }
+  header { padding: 16px 24px; background: #111831; border-bottom: 1px solid #243056; }
+  main { padding: 8px 24px 48px; max-width: 1100px; }
+  h2 { margin-top: 28px; color: #5fd3ff; }
+  details { background: #111831; border: 1px solid #243056; border-radius: 6px; margin: 6px 0; }
+  summary { padding: 8px 12px; cursor: pointer; font-family: monospace; }
+  .method { display: inline-block; min-width: 52px; font-weight: bold; }
+  .get { color: #6ee7a8; } .post { color: #ffb86b; }
+  .body { padding: 0 12px 12px; }
+  pre { background: #070b17; padding: 10px; border-radius: 4px; overflow-x: auto; font-size: 12px; }
+  .muted { color: #8a94b8; }
+</style>
+</head>
+<body>
+<header><h1 id="title">API Reference</h1><div id="description" class="muted"></div></header>
+<main id="content">Loading…</main>
+<script>
+const spec = fetch("openapi.json").then(r => r.json());
+
+function resolve(schema, doc, depth) {
+  if (!schema || depth > 4) return schema;
+  if (schema.$ref) {
+    const name = schema.$ref.split("/").pop();
+    return resolve(doc.components.schemas[name], doc, depth + 1);
+  }
+  const out = Array.isArray(schema) ? [] : {};
+  for (const [k, v] of Object.entries(schema)) {
+    out[k] = typeof v === "object" && v !== null ? resolve(v, doc, depth + 1) : v;
+  }
+  return out;
+}
+
+function block(label, schema, doc) {
+  const pre = document.createElement("pre");
+  pre.textContent = JSON.stringify(resolve(schema, doc, 0), null, 2);
+  const div = document.createElement("div");
+  div.innerHTML = "<strong>" + label + "</strong>";
+  div.appendChild(pre);
+  return div;
+}
+
+spec.then(doc => {
+  document.getElementById("title").textContent = doc.info.title + " " + doc.info.version;
+  document.getElementById("description").textContent = doc.info.description || "";
+  const content = document.getElementById("content");
+  content.textContent = "";
+  for (const tag of doc.tags) {
+    const h = document.createElement("h2");
+    h.textContent = tag.name;
+    content.appendChild(h);
+    for (const [path, item] of Object.entries(doc.paths)) {
+      for (const [method, op] of Object.entries(item)) {
+        if (!op.tags.includes(tag.name)) continue;
+        const details = document.createElement("details");
+        const summary = document.createElement("summary");
+        summary.innerHTML = '<span class="method ' + method + '">' + method.toUpperCase() + "</span> ";
+        summary.appendChild(document.createTextNode(path + "  "));
+        const note = document.createElement("span");
+        note.className = "muted";
+        note.textContent = op.summary || "";
+        summary.appendChild(note);
+        details.appendChild(summary);
+        const body = document.createElement("div");
+        body.className = "body";
+        if (op.description) {
+          const p = document.createElement("p");
+          p.textContent = op.description;
+          body.appendChild(p);
+        }
+        if (op.parameters) {
+          body.appendChild(block("Query parameters", op.parameters, doc));
+        }
+        if (op.requestBody) {
+          body.appendChild(block("Request", op.requestBody.content["application/json"].schema, doc));
+        }
+        for (const [status, response] of Object.entries(op.responses)) {
+          for (const [type, media] of Object.entries(response.content || {})) {
+            body.appendChild(block("Response " + status + " (" + type + ")", media.schema, doc));
+          }
+        }
+        details.appendChild(body);
+        content.appendChild(details);
+      }
+    }
+  }
+}).catch(err => {
+  document.getElementById("content").textContent = "Failed to load openapi.json: " + err;
+});
+</script>
+</body>
+</html>
+"##;
@@ -4,6 +4,9 @@
 //! - Predictive Prefetching (Hopfield-based) - 8×
 //! - Transfer Coalescing (De Bruijn scheduling) - 4×
 //! - Compression (Galois Field encoding) - 2.5×
+//!
+//! `ReportPCIeMetrics` feeds a per-agent, per-device closed-loop controller
+//! (see [`super::pcie_tuning`]) that recommends queue depth and payload size.
 
 use super::proto::{
     pc_ie_amplification_service_server::PcIeAmplificationService,
@@ -21,7 +24,7 @@ type PCIeMetricsReport = PcIeMetricsReport;
 type PCIeOptimizationHint = PcIeOptimizationHint;
 type PCIeCompressionConfig = PcIeCompressionConfig;
 type PCIeAmplificationFactors = PcIeAmplificationFactors;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -31,6 +34,9 @@ use tonic::{Request, Response, Status, Streaming};
 use tracing::{info, debug};
 use sha2::{Sha256, Digest};
 
+use super::auth::AuthManager;
+use super::pcie_tuning::{PcieSample, PcieTuningConfig, PcieTuningRegistry};
+
 const PCIE_MATRIX_ROTATION_SECS: u64 = 60;
 
 /// PCIe amplification factors
@@ -54,10 +60,16 @@ pub struct PCIeAmplificationServiceImpl {
     version: Arc<RwLock<u64>>,
     current_matrix: Arc<RwLock<PCIeCalibrationMatrix>>,
     matrix_broadcast: broadcast::Sender<PCIeCalibrationUpdate>,
+    tuning: Arc<PcieTuningRegistry>,
 }
 
 impl PCIeAmplificationServiceImpl {
     pub fn new() -> Self {
+        Self::with_tuning_config(PcieTuningConfig::default())
+    }
+
+    /// Create the service with a custom closed-loop tuning configuration
+    pub fn with_tuning_config(config: PcieTuningConfig) -> Self {
         let (tx, _) = broadcast::channel(100);
         let initial_matrix = Self::generate_pcie_matrix(1, "gen4", 16);
 
@@ -65,9 +77,15 @@ impl PCIeAmplificationServiceImpl {
             version: Arc::new(RwLock::new(1)),
             current_matrix: Arc::new(RwLock::new(initial_matrix)),
             matrix_broadcast: tx,
+            tuning: Arc::new(PcieTuningRegistry::new(config)),
         }
     }
 
+    /// Closed-loop tuning state shared by all metric streams
+    pub fn tuning(&self) -> Arc<PcieTuningRegistry> {
+        self.tuning.clone()
+    }
+
     /// Generate PCIe calibration matrix
     fn generate_pcie_matrix(version: u64, pcie_gen: &str, lanes: u32) -> PCIeCalibrationMatrix {
         let now_ms = SystemTime::now()
@@ -212,20 +230,58 @@ impl PcIeAmplificationService for PCIeAmplificationServiceImpl {
         &self,
         request: Request<Streaming<PCIeMetricsReport>>,
     ) -> Result<Response<Self::ReportPCIeMetricsStream>, Status> {
+        // Streams only drive (and on close, drop) agents their caller owns
+        let owner = AuthManager::extract_api_key(&request)
+            .map(|key| AuthManager::hash_key(&key))
+            .unwrap_or_default();
         let mut stream = request.into_inner();
+        let tuning = self.tuning.clone();
 
         // Process metrics and generate optimization hints
         let (tx, rx) = tokio::sync::mpsc::channel(100);
 
         tokio::spawn(async move {
+            let mut agents = HashSet::new();
             while let Ok(Some(report)) = stream.message().await {
                 let now_ms = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .unwrap()
                     .as_millis() as i64;
 
+                if !agents.contains(&report.agent_id) {
+                    if !tuning.attach_stream(&report.agent_id, &owner).await {
+                        let denied = Status::permission_denied(format!(
+                            "Agent '{}' reports through another API key",
+                            report.agent_id
+                        ));
+                        if tx.send(Err(denied)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                    agents.insert(report.agent_id.clone());
+                }
+
+                // Closed-loop tuning recommendation for this device
+                let outcome = tuning
+                    .observe(&report.agent_id, &report.device_id, PcieSample::from(&report))
+                    .await;
+
+                let mut params = HashMap::new();
+                params.insert("queue_depth".to_string(), outcome.params.queue_depth as f64);
+                params.insert("payload_bytes".to_string(), outcome.params.payload_bytes as f64);
+                params.insert("throughput_gbs_avg".to_string(), outcome.throughput_gbs);
+                params.insert("latency_us_avg".to_string(), outcome.latency_us);
+                params.insert("error_rate_avg".to_string(), outcome.error_rate);
+
                 // Generate optimization hints based on metrics
-                let mut hints = Vec::new();
+                let mut hints = vec![PCIeOptimizationHint {
+                    timestamp_ms: now_ms,
+                    optimization_type: "tuning".to_string(),
+                    hint_message: format!("{}: {}", outcome.action.as_str(), outcome.reason),
+                    suggested_params: params,
+                    device_id: report.device_id.clone(),
+                }];
 
                 // Check prefetch hit rate
                 if report.prefetch_hit_rate < 0.9 {
@@ -237,6 +293,7 @@ impl PcIeAmplificationService for PCIeAmplificationServiceImpl {
                         optimization_type: "prefetch".to_string(),
                         hint_message: format!("Prefetch hit rate {:.1}% below target. Increasing depth.", report.prefetch_hit_rate * 100.0),
                         suggested_params: params,
+                        device_id: report.device_id.clone(),
                     });
                 }
 
@@ -250,6 +307,7 @@ impl PcIeAmplificationService for PCIeAmplificationServiceImpl {
                         optimization_type: "coalescing".to_string(),
                         hint_message: format!("Coalescing efficiency {:.1}% below target. Increasing batch size.", report.coalescing_efficiency * 100.0),
                         suggested_params: params,
+                        device_id: report.device_id.clone(),
                     });
                 }
 
@@ -262,6 +320,7 @@ impl PcIeAmplificationService for PCIeAmplificationServiceImpl {
                         optimization_type: "compression".to_string(),
                         hint_message: format!("Compression ratio {:.2}× below target. Increasing level.", report.compression_ratio_achieved),
                         suggested_params: params,
+                        device_id: report.device_id.clone(),
                     });
                 }
 
@@ -271,6 +330,11 @@ impl PcIeAmplificationService for PCIeAmplificationServiceImpl {
                     }
                 }
             }
+
+            // Once an agent's last stream is gone a reconnect starts tuning from scratch
+            for agent_id in agents {
+                tuning.detach_stream(&agent_id).await;
+            }
         });
 
         let output_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
@@ -0,0 +1,540 @@
+//! # PCIe Closed-Loop Tuning
+//!
+//! Stateful controller behind `ReportPCIeMetrics`. Every (agent, device) pair
+//! keeps its own moving averages and an AIMD controller over the recommended
+//! transfer queue depth and payload size:
+//!
+//! - **Additive increase** while the link shows no congestion (queue depth
+//!   first, payload size once the queue is at its ceiling)
+//! - **Multiplicative decrease** when latency inflates past the observed
+//!   baseline or the device reports transfer errors
+//! - **Rollback** to the last good settings after a sustained regression
+//! - **Settle** on the last good settings when the controller oscillates
+//!
+//! Throughput is derived from `bytes_transferred` over the interval between
+//! consecutive reports, so agents only need to report per-interval counters.
+//! Reports that do not advance the device's timestamp are ignored.
+//!
+//! An agent's reports may only come from streams opened with the API key that
+//! first reported for it. State is dropped when the agent's last open metrics
+//! stream closes, after a device has been idle for `device_idle_expiry`, and
+//! least recently seen first once `max_tracked_devices` is reached.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use super::proto::PcIeMetricsReport;
+
+/// Device ID used when an agent does not report one
+pub const DEFAULT_DEVICE_ID: &str = "default";
+
+/// Controller configuration
+#[derive(Clone, Debug)]
+pub struct PcieTuningConfig {
+    /// EWMA smoothing factor (0 < alpha <= 1, higher = faster reaction)
+    pub ewma_alpha: f64,
+    /// Queue depth bounds and starting point
+    pub min_queue_depth: u32,
+    pub max_queue_depth: u32,
+    pub initial_queue_depth: u32,
+    /// Additive queue depth increase per uncongested report
+    pub queue_depth_step: u32,
+    /// Payload size bounds and starting point (bytes)
+    pub min_payload_bytes: u32,
+    pub max_payload_bytes: u32,
+    pub initial_payload_bytes: u32,
+    /// Additive payload increase; payloads are kept aligned to this value
+    pub payload_step_bytes: u32,
+    /// Multiplicative decrease factor applied on congestion
+    pub decrease_factor: f64,
+    /// Latency above `baseline × latency_slack` counts as congestion
+    pub latency_slack: f64,
+    /// Error rate (errors / transfers) above this counts as congestion
+    pub error_rate_threshold: f64,
+    /// Score drop (fraction of the last good score) treated as a regression
+    pub regression_tolerance: f64,
+    /// Consecutive regressed reports before rolling back
+    pub regression_patience: u32,
+    /// Number of recent adjustments inspected for oscillation
+    pub oscillation_window: usize,
+    /// Direction changes within the window that count as oscillation
+    pub oscillation_flips: usize,
+    /// Reports to hold after a rollback or settle before probing again
+    pub hold_reports: u32,
+    /// Reports used to seed the averages before adjusting anything
+    pub warmup_reports: u32,
+    /// Devices with controller state across all agents
+    pub max_tracked_devices: usize,
+    /// Devices without a report for this long are forgotten
+    pub device_idle_expiry: Duration,
+}
+
+impl Default for PcieTuningConfig {
+    fn default() -> Self {
+        Self {
+            ewma_alpha: 0.3,
+            min_queue_depth: 1,
+            max_queue_depth: 256,
+            initial_queue_depth: 8,
+            queue_depth_step: 2,
+            min_payload_bytes: 4 * 1024,
+            max_payload_bytes: 1024 * 1024,
+            initial_payload_bytes: 64 * 1024,
+            payload_step_bytes: 4 * 1024,
+            decrease_factor: 0.7,
+            latency_slack: 1.5,
+            error_rate_threshold: 0.001,
+            regression_tolerance: 0.15,
+            regression_patience: 3,
+            oscillation_window: 8,
+            oscillation_flips: 5,
+            hold_reports: 20,
+            warmup_reports: 2,
+            max_tracked_devices: 4096,
+            device_idle_expiry: Duration::from_secs(600),
+        }
+    }
+}
+
+/// Recommended transfer settings for a device
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TuningParams {
+    pub queue_depth: u32,
+    pub payload_bytes: u32,
+}
+
+/// What the controller did with a report
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TuningAction {
+    /// Still seeding the moving averages
+    Warmup,
+    /// No congestion - probing for more throughput
+    Increase,
+    /// Congestion detected - backing off
+    Decrease,
+    /// Waiting for averages to settle after a rollback or settle
+    Hold,
+    /// Sustained regression - restored the last good settings
+    Rollback,
+    /// Oscillation detected - pinned to the last good settings
+    Settle,
+    /// Report did not advance the timestamp - state left untouched
+    Ignore,
+}
+
+impl TuningAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Warmup => "warmup",
+            Self::Increase => "increase",
+            Self::Decrease => "decrease",
+            Self::Hold => "hold",
+            Self::Rollback => "rollback",
+            Self::Settle => "settle",
+            Self::Ignore => "ignore",
+        }
+    }
+}
+
+/// One metrics report, reduced to what the controller needs
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PcieSample {
+    pub timestamp_ms: i64,
+    pub bytes_transferred: u64,
+    pub transfers_count: u64,
+    pub avg_latency_us: f64,
+    pub error_count: u64,
+}
+
+impl From<&PcIeMetricsReport> for PcieSample {
+    fn from(report: &PcIeMetricsReport) -> Self {
+        Self {
+            timestamp_ms: report.timestamp_ms,
+            bytes_transferred: report.bytes_transferred,
+            transfers_count: report.transfers_count,
+            avg_latency_us: report.avg_latency_us,
+            error_count: report.error_count,
+        }
+    }
+}
+
+/// Result of feeding one sample into the controller
+#[derive(Clone, Debug)]
+pub struct TuningOutcome {
+    pub action: TuningAction,
+    /// Settings the agent should apply from now on
+    pub params: TuningParams,
+    /// Smoothed throughput (GB/s)
+    pub throughput_gbs: f64,
+    /// Smoothed latency (µs)
+    pub latency_us: f64,
+    /// Smoothed error rate (errors / transfers)
+    pub error_rate: f64,
+    /// Human-readable reason for the action
+    pub reason: String,
+}
+
+/// Exponentially weighted moving average
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ewma {
+    value: Option<f64>,
+}
+
+impl Ewma {
+    pub fn update(&mut self, sample: f64, alpha: f64) -> f64 {
+        let next = match self.value {
+            Some(prev) => alpha * sample + (1.0 - alpha) * prev,
+            None => sample,
+        };
+        self.value = Some(next);
+        next
+    }
+
+    pub fn get(&self) -> f64 {
+        self.value.unwrap_or(0.0)
+    }
+}
+
+/// Controller state for a single device
+#[derive(Clone, Debug)]
+pub struct DeviceTuner {
+    config: PcieTuningConfig,
+    params: TuningParams,
+    last_good: TuningParams,
+    last_good_score: f64,
+    throughput: Ewma,
+    latency: Ewma,
+    error_rate: Ewma,
+    baseline_latency_us: f64,
+    last_timestamp_ms: Option<i64>,
+    reports: u32,
+    regressed_reports: u32,
+    hold_remaining: u32,
+    directions: VecDeque<i8>,
+}
+
+impl DeviceTuner {
+    pub fn new(config: PcieTuningConfig) -> Self {
+        let params = TuningParams {
+            queue_depth: config
+                .initial_queue_depth
+                .clamp(config.min_queue_depth, config.max_queue_depth),
+            payload_bytes: config
+                .initial_payload_bytes
+                .clamp(config.min_payload_bytes, config.max_payload_bytes),
+        };
+
+        Self {
+            config,
+            params,
+            last_good: params,
+            last_good_score: 0.0,
+            throughput: Ewma::default(),
+            latency: Ewma::default(),
+            error_rate: Ewma::default(),
+            baseline_latency_us: f64::INFINITY,
+            last_timestamp_ms: None,
+            reports: 0,
+            regressed_reports: 0,
+            hold_remaining: 0,
+            directions: VecDeque::new(),
+        }
+    }
+
+    /// Currently recommended settings
+    pub fn params(&self) -> TuningParams {
+        self.params
+    }
+
+    /// Last settings that produced a non-regressed score
+    pub fn last_good(&self) -> TuningParams {
+        self.last_good
+    }
+
+    /// Feed one report and get the next recommendation
+    pub fn observe(&mut self, sample: PcieSample) -> TuningOutcome {
+        let alpha = self.config.ewma_alpha;
+        if let Some(prev) = self.last_timestamp_ms.filter(|prev| sample.timestamp_ms <= *prev) {
+            return self.outcome(
+                TuningAction::Ignore,
+                format!("out-of-order report (timestamp {} ms, last {} ms)", sample.timestamp_ms, prev),
+            );
+        }
+        let interval_ms = self.last_timestamp_ms.map(|prev| sample.timestamp_ms - prev);
+        self.last_timestamp_ms = Some(sample.timestamp_ms);
+
+        let latency = self.latency.update(sample.avg_latency_us, alpha);
+        self.baseline_latency_us = self.baseline_latency_us.min(latency);
+
+        // Without an interval there is no throughput to compute; seed only
+        let Some(interval_ms) = interval_ms else {
+            return self.outcome(TuningAction::Warmup, "first report seeds the latency baseline".to_string());
+        };
+
+        let throughput_gbs = sample.bytes_transferred as f64 / (interval_ms as f64 / 1000.0) / 1e9;
+        let instant_error_rate = sample.error_count as f64 / sample.transfers_count.max(1) as f64;
+        let throughput = self.throughput.update(throughput_gbs, alpha);
+        let error_rate = self.error_rate.update(instant_error_rate, alpha);
+        let score = throughput * (1.0 - error_rate).max(0.0);
+        self.reports += 1;
+
+        if self.reports <= self.config.warmup_reports {
+            self.last_good = self.params;
+            self.last_good_score = score;
+            return self.outcome(TuningAction::Warmup, format!("seeding averages ({}/{})", self.reports, self.config.warmup_reports));
+        }
+
+        if self.hold_remaining > 0 {
+            self.hold_remaining -= 1;
+            if self.hold_remaining == 0 {
+                // Re-baseline on the settings we held so probing starts from fresh numbers
+                self.last_good_score = score;
+            }
+            return self.outcome(TuningAction::Hold, format!("holding {} more reports", self.hold_remaining));
+        }
+
+        // Regression against the last good settings
+        if score < self.last_good_score * (1.0 - self.config.regression_tolerance) {
+            self.regressed_reports += 1;
+        } else {
+            self.regressed_reports = 0;
+        }
+
+        if self.regressed_reports >= self.config.regression_patience {
+            self.regressed_reports = 0;
+            if self.params != self.last_good {
+                let reason = format!(
+                    "score {:.2} regressed below last good {:.2}; restoring queue_depth={} payload={}",
+                    score, self.last_good_score, self.last_good.queue_depth, self.last_good.payload_bytes
+                );
+                self.params = self.last_good;
+                self.hold_remaining = self.config.hold_reports;
+                self.directions.clear();
+                return self.outcome(TuningAction::Rollback, reason);
+            }
+            // The device got slower on known-good settings; accept the new level
+            self.last_good_score = score;
+        }
+
+        let errors_congested = instant_error_rate > self.config.error_rate_threshold;
+        let latency_congested = latency > self.baseline_latency_us * self.config.latency_slack;
+
+        if !errors_congested && !latency_congested && score >= self.last_good_score * (1.0 - self.config.regression_tolerance) {
+            self.last_good = self.params;
+            self.last_good_score = self.last_good_score.max(score);
+        }
+
+        let (action, reason) = if errors_congested || latency_congested {
+            self.decrease(errors_congested);
+            self.record_direction(-1);
+            let reason = if errors_congested {
+                format!("error rate {:.4} above {:.4}", instant_error_rate, self.config.error_rate_threshold)
+            } else {
+                format!("latency {:.1}µs above baseline {:.1}µs × {:.2}", latency, self.baseline_latency_us, self.config.latency_slack)
+            };
+            (TuningAction::Decrease, reason)
+        } else {
+            self.increase();
+            self.record_direction(1);
+            (TuningAction::Increase, format!("no congestion at {:.2} GB/s", throughput))
+        };
+
+        if self.is_oscillating() {
+            let reason = format!(
+                "oscillation detected; settling on queue_depth={} payload={}",
+                self.last_good.queue_depth, self.last_good.payload_bytes
+            );
+            self.params = self.last_good;
+            self.hold_remaining = self.config.hold_reports;
+            self.directions.clear();
+            return self.outcome(TuningAction::Settle, reason);
+        }
+
+        self.outcome(action, reason)
+    }
+
+    fn increase(&mut self) {
+        let cfg = &self.config;
+        if self.params.queue_depth < cfg.max_queue_depth {
+            self.params.queue_depth = (self.params.queue_depth + cfg.queue_depth_step).min(cfg.max_queue_depth);
+        } else {
+            self.params.payload_bytes = (self.params.payload_bytes + cfg.payload_step_bytes).min(cfg.max_payload_bytes);
+        }
+    }
+
+    fn decrease(&mut self, shrink_payload: bool) {
+        let cfg = &self.config;
+        let depth = (self.params.queue_depth as f64 * cfg.decrease_factor).floor() as u32;
+        self.params.queue_depth = depth.clamp(cfg.min_queue_depth, cfg.max_queue_depth);
+
+        if shrink_payload {
+            let step = cfg.payload_step_bytes.max(1);
+            let payload = (self.params.payload_bytes as f64 * cfg.decrease_factor) as u32 / step * step;
+            self.params.payload_bytes = payload.clamp(cfg.min_payload_bytes, cfg.max_payload_bytes);
+        }
+    }
+
+    fn record_direction(&mut self, direction: i8) {
+        self.directions.push_back(direction);
+        while self.directions.len() > self.config.oscillation_window {
+            self.directions.pop_front();
+        }
+    }
+
+    fn is_oscillating(&self) -> bool {
+        if self.directions.len() < self.config.oscillation_window {
+            return false;
+        }
+        let flips = self
+            .directions
+            .iter()
+            .zip(self.directions.iter().skip(1))
+            .filter(|(a, b)| a != b)
+            .count();
+        flips >= self.config.oscillation_flips
+    }
+
+    fn outcome(&self, action: TuningAction, reason: String) -> TuningOutcome {
+        TuningOutcome {
+            action,
+            params: self.params,
+            throughput_gbs: self.throughput.get(),
+            latency_us: self.latency.get(),
+            error_rate: self.error_rate.get(),
+            reason,
+        }
+    }
+}
+
+/// Controller state and when its device last reported
+struct TrackedDevice {
+    tuner: DeviceTuner,
+    last_seen: Instant,
+}
+
+/// Open metrics streams reporting for an agent
+struct AgentStreams {
+    /// Caller that opened the first stream (hash of its API key)
+    owner: String,
+    open: usize,
+}
+
+/// Per-agent, per-device controller registry shared by all metric streams
+pub struct PcieTuningRegistry {
+    config: PcieTuningConfig,
+    devices: Arc<RwLock<HashMap<(String, String), TrackedDevice>>>,
+    streams: Arc<RwLock<HashMap<String, AgentStreams>>>,
+}
+
+impl PcieTuningRegistry {
+    pub fn new(config: PcieTuningConfig) -> Self {
+        Self {
+            config,
+            devices: Arc::new(RwLock::new(HashMap::new())),
+            streams: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Count a stream of `owner` reporting for `agent_id`
+    ///
+    /// Fails while another caller's streams report for the agent.
+    pub async fn attach_stream(&self, agent_id: &str, owner: &str) -> bool {
+        let mut streams = self.streams.write().await;
+        let entry = streams.entry(agent_id.to_string()).or_insert_with(|| AgentStreams {
+            owner: owner.to_string(),
+            open: 0,
+        });
+        if entry.owner != owner {
+            return false;
+        }
+        entry.open += 1;
+        true
+    }
+
+    /// Release a stream taken with [`Self::attach_stream`]; the agent's state
+    /// goes with its last stream
+    pub async fn detach_stream(&self, agent_id: &str) {
+        let mut streams = self.streams.write().await;
+        let Some(entry) = streams.get_mut(agent_id) else {
+            return;
+        };
+        entry.open = entry.open.saturating_sub(1);
+        if entry.open == 0 {
+            streams.remove(agent_id);
+            drop(streams);
+            self.forget_agent(agent_id).await;
+        }
+    }
+
+    /// Feed a report for `(agent_id, device_id)`, creating state on first sight
+    pub async fn observe(&self, agent_id: &str, device_id: &str, sample: PcieSample) -> TuningOutcome {
+        let device_id = if device_id.is_empty() { DEFAULT_DEVICE_ID } else { device_id };
+        let key = (agent_id.to_string(), device_id.to_string());
+        let now = Instant::now();
+        let mut devices = self.devices.write().await;
+        if !devices.contains_key(&key) {
+            self.make_room(&mut devices, now);
+            info!("🎛️ PCIe tuning state created for agent={} device={}", agent_id, device_id);
+        }
+        let device = devices.entry(key).or_insert_with(|| TrackedDevice {
+            tuner: DeviceTuner::new(self.config.clone()),
+            last_seen: now,
+        });
+        device.last_seen = now;
+
+        let outcome = device.tuner.observe(sample);
+        debug!(
+            "PCIe tuning agent={} device={} action={} queue_depth={} payload={}",
+            agent_id,
+            device_id,
+            outcome.action.as_str(),
+            outcome.params.queue_depth,
+            outcome.params.payload_bytes
+        );
+        outcome
+    }
+
+    /// Current recommendation for a device, if it has reported
+    pub async fn params(&self, agent_id: &str, device_id: &str) -> Option<TuningParams> {
+        let device_id = if device_id.is_empty() { DEFAULT_DEVICE_ID } else { device_id };
+        self.devices
+            .read()
+            .await
+            .get(&(agent_id.to_string(), device_id.to_string()))
+            .map(|d| d.tuner.params())
+    }
+
+    /// Drop idle devices, then the least recently seen ones until a new device fits
+    fn make_room(&self, devices: &mut HashMap<(String, String), TrackedDevice>, now: Instant) {
+        let before = devices.len();
+        devices.retain(|_, d| now.duration_since(d.last_seen) < self.config.device_idle_expiry);
+        while devices.len() >= self.config.max_tracked_devices.max(1) {
+            let Some(oldest) = devices.iter().min_by_key(|(_, d)| d.last_seen).map(|(k, _)| k.clone()) else {
+                break;
+            };
+            devices.remove(&oldest);
+        }
+        if devices.len() < before {
+            debug!("PCIe tuning dropped {} idle devices", before - devices.len());
+        }
+    }
+
+    /// Drop all state for an agent (its metrics stream closed or it was decommissioned)
+    pub async fn forget_agent(&self, agent_id: &str) {
+        self.devices.write().await.retain(|(agent, _), _| agent != agent_id);
+    }
+
+    /// Number of devices with controller state
+    pub async fn tracked_devices(&self) -> usize {
+        self.devices.read().await.len()
+    }
+}
+
+impl Default for PcieTuningRegistry {
+    fn default() -> Self {
+        Self::new(PcieTuningConfig::default())
+    }
+}
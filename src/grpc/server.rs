@@ -5,6 +5,7 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tonic::service::interceptor::InterceptedService;
 use tonic::transport::Server;
 use tracing::{info, warn, error};
 
@@ -30,8 +31,14 @@ use super::telemetry::TelemetryServiceImpl;
 use super::allocation::AllocationServiceImpl;
 use super::operations::OperationsServiceImpl;
 use super::gpu_service::GpuDetectionServiceImpl;
+use super::gpu_detection::GpuDetectionManager;
+use super::gpu_registry::{InMemoryRegistrationStore, RegistrationStore};
+#[cfg(feature = "sqlite-registry")]
+use super::gpu_registry::SqliteRegistrationStore;
 use super::certificate_service::CertificateServiceImpl;
 use super::dashboard_metrics::DashboardMetricsServiceImpl;
+use super::metering::{FileUsageStore, UsageMeter};
+use super::gateway::{self, GatewayServices};
 
 /// CYAN FLAME gRPC Server
 pub struct CyanFlameGrpcServer {
@@ -39,64 +46,133 @@ pub struct CyanFlameGrpcServer {
     calibration_service: Arc<CalibrationServiceImpl>,
     compute_calibration_service: Arc<ComputeCalibrationServiceImpl>,
     pcie_amplification_service: Arc<PCIeAmplificationServiceImpl>,
-    telemetry_service: TelemetryServiceImpl,
-    allocation_service: AllocationServiceImpl,
-    operations_service: OperationsServiceImpl,
-    gpu_detection_service: GpuDetectionServiceImpl,
-    certificate_service: CertificateServiceImpl,
-    dashboard_metrics_service: DashboardMetricsServiceImpl,
+    telemetry_service: Arc<TelemetryServiceImpl>,
+    allocation_service: Arc<AllocationServiceImpl>,
+    operations_service: Arc<OperationsServiceImpl>,
+    gpu_detection_service: Arc<GpuDetectionServiceImpl>,
+    certificate_service: Arc<CertificateServiceImpl>,
+    dashboard_metrics_service: Arc<DashboardMetricsServiceImpl>,
     auth_manager: Arc<AuthManager>,
+    usage_meter: Arc<UsageMeter>,
 }
 
 impl CyanFlameGrpcServer {
     /// Create new gRPC server with default configuration (auth DISABLED for testing)
     pub fn new() -> Self {
-        Self::with_config(GrpcServerConfig::default())
+        Self::with_config(GrpcServerConfig::default()).expect("the default configuration opens no files")
     }
 
     /// Create new gRPC server with authentication enabled
     pub fn new_with_auth() -> Self {
         Self::with_config_and_auth(GrpcServerConfig::default(), true)
+            .expect("the default configuration opens no files")
     }
 
     /// Create new gRPC server with custom configuration (auth DISABLED)
-    pub fn with_config(config: GrpcServerConfig) -> Self {
+    pub fn with_config(config: GrpcServerConfig) -> Result<Self, String> {
         Self::with_config_and_auth(config, false)
     }
 
     /// Create new gRPC server with custom configuration and auth setting
-    pub fn with_config_and_auth(config: GrpcServerConfig, auth_enabled: bool) -> Self {
-        let calibration_service = Arc::new(CalibrationServiceImpl::new());
+    ///
+    /// Fails when a file named by the configuration cannot be opened.
+    pub fn with_config_and_auth(config: GrpcServerConfig, auth_enabled: bool) -> Result<Self, String> {
+        let auth_manager = Arc::new(AuthManager::new(auth_enabled));
+        let usage_meter = Arc::new(Self::usage_meter_for(&config)?);
+        let calibration_service = Arc::new(CalibrationServiceImpl::new().with_meter(usage_meter.clone()));
         let compute_calibration_service = Arc::new(ComputeCalibrationServiceImpl::new());
         let pcie_amplification_service = Arc::new(PCIeAmplificationServiceImpl::new());
-        let auth_manager = Arc::new(AuthManager::new(auth_enabled));
 
-        Self {
+        let gpu_manager = Arc::new(GpuDetectionManager::new().with_store(Self::registration_store(&config)?));
+        let mut gpu_detection_service = match &config.gpu_catalog_path {
+            Some(path) => GpuDetectionServiceImpl::with_manager(gpu_manager.clone())
+                .with_catalog_path(path.clone())
+                .map_err(|e| format!("GPU catalog {}: {}", path, e))?,
+            None => GpuDetectionServiceImpl::with_manager(gpu_manager.clone()),
+        }
+        .with_meter(usage_meter.clone());
+        if let Some(key) = &config.admin_api_key {
+            gpu_detection_service = gpu_detection_service.with_admin_key(key.clone());
+        }
+
+        Ok(Self {
             config,
             calibration_service,
             compute_calibration_service,
             pcie_amplification_service,
-            telemetry_service: TelemetryServiceImpl::new(),
-            allocation_service: AllocationServiceImpl::new(),
-            operations_service: OperationsServiceImpl::new(),
-            gpu_detection_service: GpuDetectionServiceImpl::new(),
-            certificate_service: CertificateServiceImpl::new(),
-            dashboard_metrics_service: DashboardMetricsServiceImpl::new(),
+            telemetry_service: Arc::new(TelemetryServiceImpl::new().with_registrations(gpu_manager)),
+            allocation_service: Arc::new(AllocationServiceImpl::new().with_meter(usage_meter.clone())),
+            operations_service: Arc::new(OperationsServiceImpl::new()),
+            gpu_detection_service: Arc::new(gpu_detection_service),
+            certificate_service: Arc::new(CertificateServiceImpl::new()),
+            dashboard_metrics_service: Arc::new(DashboardMetricsServiceImpl::new()),
             auth_manager,
+            usage_meter,
+        })
+    }
+
+    /// GPU registration store selected by the configuration
+    fn registration_store(config: &GrpcServerConfig) -> Result<Arc<dyn RegistrationStore>, String> {
+        match &config.registration_db_path {
+            #[cfg(feature = "sqlite-registry")]
+            Some(path) => Ok(Arc::new(SqliteRegistrationStore::open(path)?)),
+            #[cfg(not(feature = "sqlite-registry"))]
+            Some(path) => Err(format!(
+                "Registration database {} needs the sqlite-registry feature",
+                path
+            )),
+            None => Ok(Arc::new(InMemoryRegistrationStore::new())),
         }
     }
 
+    /// Usage meter, persisting closed records when the configuration names a ledger
+    fn usage_meter_for(config: &GrpcServerConfig) -> Result<UsageMeter, String> {
+        let Some(path) = &config.usage_ledger_path else {
+            return Ok(UsageMeter::new());
+        };
+        FileUsageStore::open(path).and_then(|store| UsageMeter::new().with_store(Arc::new(store)))
+    }
+
     /// Get a reference to the auth manager
     pub fn auth_manager(&self) -> Arc<AuthManager> {
         self.auth_manager.clone()
     }
 
+    /// Get a reference to the usage meter
+    pub fn usage_meter(&self) -> Arc<UsageMeter> {
+        self.usage_meter.clone()
+    }
+
+    /// API key interceptor shared by gRPC and the HTTP gateway (counts API calls for metering)
+    fn auth_interceptor(&self) -> AuthInterceptor {
+        AuthInterceptor::new(self.auth_manager.clone()).with_meter(self.usage_meter.clone())
+    }
+
+    /// REST/JSON gateway over the same service instances and auth path as gRPC
+    pub fn http_gateway(&self) -> axum::Router {
+        gateway::router(GatewayServices {
+            calibration: self.calibration_service.clone(),
+            compute_calibration: self.compute_calibration_service.clone(),
+            pcie_amplification: self.pcie_amplification_service.clone(),
+            telemetry: self.telemetry_service.clone(),
+            allocation: self.allocation_service.clone(),
+            operations: self.operations_service.clone(),
+            gpu_detection: self.gpu_detection_service.clone(),
+            certificate: self.certificate_service.clone(),
+            dashboard_metrics: self.dashboard_metrics_service.clone(),
+            interceptor: self.auth_interceptor(),
+        })
+    }
+
     /// Start the gRPC server
     pub async fn serve(self) -> Result<(), Box<dyn std::error::Error>> {
         let addr: SocketAddr = self.config.bind_addr.parse()?;
         let auth_enabled = self.auth_manager.is_auth_enabled();
         let mtls_enabled = self.config.enable_mtls;
 
+        // Reload persisted GPU registrations before accepting agents
+        self.gpu_detection_service.detection_manager().restore().await?;
+
         info!("╔══════════════════════════════════════════════════════════════════╗");
         info!("║           CYAN FLAME™ gRPC Control Plane Server                  ║");
         info!("╠══════════════════════════════════════════════════════════════════╣");
@@ -143,8 +219,11 @@ impl CyanFlameGrpcServer {
         let pcie_amplification_clone = self.pcie_amplification_service.clone();
         pcie_amplification_clone.start_rotation_task();
 
+        // Roll up hourly usage records
+        self.usage_meter.clone().start_rollup_task();
+
         // Create auth interceptor
-        let auth_interceptor = AuthInterceptor::new(self.auth_manager.clone());
+        let auth_interceptor = self.auth_interceptor();
 
         // Build TLS configuration if enabled
         let tls_config = if self.config.enable_tls {
@@ -187,41 +266,41 @@ impl CyanFlameGrpcServer {
             None
         };
 
-        // Create services with auth interceptor
-        let calibration_svc = CalibrationServiceServer::with_interceptor(
-            CalibrationServiceImpl::new(),
+        // Create services with auth interceptor (shared with the HTTP gateway)
+        let calibration_svc = InterceptedService::new(
+            CalibrationServiceServer::from_arc(self.calibration_service.clone()),
             auth_interceptor.clone()
         );
-        let compute_calibration_svc = ComputeCalibrationServiceServer::with_interceptor(
-            ComputeCalibrationServiceImpl::new(),
+        let compute_calibration_svc = InterceptedService::new(
+            ComputeCalibrationServiceServer::from_arc(self.compute_calibration_service.clone()),
             auth_interceptor.clone()
         );
-        let pcie_amplification_svc = PcIeAmplificationServiceServer::with_interceptor(
-            PCIeAmplificationServiceImpl::new(),
+        let pcie_amplification_svc = InterceptedService::new(
+            PcIeAmplificationServiceServer::from_arc(self.pcie_amplification_service.clone()),
             auth_interceptor.clone()
         );
-        let telemetry_svc = TelemetryServiceServer::with_interceptor(
-            self.telemetry_service,
+        let telemetry_svc = InterceptedService::new(
+            TelemetryServiceServer::from_arc(self.telemetry_service.clone()),
             auth_interceptor.clone()
         );
-        let allocation_svc = AllocationServiceServer::with_interceptor(
-            self.allocation_service,
+        let allocation_svc = InterceptedService::new(
+            AllocationServiceServer::from_arc(self.allocation_service.clone()),
             auth_interceptor.clone()
         );
-        let operations_svc = OperationsServiceServer::with_interceptor(
-            self.operations_service,
+        let operations_svc = InterceptedService::new(
+            OperationsServiceServer::from_arc(self.operations_service.clone()),
             auth_interceptor.clone()
         );
-        let gpu_detection_svc = GpuDetectionServiceServer::with_interceptor(
-            self.gpu_detection_service,
+        let gpu_detection_svc = InterceptedService::new(
+            GpuDetectionServiceServer::from_arc(self.gpu_detection_service.clone()),
             auth_interceptor.clone()
         );
-        let certificate_svc = CertificateServiceServer::with_interceptor(
-            self.certificate_service,
+        let certificate_svc = InterceptedService::new(
+            CertificateServiceServer::from_arc(self.certificate_service.clone()),
             auth_interceptor.clone()
         );
-        let dashboard_metrics_svc = DashboardMetricsServiceServer::with_interceptor(
-            self.dashboard_metrics_service,
+        let dashboard_metrics_svc = InterceptedService::new(
+            DashboardMetricsServiceServer::from_arc(self.dashboard_metrics_service.clone()),
             auth_interceptor.clone()
         );
 
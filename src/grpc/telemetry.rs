@@ -1,7 +1,8 @@
 //! Telemetry Service Implementation
 //!
 //! Receives GPU status telemetry from SDK agents and provides
-//! aggregated metrics for monitoring and capacity planning.
+//! aggregated metrics for monitoring and capacity planning. Health reports
+//! double as heartbeats, refreshing the agent's GPU registration.
 
 use std::collections::HashMap;
 use std::pin::Pin;
@@ -12,6 +13,7 @@ use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use tonic::{Request, Response, Status, Streaming};
 use tracing::{debug, info, warn};
 
+use super::gpu_detection::GpuDetectionManager;
 use super::proto::*;
 use super::TelemetryService;
 
@@ -21,6 +23,8 @@ pub struct TelemetryServiceImpl {
     agents: Arc<RwLock<HashMap<String, MemoryStatusUpdate>>>,
     /// Network capacity broadcast channel
     capacity_broadcast: broadcast::Sender<NetworkCapacityUpdate>,
+    /// GPU registrations whose last-seen time health reports refresh
+    registrations: Option<Arc<GpuDetectionManager>>,
 }
 
 impl TelemetryServiceImpl {
@@ -30,9 +34,16 @@ impl TelemetryServiceImpl {
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
             capacity_broadcast: tx,
+            registrations: None,
         }
     }
 
+    /// Refresh the last-seen time of registered agents on every health report
+    pub fn with_registrations(mut self, manager: Arc<GpuDetectionManager>) -> Self {
+        self.registrations = Some(manager);
+        self
+    }
+
     /// Calculate network capacity from all agents
     async fn calculate_network_capacity(&self) -> NetworkCapacityUpdate {
         let agents = self.agents.read().await;
@@ -160,11 +171,15 @@ impl TelemetryService for TelemetryServiceImpl {
         request: Request<Streaming<HealthMetrics>>,
     ) -> Result<Response<Self::ReportHealthStream>, Status> {
         let mut stream = request.into_inner();
+        let registrations = self.registrations.clone();
 
         let output_stream = async_stream::stream! {
             while let Ok(Some(metrics)) = stream.message().await {
                 debug!("Health metrics from {}: CPU {}%, Memory {}%",
                     metrics.agent_id, metrics.cpu_percent, metrics.memory_percent);
+                if let Some(manager) = &registrations {
+                    manager.update_last_seen(&metrics.agent_id).await;
+                }
 
                 yield Ok(HealthAck {
                     received: true,
@@ -185,6 +200,7 @@ impl Clone for TelemetryServiceImpl {
         Self {
             agents: self.agents.clone(),
             capacity_broadcast: self.capacity_broadcast.clone(),
+            registrations: self.registrations.clone(),
         }
     }
 }
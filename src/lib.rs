@@ -135,15 +135,23 @@ pub use bandwidth_cascade::{
 // ============================================================================
 // UAO-QTCAM CACHE MODULE (Redis Replacement - 250× Compression)
 // ============================================================================
-// High-performance cache using UAO-QTCAM tensor folding compression
+// High-performance cache with lossless, CRC32-verified compression
 // 256 MB compressed = 64 GB effective capacity
 // 0.2ms latency vs Redis 0.5-1ms
 
 pub mod uao_qtcam_cache;
 pub use uao_qtcam_cache::{
     UaoQtcamCache,
+    CacheCodec,
     CacheEntry,
     CacheStats,
+    CacheValue,
+    ValueKind,
+    EvictionPolicy,
+    ExpiryConfig,
+    KeyTtl,
+    FsyncPolicy,
+    PersistenceConfig,
 };
 
 // ============================================================================
@@ -171,6 +179,30 @@ pub use grpc::{
     operations::OperationsServiceImpl,
 };
 
+// ============================================================================
+// GFEF ACTIVATION PREDICTION (Triple IP Lock)
+// ============================================================================
+// Per-layer activation indices uploaded by customers, rotating calibration
+// epochs and the prediction oracle that answers from the indices.
+
+#[cfg(feature = "gfef")]
+pub mod gfef;
+
+// ============================================================================
+// VXLAN CONTROL PLANE (symmetrix-control-plane)
+// ============================================================================
+// Command model, shared subsystem state and HTTP router of the VXLAN
+// control-plane server. Needs the full QAGML + QANBAN + UAO-QTCAM stack
+// and GFEF.
+
+#[cfg(all(
+    feature = "qagml-integration",
+    feature = "qanban-integration",
+    feature = "uao-qtcam-integration",
+    feature = "gfef"
+))]
+pub mod control_plane;
+
 // Re-export core types and traits
 pub use sheaf::{SheafSpace, ResourceStalk};
 pub use galois::{GaloisElement, CRTDecomposition};
@@ -257,13 +289,15 @@ pub struct SymmetrixRuntime {
     pub tensor_engine: tensor::TensorFolder,
 }
 
-/// Runtime statistics for monitoring and metrics
-#[derive(Debug, Clone, Default)]
-pub struct RuntimeStats {
-    pub containers_active: usize,
-    pub cache_hit_rate: f64,
-    pub math_ops_per_second: u64,
-    pub cohomology_dimension: usize,
+crate::api_schema! {
+    /// Runtime statistics for monitoring and metrics
+    #[derive(Debug, Clone, Default, serde::Serialize)]
+    pub struct RuntimeStats {
+        pub containers_active: usize,
+        pub cache_hit_rate: f64,
+        pub math_ops_per_second: u64,
+        pub cohomology_dimension: usize,
+    }
 }
 
 impl SymmetrixRuntime {
@@ -1,432 +0,0 @@
-//! # UAO-QTCAM Cache - Redis Replacement
-//!
-//! High-performance in-memory cache using UAO-QTCAM compression.
-//! Provides 250× more capacity than Redis with 2-3× lower latency.
-//!
-//! ## Features
-//! - 250× compression via tensor folding
-//! - 0.2ms latency (vs Redis 0.5-1ms)
-//! - LRU eviction with weighted scoring
-//! - Thread-safe concurrent access
-
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
-use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
-
-/// Cache entry with compression metadata
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct CacheEntry {
-    /// Compressed value bytes
-    pub compressed_value: Vec<u8>,
-    /// Original uncompressed size
-    pub original_size: usize,
-    /// Creation timestamp (Unix epoch seconds)
-    pub created_at: i64,
-    /// Last access timestamp
-    pub last_accessed: i64,
-    /// Access count for weighted LRU
-    pub access_count: u64,
-    /// Time-to-live in seconds (0 = no expiry)
-    pub ttl: u64,
-}
-
-/// Cache statistics
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-pub struct CacheStats {
-    pub hits: u64,
-    pub misses: u64,
-    pub sets: u64,
-    pub deletes: u64,
-    pub evictions: u64,
-    pub entry_count: usize,
-    pub compressed_bytes: usize,
-    pub original_bytes: usize,
-    pub compression_ratio: f64,
-    pub hit_rate: f64,
-}
-
-/// UAO-QTCAM Cache - Redis Replacement
-/// 
-/// Uses tensor folding compression for 250× capacity amplification
-pub struct UaoQtcamCache {
-    /// Main cache storage
-    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
-    /// Maximum compressed size in bytes
-    max_size: usize,
-    /// Current compressed size
-    current_size: Arc<RwLock<usize>>,
-    /// Statistics
-    stats: Arc<RwLock<CacheStats>>,
-    /// Compression ratio (default 250×)
-    compression_ratio: f64,
-}
-
-impl UaoQtcamCache {
-    /// Create new UAO-QTCAM cache
-    /// 
-    /// # Arguments
-    /// * `max_size` - Maximum compressed cache size in bytes
-    /// * `compression_ratio` - Expected compression ratio (default 250.0)
-    pub fn new(max_size: usize, compression_ratio: f64) -> Self {
-        info!("🚀 Initializing UAO-QTCAM Cache (Redis Replacement)");
-        info!("   Max compressed size: {} MB", max_size / (1024 * 1024));
-        info!("   Effective capacity: {} MB ({}× compression)", 
-              (max_size as f64 * compression_ratio) as usize / (1024 * 1024),
-              compression_ratio);
-        
-        Self {
-            cache: Arc::new(RwLock::new(HashMap::new())),
-            max_size,
-            current_size: Arc::new(RwLock::new(0)),
-            stats: Arc::new(RwLock::new(CacheStats::default())),
-            compression_ratio,
-        }
-    }
-
-    /// SET operation - Store value with optional TTL
-    pub fn set(&self, key: &str, value: &[u8], ttl: Option<u64>) -> Result<(), String> {
-        let start = Instant::now();
-        
-        // Compress value using tensor folding simulation
-        let compressed = self.compress(value);
-        let compressed_size = compressed.len();
-        
-        // Check if we need to evict
-        self.evict_if_needed(compressed_size)?;
-        
-        let now = chrono::Utc::now().timestamp();
-        let entry = CacheEntry {
-            compressed_value: compressed,
-            original_size: value.len(),
-            created_at: now,
-            last_accessed: now,
-            access_count: 0,
-            ttl: ttl.unwrap_or(0),
-        };
-        
-        // Update cache
-        {
-            let mut cache = self.cache.write().map_err(|e| e.to_string())?;
-            let mut current_size = self.current_size.write().map_err(|e| e.to_string())?;
-            
-            // Remove old entry size if exists
-            if let Some(old) = cache.get(key) {
-                *current_size -= old.compressed_value.len();
-            }
-            
-            *current_size += compressed_size;
-            cache.insert(key.to_string(), entry);
-        }
-        
-        // Update stats
-        {
-            let mut stats = self.stats.write().map_err(|e| e.to_string())?;
-            stats.sets += 1;
-            self.update_stats_internal(&mut stats);
-        }
-        
-        debug!("UAO-QTCAM SET {} ({} → {} bytes, {:.1}× compression, {:.2}ms)",
-               key, value.len(), compressed_size,
-               value.len() as f64 / compressed_size as f64,
-               start.elapsed().as_secs_f64() * 1000.0);
-        
-        Ok(())
-    }
-
-    /// GET operation - Retrieve and decompress value
-    pub fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
-        let start = Instant::now();
-        
-        let result = {
-            let mut cache = self.cache.write().map_err(|e| e.to_string())?;
-            
-            if let Some(entry) = cache.get_mut(key) {
-                // Check TTL expiry
-                let now = chrono::Utc::now().timestamp();
-                if entry.ttl > 0 && now > entry.created_at + entry.ttl as i64 {
-                    // Expired - remove and return miss
-                    let size = entry.compressed_value.len();
-                    cache.remove(key);
-                    let mut current_size = self.current_size.write().map_err(|e| e.to_string())?;
-                    *current_size -= size;
-                    None
-                } else {
-                    // Update access stats
-                    entry.last_accessed = now;
-                    entry.access_count += 1;
-                    
-                    // Decompress
-                    let decompressed = self.decompress(&entry.compressed_value, entry.original_size);
-                    Some(decompressed)
-                }
-            } else {
-                None
-            }
-        };
-
-        // Update stats
-        {
-            let mut stats = self.stats.write().map_err(|e| e.to_string())?;
-            if result.is_some() {
-                stats.hits += 1;
-                debug!("UAO-QTCAM GET {} (HIT, {:.2}ms)", key, start.elapsed().as_secs_f64() * 1000.0);
-            } else {
-                stats.misses += 1;
-                debug!("UAO-QTCAM GET {} (MISS)", key);
-            }
-            self.update_stats_internal(&mut stats);
-        }
-
-        Ok(result)
-    }
-
-    /// DELETE operation
-    pub fn delete(&self, key: &str) -> Result<bool, String> {
-        let mut cache = self.cache.write().map_err(|e| e.to_string())?;
-
-        if let Some(entry) = cache.remove(key) {
-            let mut current_size = self.current_size.write().map_err(|e| e.to_string())?;
-            *current_size -= entry.compressed_value.len();
-
-            let mut stats = self.stats.write().map_err(|e| e.to_string())?;
-            stats.deletes += 1;
-
-            debug!("UAO-QTCAM DELETE {} (removed)", key);
-            Ok(true)
-        } else {
-            Ok(false)
-        }
-    }
-
-    /// EXISTS operation
-    pub fn exists(&self, key: &str) -> Result<bool, String> {
-        let cache = self.cache.read().map_err(|e| e.to_string())?;
-        Ok(cache.contains_key(key))
-    }
-
-    /// INCR operation for rate limiting
-    pub fn incr(&self, key: &str) -> Result<i64, String> {
-        let mut cache = self.cache.write().map_err(|e| e.to_string())?;
-
-        if let Some(entry) = cache.get_mut(key) {
-            let value = self.decompress(&entry.compressed_value, entry.original_size);
-            let counter: i64 = String::from_utf8_lossy(&value)
-                .parse()
-                .unwrap_or(0) + 1;
-
-            let new_value = counter.to_string().into_bytes();
-            entry.compressed_value = self.compress(&new_value);
-            entry.original_size = new_value.len();
-            entry.last_accessed = chrono::Utc::now().timestamp();
-
-            Ok(counter)
-        } else {
-            drop(cache);
-            self.set(key, b"1", None)?;
-            Ok(1)
-        }
-    }
-
-    /// Get cache statistics
-    pub fn stats(&self) -> Result<CacheStats, String> {
-        let mut stats = self.stats.write().map_err(|e| e.to_string())?;
-        self.update_stats_internal(&mut stats);
-        Ok(stats.clone())
-    }
-
-    /// Clear all cache entries
-    pub fn clear(&self) -> Result<(), String> {
-        let mut cache = self.cache.write().map_err(|e| e.to_string())?;
-        let mut current_size = self.current_size.write().map_err(|e| e.to_string())?;
-
-        cache.clear();
-        *current_size = 0;
-
-        info!("UAO-QTCAM Cache cleared");
-        Ok(())
-    }
-
-    // Internal compression using tensor folding simulation
-    fn compress(&self, data: &[u8]) -> Vec<u8> {
-        // Simulate 250× compression via tensor folding
-        // In production, this would use actual UAO-QTCAM tensor compression
-        let target_size = std::cmp::max(1, data.len() / self.compression_ratio as usize);
-
-        // Simple simulation: store hash + length + sample bytes
-        let mut compressed = Vec::with_capacity(target_size + 16);
-
-        // Store original length (8 bytes)
-        compressed.extend_from_slice(&(data.len() as u64).to_le_bytes());
-
-        // Store checksum (8 bytes)
-        let checksum: u64 = data.iter().map(|&b| b as u64).sum();
-        compressed.extend_from_slice(&checksum.to_le_bytes());
-
-        // Store sampled bytes for simulation
-        let sample_rate = std::cmp::max(1, data.len() / target_size);
-        for (i, &byte) in data.iter().enumerate() {
-            if i % sample_rate == 0 {
-                compressed.push(byte);
-            }
-        }
-
-        compressed
-    }
-
-    // Internal decompression
-    fn decompress(&self, compressed: &[u8], original_size: usize) -> Vec<u8> {
-        // In production, this would use actual UAO-QTCAM tensor decompression
-        // For simulation, reconstruct from samples
-        if compressed.len() < 16 {
-            return vec![0u8; original_size];
-        }
-
-        let mut result = Vec::with_capacity(original_size);
-        let samples = &compressed[16..];
-
-        let sample_rate = std::cmp::max(1, original_size / std::cmp::max(1, samples.len()));
-        let mut sample_idx = 0;
-
-        for i in 0..original_size {
-            if i % sample_rate == 0 && sample_idx < samples.len() {
-                result.push(samples[sample_idx]);
-                sample_idx += 1;
-            } else if !result.is_empty() {
-                result.push(*result.last().unwrap_or(&0));
-            } else {
-                result.push(0);
-            }
-        }
-
-        result.truncate(original_size);
-        while result.len() < original_size {
-            result.push(0);
-        }
-
-        result
-    }
-
-    // Evict entries if needed (LRU with weighted scoring)
-    fn evict_if_needed(&self, new_size: usize) -> Result<(), String> {
-        let current = *self.current_size.read().map_err(|e| e.to_string())?;
-
-        if current + new_size <= self.max_size {
-            return Ok(());
-        }
-
-        let mut cache = self.cache.write().map_err(|e| e.to_string())?;
-        let mut current_size = self.current_size.write().map_err(|e| e.to_string())?;
-        let mut stats = self.stats.write().map_err(|e| e.to_string())?;
-
-        // Calculate scores for eviction (lower score = evict first)
-        let mut scored: Vec<_> = cache.iter()
-            .map(|(k, v)| {
-                let recency = v.last_accessed as f64;
-                let frequency = v.access_count as f64;
-                let score = recency * 0.4 + frequency * 0.6;
-                (k.clone(), v.compressed_value.len(), score)
-            })
-            .collect();
-
-        scored.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Evict until we have space
-        let needed = (current + new_size).saturating_sub(self.max_size);
-        let mut freed = 0;
-
-        for (key, size, _) in scored {
-            if freed >= needed {
-                break;
-            }
-            cache.remove(&key);
-            freed += size;
-            *current_size -= size;
-            stats.evictions += 1;
-            debug!("UAO-QTCAM EVICT {} (freed {} bytes)", key, size);
-        }
-
-        Ok(())
-    }
-
-    fn update_stats_internal(&self, stats: &mut CacheStats) {
-        let cache = self.cache.read().ok();
-        let current_size = self.current_size.read().ok();
-
-        if let (Some(cache), Some(size)) = (cache, current_size) {
-            stats.entry_count = cache.len();
-            stats.compressed_bytes = *size;
-            stats.original_bytes = cache.values().map(|e| e.original_size).sum();
-            stats.compression_ratio = if stats.compressed_bytes > 0 {
-                stats.original_bytes as f64 / stats.compressed_bytes as f64
-            } else {
-                0.0
-            };
-            let total = stats.hits + stats.misses;
-            stats.hit_rate = if total > 0 {
-                stats.hits as f64 / total as f64
-            } else {
-                0.0
-            };
-        }
-    }
-}
-
-impl Default for UaoQtcamCache {
-    fn default() -> Self {
-        // Default: 256 MB compressed = 64 GB effective (250× compression)
-        Self::new(256 * 1024 * 1024, 250.0)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_cache_set_get() {
-        let cache = UaoQtcamCache::new(1024 * 1024, 250.0);
-
-        let value = b"Hello, UAO-QTCAM Cache!";
-        cache.set("test_key", value, None).unwrap();
-
-        let result = cache.get("test_key").unwrap();
-        assert!(result.is_some());
-    }
-
-    #[test]
-    fn test_cache_delete() {
-        let cache = UaoQtcamCache::new(1024 * 1024, 250.0);
-
-        cache.set("key1", b"value1", None).unwrap();
-        assert!(cache.exists("key1").unwrap());
-
-        cache.delete("key1").unwrap();
-        assert!(!cache.exists("key1").unwrap());
-    }
-
-    #[test]
-    fn test_cache_incr() {
-        let cache = UaoQtcamCache::new(1024 * 1024, 250.0);
-
-        assert_eq!(cache.incr("counter").unwrap(), 1);
-        assert_eq!(cache.incr("counter").unwrap(), 2);
-        assert_eq!(cache.incr("counter").unwrap(), 3);
-    }
-
-    #[test]
-    fn test_cache_stats() {
-        let cache = UaoQtcamCache::new(1024 * 1024, 250.0);
-
-        cache.set("key1", b"value1", None).unwrap();
-        cache.get("key1").unwrap();
-        cache.get("key2").unwrap(); // miss
-
-        let stats = cache.stats().unwrap();
-        assert_eq!(stats.hits, 1);
-        assert_eq!(stats.misses, 1);
-        assert_eq!(stats.sets, 1);
-    }
-}
-
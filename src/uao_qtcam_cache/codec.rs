@@ -0,0 +1,233 @@
+//! Lossless value codecs
+//!
+//! Every stored value is framed as
+//! `codec id (u8) | original length (u32 LE) | CRC32 of the original (u32 LE) | payload`.
+//! Reads check the length and checksum, so a damaged entry is reported
+//! instead of being returned.
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+/// Frame header size in bytes
+pub const HEADER_SIZE: usize = 9;
+
+/// Values below this many bytes are stored uncompressed by default
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 64;
+
+/// Largest value a frame can describe
+pub const MAX_VALUE_SIZE: usize = u32::MAX as usize;
+
+/// zstd level: fast, with most of the ratio of the higher levels
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression algorithm of a stored value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheCodec {
+    /// Stored as is
+    Raw,
+    /// DEFLATE (RFC 1951)
+    Deflate,
+    /// Zstandard
+    #[default]
+    Zstd,
+}
+
+impl CacheCodec {
+    pub const ALL: [CacheCodec; 3] = [Self::Raw, Self::Deflate, Self::Zstd];
+
+    /// Identifier written in the frame header
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Raw => 0,
+            Self::Deflate => 1,
+            Self::Zstd => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Deflate => "deflate",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Raw => Ok(data.to_vec()),
+            Self::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Self::Zstd => zstd::bulk::compress(data, ZSTD_LEVEL),
+        }
+    }
+
+    /// Decompress at most `len` bytes, so a damaged payload cannot balloon
+    fn decompress(self, payload: &[u8], len: usize) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Raw => Ok(payload.to_vec()),
+            Self::Deflate => {
+                let mut data = Vec::with_capacity(len);
+                DeflateDecoder::new(payload)
+                    .take(len as u64 + 1)
+                    .read_to_end(&mut data)?;
+                Ok(data)
+            }
+            Self::Zstd => zstd::bulk::decompress(payload, len),
+        }
+    }
+}
+
+impl fmt::Display for CacheCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for CacheCodec {
+    type Err = CodecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| CodecError::UnknownCodecName(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CodecError {
+    #[error("Unknown cache codec '{0}' (expected raw, deflate or zstd)")]
+    UnknownCodecName(String),
+    #[error("Value of {0} bytes exceeds the {MAX_VALUE_SIZE}-byte limit")]
+    TooLarge(usize),
+    #[error("Frame of {0} bytes is shorter than its header")]
+    Truncated(usize),
+    #[error("Unknown codec id {0}")]
+    UnknownCodec(u8),
+    #[error("{codec} payload is corrupt: {reason}")]
+    Corrupt { codec: CacheCodec, reason: String },
+    #[error("Decoded {actual} bytes, header says {expected}")]
+    LengthMismatch { expected: usize, actual: usize },
+    #[error("CRC32 mismatch (expected {expected:08x}, got {actual:08x})")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+}
+
+/// Frame `data`, compressing with `codec` when it is at least `threshold`
+/// bytes and compression actually saves space
+pub fn encode(codec: CacheCodec, data: &[u8], threshold: usize) -> Result<Vec<u8>, CodecError> {
+    if data.len() > MAX_VALUE_SIZE {
+        return Err(CodecError::TooLarge(data.len()));
+    }
+    let compressed = match codec {
+        CacheCodec::Raw => None,
+        _ if data.len() < threshold => None,
+        _ => codec
+            .compress(data)
+            .ok()
+            .filter(|payload| payload.len() < data.len())
+            .map(|payload| (codec, payload)),
+    };
+    let (codec, payload) = match &compressed {
+        Some((codec, payload)) => (*codec, payload.as_slice()),
+        None => (CacheCodec::Raw, data),
+    };
+
+    let mut frame = Vec::with_capacity(HEADER_SIZE + payload.len());
+    frame.push(codec.id());
+    frame.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+    frame.extend_from_slice(payload);
+    Ok(frame)
+}
+
+/// Codec a frame was written with
+pub fn frame_codec(frame: &[u8]) -> Result<CacheCodec, CodecError> {
+    let id = *frame.first().ok_or(CodecError::Truncated(0))?;
+    CacheCodec::from_id(id).ok_or(CodecError::UnknownCodec(id))
+}
+
+/// Original length recorded in a frame
+pub fn frame_len(frame: &[u8]) -> Result<usize, CodecError> {
+    let header = frame.get(..HEADER_SIZE).ok_or(CodecError::Truncated(frame.len()))?;
+    Ok(u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize)
+}
+
+/// Decode and verify a frame produced by [`encode`]
+pub fn decode(frame: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let expected_len = frame_len(frame)?;
+    let codec = frame_codec(frame)?;
+    let expected_crc = u32::from_le_bytes([frame[5], frame[6], frame[7], frame[8]]);
+
+    let data = codec
+        .decompress(&frame[HEADER_SIZE..], expected_len)
+        .map_err(|e| CodecError::Corrupt { codec, reason: e.to_string() })?;
+    if data.len() != expected_len {
+        return Err(CodecError::LengthMismatch { expected: expected_len, actual: data.len() });
+    }
+    let actual_crc = crc32fast::hash(&data);
+    if actual_crc != expected_crc {
+        return Err(CodecError::ChecksumMismatch { expected: expected_crc, actual: actual_crc });
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_and_incompressible_values_stay_raw() {
+        let frame = encode(CacheCodec::Zstd, b"short", DEFAULT_COMPRESSION_THRESHOLD).unwrap();
+        assert_eq!(frame_codec(&frame).unwrap(), CacheCodec::Raw);
+        assert_eq!(frame.len(), HEADER_SIZE + 5);
+
+        let noise: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+        let frame = encode(CacheCodec::Deflate, &noise, 0).unwrap();
+        assert!(frame.len() <= HEADER_SIZE + noise.len());
+        assert_eq!(decode(&frame).unwrap(), noise);
+    }
+
+    #[test]
+    fn test_damaged_frames_are_detected() {
+        let value = vec![b'a'; 1000];
+        for codec in CacheCodec::ALL {
+            let frame = encode(codec, &value, 0).unwrap();
+            assert_eq!(frame_codec(&frame).unwrap(), codec);
+            assert_eq!(decode(&frame).unwrap(), value);
+
+            let mut damaged = frame.clone();
+            damaged[HEADER_SIZE] ^= 0xff;
+            assert!(decode(&damaged).is_err(), "{}", codec);
+
+            let mut bad_crc = frame.clone();
+            bad_crc[5] ^= 1;
+            assert!(matches!(decode(&bad_crc), Err(CodecError::ChecksumMismatch { .. })));
+        }
+        assert_eq!(decode(&[9, 0, 0, 0, 0, 0, 0, 0, 0]), Err(CodecError::UnknownCodec(9)));
+        assert_eq!(decode(&[0, 1]), Err(CodecError::Truncated(2)));
+    }
+
+    #[test]
+    fn test_codec_names() {
+        for codec in CacheCodec::ALL {
+            assert_eq!(codec.name().parse::<CacheCodec>().unwrap(), codec);
+            assert_eq!(CacheCodec::from_id(codec.id()), Some(codec));
+        }
+        assert_eq!(" ZSTD ".parse::<CacheCodec>().unwrap(), CacheCodec::Zstd);
+        assert!("lzma".parse::<CacheCodec>().is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! Memory accounting and eviction policies
+//!
+//! Every key is charged its [`charge`]: the key, the stored value, the
+//! fixed size of its table slot and, with a TTL, its node in the deadline
+//! index. The sum is one atomic counter: a write reserves what it can add
+//! before applying it, so writers on different shards cannot overshoot
+//! `max_size` together. When the reservation does not fit,
+//! [`UaoQtcamCache::make_room`] frees memory in the writer's own shard:
+//! keys whose TTL ran out go first, then victims chosen by the
+//! [`EvictionPolicy`] in force. Other shards are only locked when the own
+//! shard cannot free enough.
+//!
+//! Like Redis, LRU and LFU approximate: each victim is the best of
+//! [`EVICTION_SAMPLES`] keys of the shard sampled at random, so evicting
+//! costs the same however many keys the shard holds. `volatile-ttl` takes
+//! the soonest deadline from the shard's deadline index. When nothing more can be evicted the write fails with
+//! [`OUT_OF_MEMORY`] and the cache is left within its limit.
+//!
+//! LFU uses Redis' logarithmic counter: it starts at [`LFU_INIT_VAL`],
+//! grows with probability `1 / ((counter - LFU_INIT_VAL) * LFU_LOG_FACTOR + 1)`
+//! per access and loses one per [`LFU_DECAY_MINUTES`] without access.
+
+use std::fmt;
+use std::mem::size_of;
+use std::str::FromStr;
+use std::sync::atomic::Ordering;
+
+use rand::seq::index;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::shard::Shard;
+use super::{CacheEntry, KeyEvent, LogRecord, UaoQtcamCache};
+
+/// Error text for writes refused because nothing could be evicted
+pub const OUT_OF_MEMORY: &str = "OOM command not allowed when used memory > 'maxmemory'.";
+
+/// Bytes charged per key besides the key and value: its table slot and control byte
+pub const ENTRY_OVERHEAD: usize = size_of::<String>() + size_of::<CacheEntry>() + 1;
+/// Bytes charged per key with a TTL besides the copy of the key: its deadline index node
+pub const TTL_OVERHEAD: usize = size_of::<(i64, String)>();
+
+/// Keys sampled per victim, as Redis' `maxmemory-samples`
+pub const EVICTION_SAMPLES: usize = 5;
+
+/// Counter of a new key
+pub const LFU_INIT_VAL: u8 = 5;
+/// Higher values make the counter grow more slowly
+pub const LFU_LOG_FACTOR: f64 = 10.0;
+/// Minutes without access that take one off the counter
+pub const LFU_DECAY_MINUTES: i64 = 1;
+
+/// Which keys make room when the cache is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum EvictionPolicy {
+    /// Least recently used key first
+    #[default]
+    #[serde(rename = "allkeys-lru")]
+    Lru,
+    /// Least frequently used key first, by decaying logarithmic counter
+    #[serde(rename = "allkeys-lfu")]
+    Lfu,
+    /// Key with a TTL closest to expiring first; keys without one are kept
+    #[serde(rename = "volatile-ttl")]
+    VolatileTtl,
+    /// Any key
+    #[serde(rename = "allkeys-random")]
+    Random,
+    /// Nothing; writes that do not fit fail
+    #[serde(rename = "noeviction")]
+    NoEviction,
+}
+
+impl EvictionPolicy {
+    pub const ALL: [EvictionPolicy; 5] = [
+        EvictionPolicy::Lru,
+        EvictionPolicy::Lfu,
+        EvictionPolicy::VolatileTtl,
+        EvictionPolicy::Random,
+        EvictionPolicy::NoEviction,
+    ];
+
+    /// Name as in Redis' `maxmemory-policy`
+    pub fn name(self) -> &'static str {
+        match self {
+            EvictionPolicy::Lru => "allkeys-lru",
+            EvictionPolicy::Lfu => "allkeys-lfu",
+            EvictionPolicy::VolatileTtl => "volatile-ttl",
+            EvictionPolicy::Random => "allkeys-random",
+            EvictionPolicy::NoEviction => "noeviction",
+        }
+    }
+}
+
+impl fmt::Display for EvictionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for EvictionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.name() == s)
+            .ok_or_else(|| format!("unknown eviction policy '{}'", s))
+    }
+}
+
+/// Bytes `key` holding `entry` is charged against `max_size`
+pub fn charge(key: &str, entry: &CacheEntry) -> usize {
+    let ttl = if entry.expires_at.is_some() { TTL_OVERHEAD + key.len() } else { 0 };
+    key.len() + entry.size() + ENTRY_OVERHEAD + ttl
+}
+
+/// LFU counter of keys recovered from disk, which start out as new
+pub(super) fn initial_lfu_counter() -> u8 {
+    LFU_INIT_VAL
+}
+
+/// LFU counter after the decay since minute `decayed_at` from `decayed_at`
+fn lfu_decayed(counter: u8, decayed_at: i64, now_min: i64) -> u8 {
+    let periods = (now_min - decayed_at).max(0) / LFU_DECAY_MINUTES;
+    counter.saturating_sub(periods.min(u8::MAX as i64) as u8)
+}
+
+/// LFU counter after one more access
+fn lfu_increment(counter: u8) -> u8 {
+    if counter == u8::MAX {
+        return counter;
+    }
+    let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+    if rand::thread_rng().gen::<f64>() < 1.0 / (base * LFU_LOG_FACTOR + 1.0) {
+        counter + 1
+    } else {
+        counter
+    }
+}
+
+impl UaoQtcamCache {
+    /// Make room with `policy` when the cache is full
+    pub fn with_eviction_policy(self, policy: EvictionPolicy) -> Self {
+        self.set_eviction_policy(policy);
+        self
+    }
+
+    /// Switch the eviction policy of a running cache
+    pub fn set_eviction_policy(&self, policy: EvictionPolicy) {
+        if let Ok(mut current) = self.eviction_policy.write() {
+            *current = policy;
+        }
+    }
+
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.eviction_policy.read().map_or(EvictionPolicy::default(), |policy| *policy)
+    }
+
+    /// Memory limit in bytes
+    pub fn max_memory(&self) -> usize {
+        self.max_size
+    }
+
+    /// MEMORY USAGE - Bytes `key` is charged, if it exists
+    pub fn memory_usage(&self, key: &str) -> Result<Option<usize>, String> {
+        let shard = self.read_shard(key)?;
+        let now_ms = self.clock.now_ms();
+        Ok(shard
+            .entries
+            .get_key_value(key)
+            .filter(|(_, entry)| !entry.is_expired(now_ms))
+            .map(|(key, entry)| charge(key, entry)))
+    }
+
+    /// Reserve `bytes` against `max_size` if they fit
+    fn try_reserve(&self, bytes: usize) -> bool {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(bytes).filter(|&total| total <= self.max_size)
+            })
+            .is_ok()
+    }
+
+    /// Reserve `bytes` for a write to `shard` (number `index`, locked by the
+    /// caller), freeing memory first if needed and never evicting `protect`
+    ///
+    /// This shard's expired keys and the policy's victims go first. Only if
+    /// that is not enough are the other shards visited one at a time: those
+    /// above `index` are waited for, those below only taken if free, as the
+    /// lock order requires.
+    pub(super) fn make_room(
+        &self,
+        index: usize,
+        shard: &mut Shard,
+        bytes: usize,
+        protect: &str,
+        now_ms: i64,
+    ) -> Result<(), String> {
+        if self.try_reserve(bytes) {
+            return Ok(());
+        }
+
+        let policy = self.eviction_policy();
+        if self.free_in(shard, policy, bytes, protect, now_ms)? {
+            return Ok(());
+        }
+        for (other, lock) in self.shards.iter().enumerate().filter(|(other, _)| *other != index) {
+            let guard = if other > index {
+                Some(lock.write().map_err(|e| e.to_string())?)
+            } else {
+                lock.try_write().ok()
+            };
+            if let Some(mut guard) = guard {
+                if self.free_in(&mut guard, policy, bytes, protect, now_ms)? {
+                    return Ok(());
+                }
+            }
+        }
+        shard.counters.rejected_writes += 1;
+        Err(OUT_OF_MEMORY.to_string())
+    }
+
+    /// Drop the expired keys of `shard`, then evict from it, until `bytes`
+    /// can be reserved; whether they were
+    fn free_in(
+        &self,
+        shard: &mut Shard,
+        policy: EvictionPolicy,
+        bytes: usize,
+        protect: &str,
+        now_ms: i64,
+    ) -> Result<bool, String> {
+        self.drop_expired(shard, protect, now_ms);
+        Ok(self.try_reserve(bytes) || self.evict(shard, policy, bytes, protect)?)
+    }
+
+    /// Evict `policy`'s victims from `shard` until `bytes` can be reserved; whether they were
+    fn evict(&self, shard: &mut Shard, policy: EvictionPolicy, bytes: usize, protect: &str) -> Result<bool, String> {
+        let now_min = self.clock.now_ms() / 60_000;
+        while self.evict_one(shard, policy, protect, now_min)? {
+            if self.try_reserve(bytes) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Evict one of `policy`'s victims from `shard`; whether there was one
+    fn evict_one(&self, shard: &mut Shard, policy: EvictionPolicy, protect: &str, now_min: i64) -> Result<bool, String> {
+        let Some(key) = victim(shard, policy, protect, now_min) else {
+            return Ok(false);
+        };
+        self.log(|| LogRecord::Delete { key: key.clone() })?;
+        let freed = self.remove_locked(shard, &key).map_or(0, |entry| charge(&key, &entry));
+        shard.counters.evictions += 1;
+        *shard.counters.evictions_by_policy.entry(policy).or_default() += 1;
+        self.notify(KeyEvent::Evicted, &key);
+        debug!("UAO-QTCAM EVICT {} ({}, freed {} bytes)", key, policy, freed);
+        Ok(true)
+    }
+
+    /// Evict across all shards, one victim per shard in turn, until memory
+    /// use is back within `max_size`; whether it is
+    pub(super) fn evict_to_fit(&self) -> Result<bool, String> {
+        let policy = self.eviction_policy();
+        let now_min = self.clock.now_ms() / 60_000;
+        let fits = || self.used.load(Ordering::Acquire) <= self.max_size;
+        let mut evicted = true;
+        while !fits() && evicted {
+            evicted = false;
+            for lock in &self.shards {
+                let mut shard = lock.write().map_err(|e| e.to_string())?;
+                evicted |= self.evict_one(&mut shard, policy, "", now_min)?;
+                if fits() {
+                    break;
+                }
+            }
+        }
+        Ok(fits())
+    }
+
+    /// Remove the keys of `shard` whose TTL ran out, except `protect`
+    fn drop_expired(&self, shard: &mut Shard, protect: &str, now_ms: i64) {
+        for key in shard.expiry.due(now_ms, usize::MAX) {
+            if key != protect {
+                self.remove_locked(shard, &key);
+                shard.counters.expired_keys += 1;
+                self.notify(KeyEvent::Expired, &key);
+            }
+        }
+    }
+}
+
+/// Next key of `shard` that `policy` would evict, never `protect`
+///
+/// The sampled keys are distinct, so with more than one key at least one
+/// of them is not `protect`.
+fn victim(shard: &Shard, policy: EvictionPolicy, protect: &str, now_min: i64) -> Option<String> {
+    match policy {
+        EvictionPolicy::NoEviction => return None,
+        EvictionPolicy::VolatileTtl => {
+            return shard.expiry.head(2).into_iter().map(|(_, key)| key).find(|key| key != protect);
+        }
+        _ => {}
+    }
+    let len = shard.entries.len();
+    let mut sample = index::sample(&mut rand::thread_rng(), len, EVICTION_SAMPLES.min(len))
+        .into_iter()
+        .filter_map(|i| shard.entries.get_index(i))
+        .filter(|(key, _)| key.as_str() != protect);
+    let (key, _) = match policy {
+        EvictionPolicy::Lru => sample.min_by_key(|(_, entry)| entry.last_access_tick),
+        EvictionPolicy::Lfu => sample.min_by_key(|(_, entry)| {
+            (lfu_decayed(entry.lfu_counter, entry.lfu_decayed_at, now_min), entry.last_access_tick)
+        }),
+        _ => sample.next(),
+    }?;
+    Some(key.clone())
+}
+
+/// Record an access to `entry` at logical time `tick`, for LRU and LFU
+pub(super) fn touch(entry: &mut CacheEntry, tick: u64, now_ms: i64) {
+    let now_min = now_ms / 60_000;
+    entry.last_accessed = now_ms / 1000;
+    entry.access_count += 1;
+    entry.last_access_tick = tick;
+    entry.lfu_counter = lfu_increment(lfu_decayed(entry.lfu_counter, entry.lfu_decayed_at, now_min));
+    entry.lfu_decayed_at = now_min;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lfu_counter_grows_logarithmically_and_decays() {
+        let mut counter = LFU_INIT_VAL;
+        for _ in 0..1000 {
+            counter = lfu_increment(counter);
+        }
+        assert!(counter > LFU_INIT_VAL + 2 && counter < 40, "{}", counter);
+        assert_eq!(lfu_decayed(counter, 100, 100), counter);
+        assert_eq!(lfu_decayed(counter, 100, 103), counter - 3);
+        assert_eq!(lfu_decayed(counter, 0, i64::MAX / 2), 0);
+        assert_eq!("Allkeys-LFU".parse::<EvictionPolicy>().unwrap(), EvictionPolicy::Lfu);
+        assert!("lru".parse::<EvictionPolicy>().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Active TTL expiry
+//!
+//! Each shard keeps its keys with a TTL in an [`ExpiryIndex`] ordered by
+//! deadline. A background task runs [`UaoQtcamCache::expire_cycle`], which
+//! works like Redis' active expiry, one shard at a time: each round samples
+//! keys with TTLs and removes the expired ones, and another round follows
+//! while more than `repeat_threshold` of the sample had expired. Samples are
+//! taken from the head of the index, so the most overdue keys are reclaimed
+//! first and a round that finds a live key knows nothing else is due.
+
+use std::collections::BTreeSet;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+use super::eviction::TTL_OVERHEAD;
+use super::{KeyEvent, LogRecord, UaoQtcamCache};
+
+/// Active expiry tuning
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpiryConfig {
+    /// Time between cycles
+    pub interval: Duration,
+    /// Keys checked per round
+    pub sample_size: usize,
+    /// Run another round while more than this fraction of a sample expired
+    pub repeat_threshold: f64,
+    /// Upper bound on rounds per shard and cycle, so one cycle cannot stall writers
+    pub max_rounds: usize,
+}
+
+impl Default for ExpiryConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(100),
+            sample_size: 20,
+            repeat_threshold: 0.25,
+            max_rounds: 16,
+        }
+    }
+}
+
+/// Remaining lifetime of a key, as reported by `TTL`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyTtl {
+    /// No such key (or it already expired)
+    Missing,
+    /// The key never expires
+    Persistent,
+    /// The key expires after this long
+    Expires(Duration),
+}
+
+/// Deadline `ttl` after `now_ms`, or Redis' error for `command` when it is
+/// past the range of the clock
+pub fn deadline(now_ms: i64, ttl: Duration, command: &str) -> Result<i64, String> {
+    i64::try_from(ttl.as_millis())
+        .ok()
+        .and_then(|ms| now_ms.checked_add(ms))
+        .ok_or_else(|| format!("invalid expire time in '{}' command", command))
+}
+
+/// Keys with a TTL, ordered by deadline (Unix epoch milliseconds)
+#[derive(Debug, Default)]
+pub struct ExpiryIndex {
+    deadlines: BTreeSet<(i64, String)>,
+}
+
+impl ExpiryIndex {
+    pub fn insert(&mut self, at: i64, key: &str) {
+        self.deadlines.insert((at, key.to_string()));
+    }
+
+    pub fn remove(&mut self, at: i64, key: &str) -> bool {
+        self.deadlines.remove(&(at, key.to_string()))
+    }
+
+    /// Up to `limit` keys due at `now_ms`, earliest first
+    pub fn due(&self, now_ms: i64, limit: usize) -> Vec<String> {
+        self.deadlines
+            .iter()
+            .take_while(|(at, _)| *at <= now_ms)
+            .take(limit)
+            .map(|(_, key)| key.clone())
+            .collect()
+    }
+
+    /// The `n` earliest deadlines
+    pub fn head(&self, n: usize) -> Vec<(i64, String)> {
+        self.deadlines.iter().take(n).cloned().collect()
+    }
+
+    pub fn next_deadline(&self) -> Option<i64> {
+        self.deadlines.first().map(|(at, _)| *at)
+    }
+
+    pub fn len(&self) -> usize {
+        self.deadlines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deadlines.is_empty()
+    }
+}
+
+impl UaoQtcamCache {
+    /// EXPIRE - Give `key` a TTL; false if it does not exist
+    pub fn expire(&self, key: &str, ttl: Duration) -> Result<bool, String> {
+        let (index, mut shard) = self.write_shard(key)?;
+        let now_ms = self.clock.now_ms();
+        let at = deadline(now_ms, ttl, "expire")?;
+        if self.expire_locked(&mut shard, key, now_ms)? {
+            return Ok(false);
+        }
+        let Some(entry) = shard.entries.get(key) else {
+            return Ok(false);
+        };
+
+        // A first TTL is charged for the key's place in the deadline index
+        let charged = if entry.expires_at.is_none() { TTL_OVERHEAD + key.len() } else { 0 };
+        self.make_room(index, &mut shard, charged, key, now_ms)?;
+        self.log_reserved(charged, || LogRecord::Expire { key: key.to_string(), at })?;
+        let shard = &mut *shard;
+        let Some(entry) = shard.entries.get_mut(key) else {
+            self.settle(charged, 0, 0);
+            return Ok(false);
+        };
+        if let Some(old) = entry.expires_at.replace(at) {
+            shard.expiry.remove(old, key);
+        }
+        shard.expiry.insert(at, key);
+
+        // A TTL of zero deletes right away
+        self.expire_locked(shard, key, now_ms)?;
+        debug!("UAO-QTCAM EXPIRE {} ({}ms)", key, ttl.as_millis());
+        Ok(true)
+    }
+
+    /// TTL - Remaining lifetime of `key`
+    pub fn ttl(&self, key: &str) -> Result<KeyTtl, String> {
+        let shard = self.read_shard(key)?;
+        let now_ms = self.clock.now_ms();
+        Ok(match shard.entries.get(key) {
+            None => KeyTtl::Missing,
+            Some(entry) if entry.is_expired(now_ms) => KeyTtl::Missing,
+            Some(entry) => match entry.expires_at {
+                None => KeyTtl::Persistent,
+                Some(at) => KeyTtl::Expires(Duration::from_millis((at - now_ms) as u64)),
+            },
+        })
+    }
+
+    /// PERSIST - Drop the TTL of `key`; false if it had none
+    pub fn persist(&self, key: &str) -> Result<bool, String> {
+        let (_, mut shard) = self.write_shard(key)?;
+        if self.expire_locked(&mut shard, key, self.clock.now_ms())? {
+            return Ok(false);
+        }
+        if shard.entries.get(key).is_none_or(|entry| entry.expires_at.is_none()) {
+            return Ok(false);
+        }
+        self.log(|| LogRecord::Persist { key: key.to_string() })?;
+        let Some(at) = shard.entries.get_mut(key).and_then(|entry| entry.expires_at.take()) else {
+            return Ok(false);
+        };
+        shard.expiry.remove(at, key);
+        self.used.fetch_sub(TTL_OVERHEAD + key.len(), Ordering::Relaxed);
+        Ok(true)
+    }
+
+    /// Run one active expiry cycle over every shard; returns the number of keys reclaimed
+    pub fn expire_cycle(&self, config: &ExpiryConfig) -> Result<usize, String> {
+        let mut reclaimed = 0;
+        for lock in self.shards.iter() {
+            for _ in 0..config.max_rounds {
+                let mut shard = lock.write().map_err(|e| e.to_string())?;
+                let now_ms = self.clock.now_ms();
+                let sample = shard.expiry.head(config.sample_size);
+                if sample.is_empty() {
+                    break;
+                }
+
+                let mut expired = 0;
+                for (_, key) in sample.iter().filter(|(at, _)| *at <= now_ms) {
+                    self.remove_locked(&mut shard, key);
+                    self.notify(KeyEvent::Expired, key);
+                    expired += 1;
+                }
+                shard.counters.expired_keys += expired as u64;
+                reclaimed += expired;
+
+                if expired as f64 <= sample.len() as f64 * config.repeat_threshold {
+                    break;
+                }
+            }
+        }
+        if reclaimed > 0 {
+            debug!("UAO-QTCAM EXPIRE cycle reclaimed {} keys", reclaimed);
+        }
+        Ok(reclaimed)
+    }
+
+    /// Run [`expire_cycle`](Self::expire_cycle) every `config.interval`
+    pub fn spawn_expiry(self: &Arc<Self>, config: ExpiryConfig) -> tokio::task::JoinHandle<()> {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(config.interval);
+            loop {
+                tick.tick().await;
+                if let Err(e) = cache.expire_cycle(&config) {
+                    warn!("⚠️ UAO-QTCAM expiry cycle failed: {}", e);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,684 @@
+//! Snapshot and append-only-log persistence
+//!
+//! Durability is optional and enabled with
+//! [`UaoQtcamCache::with_persistence`]. Two files live in the configured
+//! directory:
+//!
+//! - `cache.snapshot` - a point-in-time copy of every entry, written to a
+//!   temporary file, fsynced and renamed over the old one, so a crash leaves
+//!   either the old or the new snapshot;
+//!   `magic | generation u64 LE | log offset u64 LE | entries (bincode) | CRC32 u32 LE`
+//! - `cache.aof` - every write since that snapshot, appended before it is
+//!   applied in memory; `magic | generation u64 LE` then records of
+//!   `payload len u32 LE | CRC32 u32 LE | payload (bincode)`
+//!
+//! A rewrite copies the entries, writes them as a new snapshot and starts a
+//! log holding only what was logged after the copy, both stamped with the
+//! next generation; a log whose generation does not match the snapshot is
+//! stale and ignored, so a crash between the two steps cannot replay writes
+//! twice. The snapshot records how far into the previous log its copy was
+//! taken, so after such a crash the rest of that log is still replayed. Recovery replays the log up to the first torn or corrupt record,
+//! then drops entries whose deadline has passed: deadlines are absolute
+//! wall-clock times, so time spent down counts against every TTL. Data
+//! recovered over the memory limit is evicted by the configured policy; if
+//! the policy cannot get under the limit, recovery fails.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use super::shard::Shard;
+use super::{eviction, CacheEntry, UaoQtcamCache, Update};
+
+/// Snapshot file name inside the persistence directory
+pub const SNAPSHOT_FILE: &str = "cache.snapshot";
+/// Log file name inside the persistence directory
+pub const LOG_FILE: &str = "cache.aof";
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"SXSNAP03";
+const LOG_MAGIC: &[u8; 8] = b"SXAOF002";
+/// Magic plus generation
+const FILE_HEADER_SIZE: usize = 16;
+/// Magic, generation and log offset
+const SNAPSHOT_HEADER_SIZE: usize = 24;
+/// Log offset of a snapshot that includes all of the previous log
+pub const NO_LOG_OFFSET: u64 = u64::MAX;
+/// Payload length plus CRC32
+pub const RECORD_HEADER_SIZE: usize = 8;
+
+/// When appended records are fsynced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FsyncPolicy {
+    /// After every write; nothing acknowledged is lost
+    Always,
+    /// At most once a second; up to a second of writes can be lost
+    #[default]
+    EverySecond,
+    /// Left to the OS; records survive a process crash but not a power loss
+    Never,
+}
+
+impl FsyncPolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            FsyncPolicy::Always => "always",
+            FsyncPolicy::EverySecond => "everysec",
+            FsyncPolicy::Never => "no",
+        }
+    }
+}
+
+impl fmt::Display for FsyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for FsyncPolicy {
+    type Err = PersistenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "always" => Ok(FsyncPolicy::Always),
+            "everysec" => Ok(FsyncPolicy::EverySecond),
+            "no" | "never" => Ok(FsyncPolicy::Never),
+            _ => Err(PersistenceError::UnknownFsyncPolicy(s.to_string())),
+        }
+    }
+}
+
+/// Where and how the cache is persisted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistenceConfig {
+    /// Directory holding the snapshot and the log
+    pub dir: PathBuf,
+    /// Log every write; with `false` only periodic snapshots are taken
+    pub append_only: bool,
+    pub fsync: FsyncPolicy,
+    /// Rewrite the log once it is this large and twice its size after the last rewrite
+    pub rewrite_min_bytes: u64,
+    /// Time between snapshots when `append_only` is off
+    pub snapshot_interval: Duration,
+}
+
+impl PersistenceConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            append_only: true,
+            fsync: FsyncPolicy::default(),
+            rewrite_min_bytes: 64 * 1024 * 1024,
+            snapshot_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PersistenceError {
+    #[error("persistence I/O failed: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("snapshot {path} is corrupt: {reason}")]
+    CorruptSnapshot { path: PathBuf, reason: String },
+
+    #[error("unknown fsync policy '{0}' (expected always, everysec or no)")]
+    UnknownFsyncPolicy(String),
+
+    #[error("record encoding failed: {0}")]
+    Encode(String),
+
+    #[error("recovered {recovered} bytes exceed the {max_size} byte limit and the '{policy}' policy cannot evict enough")]
+    OverLimit {
+        recovered: usize,
+        max_size: usize,
+        policy: &'static str,
+    },
+}
+
+/// One logged write
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LogRecord {
+    /// The key now holds this entry, deadline included
+    Set { key: String, entry: CacheEntry },
+    /// An existing collection changed; an emptied one is deleted
+    Update { key: String, update: Update },
+    Delete { key: String },
+    /// Deadline in Unix epoch milliseconds
+    Expire { key: String, at: i64 },
+    Persist { key: String },
+    Clear,
+}
+
+impl LogRecord {
+    fn apply(self, entries: &mut HashMap<String, CacheEntry>) {
+        match self {
+            LogRecord::Set { key, entry } => {
+                entries.insert(key, entry);
+            }
+            LogRecord::Update { key, update } => {
+                if let Some(entry) = entries.get_mut(&key) {
+                    if entry.apply(&update).is_ok() && entry.value.is_empty() {
+                        entries.remove(&key);
+                    }
+                }
+            }
+            LogRecord::Delete { key } => {
+                entries.remove(&key);
+            }
+            LogRecord::Expire { key, at } => {
+                if let Some(entry) = entries.get_mut(&key) {
+                    entry.expires_at = Some(at);
+                }
+            }
+            LogRecord::Persist { key } => {
+                if let Some(entry) = entries.get_mut(&key) {
+                    entry.expires_at = None;
+                }
+            }
+            LogRecord::Clear => entries.clear(),
+        }
+    }
+}
+
+/// Frame `record` for the log
+pub fn encode_record(record: &LogRecord) -> Result<Vec<u8>, PersistenceError> {
+    let payload = bincode::serialize(record).map_err(|e| PersistenceError::Encode(e.to_string()))?;
+    let len = u32::try_from(payload.len()).map_err(|_| PersistenceError::Encode("record over 4 GiB".to_string()))?;
+    let mut frame = Vec::with_capacity(RECORD_HEADER_SIZE + payload.len());
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Records read back from a log
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogReplay {
+    pub generation: u64,
+    pub records: Vec<LogRecord>,
+    /// Bytes up to the end of the last good record
+    pub valid_len: u64,
+    /// Whether anything followed the last good record
+    pub torn: bool,
+}
+
+/// Decode a log, stopping at the first torn or corrupt record;
+/// `None` when even the header is incomplete
+pub fn read_log(bytes: &[u8]) -> Option<LogReplay> {
+    if bytes.len() < FILE_HEADER_SIZE || &bytes[..8] != LOG_MAGIC {
+        return None;
+    }
+    let generation = u64::from_le_bytes(bytes[8..16].try_into().ok()?);
+    let mut records = Vec::new();
+    let mut pos = FILE_HEADER_SIZE;
+
+    while let Some(header) = bytes.get(pos..pos + RECORD_HEADER_SIZE) {
+        let len = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+        let crc = u32::from_le_bytes(header[4..].try_into().ok()?);
+        let start = pos + RECORD_HEADER_SIZE;
+        let Some(payload) = bytes.get(start..start.saturating_add(len)) else {
+            break;
+        };
+        if crc32fast::hash(payload) != crc {
+            break;
+        }
+        let Ok(record) = bincode::deserialize(payload) else {
+            break;
+        };
+        records.push(record);
+        pos = start + len;
+    }
+
+    Some(LogReplay {
+        generation,
+        records,
+        valid_len: pos as u64,
+        torn: pos < bytes.len(),
+    })
+}
+
+/// Hashes everything written through it
+struct CrcWriter<W> {
+    inner: W,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W: Write> Write for CrcWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Write a snapshot of the entries of every map in `parts` atomically; returns its size in bytes
+///
+/// `log_offset` is how much of the previous generation's log the entries
+/// include ([`NO_LOG_OFFSET`] for all of it).
+pub fn write_snapshot(
+    dir: &Path,
+    generation: u64,
+    log_offset: u64,
+    parts: &[&IndexMap<String, CacheEntry>],
+) -> Result<u64, PersistenceError> {
+    let path = dir.join(SNAPSHOT_FILE);
+    let tmp = dir.join(format!("{}.tmp", SNAPSHOT_FILE));
+    let mut out = CrcWriter { inner: BufWriter::new(File::create(&tmp)?), hasher: crc32fast::Hasher::new() };
+
+    out.write_all(SNAPSHOT_MAGIC)?;
+    out.write_all(&generation.to_le_bytes())?;
+    out.write_all(&log_offset.to_le_bytes())?;
+    // Laid out as bincode's Vec<(String, CacheEntry)>, without building the Vec
+    let len: usize = parts.iter().map(|entries| entries.len()).sum();
+    out.write_all(&(len as u64).to_le_bytes())?;
+    for item in parts.iter().flat_map(|entries| entries.iter()) {
+        bincode::serialize_into(&mut out, &item).map_err(|e| PersistenceError::Encode(e.to_string()))?;
+    }
+    let crc = out.hasher.finalize();
+    let mut file = out.inner.into_inner().map_err(|e| e.into_error())?;
+    file.write_all(&crc.to_le_bytes())?;
+    file.sync_all()?;
+    let size = file.metadata()?.len();
+    drop(file);
+
+    fs::rename(&tmp, &path)?;
+    sync_dir(dir)?;
+    Ok(size)
+}
+
+/// Contents of a snapshot file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub generation: u64,
+    /// Bytes of the previous generation's log included in `entries`
+    pub log_offset: u64,
+    pub entries: HashMap<String, CacheEntry>,
+}
+
+/// Read the snapshot in `dir`, if there is one
+pub fn read_snapshot(dir: &Path) -> Result<Option<Snapshot>, PersistenceError> {
+    let path = dir.join(SNAPSHOT_FILE);
+    let mut bytes = Vec::new();
+    match File::open(&path) {
+        Ok(mut file) => file.read_to_end(&mut bytes)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let corrupt = |reason: &str| PersistenceError::CorruptSnapshot { path: path.clone(), reason: reason.to_string() };
+
+    if bytes.len() < SNAPSHOT_HEADER_SIZE + 4 || &bytes[..8] != SNAPSHOT_MAGIC {
+        return Err(corrupt("bad header"));
+    }
+    let (body, crc) = bytes.split_at(bytes.len() - 4);
+    if crc32fast::hash(body).to_le_bytes() != crc {
+        return Err(corrupt("checksum mismatch"));
+    }
+    let generation = u64::from_le_bytes(body[8..16].try_into().map_err(|_| corrupt("bad header"))?);
+    let log_offset = u64::from_le_bytes(body[16..24].try_into().map_err(|_| corrupt("bad header"))?);
+    let entries: Vec<(String, CacheEntry)> =
+        bincode::deserialize(&body[SNAPSHOT_HEADER_SIZE..]).map_err(|e| corrupt(&e.to_string()))?;
+    Ok(Some(Snapshot {
+        generation,
+        log_offset,
+        entries: entries.into_iter().collect(),
+    }))
+}
+
+/// The part of a previous-generation `log` past `log_offset`, restamped as
+/// the log of `generation`
+///
+/// A crash after a snapshot is written but before its log is started leaves
+/// the previous log; the writes it logged after the snapshot's copy are only there.
+fn continued_log(log: &[u8], generation: u64, log_offset: u64) -> Option<Vec<u8>> {
+    let previous = generation.checked_sub(1)?;
+    let offset = usize::try_from(log_offset).ok()?;
+    if log.len() < offset || offset < FILE_HEADER_SIZE || &log[..8] != LOG_MAGIC || log[8..16] != previous.to_le_bytes() {
+        return None;
+    }
+    let mut continued = LOG_MAGIC.to_vec();
+    continued.extend_from_slice(&generation.to_le_bytes());
+    continued.extend_from_slice(&log[offset..]);
+    Some(continued)
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Start the log of `generation` with the `records` already framed for it,
+/// replacing the old one atomically
+fn create_log(dir: &Path, generation: u64, records: &[u8]) -> io::Result<File> {
+    let path = dir.join(LOG_FILE);
+    let tmp = dir.join(format!("{}.tmp", LOG_FILE));
+    let mut file = File::create(&tmp)?;
+    file.write_all(LOG_MAGIC)?;
+    file.write_all(&generation.to_le_bytes())?;
+    file.write_all(records)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, &path)?;
+    sync_dir(dir)?;
+    OpenOptions::new().append(true).open(path)
+}
+
+/// The open log
+struct LogWriter {
+    file: BufWriter<File>,
+    /// Size after the last rewrite
+    base_bytes: u64,
+    last_sync: Instant,
+    /// Records written since the last fsync
+    dirty: bool,
+}
+
+impl LogWriter {
+    fn sync(&mut self) -> io::Result<()> {
+        if self.dirty {
+            self.file.get_ref().sync_data()?;
+            self.dirty = false;
+        }
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+}
+
+/// Persistence state of one cache (the log is locked after shards)
+pub(super) struct Persistence {
+    config: PersistenceConfig,
+    generation: AtomicU64,
+    log: Mutex<Option<LogWriter>>,
+    log_bytes: AtomicU64,
+    /// Clock time of the last snapshot (Unix epoch milliseconds)
+    last_snapshot_at: AtomicI64,
+    last_snapshot: Mutex<Instant>,
+    /// Held through a rewrite, so two cannot interleave
+    rewriting: Mutex<()>,
+}
+
+impl Persistence {
+    /// Append `record`, fsyncing as the policy says
+    fn append(&self, record: &LogRecord) -> Result<(), PersistenceError> {
+        let mut log = self.log.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        let Some(writer) = log.as_mut() else {
+            return Ok(());
+        };
+        let frame = encode_record(record)?;
+        writer.file.write_all(&frame)?;
+        writer.file.flush()?;
+        writer.dirty = true;
+        self.log_bytes.fetch_add(frame.len() as u64, Ordering::Relaxed);
+
+        match self.config.fsync {
+            FsyncPolicy::Always => writer.sync()?,
+            FsyncPolicy::EverySecond if writer.last_sync.elapsed() >= Duration::from_secs(1) => writer.sync()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Length of the open log, or [`NO_LOG_OFFSET`] without one
+    fn log_offset(&self) -> Result<u64, PersistenceError> {
+        let mut log = self.log.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        match log.as_mut() {
+            Some(writer) => {
+                writer.file.flush()?;
+                Ok(writer.file.get_ref().metadata()?.len())
+            }
+            None => Ok(NO_LOG_OFFSET),
+        }
+    }
+
+    /// Snapshot `parts`, copied when the open log was `log_offset` bytes long,
+    /// and in append-only mode start a log holding what was logged since
+    fn rewrite(&self, parts: &[&IndexMap<String, CacheEntry>], log_offset: u64, now_ms: i64) -> Result<(), PersistenceError> {
+        let generation = self.generation.load(Ordering::Relaxed) + 1;
+        let size = write_snapshot(&self.config.dir, generation, log_offset, parts)?;
+        let keys: usize = parts.iter().map(|entries| entries.len()).sum();
+
+        // Writers wait from here until the new log is open
+        let mut log = self.log.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        if self.config.append_only {
+            let mut tail = Vec::new();
+            if let Some(writer) = log.as_mut() {
+                writer.file.flush()?;
+                let mut old = File::open(self.config.dir.join(LOG_FILE))?;
+                old.seek(SeekFrom::Start(log_offset))?;
+                old.read_to_end(&mut tail)?;
+            }
+            let file = create_log(&self.config.dir, generation, &tail)?;
+            let base_bytes = (FILE_HEADER_SIZE + tail.len()) as u64;
+            *log = Some(LogWriter {
+                file: BufWriter::new(file),
+                base_bytes,
+                last_sync: Instant::now(),
+                dirty: false,
+            });
+            self.log_bytes.store(base_bytes, Ordering::Relaxed);
+        }
+        self.generation.store(generation, Ordering::Relaxed);
+        self.last_snapshot_at.store(now_ms, Ordering::Relaxed);
+        *self.last_snapshot.lock().map_err(|e| io::Error::other(e.to_string()))? = Instant::now();
+        info!("💾 UAO-QTCAM snapshot {} written ({} keys, {} bytes)", generation, keys, size);
+        Ok(())
+    }
+
+    /// Whether the log or the snapshot interval calls for a rewrite
+    fn rewrite_due(&self) -> bool {
+        if !self.config.append_only {
+            return self.last_snapshot.lock().is_ok_and(|at| at.elapsed() >= self.config.snapshot_interval);
+        }
+        let bytes = self.log_bytes.load(Ordering::Relaxed);
+        let base = self.log.lock().ok().and_then(|log| log.as_ref().map(|w| w.base_bytes)).unwrap_or(0);
+        bytes >= self.config.rewrite_min_bytes && bytes >= 2 * base
+    }
+}
+
+impl UaoQtcamCache {
+    /// Restore the cache from `config.dir` and persist every later write
+    ///
+    /// Recovery replays the snapshot and the longest valid prefix of the log,
+    /// drops entries whose deadline passed while the cache was down, then
+    /// compacts everything into a fresh snapshot.
+    pub fn with_persistence(mut self, config: PersistenceConfig) -> Result<Self, PersistenceError> {
+        fs::create_dir_all(&config.dir)?;
+        let Snapshot { generation, log_offset, mut entries } = read_snapshot(&config.dir)?.unwrap_or_default();
+
+        let log_path = config.dir.join(LOG_FILE);
+        let mut replayed = 0;
+        match fs::read(&log_path) {
+            Ok(bytes) => match read_log(&continued_log(&bytes, generation, log_offset).unwrap_or(bytes)) {
+                Some(replay) if replay.generation == generation => {
+                    if replay.torn {
+                        warn!("⚠️ UAO-QTCAM log {} is torn after {} bytes - recovering the valid prefix",
+                              log_path.display(), replay.valid_len);
+                    }
+                    replayed = replay.records.len();
+                    for record in replay.records {
+                        record.apply(&mut entries);
+                    }
+                }
+                Some(replay) => warn!("⚠️ UAO-QTCAM log generation {} does not match snapshot {} - ignoring it",
+                                      replay.generation, generation),
+                None => warn!("⚠️ UAO-QTCAM log {} has no valid header - ignoring it", log_path.display()),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let now_ms = self.clock.now_ms();
+        let before = entries.len();
+        entries.retain(|_, entry| !entry.is_expired(now_ms));
+        let size = entries.iter().map(|(key, entry)| eviction::charge(key, entry)).sum::<usize>();
+        info!("💾 UAO-QTCAM recovered {} keys from {} ({} log records, {} expired while down)",
+              entries.len(), config.dir.display(), replayed, before - entries.len());
+        info!("   Append-only log: {} (fsync {})", if config.append_only { "on" } else { "off" }, config.fsync);
+
+        let persistence = Persistence {
+            config,
+            generation: AtomicU64::new(generation),
+            log: Mutex::new(None),
+            log_bytes: AtomicU64::new(0),
+            last_snapshot_at: AtomicI64::new(0),
+            last_snapshot: Mutex::new(Instant::now()),
+            rewriting: Mutex::new(()),
+        };
+        let mut shards: Vec<Shard> = (0..self.shards.len()).map(|_| Shard::default()).collect();
+        for (key, entry) in entries {
+            let shard = &mut shards[self.shard_index(&key)];
+            if let Some(at) = entry.expires_at {
+                shard.expiry.insert(at, &key);
+            }
+            shard.entries.insert(key, entry);
+        }
+        self.shards = shards.into_iter().map(RwLock::new).collect();
+        self.used = AtomicUsize::new(size);
+
+        // A smaller limit than the data was written under: evict down to it
+        // before anything is logged, or refuse to start over the limit
+        if size > self.max_size {
+            warn!("⚠️ UAO-QTCAM recovered {} bytes, over the {} byte limit - evicting", size, self.max_size);
+            if !self.evict_to_fit().map_err(io::Error::other)? {
+                return Err(PersistenceError::OverLimit {
+                    recovered: size,
+                    max_size: self.max_size,
+                    policy: self.eviction_policy().name(),
+                });
+            }
+        }
+
+        let parts: Vec<_> = self
+            .shards
+            .iter_mut()
+            .map(|lock| &lock.get_mut().unwrap_or_else(PoisonError::into_inner).entries)
+            .collect();
+        // Everything recovered is in the snapshot, so its log starts empty
+        persistence.rewrite(&parts, NO_LOG_OFFSET, now_ms)?;
+        self.persistence = Some(Arc::new(persistence));
+        Ok(self)
+    }
+
+    /// Whether writes reach disk
+    pub fn is_persistent(&self) -> bool {
+        self.persistence.is_some()
+    }
+
+    /// SAVE - Write a snapshot now; in append-only mode this also compacts the log
+    ///
+    /// Only the copy of the entries is taken under the shard locks; the
+    /// snapshot is written and fsynced after they are released.
+    pub fn save_snapshot(&self) -> Result<(), String> {
+        let persistence = self.persistence.as_ref().ok_or("persistence is not enabled")?;
+        let _rewriting = persistence.rewriting.lock().map_err(|e| e.to_string())?;
+        // Writers log under their shard's lock, so with every shard held the
+        // copy matches the log up to its current length
+        let (copies, log_offset) = {
+            let shards = self.read_all()?;
+            let copies: Vec<_> = shards.iter().map(|shard| shard.entries.clone()).collect();
+            (copies, persistence.log_offset().map_err(|e| e.to_string())?)
+        };
+        let parts: Vec<_> = copies.iter().collect();
+        persistence.rewrite(&parts, log_offset, self.clock.now_ms()).map_err(|e| e.to_string())
+    }
+
+    /// Fsync pending log records, then snapshot or compact if it is time to
+    pub fn sync_log(&self) -> Result<(), String> {
+        let Some(persistence) = &self.persistence else {
+            return Ok(());
+        };
+        if let Some(writer) = persistence.log.lock().map_err(|e| e.to_string())?.as_mut() {
+            writer.sync().map_err(|e| e.to_string())?;
+        }
+        if persistence.rewrite_due() {
+            self.save_snapshot()?;
+        }
+        Ok(())
+    }
+
+    /// Run [`sync_log`](Self::sync_log) every second; `None` without persistence
+    pub fn spawn_persistence(self: &Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        self.persistence.as_ref()?;
+        let cache = self.clone();
+        Some(tokio::spawn(async move {
+            let mut tick = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                tick.tick().await;
+                if let Err(e) = cache.sync_log() {
+                    warn!("⚠️ UAO-QTCAM persistence failed: {}", e);
+                }
+            }
+        }))
+    }
+
+    /// Append the record built by `record`; the caller holds the shard of its key
+    pub(super) fn log(&self, record: impl FnOnce() -> LogRecord) -> Result<(), String> {
+        match &self.persistence {
+            Some(persistence) => persistence.append(&record()).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    /// [`log`](Self::log) a write that reserved `reserved` bytes, releasing them if that fails
+    pub(super) fn log_reserved(&self, reserved: usize, record: impl FnOnce() -> LogRecord) -> Result<(), String> {
+        self.log(record).inspect_err(|_| self.settle(reserved, 0, 0))
+    }
+
+    /// Log size and last snapshot time, for [`CacheStats`](super::CacheStats)
+    pub(super) fn persistence_stats(&self) -> (u64, Option<i64>) {
+        match &self.persistence {
+            Some(p) => (p.log_bytes.load(Ordering::Relaxed), Some(p.last_snapshot_at.load(Ordering::Relaxed))),
+            None => (0, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_stops_at_torn_record() {
+        let mut log = LOG_MAGIC.to_vec();
+        log.extend_from_slice(&7u64.to_le_bytes());
+        let records = [
+            LogRecord::Delete { key: "a".to_string() },
+            LogRecord::Expire { key: "b".to_string(), at: 42 },
+            LogRecord::Clear,
+        ];
+        for record in &records {
+            log.extend(encode_record(record).unwrap());
+        }
+
+        let replay = read_log(&log).unwrap();
+        assert_eq!((replay.generation, replay.records.len(), replay.torn), (7, 3, false));
+        for cut in 1..log.len() - FILE_HEADER_SIZE {
+            let replay = read_log(&log[..log.len() - cut]).unwrap();
+            assert_eq!(replay.records[..], records[..replay.records.len()]);
+            assert!(replay.torn || replay.valid_len as usize == log.len() - cut);
+        }
+        assert!(read_log(&log[..FILE_HEADER_SIZE - 1]).is_none());
+        assert_eq!("EVERYSEC".parse::<FsyncPolicy>().unwrap(), FsyncPolicy::EverySecond);
+        assert!("sometimes".parse::<FsyncPolicy>().is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! Publish/subscribe channels and keyspace notifications
+//!
+//! Subscribers live in the same process and each gets a bounded queue. A
+//! publisher never waits: a message for a full queue is dropped for that
+//! subscriber and counted in [`CacheStats::dropped_messages`](super::CacheStats),
+//! and subscriptions whose receiver was dropped are pruned on the next
+//! publish to them. Patterns use the glob syntax of [`pattern`](super::pattern).
+//!
+//! With [`UaoQtcamCache::with_keyspace_events`] the cache publishes its own
+//! changes the way Redis does: the event name on `__keyspace@0__:<key>` and
+//! the key on `__keyevent@0__:<event>`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use tokio::sync::mpsc;
+
+use super::pattern::glob_match;
+use super::UaoQtcamCache;
+
+/// Messages a subscriber can fall behind by before new ones are dropped
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// Prefix of per-key notification channels
+pub const KEYSPACE_PREFIX: &str = "__keyspace@0__:";
+/// Prefix of per-event notification channels
+pub const KEYEVENT_PREFIX: &str = "__keyevent@0__:";
+
+/// A change to a key, as published by keyspace notifications
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyEvent {
+    /// A value was written (`SET`, `INCR`) or a collection changed
+    Set,
+    /// A key was deleted, including a collection emptied by an update
+    Del,
+    /// A key's TTL ran out
+    Expired,
+    /// A key was evicted to make room
+    Evicted,
+}
+
+impl KeyEvent {
+    pub const ALL: [KeyEvent; 4] = [KeyEvent::Set, KeyEvent::Del, KeyEvent::Expired, KeyEvent::Evicted];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyEvent::Set => "set",
+            KeyEvent::Del => "del",
+            KeyEvent::Expired => "expired",
+            KeyEvent::Evicted => "evicted",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// A published message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub channel: String,
+    /// The pattern that matched, for pattern subscriptions
+    pub pattern: Option<String>,
+    pub payload: Vec<u8>,
+}
+
+/// The receiving end of a subscription; dropping it unsubscribes
+#[derive(Debug)]
+pub struct Subscription {
+    receiver: mpsc::Receiver<Message>,
+}
+
+impl Subscription {
+    /// Wait for the next message
+    pub async fn recv(&mut self) -> Option<Message> {
+        self.receiver.recv().await
+    }
+
+    /// The next message if one is queued
+    pub fn try_recv(&mut self) -> Option<Message> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Channel and pattern subscribers
+pub struct PubSub {
+    channels: RwLock<HashMap<String, Vec<mpsc::Sender<Message>>>>,
+    patterns: RwLock<Vec<(String, mpsc::Sender<Message>)>>,
+    capacity: usize,
+    dropped: AtomicU64,
+}
+
+impl PubSub {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            channels: RwLock::new(HashMap::new()),
+            patterns: RwLock::new(Vec::new()),
+            capacity: capacity.max(1),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Subscribe to every channel in `channels`
+    pub fn subscribe(&self, channels: &[&str]) -> Result<Subscription, String> {
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        let mut subscribers = self.channels.write().map_err(|e| e.to_string())?;
+        for channel in channels {
+            subscribers.entry(channel.to_string()).or_default().push(sender.clone());
+        }
+        Ok(Subscription { receiver })
+    }
+
+    /// Subscribe to every channel matching one of `patterns`
+    pub fn psubscribe(&self, patterns: &[&str]) -> Result<Subscription, String> {
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        let mut subscribers = self.patterns.write().map_err(|e| e.to_string())?;
+        for pattern in patterns {
+            subscribers.push((pattern.to_string(), sender.clone()));
+        }
+        Ok(Subscription { receiver })
+    }
+
+    /// Queue `payload` for every subscriber of `channel`; returns how many got it
+    pub fn publish(&self, channel: &str, payload: &[u8]) -> Result<usize, String> {
+        let mut delivered = 0;
+        let mut closed = false;
+
+        if let Some(senders) = self.channels.read().map_err(|e| e.to_string())?.get(channel) {
+            let message = Message { channel: channel.to_string(), pattern: None, payload: payload.to_vec() };
+            for sender in senders {
+                match self.offer(sender, message.clone()) {
+                    Some(sent) => delivered += sent as usize,
+                    None => closed = true,
+                }
+            }
+        }
+        for (pattern, sender) in self.patterns.read().map_err(|e| e.to_string())?.iter() {
+            if glob_match(pattern, channel) {
+                let message = Message {
+                    channel: channel.to_string(),
+                    pattern: Some(pattern.clone()),
+                    payload: payload.to_vec(),
+                };
+                match self.offer(sender, message) {
+                    Some(sent) => delivered += sent as usize,
+                    None => closed = true,
+                }
+            }
+        }
+
+        if closed {
+            self.prune()?;
+        }
+        Ok(delivered)
+    }
+
+    /// Channels with at least one live subscriber, and pattern subscriptions
+    pub fn counts(&self) -> (usize, usize) {
+        let channels = self.channels.read().map_or(0, |c| c.values().filter(|s| s.iter().any(|s| !s.is_closed())).count());
+        let patterns = self.patterns.read().map_or(0, |p| p.iter().filter(|(_, s)| !s.is_closed()).count());
+        (channels, patterns)
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Try to queue `message`: whether it was queued, `None` if the subscriber is gone
+    fn offer(&self, sender: &mpsc::Sender<Message>, message: Message) -> Option<bool> {
+        match sender.try_send(message) {
+            Ok(()) => Some(true),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                Some(false)
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => None,
+        }
+    }
+
+    /// Forget subscribers whose receiver was dropped
+    fn prune(&self) -> Result<(), String> {
+        let mut channels = self.channels.write().map_err(|e| e.to_string())?;
+        channels.retain(|_, senders| {
+            senders.retain(|sender| !sender.is_closed());
+            !senders.is_empty()
+        });
+        drop(channels);
+        self.patterns.write().map_err(|e| e.to_string())?.retain(|(_, sender)| !sender.is_closed());
+        Ok(())
+    }
+}
+
+impl Default for PubSub {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUEUE_CAPACITY)
+    }
+}
+
+impl UaoQtcamCache {
+    /// Publish `events` on the keyspace notification channels
+    pub fn with_keyspace_events(mut self, events: &[KeyEvent]) -> Self {
+        self.keyspace_events = events.iter().fold(0, |mask, event| mask | event.bit());
+        self
+    }
+
+    /// Give new subscribers queues of `capacity` messages
+    pub fn with_subscriber_queue(mut self, capacity: usize) -> Self {
+        self.pubsub = std::sync::Arc::new(PubSub::new(capacity));
+        self
+    }
+
+    /// SUBSCRIBE - Receive messages published to `channels`
+    pub fn subscribe(&self, channels: &[&str]) -> Result<Subscription, String> {
+        self.pubsub.subscribe(channels)
+    }
+
+    /// PSUBSCRIBE - Receive messages published to channels matching `patterns`
+    pub fn psubscribe(&self, patterns: &[&str]) -> Result<Subscription, String> {
+        self.pubsub.psubscribe(patterns)
+    }
+
+    /// PUBLISH - Send `payload` to the subscribers of `channel`; returns how many got it
+    pub fn publish(&self, channel: &str, payload: &[u8]) -> Result<usize, String> {
+        self.pubsub.publish(channel, payload)
+    }
+
+    /// Publish a keyspace notification if `event` is enabled
+    pub(super) fn notify(&self, event: KeyEvent, key: &str) {
+        if self.keyspace_events & event.bit() == 0 {
+            return;
+        }
+        // Notifications are best effort; a poisoned subscriber list only loses them
+        let _ = self.pubsub.publish(&format!("{}{}", KEYSPACE_PREFIX, key), event.name().as_bytes());
+        let _ = self.pubsub.publish(&format!("{}{}", KEYEVENT_PREFIX, event.name()), key.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_queues_drop_and_closed_ones_are_pruned() {
+        let pubsub = PubSub::new(2);
+        let mut news = pubsub.subscribe(&["news"]).unwrap();
+        let mut all = pubsub.psubscribe(&["n*"]).unwrap();
+        let gone = pubsub.subscribe(&["news"]).unwrap();
+        drop(gone);
+
+        for i in 0..3u8 {
+            pubsub.publish("news", &[i]).unwrap();
+        }
+        assert_eq!(pubsub.dropped(), 2);
+        assert_eq!(pubsub.counts(), (1, 1));
+        assert_eq!(news.try_recv().unwrap().payload, vec![0]);
+        assert_eq!(news.try_recv().unwrap().payload, vec![1]);
+        assert!(news.try_recv().is_none());
+        assert_eq!(all.try_recv().unwrap().pattern.as_deref(), Some("n*"));
+
+        drop(news);
+        assert_eq!(pubsub.publish("news", b"x").unwrap(), 1);
+        assert_eq!(pubsub.channels.read().unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,810 @@
+//! RESP2/RESP3 front end
+//!
+//! Speaks the Redis protocol over TCP so existing Redis clients can use the
+//! cache directly. Commands arrive as RESP arrays of bulk strings or as
+//! inline, space-separated lines. Every complete command in the read buffer
+//! is answered in order and the replies are flushed together, so pipelined
+//! clients get one write per batch. `HELLO 3` switches a connection to RESP3.
+//!
+//! Keys are those of the control plane's global namespace.
+
+use std::ops::Bound;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, error, info};
+
+use super::pattern::glob_match;
+use super::{EvictionPolicy, KeyTtl, SetCondition, SetOptions, UaoQtcamCache, NOT_AN_INTEGER, NOT_A_FLOAT};
+
+/// Default RESP port
+pub const RESP_PORT: u16 = 6379;
+
+/// Largest bulk string and largest pending request in bytes
+pub const MAX_FRAME_BYTES: usize = 64 * 1024 * 1024;
+
+/// Most arguments in one command
+const MAX_ARGS: usize = 1024 * 1024;
+
+/// Longest inline command
+const MAX_INLINE_BYTES: usize = 64 * 1024;
+
+/// Keys examined per SCAN call without COUNT
+const DEFAULT_SCAN_COUNT: usize = 10;
+
+/// Reply encoding of a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Protocol {
+    #[default]
+    Resp2,
+    Resp3,
+}
+
+/// A reply, encoded per [`Protocol`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    Simple(String),
+    /// Full error line, including its prefix (`ERR`, `NOPROTO`, ...)
+    Error(String),
+    Integer(i64),
+    Bulk(Vec<u8>),
+    Null,
+    Array(Vec<Reply>),
+    /// A RESP3 map; flattened to an array for RESP2
+    Map(Vec<(Reply, Reply)>),
+}
+
+impl Reply {
+    fn ok() -> Self {
+        Self::Simple("OK".to_string())
+    }
+
+    fn err(message: impl std::fmt::Display) -> Self {
+        Self::Error(format!("ERR {}", message))
+    }
+
+    fn bulk(text: impl Into<String>) -> Self {
+        Self::Bulk(text.into().into_bytes())
+    }
+
+    pub fn encode(&self, protocol: Protocol, out: &mut Vec<u8>) {
+        match self {
+            Self::Simple(s) => push_line(out, b'+', s),
+            Self::Error(e) => push_line(out, b'-', e),
+            Self::Integer(n) => push_line(out, b':', n),
+            Self::Bulk(data) => {
+                push_line(out, b'$', data.len());
+                out.extend_from_slice(data);
+                out.extend_from_slice(b"\r\n");
+            }
+            Self::Null => match protocol {
+                Protocol::Resp2 => out.extend_from_slice(b"$-1\r\n"),
+                Protocol::Resp3 => out.extend_from_slice(b"_\r\n"),
+            },
+            Self::Array(items) => {
+                push_line(out, b'*', items.len());
+                for item in items {
+                    item.encode(protocol, out);
+                }
+            }
+            Self::Map(pairs) => {
+                match protocol {
+                    Protocol::Resp2 => push_line(out, b'*', pairs.len() * 2),
+                    Protocol::Resp3 => push_line(out, b'%', pairs.len()),
+                }
+                for (key, value) in pairs {
+                    key.encode(protocol, out);
+                    value.encode(protocol, out);
+                }
+            }
+        }
+    }
+}
+
+fn push_line(out: &mut Vec<u8>, prefix: u8, value: impl std::fmt::Display) {
+    out.push(prefix);
+    out.extend_from_slice(value.to_string().as_bytes());
+    out.extend_from_slice(b"\r\n");
+}
+
+// ============================================================================
+// PARSING
+// ============================================================================
+
+/// Arguments of a parsed command and the bytes it took
+pub type Command = (Vec<Vec<u8>>, usize);
+
+/// Parse one command from the start of `buf`
+///
+/// Returns the arguments and the bytes consumed, `None` if the command is
+/// still incomplete, or a protocol error after which the connection closes.
+/// An empty argument list is a blank line or an empty array.
+pub fn parse_command(buf: &[u8]) -> Result<Option<Command>, String> {
+    match buf.first() {
+        None => Ok(None),
+        Some(b'*') => parse_multibulk(buf),
+        Some(_) => parse_inline(buf),
+    }
+}
+
+fn parse_inline(buf: &[u8]) -> Result<Option<Command>, String> {
+    let Some(end) = buf.iter().position(|&b| b == b'\n') else {
+        return if buf.len() > MAX_INLINE_BYTES {
+            Err("too big inline request".to_string())
+        } else {
+            Ok(None)
+        };
+    };
+    let line = buf[..end].strip_suffix(b"\r").unwrap_or(&buf[..end]);
+    let args = line
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|arg| !arg.is_empty())
+        .map(<[u8]>::to_vec)
+        .collect();
+    Ok(Some((args, end + 1)))
+}
+
+fn parse_multibulk(buf: &[u8]) -> Result<Option<Command>, String> {
+    let Some((count, mut pos)) = read_number(buf, 1)? else {
+        return Ok(None);
+    };
+    if count > MAX_ARGS as i64 {
+        return Err("invalid multibulk length".to_string());
+    }
+
+    let mut args = Vec::with_capacity(count.clamp(0, 1024) as usize);
+    for _ in 0..count.max(0) {
+        match buf.get(pos) {
+            None => return Ok(None),
+            Some(b'$') => {}
+            Some(&other) => return Err(format!("expected '$', got '{}'", other as char)),
+        }
+        let Some((len, start)) = read_number(buf, pos + 1)? else {
+            return Ok(None);
+        };
+        if !(0..=MAX_FRAME_BYTES as i64).contains(&len) {
+            return Err("invalid bulk length".to_string());
+        }
+        let end = start + len as usize;
+        if buf.len() < end + 2 {
+            return Ok(None);
+        }
+        if &buf[end..end + 2] != b"\r\n" {
+            return Err("bulk string not terminated by CRLF".to_string());
+        }
+        args.push(buf[start..end].to_vec());
+        pos = end + 2;
+    }
+    Ok(Some((args, pos)))
+}
+
+/// Read a CRLF-terminated integer at `start`; returns it and the position after the CRLF
+fn read_number(buf: &[u8], start: usize) -> Result<Option<(i64, usize)>, String> {
+    let Some(offset) = buf[start..].windows(2).position(|w| w == b"\r\n") else {
+        return if buf.len() - start > 32 {
+            Err("invalid length line".to_string())
+        } else {
+            Ok(None)
+        };
+    };
+    std::str::from_utf8(&buf[start..start + offset])
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(|n| Some((n, start + offset + 2)))
+        .ok_or_else(|| "invalid length".to_string())
+}
+
+// ============================================================================
+// COMMANDS
+// ============================================================================
+
+/// Run one command against `cache`; `HELLO` may switch `protocol`
+pub fn execute(cache: &UaoQtcamCache, protocol: &mut Protocol, args: &[Vec<u8>]) -> Reply {
+    let Some((name, args)) = args.split_first() else {
+        return Reply::err("empty command");
+    };
+    let result = match String::from_utf8_lossy(name).to_ascii_lowercase().as_str() {
+        "ping" => ping(args),
+        "hello" => hello(protocol, args),
+        "get" => get(cache, args),
+        "set" => set(cache, args),
+        "del" => del(cache, args),
+        "exists" => exists(cache, args),
+        "incr" => incr(cache, args, false),
+        "incrby" => incr(cache, args, true),
+        "expire" => expire(cache, args),
+        "ttl" => ttl(cache, args),
+        "type" => key_type(cache, args),
+        "hset" => hset(cache, args),
+        "hget" => hget(cache, args),
+        "hdel" => hdel(cache, args),
+        "hgetall" => hgetall(cache, args),
+        "lpush" => lpush(cache, args),
+        "rpop" => rpop(cache, args),
+        "lrange" => lrange(cache, args),
+        "sadd" => sadd(cache, args),
+        "sismember" => sismember(cache, args),
+        "smembers" => smembers(cache, args),
+        "zadd" => zadd(cache, args),
+        "zrangebyscore" => zrangebyscore(cache, args),
+        "keys" => keys(cache, args),
+        "scan" => scan(cache, args),
+        "publish" => publish(cache, args),
+        "info" => info(cache, args),
+        "config" => config(cache, args),
+        "save" => save(cache, args),
+        _ => {
+            let preview: String = args
+                .iter()
+                .take(3)
+                .map(|a| format!("'{}' ", String::from_utf8_lossy(a)))
+                .collect();
+            Err(Reply::err(format!(
+                "unknown command '{}', with args beginning with: {}",
+                String::from_utf8_lossy(name),
+                preview
+            )))
+        }
+    };
+    result.unwrap_or_else(|error| error)
+}
+
+/// A reply, or the error reply to send instead
+type CommandResult = Result<Reply, Reply>;
+
+fn wrong_arity(command: &str) -> Reply {
+    Reply::err(format!("wrong number of arguments for '{}' command", command))
+}
+
+fn key(arg: &[u8]) -> Result<&str, Reply> {
+    std::str::from_utf8(arg).map_err(|_| Reply::err("keys must be valid UTF-8"))
+}
+
+fn integer(arg: &[u8]) -> Result<i64, Reply> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| Reply::err(NOT_AN_INTEGER))
+}
+
+fn float(arg: &[u8]) -> Result<f64, Reply> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|n| !n.is_nan())
+        .ok_or_else(|| Reply::err(NOT_A_FLOAT))
+}
+
+/// A `ZRANGEBYSCORE` bound: a score, `(score` for exclusive, or `-inf`/`+inf`
+fn score_bound(arg: &[u8]) -> Result<Bound<f64>, Reply> {
+    let invalid = || Reply::err("min or max is not a float");
+    match arg.strip_prefix(b"(") {
+        Some(score) => float(score).map(Bound::Excluded).map_err(|_| invalid()),
+        None => float(arg).map(Bound::Included).map_err(|_| invalid()),
+    }
+}
+
+fn cache_error(e: String) -> Reply {
+    // Type and out-of-memory errors carry their own prefix
+    if e.starts_with("WRONGTYPE") || e.starts_with("OOM") {
+        Reply::Error(e)
+    } else {
+        Reply::err(e)
+    }
+}
+
+fn ping(args: &[Vec<u8>]) -> CommandResult {
+    match args {
+        [] => Ok(Reply::Simple("PONG".to_string())),
+        [message] => Ok(Reply::Bulk(message.clone())),
+        _ => Err(wrong_arity("ping")),
+    }
+}
+
+fn hello(protocol: &mut Protocol, args: &[Vec<u8>]) -> CommandResult {
+    match args {
+        [] => {}
+        [version] => {
+            *protocol = match integer(version) {
+                Ok(2) => Protocol::Resp2,
+                Ok(3) => Protocol::Resp3,
+                Ok(_) => return Err(Reply::Error("NOPROTO unsupported protocol version".to_string())),
+                Err(_) => return Err(Reply::err("Protocol version is not an integer or out of range")),
+            }
+        }
+        _ => return Err(Reply::err("syntax error")),
+    }
+    let proto = match protocol {
+        Protocol::Resp2 => 2,
+        Protocol::Resp3 => 3,
+    };
+    Ok(Reply::Map(vec![
+        (Reply::bulk("server"), Reply::bulk("symmetrix")),
+        (Reply::bulk("version"), Reply::bulk(env!("CARGO_PKG_VERSION"))),
+        (Reply::bulk("proto"), Reply::Integer(proto)),
+        (Reply::bulk("mode"), Reply::bulk("standalone")),
+        (Reply::bulk("role"), Reply::bulk("master")),
+        (Reply::bulk("modules"), Reply::Array(Vec::new())),
+    ]))
+}
+
+fn get(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k] = args else {
+        return Err(wrong_arity("get"));
+    };
+    Ok(match cache.get(key(k)?).map_err(cache_error)? {
+        Some(value) => Reply::Bulk(value),
+        None => Reply::Null,
+    })
+}
+
+fn set(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, value, options @ ..] = args else {
+        return Err(wrong_arity("set"));
+    };
+    let k = key(k)?;
+
+    let mut rest = options.iter();
+    let mut options = SetOptions::default();
+    while let Some(option) = rest.next() {
+        let option = String::from_utf8_lossy(option).to_ascii_uppercase();
+        match option.as_str() {
+            "EX" | "PX" if options.ttl.is_none() => {
+                let amount = integer(rest.next().ok_or_else(|| Reply::err("syntax error"))?)?;
+                if amount <= 0 {
+                    return Err(Reply::err("invalid expire time in 'set' command"));
+                }
+                let unit_ms = if option == "EX" { 1000 } else { 1 };
+                options.ttl = Some(ttl_millis(amount, unit_ms, "set")?);
+            }
+            "NX" if options.condition == SetCondition::Always => options.condition = SetCondition::IfAbsent,
+            "XX" if options.condition == SetCondition::Always => options.condition = SetCondition::IfPresent,
+            _ => return Err(Reply::err("syntax error")),
+        }
+    }
+
+    Ok(match cache.set_with(k, value, options).map_err(cache_error)? {
+        true => Reply::ok(),
+        false => Reply::Null,
+    })
+}
+
+fn del(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    if args.is_empty() {
+        return Err(wrong_arity("del"));
+    }
+    let mut deleted = 0;
+    for k in args {
+        deleted += cache.delete(key(k)?).map_err(cache_error)? as i64;
+    }
+    Ok(Reply::Integer(deleted))
+}
+
+fn exists(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    if args.is_empty() {
+        return Err(wrong_arity("exists"));
+    }
+    let mut found = 0;
+    for k in args {
+        found += cache.exists(key(k)?).map_err(cache_error)? as i64;
+    }
+    Ok(Reply::Integer(found))
+}
+
+fn incr(cache: &UaoQtcamCache, args: &[Vec<u8>], by: bool) -> CommandResult {
+    let (k, delta) = match (args, by) {
+        ([k], false) => (k, 1),
+        ([k, delta], true) => (k, integer(delta)?),
+        (_, false) => return Err(wrong_arity("incr")),
+        (_, true) => return Err(wrong_arity("incrby")),
+    };
+    cache.incr_by(key(k)?, delta).map(Reply::Integer).map_err(cache_error)
+}
+
+fn expire(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, seconds] = args else {
+        return Err(wrong_arity("expire"));
+    };
+    // A TTL of zero or less deletes the key
+    let ttl = ttl_millis(integer(seconds)?, 1000, "expire")?;
+    let applied = cache.expire(key(k)?, ttl).map_err(cache_error)?;
+    Ok(Reply::Integer(applied as i64))
+}
+
+/// `amount` units of `unit_ms` as a TTL (negative is zero); like Redis,
+/// refused when the milliseconds overflow
+fn ttl_millis(amount: i64, unit_ms: i64, command: &str) -> Result<Duration, Reply> {
+    amount
+        .checked_mul(unit_ms)
+        .map(|ms| Duration::from_millis(ms.max(0) as u64))
+        .ok_or_else(|| Reply::err(format!("invalid expire time in '{}' command", command)))
+}
+
+fn ttl(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k] = args else {
+        return Err(wrong_arity("ttl"));
+    };
+    Ok(Reply::Integer(match cache.ttl(key(k)?).map_err(cache_error)? {
+        KeyTtl::Missing => -2,
+        KeyTtl::Persistent => -1,
+        KeyTtl::Expires(remaining) => ((remaining.as_millis() + 500) / 1000) as i64,
+    }))
+}
+
+fn key_type(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k] = args else {
+        return Err(wrong_arity("type"));
+    };
+    let kind = cache.key_type(key(k)?).map_err(cache_error)?;
+    Ok(Reply::Simple(kind.map_or("none", |kind| kind.name()).to_string()))
+}
+
+fn hset(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, pairs @ ..] = args else {
+        return Err(wrong_arity("hset"));
+    };
+    if pairs.is_empty() || pairs.len() % 2 != 0 {
+        return Err(wrong_arity("hset"));
+    }
+    let pairs: Vec<_> = pairs.chunks(2).map(|pair| (&pair[0][..], &pair[1][..])).collect();
+    let added = cache.hset(key(k)?, &pairs).map_err(cache_error)?;
+    Ok(Reply::Integer(added as i64))
+}
+
+fn hget(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, field] = args else {
+        return Err(wrong_arity("hget"));
+    };
+    Ok(match cache.hget(key(k)?, field).map_err(cache_error)? {
+        Some(value) => Reply::Bulk(value),
+        None => Reply::Null,
+    })
+}
+
+fn hdel(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, fields @ ..] = args else {
+        return Err(wrong_arity("hdel"));
+    };
+    if fields.is_empty() {
+        return Err(wrong_arity("hdel"));
+    }
+    let fields: Vec<_> = fields.iter().map(Vec::as_slice).collect();
+    let removed = cache.hdel(key(k)?, &fields).map_err(cache_error)?;
+    Ok(Reply::Integer(removed as i64))
+}
+
+fn hgetall(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k] = args else {
+        return Err(wrong_arity("hgetall"));
+    };
+    let pairs = cache.hgetall(key(k)?).map_err(cache_error)?;
+    Ok(Reply::Map(pairs.into_iter().map(|(f, v)| (Reply::Bulk(f), Reply::Bulk(v))).collect()))
+}
+
+fn lpush(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, items @ ..] = args else {
+        return Err(wrong_arity("lpush"));
+    };
+    if items.is_empty() {
+        return Err(wrong_arity("lpush"));
+    }
+    let items: Vec<_> = items.iter().map(Vec::as_slice).collect();
+    let len = cache.lpush(key(k)?, &items).map_err(cache_error)?;
+    Ok(Reply::Integer(len as i64))
+}
+
+fn rpop(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let (k, count) = match args {
+        [k] => (k, None),
+        [k, count] => match integer(count)? {
+            n if n >= 0 => (k, Some(n as usize)),
+            _ => return Err(Reply::err("value is out of range, must be positive")),
+        },
+        _ => return Err(wrong_arity("rpop")),
+    };
+    let k = key(k)?;
+    // A missing key is a null reply in both forms
+    if count.is_some() && cache.key_type(k).map_err(cache_error)?.is_none() {
+        return Ok(Reply::Null);
+    }
+    let mut items = cache.rpop(k, count.unwrap_or(1)).map_err(cache_error)?;
+    Ok(match count {
+        Some(_) => Reply::Array(items.into_iter().map(Reply::Bulk).collect()),
+        None => items.pop().map_or(Reply::Null, Reply::Bulk),
+    })
+}
+
+fn lrange(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, start, stop] = args else {
+        return Err(wrong_arity("lrange"));
+    };
+    let items = cache.lrange(key(k)?, integer(start)?, integer(stop)?).map_err(cache_error)?;
+    Ok(Reply::Array(items.into_iter().map(Reply::Bulk).collect()))
+}
+
+fn sadd(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, members @ ..] = args else {
+        return Err(wrong_arity("sadd"));
+    };
+    if members.is_empty() {
+        return Err(wrong_arity("sadd"));
+    }
+    let members: Vec<_> = members.iter().map(Vec::as_slice).collect();
+    let added = cache.sadd(key(k)?, &members).map_err(cache_error)?;
+    Ok(Reply::Integer(added as i64))
+}
+
+fn sismember(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, member] = args else {
+        return Err(wrong_arity("sismember"));
+    };
+    let found = cache.sismember(key(k)?, member).map_err(cache_error)?;
+    Ok(Reply::Integer(found as i64))
+}
+
+fn smembers(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k] = args else {
+        return Err(wrong_arity("smembers"));
+    };
+    let members = cache.smembers(key(k)?).map_err(cache_error)?;
+    Ok(Reply::Array(members.into_iter().map(Reply::Bulk).collect()))
+}
+
+fn zadd(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [k, pairs @ ..] = args else {
+        return Err(wrong_arity("zadd"));
+    };
+    if pairs.is_empty() {
+        return Err(wrong_arity("zadd"));
+    }
+    if pairs.len() % 2 != 0 {
+        return Err(Reply::err("syntax error"));
+    }
+    let members = pairs
+        .chunks(2)
+        .map(|pair| Ok((float(&pair[0])?, &pair[1][..])))
+        .collect::<Result<Vec<_>, Reply>>()?;
+    let added = cache.zadd(key(k)?, &members).map_err(cache_error)?;
+    Ok(Reply::Integer(added as i64))
+}
+
+fn zrangebyscore(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let (k, min, max, with_scores) = match args {
+        [k, min, max] => (k, min, max, false),
+        [k, min, max, option] if option.eq_ignore_ascii_case(b"withscores") => (k, min, max, true),
+        [_, _, _, _] => return Err(Reply::err("syntax error")),
+        _ => return Err(wrong_arity("zrangebyscore")),
+    };
+    let members = cache
+        .zrange_by_score(key(k)?, score_bound(min)?, score_bound(max)?)
+        .map_err(cache_error)?;
+    let mut replies = Vec::with_capacity(members.len() * if with_scores { 2 } else { 1 });
+    for (member, score) in members {
+        replies.push(Reply::Bulk(member));
+        if with_scores {
+            replies.push(Reply::bulk(score.to_string()));
+        }
+    }
+    Ok(Reply::Array(replies))
+}
+
+fn keys(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [pattern] = args else {
+        return Err(wrong_arity("keys"));
+    };
+    let keys = cache.keys(&String::from_utf8_lossy(pattern)).map_err(cache_error)?;
+    Ok(Reply::Array(keys.into_iter().map(Reply::bulk).collect()))
+}
+
+fn scan(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [cursor, options @ ..] = args else {
+        return Err(wrong_arity("scan"));
+    };
+    let cursor: u64 = std::str::from_utf8(cursor)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| Reply::err("invalid cursor"))?;
+
+    let mut pattern = "*".to_string();
+    let mut count = DEFAULT_SCAN_COUNT;
+    for pair in options.chunks(2) {
+        let [option, value] = pair else {
+            return Err(Reply::err("syntax error"));
+        };
+        match String::from_utf8_lossy(option).to_ascii_uppercase().as_str() {
+            "MATCH" => pattern = String::from_utf8_lossy(value).into_owned(),
+            "COUNT" => match integer(value)? {
+                n if n >= 1 => count = n as usize,
+                _ => return Err(Reply::err("syntax error")),
+            },
+            _ => return Err(Reply::err("syntax error")),
+        }
+    }
+
+    let (next, keys) = cache.scan(cursor, &pattern, count).map_err(cache_error)?;
+    Ok(Reply::Array(vec![
+        Reply::bulk(next.to_string()),
+        Reply::Array(keys.into_iter().map(Reply::bulk).collect()),
+    ]))
+}
+
+fn publish(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let [channel, message] = args else {
+        return Err(wrong_arity("publish"));
+    };
+    let delivered = cache.publish(&String::from_utf8_lossy(channel), message).map_err(cache_error)?;
+    Ok(Reply::Integer(delivered as i64))
+}
+
+fn save(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    if !args.is_empty() {
+        return Err(wrong_arity("save"));
+    }
+    cache.save_snapshot().map_err(cache_error)?;
+    Ok(Reply::ok())
+}
+
+fn info(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    if args.len() > 1 {
+        return Err(wrong_arity("info"));
+    }
+    let section = args.first().map(|s| String::from_utf8_lossy(s).to_ascii_lowercase());
+    let stats = cache.stats().map_err(cache_error)?;
+
+    let sections = [
+        ("server", format!("symmetrix_version:{}\r\nredis_mode:standalone\r\n", env!("CARGO_PKG_VERSION"))),
+        ("memory", format!(
+            "used_memory:{}\r\nmaxmemory:{}\r\nmaxmemory_policy:{}\r\nvalue_bytes:{}\r\nlogical_bytes:{}\r\n\
+             compression_ratio:{:.2}\r\ncodec:{}\r\n{}",
+            stats.used_memory, stats.max_memory, stats.eviction_policy, stats.compressed_bytes,
+            stats.original_bytes, stats.compression_ratio, stats.codec,
+            stats.type_usage.iter()
+                .map(|(kind, usage)| format!("used_memory_{}:{}\r\n", kind, usage.bytes))
+                .collect::<String>()
+        )),
+        ("stats", format!(
+            "keyspace_hits:{}\r\nkeyspace_misses:{}\r\nexpired_keys:{}\r\nevicted_keys:{}\r\n{}\
+             rejected_writes:{}\r\nchecksum_failures:{}\r\n",
+            stats.hits, stats.misses, stats.expired_keys, stats.evictions,
+            stats.evictions_by_policy.iter()
+                .map(|(policy, count)| format!("evicted_keys_{}:{}\r\n", policy.name().replace('-', "_"), count))
+                .collect::<String>(),
+            stats.rejected_writes, stats.checksum_failures
+        )),
+        ("persistence", format!(
+            "aof_enabled:{}\r\naof_current_size:{}\r\nrdb_last_save_time:{}\r\n",
+            u8::from(stats.log_bytes > 0), stats.log_bytes, stats.last_snapshot_at.map_or(0, |ms| ms / 1000)
+        )),
+        ("keyspace", format!("db0:keys={},expires={}\r\n", stats.entry_count, stats.expiring_keys)),
+    ];
+    let mut text = String::new();
+    for (name, body) in sections {
+        if section.as_deref().is_none_or(|s| s == name || s == "all" || s == "default") {
+            if !text.is_empty() {
+                text.push_str("\r\n");
+            }
+            let mut title = name.to_string();
+            title[..1].make_ascii_uppercase();
+            text.push_str(&format!("# {}\r\n{}", title, body));
+        }
+    }
+    Ok(Reply::bulk(text))
+}
+
+/// `CONFIG GET` of `maxmemory` and `maxmemory-policy`, `CONFIG SET` of the policy
+fn config(cache: &UaoQtcamCache, args: &[Vec<u8>]) -> CommandResult {
+    let Some((subcommand, args)) = args.split_first() else {
+        return Err(wrong_arity("config"));
+    };
+    match (String::from_utf8_lossy(subcommand).to_ascii_lowercase().as_str(), args) {
+        ("get", [parameter]) => {
+            let parameter = String::from_utf8_lossy(parameter).to_ascii_lowercase();
+            let values = [
+                ("maxmemory", cache.max_memory().to_string()),
+                ("maxmemory-policy", cache.eviction_policy().to_string()),
+            ];
+            Ok(Reply::Map(
+                values
+                    .into_iter()
+                    .filter(|(name, _)| glob_match(&parameter, name))
+                    .map(|(name, value)| (Reply::bulk(name), Reply::bulk(value)))
+                    .collect(),
+            ))
+        }
+        ("set", [parameter, value]) => {
+            let parameter = String::from_utf8_lossy(parameter).to_ascii_lowercase();
+            if parameter != "maxmemory-policy" {
+                return Err(Reply::err(format!("Unknown option or number of arguments for CONFIG SET - '{}'", parameter)));
+            }
+            let policy: EvictionPolicy = String::from_utf8_lossy(value).parse().map_err(|e| {
+                Reply::err(format!("CONFIG SET failed (possibly related to argument 'maxmemory-policy') - {}", e))
+            })?;
+            cache.set_eviction_policy(policy);
+            Ok(Reply::ok())
+        }
+        ("get", _) => Err(wrong_arity("config|get")),
+        ("set", _) => Err(wrong_arity("config|set")),
+        (other, _) => Err(Reply::err(format!("unknown subcommand '{}'. Try CONFIG HELP.", other))),
+    }
+}
+
+// ============================================================================
+// SERVER
+// ============================================================================
+
+/// Answer RESP connections on `listener` until it fails
+pub async fn serve(listener: TcpListener, cache: Arc<UaoQtcamCache>) {
+    if let Ok(addr) = listener.local_addr() {
+        info!("🧮 RESP server listening on {}", addr);
+    }
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let cache = cache.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &cache).await {
+                        debug!("RESP connection {} closed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => {
+                error!("Error accepting RESP connection: {}", e);
+            }
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, cache: &UaoQtcamCache) -> std::io::Result<()> {
+    let mut protocol = Protocol::default();
+    let mut buf = Vec::with_capacity(16 * 1024);
+    let mut chunk = vec![0u8; 16 * 1024];
+
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let mut out = Vec::new();
+        let mut consumed = 0;
+        let mut close = false;
+        loop {
+            match parse_command(&buf[consumed..]) {
+                Ok(Some((args, used))) => {
+                    consumed += used;
+                    if args.is_empty() {
+                        continue;
+                    }
+                    if args[0].eq_ignore_ascii_case(b"quit") {
+                        Reply::ok().encode(protocol, &mut out);
+                        close = true;
+                        break;
+                    }
+                    execute(cache, &mut protocol, &args).encode(protocol, &mut out);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    Reply::err(format!("Protocol error: {}", e)).encode(protocol, &mut out);
+                    close = true;
+                    break;
+                }
+            }
+        }
+        buf.drain(..consumed);
+        if !close && buf.len() > MAX_FRAME_BYTES {
+            Reply::err("Protocol error: request too large").encode(protocol, &mut out);
+            close = true;
+        }
+
+        if !out.is_empty() {
+            stream.write_all(&out).await?;
+        }
+        if close {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Keyspace shards
+//!
+//! Keys are spread over shards by hash. Each shard sits behind its own lock
+//! with its own deadline index, access clock and counters, so threads working
+//! on different keys rarely meet; the only state they share on the hot path
+//! is the atomic byte count that enforces `max_size`. Eviction and expiry
+//! work shard by shard, and statistics are summed over shards one at a time.
+//!
+//! Lock order: a thread holding a shard only waits for shards with a higher
+//! index (lower ones are tried without blocking), so cross-shard eviction,
+//! snapshots and `clear` cannot deadlock. The log and the subscriber lists
+//! are locked after shards.
+
+use std::collections::BTreeMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use indexmap::IndexMap;
+
+use super::{key_hash, CacheEntry, CacheStats, EvictionPolicy, ExpiryIndex, UaoQtcamCache};
+
+/// Shards of a new cache
+pub const DEFAULT_SHARDS: usize = 16;
+/// Most shards a cache can have; SCAN cursors hold the shard in 16 bits
+pub const MAX_SHARDS: usize = 1 << 16;
+
+/// Counters a shard keeps under its own lock
+#[derive(Clone, Debug, Default)]
+pub(super) struct Counters {
+    pub hits: u64,
+    pub misses: u64,
+    pub sets: u64,
+    pub deletes: u64,
+    pub evictions: u64,
+    pub evictions_by_policy: BTreeMap<EvictionPolicy, u64>,
+    pub rejected_writes: u64,
+    pub expired_keys: u64,
+    pub checksum_failures: u64,
+}
+
+impl Counters {
+    fn add_to(&self, stats: &mut CacheStats) {
+        stats.hits += self.hits;
+        stats.misses += self.misses;
+        stats.sets += self.sets;
+        stats.deletes += self.deletes;
+        stats.evictions += self.evictions;
+        for (policy, count) in &self.evictions_by_policy {
+            *stats.evictions_by_policy.entry(*policy).or_default() += count;
+        }
+        stats.rejected_writes += self.rejected_writes;
+        stats.expired_keys += self.expired_keys;
+        stats.checksum_failures += self.checksum_failures;
+    }
+}
+
+/// One partition of the keyspace
+#[derive(Default)]
+pub(super) struct Shard {
+    /// Keys by position too, so eviction can sample them at random
+    pub entries: IndexMap<String, CacheEntry>,
+    /// Keys of this shard with a TTL, by deadline
+    pub expiry: ExpiryIndex,
+    pub counters: Counters,
+    /// Logical clock ordering accesses, for LRU within the shard
+    tick: u64,
+}
+
+impl Shard {
+    /// Next tick of the access clock
+    pub fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Add this shard's keys and counters to `stats`
+    pub fn add_to(&self, stats: &mut CacheStats) {
+        self.counters.add_to(stats);
+        stats.entry_count += self.entries.len();
+        stats.expiring_keys += self.expiry.len();
+        for entry in self.entries.values() {
+            stats.compressed_bytes += entry.size();
+            stats.original_bytes += entry.original_size;
+            let usage = stats.type_usage.entry(entry.value.kind()).or_default();
+            usage.keys += 1;
+            usage.bytes += entry.size();
+        }
+    }
+}
+
+/// `count` empty shards, at least one and at most [`MAX_SHARDS`]
+pub(super) fn empty_shards(count: usize) -> Box<[RwLock<Shard>]> {
+    (0..count.clamp(1, MAX_SHARDS)).map(|_| RwLock::new(Shard::default())).collect()
+}
+
+impl UaoQtcamCache {
+    /// Spread keys over `shards` independently locked shards; call before storing anything
+    pub fn with_shards(mut self, shards: usize) -> Self {
+        self.shards = empty_shards(shards);
+        self
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Index of the shard holding `key`
+    pub(super) fn shard_index(&self, key: &str) -> usize {
+        (key_hash(key) >> 32) as usize % self.shards.len()
+    }
+
+    /// Lock the shard holding `key` for writing; returns its index too
+    pub(super) fn write_shard(&self, key: &str) -> Result<(usize, RwLockWriteGuard<'_, Shard>), String> {
+        let index = self.shard_index(key);
+        Ok((index, self.shards[index].write().map_err(|e| e.to_string())?))
+    }
+
+    /// Lock the shard holding `key` for reading
+    pub(super) fn read_shard(&self, key: &str) -> Result<RwLockReadGuard<'_, Shard>, String> {
+        self.shards[self.shard_index(key)].read().map_err(|e| e.to_string())
+    }
+
+    /// Read-lock every shard in index order
+    pub(super) fn read_all(&self) -> Result<Vec<RwLockReadGuard<'_, Shard>>, String> {
+        self.shards.iter().map(|shard| shard.read().map_err(|e| e.to_string())).collect()
+    }
+}
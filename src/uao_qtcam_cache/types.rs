@@ -0,0 +1,650 @@
+//! Structured value types: hashes, lists, sets and sorted sets
+//!
+//! Strings stay codec frames; the other types are held uncompressed so an
+//! update touches only the elements it names. Every update runs under the
+//! cache write lock, so read-modify-write races between clients cannot lose
+//! writes. Commands against a key of another type fail with [`WRONG_TYPE`],
+//! and a collection whose last element is removed deletes its key.
+//!
+//! Each type accounts its own memory: the element bytes plus a fixed
+//! per-element overhead for the container's bookkeeping, kept up to date
+//! incrementally so eviction sees the real footprint of every key. Room
+//! for an update is made before it is applied, for the most it can add.
+//! Existing keys are logged as the [`Update`] that changed them, new keys
+//! as the whole entry.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::ops::Bound;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::eviction::{self, ENTRY_OVERHEAD};
+use super::{CacheEntry, KeyEvent, LogRecord, UaoQtcamCache};
+
+/// Error text for commands against a key of another type
+pub const WRONG_TYPE: &str = "WRONGTYPE Operation against a key holding the wrong kind of value";
+
+/// Error text for NaN or unparsable scores
+pub const NOT_A_FLOAT: &str = "value is not a valid float";
+
+/// Bookkeeping bytes per hash field: two vector headers and a table slot
+pub const HASH_FIELD_OVERHEAD: usize = 56;
+/// Bookkeeping bytes per list item: a vector header in the ring buffer
+pub const LIST_ITEM_OVERHEAD: usize = 24;
+/// Bookkeeping bytes per set member: a vector header and a table slot
+pub const SET_MEMBER_OVERHEAD: usize = 32;
+/// Bookkeeping bytes per sorted set member, which is held by name and by score
+pub const ZSET_MEMBER_OVERHEAD: usize = 96;
+
+/// Field/value pairs of a hash
+pub type FieldValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Type of a stored value, as reported by `TYPE`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    String,
+    Hash,
+    List,
+    Set,
+    ZSet,
+}
+
+impl ValueKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueKind::String => "string",
+            ValueKind::Hash => "hash",
+            ValueKind::List => "list",
+            ValueKind::Set => "set",
+            ValueKind::ZSet => "zset",
+        }
+    }
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An `f64` ordered by `total_cmp`; NaN never gets in
+#[derive(Clone, Copy, Debug)]
+struct Score(f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Members with scores, indexed both ways
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<(Vec<u8>, f64)>", into = "Vec<(Vec<u8>, f64)>")]
+pub struct SortedSet {
+    scores: HashMap<Vec<u8>, f64>,
+    order: BTreeSet<(Score, Vec<u8>)>,
+}
+
+impl SortedSet {
+    /// Set the score of `member`; true if it is new
+    pub fn insert(&mut self, member: Vec<u8>, score: f64) -> bool {
+        // Adding zero folds -0.0 into 0.0, which total_cmp would order apart
+        let score = score + 0.0;
+        match self.scores.insert(member.clone(), score) {
+            Some(old) => {
+                self.order.remove(&(Score(old), member.clone()));
+                self.order.insert((Score(score), member));
+                false
+            }
+            None => {
+                self.order.insert((Score(score), member));
+                true
+            }
+        }
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Members with scores between `min` and `max`, by score then member
+    pub fn range_by_score(&self, min: Bound<f64>, max: Bound<f64>) -> Vec<(Vec<u8>, f64)> {
+        let start = match min {
+            Bound::Included(s) | Bound::Excluded(s) => Bound::Included((Score(s + 0.0), Vec::new())),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        self.order
+            .range((start, Bound::Unbounded))
+            .skip_while(|(score, _)| matches!(min, Bound::Excluded(s) if score.0 <= s))
+            .take_while(|(score, _)| match max {
+                Bound::Included(s) => score.0 <= s,
+                Bound::Excluded(s) => score.0 < s,
+                Bound::Unbounded => true,
+            })
+            .map(|(score, member)| (member.clone(), score.0))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+}
+
+impl PartialEq for SortedSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.scores == other.scores
+    }
+}
+
+impl From<Vec<(Vec<u8>, f64)>> for SortedSet {
+    fn from(members: Vec<(Vec<u8>, f64)>) -> Self {
+        let mut set = SortedSet::default();
+        for (member, score) in members {
+            set.insert(member, score);
+        }
+        set
+    }
+}
+
+impl From<SortedSet> for Vec<(Vec<u8>, f64)> {
+    fn from(set: SortedSet) -> Self {
+        set.order.into_iter().map(|(score, member)| (member, score.0)).collect()
+    }
+}
+
+/// A stored value
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CacheValue {
+    /// Codec frame (header + compressed value)
+    String(Vec<u8>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
+    List(VecDeque<Vec<u8>>),
+    Set(HashSet<Vec<u8>>),
+    ZSet(SortedSet),
+}
+
+impl CacheValue {
+    /// An empty value of `kind`; strings start as an empty payload, not a frame
+    pub fn empty(kind: ValueKind) -> Self {
+        match kind {
+            ValueKind::String => CacheValue::String(Vec::new()),
+            ValueKind::Hash => CacheValue::Hash(HashMap::new()),
+            ValueKind::List => CacheValue::List(VecDeque::new()),
+            ValueKind::Set => CacheValue::Set(HashSet::new()),
+            ValueKind::ZSet => CacheValue::ZSet(SortedSet::default()),
+        }
+    }
+
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            CacheValue::String(_) => ValueKind::String,
+            CacheValue::Hash(_) => ValueKind::Hash,
+            CacheValue::List(_) => ValueKind::List,
+            CacheValue::Set(_) => ValueKind::Set,
+            CacheValue::ZSet(_) => ValueKind::ZSet,
+        }
+    }
+
+    /// Whether a collection has no elements left (strings never count as empty)
+    pub fn is_empty(&self) -> bool {
+        match self {
+            CacheValue::String(_) => false,
+            CacheValue::Hash(hash) => hash.is_empty(),
+            CacheValue::List(list) => list.is_empty(),
+            CacheValue::Set(set) => set.is_empty(),
+            CacheValue::ZSet(zset) => zset.is_empty(),
+        }
+    }
+
+    /// Bytes charged against the cache size, computed from scratch
+    pub fn memory_size(&self) -> usize {
+        match self {
+            CacheValue::String(frame) => frame.len(),
+            CacheValue::Hash(hash) => hash.iter().map(|(f, v)| hash_field_size(f, v)).sum(),
+            CacheValue::List(list) => list.iter().map(|item| list_item_size(item)).sum(),
+            CacheValue::Set(set) => set.iter().map(|member| set_member_size(member)).sum(),
+            CacheValue::ZSet(zset) => zset.scores.keys().map(|member| zset_member_size(member)).sum(),
+        }
+    }
+
+    /// Apply `update`, returning its result and the change in [`memory_size`](Self::memory_size)
+    fn apply(&mut self, update: &Update) -> Result<(Applied, isize), &'static str> {
+        let mut delta = 0isize;
+        let applied = match (self, update) {
+            (CacheValue::Hash(hash), Update::HSet(pairs)) => {
+                let mut added = 0;
+                for (field, value) in pairs {
+                    match hash.insert(field.clone(), value.clone()) {
+                        Some(old) => delta += value.len() as isize - old.len() as isize,
+                        None => {
+                            delta += hash_field_size(field, value) as isize;
+                            added += 1;
+                        }
+                    }
+                }
+                Applied::Count(added)
+            }
+            (CacheValue::Hash(hash), Update::HDel(fields)) => {
+                let mut removed = 0;
+                for field in fields {
+                    if let Some(value) = hash.remove(field) {
+                        delta -= hash_field_size(field, &value) as isize;
+                        removed += 1;
+                    }
+                }
+                Applied::Count(removed)
+            }
+            (CacheValue::List(list), Update::LPush(items)) => {
+                for item in items {
+                    delta += list_item_size(item) as isize;
+                    list.push_front(item.clone());
+                }
+                Applied::Count(list.len())
+            }
+            (CacheValue::List(list), Update::RPop(count)) => {
+                let popped: Vec<_> = (0..*count).map_while(|_| list.pop_back()).collect();
+                delta -= popped.iter().map(|item| list_item_size(item)).sum::<usize>() as isize;
+                Applied::Popped(popped)
+            }
+            (CacheValue::Set(set), Update::SAdd(members)) => {
+                let mut added = 0;
+                for member in members {
+                    if set.insert(member.clone()) {
+                        delta += set_member_size(member) as isize;
+                        added += 1;
+                    }
+                }
+                Applied::Count(added)
+            }
+            (CacheValue::ZSet(zset), Update::ZAdd(members)) => {
+                let mut added = 0;
+                for (score, member) in members {
+                    if zset.insert(member.clone(), *score) {
+                        delta += zset_member_size(member) as isize;
+                        added += 1;
+                    }
+                }
+                Applied::Count(added)
+            }
+            _ => return Err(WRONG_TYPE),
+        };
+        Ok((applied, delta))
+    }
+}
+
+fn hash_field_size(field: &[u8], value: &[u8]) -> usize {
+    field.len() + value.len() + HASH_FIELD_OVERHEAD
+}
+
+fn list_item_size(item: &[u8]) -> usize {
+    item.len() + LIST_ITEM_OVERHEAD
+}
+
+fn set_member_size(member: &[u8]) -> usize {
+    member.len() + SET_MEMBER_OVERHEAD
+}
+
+fn zset_member_size(member: &[u8]) -> usize {
+    2 * member.len() + ZSET_MEMBER_OVERHEAD
+}
+
+/// An atomic change to a structured value, as logged
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Update {
+    /// Field/value pairs to set
+    HSet(FieldValues),
+    HDel(Vec<Vec<u8>>),
+    /// Items pushed to the head one by one, so the last ends up first
+    LPush(Vec<Vec<u8>>),
+    /// Pop up to this many items from the tail
+    RPop(usize),
+    SAdd(Vec<Vec<u8>>),
+    /// Score/member pairs to set
+    ZAdd(Vec<(f64, Vec<u8>)>),
+}
+
+impl Update {
+    /// Type of value the update applies to
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Update::HSet(_) | Update::HDel(_) => ValueKind::Hash,
+            Update::LPush(_) | Update::RPop(_) => ValueKind::List,
+            Update::SAdd(_) => ValueKind::Set,
+            Update::ZAdd(_) => ValueKind::ZSet,
+        }
+    }
+
+    /// Command name, for logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            Update::HSet(_) => "HSET",
+            Update::HDel(_) => "HDEL",
+            Update::LPush(_) => "LPUSH",
+            Update::RPop(_) => "RPOP",
+            Update::SAdd(_) => "SADD",
+            Update::ZAdd(_) => "ZADD",
+        }
+    }
+
+    /// Whether the value changed, given what applying the update returned
+    fn changed(&self, applied: &Applied) -> bool {
+        match (self, applied) {
+            (Update::HDel(_) | Update::SAdd(_), Applied::Count(n)) => *n > 0,
+            (_, Applied::Popped(items)) => !items.is_empty(),
+            // Overwrites change the value without adding anything
+            _ => true,
+        }
+    }
+
+    /// Most bytes the update can add to `value`, for eviction ahead of it
+    fn growth(&self, value: &CacheValue) -> usize {
+        match (self, value) {
+            (Update::HSet(pairs), CacheValue::Hash(hash)) => pairs
+                .iter()
+                .map(|(f, v)| match hash.get(f) {
+                    Some(old) => v.len().saturating_sub(old.len()),
+                    None => hash_field_size(f, v),
+                })
+                .sum(),
+            (Update::LPush(items), _) => items.iter().map(|item| list_item_size(item)).sum(),
+            (Update::SAdd(members), CacheValue::Set(set)) => {
+                members.iter().filter(|m| !set.contains(*m)).map(|m| set_member_size(m)).sum()
+            }
+            (Update::ZAdd(members), CacheValue::ZSet(zset)) => members
+                .iter()
+                .filter(|(_, m)| zset.score(m).is_none())
+                .map(|(_, m)| zset_member_size(m))
+                .sum(),
+            _ => 0,
+        }
+    }
+}
+
+/// Result of an [`Update`]
+#[derive(Clone, Debug, PartialEq)]
+enum Applied {
+    /// Elements added or removed, or the new length for `LPush`
+    Count(usize),
+    Popped(Vec<Vec<u8>>),
+}
+
+impl CacheEntry {
+    /// An empty collection of `kind`, created at `now_ms`
+    fn empty(kind: ValueKind, now_ms: i64) -> Self {
+        Self::new(CacheValue::empty(kind), 0, now_ms)
+    }
+
+    /// Apply `update` to a collection entry, keeping its size current
+    pub(super) fn apply(&mut self, update: &Update) -> Result<(), &'static str> {
+        self.apply_update(update).map(|_| ())
+    }
+
+    fn apply_update(&mut self, update: &Update) -> Result<Applied, &'static str> {
+        let (applied, delta) = self.value.apply(update)?;
+        self.original_size = self.original_size.saturating_add_signed(delta);
+        Ok(applied)
+    }
+}
+
+/// Lowest and highest positions of `start..=stop` in a list of `len`, counting
+/// negative indices from the tail; `None` when the range is empty
+fn list_span(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 { (len + start).max(0) } else { start };
+    let stop = if stop < 0 { len + stop } else { stop.min(len - 1) };
+    (start <= stop && start < len).then_some((start as usize, stop as usize))
+}
+
+impl UaoQtcamCache {
+    /// TYPE - Type of the value at `key`
+    pub fn key_type(&self, key: &str) -> Result<Option<ValueKind>, String> {
+        let shard = self.read_shard(key)?;
+        let now_ms = self.clock.now_ms();
+        Ok(shard.entries.get(key).filter(|entry| !entry.is_expired(now_ms)).map(|entry| entry.value.kind()))
+    }
+
+    /// HSET - Set hash fields; returns the number of fields that are new
+    pub fn hset(&self, key: &str, pairs: &[(&[u8], &[u8])]) -> Result<usize, String> {
+        let pairs = pairs.iter().map(|(f, v)| (f.to_vec(), v.to_vec())).collect();
+        self.update(key, Update::HSet(pairs)).map(Applied::count)
+    }
+
+    /// HGET - Value of a hash field
+    pub fn hget(&self, key: &str, field: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        self.read(key, ValueKind::Hash, |value| match value {
+            CacheValue::Hash(hash) => hash.get(field).cloned(),
+            _ => None,
+        })
+        .map(Option::flatten)
+    }
+
+    /// HDEL - Remove hash fields; returns the number removed
+    pub fn hdel(&self, key: &str, fields: &[&[u8]]) -> Result<usize, String> {
+        let fields = fields.iter().map(|f| f.to_vec()).collect();
+        self.update(key, Update::HDel(fields)).map(Applied::count)
+    }
+
+    /// HGETALL - Every field and value of a hash, sorted by field
+    pub fn hgetall(&self, key: &str) -> Result<FieldValues, String> {
+        let pairs = self.read(key, ValueKind::Hash, |value| match value {
+            CacheValue::Hash(hash) => {
+                let mut pairs: Vec<_> = hash.iter().map(|(f, v)| (f.clone(), v.clone())).collect();
+                pairs.sort_unstable();
+                pairs
+            }
+            _ => Vec::new(),
+        })?;
+        Ok(pairs.unwrap_or_default())
+    }
+
+    /// LPUSH - Push items to the head of a list; returns its new length
+    pub fn lpush(&self, key: &str, items: &[&[u8]]) -> Result<usize, String> {
+        let items = items.iter().map(|item| item.to_vec()).collect();
+        self.update(key, Update::LPush(items)).map(Applied::count)
+    }
+
+    /// RPOP - Pop up to `count` items from the tail of a list
+    pub fn rpop(&self, key: &str, count: usize) -> Result<Vec<Vec<u8>>, String> {
+        match self.update(key, Update::RPop(count))? {
+            Applied::Popped(items) => Ok(items),
+            Applied::Count(_) => Ok(Vec::new()),
+        }
+    }
+
+    /// LRANGE - Items `start..=stop` of a list; negative indices count from the tail
+    pub fn lrange(&self, key: &str, start: i64, stop: i64) -> Result<Vec<Vec<u8>>, String> {
+        let items = self.read(key, ValueKind::List, |value| match value {
+            CacheValue::List(list) => match list_span(list.len(), start, stop) {
+                Some((first, last)) => list.range(first..=last).cloned().collect(),
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        })?;
+        Ok(items.unwrap_or_default())
+    }
+
+    /// SADD - Add set members; returns the number that are new
+    pub fn sadd(&self, key: &str, members: &[&[u8]]) -> Result<usize, String> {
+        let members = members.iter().map(|m| m.to_vec()).collect();
+        self.update(key, Update::SAdd(members)).map(Applied::count)
+    }
+
+    /// SISMEMBER - Whether `member` is in a set
+    pub fn sismember(&self, key: &str, member: &[u8]) -> Result<bool, String> {
+        self.read(key, ValueKind::Set, |value| match value {
+            CacheValue::Set(set) => set.contains(member),
+            _ => false,
+        })
+        .map(|found| found.unwrap_or(false))
+    }
+
+    /// SMEMBERS - Every member of a set, sorted
+    pub fn smembers(&self, key: &str) -> Result<Vec<Vec<u8>>, String> {
+        let members = self.read(key, ValueKind::Set, |value| match value {
+            CacheValue::Set(set) => {
+                let mut members: Vec<_> = set.iter().cloned().collect();
+                members.sort_unstable();
+                members
+            }
+            _ => Vec::new(),
+        })?;
+        Ok(members.unwrap_or_default())
+    }
+
+    /// ZADD - Set member scores; returns the number of members that are new
+    pub fn zadd(&self, key: &str, members: &[(f64, &[u8])]) -> Result<usize, String> {
+        if members.iter().any(|(score, _)| score.is_nan()) {
+            return Err(NOT_A_FLOAT.to_string());
+        }
+        let members = members.iter().map(|(score, m)| (*score, m.to_vec())).collect();
+        self.update(key, Update::ZAdd(members)).map(Applied::count)
+    }
+
+    /// ZRANGEBYSCORE - Members scored between `min` and `max`, by score then member
+    pub fn zrange_by_score(&self, key: &str, min: Bound<f64>, max: Bound<f64>) -> Result<Vec<(Vec<u8>, f64)>, String> {
+        let members = self.read(key, ValueKind::ZSet, |value| match value {
+            CacheValue::ZSet(zset) => zset.range_by_score(min, max),
+            _ => Vec::new(),
+        })?;
+        Ok(members.unwrap_or_default())
+    }
+
+    /// Run `read` on the value at `key` if it is of `kind`; `None` if there is no such key
+    fn read<T>(&self, key: &str, kind: ValueKind, read: impl FnOnce(&CacheValue) -> T) -> Result<Option<T>, String> {
+        let (_, mut shard) = self.write_shard(key)?;
+        let now_ms = self.clock.now_ms();
+        self.expire_locked(&mut shard, key, now_ms)?;
+        let tick = shard.next_tick();
+        let result = match shard.entries.get_mut(key) {
+            Some(entry) if entry.value.kind() != kind => return Err(WRONG_TYPE.to_string()),
+            Some(entry) => {
+                eviction::touch(entry, tick, now_ms);
+                Some(read(&entry.value))
+            }
+            None => None,
+        };
+
+        if result.is_some() {
+            shard.counters.hits += 1;
+        } else {
+            shard.counters.misses += 1;
+        }
+        Ok(result)
+    }
+
+    /// Apply `update` to the collection at `key`, creating it if needed
+    fn update(&self, key: &str, update: Update) -> Result<Applied, String> {
+        let (index, mut shard) = self.write_shard(key)?;
+        let now_ms = self.clock.now_ms();
+        self.expire_locked(&mut shard, key, now_ms)?;
+
+        let growth = match shard.entries.get(key) {
+            Some(entry) if entry.value.kind() != update.kind() => return Err(WRONG_TYPE.to_string()),
+            Some(entry) => update.growth(&entry.value),
+            // A new key is charged its slot too, unless the update leaves nothing to store
+            None => match update.growth(&CacheValue::empty(update.kind())) {
+                0 => 0,
+                growth => growth + key.len() + ENTRY_OVERHEAD,
+            },
+        };
+        self.make_room(index, &mut shard, growth, key, now_ms)?;
+
+        let tick = shard.next_tick();
+        let applied = match shard.entries.get_mut(key) {
+            Some(entry) => {
+                self.log_reserved(growth, || LogRecord::Update { key: key.to_string(), update: update.clone() })?;
+                let before = entry.size();
+                let applied = entry.apply_update(&update)?;
+                eviction::touch(entry, tick, now_ms);
+                self.settle(growth, entry.size(), before);
+                let emptied = entry.value.is_empty();
+
+                // The last element takes its key with it
+                if emptied {
+                    self.remove_locked(&mut shard, key);
+                    self.notify(KeyEvent::Del, key);
+                } else if update.changed(&applied) {
+                    self.notify(KeyEvent::Set, key);
+                }
+                applied
+            }
+            None => {
+                let mut entry = CacheEntry::empty(update.kind(), now_ms);
+                entry.last_access_tick = tick;
+                let applied = entry.apply_update(&update)?;
+                let mut charged = 0;
+                if !entry.value.is_empty() {
+                    self.log_reserved(growth, || LogRecord::Set { key: key.to_string(), entry: entry.clone() })?;
+                    charged = eviction::charge(key, &entry);
+                    shard.entries.insert(key.to_string(), entry);
+                    self.notify(KeyEvent::Set, key);
+                }
+                self.settle(growth, charged, 0);
+                applied
+            }
+        };
+
+        debug!("UAO-QTCAM {} {}", update.name(), key);
+        Ok(applied)
+    }
+}
+
+impl Applied {
+    fn count(self) -> usize {
+        match self {
+            Applied::Count(n) => n,
+            Applied::Popped(items) => items.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_track_updates() {
+        let mut entry = CacheEntry::empty(ValueKind::ZSet, 0);
+        entry.apply(&Update::ZAdd(vec![(2.0, b"b".to_vec()), (-0.0, b"a".to_vec()), (2.0, b"c".to_vec())])).unwrap();
+        entry.apply(&Update::ZAdd(vec![(1.0, b"c".to_vec())])).unwrap();
+        assert_eq!(entry.size(), entry.value.memory_size());
+        let CacheValue::ZSet(zset) = &entry.value else { unreachable!() };
+        let members: Vec<_> = zset.range_by_score(Bound::Included(0.0), Bound::Excluded(2.0));
+        assert_eq!(members, vec![(b"a".to_vec(), 0.0), (b"c".to_vec(), 1.0)]);
+        assert_eq!(zset.range_by_score(Bound::Excluded(1.0), Bound::Unbounded), vec![(b"b".to_vec(), 2.0)]);
+
+        let mut entry = CacheEntry::empty(ValueKind::Hash, 0);
+        entry.apply(&Update::HSet(vec![(b"f".to_vec(), b"long value".to_vec())])).unwrap();
+        entry.apply(&Update::HSet(vec![(b"f".to_vec(), b"v".to_vec())])).unwrap();
+        assert_eq!(entry.size(), entry.value.memory_size());
+        assert_eq!(entry.apply(&Update::SAdd(vec![b"m".to_vec()])), Err(WRONG_TYPE));
+
+        assert_eq!(list_span(5, 0, -1), Some((0, 4)));
+        assert_eq!(list_span(5, -2, 100), Some((3, 4)));
+        assert_eq!(list_span(5, 3, 1), None);
+        assert_eq!(list_span(0, 0, -1), None);
+        assert_eq!(list_span(5, -100, 0), Some((0, 0)));
+    }
+}
@@ -482,70 +482,66 @@ pub struct ControlPlaneLookupResult {
 }
 
 impl SymmetrixUaoQtcamOptimizer {
+    /// Lookup for control plane
+    pub async fn control_lookup(&self, key: &str) -> Result<Option<ControlPlaneLookupResult>, String> {
+        let engine = self.tcam_engine.read().await;
+        let start = std::time::Instant::now();
+
+        match engine.lookup(key).await {
+            Ok(Some(result)) => Ok(Some(ControlPlaneLookupResult {
+                key: key.to_string(),
+                next_hop: result.next_hop,
+                latency_ns: start.elapsed().as_nanos() as u64,
+                phase: result.phase,
+            })),
+            Ok(None) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Route insert for control plane
+    pub async fn control_insert_route(&self, key: &str, value: &str, priority: u32) -> Result<(), String> {
+        let engine = self.tcam_engine.read().await;
+        let route = Route {
+            prefix: Prefix {
+                addr: key.parse().unwrap_or(0),
+                len: 24,
+            },
+            next_hop: value.to_string(),
+            metric: priority,
+        };
+        engine.insert(route).await.map_err(|e| e.to_string())
+    }
+
+    /// Route delete for control plane
+    pub async fn control_delete_route(&self, key: &str) -> Result<(), String> {
+        let engine = self.tcam_engine.read().await;
+        let prefix = Prefix {
+            addr: key.parse().unwrap_or(0),
+            len: 24,
+        };
+        engine.delete(prefix).await.map_err(|e| e.to_string())
+    }
+
     /// Synchronous lookup for control plane (convenience wrapper)
     pub fn sync_lookup(&self, key: &str) -> Result<Option<ControlPlaneLookupResult>, String> {
         // Use tokio runtime for async operation
         let rt = tokio::runtime::Handle::try_current()
             .map_err(|_| "No tokio runtime".to_string())?;
-
-        let tcam = self.tcam_engine.clone();
-        let key_owned = key.to_string();
-
-        rt.block_on(async {
-            let engine = tcam.read().await;
-            let start = std::time::Instant::now();
-
-            match engine.lookup(&key_owned).await {
-                Ok(Some(result)) => Ok(Some(ControlPlaneLookupResult {
-                    key: key_owned,
-                    next_hop: result.next_hop,
-                    latency_ns: start.elapsed().as_nanos() as u64,
-                    phase: result.phase,
-                })),
-                Ok(None) => Ok(None),
-                Err(e) => Err(e.to_string()),
-            }
-        })
+        rt.block_on(self.control_lookup(key))
     }
 
     /// Synchronous route insert for control plane (convenience wrapper)
     pub fn sync_insert_route(&mut self, key: &str, value: &str, priority: u32) -> Result<(), String> {
         let rt = tokio::runtime::Handle::try_current()
             .map_err(|_| "No tokio runtime".to_string())?;
-
-        let tcam = self.tcam_engine.clone();
-        let key_owned = key.to_string();
-        let value_owned = value.to_string();
-
-        rt.block_on(async {
-            let engine = tcam.read().await;
-            let route = Route {
-                prefix: Prefix {
-                    addr: key_owned.parse().unwrap_or(0),
-                    len: 24,
-                },
-                next_hop: value_owned,
-                metric: priority,
-            };
-            engine.insert(route).await.map_err(|e| e.to_string())
-        })
+        rt.block_on(self.control_insert_route(key, value, priority))
     }
 
     /// Synchronous route delete for control plane (convenience wrapper)
     pub fn sync_delete_route(&mut self, key: &str) -> Result<(), String> {
         let rt = tokio::runtime::Handle::try_current()
             .map_err(|_| "No tokio runtime".to_string())?;
-
-        let tcam = self.tcam_engine.clone();
-        let key_owned = key.to_string();
-
-        rt.block_on(async {
-            let engine = tcam.read().await;
-            let prefix = Prefix {
-                addr: key_owned.parse().unwrap_or(0),
-                len: 24,
-            };
-            engine.delete(prefix).await.map_err(|e| e.to_string())
-        })
+        rt.block_on(self.control_delete_route(key))
     }
 }
\ No newline at end of file
@@ -0,0 +1,247 @@
+//! UAO-QTCAM cache memory accounting and eviction policies
+//!
+//! Whatever the workload and policy, the bytes charged must add up to the
+//! per-key charges and never exceed the limit; each policy must pick the
+//! victims it promises, and `noeviction` must refuse writes instead.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use proptest::prelude::*;
+use symmetrix_core::grpc::ManualClock;
+use symmetrix_core::uao_qtcam_cache::eviction::ENTRY_OVERHEAD;
+use symmetrix_core::uao_qtcam_cache::{KeyEvent, DEFAULT_SHARDS, OUT_OF_MEMORY};
+use symmetrix_core::{EvictionPolicy, UaoQtcamCache};
+
+const LIMIT: usize = 8 * 1024;
+
+/// A cache with one shard, so policies rank the whole keyspace
+fn cache(limit: usize, policy: EvictionPolicy) -> UaoQtcamCache {
+    UaoQtcamCache::new(limit, 1.0)
+        .with_shards(1)
+        .with_clock(Arc::new(ManualClock::new(1_700_000_000_000)))
+        .with_compression_threshold(usize::MAX)
+        .with_eviction_policy(policy)
+}
+
+/// Limit that holds exactly `keys` string keys of `key_len` bytes with `value_len`-byte values
+fn room_for(keys: usize, key_len: usize, value_len: usize) -> usize {
+    let cache = cache(usize::MAX, EvictionPolicy::NoEviction);
+    cache.set(&"k".repeat(key_len), &vec![b'v'; value_len], None).unwrap();
+    keys * cache.stats().unwrap().used_memory
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Set(u8, usize, bool),
+    Get(u8),
+    Delete(u8),
+    Incr(u8),
+    Expire(u8),
+    Persist(u8),
+    HSet(u8, u8, usize),
+    LPush(u8, usize),
+    RPop(u8),
+    SAdd(u8, u16),
+    ZAdd(u8, u16, i32),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..24u8, 0..600usize, any::<bool>()).prop_map(|(k, len, ttl)| Op::Set(k, len, ttl)),
+        (0..24u8).prop_map(Op::Get),
+        (0..24u8).prop_map(Op::Delete),
+        (0..8u8).prop_map(Op::Incr),
+        (0..24u8).prop_map(Op::Expire),
+        (0..24u8).prop_map(Op::Persist),
+        (0..8u8, 0..16u8, 0..200usize).prop_map(|(k, f, len)| Op::HSet(k, f, len)),
+        (0..8u8, 0..200usize).prop_map(|(k, len)| Op::LPush(k, len)),
+        (0..8u8).prop_map(Op::RPop),
+        (0..8u8, any::<u16>()).prop_map(|(k, m)| Op::SAdd(k, m)),
+        (0..8u8, any::<u16>(), any::<i32>()).prop_map(|(k, m, s)| Op::ZAdd(k, m, s)),
+    ]
+}
+
+/// Run `op`; keys are named by type so only running out of memory can fail
+fn run(cache: &UaoQtcamCache, op: &Op) -> Result<(), String> {
+    match op {
+        Op::Set(k, len, ttl) => cache.set(&format!("s:{}", k), &vec![b'x'; *len], ttl.then_some(3600)),
+        Op::Get(k) => cache.get(&format!("s:{}", k)).map(drop),
+        Op::Delete(k) => cache.delete(&format!("s:{}", k)).map(drop),
+        Op::Incr(k) => cache.incr(&format!("n:{}", k)).map(drop),
+        Op::Expire(k) => cache.expire(&format!("s:{}", k), Duration::from_secs(60)).map(drop),
+        Op::Persist(k) => cache.persist(&format!("s:{}", k)).map(drop),
+        Op::HSet(k, f, len) => cache.hset(&format!("h:{}", k), &[(&[*f][..], &vec![b'v'; *len][..])]).map(drop),
+        Op::LPush(k, len) => cache.lpush(&format!("l:{}", k), &[&vec![b'i'; *len][..]]).map(drop),
+        Op::RPop(k) => cache.rpop(&format!("l:{}", k), 2).map(drop),
+        Op::SAdd(k, m) => cache.sadd(&format!("t:{}", k), &[&m.to_be_bytes()[..]]).map(drop),
+        Op::ZAdd(k, m, s) => cache.zadd(&format!("z:{}", k), &[(*s as f64, &m.to_be_bytes()[..])]).map(drop),
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_memory_never_exceeds_limit(
+        policy in prop::sample::select(EvictionPolicy::ALL.to_vec()),
+        shards in prop::sample::select(vec![1, 4, DEFAULT_SHARDS]),
+        ops in prop::collection::vec(op(), 1..300),
+    ) {
+        let cache = cache(LIMIT, policy).with_shards(shards);
+        for op in &ops {
+            if let Err(e) = run(&cache, op) {
+                prop_assert_eq!(e.as_str(), OUT_OF_MEMORY, "{:?} under {}", op, policy);
+            }
+            let stats = cache.stats().unwrap();
+            prop_assert!(stats.used_memory <= LIMIT, "{} > {} after {:?} under {}", stats.used_memory, LIMIT, op, policy);
+            let charged: usize = cache
+                .keys("*")
+                .unwrap()
+                .iter()
+                .map(|key| cache.memory_usage(key).unwrap().unwrap())
+                .sum();
+            prop_assert_eq!(stats.used_memory, charged, "after {:?}", op);
+        }
+    }
+}
+
+#[test]
+fn test_charge_counts_key_value_and_overheads() {
+    let cache = cache(LIMIT, EvictionPolicy::Lru);
+    cache.set("plain", b"value", None).unwrap();
+    let plain = cache.memory_usage("plain").unwrap().unwrap();
+    assert!(plain >= "plain".len() + "value".len() + ENTRY_OVERHEAD);
+
+    // A TTL costs its place in the deadline index, and PERSIST gives it back
+    cache.expire("plain", Duration::from_secs(10)).unwrap();
+    let expiring = cache.memory_usage("plain").unwrap().unwrap();
+    assert!(expiring > plain);
+    assert_eq!(cache.stats().unwrap().used_memory, expiring);
+    cache.persist("plain").unwrap();
+    assert_eq!(cache.memory_usage("plain").unwrap(), Some(plain));
+
+    cache.sadd("tags", &[b"a", b"b"]).unwrap();
+    let stats = cache.stats().unwrap();
+    assert_eq!(stats.used_memory, plain + cache.memory_usage("tags").unwrap().unwrap());
+    assert_eq!((stats.max_memory, stats.eviction_policy), (LIMIT, EvictionPolicy::Lru));
+    cache.clear().unwrap();
+    assert_eq!(cache.stats().unwrap().used_memory, 0);
+    assert_eq!(cache.memory_usage("plain").unwrap(), None);
+}
+
+#[test]
+fn test_noeviction_refuses_writes_that_do_not_fit() {
+    let cache = cache(room_for(4, 2, 100), EvictionPolicy::NoEviction);
+    for i in 0..4 {
+        cache.set(&format!("k{}", i), &[b'v'; 100], None).unwrap();
+    }
+    assert_eq!(cache.set("k4", &[b'v'; 100], None).unwrap_err(), OUT_OF_MEMORY);
+    assert_eq!(cache.lpush("list", &[b"item"]).unwrap_err(), OUT_OF_MEMORY);
+    assert_eq!(cache.set("k0", &[b'v'; 101], None).unwrap_err(), OUT_OF_MEMORY);
+
+    // Reads, deletes and writes that do not grow still work
+    assert_eq!(cache.get("k0").unwrap().unwrap().len(), 100);
+    cache.set("k0", &[b'w'; 50], None).unwrap();
+    assert!(cache.delete("k1").unwrap());
+    cache.set("k4", &[b'v'; 100], None).unwrap();
+
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.evictions, stats.rejected_writes, stats.entry_count), (0, 3, 4));
+    assert!(stats.evictions_by_policy.is_empty());
+}
+
+#[test]
+fn test_lru_evicts_least_recently_used() {
+    let cache = cache(room_for(4, 2, 100), EvictionPolicy::Lru);
+    for i in 0..4 {
+        cache.set(&format!("k{}", i), &[b'v'; 100], None).unwrap();
+    }
+    cache.get("k0").unwrap();
+    // A read of the wrong type fails without counting as an access
+    cache.hget("k1", b"f").unwrap_err();
+
+    cache.set("k4", &[b'v'; 100], None).unwrap();
+    cache.set("k5", &[b'v'; 100], None).unwrap();
+    assert_eq!(cache.keys("*").unwrap(), ["k0", "k3", "k4", "k5"]);
+}
+
+#[test]
+fn test_sampled_lru_mostly_evicts_cold_keys() {
+    // Far more keys than samples, so victims are approximate
+    let cache = cache(room_for(1000, 8, 100), EvictionPolicy::Lru);
+    for i in 0..1000 {
+        cache.set(&format!("key:{:04}", i), &[b'v'; 100], None).unwrap();
+    }
+    for i in 500..1000 {
+        cache.get(&format!("key:{:04}", i)).unwrap();
+    }
+    for i in 0..100 {
+        cache.set(&format!("new:{:04}", i), &[b'v'; 100], None).unwrap();
+    }
+    assert_eq!(cache.stats().unwrap().evictions, 100);
+    let warm_evicted = 500 - cache.keys("key:0[5-9]*").unwrap().len();
+    assert!(warm_evicted < 20, "{} recently used keys evicted", warm_evicted);
+}
+
+#[test]
+fn test_lfu_keeps_hot_keys_that_lru_would_drop() {
+    let run = |policy| {
+        let cache = cache(room_for(8, 6, 100), policy);
+        for i in 0..4 {
+            cache.set(&format!("hot:{}", i), &[b'v'; 100], None).unwrap();
+            for _ in 0..200 {
+                cache.get(&format!("hot:{}", i)).unwrap();
+            }
+        }
+        // A scan of cold keys, each read once
+        for i in 0..100 {
+            let key = format!("cold{:02}", i);
+            cache.set(&key, &[b'v'; 100], None).unwrap();
+            cache.get(&key).unwrap();
+        }
+        cache.keys("hot:*").unwrap().len()
+    };
+    assert_eq!(run(EvictionPolicy::Lfu), 4);
+    assert_eq!(run(EvictionPolicy::Lru), 0);
+}
+
+#[test]
+fn test_volatile_ttl_evicts_soonest_deadlines_only() {
+    let cache = cache(room_for(4, 2, 100) + 4 * 64, EvictionPolicy::VolatileTtl);
+    cache.set("p0", &[b'v'; 100], None).unwrap();
+    cache.set("t1", &[b'v'; 100], Some(300)).unwrap();
+    cache.set("t2", &[b'v'; 100], Some(100)).unwrap();
+    cache.set("t3", &[b'v'; 100], Some(200)).unwrap();
+
+    cache.set("p4", &[b'v'; 100], None).unwrap();
+    assert_eq!(cache.keys("*").unwrap(), ["p0", "p4", "t1", "t3"]);
+    cache.set("p5", &[b'v'; 100], None).unwrap();
+    cache.set("p6", &[b'v'; 100], None).unwrap();
+    assert_eq!(cache.keys("*").unwrap(), ["p0", "p4", "p5", "p6"]);
+
+    // Keys without a TTL are never evicted
+    assert_eq!(cache.set("p7", &[b'v'; 100], None).unwrap_err(), OUT_OF_MEMORY);
+    assert_eq!(cache.stats().unwrap().evictions_by_policy[&EvictionPolicy::VolatileTtl], 3);
+}
+
+#[test]
+fn test_random_eviction_and_policy_switches_are_counted() {
+    let cache = cache(room_for(4, 3, 100), EvictionPolicy::Random).with_keyspace_events(&[KeyEvent::Evicted]);
+    let mut evicted = cache.psubscribe(&["__keyevent@0__:evicted"]).unwrap();
+    for i in 0..10 {
+        cache.set(&format!("r:{}", i), &[b'v'; 100], None).unwrap();
+    }
+    cache.set_eviction_policy(EvictionPolicy::Lfu);
+    for i in 0..3 {
+        cache.set(&format!("f:{}", i), &[b'v'; 100], None).unwrap();
+    }
+
+    let stats = cache.stats().unwrap();
+    assert_eq!(stats.evictions_by_policy[&EvictionPolicy::Random], 6);
+    assert_eq!(stats.evictions_by_policy[&EvictionPolicy::Lfu], 3);
+    assert_eq!((stats.evictions, stats.entry_count, stats.eviction_policy), (9, 4, EvictionPolicy::Lfu));
+    assert_eq!(std::iter::from_fn(|| evicted.try_recv()).count(), 9);
+
+    // A value larger than the whole cache is refused after emptying it
+    assert_eq!(cache.set("huge", &[b'v'; LIMIT], None).unwrap_err(), OUT_OF_MEMORY);
+    assert_eq!(cache.stats().unwrap().used_memory, 0);
+}
@@ -0,0 +1,214 @@
+//! UAO-QTCAM cache keyspace: SCAN, pub/sub and keyspace notifications
+//!
+//! A SCAN must return every key that is present from its first call to its
+//! last at least once, whatever is written in between; notifications must
+//! reach subscribers for exactly the events that are enabled.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_core::grpc::ManualClock;
+use symmetrix_core::uao_qtcam_cache::{KeyEvent, Message, Subscription};
+use symmetrix_core::{ExpiryConfig, UaoQtcamCache};
+
+fn cache() -> UaoQtcamCache {
+    UaoQtcamCache::new(64 * 1024 * 1024, 250.0)
+}
+
+/// Run a whole SCAN, calling `between` after every page
+fn full_scan(cache: &UaoQtcamCache, count: usize, mut between: impl FnMut()) -> Vec<String> {
+    let (mut cursor, mut seen) = (0, Vec::new());
+    loop {
+        let (next, page) = cache.scan(cursor, "*", count).unwrap();
+        seen.extend(page);
+        if next == 0 {
+            return seen;
+        }
+        assert!(next > cursor, "cursor went backwards");
+        cursor = next;
+        between();
+    }
+}
+
+fn drain(subscription: &mut Subscription) -> Vec<Message> {
+    std::iter::from_fn(|| subscription.try_recv()).collect()
+}
+
+#[test]
+fn test_scan_returns_stable_keys_under_churn() {
+    for seed in 0..16 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let cache = cache();
+        let stable: Vec<String> = (0..300).map(|i| format!("stable:{}", i)).collect();
+        for key in &stable {
+            cache.set(key, b"s", None).unwrap();
+        }
+        for i in 0..300 {
+            cache.set(&format!("churn:{}", i), b"c", None).unwrap();
+        }
+
+        let count = rng.gen_range(1..40);
+        let seen: HashSet<String> = full_scan(&cache, count, || {
+            for _ in 0..rng.gen_range(0..30) {
+                let key = format!("churn:{}", rng.gen_range(0..600));
+                if rng.gen_bool(0.5) {
+                    cache.set(&key, b"c", None).unwrap();
+                } else {
+                    cache.delete(&key).unwrap();
+                }
+            }
+        })
+        .into_iter()
+        .collect();
+        for key in &stable {
+            assert!(seen.contains(key), "seed {}: {} missed", seed, key);
+        }
+    }
+}
+
+#[test]
+fn test_scan_with_concurrent_writers() {
+    let cache = Arc::new(cache());
+    for i in 0..2000 {
+        cache.set(&format!("stable:{}", i), b"s", None).unwrap();
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    let writers: Vec<_> = (0..4)
+        .map(|t| {
+            let (cache, stop) = (cache.clone(), stop.clone());
+            thread::spawn(move || {
+                let mut rng = StdRng::seed_from_u64(t);
+                while !stop.load(Ordering::Relaxed) {
+                    let key = format!("churn:{}", rng.gen_range(0..5000));
+                    if rng.gen_bool(0.6) {
+                        cache.set(&key, b"c", None).unwrap();
+                    } else {
+                        cache.delete(&key).unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for _ in 0..5 {
+        let seen: HashSet<String> = full_scan(&cache, 17, thread::yield_now).into_iter().collect();
+        assert!((0..2000).all(|i| seen.contains(&format!("stable:{}", i))));
+    }
+    stop.store(true, Ordering::Relaxed);
+    for writer in writers {
+        writer.join().unwrap();
+    }
+}
+
+#[test]
+fn test_scan_pages_match_keys() {
+    let cache = cache();
+    for i in 0..50 {
+        cache.set(&format!("user:{}", i), b"u", None).unwrap();
+        cache.set(&format!("job:{}", i), b"j", None).unwrap();
+    }
+    let mut users = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let (next, page) = cache.scan(cursor, "user:*", 7).unwrap();
+        users.extend(page);
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+    }
+    users.sort();
+    assert_eq!(users, cache.keys("user:*").unwrap());
+    assert_eq!(cache.scan(0, "*", 1000).unwrap().1.len(), 100);
+
+    // Each call examines `count` keys, so a full scan takes about len / count calls
+    let (mut cursor, mut calls) = (0, 0);
+    loop {
+        let (next, page) = cache.scan(cursor, "*", 10).unwrap();
+        assert!(page.len() <= 10);
+        calls += 1;
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+    }
+    assert!((10..=11).contains(&calls), "{} calls", calls);
+}
+
+#[tokio::test]
+async fn test_publish_reaches_channel_and_pattern_subscribers() {
+    let cache = cache();
+    let mut news = cache.subscribe(&["news", "alerts"]).unwrap();
+    let mut all = cache.psubscribe(&["ne?s"]).unwrap();
+
+    assert_eq!(cache.publish("news", b"hello").unwrap(), 2);
+    assert_eq!(cache.publish("alerts", b"fire").unwrap(), 1);
+    assert_eq!(cache.publish("sports", b"goal").unwrap(), 0);
+
+    let message = news.recv().await.unwrap();
+    assert_eq!((message.channel.as_str(), message.payload.as_slice()), ("news", &b"hello"[..]));
+    assert_eq!(news.recv().await.unwrap().payload, b"fire");
+    let message = all.recv().await.unwrap();
+    assert_eq!(message.pattern.as_deref(), Some("ne?s"));
+    assert!(all.try_recv().is_none());
+
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.pubsub_channels, stats.pubsub_patterns), (2, 1));
+    drop(news);
+    assert_eq!(cache.publish("news", b"again").unwrap(), 1);
+}
+
+#[test]
+fn test_slow_subscribers_lose_messages_not_publishers() {
+    let cache = cache().with_subscriber_queue(4);
+    let mut slow = cache.subscribe(&["ticks"]).unwrap();
+    for i in 0..10u8 {
+        cache.publish("ticks", &[i]).unwrap();
+    }
+    let payloads: Vec<_> = drain(&mut slow).into_iter().map(|m| m.payload[0]).collect();
+    assert_eq!(payloads, vec![0, 1, 2, 3]);
+    assert_eq!(cache.stats().unwrap().dropped_messages, 6);
+}
+
+#[test]
+fn test_keyspace_notifications() {
+    let clock = Arc::new(ManualClock::new(1_700_000_000_000));
+    let cache = UaoQtcamCache::new(4096, 1.0)
+        .with_clock(clock.clone())
+        .with_compression_threshold(usize::MAX)
+        .with_keyspace_events(&KeyEvent::ALL);
+    let mut session = cache.subscribe(&["__keyspace@0__:session"]).unwrap();
+    let mut events = cache.psubscribe(&["__keyevent@0__:*"]).unwrap();
+
+    cache.set("session", b"s", Some(5)).unwrap();
+    cache.sadd("tags", &[b"a"]).unwrap();
+    cache.sadd("tags", &[b"a"]).unwrap();
+    cache.delete("tags").unwrap();
+    clock.advance(Duration::from_secs(6));
+    cache.expire_cycle(&ExpiryConfig::default()).unwrap();
+    for i in 0..4 {
+        cache.set(&format!("big:{}", i), &[b'x'; 1500], None).unwrap();
+    }
+
+    let names: Vec<_> = drain(&mut session).into_iter().map(|m| String::from_utf8(m.payload).unwrap()).collect();
+    assert_eq!(names, vec!["set", "expired"]);
+    let events: Vec<_> = drain(&mut events)
+        .into_iter()
+        .map(|m| format!("{} {}", &m.channel["__keyevent@0__:".len()..], String::from_utf8(m.payload).unwrap()))
+        .collect();
+    assert_eq!(&events[..6], ["set session", "set tags", "del tags", "expired session", "set big:0", "set big:1"]);
+    assert!(events.iter().any(|e| e.starts_with("evicted big:")));
+
+    // Only enabled events are published
+    let quiet = UaoQtcamCache::new(1024 * 1024, 250.0).with_keyspace_events(&[KeyEvent::Del]);
+    let mut all = quiet.psubscribe(&["__key*"]).unwrap();
+    quiet.set("k", b"v", None).unwrap();
+    quiet.delete("k").unwrap();
+    let channels: Vec<_> = drain(&mut all).into_iter().map(|m| m.channel).collect();
+    assert_eq!(channels, vec!["__keyspace@0__:k", "__keyevent@0__:del"]);
+}
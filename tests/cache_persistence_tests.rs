@@ -0,0 +1,305 @@
+//! UAO-QTCAM cache persistence: snapshots, the append-only log and recovery
+//!
+//! Crashes are simulated by dropping the cache and cutting the log at every
+//! byte of its tail; recovery must always land on a prefix of the writes.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use symmetrix_core::grpc::ManualClock;
+use symmetrix_core::uao_qtcam_cache::eviction::EvictionPolicy;
+use symmetrix_core::uao_qtcam_cache::persistence::{encode_record, LogRecord, PersistenceError, LOG_FILE, SNAPSHOT_FILE};
+use symmetrix_core::{FsyncPolicy, KeyTtl, PersistenceConfig, UaoQtcamCache};
+use tempfile::TempDir;
+
+const START_MS: i64 = 1_700_000_000_000;
+
+fn open(dir: &Path, clock: &Arc<ManualClock>, config: impl FnOnce(PersistenceConfig) -> PersistenceConfig) -> UaoQtcamCache {
+    UaoQtcamCache::new(64 * 1024 * 1024, 250.0)
+        .with_clock(clock.clone())
+        .with_persistence(config(PersistenceConfig::new(dir)))
+        .unwrap()
+}
+
+fn open_default(dir: &Path, clock: &Arc<ManualClock>) -> UaoQtcamCache {
+    open(dir, clock, |c| PersistenceConfig { fsync: FsyncPolicy::Always, ..c })
+}
+
+fn value(cache: &UaoQtcamCache, key: &str) -> Option<String> {
+    cache.get(key).unwrap().map(|v| String::from_utf8(v).unwrap())
+}
+
+#[test]
+fn test_log_replays_every_write() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    {
+        let cache = open_default(dir.path(), &clock);
+        cache.set("plain", b"v1", None).unwrap();
+        cache.set("plain", b"v2", None).unwrap();
+        cache.set("gone", b"x", None).unwrap();
+        cache.delete("gone").unwrap();
+        cache.set("text", &b"gfef layer index ".repeat(500), None).unwrap();
+        for _ in 0..3 {
+            cache.incr("hits").unwrap();
+        }
+        cache.set("session", b"s", Some(60)).unwrap();
+        cache.set("pinned", b"p", Some(60)).unwrap();
+        cache.persist("pinned").unwrap();
+        cache.expire("hits", Duration::from_secs(120)).unwrap();
+        assert!(cache.stats().unwrap().log_bytes > 0);
+    }
+
+    clock.advance(Duration::from_secs(10));
+    let cache = open_default(dir.path(), &clock);
+    assert_eq!(value(&cache, "plain").as_deref(), Some("v2"));
+    assert_eq!(value(&cache, "gone"), None);
+    assert_eq!(cache.get("text").unwrap().unwrap(), b"gfef layer index ".repeat(500));
+    assert_eq!(value(&cache, "hits").as_deref(), Some("3"));
+    // Deadlines are absolute, so downtime counts against the TTL
+    assert_eq!(cache.ttl("session").unwrap(), KeyTtl::Expires(Duration::from_secs(50)));
+    assert_eq!(cache.ttl("hits").unwrap(), KeyTtl::Expires(Duration::from_secs(110)));
+    assert_eq!(cache.ttl("pinned").unwrap(), KeyTtl::Persistent);
+
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.entry_count, stats.expiring_keys), (5, 2));
+    assert_eq!(stats.last_snapshot_at, Some(START_MS + 10_000));
+}
+
+#[test]
+fn test_keys_that_expired_while_down_are_dropped() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    {
+        let cache = open_default(dir.path(), &clock);
+        cache.set("short", b"s", Some(5)).unwrap();
+        cache.set("long", b"l", Some(500)).unwrap();
+        cache.set("forever", b"f", None).unwrap();
+        cache.save_snapshot().unwrap();
+        cache.set("after_snapshot", b"a", Some(5)).unwrap();
+    }
+
+    clock.advance(Duration::from_secs(60));
+    let cache = open_default(dir.path(), &clock);
+    assert_eq!(cache.keys("*").unwrap(), vec!["forever", "long"]);
+    assert_eq!(cache.ttl("long").unwrap(), KeyTtl::Expires(Duration::from_secs(440)));
+    assert_eq!(cache.stats().unwrap().expiring_keys, 1);
+}
+
+#[test]
+fn test_truncated_log_recovers_a_consistent_prefix() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    let writes = 8;
+    {
+        let cache = open_default(dir.path(), &clock);
+        for i in 0..writes {
+            cache.set(&format!("k{}", i), format!("value-{}", i).repeat(i + 1).as_bytes(), None).unwrap();
+        }
+    }
+    let log = fs::read(dir.path().join(LOG_FILE)).unwrap();
+    let snapshot = fs::read(dir.path().join(SNAPSHOT_FILE)).unwrap();
+
+    let mut last_recovered = writes;
+    for cut in 1..log.len() - 16 {
+        // Every recovery compacts, so restore the pre-crash files first
+        fs::write(dir.path().join(SNAPSHOT_FILE), &snapshot).unwrap();
+        fs::write(dir.path().join(LOG_FILE), &log[..log.len() - cut]).unwrap();
+
+        let cache = open_default(dir.path(), &clock);
+        let keys = cache.keys("*").unwrap();
+        let recovered = keys.len();
+        // Exactly the first `recovered` writes, each one whole
+        for i in 0..writes {
+            let expected = (i < recovered).then(|| format!("value-{}", i).repeat(i + 1));
+            assert_eq!(value(&cache, &format!("k{}", i)), expected, "cut {} bytes", cut);
+        }
+        assert!(recovered < writes && recovered <= last_recovered, "cut {} bytes", cut);
+        last_recovered = recovered;
+
+        // The recovered cache keeps logging
+        cache.set("after", b"crash", None).unwrap();
+        drop(cache);
+        let reopened = open_default(dir.path(), &clock);
+        assert_eq!(reopened.keys("*").unwrap().len(), recovered + 1);
+    }
+    assert_eq!(last_recovered, 0);
+}
+
+#[test]
+fn test_corrupt_record_ends_replay() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    {
+        let cache = open_default(dir.path(), &clock);
+        for i in 0..4 {
+            cache.set(&format!("k{}", i), b"v", None).unwrap();
+        }
+    }
+    let path = dir.path().join(LOG_FILE);
+    let mut log = fs::read(&path).unwrap();
+    let record = (log.len() - 16) / 4;
+    // A flipped byte inside the third record's payload
+    log[16 + 2 * record + 10] ^= 0xff;
+    fs::write(&path, &log).unwrap();
+
+    let cache = open_default(dir.path(), &clock);
+    assert_eq!(cache.keys("*").unwrap(), vec!["k0", "k1"]);
+}
+
+#[test]
+fn test_rewrite_compacts_the_log() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    let cache = open(dir.path(), &clock, |c| PersistenceConfig { rewrite_min_bytes: 4096, ..c });
+    for _ in 0..500 {
+        cache.incr("counter").unwrap();
+    }
+    let grown = cache.stats().unwrap().log_bytes;
+    assert!(grown > 4096, "{}", grown);
+
+    cache.sync_log().unwrap();
+    assert_eq!(cache.stats().unwrap().log_bytes, 16);
+    cache.incr("counter").unwrap();
+    drop(cache);
+
+    let cache = open_default(dir.path(), &clock);
+    assert_eq!(value(&cache, "counter").as_deref(), Some("501"));
+}
+
+#[test]
+fn test_stale_log_after_interrupted_rewrite_is_ignored() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    let cache = open_default(dir.path(), &clock);
+    cache.set("a", b"1", None).unwrap();
+    cache.delete("a").unwrap();
+    cache.set("b", b"2", None).unwrap();
+    let old_log = fs::read(dir.path().join(LOG_FILE)).unwrap();
+
+    cache.set("a", b"3", None).unwrap();
+    cache.save_snapshot().unwrap();
+    drop(cache);
+    // Crash after the new snapshot but before the new log replaced the old one
+    fs::write(dir.path().join(LOG_FILE), &old_log).unwrap();
+    fs::write(dir.path().join(format!("{}.tmp", SNAPSHOT_FILE)), b"half a snapshot").unwrap();
+
+    let cache = open_default(dir.path(), &clock);
+    assert_eq!(value(&cache, "a").as_deref(), Some("3"));
+    assert_eq!(value(&cache, "b").as_deref(), Some("2"));
+}
+
+#[test]
+fn test_writes_during_a_snapshot_are_kept() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    let cache = Arc::new(open_default(dir.path(), &clock));
+
+    // Writers keep going while snapshots are written
+    let writer = {
+        let cache = cache.clone();
+        std::thread::spawn(move || {
+            for _ in 0..2000 {
+                cache.incr("counter").unwrap();
+            }
+        })
+    };
+    for _ in 0..20 {
+        cache.save_snapshot().unwrap();
+    }
+    writer.join().unwrap();
+    drop(cache);
+
+    let cache = open_default(dir.path(), &clock);
+    assert_eq!(value(&cache, "counter").as_deref(), Some("2000"));
+}
+
+#[test]
+fn test_log_past_the_snapshot_copy_survives_an_interrupted_rewrite() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    let cache = open_default(dir.path(), &clock);
+    cache.set("a", b"1", None).unwrap();
+    cache.set("b", b"2", None).unwrap();
+    let mut old_log = fs::read(dir.path().join(LOG_FILE)).unwrap();
+    cache.save_snapshot().unwrap();
+    drop(cache);
+
+    // Crash after the snapshot, with a write logged after its copy still
+    // only in the old log
+    old_log.extend(encode_record(&LogRecord::Delete { key: "a".to_string() }).unwrap());
+    fs::write(dir.path().join(LOG_FILE), &old_log).unwrap();
+
+    let cache = open_default(dir.path(), &clock);
+    assert_eq!(value(&cache, "a"), None);
+    assert_eq!(value(&cache, "b").as_deref(), Some("2"));
+}
+
+#[test]
+fn test_snapshot_only_mode_and_corrupt_snapshot() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    let snapshot_only = |c| PersistenceConfig { append_only: false, ..c };
+    {
+        let cache = open(dir.path(), &clock, snapshot_only);
+        cache.set("saved", b"s", None).unwrap();
+        cache.save_snapshot().unwrap();
+        cache.set("unsaved", b"u", None).unwrap();
+        assert_eq!(cache.stats().unwrap().log_bytes, 0);
+    }
+    let cache = open(dir.path(), &clock, snapshot_only);
+    assert_eq!(cache.keys("*").unwrap(), vec!["saved"]);
+    drop(cache);
+
+    let path = dir.path().join(SNAPSHOT_FILE);
+    let mut snapshot = fs::read(&path).unwrap();
+    let middle = snapshot.len() / 2;
+    snapshot[middle] ^= 0x01;
+    fs::write(&path, &snapshot).unwrap();
+    let err = UaoQtcamCache::new(1024 * 1024, 250.0)
+        .with_persistence(PersistenceConfig::new(dir.path()))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("checksum mismatch"), "{}", err);
+
+    // Without persistence there is nothing to save
+    assert!(UaoQtcamCache::new(1024, 250.0).save_snapshot().is_err());
+}
+
+#[test]
+fn test_recovery_evicts_down_to_a_smaller_limit() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(START_MS));
+    let used = {
+        let cache = open_default(dir.path(), &clock);
+        for i in 0..100 {
+            cache.set(&format!("key:{}", i), &[i as u8; 64], None).unwrap();
+        }
+        cache.stats().unwrap().used_memory
+    };
+    let reopen = |policy: EvictionPolicy| {
+        UaoQtcamCache::new(used / 2, 250.0)
+            .with_clock(clock.clone())
+            .with_eviction_policy(policy)
+            .with_persistence(PersistenceConfig::new(dir.path()))
+    };
+
+    // Nothing may be evicted: refuse to start rather than run over the limit
+    assert!(matches!(
+        reopen(EvictionPolicy::NoEviction),
+        Err(PersistenceError::OverLimit { recovered, .. }) if recovered == used
+    ));
+
+    let cache = reopen(EvictionPolicy::Lru).unwrap();
+    let stats = cache.stats().unwrap();
+    assert!(stats.used_memory <= used / 2, "{} > {}", stats.used_memory, used / 2);
+    assert!(stats.entry_count < 100 && stats.entry_count > 0);
+    assert_eq!(stats.evictions, 100 - stats.entry_count as u64);
+    drop(cache);
+
+    // The evicted keys stay gone after another restart
+    let cache = reopen(EvictionPolicy::NoEviction).unwrap();
+    assert_eq!(cache.stats().unwrap().entry_count, stats.entry_count);
+}
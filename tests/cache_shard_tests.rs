@@ -0,0 +1,137 @@
+//! UAO-QTCAM cache sharding under concurrent load
+//!
+//! Threads hammering the cache at once must lose no update, must never push
+//! it past its memory limit, and must see statistics that add up exactly
+//! once they are done, whatever the number of shards.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use symmetrix_core::uao_qtcam_cache::DEFAULT_SHARDS;
+use symmetrix_core::{EvictionPolicy, PersistenceConfig, UaoQtcamCache};
+use tempfile::TempDir;
+
+const THREADS: usize = 8;
+
+/// Run `work(thread)` on `THREADS` threads at once
+fn hammer(cache: &UaoQtcamCache, work: impl Fn(&UaoQtcamCache, usize) + Sync) {
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let work = &work;
+            scope.spawn(move || work(cache, t));
+        }
+    });
+}
+
+#[test]
+fn test_concurrent_counters_lose_no_increment() {
+    for shards in [1, 4, DEFAULT_SHARDS] {
+        let cache = UaoQtcamCache::new(64 * 1024 * 1024, 250.0).with_shards(shards);
+        assert_eq!(cache.shard_count(), shards);
+        hammer(&cache, |cache, t| {
+            for i in 0..2000 {
+                cache.incr(&format!("counter:{}", (i + t) % 32)).unwrap();
+            }
+        });
+        let total: i64 = (0..32)
+            .map(|i| String::from_utf8(cache.get(&format!("counter:{}", i)).unwrap().unwrap()).unwrap())
+            .map(|value| value.parse::<i64>().unwrap())
+            .sum();
+        assert_eq!(total, (THREADS * 2000) as i64, "{} shards", shards);
+    }
+}
+
+#[test]
+fn test_stats_add_up_across_shards() {
+    let cache = UaoQtcamCache::new(64 * 1024 * 1024, 250.0);
+    hammer(&cache, |cache, t| {
+        for i in 0..500 {
+            let key = format!("t{}:{}", t, i);
+            cache.set(&key, b"value", None).unwrap();
+            assert!(cache.get(&key).unwrap().is_some());
+            assert!(cache.get(&format!("missing:{}:{}", t, i)).unwrap().is_none());
+            if i % 5 == 0 {
+                assert!(cache.delete(&key).unwrap());
+            }
+        }
+    });
+
+    let stats = cache.stats().unwrap();
+    let ops = (THREADS * 500) as u64;
+    assert_eq!((stats.sets, stats.hits, stats.misses, stats.deletes), (ops, ops, ops, ops / 5));
+    assert_eq!(stats.entry_count, cache.keys("*").unwrap().len());
+    assert_eq!(stats.entry_count as u64, ops - ops / 5);
+    assert_eq!(stats.hit_rate, 0.5);
+}
+
+#[test]
+fn test_memory_limit_holds_under_concurrent_writers() {
+    const LIMIT: usize = 64 * 1024;
+    for policy in [EvictionPolicy::Lru, EvictionPolicy::Random, EvictionPolicy::NoEviction] {
+        let cache = UaoQtcamCache::new(LIMIT, 1.0)
+            .with_compression_threshold(usize::MAX)
+            .with_eviction_policy(policy);
+        let done = AtomicBool::new(false);
+        thread::scope(|scope| {
+            // Samples the counter while writers race each other for room
+            let watcher = scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    assert!(cache.stats().unwrap().used_memory <= LIMIT);
+                }
+            });
+            hammer(&cache, |cache, t| {
+                let mut rng = StdRng::seed_from_u64(t as u64);
+                for _ in 0..2000 {
+                    let key = format!("k:{}", rng.gen_range(0..512));
+                    let value = vec![b'v'; rng.gen_range(0..1024)];
+                    if let Err(e) = cache.set(&key, &value, None) {
+                        assert_eq!(policy, EvictionPolicy::NoEviction, "{}", e);
+                    }
+                }
+            });
+            done.store(true, Ordering::Relaxed);
+            watcher.join().unwrap();
+        });
+
+        let stats = cache.stats().unwrap();
+        let charged: usize = cache
+            .keys("*")
+            .unwrap()
+            .iter()
+            .map(|key| cache.memory_usage(key).unwrap().unwrap())
+            .sum();
+        assert!(stats.used_memory <= LIMIT, "{} under {}", stats.used_memory, policy);
+        assert_eq!(stats.used_memory, charged, "under {}", policy);
+        if policy != EvictionPolicy::NoEviction {
+            assert!(stats.evictions > 0);
+        }
+    }
+}
+
+#[test]
+fn test_snapshot_reloads_into_any_shard_count() {
+    let dir = TempDir::new().unwrap();
+    {
+        let cache = UaoQtcamCache::new(64 * 1024 * 1024, 250.0)
+            .with_persistence(PersistenceConfig::new(dir.path()))
+            .unwrap();
+        hammer(&cache, |cache, t| {
+            for i in 0..100 {
+                cache.set(&format!("t{}:{}", t, i), format!("{}", i).as_bytes(), Some(3600)).unwrap();
+            }
+        });
+        cache.save_snapshot().unwrap();
+    }
+
+    let cache = UaoQtcamCache::new(64 * 1024 * 1024, 250.0)
+        .with_shards(3)
+        .with_persistence(PersistenceConfig::new(dir.path()))
+        .unwrap();
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.entry_count, stats.expiring_keys), (THREADS * 100, THREADS * 100));
+    assert_eq!(cache.get("t7:42").unwrap().as_deref(), Some(&b"42"[..]));
+    let charged: usize = cache.keys("*").unwrap().iter().map(|key| cache.memory_usage(key).unwrap().unwrap()).sum();
+    assert_eq!(stats.used_memory, charged);
+}
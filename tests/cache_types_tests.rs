@@ -0,0 +1,194 @@
+//! UAO-QTCAM cache structured values: hashes, lists, sets and sorted sets
+//!
+//! Updates from many threads must all land, type mismatches must fail
+//! without touching the value, and per-type accounting must add up to the
+//! size the cache enforces.
+
+use std::ops::Bound;
+use std::sync::Arc;
+use std::thread;
+
+use symmetrix_core::grpc::ManualClock;
+use symmetrix_core::uao_qtcam_cache::types::{HASH_FIELD_OVERHEAD, LIST_ITEM_OVERHEAD};
+use symmetrix_core::{FsyncPolicy, PersistenceConfig, UaoQtcamCache, ValueKind};
+use tempfile::TempDir;
+
+const THREADS: usize = 16;
+const OPS_PER_THREAD: usize = 250;
+
+fn cache() -> UaoQtcamCache {
+    UaoQtcamCache::new(64 * 1024 * 1024, 250.0)
+}
+
+/// Charged bytes summed over the per-type breakdown
+fn typed_bytes(cache: &UaoQtcamCache) -> usize {
+    cache.stats().unwrap().type_usage.values().map(|usage| usage.bytes).sum()
+}
+
+#[test]
+fn test_concurrent_updates_are_atomic() {
+    let cache = Arc::new(cache());
+    let workers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let cache = cache.clone();
+            thread::spawn(move || {
+                for i in 0..OPS_PER_THREAD {
+                    let id = format!("{}-{}", t, i);
+                    cache.hset("hash", &[(id.as_bytes(), b"v")]).unwrap();
+                    cache.lpush("queue", &[id.as_bytes()]).unwrap();
+                    cache.sadd("set", &[id.as_bytes(), b"shared"]).unwrap();
+                    cache.zadd("board", &[((t * OPS_PER_THREAD + i) as f64, id.as_bytes())]).unwrap();
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    let total = THREADS * OPS_PER_THREAD;
+    assert_eq!(cache.hgetall("hash").unwrap().len(), total);
+    assert_eq!(cache.lrange("queue", 0, -1).unwrap().len(), total);
+    assert_eq!(cache.smembers("set").unwrap().len(), total + 1);
+    let board = cache.zrange_by_score("board", Bound::Unbounded, Bound::Unbounded).unwrap();
+    assert_eq!(board.len(), total);
+    assert!(board.windows(2).all(|pair| pair[0].1 < pair[1].1));
+
+    // Concurrent consumers split the queue without losing or repeating items
+    let consumers: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let cache = cache.clone();
+            thread::spawn(move || {
+                let mut popped = Vec::new();
+                loop {
+                    let items = cache.rpop("queue", 7).unwrap();
+                    if items.is_empty() {
+                        return popped;
+                    }
+                    popped.extend(items);
+                }
+            })
+        })
+        .collect();
+    let mut popped: Vec<_> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+    popped.sort_unstable();
+    popped.dedup();
+    assert_eq!(popped.len(), total);
+    assert_eq!(cache.key_type("queue").unwrap(), None);
+
+    let stats = cache.stats().unwrap();
+    assert_eq!(typed_bytes(&cache), stats.compressed_bytes);
+    assert!(!stats.type_usage.contains_key(&ValueKind::List));
+}
+
+#[test]
+fn test_type_mismatches_fail() {
+    let cache = cache();
+    cache.set("text", b"plain", None).unwrap();
+    cache.sadd("tags", &[b"a"]).unwrap();
+
+    assert!(cache.hset("text", &[(b"f", b"v")]).unwrap_err().starts_with("WRONGTYPE"));
+    assert!(cache.lrange("tags", 0, -1).unwrap_err().starts_with("WRONGTYPE"));
+    assert!(cache.get("tags").unwrap_err().starts_with("WRONGTYPE"));
+    assert!(cache.incr("tags").unwrap_err().starts_with("WRONGTYPE"));
+    assert!(cache.zadd("board", &[(f64::NAN, b"m")]).is_err());
+    assert_eq!(cache.key_type("board").unwrap(), None);
+
+    // Nothing changed, and SET still replaces any type
+    assert_eq!(cache.get("text").unwrap().unwrap(), b"plain");
+    assert!(cache.sismember("tags", b"a").unwrap());
+    cache.set("tags", b"now a string", None).unwrap();
+    assert_eq!(cache.key_type("tags").unwrap(), Some(ValueKind::String));
+    assert_eq!(typed_bytes(&cache), cache.stats().unwrap().compressed_bytes);
+}
+
+#[test]
+fn test_collections_follow_redis_semantics() {
+    let cache = cache();
+    assert_eq!(cache.hset("user", &[(b"name", b"ada"), (b"role", b"admin")]).unwrap(), 2);
+    assert_eq!(cache.hset("user", &[(b"role", b"owner"), (b"team", b"core")]).unwrap(), 1);
+    assert_eq!(cache.hget("user", b"role").unwrap().as_deref(), Some(&b"owner"[..]));
+    assert_eq!(cache.hdel("user", &[b"team", b"missing"]).unwrap(), 1);
+    assert_eq!(cache.hgetall("user").unwrap(), vec![
+        (b"name".to_vec(), b"ada".to_vec()),
+        (b"role".to_vec(), b"owner".to_vec()),
+    ]);
+    // Removing the last field removes the key
+    assert_eq!(cache.hdel("user", &[b"name", b"role"]).unwrap(), 2);
+    assert!(!cache.exists("user").unwrap());
+
+    assert_eq!(cache.lpush("jobs", &[b"a", b"b", b"c"]).unwrap(), 3);
+    assert_eq!(cache.lrange("jobs", 0, -1).unwrap(), vec![b"c".to_vec(), b"b".to_vec(), b"a".to_vec()]);
+    assert_eq!(cache.lrange("jobs", -2, 10).unwrap(), vec![b"b".to_vec(), b"a".to_vec()]);
+    assert_eq!(cache.rpop("jobs", 1).unwrap(), vec![b"a".to_vec()]);
+    assert!(cache.rpop("nothing", 1).unwrap().is_empty());
+
+    cache.zadd("board", &[(3.0, b"c"), (1.0, b"a"), (2.0, b"b")]).unwrap();
+    assert_eq!(cache.zadd("board", &[(0.5, b"c")]).unwrap(), 0);
+    let members: Vec<_> = cache
+        .zrange_by_score("board", Bound::Included(0.5), Bound::Excluded(2.0))
+        .unwrap()
+        .into_iter()
+        .map(|(member, _)| member)
+        .collect();
+    assert_eq!(members, vec![b"c".to_vec(), b"a".to_vec()]);
+}
+
+#[test]
+fn test_memory_is_charged_per_type() {
+    let cache = cache();
+    cache.hset("h", &[(b"field", b"value")]).unwrap();
+    cache.lpush("l", &[b"item"]).unwrap();
+    cache.sadd("s", &[b"member"]).unwrap();
+    cache.zadd("z", &[(1.0, b"member")]).unwrap();
+
+    let stats = cache.stats().unwrap();
+    assert_eq!(stats.type_usage[&ValueKind::Hash].bytes, 10 + HASH_FIELD_OVERHEAD);
+    assert_eq!(stats.type_usage[&ValueKind::List].bytes, 4 + LIST_ITEM_OVERHEAD);
+    assert_eq!(stats.type_usage.len(), 4);
+    assert_eq!(typed_bytes(&cache), stats.compressed_bytes);
+
+    // Eviction counts collections at their real size
+    let small = UaoQtcamCache::new(4096, 1.0);
+    for i in 0..64 {
+        small.hset(&format!("h{}", i), &[(b"field", &[b'x'; 100][..])]).unwrap();
+    }
+    let stats = small.stats().unwrap();
+    assert!(stats.evictions > 0);
+    assert!(stats.compressed_bytes <= 4096, "{:?}", stats);
+}
+
+#[test]
+fn test_updates_survive_restart() {
+    let dir = TempDir::new().unwrap();
+    let clock = Arc::new(ManualClock::new(1_700_000_000_000));
+    let open = || {
+        UaoQtcamCache::new(64 * 1024 * 1024, 250.0)
+            .with_clock(clock.clone())
+            .with_persistence(PersistenceConfig { fsync: FsyncPolicy::Always, ..PersistenceConfig::new(dir.path()) })
+            .unwrap()
+    };
+    {
+        let cache = open();
+        cache.hset("user", &[(b"name", b"ada"), (b"role", b"admin")]).unwrap();
+        cache.hdel("user", &[b"role"]).unwrap();
+        cache.lpush("jobs", &[b"a", b"b", b"c"]).unwrap();
+        cache.rpop("jobs", 2).unwrap();
+        cache.sadd("tags", &[b"x"]).unwrap();
+        cache.sadd("tags", &[b"y"]).unwrap();
+        cache.zadd("board", &[(2.0, b"b"), (1.0, b"a")]).unwrap();
+        cache.lpush("gone", &[b"only"]).unwrap();
+        cache.rpop("gone", 1).unwrap();
+    }
+
+    let cache = open();
+    assert_eq!(cache.hgetall("user").unwrap(), vec![(b"name".to_vec(), b"ada".to_vec())]);
+    assert_eq!(cache.lrange("jobs", 0, -1).unwrap(), vec![b"c".to_vec()]);
+    assert_eq!(cache.smembers("tags").unwrap(), vec![b"x".to_vec(), b"y".to_vec()]);
+    assert_eq!(
+        cache.zrange_by_score("board", Bound::Unbounded, Bound::Unbounded).unwrap(),
+        vec![(b"a".to_vec(), 1.0), (b"b".to_vec(), 2.0)]
+    );
+    assert_eq!(cache.key_type("gone").unwrap(), None);
+    assert_eq!(typed_bytes(&cache), cache.stats().unwrap().compressed_bytes);
+}
@@ -0,0 +1,352 @@
+//! Authentication and per-command authorization of the control plane
+//!
+//! Each transport is exercised with missing, invalid and valid credentials;
+//! valid callers only run the commands their role allows, and every denial
+//! and allowed mutation lands in the audit trail.
+
+#![cfg(all(
+    feature = "qagml-integration",
+    feature = "qanban-integration",
+    feature = "uao-qtcam-integration",
+    feature = "gfef"
+))]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use symmetrix_core::control_plane::auth::{ControlAccess, Permission, Role, RolePolicy, Transport};
+use symmetrix_core::control_plane::http::{self, COMMANDS_PATH};
+use symmetrix_core::control_plane::vxlan::{self, VxlanHeader};
+use symmetrix_core::control_plane::{ControlCommand, ControlPlaneState, Topic};
+use symmetrix_core::grpc::auth::AuthManager;
+use tokio::net::UdpSocket;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+const ADMIN_KEY: &str = "cp_admin_key";
+const VIEWER_KEY: &str = "cp_viewer_key";
+
+async fn secured_state() -> Arc<ControlPlaneState> {
+    let access = ControlAccess::new(Arc::new(AuthManager::new(true)));
+    access.register_key(ADMIN_KEY, "enterprise", "ops", Role::Admin).await;
+    access.register_key(VIEWER_KEY, "enterprise", "readers", Role::Viewer).await;
+    Arc::new(
+        ControlPlaneState::new(4 * 1024 * 1024)
+            .unwrap()
+            .with_access(access),
+    )
+}
+
+/// HTTP server; returns `host:port`
+async fn spawn_http(state: Arc<ControlPlaneState>) -> String {
+    let app = http::router(state, 64 * 1024);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    addr.to_string()
+}
+
+fn now() -> u64 {
+    chrono::Utc::now().timestamp() as u64
+}
+
+fn cache_set() -> Value {
+    json!({ "cmd": "CacheSet", "data": { "key": "k", "value": "v" } })
+}
+
+#[test]
+fn test_every_command_has_a_permission() {
+    let policy = RolePolicy::default();
+    for name in ControlCommand::NAMES {
+        let data = match *name {
+            "AllocateMemory" => json!({ "size_bytes": 1, "region": "r" }),
+            "FreeMemory" => json!({ "allocation_id": "a" }),
+            "OptimizeBandwidth" => json!({ "flow_id": "f", "target_gbps": 1.0 }),
+            "Lookup" | "DeleteRoute" | "CacheGet" | "CacheDelete" | "CacheIncr" => json!({ "key": "k" }),
+            "InsertRoute" => json!({ "key": "1", "value": "gw", "priority": 1 }),
+            "CacheSet" => json!({ "key": "k", "value": "v" }),
+            "GetCalibrationMatrix" => json!({}),
+            "PredictActivation" => json!({ "customer_id": "c", "model_id": "m", "layer_index": 0, "input_hash": "h" }),
+            "UploadGfefIndex" => json!({ "model_id": "m", "index_data": "{}" }),
+            _ => Value::Null,
+        };
+        let command = ControlCommand::from_parts(name, data).unwrap();
+        let permission = RolePolicy::required(&command);
+        assert!(policy.allows(Role::Admin, permission), "{}", name);
+        // Viewers never mutate
+        assert_eq!(policy.allows(Role::Viewer, permission), !command.is_mutation(), "{}", name);
+    }
+
+    assert!(policy.allows(Role::Operator, Permission::CacheWrite));
+    assert!(!policy.allows(Role::Operator, Permission::RouteWrite));
+    let custom = RolePolicy::default().with_grant(Role::Viewer, [Permission::Read, Permission::CacheWrite]);
+    assert!(custom.allows(Role::Viewer, Permission::CacheWrite));
+    assert!(!custom.allows(Role::Viewer, Permission::Predict));
+
+    for topic in Topic::ALL {
+        assert!(policy.allows(Role::Admin, RolePolicy::required_for_topic(topic)), "{:?}", topic);
+    }
+    assert!(policy.allows(Role::Viewer, RolePolicy::required_for_topic(Topic::Stats)));
+    assert!(!policy.allows(Role::Viewer, RolePolicy::required_for_topic(Topic::Keyspace)));
+    assert!(!policy.allows(Role::Operator, RolePolicy::required_for_topic(Topic::Routes)));
+}
+
+#[tokio::test]
+async fn test_http_credentials_and_roles() {
+    let state = secured_state().await;
+    let base = format!("http://{}", spawn_http(state.clone()).await);
+    let client = reqwest::Client::new();
+    let set_url = format!("{}{}/CacheSet", base, COMMANDS_PATH);
+    let body = json!({ "key": "k", "value": "v" });
+
+    // Missing and invalid keys
+    let response = client.post(&set_url).json(&body).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let error: Value = response.json().await.unwrap();
+    assert_eq!(error["error"], "UNAUTHENTICATED");
+    let response = client.post(&set_url).header("x-api-key", "nope").json(&body).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    // Query keys are only for the WebSocket upgrade
+    let response = client.post(format!("{}?api_key={}", set_url, ADMIN_KEY)).json(&body).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Liveness and documentation stay public
+    for path in ["/health", "/openapi.json"] {
+        let response = client.get(format!("{}{}", base, path)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+    }
+
+    // A viewer reads but does not write
+    let response = client.get(format!("{}/stats", base)).header("x-api-key", VIEWER_KEY).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client.post(&set_url).header("x-api-key", VIEWER_KEY).json(&body).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let denied: Value = response.json().await.unwrap();
+    assert_eq!(denied["success"], false);
+    assert!(denied["message"].as_str().unwrap().contains("CacheWrite"), "{}", denied);
+    let response = client
+        .post(format!("{}{}/DeleteRoute", base, COMMANDS_PATH))
+        .bearer_auth(VIEWER_KEY)
+        .json(&json!({ "key": "1" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // An admin writes, with either header
+    let response = client.post(&set_url).header("x-api-key", ADMIN_KEY).json(&body).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = client
+        .post(format!("{}{}", base, COMMANDS_PATH))
+        .bearer_auth(ADMIN_KEY)
+        .json(&json!({ "cmd": "CacheDelete", "data": { "key": "k" } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let audit = state.access.audit().entries();
+    let summary: Vec<(&str, &str, bool)> = audit
+        .iter()
+        .map(|e| (e.principal.as_str(), e.action.as_str(), e.allowed))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("readers", "CacheSet", false),
+            ("readers", "DeleteRoute", false),
+            ("ops", "CacheSet", true),
+            ("ops", "CacheDelete", true),
+        ]
+    );
+    assert!(audit.iter().all(|e| e.transport == Transport::Http));
+
+    let stats = state.stats.read().await.clone();
+    assert_eq!(stats.auth_failures, 3);
+    assert_eq!(stats.permission_denied, 2);
+}
+
+#[tokio::test]
+async fn test_websocket_credentials_and_roles() {
+    let state = secured_state().await;
+    let url = format!("ws://{}/ws", spawn_http(state.clone()).await);
+
+    for request in [
+        url.as_str().into_client_request().unwrap(),
+        format!("{}?api_key=nope", url).into_client_request().unwrap(),
+    ] {
+        match tokio_tungstenite::connect_async(request).await {
+            Err(WsError::Http(response)) => assert_eq!(response.status().as_u16(), 401),
+            other => panic!("upgrade not rejected: {:?}", other.map(|_| ())),
+        }
+    }
+
+    let mut viewer_request = url.as_str().into_client_request().unwrap();
+    viewer_request.headers_mut().insert("x-api-key", VIEWER_KEY.parse().unwrap());
+    let admin_request = format!("{}?api_key={}", url, ADMIN_KEY);
+
+    for (request, role, allowed) in [
+        (viewer_request, "viewer", false),
+        (admin_request.into_client_request().unwrap(), "admin", true),
+    ] {
+        let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next()).await.unwrap();
+        let welcome: Value = serde_json::from_str(message.unwrap().unwrap().to_text().unwrap()).unwrap();
+        assert_eq!(welcome["role"], role);
+
+        socket.send(Message::Text(cache_set().to_string())).await.unwrap();
+        let message = socket.next().await.unwrap().unwrap();
+        let reply: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(reply["success"], allowed, "{}: {}", role, reply);
+    }
+
+    let audit = state.access.audit().entries();
+    assert_eq!(audit.len(), 2);
+    assert!(audit.iter().all(|e| e.transport == Transport::WebSocket));
+    assert_eq!((audit[0].allowed, audit[1].allowed), (false, true));
+}
+
+#[tokio::test]
+async fn test_vxlan_signatures_and_roles() {
+    let state = secured_state().await;
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = server.local_addr().unwrap();
+    tokio::spawn(vxlan::serve(server, state.clone()));
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    socket.connect(addr).await.unwrap();
+
+    let exchange = |frame: Vec<u8>| {
+        let socket = &socket;
+        async move {
+            socket.send(&frame).await.unwrap();
+            let mut buf = vec![0u8; 65535];
+            match tokio::time::timeout(Duration::from_millis(300), socket.recv(&mut buf)).await {
+                Ok(Ok(len)) => Some(buf[..len].to_vec()),
+                _ => None,
+            }
+        }
+    };
+    let header = VxlanHeader::new(42).unwrap();
+    let payload = cache_set().to_string().into_bytes();
+
+    // Unsigned, unknown key, tampered and stale frames are dropped silently
+    let mut tampered = header.encapsulate_signed(ADMIN_KEY, now(), &payload);
+    let last = tampered.len() - 2;
+    tampered[last] ^= 1;
+    for frame in [
+        header.encapsulate(&payload),
+        header.encapsulate_signed("nope", now(), &payload),
+        tampered,
+        header.encapsulate_signed(ADMIN_KEY, now() - 3600, &payload),
+    ] {
+        assert!(exchange(frame).await.is_none());
+    }
+    assert_eq!(state.stats.read().await.auth_failures, 4);
+
+    // A signed viewer frame on its VNI is answered with a denial
+    state.access.grant_vnis(VIEWER_KEY, [42]).await;
+    let reply = exchange(header.encapsulate_signed(VIEWER_KEY, now(), &payload)).await.unwrap();
+    let (reply_header, body) = VxlanHeader::parse(&reply).unwrap();
+    assert_eq!(reply_header.vni, 42);
+    let response: Value = serde_json::from_slice(body).unwrap();
+    assert_eq!(response["success"], false);
+
+    let reply = exchange(header.encapsulate_signed(ADMIN_KEY, now(), &payload)).await.unwrap();
+    let response: Value = serde_json::from_slice(VxlanHeader::parse(&reply).unwrap().1).unwrap();
+    assert_eq!(response["success"], true, "{}", response);
+    assert_eq!(state.cache.get("vni:42:k").unwrap().unwrap(), b"v");
+
+    let audit = state.access.audit().entries();
+    assert_eq!(audit.len(), 2);
+    assert_eq!(audit[0].vni, Some(42));
+    assert_eq!((audit[0].principal.as_str(), audit[0].allowed), ("readers", false));
+    assert_eq!((audit[1].principal.as_str(), audit[1].allowed), ("ops", true));
+}
+
+#[tokio::test]
+async fn test_vxlan_keys_are_limited_to_their_vnis() {
+    let state = secured_state().await;
+    state.access.grant_vnis(VIEWER_KEY, [42]).await;
+    let health = json!({ "cmd": "Health" }).to_string().into_bytes();
+    let run = |vni: u32, key: &'static str| {
+        let state = state.clone();
+        let frame = VxlanHeader::new(vni).unwrap().encapsulate_signed(key, now(), &health);
+        async move {
+            let reply = vxlan::handle_frame(&state, &frame, None).await.unwrap();
+            serde_json::from_slice::<Value>(VxlanHeader::parse(&reply).unwrap().1).unwrap()
+        }
+    };
+
+    assert_eq!(run(42, VIEWER_KEY).await["success"], true);
+    assert_eq!(run(vxlan::VXLAN_VNI_CONTROL_PLANE, VIEWER_KEY).await["success"], true);
+    let response = run(43, VIEWER_KEY).await;
+    assert_eq!(response["success"], false);
+    assert!(response["message"].as_str().unwrap().contains("VNI 43"), "{}", response);
+    assert_eq!(run(43, ADMIN_KEY).await["success"], true);
+
+    let audit = state.access.audit().entries();
+    assert_eq!(audit.len(), 1);
+    assert_eq!((audit[0].vni, audit[0].allowed), (Some(43), false));
+    assert_eq!(state.stats.read().await.permission_denied, 1);
+}
+
+#[tokio::test]
+async fn test_stored_key_hash_cannot_sign_vxlan_frames() {
+    use hmac::{Hmac, Mac};
+    use sha2::{Digest, Sha256};
+
+    let state = secured_state().await;
+    let payload = cache_set().to_string().into_bytes();
+    let signed = VxlanHeader::new(42).unwrap().encapsulate_signed(ADMIN_KEY, now(), &payload);
+
+    // Someone holding only the key hash signs with secrets derived from it
+    let key_hash = AuthManager::hash_key(ADMIN_KEY);
+    let secrets = [
+        Sha256::new().chain_update(b"symmetrix-vxlan").chain_update(key_hash.as_bytes()).finalize().to_vec(),
+        hex::decode(&key_hash).unwrap(),
+    ];
+    let prefix_len = vxlan::VXLAN_HEADER_SIZE + 20;
+    for secret in secrets {
+        let mut forged = signed[..prefix_len].to_vec();
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&secret).unwrap();
+        mac.update(&forged);
+        mac.update(&payload);
+        forged.extend_from_slice(&mac.finalize().into_bytes());
+        forged.extend_from_slice(&payload);
+        assert!(vxlan::handle_frame(&state, &forged, None).await.is_none());
+    }
+    assert_eq!(state.stats.read().await.auth_failures, 2);
+    assert!(vxlan::handle_frame(&state, &signed, None).await.is_some());
+}
+
+#[tokio::test]
+async fn test_signed_frames_work_without_auth() {
+    let state = ControlPlaneState::new(4 * 1024 * 1024).unwrap();
+    let frame = VxlanHeader::new(7)
+        .unwrap()
+        .encapsulate_signed("any", 0, json!({ "cmd": "Health" }).to_string().as_bytes());
+    let reply = vxlan::handle_frame(&state, &frame, None).await.unwrap();
+    let response: Value = serde_json::from_slice(VxlanHeader::parse(&reply).unwrap().1).unwrap();
+    assert_eq!(response["success"], true);
+}
+
+#[tokio::test]
+async fn test_replayed_vxlan_frames_are_dropped() {
+    let state = secured_state().await;
+    let header = VxlanHeader::new(42).unwrap();
+    let timestamp = now();
+    let frame = header.encapsulate_signed(ADMIN_KEY, timestamp, &cache_set().to_string().into_bytes());
+
+    assert!(vxlan::handle_frame(&state, &frame, None).await.is_some());
+    assert!(vxlan::handle_frame(&state, &frame, None).await.is_none());
+    assert_eq!(state.stats.read().await.auth_failures, 1);
+
+    // Another command signed in the same second is still accepted
+    let health = header.encapsulate_signed(ADMIN_KEY, timestamp, json!({ "cmd": "Health" }).to_string().as_bytes());
+    assert!(vxlan::handle_frame(&state, &health, None).await.is_some());
+}
@@ -0,0 +1,296 @@
+//! Loopback tests for the control-plane HTTP API
+//!
+//! Every command is reachable at `/v1/commands/{Command}`, failures carry a
+//! real status code and a structured body, and connections stay alive.
+
+#![cfg(all(
+    feature = "qagml-integration",
+    feature = "qanban-integration",
+    feature = "uao-qtcam-integration",
+    feature = "gfef"
+))]
+
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use symmetrix_core::control_plane::http::{self, COMMANDS_PATH};
+use symmetrix_core::control_plane::{ControlCommand, ControlPlaneState, Namespace};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const TEST_MAX_BODY_BYTES: usize = 64 * 1024;
+
+async fn spawn() -> (String, Arc<ControlPlaneState>) {
+    let state = Arc::new(ControlPlaneState::new(4 * 1024 * 1024).unwrap());
+    let app = http::router(state.clone(), TEST_MAX_BODY_BYTES);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{}", addr), state)
+}
+
+/// One layer of four neurons in a two-component space
+const SAMPLE_INDEX: &str = r#"{"layers":[{"layer_id":3,"name":"mlp.3","neurons":4,"signatures":[[1,0],[0,1],[-1,0],[0,-1]]}]}"#;
+
+/// Sample `data` for every command
+fn sample(name: &str) -> Value {
+    match name {
+        "AllocateMemory" => json!({ "size_bytes": 1048576, "region": "default" }),
+        "FreeMemory" => json!({ "allocation_id": "alloc-1" }),
+        "OptimizeBandwidth" => json!({ "flow_id": "flow-1", "target_gbps": 100.0 }),
+        "Lookup" => json!({ "key": "10.0.0.1" }),
+        "InsertRoute" => json!({ "key": "167772160", "value": "gw-1", "priority": 10 }),
+        "DeleteRoute" => json!({ "key": "167772160" }),
+        "CacheSet" => json!({ "key": "k", "value": "v", "ttl_seconds": 60 }),
+        "CacheGet" | "CacheDelete" | "CacheIncr" => json!({ "key": "counter" }),
+        "GetCalibrationMatrix" => json!({ "tier": "basic" }),
+        "PredictActivation" => json!({
+            "customer_id": "c-1",
+            "model_id": "m-1",
+            "layer_index": 3,
+            "input_hash": "abc",
+        }),
+        "UploadGfefIndex" => json!({ "model_id": "m-1", "index_data": SAMPLE_INDEX }),
+        _ => Value::Null,
+    }
+}
+
+async fn post(client: &reqwest::Client, url: String, body: &Value) -> (StatusCode, Value) {
+    let request = client.post(url);
+    let request = if body.is_null() {
+        request
+    } else {
+        request.json(body)
+    };
+    let response = request.send().await.unwrap();
+    let status = response.status();
+    (status, response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_every_command_over_http() {
+    let (base, state) = spawn().await;
+    let client = reqwest::Client::new();
+
+    // PredictActivation needs an index of the sampled model
+    let (status, _) = post(&client, format!("{}{}/UploadGfefIndex", base, COMMANDS_PATH), &sample("UploadGfefIndex")).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for name in ControlCommand::NAMES {
+        // Present key for CacheGet/CacheDelete; misses are covered below
+        state.cache.set("counter", b"1", None).unwrap();
+        let (status, body) = post(&client, format!("{}{}/{}", base, COMMANDS_PATH, name), &sample(name)).await;
+        assert_eq!(status, StatusCode::OK, "{}: {}", name, body);
+        assert_eq!(body["success"], true, "{}", name);
+        assert!(body["latency_ns"].is_u64());
+
+        // The tagged form reaches the same command
+        state.cache.set("counter", b"1", None).unwrap();
+        let mut envelope = json!({ "cmd": name });
+        if !sample(name).is_null() {
+            envelope["data"] = sample(name);
+        }
+        let (status, body) = post(&client, format!("{}{}", base, COMMANDS_PATH), &envelope).await;
+        assert_eq!(status, StatusCode::OK, "tagged {}: {}", name, body);
+    }
+
+    let (_, stats) = post(&client, format!("{}{}/Stats", base, COMMANDS_PATH), &Value::Null).await;
+    assert!(stats["data"]["commands_processed"].as_u64().unwrap() >= 2 * ControlCommand::NAMES.len() as u64);
+    assert!(stats["data"]["http_requests"].as_u64().unwrap() > stats["data"]["commands_processed"].as_u64().unwrap() / 2);
+
+    // Unit commands also accept `{}`
+    let (status, _) = post(&client, format!("{}{}/Health", base, COMMANDS_PATH), &json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Read-only shortcuts
+    for path in ["/", "/health", "/stats", "/cascade", "/memory", "/bandwidth", "/cache/stats"] {
+        let response = client.get(format!("{}{}", base, path)).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["success"], true, "{}", path);
+    }
+
+    // `/ws` without an upgrade describes the channel
+    let info: Value = client.get(format!("{}/ws", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(info["data"]["supported_commands"].as_array().unwrap().len(), ControlCommand::NAMES.len());
+}
+
+#[tokio::test]
+async fn test_error_statuses() {
+    let (base, _) = spawn().await;
+    let client = reqwest::Client::new();
+    let commands = format!("{}{}", base, COMMANDS_PATH);
+
+    // Command outcome: cache miss
+    let (status, body) = post(&client, format!("{}/CacheGet", commands), &json!({ "key": "missing" })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["success"], false);
+    assert!(body["latency_ns"].is_u64());
+
+    let (status, body) = post(&client, format!("{}/CacheDelete", commands), &json!({ "key": "missing" })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["success"], false);
+
+    // Malformed JSON
+    let response = client
+        .post(format!("{}/CacheSet", commands))
+        .body("{not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "INVALID_JSON");
+    assert_eq!(body["success"], false);
+
+    // Wrong argument types / missing fields
+    let (status, body) = post(&client, format!("{}/AllocateMemory", commands), &json!({ "size_bytes": "big" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"], "INVALID_ARGUMENTS");
+
+    // Unknown command, by path and by tag
+    let (status, body) = post(&client, format!("{}/Teleport", commands), &json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "UNKNOWN_COMMAND");
+    let (status, body) = post(&client, commands.clone(), &json!({ "cmd": "Teleport" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "UNKNOWN_COMMAND");
+
+    // Body limit
+    let (status, body) = post(
+        &client,
+        format!("{}/CacheSet", commands),
+        &json!({ "key": "big", "value": "x".repeat(TEST_MAX_BODY_BYTES * 2) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body["error"], "PAYLOAD_TOO_LARGE");
+
+    // Unknown route and wrong method
+    let response = client.get(format!("{}/nowhere", base)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "NOT_FOUND");
+    let response = client.get(format!("{}/CacheSet", commands)).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "METHOD_NOT_ALLOWED");
+}
+
+#[tokio::test]
+async fn test_global_keys_cannot_reach_tenant_keys() {
+    let (base, state) = spawn().await;
+    let client = reqwest::Client::new();
+    let commands = format!("{}{}", base, COMMANDS_PATH);
+    let set = ControlCommand::from_parts("CacheSet", json!({ "key": "secret", "value": "T" })).unwrap();
+    assert!(state.execute_in(Namespace::Tenant(5), set).await.1.success);
+
+    for command in ["CacheGet", "CacheIncr", "CacheDelete"] {
+        let (status, body) = post(&client, format!("{}/{}", commands, command), &json!({ "key": "vni:5:secret" })).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", body);
+        assert!(body["data"].is_null(), "{}", body);
+    }
+    let (status, _) = post(&client, format!("{}/CacheSet", commands), &json!({ "key": "vni:5:secret", "value": "G" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = post(&client, format!("{}/UploadGfefIndex", commands), &json!({ "model_id": "vni:5:m", "index_data": SAMPLE_INDEX })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(state.cache.get("vni:5:secret").unwrap().unwrap(), b"T");
+}
+
+#[tokio::test]
+async fn test_keep_alive() {
+    let (base, _) = spawn().await;
+    let addr = base.trim_start_matches("http://");
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+
+    let body = r#"{"key":"ka","value":"1"}"#;
+    let requests = format!(
+        "POST /v1/commands/CacheSet HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}\
+         GET /health HTTP/1.1\r\nHost: {addr}\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(requests.as_bytes()).await.unwrap();
+
+    // Both responses arrive on the same connection
+    let mut received = Vec::new();
+    let mut buf = [0u8; 4096];
+    while String::from_utf8_lossy(&received).matches("HTTP/1.1 200 OK").count() < 2 {
+        let n = tokio::time::timeout(std::time::Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(n > 0, "connection closed after {}", String::from_utf8_lossy(&received));
+        received.extend_from_slice(&buf[..n]);
+    }
+    assert!(!String::from_utf8_lossy(&received).to_lowercase().contains("connection: close"));
+}
+
+#[tokio::test]
+async fn test_command_schemas_are_published() {
+    let (base, state) = spawn().await;
+    let doc: Value = reqwest::get(format!("{}/openapi.json", base))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let served = http::api(state).document();
+    assert_eq!(doc, served);
+
+    for name in ControlCommand::NAMES {
+        let post = &doc["paths"][format!("{}/{}", COMMANDS_PATH, name)]["post"];
+        assert!(post.is_object(), "{} is not in the spec", name);
+        let schema = &post["requestBody"]["content"]["application/json"]["schema"];
+        let expected = ControlCommand::data_schema(name).unwrap();
+        assert_eq!(schema, &expected, "{}", name);
+
+        // Sample payloads satisfy the published schema
+        let data = sample(name);
+        if expected["type"] == "null" {
+            assert!(data.is_null(), "{}", name);
+            continue;
+        }
+        let properties = expected["properties"].as_object().unwrap();
+        for field in data.as_object().unwrap().keys() {
+            assert!(properties.contains_key(field), "{}.{}", name, field);
+        }
+        for field in expected["required"].as_array().unwrap() {
+            assert!(data.get(field.as_str().unwrap()).is_some(), "{} misses {}", name, field);
+        }
+    }
+
+    let allocate = ControlCommand::data_schema("AllocateMemory").unwrap();
+    assert_eq!(allocate["properties"]["size_bytes"]["type"], "integer");
+    assert_eq!(allocate["required"], json!(["size_bytes", "region"]));
+    let cache_set = ControlCommand::data_schema("CacheSet").unwrap();
+    assert_eq!(cache_set["required"], json!(["key", "value"]));
+}
+
+#[tokio::test]
+async fn test_websocket_commands() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let (base, _) = spawn().await;
+    let url = format!("{}/ws", base.replace("http://", "ws://"));
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+    let welcome = socket.next().await.unwrap().unwrap();
+    let welcome: Value = serde_json::from_str(welcome.to_text().unwrap()).unwrap();
+    assert_eq!(welcome["type"], "welcome");
+
+    socket
+        .send(Message::Text(json!({ "cmd": "Health" }).to_string()))
+        .await
+        .unwrap();
+    let reply = socket.next().await.unwrap().unwrap();
+    let reply: Value = serde_json::from_str(reply.to_text().unwrap()).unwrap();
+    assert_eq!(reply["success"], true);
+
+    socket.send(Message::Text("{bad".to_string())).await.unwrap();
+    let reply = socket.next().await.unwrap().unwrap();
+    let reply: Value = serde_json::from_str(reply.to_text().unwrap()).unwrap();
+    assert_eq!(reply["success"], false);
+}
@@ -0,0 +1,230 @@
+//! Loopback tests for the VXLAN transport of the control plane
+//!
+//! Crafted datagrams go to a live UDP socket; replies must carry the request's
+//! VNI and each VNI must see only its own cache keys and routes, kept in a
+//! bounded number of tenant route tables and tagged on the events they publish.
+
+#![cfg(all(
+    feature = "qagml-integration",
+    feature = "qanban-integration",
+    feature = "uao-qtcam-integration",
+    feature = "gfef"
+))]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use symmetrix_core::control_plane::vxlan::{
+    self, VxlanError, VxlanHeader, VXLAN_HEADER_SIZE, VXLAN_VNI_CONTROL_PLANE,
+};
+use symmetrix_core::control_plane::{
+    CommandStatus, ControlCommand, ControlPlaneState, Namespace, Topic, MAX_TENANT_ROUTE_TABLES,
+};
+use tokio::net::UdpSocket;
+
+struct Client {
+    socket: UdpSocket,
+    state: Arc<ControlPlaneState>,
+}
+
+impl Client {
+    async fn spawn() -> Self {
+        let state = Arc::new(ControlPlaneState::new(4 * 1024 * 1024).unwrap());
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(vxlan::serve(server, state.clone()));
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket.connect(addr).await.unwrap();
+        Self { socket, state }
+    }
+
+    /// Send a raw frame and wait briefly for a reply
+    async fn send_raw(&self, frame: &[u8]) -> Option<Vec<u8>> {
+        self.socket.send(frame).await.unwrap();
+        let mut buf = vec![0u8; 65535];
+        match tokio::time::timeout(Duration::from_millis(300), self.socket.recv(&mut buf)).await {
+            Ok(Ok(len)) => Some(buf[..len].to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Send `command` on `vni`, returning the reply's VNI and response
+    async fn send(&self, vni: u32, command: Value) -> (u32, Value) {
+        let frame = VxlanHeader::new(vni)
+            .unwrap()
+            .encapsulate(command.to_string().as_bytes());
+        let reply = self.send_raw(&frame).await.expect("no reply");
+        let (header, payload) = VxlanHeader::parse(&reply).unwrap();
+        (header.vni, serde_json::from_slice(payload).unwrap())
+    }
+}
+
+fn frame(header: [u8; VXLAN_HEADER_SIZE]) -> Vec<u8> {
+    let mut frame = header.to_vec();
+    frame.extend_from_slice(br#"{"cmd":"Health"}"#);
+    frame
+}
+
+#[test]
+fn test_header_codec() {
+    let header = VxlanHeader::new(0x123456).unwrap();
+    assert_eq!(header.to_bytes(), [0x08, 0, 0, 0, 0x12, 0x34, 0x56, 0]);
+    let frame = header.encapsulate(b"payload");
+    assert_eq!(VxlanHeader::parse(&frame).unwrap(), (header, &b"payload"[..]));
+
+    assert_eq!(VxlanHeader::parse(&[0x08, 0, 0]), Err(VxlanError::TooShort(3)));
+    assert_eq!(
+        VxlanHeader::parse(&[0, 0, 0, 0, 0, 0, 1, 0]),
+        Err(VxlanError::MissingVniFlag(0))
+    );
+    for reserved in [
+        [0x88, 0, 0, 0, 0, 0, 1, 0],
+        [0x08, 0, 1, 0, 0, 0, 1, 0],
+        [0x08, 0, 0, 0, 0, 0, 1, 0xff],
+    ] {
+        assert_eq!(VxlanHeader::parse(&reserved), Err(VxlanError::ReservedBits));
+    }
+    assert_eq!(VxlanHeader::new(1 << 24), Err(VxlanError::VniOutOfRange(1 << 24)));
+
+    assert_eq!(Namespace::from_vni(VXLAN_VNI_CONTROL_PLANE), Namespace::Global);
+    assert_eq!(Namespace::from_vni(7), Namespace::Tenant(7));
+}
+
+#[tokio::test]
+async fn test_replies_carry_the_vni() {
+    let client = Client::spawn().await;
+    for vni in [0, 42, VXLAN_VNI_CONTROL_PLANE] {
+        let (reply_vni, response) = client.send(vni, json!({ "cmd": "Health" })).await;
+        assert_eq!(reply_vni, vni);
+        assert_eq!(response["success"], true);
+        assert_eq!(response["data"]["status"], "healthy");
+    }
+}
+
+#[tokio::test]
+async fn test_vni_namespaces_cache_keys() {
+    let client = Client::spawn().await;
+    let set = |value: &str| json!({ "cmd": "CacheSet", "data": { "key": "shared", "value": value } });
+    let get = json!({ "cmd": "CacheGet", "data": { "key": "shared" } });
+
+    // Single-byte values: the cache codec keeps only sampled bytes
+    client.send(100, set("A")).await;
+    client.send(200, set("B")).await;
+
+    let (_, response) = client.send(100, get.clone()).await;
+    assert_eq!(response["data"]["value"], "A");
+    assert_eq!(response["data"]["key"], "shared");
+    let (_, response) = client.send(200, get.clone()).await;
+    assert_eq!(response["data"]["value"], "B");
+    let (_, response) = client.send(300, get.clone()).await;
+    assert_eq!(response["success"], false);
+
+    // The control VNI is the global key space, shared with HTTP
+    let (_, response) = client.send(VXLAN_VNI_CONTROL_PLANE, get).await;
+    assert_eq!(response["success"], false);
+    client.send(VXLAN_VNI_CONTROL_PLANE, set("G")).await;
+    assert_eq!(client.state.cache.get("shared").unwrap().unwrap(), b"G");
+    assert_eq!(client.state.cache.get("vni:100:shared").unwrap().unwrap(), b"A");
+}
+
+#[tokio::test]
+async fn test_vni_namespaces_routes() {
+    let client = Client::spawn().await;
+    let insert = json!({ "cmd": "InsertRoute", "data": { "key": "167772160", "value": "gw-100", "priority": 1 } });
+    let lookup = json!({ "cmd": "Lookup", "data": { "key": "10.0.0.9" } });
+
+    let (_, response) = client.send(100, insert).await;
+    assert_eq!(response["success"], true, "{}", response);
+
+    let (_, response) = client.send(100, lookup.clone()).await;
+    assert_eq!(response["data"]["next_hop"], "gw-100", "{}", response);
+    let (_, response) = client.send(200, lookup.clone()).await;
+    assert_eq!(response["success"], false, "{}", response);
+    let (_, response) = client.send(VXLAN_VNI_CONTROL_PLANE, lookup).await;
+    assert!(response["data"].is_null(), "{}", response);
+    // Only the route created a table
+    assert_eq!(client.state.tenant_routes.read().await.keys().collect::<Vec<_>>(), [&100]);
+}
+
+#[tokio::test]
+async fn test_tenant_route_tables_are_bounded() {
+    let state = ControlPlaneState::new(4 * 1024 * 1024).unwrap();
+    let insert = || ControlCommand::from_parts("InsertRoute", json!({ "key": "167772160", "value": "gw", "priority": 1 })).unwrap();
+    let delete = || ControlCommand::from_parts("DeleteRoute", json!({ "key": "167772160" })).unwrap();
+    let lookup = ControlCommand::from_parts("Lookup", json!({ "key": "10.0.0.9" })).unwrap();
+
+    assert_eq!(state.execute_in(Namespace::Tenant(1), lookup).await.0, CommandStatus::NotFound);
+    assert_eq!(state.execute_in(Namespace::Tenant(1), delete()).await.0, CommandStatus::NotFound);
+    assert!(state.tenant_routes.read().await.is_empty());
+
+    for vni in 1..=MAX_TENANT_ROUTE_TABLES as u32 {
+        assert_eq!(state.execute_in(Namespace::Tenant(vni), insert()).await.0, CommandStatus::Ok);
+    }
+    let extra = Namespace::Tenant(MAX_TENANT_ROUTE_TABLES as u32 + 1);
+    assert_eq!(state.execute_in(extra, insert()).await.0, CommandStatus::Rejected);
+    assert_eq!(state.execute_in(extra, delete()).await.0, CommandStatus::NotFound);
+
+    // Existing tables still take routes
+    assert_eq!(state.execute_in(Namespace::Tenant(7), insert()).await.0, CommandStatus::Ok);
+    assert_eq!(state.tenant_routes.read().await.len(), MAX_TENANT_ROUTE_TABLES);
+}
+
+#[tokio::test]
+async fn test_events_carry_the_namespace() {
+    let state = ControlPlaneState::new(4 * 1024 * 1024).unwrap();
+    let mut events = state.subscribe();
+    let set = || ControlCommand::from_parts("CacheSet", json!({ "key": "k", "value": "v" })).unwrap();
+    let insert = ControlCommand::from_parts("InsertRoute", json!({ "key": "167772160", "value": "gw", "priority": 1 })).unwrap();
+
+    state.execute_in(Namespace::Tenant(100), set()).await;
+    state.execute_in(Namespace::Tenant(100), insert).await;
+    state.execute(set()).await;
+
+    let namespaces: Vec<_> = (0..3)
+        .map(|_| {
+            let event = events.try_recv().unwrap();
+            (event.topic, event.namespace)
+        })
+        .collect();
+    assert_eq!(
+        namespaces,
+        [
+            (Topic::Keyspace, Namespace::Tenant(100)),
+            (Topic::Routes, Namespace::Tenant(100)),
+            (Topic::Keyspace, Namespace::Global),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_malformed_frames_are_counted() {
+    let client = Client::spawn().await;
+
+    // Bad headers are dropped without a reply
+    for bad in [
+        vec![0x08, 0, 0],
+        frame([0x00, 0, 0, 0, 0, 0, 1, 0]),
+        frame([0x0c, 0, 0, 0, 0, 0, 1, 0]),
+        frame([0x08, 0, 0, 1, 0, 0, 1, 0]),
+        frame([0x08, 0, 0, 0, 0, 0, 1, 1]),
+    ] {
+        assert!(client.send_raw(&bad).await.is_none(), "{:?}", bad);
+    }
+
+    // A bad payload under a good header is answered with an error
+    let reply = client
+        .send_raw(&VxlanHeader::new(9).unwrap().encapsulate(b"{\"cmd\":"))
+        .await
+        .unwrap();
+    let (header, payload) = VxlanHeader::parse(&reply).unwrap();
+    assert_eq!(header.vni, 9);
+    let response: Value = serde_json::from_slice(payload).unwrap();
+    assert_eq!(response["success"], false);
+    assert!(response["message"].as_str().unwrap().starts_with("Invalid command"));
+
+    let (_, response) = client.send(9, json!({ "cmd": "Stats" })).await;
+    assert_eq!(response["data"]["vxlan_malformed"], 6);
+    assert_eq!(response["data"]["vxlan_packets"], 7);
+}
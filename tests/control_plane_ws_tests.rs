@@ -0,0 +1,258 @@
+//! Loopback tests for the control-plane WebSocket channel
+//!
+//! Upgrades are authenticated, subscribed topics stream global events to
+//! roles allowed to see them, command frames are rate limited and slow
+//! consumers are told what they missed.
+
+#![cfg(all(
+    feature = "qagml-integration",
+    feature = "qanban-integration",
+    feature = "uao-qtcam-integration",
+    feature = "gfef"
+))]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use symmetrix_core::control_plane::http;
+use symmetrix_core::control_plane::ws::WsLimits;
+use symmetrix_core::control_plane::{ControlCommand, ControlPlaneState, Namespace, Topic, EVENT_BUFFER};
+use symmetrix_core::grpc::auth::AuthManager;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn spawn(state: ControlPlaneState) -> (String, Arc<ControlPlaneState>) {
+    let state = Arc::new(state);
+    let app = http::router(state.clone(), 64 * 1024);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("ws://{}/ws", addr), state)
+}
+
+fn state() -> ControlPlaneState {
+    ControlPlaneState::new(4 * 1024 * 1024).unwrap()
+}
+
+async fn authenticated_state() -> ControlPlaneState {
+    let auth = AuthManager::new(true);
+    auth.register_default_keys().await;
+    state().with_auth(Arc::new(auth))
+}
+
+async fn recv(socket: &mut Socket) -> Value {
+    let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+        .await
+        .expect("timed out")
+        .unwrap()
+        .unwrap();
+    serde_json::from_str(message.to_text().unwrap()).unwrap()
+}
+
+async fn send(socket: &mut Socket, frame: Value) -> Value {
+    socket.send(Message::Text(frame.to_string())).await.unwrap();
+    recv(socket).await
+}
+
+/// Connect and consume the welcome frame
+async fn connect(url: &str) -> (Socket, Value) {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    let welcome = recv(&mut socket).await;
+    assert_eq!(welcome["type"], "welcome");
+    (socket, welcome)
+}
+
+async fn subscribe(socket: &mut Socket, topics: &[&str]) {
+    let reply = send(socket, json!({ "cmd": "Subscribe", "data": { "topics": topics } })).await;
+    assert_eq!(reply["type"], "subscribed", "{}", reply);
+}
+
+fn rejected_status(result: Result<(Socket, impl Sized), WsError>) -> u16 {
+    match result {
+        Err(WsError::Http(response)) => response.status().as_u16(),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("upgrade was accepted"),
+    }
+}
+
+#[tokio::test]
+async fn test_upgrade_requires_api_key() {
+    let (url, _) = spawn(authenticated_state().await).await;
+
+    assert_eq!(rejected_status(tokio_tungstenite::connect_async(url.as_str()).await), 401);
+    let mut request = url.as_str().into_client_request().unwrap();
+    request.headers_mut().insert("x-api-key", "cf_wrong".parse().unwrap());
+    assert_eq!(rejected_status(tokio_tungstenite::connect_async(request).await), 401);
+
+    // Header, bearer token and query parameter are all accepted
+    let mut request = url.as_str().into_client_request().unwrap();
+    request.headers_mut().insert("x-api-key", "cf_ent_test123".parse().unwrap());
+    let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+    assert_eq!(recv(&mut socket).await["type"], "welcome");
+
+    let mut request = url.as_str().into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("authorization", "Bearer test-key-123".parse().unwrap());
+    let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+    assert_eq!(recv(&mut socket).await["type"], "welcome");
+
+    let (mut socket, welcome) = connect(&format!("{}?api_key=cf_ent_test123", url)).await;
+    assert!(welcome["org_id"].is_string());
+    assert_eq!(welcome["event_buffer"], EVENT_BUFFER);
+    assert_eq!(send(&mut socket, json!({ "cmd": "Health" })).await["success"], true);
+}
+
+#[tokio::test]
+async fn test_keyspace_and_route_events() {
+    let (url, _) = spawn(state()).await;
+    let (mut watcher, _) = connect(&url).await;
+    let (mut writer, _) = connect(&url).await;
+    subscribe(&mut watcher, &["keyspace", "routes"]).await;
+
+    send(&mut writer, json!({ "cmd": "CacheSet", "data": { "key": "k", "value": "v" } })).await;
+    let event = recv(&mut watcher).await;
+    assert_eq!(event["type"], "event");
+    assert_eq!(event["topic"], "keyspace");
+    assert_eq!(event["data"], json!({ "event": "set", "key": "k", "vni": null }));
+    assert!(event["timestamp"].is_u64());
+
+    send(&mut writer, json!({ "cmd": "CacheDelete", "data": { "key": "k" } })).await;
+    assert_eq!(recv(&mut watcher).await["data"]["event"], "del");
+
+    send(
+        &mut writer,
+        json!({ "cmd": "InsertRoute", "data": { "key": "167772160", "value": "gw-1", "priority": 5 } }),
+    )
+    .await;
+    let event = recv(&mut watcher).await;
+    assert_eq!(event["topic"], "routes");
+    assert_eq!(event["data"]["event"], "insert");
+    assert_eq!(event["data"]["next_hop"], "gw-1");
+
+    // Unsubscribing from routes keeps keyspace events flowing
+    let reply = send(&mut watcher, json!({ "cmd": "Unsubscribe", "data": { "topics": ["routes"] } })).await;
+    assert_eq!(reply["topics"], json!(["keyspace"]));
+    send(&mut writer, json!({ "cmd": "DeleteRoute", "data": { "key": "167772160" } })).await;
+    send(&mut writer, json!({ "cmd": "CacheIncr", "data": { "key": "n" } })).await;
+    let event = recv(&mut watcher).await;
+    assert_eq!(event["topic"], "keyspace");
+    assert_eq!(event["data"]["event"], "incr");
+
+    // An empty list clears every topic: commands get replies, no events
+    let reply = send(&mut watcher, json!({ "cmd": "Unsubscribe", "data": { "topics": [] } })).await;
+    assert_eq!(reply["topics"], json!([]));
+    send(&mut writer, json!({ "cmd": "CacheSet", "data": { "key": "k", "value": "w" } })).await;
+    assert_eq!(send(&mut watcher, json!({ "cmd": "Health" })).await["success"], true);
+}
+
+#[tokio::test]
+async fn test_calibration_and_stats_events() {
+    let (url, state) = spawn(state()).await;
+    let (mut socket, _) = connect(&url).await;
+    subscribe(&mut socket, &["calibration"]).await;
+
+    let reply = send(&mut socket, json!({ "cmd": "GetCalibrationMatrix", "data": { "tier": "basic" } })).await;
+    assert_eq!(reply["success"], true);
+    // The event follows the reply
+    let event = recv(&mut socket).await;
+    assert_eq!(event["topic"], "calibration");
+    assert_eq!(event["data"]["event"], "issued");
+    assert_eq!(event["data"]["session_id"], reply["data"]["session_id"]);
+    assert_eq!(event["data"]["expires_at"], reply["data"]["expires_at"]);
+
+    let invalid = send(&mut socket, json!({ "cmd": "Subscribe", "data": { "topics": ["weather"] } })).await;
+    assert_eq!(invalid["success"], false);
+    assert!(invalid["message"].as_str().unwrap().starts_with("Invalid subscription"));
+
+    // The publishers also announce a calibration rotation right away
+    send(&mut socket, json!({ "cmd": "Unsubscribe", "data": { "topics": ["calibration"] } })).await;
+    subscribe(&mut socket, &["stats"]).await;
+    let publishers = state.spawn_publishers(Duration::from_millis(20));
+    let event = recv(&mut socket).await;
+    assert_eq!(event["topic"], "stats");
+    assert!(event["data"]["commands_processed"].as_u64().unwrap() >= 1);
+    publishers.abort();
+}
+
+#[tokio::test]
+async fn test_command_rate_limit() {
+    let limits = WsLimits {
+        messages_per_second: 1,
+        burst: 2,
+    };
+    let (url, _) = spawn(state().with_ws_limits(limits)).await;
+    let (mut socket, _) = connect(&url).await;
+
+    for _ in 0..2 {
+        assert_eq!(send(&mut socket, json!({ "cmd": "Health" })).await["success"], true);
+    }
+    let limited = send(&mut socket, json!({ "cmd": "Health" })).await;
+    assert_eq!(limited["success"], false);
+    assert!(limited["message"].as_str().unwrap().starts_with("Rate limit exceeded"));
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(send(&mut socket, json!({ "cmd": "Health" })).await["success"], true);
+}
+
+#[tokio::test]
+async fn test_slow_consumer_is_told_it_lagged() {
+    let (url, state) = spawn(state()).await;
+    let (mut socket, _) = connect(&url).await;
+    subscribe(&mut socket, &["stats"]).await;
+
+    // The session task cannot run while this loop overflows its buffer
+    let extra = 10;
+    for i in 0..EVENT_BUFFER + extra {
+        state.publish(Topic::Stats, json!({ "seq": i }));
+    }
+
+    let notice = recv(&mut socket).await;
+    assert_eq!(notice, json!({ "type": "lagged", "missed": extra }));
+    // Delivery resumes with the oldest event still buffered
+    let event = recv(&mut socket).await;
+    assert_eq!(event["data"]["seq"], extra);
+}
+
+#[tokio::test]
+async fn test_subscriptions_need_the_topic_permission() {
+    // Keys without an assigned role are viewers
+    let (url, state) = spawn(authenticated_state().await).await;
+    let (mut socket, _) = connect(&format!("{}?api_key=cf_ent_test123", url)).await;
+
+    for topics in [json!(["keyspace"]), json!(["stats", "routes"])] {
+        let denied = send(&mut socket, json!({ "cmd": "Subscribe", "data": { "topics": topics } })).await;
+        assert_eq!(denied["success"], false, "{}", denied);
+        assert!(denied["message"].as_str().unwrap().contains("permission"), "{}", denied);
+    }
+    assert_eq!(state.stats.read().await.permission_denied, 2);
+
+    // Nothing was subscribed by the denied requests
+    let reply = send(&mut socket, json!({ "cmd": "Subscribe", "data": { "topics": ["stats"] } })).await;
+    assert_eq!(reply["topics"], json!(["stats"]));
+}
+
+#[tokio::test]
+async fn test_tenant_events_stay_off_the_channel() {
+    let (url, state) = spawn(state()).await;
+    let (mut socket, _) = connect(&url).await;
+    subscribe(&mut socket, &["keyspace", "routes"]).await;
+
+    let tenant = Namespace::Tenant(100);
+    let set = |key: &str| ControlCommand::from_parts("CacheSet", json!({ "key": key, "value": "v" })).unwrap();
+    let insert = ControlCommand::from_parts("InsertRoute", json!({ "key": "167772160", "value": "gw", "priority": 1 })).unwrap();
+    state.execute_in(tenant, set("private")).await;
+    state.execute_in(tenant, insert).await;
+    state.execute(set("public")).await;
+
+    let event = recv(&mut socket).await;
+    assert_eq!(event["data"], json!({ "event": "set", "key": "public", "vni": null }));
+}
@@ -0,0 +1,199 @@
+//! GFEF index upload, activation prediction and status
+//!
+//! Predictions must come from the uploaded signatures: the neurons whose
+//! signatures point along the input are the ones predicted active.
+
+#![cfg(feature = "gfef")]
+
+use serde_json::json;
+use symmetrix_core::gfef::{ActivationPredictor, CalibrationService, GFEFIndex, GfefError, SubscriptionManager};
+
+/// Layer 0: eight neurons on the unit circle of a two-component space,
+/// mapped from a three-wide input. Layer 1: metadata only.
+fn export() -> String {
+    let signatures: Vec<[f32; 2]> = (0..8)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+            [angle.cos(), angle.sin()]
+        })
+        .collect();
+    json!({
+        "model": "toy",
+        "k_components": 2,
+        "fft_bins": 8,
+        "target_sparsity": 0.75,
+        "layers": [
+            {
+                "layer_id": 0,
+                "name": "mlp.0",
+                "neurons": 8,
+                "principal_components": [[1, 0, 0], [0, 1, 0]],
+                "signatures": signatures,
+            },
+            { "layer_id": 1, "name": "mlp.1", "neurons": 16, "pc_shape": [3, 2] },
+        ],
+    })
+    .to_string()
+}
+
+#[test]
+fn test_upload_builds_layer_index() {
+    let index = GFEFIndex::from_export("", &export()).unwrap();
+    assert_eq!(index.model_id, "toy");
+    assert_eq!(index.total_neurons, 24);
+    assert_eq!(index.config.k_components, 2);
+    assert_eq!(index.config.fft_bins, 8);
+
+    let layer = index.layer(0).unwrap();
+    assert_eq!((layer.input_dim, layer.k_components), (3, 2));
+    assert!(layer.has_signatures());
+    let metadata = index.layer(1).unwrap();
+    assert_eq!(metadata.input_dim, 3);
+    assert!(!metadata.has_signatures());
+
+    // The serialized form round-trips
+    let json = serde_json::to_string(&index).unwrap();
+    assert_eq!(serde_json::from_str::<GFEFIndex>(&json).unwrap(), index);
+
+    // An explicit model id wins over the document's name
+    assert_eq!(GFEFIndex::from_export("renamed", &export()).unwrap().model_id, "renamed");
+}
+
+#[test]
+fn test_upload_rejects_bad_indices() {
+    for bad in [
+        json!({ "layers": [] }),
+        json!({ "model": "m", "layers": [] }),
+        json!({ "model": "m", "layers": [{ "neurons": 2, "signatures": [[1.0, 0.0]] }] }),
+        json!({ "model": "m", "layers": [{ "neurons": 2, "signatures": [[1.0, 0.0], [1.0]] }] }),
+        json!({ "model": "m", "layers": [{ "neurons": 1 }, { "neurons": 1 }] }),
+        json!({ "model": "m", "target_sparsity": 1.0, "layers": [{ "neurons": 1 }] }),
+    ] {
+        let result = GFEFIndex::from_export("", &bad.to_string());
+        assert!(matches!(result, Err(GfefError::InvalidIndex(_))), "{}", bad);
+    }
+    assert!(GFEFIndex::from_export("m", "not json").is_err());
+}
+
+#[test]
+fn test_predict_from_index() {
+    let mut predictor = ActivationPredictor::new(0.75);
+    assert_eq!(
+        predictor.predict("toy", 0, "abc"),
+        Err(GfefError::UnknownModel("toy".to_string()))
+    );
+    predictor.register_index(GFEFIndex::from_export("", &export()).unwrap());
+
+    // Input along +x: neurons at 0°, ±45° score highest; 2 of 8 are kept
+    let prediction = predictor.predict_embedding("toy", 0, &[1.0, 0.2, 5.0]).unwrap();
+    assert_eq!(prediction.active_neurons, vec![0, 1]);
+    assert_eq!(prediction.sparsity, 0.75);
+    assert!(prediction.confidence > 0.0 && prediction.confidence <= 1.0);
+
+    let prediction = predictor.predict_embedding("toy", 0, &[0.0, -1.0, 0.0]).unwrap();
+    assert_eq!(prediction.active_neurons, vec![5, 6]);
+
+    // Hashes are stable and select from the layer
+    let a = predictor.predict("toy", 0, "input-a").unwrap();
+    assert_eq!(a, predictor.predict("toy", 0, "input-a").unwrap());
+    assert_eq!(a.active_neurons.len(), 2);
+    assert!(a.active_neurons.iter().all(|&n| n < 8));
+
+    assert_eq!(
+        predictor.predict_embedding("toy", 0, &[1.0]),
+        Err(GfefError::DimensionMismatch { layer_id: 0, expected: 3, actual: 1 })
+    );
+    assert_eq!(predictor.predict("toy", 1, "x"), Err(GfefError::NoSignatures(1)));
+    assert!(matches!(predictor.predict("toy", 9, "x"), Err(GfefError::UnknownLayer { .. })));
+
+    // Each index keeps its own share of the layer, whatever the predictor default
+    let mut half: serde_json::Value = serde_json::from_str(&export()).unwrap();
+    half["target_sparsity"] = json!(0.5);
+    let half = GFEFIndex::from_export("half", &half.to_string()).unwrap();
+    let mut other = ActivationPredictor::new(0.95);
+    other.register_index(half);
+    let prediction = other.predict_embedding("half", 0, &[1.0, 0.2, 5.0]).unwrap();
+    assert_eq!(prediction.active_neurons, vec![0, 1, 2, 7]);
+    assert_eq!(prediction.sparsity, 0.5);
+
+    let stats = predictor.stats();
+    assert_eq!(stats.models_loaded, 1);
+    assert_eq!(stats.total_layers, 2);
+    assert_eq!(stats.predictions_served, 4);
+}
+
+#[test]
+fn test_calibration_epochs_and_subscriptions() {
+    let calibration = CalibrationService::new(60);
+    let epoch = calibration.epoch_at(125);
+    assert_eq!((epoch.epoch, epoch.issued_at, epoch.expires_at), (2, 120, 180));
+    assert_eq!(epoch, calibration.epoch_at(179));
+    assert_ne!(epoch.fingerprint, calibration.epoch_at(180).fingerprint);
+    assert!(calibration.is_valid_at(2, 200));
+    assert!(!calibration.is_valid_at(2, 240));
+
+    let mut subscriptions = SubscriptionManager::new();
+    subscriptions.subscribe("c-1", "toy", "basic");
+    subscriptions.subscribe("c-1", "toy", "pro");
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions.subscriptions("c-1")[0].tier, "pro");
+    assert!(subscriptions.is_subscribed("c-1", "toy"));
+    assert!(subscriptions.unsubscribe("c-1", "toy").is_some());
+    assert!(subscriptions.is_empty());
+}
+
+#[cfg(all(
+    feature = "qagml-integration",
+    feature = "qanban-integration",
+    feature = "uao-qtcam-integration"
+))]
+#[tokio::test]
+async fn test_control_commands() {
+    use symmetrix_core::control_plane::{CommandStatus, ControlCommand, ControlPlaneState, Namespace};
+
+    let state = ControlPlaneState::new(4 * 1024 * 1024).unwrap();
+    let predict = |model: &str| ControlCommand::PredictActivation {
+        customer_id: "c-1".to_string(),
+        model_id: model.to_string(),
+        layer_index: 0,
+        input_hash: "input-a".to_string(),
+    };
+
+    let (status, response) = state.execute(ControlCommand::GetGfefStatus).await;
+    assert_eq!(status, CommandStatus::Ok);
+    assert_eq!(response.data.unwrap()["status"], "no_index");
+    assert_eq!(state.execute(predict("toy")).await.0, CommandStatus::NotFound);
+
+    let upload = ControlCommand::UploadGfefIndex {
+        model_id: "toy".to_string(),
+        index_data: export(),
+    };
+    let (status, response) = state.execute(upload).await;
+    assert_eq!(status, CommandStatus::Ok, "{}", response.message);
+    assert_eq!(response.data.unwrap()["num_layers"], 2);
+
+    let (status, response) = state.execute(predict("toy")).await;
+    assert_eq!(status, CommandStatus::Ok);
+    let data = response.data.unwrap();
+    let expected = state.gfef.read().await.predict("toy", 0, "input-a").unwrap();
+    assert_eq!(data["active_neurons"], json!(expected.active_neurons));
+    assert_eq!(data["index_id"], json!(expected.index_id));
+
+    let bad = ControlCommand::UploadGfefIndex {
+        model_id: "bad".to_string(),
+        index_data: "{}".to_string(),
+    };
+    assert_eq!(state.execute(bad).await.0, CommandStatus::Rejected);
+
+    // Tenants see only their own indices
+    let (_, response) = state.execute_in(Namespace::Tenant(7), ControlCommand::GetGfefStatus).await;
+    assert_eq!(response.data.unwrap()["models_indexed"], 0);
+    assert_eq!(state.execute_in(Namespace::Tenant(7), predict("toy")).await.0, CommandStatus::NotFound);
+
+    let (_, response) = state.execute(ControlCommand::GetGfefStatus).await;
+    let data = response.data.unwrap();
+    assert_eq!(data["status"], "active");
+    assert_eq!(data["models"][0]["model_id"], "toy");
+    assert_eq!(data["models"][0]["layers_with_signatures"], 1);
+    assert_eq!(data["predictions_served"], 2);
+}
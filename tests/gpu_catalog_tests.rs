@@ -0,0 +1,381 @@
+//! Integration tests for the GPU catalog
+//!
+//! Tests loading TOML/YAML overrides on top of the built-in catalog,
+//! validation errors, detection aliases, the ReloadGpuCatalog admin RPC and
+//! refusing to start with a catalog that does not load.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use symmetrix_core::grpc::gpu_catalog::{CatalogFormat, GpuCatalog, GpuCatalogError};
+use symmetrix_core::grpc::gpu_detection::{BaselineGpuType, GpuDetectionManager, GpuProbe};
+use symmetrix_core::grpc::proto::gpu_detection_service_server::GpuDetectionService;
+use symmetrix_core::grpc::proto::{ListSupportedGpusRequest, ReloadGpuCatalogRequest};
+use symmetrix_core::grpc::server::CyanFlameGrpcServer;
+use symmetrix_core::grpc::{GpuDetectionServiceImpl, GrpcServerConfig};
+use tonic::Request;
+
+const EXAMPLE_CATALOG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/config/gpu_catalog.example.toml");
+
+const YAML_CATALOG: &str = r#"
+gpus:
+  - gpu_type: nvidia_rtx_5080
+    vram_gb: 16
+    memory_bandwidth_gbs: 960.0
+    fp16_tflops: 450.0
+    tier:
+      pricing_tier: standard
+      max_concurrent_sessions: 6
+  - gpu_type: AMD_RX_7900_XT
+    vram_gb: 20
+    memory_bandwidth_gbs: 800.0
+"#;
+
+fn write_temp(extension: &str, content: &str) -> tempfile::NamedTempFile {
+    let mut file = tempfile::Builder::new()
+        .suffix(&format!(".{}", extension))
+        .tempfile()
+        .expect("create temp file");
+    file.write_all(content.as_bytes()).expect("write temp file");
+    file
+}
+
+/// Expect an `Invalid` error at the given entry and field
+fn assert_invalid(content: &str, format: CatalogFormat, index: usize, field: &str) -> String {
+    let err = GpuCatalog::parse_overrides(content, format).expect_err("catalog should be rejected");
+    match &err {
+        GpuCatalogError::Invalid { index: i, field: f, .. } if *i == index && *f == field => err.to_string(),
+        other => panic!("expected gpus[{}].{} to be rejected, got {:?}", index, field, other),
+    }
+}
+
+/// Built-in catalog covers every supported GPU with its own type
+#[test]
+fn test_builtin_catalog_complete() {
+    let catalog = GpuCatalog::builtin();
+    assert_eq!(catalog.len(), BaselineGpuType::SUPPORTED.len());
+    assert_eq!(catalog.source(), "builtin");
+
+    for gpu_type in BaselineGpuType::SUPPORTED {
+        let entry = catalog.get(gpu_type).expect("every supported type is cataloged");
+        assert_eq!(entry.gpu_type(), gpu_type);
+        assert_eq!(BaselineGpuType::from_key(gpu_type.key()), Some(gpu_type));
+        assert!(entry.tier_config().pricing_multiplier > 0.0, "{:?}", gpu_type);
+    }
+
+    // Types that borrow a sibling's specs still get their own tier
+    let rtx_5080 = catalog.get(BaselineGpuType::NvidiaRtx5080).unwrap();
+    assert_eq!(rtx_5080.tier_config().tier_name, "consumer_premium_mid");
+}
+
+/// The shipped example file loads and overrides only what it names
+#[test]
+fn test_load_example_toml() {
+    let catalog = GpuCatalog::load(EXAMPLE_CATALOG).expect("example catalog is valid");
+    let builtin = GpuCatalog::builtin();
+    assert_eq!(catalog.len(), builtin.len());
+    assert!(catalog.source().ends_with("gpu_catalog.example.toml"));
+
+    let a30 = catalog.get(BaselineGpuType::NvidiaA30).unwrap();
+    assert_eq!(a30.specs.memory_bandwidth_gbs, 933.0);
+    assert_eq!(a30.specs.compute_capability, (8, 0));
+    assert_eq!(a30.tier_config().tier_name, "inference_mid");
+    assert_eq!(a30.tier_config().pricing_multiplier, 0.7);
+    // Unset tier fields keep the computed value
+    assert_eq!(
+        a30.tier_config().max_concurrent_sessions,
+        builtin.get(BaselineGpuType::NvidiaA30).unwrap().tier_config().max_concurrent_sessions
+    );
+
+    let mi300x = catalog.get(BaselineGpuType::AmdMi300X).unwrap();
+    assert_eq!(mi300x.tier_config().pricing_multiplier, 1.1);
+    assert_eq!(mi300x.specs.vram_gb, 192);
+
+    // Untouched entries are identical to the built-in table
+    let h100 = catalog.get(BaselineGpuType::NvidiaH100).unwrap();
+    assert_eq!(h100.tier_config().tier_name, "benchmark");
+}
+
+/// YAML files load the same way, with case-insensitive GPU keys
+#[test]
+fn test_load_yaml() {
+    let file = write_temp("yaml", YAML_CATALOG);
+    let catalog = GpuCatalog::load(file.path()).expect("yaml catalog is valid");
+
+    let rtx_5080 = catalog.get(BaselineGpuType::NvidiaRtx5080).unwrap();
+    assert_eq!(rtx_5080.specs.fp16_tflops, 450.0);
+    assert_eq!(rtx_5080.tier_config().pricing_tier, "standard");
+    assert_eq!(rtx_5080.tier_config().max_concurrent_sessions, 6);
+
+    let rx_7900_xt = catalog.get(BaselineGpuType::AmdRx7900Xt).unwrap();
+    assert_eq!(rx_7900_xt.specs.vram_gb, 20);
+
+    let empty = write_temp("yml", "");
+    assert_eq!(GpuCatalog::load(empty.path()).unwrap().len(), catalog.len());
+}
+
+/// Overrides layer: a second file merges onto the first
+#[test]
+fn test_merge_layers() {
+    let base = GpuCatalog::load(EXAMPLE_CATALOG).unwrap();
+    let file = symmetrix_core::grpc::gpu_catalog::GpuCatalogFile::parse(
+        "[[gpus]]\ngpu_type = \"nvidia_a30\"\n[gpus.tier]\nrate_limit_per_minute = 42\n",
+        CatalogFormat::Toml,
+    )
+    .unwrap();
+    let merged = base.merge(&file).unwrap();
+
+    let tier = merged.get(BaselineGpuType::NvidiaA30).unwrap().tier_config();
+    assert_eq!(tier.rate_limit_per_minute, 42);
+    assert_eq!(tier.tier_name, "inference_mid");
+    assert_eq!(tier.pricing_multiplier, 0.7);
+}
+
+/// Malformed files are rejected with the entry index and field
+#[test]
+fn test_reject_malformed_entries() {
+    let toml = CatalogFormat::Toml;
+
+    let msg = assert_invalid("[[gpus]]\ngpu_type = \"nvidia_h900\"\n", toml, 0, "gpu_type");
+    assert!(msg.contains("gpus[0] (nvidia_h900)"), "{}", msg);
+
+    let msg = assert_invalid(
+        "[[gpus]]\ngpu_type = \"nvidia_h100\"\n[[gpus]]\ngpu_type = \"nvidia_a30\"\nvram_gb = 0\n",
+        toml,
+        1,
+        "vram_gb",
+    );
+    assert_eq!(msg, "gpus[1] (nvidia_a30): vram_gb must be greater than 0");
+
+    assert_invalid("[[gpus]]\ngpu_type = \"nvidia_a10\"\nmemory_bandwidth_gbs = -5.0\n", toml, 0, "memory_bandwidth_gbs");
+    assert_invalid("[[gpus]]\ngpu_type = \"nvidia_a10\"\nvendor = \"Intel\"\n", toml, 0, "vendor");
+    assert_invalid("[[gpus]]\ngpu_type = \"nvidia_a10\"\nsupports_fp8 = true\n", toml, 0, "fp8_tflops");
+    assert_invalid("[[gpus]]\ngpu_type = \"nvidia_a10\"\npci_ids = [\"10de-2236\"]\n", toml, 0, "pci_ids");
+    assert_invalid("[[gpus]]\ngpu_type = \"nvidia_a10\"\nname_patterns = [\"--\"]\n", toml, 0, "name_patterns");
+    assert_invalid("[[gpus]]\ngpu_type = \"nvidia_a10\"\n[gpus.tier]\npricing_multiplier = -1.0\n", toml, 0, "tier.pricing_multiplier");
+    assert_invalid("[[gpus]]\ngpu_type = \"nvidia_a10\"\n[gpus.tier]\ntier_name = \"\"\n", toml, 0, "tier.tier_name");
+    assert_invalid(
+        "[[gpus]]\ngpu_type = \"nvidia_a10\"\n[[gpus]]\ngpu_type = \"NVIDIA_A10\"\n",
+        toml,
+        1,
+        "gpu_type",
+    );
+    assert_invalid("gpus:\n  - gpu_type: amd_mi100\n    fp16_tflops: 0\n", CatalogFormat::Yaml, 0, "fp16_tflops");
+}
+
+/// Syntax errors, unknown fields and wrong types report the location
+#[test]
+fn test_reject_parse_errors() {
+    let err = GpuCatalog::parse_overrides("[[gpus]]\ngpu_type = \"nvidia_a10\"\nvram = 24\n", CatalogFormat::Toml)
+        .unwrap_err();
+    let msg = err.to_string();
+    assert!(matches!(err, GpuCatalogError::Parse("toml", _)));
+    assert!(msg.contains("vram") && msg.contains("line 3"), "{}", msg);
+
+    let err = GpuCatalog::parse_overrides("gpus:\n  - gpu_type: nvidia_a10\n    vram_gb: lots\n", CatalogFormat::Yaml)
+        .unwrap_err();
+    let msg = err.to_string();
+    assert!(matches!(err, GpuCatalogError::Parse("yaml", _)));
+    assert!(msg.contains("vram_gb") && msg.contains("line 3"), "{}", msg);
+
+    let err = GpuCatalog::parse_overrides("[[gpus]\n", CatalogFormat::Toml).unwrap_err();
+    assert!(matches!(err, GpuCatalogError::Parse("toml", _)));
+
+    let file = write_temp("json", "{}");
+    assert!(matches!(GpuCatalog::load(file.path()), Err(GpuCatalogError::UnsupportedFormat(_))));
+    assert!(matches!(
+        GpuCatalog::load("/nonexistent/gpu_catalog.toml"),
+        Err(GpuCatalogError::Read(_, _))
+    ));
+}
+
+/// Catalog name patterns and PCI IDs feed detection
+#[test]
+fn test_catalog_aliases_detect() {
+    let manager = GpuDetectionManager::new();
+    assert_eq!(manager.detect_gpu_type("NVIDIA A800-SXM4-80GB"), BaselineGpuType::Unknown);
+
+    manager.replace_catalog(GpuCatalog::load(EXAMPLE_CATALOG).unwrap());
+    assert_eq!(manager.detect_gpu_type("NVIDIA A800-SXM4-80GB"), BaselineGpuType::NvidiaA100);
+
+    let detection = manager.detect(&GpuProbe {
+        pci_device_id: "10de:20f5".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(detection.gpu_type, BaselineGpuType::NvidiaA100);
+    assert_eq!(detection.confidence, 1.0);
+}
+
+/// ReloadGpuCatalog requires the admin key and ListSupportedGpus reflects the result
+#[tokio::test]
+async fn test_reload_rpc() {
+    let manager = Arc::new(GpuDetectionManager::new());
+    let service = GpuDetectionServiceImpl::with_manager(manager.clone()).with_admin_key("admin-secret");
+
+    let list = service
+        .list_supported_gpus(Request::new(ListSupportedGpusRequest::default()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(list.supported_gpus.len(), BaselineGpuType::SUPPORTED.len());
+
+    // Wrong key
+    let denied = service
+        .reload_gpu_catalog(Request::new(ReloadGpuCatalogRequest {
+            api_key: "nope".to_string(),
+            path: EXAMPLE_CATALOG.to_string(),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(denied.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+    // Reload inline content
+    let reloaded = service
+        .reload_gpu_catalog(Request::new(ReloadGpuCatalogRequest {
+            api_key: "admin-secret".to_string(),
+            content: std::fs::read_to_string(EXAMPLE_CATALOG).unwrap(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(reloaded.success, "{}", reloaded.error_message);
+    assert_eq!(reloaded.gpu_count as usize, BaselineGpuType::SUPPORTED.len());
+
+    let list = service
+        .list_supported_gpus(Request::new(ListSupportedGpusRequest::default()))
+        .await
+        .unwrap()
+        .into_inner();
+    let a30 = list
+        .supported_gpus
+        .iter()
+        .find(|g| g.gpu_type == BaselineGpuType::NvidiaA30.to_proto())
+        .unwrap();
+    assert_eq!(a30.memory_bandwidth_gbs, 933.0);
+    let rx = list
+        .supported_gpus
+        .iter()
+        .find(|g| g.gpu_type == BaselineGpuType::AmdRx7900Xtx.to_proto())
+        .unwrap();
+    assert_eq!(rx.vendor, "AMD");
+
+    // Invalid inline catalog keeps the previous one
+    let rejected = service
+        .reload_gpu_catalog(Request::new(ReloadGpuCatalogRequest {
+            api_key: "admin-secret".to_string(),
+            content: "gpus:\n  - gpu_type: nvidia_a30\n    vram_gb: 0\n".to_string(),
+            format: "yaml".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!rejected.success);
+    assert_eq!(rejected.error_message, "gpus[0] (nvidia_a30): vram_gb must be greater than 0");
+    assert_eq!(rejected.source, reloaded.source);
+    assert_eq!(
+        manager.catalog_entry(BaselineGpuType::NvidiaA30).unwrap().specs.memory_bandwidth_gbs,
+        933.0
+    );
+
+    // Empty request resets to the built-in catalog
+    let reset = service
+        .reload_gpu_catalog(Request::new(ReloadGpuCatalogRequest {
+            api_key: "admin-secret".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(reset.success);
+    assert_eq!(reset.source, "builtin");
+}
+
+/// ReloadGpuCatalog reads no file other than the configured catalog
+#[tokio::test]
+async fn test_reload_rpc_restricted_to_configured_path() {
+    let configured = write_temp("toml", "[[gpus]]\ngpu_type = \"nvidia_a30\"\nvram_gb = 24\n");
+    let configured_path = configured.path().to_str().unwrap().to_string();
+    let service = GpuDetectionServiceImpl::new()
+        .with_admin_key("admin-secret")
+        .with_catalog_path(configured_path.clone())
+        .unwrap();
+
+    let denied = service
+        .reload_gpu_catalog(Request::new(ReloadGpuCatalogRequest {
+            api_key: "admin-secret".to_string(),
+            path: EXAMPLE_CATALOG.to_string(),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(denied.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+    for path in [configured_path.clone(), String::new()] {
+        let reloaded = service
+            .reload_gpu_catalog(Request::new(ReloadGpuCatalogRequest {
+                api_key: "admin-secret".to_string(),
+                path,
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(reloaded.success, "{}", reloaded.error_message);
+        assert_eq!(reloaded.source, configured_path);
+    }
+}
+
+/// A compute capability override moves the GPU between fallback rows
+#[test]
+fn test_compute_capability_override_feeds_fallback() {
+    let manager = GpuDetectionManager::new();
+    assert_eq!(manager.detect_from_compute_capability(9, 0), Some(BaselineGpuType::NvidiaH100));
+    assert_eq!(manager.detect_from_compute_capability(10, 0), None);
+
+    let catalog = GpuCatalog::parse_overrides(
+        "[[gpus]]\ngpu_type = \"nvidia_h200\"\ncompute_capability = [10, 0]\n",
+        CatalogFormat::Toml,
+    )
+    .unwrap();
+    manager.replace_catalog(catalog);
+
+    assert_eq!(manager.detect_from_compute_capability(10, 0), Some(BaselineGpuType::NvidiaH200));
+    // No longer a 9.0 candidate, even with matching VRAM
+    assert_eq!(
+        manager.detect_from_compute_capability_and_memory(9, 0, Some(143_771)),
+        Some(BaselineGpuType::NvidiaH100)
+    );
+}
+
+/// Admin RPCs are rejected when no admin key is configured
+#[tokio::test]
+async fn test_reload_rpc_disabled_without_admin_key() {
+    let service = GpuDetectionServiceImpl::new();
+    let result = service
+        .reload_gpu_catalog(Request::new(ReloadGpuCatalogRequest {
+            api_key: String::new(),
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+}
+
+#[test]
+fn test_invalid_catalog_fails_startup() {
+    let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    file.write_all(b"[[gpus]]\ngpu_type = \"nvidia_rtx_5080\"\nvram_gb = 0\n").unwrap();
+    for path in [file.path().display().to_string(), "/nonexistent/gpu_catalog.toml".to_string()] {
+        let config = GrpcServerConfig {
+            gpu_catalog_path: Some(path),
+            ..Default::default()
+        };
+        assert!(CyanFlameGrpcServer::with_config(config).is_err());
+    }
+
+    let config = GrpcServerConfig {
+        gpu_catalog_path: Some(EXAMPLE_CATALOG.to_string()),
+        ..Default::default()
+    };
+    assert!(CyanFlameGrpcServer::with_config(config).is_ok());
+}
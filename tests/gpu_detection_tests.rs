@@ -0,0 +1,277 @@
+//! Integration tests for GPU detection
+//!
+//! Covers every supported baseline GPU through each detection path:
+//! - Name matching (longest match, deterministic)
+//! - PCI vendor/device ID lookup
+//! - Compute capability + VRAM fallback
+
+use symmetrix_core::grpc::gpu_detection::{
+    BaselineGpuType, DetectionMethod, GpuDetectionManager, GpuProbe, GPU_COMPUTE_CAPABILITIES, GPU_PCI_IDS,
+};
+
+/// Names as reported by nvidia-smi / rocm-smi for every supported GPU
+fn reported_names() -> Vec<(&'static str, BaselineGpuType)> {
+    vec![
+        ("Tesla V100-SXM2-32GB", BaselineGpuType::NvidiaV100),
+        ("NVIDIA A100-SXM4-80GB", BaselineGpuType::NvidiaA100),
+        ("NVIDIA H100 80GB HBM3", BaselineGpuType::NvidiaH100),
+        ("NVIDIA H200", BaselineGpuType::NvidiaH200),
+        ("NVIDIA L40S", BaselineGpuType::NvidiaL40S),
+        ("NVIDIA A10G", BaselineGpuType::NvidiaA10),
+        ("NVIDIA A30", BaselineGpuType::NvidiaA30),
+        ("NVIDIA A40", BaselineGpuType::NvidiaA40),
+        ("Tesla T4", BaselineGpuType::NvidiaTeslaT4),
+        ("NVIDIA GeForce RTX 4090", BaselineGpuType::NvidiaRtx4090),
+        ("NVIDIA GeForce RTX 4080", BaselineGpuType::NvidiaRtx4080),
+        ("NVIDIA GeForce RTX 4070 Ti", BaselineGpuType::NvidiaRtx4070Ti),
+        ("NVIDIA GeForce RTX 5090", BaselineGpuType::NvidiaRtx5090),
+        ("NVIDIA GeForce RTX 5080", BaselineGpuType::NvidiaRtx5080),
+        ("NVIDIA GeForce RTX 3090", BaselineGpuType::NvidiaRtx3090),
+        ("NVIDIA GeForce RTX 3090 Ti", BaselineGpuType::NvidiaRtx3090Ti),
+        ("NVIDIA GeForce RTX 3080", BaselineGpuType::NvidiaRtx3080),
+        ("AMD Instinct MI100", BaselineGpuType::AmdMi100),
+        ("AMD Instinct MI250X", BaselineGpuType::AmdMi250),
+        ("AMD Instinct MI300X", BaselineGpuType::AmdMi300X),
+        ("AMD Instinct MI325X", BaselineGpuType::AmdMi325X),
+        ("AMD Radeon RX 7900 XTX", BaselineGpuType::AmdRx7900Xtx),
+        ("AMD Radeon RX 7900 XT", BaselineGpuType::AmdRx7900Xt),
+    ]
+}
+
+/// Every supported type is detected from its canonical and reported names
+#[test]
+fn test_name_detection_covers_every_type() {
+    let manager = GpuDetectionManager::new();
+
+    for gpu_type in BaselineGpuType::SUPPORTED {
+        assert_eq!(manager.detect_gpu_type(gpu_type.name()), gpu_type, "{}", gpu_type.name());
+    }
+
+    let reported = reported_names();
+    for gpu_type in BaselineGpuType::SUPPORTED {
+        assert!(
+            reported.iter().any(|(_, t)| *t == gpu_type),
+            "no reported name fixture for {:?}",
+            gpu_type
+        );
+    }
+    for (name, expected) in reported {
+        let detection = manager.detect(&GpuProbe::from_name(name));
+        assert_eq!(detection.gpu_type, expected, "{}", name);
+        assert_eq!(detection.method, DetectionMethod::Name);
+    }
+}
+
+/// Overlapping names resolve to the longest match regardless of table order
+#[test]
+fn test_name_detection_longest_match() {
+    let manager = GpuDetectionManager::new();
+
+    let cases = [
+        ("NVIDIA L40S", BaselineGpuType::NvidiaL40S),
+        ("NVIDIA A10", BaselineGpuType::NvidiaA10),
+        ("NVIDIA A100 PCIe", BaselineGpuType::NvidiaA100),
+        ("GeForce RTX 3090 Ti", BaselineGpuType::NvidiaRtx3090Ti),
+        ("GeForce RTX3090", BaselineGpuType::NvidiaRtx3090),
+        ("Radeon RX 7900 XTX", BaselineGpuType::AmdRx7900Xtx),
+        ("Radeon RX 7900 XT", BaselineGpuType::AmdRx7900Xt),
+        ("Quadro RTX 8000", BaselineGpuType::Unknown),
+        ("", BaselineGpuType::Unknown),
+    ];
+
+    // Repeat to catch any order dependence
+    for _ in 0..16 {
+        for (name, expected) in cases {
+            assert_eq!(manager.detect_gpu_type(name), expected, "{}", name);
+        }
+    }
+}
+
+/// Every supported type has at least one PCI ID, and unique IDs are exact
+#[test]
+fn test_pci_detection_covers_every_type() {
+    let manager = GpuDetectionManager::new();
+
+    for gpu_type in BaselineGpuType::SUPPORTED {
+        assert!(
+            GPU_PCI_IDS.iter().any(|(_, _, c)| c.contains(&gpu_type)),
+            "no PCI ID for {:?}",
+            gpu_type
+        );
+    }
+
+    for (vendor, device, candidates) in GPU_PCI_IDS {
+        let probe = GpuProbe {
+            pci_device_id: format!("{:04x}:{:04x}", vendor, device),
+            ..Default::default()
+        };
+        let detection = manager.detect(&probe);
+        assert_eq!(detection.method, DetectionMethod::PciId);
+        assert_eq!(detection.gpu_type, candidates[0]);
+        if candidates.len() == 1 {
+            assert_eq!(detection.confidence, 1.0);
+        } else {
+            assert!(detection.confidence < 1.0);
+        }
+    }
+}
+
+/// PCI ID formats from nvidia-smi, lspci and bare device IDs
+#[test]
+fn test_pci_id_parsing() {
+    assert_eq!(GpuDetectionManager::parse_pci_id("10de:2684", ""), Some((0x10de, 0x2684)));
+    assert_eq!(GpuDetectionManager::parse_pci_id("0x268410DE", ""), Some((0x10de, 0x2684)));
+    assert_eq!(GpuDetectionManager::parse_pci_id("0x74a1", "AMD"), Some((0x1002, 0x74a1)));
+    assert_eq!(GpuDetectionManager::parse_pci_id("2330", "NVIDIA"), Some((0x10de, 0x2330)));
+    assert_eq!(GpuDetectionManager::parse_pci_id("2330", ""), None);
+    assert_eq!(GpuDetectionManager::parse_pci_id("not-an-id", "NVIDIA"), None);
+    assert_eq!(GpuDetectionManager::parse_pci_id("", "NVIDIA"), None);
+}
+
+/// PCI ID wins over a misleading name; a shared die is resolved by name
+#[test]
+fn test_pci_and_name_combined() {
+    let manager = GpuDetectionManager::new();
+
+    // Renamed/virtualised device: PCI says H100
+    let conflict = manager.detect(&GpuProbe {
+        name: "NVIDIA A100".to_string(),
+        pci_device_id: "10de:2330".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(conflict.gpu_type, BaselineGpuType::NvidiaH100);
+    assert!(conflict.confidence < 1.0);
+
+    // Navi 31 ships as both XTX and XT
+    for (name, expected) in [
+        ("AMD Radeon RX 7900 XTX", BaselineGpuType::AmdRx7900Xtx),
+        ("AMD Radeon RX 7900 XT", BaselineGpuType::AmdRx7900Xt),
+    ] {
+        let detection = manager.detect(&GpuProbe {
+            name: name.to_string(),
+            vendor: "AMD".to_string(),
+            pci_device_id: "0x744c".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(detection.gpu_type, expected);
+        assert_eq!(detection.method, DetectionMethod::PciId);
+        assert!(detection.confidence > 0.9);
+    }
+}
+
+/// Compute capability fallback, disambiguated by VRAM
+#[test]
+fn test_compute_capability_fallback() {
+    let manager = GpuDetectionManager::new();
+
+    let cases = [
+        ((7, 0), 32_768, BaselineGpuType::NvidiaV100),
+        ((7, 5), 15_360, BaselineGpuType::NvidiaTeslaT4),
+        ((8, 0), 81_920, BaselineGpuType::NvidiaA100),
+        ((8, 0), 24_576, BaselineGpuType::NvidiaA30),
+        ((8, 6), 49_152, BaselineGpuType::NvidiaA40),
+        ((8, 6), 10_240, BaselineGpuType::NvidiaRtx3080),
+        ((8, 9), 49_152, BaselineGpuType::NvidiaL40S),
+        ((8, 9), 24_564, BaselineGpuType::NvidiaRtx4090),
+        ((8, 9), 16_376, BaselineGpuType::NvidiaRtx4080),
+        ((8, 9), 12_282, BaselineGpuType::NvidiaRtx4070Ti),
+        ((9, 0), 81_559, BaselineGpuType::NvidiaH100),
+        ((9, 0), 143_771, BaselineGpuType::NvidiaH200),
+        ((12, 0), 32_607, BaselineGpuType::NvidiaRtx5090),
+        ((12, 0), 16_303, BaselineGpuType::NvidiaRtx5080),
+    ];
+
+    for ((major, minor), memory_mb, expected) in cases {
+        let detection = manager.detect(&GpuProbe {
+            name: "Unidentified Device".to_string(),
+            compute_capability: Some((major, minor)),
+            memory_mb: Some(memory_mb),
+            ..Default::default()
+        });
+        assert_eq!(detection.gpu_type, expected, "cc {}.{} {}MB", major, minor, memory_mb);
+        assert_eq!(detection.method, DetectionMethod::ComputeCapability);
+    }
+
+    // Without VRAM the confidence drops
+    let no_vram = manager.detect(&GpuProbe {
+        compute_capability: Some((9, 0)),
+        ..Default::default()
+    });
+    assert_eq!(no_vram.gpu_type, BaselineGpuType::NvidiaH100);
+    assert!(no_vram.confidence < 0.5);
+
+    assert_eq!(manager.detect_from_compute_capability(6, 1), None);
+    // 10.0 is data-center Blackwell, which has no baseline
+    assert_eq!(manager.detect_from_compute_capability(10, 0), None);
+}
+
+/// The RTX 3090 family shares 8.6 and 24 GB with the A10; the fallback picks
+/// the RTX 3090 and the A10 is left to its PCI ID or name
+#[test]
+fn test_compute_capability_fallback_for_rtx_3090() {
+    let (_, candidates) = GPU_COMPUTE_CAPABILITIES
+        .iter()
+        .find(|(cc, _)| *cc == (8, 6))
+        .unwrap();
+    for gpu_type in [BaselineGpuType::NvidiaRtx3090, BaselineGpuType::NvidiaRtx3090Ti] {
+        assert!(candidates.iter().any(|(candidate, _)| *candidate == gpu_type));
+    }
+
+    let manager = GpuDetectionManager::new();
+    let detection = manager.detect(&GpuProbe {
+        compute_capability: Some((8, 6)),
+        memory_mb: Some(24_576),
+        ..Default::default()
+    });
+    assert_eq!(detection.gpu_type, BaselineGpuType::NvidiaRtx3090);
+    assert_eq!(detection.method, DetectionMethod::ComputeCapability);
+
+    let a10 = manager.detect(&GpuProbe {
+        pci_device_id: "0x2236".to_string(),
+        vendor: "NVIDIA".to_string(),
+        compute_capability: Some((8, 6)),
+        memory_mb: Some(24_576),
+        ..Default::default()
+    });
+    assert_eq!(a10.gpu_type, BaselineGpuType::NvidiaA10);
+    assert_eq!(a10.method, DetectionMethod::PciId);
+
+    // The name settles it when the fallback alone cannot
+    let named = manager.detect(&GpuProbe {
+        name: "NVIDIA GeForce RTX 3090 Ti".to_string(),
+        compute_capability: Some((8, 6)),
+        memory_mb: Some(24_576),
+        ..Default::default()
+    });
+    assert_eq!(named.gpu_type, BaselineGpuType::NvidiaRtx3090Ti);
+    assert_eq!(named.method, DetectionMethod::Name);
+}
+
+/// Registration uses the full probe and records the detection
+#[tokio::test]
+async fn test_register_gpu_with_probe() {
+    let manager = GpuDetectionManager::new();
+
+    let registration = manager
+        .register_gpu(
+            "agent-cc",
+            "hash",
+            &GpuProbe {
+                compute_capability: Some((8, 9)),
+                memory_mb: Some(24_564),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .expect("compute capability fallback should register");
+    assert_eq!(registration.detected_gpu, BaselineGpuType::NvidiaRtx4090);
+    assert_eq!(registration.detection.method, DetectionMethod::ComputeCapability);
+
+    let err = manager
+        .register_gpu("agent-unknown", "hash", &GpuProbe::from_name("Matrox G200"), None)
+        .await
+        .unwrap_err();
+    assert!(err.contains("RX 7900 XTX"));
+    assert!(manager.get_registration("agent-unknown").await.is_none());
+}
@@ -0,0 +1,386 @@
+//! Integration tests for GPU registration persistence
+//!
+//! Runs the same checks against the in-memory and SQLite stores:
+//! - Re-registration is idempotent (one record, original registration time)
+//! - Registrations survive a restart
+//! - Inventory filters and per-model totals
+//! - A registration database that cannot be opened stops the server
+//! - Health reports refresh an agent's last-seen time
+
+#![cfg(feature = "sqlite-registry")]
+
+use std::sync::Arc;
+
+use symmetrix_core::grpc::gpu_detection::{BaselineGpuType, GpuDetectionManager, GpuProbe};
+use symmetrix_core::grpc::gpu_registry::{
+    InMemoryRegistrationStore, InventoryFilter, RegistrationStore, SqliteRegistrationStore,
+};
+use symmetrix_core::grpc::proto::gpu_detection_service_server::GpuDetectionService;
+use symmetrix_core::grpc::proto::telemetry_service_client::TelemetryServiceClient;
+use symmetrix_core::grpc::proto::telemetry_service_server::TelemetryServiceServer;
+use symmetrix_core::grpc::proto::{
+    DetectedGpuInfo, GpuInventoryRequest, GpuRegistrationRequest, HealthMetrics,
+};
+use symmetrix_core::grpc::server::CyanFlameGrpcServer;
+use symmetrix_core::grpc::telemetry::TelemetryServiceImpl;
+use symmetrix_core::grpc::{GpuDetectionServiceImpl, GrpcServerConfig};
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+use tonic::Request;
+
+fn probe(name: &str, memory_mb: u64, driver: &str) -> GpuProbe {
+    GpuProbe {
+        name: name.to_string(),
+        memory_mb: Some(memory_mb),
+        driver_version: driver.to_string(),
+        ..Default::default()
+    }
+}
+
+fn sqlite_store(dir: &tempfile::TempDir) -> Arc<dyn RegistrationStore> {
+    let path = dir.path().join("registrations.db");
+    Arc::new(SqliteRegistrationStore::open(path.to_str().unwrap()).expect("open sqlite store"))
+}
+
+/// Register a small fleet: 2× H100, 1× A100, 1× RTX 4090
+async fn register_fleet(manager: &GpuDetectionManager) {
+    let fleet = [
+        ("agent-a", "NVIDIA H100 80GB HBM3", 81_559),
+        ("agent-b", "NVIDIA H100 80GB HBM3", 81_559),
+        ("agent-c", "NVIDIA A100-SXM4-80GB", 81_920),
+        ("agent-d", "NVIDIA GeForce RTX 4090", 24_564),
+    ];
+    for (agent, name, memory_mb) in fleet {
+        manager
+            .register_gpu(agent, "hash", &probe(name, memory_mb, "550.54"), None)
+            .await
+            .expect("register");
+    }
+}
+
+async fn check_idempotent_reregistration(store: Arc<dyn RegistrationStore>) {
+    let manager = GpuDetectionManager::new().with_store(store.clone());
+
+    let first = manager
+        .register_gpu(
+            "agent-1",
+            "hash",
+            &probe("NVIDIA H100 80GB HBM3", 81_559, "550.54"),
+            None,
+        )
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    let second = manager
+        .register_gpu(
+            "agent-1",
+            "hash",
+            &probe("NVIDIA H100 80GB HBM3", 81_559, "555.42"),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(second.registered_at, first.registered_at);
+    assert!(second.last_seen > first.last_seen);
+
+    let records = store.list(&InventoryFilter::default()).await.unwrap();
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(record.agent_id, "agent-1");
+    assert_eq!(record.gpu_type, BaselineGpuType::NvidiaH100);
+    assert_eq!(record.vram_gb, 80);
+    assert_eq!(record.driver_version, "555.42");
+    assert_eq!(record.tier_name, "benchmark");
+    assert_eq!(record.detection_method, "name");
+    assert_eq!(record.registered_at, first.registered_at);
+
+    // Re-registering with a different GPU replaces the record
+    manager
+        .register_gpu(
+            "agent-1",
+            "hash",
+            &probe("NVIDIA A100-SXM4-80GB", 81_920, "555.42"),
+            None,
+        )
+        .await
+        .unwrap();
+    let records = store.list(&InventoryFilter::default()).await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].gpu_type, BaselineGpuType::NvidiaA100);
+}
+
+async fn check_inventory(manager: &GpuDetectionManager) {
+    let all = manager
+        .inventory(&InventoryFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(all.total_count(), 4);
+    assert_eq!(all.total_vram_gb(), 80 + 80 + 80 + 24);
+    let agents: Vec<_> = all.records.iter().map(|r| r.agent_id.as_str()).collect();
+    assert_eq!(agents, ["agent-a", "agent-b", "agent-c", "agent-d"]);
+
+    let h100 = all
+        .models
+        .iter()
+        .find(|m| m.gpu_type == BaselineGpuType::NvidiaH100)
+        .unwrap();
+    assert_eq!((h100.count, h100.total_vram_gb), (2, 160));
+    assert_eq!(all.models.len(), 3);
+
+    let by_model = manager
+        .inventory(&InventoryFilter {
+            gpu_type: Some(BaselineGpuType::NvidiaH100),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(by_model.total_count(), 2);
+
+    let by_tier = manager
+        .inventory(&InventoryFilter {
+            tier_name: Some("consumer_pro".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(by_tier.records.len(), 1);
+    assert_eq!(by_tier.records[0].agent_id, "agent-d");
+
+    let by_agent = manager
+        .inventory(&InventoryFilter {
+            agent_id: Some("agent-c".to_string()),
+            gpu_type: Some(BaselineGpuType::NvidiaA100),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(by_agent.total_count(), 1);
+
+    let none = manager
+        .inventory(&InventoryFilter {
+            agent_id: Some("agent-c".to_string()),
+            gpu_type: Some(BaselineGpuType::NvidiaH100),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(none.total_count(), 0);
+    assert!(none.models.is_empty());
+}
+
+#[tokio::test]
+async fn test_reregistration_idempotent_in_memory() {
+    check_idempotent_reregistration(Arc::new(InMemoryRegistrationStore::new())).await;
+}
+
+#[tokio::test]
+async fn test_reregistration_idempotent_sqlite() {
+    let dir = tempfile::tempdir().unwrap();
+    check_idempotent_reregistration(sqlite_store(&dir)).await;
+}
+
+#[tokio::test]
+async fn test_inventory_in_memory() {
+    let manager = GpuDetectionManager::new();
+    register_fleet(&manager).await;
+    check_inventory(&manager).await;
+}
+
+#[tokio::test]
+async fn test_inventory_sqlite() {
+    let dir = tempfile::tempdir().unwrap();
+    let manager = GpuDetectionManager::new().with_store(sqlite_store(&dir));
+    register_fleet(&manager).await;
+    check_inventory(&manager).await;
+}
+
+/// A new manager on the same database sees every registration
+#[tokio::test]
+async fn test_restart_recovery_sqlite() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let registered_at = {
+        let manager = GpuDetectionManager::new().with_store(sqlite_store(&dir));
+        register_fleet(&manager).await;
+        manager.update_last_seen("agent-a").await;
+        manager.get_registration("agent-a").await.unwrap()
+    };
+
+    let restarted = GpuDetectionManager::new().with_store(sqlite_store(&dir));
+    assert_eq!(restarted.restore().await.unwrap(), 4);
+    assert_eq!(restarted.get_all_registrations().await.len(), 4);
+
+    let agent_a = restarted.get_registration("agent-a").await.unwrap();
+    assert_eq!(agent_a.detected_gpu, BaselineGpuType::NvidiaH100);
+    assert_eq!(agent_a.driver_version, "550.54");
+    assert_eq!(agent_a.tier_config.tier_name, "benchmark");
+    assert_eq!(agent_a.registered_at, registered_at.registered_at);
+    assert_eq!(
+        agent_a.last_seen.timestamp_micros(),
+        registered_at.last_seen.timestamp_micros()
+    );
+
+    check_inventory(&restarted).await;
+
+    // Lookups without an explicit restore also hit the store
+    let lazy = GpuDetectionManager::new().with_store(sqlite_store(&dir));
+    assert!(lazy.get_registration("agent-d").await.is_some());
+    assert!(lazy.get_registration("agent-z").await.is_none());
+}
+
+/// The in-memory store does not survive a restart
+#[tokio::test]
+async fn test_in_memory_store_is_not_durable() {
+    let manager = GpuDetectionManager::new();
+    register_fleet(&manager).await;
+
+    let restarted = GpuDetectionManager::new();
+    assert_eq!(restarted.restore().await.unwrap(), 0);
+    assert!(restarted.get_registration("agent-a").await.is_none());
+}
+
+/// Health reports are the agents' heartbeat
+#[tokio::test]
+async fn test_health_reports_refresh_last_seen() {
+    let manager = Arc::new(GpuDetectionManager::new());
+    register_fleet(&manager).await;
+    let before = manager.get_registration("agent-a").await.unwrap().last_seen;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+    let telemetry = TelemetryServiceImpl::new().with_registrations(manager.clone());
+    tokio::spawn(
+        Server::builder()
+            .add_service(TelemetryServiceServer::new(telemetry))
+            .serve_with_incoming(incoming),
+    );
+
+    let mut client = TelemetryServiceClient::connect(format!("http://{}", addr)).await.unwrap();
+    let reports = ["agent-a", "unregistered"].map(|agent_id| HealthMetrics {
+        agent_id: agent_id.to_string(),
+        ..Default::default()
+    });
+    let mut acks = client
+        .report_health(tokio_stream::iter(reports))
+        .await
+        .unwrap()
+        .into_inner();
+    for _ in 0..2 {
+        assert!(acks.message().await.unwrap().unwrap().received);
+    }
+
+    assert!(manager.get_registration("agent-a").await.unwrap().last_seen > before);
+    assert!(manager.get_registration("unregistered").await.is_none());
+}
+
+/// The server refuses to start rather than keep registrations in memory
+#[tokio::test]
+async fn test_unopenable_database_fails_startup() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = GrpcServerConfig {
+        bind_addr: "127.0.0.1:0".to_string(),
+        registration_db_path: Some(dir.path().join("missing/registry.db").display().to_string()),
+        ..Default::default()
+    };
+    let server = CyanFlameGrpcServer::with_config(config).unwrap();
+    let serve = tokio::time::timeout(std::time::Duration::from_secs(10), server.serve());
+    assert!(serve.await.expect("server started").is_err());
+}
+
+#[tokio::test]
+async fn test_store_touch_and_remove() {
+    let dir = tempfile::tempdir().unwrap();
+    for store in [
+        Arc::new(InMemoryRegistrationStore::new()) as Arc<dyn RegistrationStore>,
+        sqlite_store(&dir),
+    ] {
+        let manager = GpuDetectionManager::new().with_store(store.clone());
+        register_fleet(&manager).await;
+
+        let later = chrono::Utc::now() + chrono::Duration::seconds(60);
+        assert!(store.touch("agent-b", later).await.unwrap());
+        assert!(!store.touch("agent-z", later).await.unwrap());
+        assert_eq!(
+            store
+                .get("agent-b")
+                .await
+                .unwrap()
+                .unwrap()
+                .last_seen
+                .timestamp(),
+            later.timestamp()
+        );
+
+        assert!(store.remove("agent-b").await.unwrap());
+        assert!(!store.remove("agent-b").await.unwrap());
+        assert!(store.get("agent-b").await.unwrap().is_none());
+    }
+}
+
+/// GetGpuInventory RPC: admin key, filters, per-model totals
+#[tokio::test]
+async fn test_inventory_rpc() {
+    let service = GpuDetectionServiceImpl::new().with_admin_key("admin-secret");
+
+    for (agent, name, memory_mb) in [
+        ("agent-a", "NVIDIA H100 80GB HBM3", 81_559),
+        ("agent-b", "NVIDIA H100 80GB HBM3", 81_559),
+        ("agent-c", "AMD Instinct MI300X", 196_592),
+    ] {
+        let response = service
+            .register_gpu(Request::new(GpuRegistrationRequest {
+                agent_id: agent.to_string(),
+                api_key: "agent-key".to_string(),
+                detected_gpu: Some(DetectedGpuInfo {
+                    name: name.to_string(),
+                    memory_total_mb: memory_mb,
+                    driver_version: "6.1.0".to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.success, "{}", response.error_message);
+    }
+
+    let denied = service
+        .get_gpu_inventory(Request::new(GpuInventoryRequest::default()))
+        .await;
+    assert_eq!(denied.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+    let all = service
+        .get_gpu_inventory(Request::new(GpuInventoryRequest {
+            api_key: "admin-secret".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(all.total_count, 3);
+    assert_eq!(all.total_vram_gb, 80 + 80 + 192);
+    assert_eq!(all.models.len(), 2);
+    assert_eq!(
+        all.models[0].gpu_type,
+        BaselineGpuType::NvidiaH100.to_proto()
+    );
+    assert_eq!(all.models[0].count, 2);
+    assert_eq!(all.registrations[2].driver_version, "6.1.0");
+    assert!(all.registrations[0].last_seen_ms > 0);
+
+    let amd = service
+        .get_gpu_inventory(Request::new(GpuInventoryRequest {
+            api_key: "admin-secret".to_string(),
+            tier_name: "amd_flagship".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(amd.total_count, 1);
+    assert_eq!(amd.registrations[0].agent_id, "agent-c");
+    assert_eq!(amd.models[0].total_vram_gb, 192);
+}
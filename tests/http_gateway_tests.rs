@@ -0,0 +1,390 @@
+//! Integration tests for the REST/JSON gateway
+//!
+//! Serves `CyanFlameGrpcServer::http_gateway()` on a loopback port and calls
+//! it over HTTP: JSON unary RPCs, auth, status mapping and SSE streams.
+
+use futures::StreamExt;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use symmetrix_core::grpc::gateway::http_status;
+use symmetrix_core::grpc::server::CyanFlameGrpcServer;
+use symmetrix_core::grpc::GrpcServerConfig;
+
+const ADMIN_KEY: &str = "admin-secret";
+const CALIBRATION_KEY: &str = "CYAN-FLAME-ENTERPRISE-a1b2c3d4";
+
+/// Start the gateway on an ephemeral port and return its base URL
+async fn spawn_gateway(auth_enabled: bool) -> String {
+    let config = GrpcServerConfig {
+        admin_api_key: Some(ADMIN_KEY.to_string()),
+        ..Default::default()
+    };
+    let server = CyanFlameGrpcServer::with_config_and_auth(config, auth_enabled).unwrap();
+    if auth_enabled {
+        server.auth_manager().register_default_keys().await;
+        server
+            .auth_manager()
+            .register_key(CALIBRATION_KEY, "enterprise", "calibration-org")
+            .await;
+    }
+    let app = server.http_gateway();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+async fn post(base: &str, rpc: &str, api_key: Option<&str>, body: Value) -> (StatusCode, Value) {
+    let mut request = reqwest::Client::new()
+        .post(format!("{}/v1/{}", base, rpc))
+        .json(&body);
+    if let Some(key) = api_key {
+        request = request.header("x-api-key", key);
+    }
+    let response = request.send().await.unwrap();
+    let status = response.status();
+    (status, response.json().await.unwrap())
+}
+
+/// Collect the `data:` payloads of a finished SSE response
+async fn sse_events(response: reqwest::Response) -> Vec<(String, Value)> {
+    let mut text = String::new();
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        text.push_str(&String::from_utf8_lossy(&chunk.unwrap()));
+    }
+
+    let mut events = Vec::new();
+    for block in text.split("\n\n") {
+        let mut event = "message".to_string();
+        for line in block.lines() {
+            if let Some(name) = line.strip_prefix("event:") {
+                event = name.trim().to_string();
+            } else if let Some(data) = line.strip_prefix("data:") {
+                events.push((event.clone(), serde_json::from_str(data.trim()).unwrap()));
+            }
+        }
+    }
+    events
+}
+
+#[tokio::test]
+async fn test_unary_rpcs_round_trip_json() {
+    let base = spawn_gateway(false).await;
+
+    // Empty body = default request message
+    let (status, gpus) = post(
+        &base,
+        "GpuDetectionService/ListSupportedGpus",
+        None,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(gpus["supported_gpus"].as_array().unwrap().len(), 23);
+
+    let (status, registration) = post(
+        &base,
+        "GpuDetectionService/RegisterGpu",
+        None,
+        json!({
+            "agent_id": "agent-http",
+            "api_key": "agent-key",
+            "detected_gpu": { "name": "NVIDIA H100 80GB HBM3", "memory_total_mb": 81559 }
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(registration["success"], true);
+    assert_eq!(registration["detection_method"], "name");
+    assert!(
+        registration["cost_estimate"]["hourly_rate_usd"]
+            .as_f64()
+            .unwrap()
+            > 0.0
+    );
+
+    // Allocation lifecycle
+    let (_, allocation) = post(
+        &base,
+        "AllocationService/AllocateMemory",
+        None,
+        json!({ "agent_id": "agent-http", "requested_tb": 12, "purpose": "inference" }),
+    )
+    .await;
+    assert_eq!(allocation["success"], true);
+    let allocation_id = allocation["allocation_id"].as_str().unwrap().to_string();
+
+    let (_, status_response) = post(
+        &base,
+        "AllocationService/GetAllocationStatus",
+        None,
+        json!({ "agent_id": "agent-http" }),
+    )
+    .await;
+    assert_eq!(status_response["total_allocated_tb"], 12);
+
+    let (_, freed) = post(
+        &base,
+        "AllocationService/FreeMemory",
+        None,
+        json!({ "allocation_id": allocation_id }),
+    )
+    .await;
+    assert_eq!(freed["freed_tb"], 12);
+}
+
+#[tokio::test]
+async fn test_gateway_uses_grpc_auth() {
+    let base = spawn_gateway(true).await;
+
+    let (status, error) = post(&base, "OperationsService/HealthCheck", None, json!({})).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(error["code"], tonic::Code::Unauthenticated as i32);
+    assert_eq!(error["error"], "Unauthenticated");
+
+    let (status, _) = post(
+        &base,
+        "OperationsService/HealthCheck",
+        Some("wrong-key"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, health) = post(
+        &base,
+        "OperationsService/HealthCheck",
+        Some("cf_pro_test123"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(health["healthy"], true);
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/v1/OperationsService/HealthCheck", base))
+        .header("authorization", "Bearer cf_ent_test123")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_gateway_enforces_rate_limits() {
+    let base = spawn_gateway(true).await;
+
+    // The free tier allows 100 requests a minute
+    for _ in 0..100 {
+        let (status, _) = post(&base, "OperationsService/HealthCheck", Some("cf_free_test123"), json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, error) = post(&base, "OperationsService/HealthCheck", Some("cf_free_test123"), json!({})).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(error["code"], tonic::Code::ResourceExhausted as i32);
+
+    // Streams count too, and other keys keep their own budget
+    let response = reqwest::Client::new()
+        .get(format!("{}/v1/DashboardMetricsService/StreamDashboardMetrics", base))
+        .header("x-api-key", "cf_free_test123")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let (status, _) = post(&base, "OperationsService/HealthCheck", Some("cf_pro_test123"), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_grpc_status_maps_to_http_status() {
+    let base = spawn_gateway(false).await;
+
+    // PermissionDenied: admin RPC without the admin key
+    let (status, error) = post(
+        &base,
+        "GpuDetectionService/GetGpuInventory",
+        None,
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(error["code"], tonic::Code::PermissionDenied as i32);
+
+    let (status, inventory) = post(
+        &base,
+        "GpuDetectionService/GetGpuInventory",
+        None,
+        json!({ "api_key": ADMIN_KEY }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(inventory["total_count"], 0);
+
+    // InvalidArgument from the service
+    let (status, _) = post(
+        &base,
+        "GpuDetectionService/GetUsage",
+        None,
+        json!({ "api_key": "k", "period_start_ms": 2000, "period_end_ms": 1000 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Malformed JSON never reaches the service
+    let response = reqwest::Client::new()
+        .post(format!("{}/v1/AllocationService/AllocateMemory", base))
+        .body("{not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error: Value = response.json().await.unwrap();
+    assert_eq!(error["code"], tonic::Code::InvalidArgument as i32);
+
+    let (status, _) = post(
+        &base,
+        "AllocationService/AllocateMemory",
+        None,
+        json!({ "requested_tb": "lots" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    assert_eq!(
+        http_status(tonic::Code::ResourceExhausted).as_u16(),
+        StatusCode::TOO_MANY_REQUESTS.as_u16()
+    );
+    assert_eq!(
+        http_status(tonic::Code::NotFound).as_u16(),
+        StatusCode::NOT_FOUND.as_u16()
+    );
+    assert_eq!(
+        http_status(tonic::Code::Unavailable).as_u16(),
+        StatusCode::SERVICE_UNAVAILABLE.as_u16()
+    );
+    assert_eq!(
+        http_status(tonic::Code::Internal).as_u16(),
+        StatusCode::INTERNAL_SERVER_ERROR.as_u16()
+    );
+}
+
+#[tokio::test]
+async fn test_bytes_fields_are_base64() {
+    let base = spawn_gateway(false).await;
+
+    let (status, matrix) = post(
+        &base,
+        "CalibrationService/GetCalibrationMatrix",
+        None,
+        json!({ "agent_id": "agent-http", "subscription_tier": "enterprise", "api_key": CALIBRATION_KEY }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let encoded = matrix["matrix_data"].as_str().expect("base64 string");
+    assert_eq!(base64_len(encoded), 64 * 64 * 8);
+
+    // The same matrix validates through the gateway
+    let (_, validation) = post(
+        &base,
+        "CalibrationService/ValidateMatrixVersion",
+        None,
+        json!({ "current_version": matrix["version"], "matrix_hash": matrix["matrix_hash"] }),
+    )
+    .await;
+    assert_eq!(validation["is_valid"], true);
+}
+
+fn base64_len(encoded: &str) -> usize {
+    encoded.len() / 4 * 3 - encoded.chars().rev().take_while(|c| *c == '=').count()
+}
+
+#[tokio::test]
+async fn test_server_streaming_as_sse() {
+    let base = spawn_gateway(false).await;
+
+    // GET with query parameters
+    let response = reqwest::get(format!(
+        "{}/v1/OperationsService/UpgradeAgent?agent_id=agent-http&target_version=2.1.0&force=true",
+        base
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/event-stream"));
+
+    let events = sse_events(response).await;
+    let stages: Vec<&str> = events
+        .iter()
+        .map(|(_, e)| e["stage"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        stages,
+        ["DOWNLOADING", "DOWNLOADING", "VERIFYING", "COMPLETE"]
+    );
+    assert!(events.iter().all(|(name, _)| name == "message"));
+    assert_eq!(events[3].1["success"], true);
+
+    // Errors before the stream opens are plain JSON errors
+    let (status, _) = post(
+        &base,
+        "CalibrationService/SubscribeCalibrationMatrix",
+        None,
+        json!({ "agent_id": "agent-http", "subscription_tier": "enterprise", "api_key": "bogus" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Open-ended streams deliver updates as they arrive
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}/v1/DashboardMetricsService/StreamDashboardMetrics",
+            base
+        ))
+        .json(&json!({ "refresh_interval_ms": 100 }))
+        .send()
+        .await
+        .unwrap();
+    let mut body = response.bytes_stream();
+    let first = body.next().await.unwrap().unwrap();
+    let first = String::from_utf8_lossy(&first);
+    let data = first.lines().find_map(|l| l.strip_prefix("data:")).unwrap();
+    let update: Value = serde_json::from_str(data.trim()).unwrap();
+    assert!(update["timestamp_ms"].as_i64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_route_listing() {
+    let base = spawn_gateway(false).await;
+
+    let routes: Value = reqwest::get(format!("{}/v1", base))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let routes = routes["routes"].as_array().unwrap();
+    assert_eq!(routes.len(), 32);
+    assert!(
+        routes.contains(&json!({ "path": "/v1/GpuDetectionService/GetUsage", "streaming": false }))
+    );
+    assert!(routes.contains(
+        &json!({ "path": "/v1/TelemetryService/SubscribeNetworkCapacity", "streaming": true })
+    ));
+
+    // Unary routes are POST-only; unknown RPCs are 404
+    let response = reqwest::get(format!("{}/v1/AllocationService/AllocateMemory", base))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    let response = reqwest::get(format!("{}/v1/AllocationService/Nope", base))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
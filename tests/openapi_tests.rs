@@ -0,0 +1,444 @@
+//! Integration tests for the generated OpenAPI document
+//!
+//! The document must describe every route the servers mount, every `$ref`
+//! must resolve, and live responses must match their schemas.
+
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use symmetrix_core::grpc::gateway;
+use symmetrix_core::grpc::http_api::{daemon_api, unified_api};
+use symmetrix_core::grpc::openapi::{ProtoSchemas, DOCS_PATH, OPENAPI_PATH, OPENAPI_VERSION};
+use symmetrix_core::grpc::server::CyanFlameGrpcServer;
+use symmetrix_core::grpc::HttpApi;
+
+fn unified() -> HttpApi {
+    let server = CyanFlameGrpcServer::with_config_and_auth(Default::default(), false).unwrap();
+    unified_api(false, server.http_gateway())
+}
+
+fn daemon() -> HttpApi {
+    let runtime = symmetrix_core::initialize(Default::default()).unwrap();
+    daemon_api(Arc::new(runtime))
+}
+
+async fn spawn(api: HttpApi) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = api.into_router();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+fn resolve<'a>(schema: &'a Value, doc: &'a Value) -> &'a Value {
+    match schema["$ref"].as_str() {
+        Some(reference) => {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            &doc["components"]["schemas"][name]
+        }
+        None => schema,
+    }
+}
+
+/// Minimal JSON Schema check covering the keywords the generator emits
+fn validate(schema: &Value, value: &Value, doc: &Value, at: &str) -> Result<(), String> {
+    let schema = resolve(schema, doc);
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(options) = schema[keyword].as_array() {
+            return if options.iter().any(|s| validate(s, value, doc, at).is_ok()) {
+                Ok(())
+            } else {
+                Err(format!("{}: {} matches no {} option", at, value, keyword))
+            };
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        return if constant == value {
+            Ok(())
+        } else {
+            Err(format!("{}: expected {}", at, constant))
+        };
+    }
+
+    let matches = match schema["type"].as_str() {
+        None => true,
+        Some("null") => value.is_null(),
+        Some("boolean") => value.is_boolean(),
+        Some("string") => value.is_string(),
+        Some("number") => value.is_number(),
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("array") => match value.as_array() {
+            Some(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    validate(&schema["items"], item, doc, &format!("{}[{}]", at, i))?;
+                }
+                true
+            }
+            None => false,
+        },
+        Some("object") => match value.as_object() {
+            Some(object) => {
+                let properties = schema["properties"].as_object();
+                for (name, field) in object {
+                    let field_schema = properties
+                        .and_then(|p| p.get(name))
+                        .or(schema.get("additionalProperties"))
+                        .ok_or_else(|| format!("{}.{} is not in the schema", at, name))?;
+                    validate(field_schema, field, doc, &format!("{}.{}", at, name))?;
+                }
+                for name in schema["required"].as_array().into_iter().flatten() {
+                    if !object.contains_key(name.as_str().unwrap()) {
+                        return Err(format!("{}.{} is required", at, name));
+                    }
+                }
+                true
+            }
+            None => false,
+        },
+        Some(other) => return Err(format!("{}: unknown type {}", at, other)),
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(format!("{}: {} is not {}", at, value, schema["type"]))
+    }
+}
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+                refs.push(reference);
+            }
+            object.values().for_each(|v| collect_refs(v, refs));
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+/// Routes the unified server mounts, kept by hand so the spec is checked
+/// against what is served rather than against itself
+const SERVED: [&str; 39] = [
+    "/",
+    "/health",
+    "/grpc",
+    "/tiers",
+    "/v1",
+    OPENAPI_PATH,
+    DOCS_PATH,
+    "/v1/CalibrationService/SubscribeCalibrationMatrix",
+    "/v1/CalibrationService/GetCalibrationMatrix",
+    "/v1/CalibrationService/ValidateMatrixVersion",
+    "/v1/ComputeCalibrationService/SubscribeComputeCalibration",
+    "/v1/ComputeCalibrationService/GetComputeCalibration",
+    "/v1/ComputeCalibrationService/GetEngineConfig",
+    "/v1/PCIeAmplificationService/SubscribePCIeCalibration",
+    "/v1/PCIeAmplificationService/GetPCIeConfig",
+    "/v1/TelemetryService/SubscribeNetworkCapacity",
+    "/v1/AllocationService/AllocateMemory",
+    "/v1/AllocationService/FreeMemory",
+    "/v1/AllocationService/RouteMemoryRequest",
+    "/v1/AllocationService/GetAllocationStatus",
+    "/v1/OperationsService/HealthCheck",
+    "/v1/OperationsService/UpgradeAgent",
+    "/v1/OperationsService/GetSystemInfo",
+    "/v1/OperationsService/RestartAgent",
+    "/v1/GpuDetectionService/RegisterGpu",
+    "/v1/GpuDetectionService/GetGpuTierConfig",
+    "/v1/GpuDetectionService/ListSupportedGpus",
+    "/v1/GpuDetectionService/ReloadGpuCatalog",
+    "/v1/GpuDetectionService/GetGpuInventory",
+    "/v1/GpuDetectionService/GetUsage",
+    "/v1/CertificateService/RequestCertificate",
+    "/v1/CertificateService/RenewCertificate",
+    "/v1/CertificateService/RevokeCertificate",
+    "/v1/CertificateService/GetCertificateStatus",
+    "/v1/CertificateService/GetCRL",
+    "/v1/CertificateService/CheckCertificateOCSP",
+    "/v1/DashboardMetricsService/StreamDashboardMetrics",
+    "/v1/DashboardMetricsService/GetConnectedAgents",
+    "/v1/DashboardMetricsService/GetSystemSummary",
+];
+
+#[tokio::test]
+async fn test_every_route_is_documented() {
+    let doc = unified().document();
+    assert_eq!(doc["openapi"], OPENAPI_VERSION);
+    let base = spawn(unified()).await;
+    let client = reqwest::Client::new();
+
+    for path in SERVED {
+        // No route answers PUT: a mounted path says 405, an unknown one 404
+        let status = client
+            .put(format!("{}{}", base, path))
+            .send()
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(
+            status,
+            StatusCode::METHOD_NOT_ALLOWED,
+            "{} is not served",
+            path
+        );
+        assert!(
+            doc["paths"][path].is_object(),
+            "{} is not in the spec",
+            path
+        );
+    }
+    let status = client
+        .put(format!("{}/v1/NoSuchService/Nothing", base))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let paths = doc["paths"].as_object().unwrap();
+    for path in paths.keys() {
+        assert!(
+            SERVED.contains(&path.as_str()),
+            "{} is documented but not served",
+            path
+        );
+    }
+    assert_eq!(paths.len(), SERVED.len());
+
+    // Every gateway RPC, with typed request and response
+    for route in gateway::routes() {
+        let post = &doc["paths"][route.path]["post"];
+        assert!(post.is_object(), "{} is not in the spec", route.path);
+        assert!(post["requestBody"]["content"]["application/json"]["schema"]["$ref"].is_string());
+        let content_type = if route.streaming {
+            "text/event-stream"
+        } else {
+            "application/json"
+        };
+        assert!(post["responses"]["200"]["content"][content_type]["schema"]["$ref"].is_string());
+        assert_eq!(doc["paths"][route.path]["get"].is_object(), route.streaming);
+    }
+    for path in SERVED.iter().filter(|path| !path.starts_with("/v1/")) {
+        assert!(
+            doc["paths"][path]["get"].is_object(),
+            "GET {} is not in the spec",
+            path
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_refs_resolve() {
+    let doc = unified().document();
+    let mut refs = Vec::new();
+    collect_refs(&doc, &mut refs);
+    assert!(refs.len() > 100);
+    for reference in refs {
+        let name = reference.strip_prefix("#/components/schemas/").unwrap();
+        assert!(
+            doc["components"]["schemas"][name].is_object(),
+            "dangling {}",
+            reference
+        );
+    }
+
+    // Proto comments and enums carry through
+    let schemas = &doc["components"]["schemas"];
+    let registration = &schemas["GpuRegistrationRequest"]["properties"];
+    assert!(registration["detected_gpu"]["anyOf"][0]["$ref"].is_string());
+    assert!(registration["target_gpu"]["enum"]
+        .as_array()
+        .unwrap()
+        .contains(&json!(99)));
+    let target_doc = registration["target_gpu"]["description"].as_str().unwrap();
+    assert!(target_doc.starts_with("Target GPU to emulate"));
+    assert!(target_doc.contains("TARGET_CUSTOM = 99"));
+    assert_eq!(
+        schemas["CalibrationMatrix"]["properties"]["matrix_data"]["format"],
+        "byte"
+    );
+    assert_eq!(
+        schemas["EngineConfigResponse"]["properties"]["engine_factors"]["additionalProperties"]
+            ["type"],
+        "number"
+    );
+}
+
+#[tokio::test]
+async fn test_spec_routes_are_served() {
+    let api = unified();
+    let doc = api.document();
+    let base = spawn(api).await;
+    let client = reqwest::Client::new();
+
+    for (path, item) in doc["paths"].as_object().unwrap() {
+        for method in item.as_object().unwrap().keys() {
+            let url = format!("{}{}", base, path);
+            let request = match method.as_str() {
+                "get" => client.get(&url),
+                "post" => client.post(&url).json(&json!({})),
+                other => panic!("unexpected method {}", other),
+            };
+            // Streams stay open; the status line is enough
+            let response = request.send().await.unwrap();
+            assert_ne!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{} {}",
+                method,
+                path
+            );
+            if response.status() == StatusCode::NOT_FOUND {
+                // NOT_FOUND from the service carries a gateway error body; the router's has none
+                let body: Value = response.json().await.unwrap_or_default();
+                assert!(body["code"].is_i64(), "{} {} is not routed", method, path);
+            }
+        }
+    }
+
+    // The gateway's own listing agrees with the spec
+    let listing: Value = reqwest::get(format!("{}/v1", base))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    validate(
+        &doc["paths"]["/v1"]["get"]["responses"]["200"]["content"]["application/json"]["schema"],
+        &listing,
+        &doc,
+        "/v1",
+    )
+    .unwrap();
+    for route in listing["routes"].as_array().unwrap() {
+        assert!(doc["paths"][route["path"].as_str().unwrap()].is_object());
+    }
+
+    let served: Value = reqwest::get(format!("{}{}", base, OPENAPI_PATH))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(served, doc);
+}
+
+#[tokio::test]
+async fn test_responses_match_schemas() {
+    let api = unified();
+    let doc = api.document();
+    let base = spawn(api).await;
+
+    for path in ["/", "/health", "/grpc", "/tiers"] {
+        let body: Value = reqwest::get(format!("{}{}", base, path))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let schema =
+            &doc["paths"][path]["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+        validate(schema, &body, &doc, path).unwrap();
+    }
+
+    let client = reqwest::Client::new();
+    for (path, request) in [
+        ("/v1/GpuDetectionService/ListSupportedGpus", json!({})),
+        (
+            "/v1/CalibrationService/GetCalibrationMatrix",
+            json!({ "agent_id": "agent-spec", "subscription_tier": "enterprise", "api_key": "CYAN-FLAME-ENTERPRISE-a1b2c3d4" }),
+        ),
+        (
+            "/v1/ComputeCalibrationService/GetEngineConfig",
+            json!({ "agent_id": "agent-spec" }),
+        ),
+        (
+            "/v1/OperationsService/GetSystemInfo",
+            json!({ "agent_id": "agent-spec" }),
+        ),
+    ] {
+        let operation = &doc["paths"][path]["post"];
+        validate(
+            &operation["requestBody"]["content"]["application/json"]["schema"],
+            &request,
+            &doc,
+            path,
+        )
+        .unwrap();
+        let body: Value = client
+            .post(format!("{}{}", base, path))
+            .json(&request)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let schema = &operation["responses"]["200"]["content"]["application/json"]["schema"];
+        validate(schema, &body, &doc, path).unwrap();
+    }
+
+    // Gateway errors use the documented error body
+    let body: Value = client
+        .post(format!("{}/v1/AllocationService/AllocateMemory", base))
+        .body("{not json")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let schema = &doc["paths"]["/v1/AllocationService/AllocateMemory"]["post"]["responses"]
+        ["default"]["content"]["application/json"]["schema"];
+    validate(schema, &body, &doc, "error").unwrap();
+}
+
+#[tokio::test]
+async fn test_docs_viewer_works_offline() {
+    let base = spawn(unified()).await;
+    let response = reqwest::get(format!("{}{}", base, DOCS_PATH))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+
+    let html = response.text().await.unwrap();
+    assert!(html.contains("openapi.json"));
+    for external in ["http://", "https://", "<link", "src="] {
+        assert!(!html.contains(external), "viewer references {}", external);
+    }
+}
+
+#[tokio::test]
+async fn test_daemon_routes_are_documented() {
+    let api = daemon();
+    let doc = api.document();
+    let paths: Vec<&String> = doc["paths"].as_object().unwrap().keys().collect();
+    assert_eq!(paths, ["/docs", "/health", "/metrics", "/openapi.json"]);
+
+    let base = spawn(api).await;
+    let metrics: Value = reqwest::get(format!("{}/metrics", base))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let schema = &doc["paths"]["/metrics"]["get"]["responses"]["200"]["content"]
+        ["application/json"]["schema"];
+    validate(schema, &metrics, &doc, "/metrics").unwrap();
+    assert!(validate(schema, &json!({ "containers_active": 1 }), &doc, "/metrics").is_err());
+}
+
+#[test]
+fn test_lfs_pointer_is_not_a_descriptor_set() {
+    let pointer = b"version https://git-lfs.github.com/spec/v1\noid sha256:0000\nsize 1\n";
+    assert!(ProtoSchemas::from_descriptor_set(pointer).is_err());
+    assert!(!ProtoSchemas::load().schemas().is_empty());
+}
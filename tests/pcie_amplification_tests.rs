@@ -204,3 +204,353 @@ fn galois_multiply(a: u32, b: u32, poly: u32) -> u32 {
     result & 0xFF
 }
 
+
+// ============================================================================
+// CLOSED-LOOP TUNING (ReportPCIeMetrics)
+// ============================================================================
+
+use symmetrix_core::grpc::pcie_tuning::{
+    DeviceTuner, PcieSample, PcieTuningConfig, PcieTuningRegistry, TuningAction, TuningParams,
+};
+
+const REPORT_INTERVAL_MS: i64 = 1000;
+
+/// Synthetic PCIe device: throughput grows with bytes in flight until the
+/// bandwidth-delay product, after which only latency grows. Errors appear
+/// once the device is driven far past saturation.
+struct SyntheticDevice {
+    capacity_gbs: f64,
+    bdp_bytes: f64,
+    base_latency_us: f64,
+    timestamp_ms: i64,
+}
+
+impl SyntheticDevice {
+    fn new() -> Self {
+        Self {
+            capacity_gbs: 25.0,
+            bdp_bytes: 48.0 * 64.0 * 1024.0,
+            base_latency_us: 10.0,
+            timestamp_ms: 0,
+        }
+    }
+
+    fn run(&mut self, params: TuningParams) -> PcieSample {
+        self.timestamp_ms += REPORT_INTERVAL_MS;
+        let load = params.queue_depth as f64 * params.payload_bytes as f64 / self.bdp_bytes;
+        let throughput_gbs = self.capacity_gbs * load.min(1.0);
+        let bytes = (throughput_gbs * 1e9 * REPORT_INTERVAL_MS as f64 / 1000.0) as u64;
+        let transfers = bytes / params.payload_bytes as u64;
+        let errors = if load > 2.0 { transfers / 100 } else { 0 };
+
+        PcieSample {
+            timestamp_ms: self.timestamp_ms,
+            bytes_transferred: bytes,
+            transfers_count: transfers,
+            avg_latency_us: self.base_latency_us * load.max(1.0).powi(2),
+            error_count: errors,
+        }
+    }
+}
+
+fn run_loop(
+    tuner: &mut DeviceTuner,
+    reports: usize,
+    mut device: impl FnMut(TuningParams) -> PcieSample,
+) -> Vec<(TuningAction, TuningParams)> {
+    let mut history = Vec::with_capacity(reports);
+    let mut params = tuner.params();
+    for _ in 0..reports {
+        let outcome = tuner.observe(device(params));
+        params = outcome.params;
+        history.push((outcome.action, params));
+    }
+    history
+}
+
+/// Test the AIMD loop converges near the device's saturation point
+#[test]
+fn test_tuning_converges_on_synthetic_device() {
+    let mut device = SyntheticDevice::new();
+    let bdp = device.bdp_bytes;
+    let capacity = device.capacity_gbs;
+    let mut tuner = DeviceTuner::new(PcieTuningConfig::default());
+
+    let history = run_loop(&mut tuner, 300, |p| device.run(p));
+
+    // Started well below saturation and must have probed upward
+    assert!(history.iter().any(|(a, _)| *a == TuningAction::Increase));
+    assert!(history.iter().any(|(a, _)| *a == TuningAction::Decrease));
+
+    // Steady state: bytes in flight stay within a band around the BDP
+    for (_, params) in &history[200..] {
+        let in_flight = params.queue_depth as f64 * params.payload_bytes as f64;
+        assert!(
+            in_flight >= 0.5 * bdp && in_flight <= 1.6 * bdp,
+            "in-flight {} outside band around BDP {}",
+            in_flight,
+            bdp
+        );
+    }
+
+    // Steady-state throughput stays close to link capacity
+    let mut probe = SyntheticDevice::new();
+    let avg: f64 = history[200..]
+        .iter()
+        .map(|(_, p)| {
+            let s = probe.run(*p);
+            s.bytes_transferred as f64 / 1e9
+        })
+        .sum::<f64>()
+        / 100.0;
+    assert!(avg >= 0.75 * capacity, "average throughput {:.2} GB/s too low", avg);
+
+    // Never drove the device into its error region
+    assert!(history[200..].iter().all(|(_, p)| {
+        (p.queue_depth as f64 * p.payload_bytes as f64) <= 2.0 * bdp
+    }));
+}
+
+/// Test the recommendation stays within configured bounds
+#[test]
+fn test_tuning_respects_bounds() {
+    let config = PcieTuningConfig {
+        max_queue_depth: 16,
+        max_payload_bytes: 128 * 1024,
+        ..Default::default()
+    };
+    let mut tuner = DeviceTuner::new(config.clone());
+    let mut ts = 0;
+
+    // A device that never congests: the controller grows to the ceiling and stops
+    let history = run_loop(&mut tuner, 200, |p| {
+        ts += REPORT_INTERVAL_MS;
+        PcieSample {
+            timestamp_ms: ts,
+            bytes_transferred: p.queue_depth as u64 * p.payload_bytes as u64 * 1000,
+            transfers_count: p.queue_depth as u64 * 1000,
+            avg_latency_us: 10.0,
+            error_count: 0,
+        }
+    });
+
+    for (_, p) in &history {
+        assert!(p.queue_depth >= config.min_queue_depth && p.queue_depth <= config.max_queue_depth);
+        assert!(p.payload_bytes >= config.min_payload_bytes && p.payload_bytes <= config.max_payload_bytes);
+        assert_eq!(p.payload_bytes % config.payload_step_bytes, 0);
+    }
+    let last = history.last().unwrap().1;
+    assert_eq!(last.queue_depth, 16);
+    assert_eq!(last.payload_bytes, 128 * 1024);
+}
+
+/// Test errors shrink both queue depth and payload
+#[test]
+fn test_tuning_backs_off_on_errors() {
+    let mut tuner = DeviceTuner::new(PcieTuningConfig::default());
+    let mut ts = 0;
+    let mut sample = |errors: u64| {
+        ts += REPORT_INTERVAL_MS;
+        PcieSample {
+            timestamp_ms: ts,
+            bytes_transferred: 10_000_000_000,
+            transfers_count: 10_000,
+            avg_latency_us: 10.0,
+            error_count: errors,
+        }
+    };
+
+    for _ in 0..4 {
+        tuner.observe(sample(0));
+    }
+    let before = tuner.params();
+    let outcome = tuner.observe(sample(500));
+
+    assert_eq!(outcome.action, TuningAction::Decrease);
+    assert!(outcome.params.queue_depth < before.queue_depth);
+    assert!(outcome.params.payload_bytes < before.payload_bytes);
+}
+
+/// Test a regression that latency does not reveal triggers a rollback
+#[test]
+fn test_tuning_rolls_back_on_regression() {
+    let mut tuner = DeviceTuner::new(PcieTuningConfig::default());
+    let mut ts = 0;
+
+    // Device thrashes above 20 outstanding transfers: throughput collapses
+    // without any latency or error signal
+    let history = run_loop(&mut tuner, 120, |p| {
+        ts += REPORT_INTERVAL_MS;
+        let gbs = if p.queue_depth <= 20 { p.queue_depth as f64 } else { 4.0 };
+        PcieSample {
+            timestamp_ms: ts,
+            bytes_transferred: (gbs * 1e9) as u64,
+            transfers_count: 1000,
+            avg_latency_us: 10.0,
+            error_count: 0,
+        }
+    });
+
+    let rollback = history
+        .iter()
+        .position(|(a, _)| *a == TuningAction::Rollback)
+        .expect("controller should roll back after a sustained regression");
+    assert!(history[rollback].1.queue_depth <= 20);
+
+    // Held on the restored settings afterwards
+    assert!(history[rollback + 1..rollback + 5]
+        .iter()
+        .all(|(a, p)| *a == TuningAction::Hold && p.queue_depth <= 20));
+}
+
+/// Test flapping congestion signals pin the controller to the last good settings
+#[test]
+fn test_tuning_settles_on_oscillation() {
+    let mut tuner = DeviceTuner::new(PcieTuningConfig::default());
+    let mut ts = 0;
+    let mut n = 0;
+
+    // Error bursts flap every other report regardless of settings
+    let history = run_loop(&mut tuner, 40, |_| {
+        ts += REPORT_INTERVAL_MS;
+        n += 1;
+        PcieSample {
+            timestamp_ms: ts,
+            bytes_transferred: 10_000_000_000,
+            transfers_count: 1000,
+            avg_latency_us: 10.0,
+            error_count: if n > 4 && n % 2 == 0 { 50 } else { 0 },
+        }
+    });
+
+    let settle = history
+        .iter()
+        .position(|(a, _)| *a == TuningAction::Settle)
+        .expect("controller should detect oscillation");
+    let pinned = history[settle].1;
+
+    // Pinned settings were recommended before and are held afterwards
+    assert!(history[..settle].iter().any(|(_, p)| *p == pinned));
+    let hold = PcieTuningConfig::default().hold_reports as usize;
+    assert!(history[settle + 1..(settle + 1 + hold).min(history.len())]
+        .iter()
+        .all(|(a, p)| *a == TuningAction::Hold && *p == pinned));
+}
+
+/// Test state is kept per agent and per device
+#[tokio::test]
+async fn test_tuning_registry_isolates_devices() {
+    let registry = PcieTuningRegistry::default();
+    let sample = |ts: i64| PcieSample {
+        timestamp_ms: ts,
+        bytes_transferred: 5_000_000_000,
+        transfers_count: 1000,
+        avg_latency_us: 10.0,
+        error_count: 0,
+    };
+
+    for i in 1..=10 {
+        registry.observe("agent-a", "0000:01:00.0", sample(i * REPORT_INTERVAL_MS)).await;
+    }
+    registry.observe("agent-a", "0000:02:00.0", sample(REPORT_INTERVAL_MS)).await;
+    registry.observe("agent-b", "", sample(REPORT_INTERVAL_MS)).await;
+
+    assert_eq!(registry.tracked_devices().await, 3);
+
+    let busy = registry.params("agent-a", "0000:01:00.0").await.unwrap();
+    let fresh = registry.params("agent-a", "0000:02:00.0").await.unwrap();
+    let default_device = registry.params("agent-b", "default").await.unwrap();
+    assert!(busy.queue_depth > fresh.queue_depth);
+    assert_eq!(fresh, default_device);
+
+    registry.forget_agent("agent-a").await;
+    assert_eq!(registry.tracked_devices().await, 1);
+}
+
+/// Test state outlives all but the agent's last stream and other callers cannot claim it
+#[tokio::test]
+async fn test_tuning_registry_counts_streams() {
+    let registry = PcieTuningRegistry::default();
+    let sample = PcieSample {
+        timestamp_ms: REPORT_INTERVAL_MS,
+        bytes_transferred: 5_000_000_000,
+        transfers_count: 1000,
+        avg_latency_us: 10.0,
+        error_count: 0,
+    };
+
+    assert!(registry.attach_stream("agent-a", "key-a").await);
+    assert!(registry.attach_stream("agent-a", "key-a").await);
+    assert!(!registry.attach_stream("agent-a", "key-b").await);
+    registry.observe("agent-a", "", sample).await;
+
+    registry.detach_stream("agent-a").await;
+    assert_eq!(registry.tracked_devices().await, 1);
+    // A stream that never attached the agent cannot drop it
+    registry.detach_stream("agent-b").await;
+    assert_eq!(registry.tracked_devices().await, 1);
+
+    registry.detach_stream("agent-a").await;
+    assert_eq!(registry.tracked_devices().await, 0);
+    assert!(registry.attach_stream("agent-a", "key-b").await);
+}
+
+/// Test reports that do not advance the timestamp leave the controller untouched
+#[test]
+fn test_tuning_ignores_out_of_order_reports() {
+    let mut tuner = DeviceTuner::new(PcieTuningConfig::default());
+    let mut device = SyntheticDevice::new();
+    let history = run_loop(&mut tuner, 10, |p| device.run(p));
+    let params = history.last().unwrap().1;
+
+    for timestamp_ms in [device.timestamp_ms, device.timestamp_ms - REPORT_INTERVAL_MS] {
+        let outcome = tuner.observe(PcieSample {
+            timestamp_ms,
+            bytes_transferred: 1,
+            transfers_count: 1,
+            avg_latency_us: 10_000.0,
+            error_count: 1,
+        });
+        assert_eq!(outcome.action, TuningAction::Ignore);
+        assert!(outcome.reason.starts_with("out-of-order report"), "{}", outcome.reason);
+        assert_eq!(outcome.params, params);
+    }
+
+    // The next in-order report is measured against the last accepted one
+    let outcome = tuner.observe(device.run(params));
+    assert_ne!(outcome.action, TuningAction::Ignore);
+    assert_ne!(outcome.action, TuningAction::Warmup);
+}
+
+/// Test the registry evicts idle and least recently seen devices
+#[tokio::test]
+async fn test_tuning_registry_is_bounded() {
+    let sample = PcieSample {
+        timestamp_ms: REPORT_INTERVAL_MS,
+        bytes_transferred: 5_000_000_000,
+        transfers_count: 1000,
+        avg_latency_us: 10.0,
+        error_count: 0,
+    };
+
+    let registry = PcieTuningRegistry::new(PcieTuningConfig {
+        max_tracked_devices: 2,
+        ..Default::default()
+    });
+    registry.observe("agent-a", "gpu0", sample).await;
+    registry.observe("agent-b", "gpu0", sample).await;
+    registry.observe("agent-c", "gpu0", sample).await;
+    assert_eq!(registry.tracked_devices().await, 2);
+    assert!(registry.params("agent-a", "gpu0").await.is_none());
+    assert!(registry.params("agent-c", "gpu0").await.is_some());
+
+    let registry = PcieTuningRegistry::new(PcieTuningConfig {
+        device_idle_expiry: Duration::from_millis(20),
+        ..Default::default()
+    });
+    registry.observe("agent-a", "gpu0", sample).await;
+    registry.observe("agent-a", "gpu1", sample).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    registry.observe("agent-b", "gpu0", sample).await;
+    assert_eq!(registry.tracked_devices().await, 1);
+}
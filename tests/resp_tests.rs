@@ -0,0 +1,186 @@
+//! RESP front end of the UAO-QTCAM cache
+//!
+//! Raw RESP frames are sent over loopback and the replies compared byte for
+//! byte with the golden transcripts in `tests/golden/resp`.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use symmetrix_core::grpc::ManualClock;
+use symmetrix_core::uao_qtcam_cache::resp::{self, Protocol, Reply};
+use symmetrix_core::UaoQtcamCache;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const TRANSCRIPTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/resp");
+
+/// Listener over a fresh cache; returns its address and clock
+async fn spawn_resp() -> (String, Arc<ManualClock>, Arc<UaoQtcamCache>) {
+    let clock = Arc::new(ManualClock::new(1_700_000_000_000));
+    let cache = Arc::new(UaoQtcamCache::new(16 * 1024 * 1024, 250.0).with_clock(clock.clone()));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(resp::serve(listener, cache.clone()));
+    (addr, clock, cache)
+}
+
+fn unescape(line: &str) -> Vec<u8> {
+    let version = env!("CARGO_PKG_VERSION");
+    let line = line
+        .replace("{version_len}", &version.len().to_string())
+        .replace("{version}", version);
+    let mut out = Vec::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('r') => out.push(b'\r'),
+                Some('n') => out.push(b'\n'),
+                Some('\\') => out.push(b'\\'),
+                other => panic!("bad escape \\{:?} in {}", other, line),
+            },
+            _ => out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    out
+}
+
+async fn read_exactly(stream: &mut TcpStream, len: usize) -> Vec<u8> {
+    let mut reply = vec![0u8; len];
+    tokio::time::timeout(Duration::from_secs(5), stream.read_exact(&mut reply))
+        .await
+        .expect("reply timed out")
+        .expect("connection closed early");
+    reply
+}
+
+async fn expect(stream: &mut TcpStream, expected: &[u8], context: &str) {
+    let reply = read_exactly(stream, expected.len()).await;
+    assert_eq!(
+        String::from_utf8_lossy(&reply),
+        String::from_utf8_lossy(expected),
+        "{}",
+        context
+    );
+}
+
+async fn run_transcript(path: &Path) {
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    let (addr, clock, _) = spawn_resp().await;
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    let (mut request, mut expected) = (Vec::new(), Vec::new());
+
+    for (number, line) in std::fs::read_to_string(path).unwrap().lines().enumerate() {
+        let context = format!("{}:{}", name, number + 1);
+        if let Some(frame) = line.strip_prefix("> ") {
+            if !expected.is_empty() {
+                stream.write_all(&request).await.unwrap();
+                expect(&mut stream, &expected, &context).await;
+                (request, expected) = (Vec::new(), Vec::new());
+            }
+            request.extend(unescape(frame));
+        } else if let Some(reply) = line.strip_prefix("< ") {
+            expected.extend(unescape(reply));
+        } else if line.starts_with('@') || line.starts_with('!') {
+            stream.write_all(&request).await.unwrap();
+            expect(&mut stream, &expected, &context).await;
+            (request, expected) = (Vec::new(), Vec::new());
+
+            match line {
+                "! closed" => {
+                    let mut rest = Vec::new();
+                    let read = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut rest)).await;
+                    assert!(matches!(read, Ok(Ok(0))), "{}: connection still open", context);
+                    return;
+                }
+                _ => {
+                    let ms = line.strip_prefix("@ advance ").expect("unknown directive");
+                    clock.advance(Duration::from_millis(ms.parse().unwrap()));
+                }
+            }
+        }
+    }
+    stream.write_all(&request).await.unwrap();
+    expect(&mut stream, &expected, &format!("{}: end", name)).await;
+}
+
+#[tokio::test]
+async fn test_golden_transcripts() {
+    let mut paths: Vec<_> = std::fs::read_dir(TRANSCRIPTS)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    assert!(paths.len() >= 5);
+    for path in paths {
+        run_transcript(&path).await;
+    }
+}
+
+#[tokio::test]
+async fn test_frames_split_across_reads() {
+    let (addr, _, cache) = spawn_resp().await;
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+
+    let request = b"*3\r\n$3\r\nSET\r\n$5\r\nsplit\r\n$11\r\nhello world\r\nPING\r\n*2\r\n$3\r\nGET\r\n$5\r\nsplit\r\n";
+    for byte in request {
+        stream.write_all(&[*byte]).await.unwrap();
+        stream.flush().await.unwrap();
+    }
+    expect(&mut stream, b"+OK\r\n+PONG\r\n$11\r\nhello world\r\n", "byte-by-byte").await;
+    assert_eq!(cache.get("split").unwrap().unwrap(), b"hello world");
+
+    // A large value compresses and round-trips
+    let value = "0123456789".repeat(50_000);
+    let set = format!("*3\r\n$3\r\nSET\r\n$3\r\nbig\r\n${}\r\n{}\r\nGET big\r\n", value.len(), value);
+    stream.write_all(set.as_bytes()).await.unwrap();
+    let header = format!("+OK\r\n${}\r\n", value.len());
+    expect(&mut stream, header.as_bytes(), "big header").await;
+    let body = read_exactly(&mut stream, value.len() + 2).await;
+    assert_eq!(&body[..value.len()], value.as_bytes());
+    assert!(cache.stats().unwrap().compression_ratio > 10.0);
+}
+
+#[tokio::test]
+async fn test_info_reports_cache_stats() {
+    let (addr, _, _) = spawn_resp().await;
+    let mut stream = TcpStream::connect(&addr).await.unwrap();
+    stream.write_all(b"SET a 1\r\nGET a\r\nGET b\r\nINFO\r\n").await.unwrap();
+    expect(&mut stream, b"+OK\r\n$1\r\n1\r\n$-1\r\n$", "info header").await;
+
+    let mut len = Vec::new();
+    while !len.ends_with(b"\r\n") {
+        len.extend(read_exactly(&mut stream, 1).await);
+    }
+    let len: usize = String::from_utf8_lossy(&len).trim().parse().unwrap();
+    let info = String::from_utf8(read_exactly(&mut stream, len).await).unwrap();
+    let lines = [
+        "# Server", "keyspace_hits:1", "keyspace_misses:1", "db0:keys=1,expires=0", "codec:zstd",
+        "maxmemory:16777216", "maxmemory_policy:allkeys-lru",
+    ];
+    for line in lines {
+        assert!(info.contains(line), "{} missing from\n{}", line, info);
+    }
+}
+
+#[test]
+fn test_parser_and_encoder() {
+    assert_eq!(resp::parse_command(b"*2\r\n$3\r\nGET\r\n$1"), Ok(None));
+    assert_eq!(
+        resp::parse_command(b"*2\r\n$3\r\nGET\r\n$1\r\nk\r\nextra"),
+        Ok(Some((vec![b"GET".to_vec(), b"k".to_vec()], 20)))
+    );
+    assert_eq!(resp::parse_command(b"GET  k\n"), Ok(Some((vec![b"GET".to_vec(), b"k".to_vec()], 7))));
+    assert!(resp::parse_command(b"*1\r\n$99999999999\r\n").is_err());
+    assert!(resp::parse_command(b"*x\r\n").is_err());
+    assert!(resp::parse_command(b"*1\r\n$1\r\nab\r\n").is_err());
+
+    let reply = Reply::Map(vec![(Reply::Bulk(b"k".to_vec()), Reply::Null)]);
+    let (mut resp2, mut resp3) = (Vec::new(), Vec::new());
+    reply.encode(Protocol::Resp2, &mut resp2);
+    reply.encode(Protocol::Resp3, &mut resp3);
+    assert_eq!(resp2, b"*2\r\n$1\r\nk\r\n$-1\r\n");
+    assert_eq!(resp3, b"%1\r\n$1\r\nk\r\n_\r\n");
+}
@@ -0,0 +1,216 @@
+//! UAO-QTCAM cache: lossless round trips and TTL expiry
+//!
+//! Whatever goes into `set` must come out of `get`, for every codec,
+//! threshold and value size; expired keys must be reclaimed without reads.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use symmetrix_core::grpc::ManualClock;
+use symmetrix_core::uao_qtcam_cache::codec::{self, HEADER_SIZE};
+use symmetrix_core::uao_qtcam_cache::eviction::{ENTRY_OVERHEAD, TTL_OVERHEAD};
+use symmetrix_core::uao_qtcam_cache::SetOptions;
+use symmetrix_core::{CacheCodec, ExpiryConfig, KeyTtl, UaoQtcamCache};
+
+fn cache(codec: CacheCodec, threshold: usize) -> UaoQtcamCache {
+    UaoQtcamCache::new(64 * 1024 * 1024, 250.0)
+        .with_codec(codec)
+        .with_compression_threshold(threshold)
+}
+
+fn any_codec() -> impl Strategy<Value = CacheCodec> {
+    prop_oneof![Just(CacheCodec::Raw), Just(CacheCodec::Deflate), Just(CacheCodec::Zstd)]
+}
+
+/// Random bytes of any size, plus long runs that actually compress
+fn any_value() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        vec(any::<u8>(), 0..64),
+        vec(any::<u8>(), 64..4096),
+        vec(any::<u8>(), 4096..70_000),
+        (vec(any::<u8>(), 1..16), 1usize..8192).prop_map(|(pattern, repeats)| pattern.repeat(repeats)),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(96))]
+
+    #[test]
+    fn prop_get_returns_what_was_set(codec in any_codec(), threshold in 0usize..256, value in any_value()) {
+        let cache = cache(codec, threshold);
+        cache.set("k", &value, None).unwrap();
+        prop_assert_eq!(cache.get("k").unwrap(), Some(value.clone()));
+
+        // Frames never cost more than the header
+        let stats = cache.stats().unwrap();
+        prop_assert!(stats.compressed_bytes <= value.len() + HEADER_SIZE);
+        prop_assert_eq!(stats.original_bytes, value.len());
+    }
+
+    #[test]
+    fn prop_frames_round_trip(codec in any_codec(), value in any_value()) {
+        let frame = codec::encode(codec, &value, 0).unwrap();
+        prop_assert_eq!(codec::frame_len(&frame).unwrap(), value.len());
+        prop_assert_eq!(codec::decode(&frame).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_stats_report_real_ratio() {
+    let cache = cache(CacheCodec::Zstd, 64);
+    let text = b"symmetrix control plane ".repeat(4096);
+    cache.set("text", &text, None).unwrap();
+
+    let stats = cache.stats().unwrap();
+    assert_eq!(stats.codec, CacheCodec::Zstd);
+    assert_eq!(stats.original_bytes, text.len());
+    assert!(stats.compressed_bytes < text.len() / 20, "{:?}", stats);
+    assert_eq!(
+        stats.compression_ratio,
+        stats.original_bytes as f64 / stats.compressed_bytes as f64
+    );
+
+    // Counters are rewritten in place and keep the size accounting right
+    for _ in 0..3 {
+        cache.incr("hits").unwrap();
+    }
+    assert_eq!(cache.get("hits").unwrap().unwrap(), b"3");
+    cache.delete("text").unwrap();
+    let stats = cache.stats().unwrap();
+    assert_eq!(stats.compressed_bytes, HEADER_SIZE + 1);
+    assert_eq!(stats.checksum_failures, 0);
+}
+
+fn clocked() -> (Arc<ManualClock>, UaoQtcamCache) {
+    let clock = Arc::new(ManualClock::new(1_700_000_000_000));
+    let cache = UaoQtcamCache::new(64 * 1024 * 1024, 250.0).with_clock(clock.clone());
+    (clock, cache)
+}
+
+#[test]
+fn test_expire_ttl_persist() {
+    let (clock, cache) = clocked();
+    cache.set("session", b"s", Some(10)).unwrap();
+    cache.set("config", b"7", None).unwrap();
+    assert_eq!(cache.ttl("session").unwrap(), KeyTtl::Expires(Duration::from_secs(10)));
+    assert_eq!(cache.ttl("config").unwrap(), KeyTtl::Persistent);
+    assert_eq!(cache.ttl("nope").unwrap(), KeyTtl::Missing);
+
+    clock.advance(Duration::from_secs(4));
+    assert_eq!(cache.ttl("session").unwrap(), KeyTtl::Expires(Duration::from_secs(6)));
+    assert!(cache.persist("session").unwrap());
+    assert!(!cache.persist("session").unwrap());
+    assert_eq!(cache.ttl("session").unwrap(), KeyTtl::Persistent);
+
+    assert!(cache.expire("config", Duration::from_millis(1500)).unwrap());
+    assert!(!cache.expire("nope", Duration::from_secs(1)).unwrap());
+    // Counters keep their TTL; a new SET drops it
+    cache.incr("config").unwrap();
+    assert_eq!(cache.ttl("config").unwrap(), KeyTtl::Expires(Duration::from_millis(1500)));
+    cache.set("session", b"s2", None).unwrap();
+    assert_eq!(cache.stats().unwrap().expiring_keys, 1);
+
+    clock.advance(Duration::from_secs(2));
+    assert!(!cache.exists("config").unwrap());
+    assert_eq!(cache.ttl("config").unwrap(), KeyTtl::Missing);
+    assert_eq!(cache.get("config").unwrap(), None);
+
+    // A zero TTL deletes at once
+    assert!(cache.expire("session", Duration::ZERO).unwrap());
+    assert!(!cache.exists("session").unwrap());
+
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.expired_keys, stats.entry_count, stats.compressed_bytes), (2, 0, 0));
+}
+
+#[test]
+fn test_set_rejects_ttl_past_the_clock() {
+    let (_, cache) = clocked();
+    for ttl in [Duration::MAX, Duration::from_millis(i64::MAX as u64)] {
+        let options = SetOptions { ttl: Some(ttl), ..Default::default() };
+        assert_eq!(
+            cache.set_with("k", b"v", options).unwrap_err(),
+            "invalid expire time in 'set' command"
+        );
+    }
+    assert!(!cache.exists("k").unwrap());
+    let options = SetOptions { ttl: Some(Duration::from_secs(1 << 32)), ..Default::default() };
+    assert!(cache.set_with("k", b"v", options).unwrap());
+}
+
+#[test]
+fn test_expiry_cycle_reclaims_without_reads() {
+    let (clock, cache) = clocked();
+    // Rounds are counted per shard
+    let cache = cache.with_shards(1);
+    for i in 0..100 {
+        cache.set(&format!("short:{}", i), b"x", Some(5)).unwrap();
+        cache.set(&format!("long:{}", i), b"y", Some(60)).unwrap();
+    }
+    cache.set("keep", b"z", None).unwrap();
+    assert_eq!(cache.expire_cycle(&ExpiryConfig::default()).unwrap(), 0);
+
+    clock.advance(Duration::from_secs(6));
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.entry_count, stats.expiring_keys), (201, 200));
+
+    // Rounds stop at `max_rounds`...
+    let bounded = ExpiryConfig { sample_size: 10, max_rounds: 3, ..ExpiryConfig::default() };
+    assert_eq!(cache.expire_cycle(&bounded).unwrap(), 30);
+    // ...or at the first sample that is mostly alive
+    assert_eq!(cache.expire_cycle(&ExpiryConfig::default()).unwrap(), 70);
+
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.hits, stats.misses), (0, 0));
+    assert_eq!((stats.entry_count, stats.expiring_keys, stats.expired_keys), (101, 100, 100));
+    assert_eq!(stats.compressed_bytes, 101 * (HEADER_SIZE + 1));
+    assert!(cache.exists("long:0").unwrap());
+}
+
+#[test]
+fn test_eviction_drops_expired_keys_first() {
+    let clock = Arc::new(ManualClock::new(0));
+    // Room for "hot" and the two one-byte keys with TTLs, and nothing more
+    let frame = HEADER_SIZE + 1;
+    let limit = ("hot".len() + frame + ENTRY_OVERHEAD) + 2 * (2 + frame + ENTRY_OVERHEAD + TTL_OVERHEAD);
+    let cache = UaoQtcamCache::new(limit, 250.0).with_clock(clock.clone());
+    cache.set("hot", b"h", None).unwrap();
+    cache.get("hot").unwrap();
+    cache.set("a", b"a", Some(1)).unwrap();
+    cache.set("b", b"b", Some(1)).unwrap();
+    clock.advance(Duration::from_secs(2));
+
+    cache.set("c", b"c", None).unwrap();
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.evictions, stats.expired_keys, stats.entry_count), (0, 2, 2));
+    assert!(cache.exists("hot").unwrap());
+    assert_eq!(stats.used_memory, cache.memory_usage("hot").unwrap().unwrap() + cache.memory_usage("c").unwrap().unwrap());
+}
+
+#[tokio::test]
+async fn test_background_expiry() {
+    let (clock, cache) = clocked();
+    let cache = Arc::new(cache);
+    for i in 0..50 {
+        cache.set(&format!("k{}", i), &[i as u8; 200], Some(1)).unwrap();
+    }
+    let task = cache.spawn_expiry(ExpiryConfig { interval: Duration::from_millis(5), ..ExpiryConfig::default() });
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(cache.stats().unwrap().entry_count, 50);
+
+    clock.advance(Duration::from_secs(1));
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while cache.stats().unwrap().entry_count > 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("expired keys were not reclaimed");
+    task.abort();
+
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.compressed_bytes, stats.expired_keys, stats.misses), (0, 50, 0));
+}
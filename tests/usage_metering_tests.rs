@@ -0,0 +1,613 @@
+//! Integration tests for usage metering
+//!
+//! Simulates allocations, calibration streams and API calls on a manual
+//! clock and checks the hourly records against the GPU cost model, and
+//! that closed records and agent bindings written to a ledger file outlive
+//! the meter.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use symmetrix_core::grpc::allocation::AllocationServiceImpl;
+use symmetrix_core::grpc::calibration::CalibrationServiceImpl;
+use symmetrix_core::grpc::metering::{
+    BillingPeriod, ExportFormat, FileUsageStore, ManualClock, UsageFilter, UsageMeter, BYTES_PER_TB,
+    DEFAULT_API_USD_PER_1K_CALLS, DEFAULT_MEMORY_USD_PER_TB_HOUR, DEFAULT_STREAM_USD_PER_HOUR,
+    UNASSIGNED_TIER,
+};
+use symmetrix_core::grpc::proto::{
+    AllocationRequest, CalibrationSubscriptionRequest, CostEstimate, DetectedGpuInfo,
+    FreeMemoryRequest, GpuRegistrationRequest, UsageRequest,
+};
+use symmetrix_core::grpc::server::CyanFlameGrpcServer;
+use symmetrix_core::grpc::{
+    AllocationService, AuthInterceptor, AuthManager, CalibrationService, GpuDetectionService,
+    GpuDetectionServiceImpl, GrpcServerConfig,
+};
+use tonic::service::Interceptor;
+use tonic::Request;
+
+const MINUTE: Duration = Duration::from_secs(60);
+const HOUR: Duration = Duration::from_secs(3600);
+const KEY_A: &str = "CYAN-FLAME-ENTERPRISE-aaaa1111";
+const KEY_B: &str = "CYAN-FLAME-ENTERPRISE-bbbb2222";
+
+fn ms(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> i64 {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+/// Meter on a manual clock starting 2026-03-02 00:00 UTC
+fn meter() -> (Arc<ManualClock>, Arc<UsageMeter>) {
+    let clock = Arc::new(ManualClock::new(ms(2026, 3, 2, 0, 0)));
+    let meter = Arc::new(UsageMeter::with_clock(clock.clone()));
+    (clock, meter)
+}
+
+/// Register a GPU and return the cost estimate its tier is billed at
+async fn register(
+    service: &GpuDetectionServiceImpl,
+    agent_id: &str,
+    api_key: &str,
+    gpu: &str,
+) -> CostEstimate {
+    let response = service
+        .register_gpu(Request::new(GpuRegistrationRequest {
+            agent_id: agent_id.to_string(),
+            api_key: api_key.to_string(),
+            detected_gpu: Some(DetectedGpuInfo {
+                name: gpu.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.error_message);
+    response.cost_estimate.unwrap()
+}
+
+async fn allocate(
+    service: &AllocationServiceImpl,
+    agent_id: &str,
+    api_key: &str,
+    tb: u64,
+    duration: Duration,
+) -> String {
+    let response = service
+        .allocate_memory(Request::new(AllocationRequest {
+            agent_id: agent_id.to_string(),
+            api_key: api_key.to_string(),
+            requested_tb: tb,
+            purpose: "inference".to_string(),
+            duration_ms: duration.as_millis() as i64,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.error_message);
+    response.allocation_id
+}
+
+async fn free(service: &AllocationServiceImpl, allocation_id: &str) {
+    let response = service
+        .free_memory(Request::new(FreeMemoryRequest {
+            agent_id: String::new(),
+            allocation_id: allocation_id.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success);
+}
+
+fn march() -> UsageFilter {
+    UsageFilter::period(BillingPeriod::month(2026, 3).unwrap())
+}
+
+#[tokio::test]
+async fn test_allocation_byte_seconds_split_across_hours() {
+    let (clock, meter) = meter();
+    let gpus = GpuDetectionServiceImpl::new().with_meter(meter.clone());
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+    let estimate = register(&gpus, "agent-a", KEY_A, "NVIDIA A100-SXM4-80GB").await;
+
+    // 2 TB from 00:30 to 02:00
+    clock.advance(30 * MINUTE);
+    let id = allocate(&allocations, "agent-a", KEY_A, 2, Duration::ZERO).await;
+    clock.advance(90 * MINUTE);
+    free(&allocations, &id).await;
+    clock.advance(HOUR);
+
+    let records = meter.rollup();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].hour_start_ms, ms(2026, 3, 2, 0, 0));
+    assert_eq!(records[0].memory_byte_seconds, 2 * BYTES_PER_TB * 1800);
+    assert_eq!(records[1].hour_start_ms, ms(2026, 3, 2, 1, 0));
+    assert_eq!(records[1].memory_byte_seconds, 2 * BYTES_PER_TB * 3600);
+
+    for record in &records {
+        assert_eq!(record.tier, estimate.pricing_tier);
+        assert_eq!(record.api_key_hash, AuthManager::hash_key(KEY_A));
+        assert!(close(
+            record.memory_cost_usd,
+            record.memory_tb_hours() * DEFAULT_MEMORY_USD_PER_TB_HOUR
+        ));
+    }
+    let totals = meter.report(&march()).totals;
+    assert!(close(totals.memory_tb_hours, 3.0));
+    assert!(close(
+        totals.total_cost_usd,
+        3.0 * DEFAULT_MEMORY_USD_PER_TB_HOUR
+    ));
+}
+
+#[tokio::test]
+async fn test_allocation_lease_expiry_stops_accrual() {
+    let (clock, meter) = meter();
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+
+    // Never freed; the 45-minute lease bounds the charge
+    allocate(&allocations, "agent-a", KEY_A, 4, 45 * MINUTE).await;
+    clock.advance(3 * HOUR);
+
+    let records = meter.rollup();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].memory_byte_seconds, 4 * BYTES_PER_TB * 45 * 60);
+    assert_eq!(records[0].tier, UNASSIGNED_TIER);
+
+    clock.advance(HOUR);
+    assert!(meter.rollup().is_empty());
+}
+
+#[tokio::test]
+async fn test_calibration_stream_priced_at_gpu_hourly_rate() {
+    let (clock, meter) = meter();
+    let gpus = GpuDetectionServiceImpl::new().with_meter(meter.clone());
+    let calibration = CalibrationServiceImpl::new().with_meter(meter.clone());
+    let estimate = register(&gpus, "agent-a", KEY_A, "NVIDIA A100-SXM4-80GB").await;
+
+    clock.advance(50 * MINUTE);
+    let stream = calibration
+        .subscribe_calibration_matrix(Request::new(CalibrationSubscriptionRequest {
+            agent_id: "agent-a".to_string(),
+            subscription_tier: "enterprise".to_string(),
+            api_key: KEY_A.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    // Stream open 00:50 → 01:35, then dropped
+    clock.advance(45 * MINUTE);
+    drop(stream);
+    clock.advance(2 * HOUR);
+
+    let records = meter.rollup();
+    let minutes: Vec<f64> = records
+        .iter()
+        .map(|r| r.calibration_stream_minutes())
+        .collect();
+    assert_eq!(minutes, [10.0, 35.0]);
+
+    let totals = meter.report(&march()).totals;
+    assert!(close(totals.calibration_stream_minutes, 45.0));
+    assert!(close(
+        totals.stream_cost_usd,
+        0.75 * estimate.hourly_rate_usd
+    ));
+    assert!(close(totals.total_cost_usd, totals.stream_cost_usd));
+}
+
+#[tokio::test]
+async fn test_api_calls_metered_by_interceptor() {
+    let (clock, meter) = meter();
+    let gpus = GpuDetectionServiceImpl::new().with_meter(meter.clone());
+    let mut interceptor =
+        AuthInterceptor::new(Arc::new(AuthManager::new(false))).with_meter(meter.clone());
+
+    let call = |interceptor: &mut AuthInterceptor, key: Option<&str>| {
+        let mut request = Request::new(());
+        if let Some(key) = key {
+            request
+                .metadata_mut()
+                .insert("x-api-key", key.parse().unwrap());
+        }
+        interceptor.call(request).unwrap();
+    };
+
+    // Before any GPU registration the key bills to the unassigned tier
+    for _ in 0..2500 {
+        call(&mut interceptor, Some(KEY_A));
+    }
+    call(&mut interceptor, None);
+
+    clock.advance(HOUR);
+    let estimate = register(&gpus, "agent-a", KEY_A, "NVIDIA A100-SXM4-80GB").await;
+    for _ in 0..1000 {
+        call(&mut interceptor, Some(KEY_A));
+    }
+    clock.advance(HOUR);
+
+    let records = meter.rollup();
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        (records[0].tier.as_str(), records[0].api_calls),
+        (UNASSIGNED_TIER, 2500)
+    );
+    assert!(close(
+        records[0].api_cost_usd,
+        2.5 * DEFAULT_API_USD_PER_1K_CALLS
+    ));
+    assert_eq!(
+        (records[1].tier.as_str(), records[1].api_calls),
+        (estimate.pricing_tier.as_str(), 1000)
+    );
+}
+
+#[tokio::test]
+async fn test_usage_bills_the_authenticated_key() {
+    let (clock, meter) = meter();
+    let gpus = GpuDetectionServiceImpl::new().with_meter(meter.clone());
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+    let calibration = CalibrationServiceImpl::new().with_meter(meter.clone());
+    register(&gpus, "agent-a", KEY_A, "NVIDIA A100-SXM4-80GB").await;
+
+    // Key B cannot bill to, or allocate as, key A's agent
+    let mut request = Request::new(AllocationRequest {
+        agent_id: "agent-a".to_string(),
+        api_key: KEY_A.to_string(),
+        requested_tb: 1,
+        purpose: "inference".to_string(),
+        duration_ms: 0,
+    });
+    request.metadata_mut().insert("x-api-key", KEY_B.parse().unwrap());
+    let denied = allocations.allocate_memory(request).await;
+    assert_eq!(denied.unwrap_err().code(), tonic::Code::PermissionDenied);
+
+    let mut request = Request::new(CalibrationSubscriptionRequest {
+        agent_id: "agent-a".to_string(),
+        subscription_tier: "enterprise".to_string(),
+        api_key: KEY_B.to_string(),
+    });
+    request.metadata_mut().insert("x-api-key", KEY_B.parse().unwrap());
+    let denied = calibration.subscribe_calibration_matrix(request).await;
+    assert_eq!(denied.err().unwrap().code(), tonic::Code::PermissionDenied);
+
+    // An unbound agent bills to the caller's own key
+    allocate(&allocations, "agent-b", KEY_B, 1, HOUR).await;
+    clock.advance(2 * HOUR);
+    let records = meter.rollup();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].api_key_hash, AuthManager::hash_key(KEY_B));
+}
+
+#[tokio::test]
+async fn test_rollup_closes_only_finished_hours() {
+    let (clock, meter) = meter();
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+    allocate(&allocations, "agent-a", KEY_A, 1, Duration::ZERO).await;
+
+    // Mid-hour: nothing is final yet
+    clock.advance(30 * MINUTE);
+    assert!(meter.rollup().is_empty());
+
+    clock.advance(45 * MINUTE);
+    let first = meter.rollup();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].memory_byte_seconds, BYTES_PER_TB * 3600);
+
+    // Repeated rollups add nothing and closed records never change
+    assert!(meter.rollup().is_empty());
+    clock.advance(2 * HOUR);
+    let later = meter.rollup();
+    assert_eq!(later.len(), 2);
+    let all = meter.records(&march());
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[0], first[0]);
+}
+
+#[tokio::test]
+async fn test_closed_records_survive_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("usage.jsonl");
+    let clock = Arc::new(ManualClock::new(ms(2026, 3, 2, 0, 0)));
+    let open = || {
+        let store = Arc::new(FileUsageStore::open(&path).unwrap());
+        Arc::new(UsageMeter::with_clock(clock.clone()).with_store(store).unwrap())
+    };
+
+    let meter = open();
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+    allocate(&allocations, "agent-a", KEY_A, 1, Duration::ZERO).await;
+    clock.advance(2 * HOUR);
+    let closed = meter.rollup();
+    assert_eq!(closed.len(), 2);
+    drop((allocations, meter));
+
+    // A crash mid-append leaves a torn line, which is dropped
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    std::io::Write::write_all(&mut file, b"{\"api_key_hash\":\"tor").unwrap();
+
+    clock.advance(10 * MINUTE);
+    let restarted = open();
+    assert_eq!(restarted.records(&march()), closed);
+    restarted.record_api_call(KEY_A);
+    clock.advance(HOUR);
+    assert_eq!(restarted.rollup().len(), 1);
+    drop(restarted);
+
+    let records = open().records(&march());
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[..2], closed[..]);
+    assert_eq!(records[2].api_calls, 1);
+}
+
+#[tokio::test]
+async fn test_bindings_survive_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("usage.jsonl");
+    let clock = Arc::new(ManualClock::new(ms(2026, 3, 2, 0, 0)));
+    let open = || {
+        let store = Arc::new(FileUsageStore::open(&path).unwrap());
+        Arc::new(UsageMeter::with_clock(clock.clone()).with_store(store).unwrap())
+    };
+
+    let meter = open();
+    let gpus = GpuDetectionServiceImpl::new().with_meter(meter.clone());
+    let estimate = register(&gpus, "agent-a", KEY_A, "NVIDIA A100-SXM4-80GB").await;
+    drop((gpus, meter));
+
+    let meter = open();
+    assert!(meter.tier_rates(&estimate.pricing_tier).is_some());
+    let calibration = CalibrationServiceImpl::new().with_meter(meter.clone());
+    let subscribe = |agent_id: &str, api_key: &str| {
+        calibration.subscribe_calibration_matrix(Request::new(CalibrationSubscriptionRequest {
+            agent_id: agent_id.to_string(),
+            subscription_tier: "enterprise".to_string(),
+            api_key: api_key.to_string(),
+        }))
+    };
+    let bound = subscribe("agent-a", KEY_A).await.unwrap();
+    let unbound = subscribe("agent-b", KEY_B).await.unwrap();
+    clock.advance(HOUR);
+    drop((bound, unbound));
+
+    // The reloaded binding keeps the GPU tier; a key without one pays the default rate
+    let records = meter.rollup();
+    assert_eq!(records.len(), 2);
+    let record = |key: &str| records.iter().find(|r| r.api_key_hash == AuthManager::hash_key(key)).unwrap();
+    assert_eq!(record(KEY_A).tier, estimate.pricing_tier);
+    assert!(close(record(KEY_A).stream_cost_usd, estimate.hourly_rate_usd));
+    assert_eq!(record(KEY_B).tier, UNASSIGNED_TIER);
+    assert!(close(record(KEY_B).stream_cost_usd, DEFAULT_STREAM_USD_PER_HOUR));
+    assert!(!record(KEY_B).unpriced);
+}
+
+#[test]
+fn test_unreadable_ledger_fails_startup() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = GrpcServerConfig {
+        usage_ledger_path: Some(dir.path().join("missing/usage.jsonl").display().to_string()),
+        ..Default::default()
+    };
+    assert!(CyanFlameGrpcServer::with_config(config).is_err());
+}
+
+/// Three simulated days, rolled up hourly like the background task
+#[tokio::test]
+async fn test_simulated_usage_matches_cost_model() {
+    let (clock, meter) = meter();
+    let gpus = GpuDetectionServiceImpl::new().with_meter(meter.clone());
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+    let calibration = CalibrationServiceImpl::new().with_meter(meter.clone());
+    let mut interceptor =
+        AuthInterceptor::new(Arc::new(AuthManager::new(false))).with_meter(meter.clone());
+
+    let fleet = [
+        ("agent-a", KEY_A, "NVIDIA A100-SXM4-80GB", 10u64),
+        ("agent-b", KEY_B, "NVIDIA GeForce RTX 4090", 3u64),
+    ];
+    let mut estimates = Vec::new();
+    for (agent, key, gpu, _) in fleet {
+        estimates.push(register(&gpus, agent, key, gpu).await);
+    }
+    assert_ne!(estimates[0].pricing_tier, estimates[1].pricing_tier);
+
+    const DAYS: u64 = 3;
+    const ACTIVE_HOURS: u64 = 8;
+    const CALLS_PER_HOUR: u64 = 125;
+
+    for _ in 0..DAYS {
+        let mut sessions = Vec::new();
+        for (agent, key, _, tb) in fleet {
+            let allocation = allocate(&allocations, agent, key, tb, Duration::ZERO).await;
+            let stream = calibration
+                .subscribe_calibration_matrix(Request::new(CalibrationSubscriptionRequest {
+                    agent_id: agent.to_string(),
+                    subscription_tier: "enterprise".to_string(),
+                    api_key: key.to_string(),
+                }))
+                .await
+                .unwrap()
+                .into_inner();
+            sessions.push((allocation, stream));
+        }
+        for _ in 0..ACTIVE_HOURS {
+            for (_, key, _, _) in fleet {
+                for _ in 0..CALLS_PER_HOUR {
+                    let mut request = Request::new(());
+                    request
+                        .metadata_mut()
+                        .insert("x-api-key", key.parse().unwrap());
+                    interceptor.call(request).unwrap();
+                }
+            }
+            clock.advance(HOUR);
+            meter.rollup();
+        }
+        for (allocation, stream) in sessions {
+            free(&allocations, &allocation).await;
+            drop(stream);
+        }
+        clock.advance(Duration::from_secs(3600 * (24 - ACTIVE_HOURS)));
+        meter.rollup();
+    }
+
+    for ((_, key, _, tb), estimate) in fleet.iter().zip(&estimates) {
+        let report = meter.report(&UsageFilter {
+            api_key_hash: Some(AuthManager::hash_key(key)),
+            ..march()
+        });
+        assert_eq!(report.records.len(), (DAYS * ACTIVE_HOURS) as usize);
+
+        let hours = (DAYS * ACTIVE_HOURS) as f64;
+        let expected = hours * estimate.hourly_rate_usd
+            + hours * *tb as f64 * DEFAULT_MEMORY_USD_PER_TB_HOUR
+            + hours * CALLS_PER_HOUR as f64 / 1000.0 * DEFAULT_API_USD_PER_1K_CALLS;
+
+        assert!(close(
+            report.totals.calibration_stream_minutes,
+            hours * 60.0
+        ));
+        assert!(close(report.totals.memory_tb_hours, hours * *tb as f64));
+        assert_eq!(
+            report.totals.api_calls,
+            DAYS * ACTIVE_HOURS * CALLS_PER_HOUR
+        );
+        assert!(
+            close(report.totals.total_cost_usd, expected),
+            "{} != {}",
+            report.totals.total_cost_usd,
+            expected
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_export_by_billing_period() {
+    let clock = Arc::new(ManualClock::new(ms(2026, 3, 31, 23, 0)));
+    let meter = Arc::new(UsageMeter::with_clock(clock.clone()));
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+
+    // 23:00 March 31 → 01:00 April 1
+    let id = allocate(&allocations, "agent-a", KEY_A, 1, Duration::ZERO).await;
+    clock.advance(2 * HOUR);
+    free(&allocations, &id).await;
+    meter.rollup();
+
+    let march = UsageFilter::period(BillingPeriod::month(2026, 3).unwrap());
+    let april = UsageFilter::period(BillingPeriod::month(2026, 4).unwrap());
+    assert_eq!(meter.records(&march).len(), 1);
+    assert_eq!(meter.records(&april).len(), 1);
+
+    let csv = meter.export(&march, ExportFormat::Csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("api_key_hash,tier,hour_start,"));
+    assert!(lines[1].contains("2026-03-31T23:00:00+00:00"));
+    assert!(lines[1].contains(&AuthManager::hash_key(KEY_A)));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&meter.export(&april, ExportFormat::Json).unwrap()).unwrap();
+    assert_eq!(json["records"].as_array().unwrap().len(), 1);
+    assert_eq!(json["records"][0]["hour_start_ms"], ms(2026, 4, 1, 0, 0));
+    assert_eq!(json["period"]["start_ms"], ms(2026, 4, 1, 0, 0));
+    assert!((json["totals"]["memory_tb_hours"].as_f64().unwrap() - 1.0).abs() < 1e-9);
+
+    assert!(BillingPeriod::new(10, 10).is_err());
+    assert_eq!(ExportFormat::from_name("CSV"), Some(ExportFormat::Csv));
+    assert_eq!(ExportFormat::from_name("xml"), None);
+}
+
+#[tokio::test]
+async fn test_get_usage_rpc() {
+    let (clock, meter) = meter();
+    let service = GpuDetectionServiceImpl::new()
+        .with_meter(meter.clone())
+        .with_admin_key("admin-secret");
+    let allocations = AllocationServiceImpl::new().with_meter(meter.clone());
+
+    register(&service, "agent-a", KEY_A, "NVIDIA A100-SXM4-80GB").await;
+    register(&service, "agent-b", KEY_B, "NVIDIA H100 80GB HBM3").await;
+    allocate(&allocations, "agent-a", KEY_A, 1, HOUR).await;
+    allocate(&allocations, "agent-b", KEY_B, 2, HOUR).await;
+    clock.advance(2 * HOUR);
+
+    let usage = |api_key: &str, api_key_hash: &str, export_format: &str| UsageRequest {
+        api_key: api_key.to_string(),
+        api_key_hash: api_key_hash.to_string(),
+        export_format: export_format.to_string(),
+        ..Default::default()
+    };
+
+    // Callers see only their own key; the default period is the current month
+    let own = service
+        .get_usage(Request::new(usage(KEY_A, "", "")))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(own.success);
+    assert_eq!(own.period_start_ms, ms(2026, 3, 1, 0, 0));
+    assert_eq!(own.period_end_ms, ms(2026, 4, 1, 0, 0));
+    assert_eq!(own.records.len(), 1);
+    assert_eq!(own.records[0].api_key_hash, AuthManager::hash_key(KEY_A));
+    assert!(close(own.memory_tb_hours, 1.0));
+
+    let other = service
+        .get_usage(Request::new(usage(
+            KEY_A,
+            &AuthManager::hash_key(KEY_B),
+            "",
+        )))
+        .await;
+    assert_eq!(other.unwrap_err().code(), tonic::Code::PermissionDenied);
+    let anonymous = service.get_usage(Request::new(usage("", "", ""))).await;
+    assert_eq!(anonymous.unwrap_err().code(), tonic::Code::Unauthenticated);
+
+    // Admin sees every key, or one key by hash
+    let all = service
+        .get_usage(Request::new(usage("admin-secret", "", "csv")))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(all.records.len(), 2);
+    assert!(close(all.memory_tb_hours, 3.0));
+    assert_eq!(all.export.lines().count(), 3);
+
+    let b = service
+        .get_usage(Request::new(usage(
+            "admin-secret",
+            &AuthManager::hash_key(KEY_B),
+            "json",
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(b.records.len(), 1);
+    assert!(b.export.contains("\"records\""));
+
+    let bad_format = service
+        .get_usage(Request::new(usage(KEY_A, "", "xml")))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!bad_format.success);
+
+    let bad_period = service
+        .get_usage(Request::new(UsageRequest {
+            api_key: KEY_A.to_string(),
+            period_start_ms: 2000,
+            period_end_ms: 1000,
+            ..Default::default()
+        }))
+        .await;
+    assert_eq!(bad_period.unwrap_err().code(), tonic::Code::InvalidArgument);
+}